@@ -0,0 +1,707 @@
+use crate::progress::Progress;
+use anyhow::Result;
+use cvxrs_core::math::{axpy, dot, norm2, norm_inf, RealNumber};
+use cvxrs_core::options::SolveOptions;
+use cvxrs_core::problem::{
+    Bounds, CscMatrix, EqualityConstraints, InequalityConstraints, ProblemLP, ProblemQP,
+};
+use cvxrs_core::solution::{Certificate, Solution, Status};
+use cvxrs_core::stats::{ActiveSet, InterruptFlag, IterationSink};
+use num_traits::FromPrimitive;
+
+// Pivots of the reduced Hessian below this share of its largest diagonal
+// entry count as directions of zero curvature.
+const PIVOT_TOLERANCE: f64 = 1e-10;
+
+// Feasibility, stationarity and multiplier tolerance of the steps, relative
+// to the size of the quantities they are compared with.
+const ACTIVE_SET_TOLERANCE: f64 = 1e-9;
+
+pub struct ActiveSetSolver<T: RealNumber> {
+    iteration_log: Option<IterationSink<T>>,
+    interrupt: Option<InterruptFlag>,
+}
+
+impl<T> Default for ActiveSetSolver<T>
+where
+    T: RealNumber + FromPrimitive,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> ActiveSetSolver<T>
+where
+    T: RealNumber + FromPrimitive,
+{
+    pub fn new() -> Self {
+        Self {
+            iteration_log: None,
+            interrupt: None,
+        }
+    }
+
+    pub fn with_iteration_log(mut self, sink: IterationSink<T>) -> Self {
+        self.iteration_log = Some(sink);
+        self
+    }
+
+    pub fn with_interrupt(mut self, flag: InterruptFlag) -> Self {
+        self.interrupt = Some(flag);
+        self
+    }
+
+    pub fn solve_qp(
+        &self,
+        problem: &ProblemQP<T>,
+        options: &SolveOptions<T>,
+    ) -> Result<Solution<T>> {
+        problem.validate()?;
+        problem.require_linear_constraints()?;
+        let qp = DenseQp::new(
+            Some(&problem.quadratic),
+            &problem.linear,
+            problem.equalities.as_ref(),
+            problem.inequalities.as_ref(),
+            problem.bounds.as_ref(),
+        );
+        let mut solution = self.solve(&qp, options);
+        solution.objective_value += problem.offset;
+        Ok(solution)
+    }
+
+    pub fn solve_lp(
+        &self,
+        problem: &ProblemLP<T>,
+        options: &SolveOptions<T>,
+    ) -> Result<Solution<T>> {
+        problem.validate()?;
+        let qp = DenseQp::new(
+            None,
+            &problem.cost,
+            problem.equalities.as_ref(),
+            problem.inequalities.as_ref(),
+            problem.bounds.as_ref(),
+        );
+        let mut solution = self.solve(&qp, options);
+        solution.objective_value += problem.offset;
+        Ok(solution)
+    }
+
+    // Primal active-set method. Phase one minimizes the total violation of
+    // the rows from a point inside the bounds; phase two starts from the
+    // feasible point it finds, with the rows it left active.
+    fn solve(&self, qp: &DenseQp<T>, options: &SolveOptions<T>) -> Solution<T> {
+        let mut progress = Progress::new(
+            options,
+            self.iteration_log.as_ref(),
+            self.interrupt.as_ref(),
+        );
+        let tolerance = T::from_f64(ACTIVE_SET_TOLERANCE).unwrap();
+        let (phase_one, mut start) = qp.phase_one();
+        let mut working = Vec::new();
+        let outcome = minimize(&phase_one, &mut start, &mut working, &mut progress);
+
+        let mut solution = Solution::with_capacity(qp.n, qp.meq, qp.dual_len());
+        let status = match outcome {
+            Outcome::Optimal { .. }
+                if phase_one.objective(&start)
+                    > tolerance * (T::one() + norm_inf(&qp.b).max(norm_inf(&qp.h))) =>
+            {
+                solution.objective_value = T::infinity();
+                Status::PrimalInfeasible
+            }
+            Outcome::Optimal { .. } => {
+                let mut x = start[..qp.n].to_vec();
+                working.retain(|&row| row < qp.h.len());
+                match minimize(qp, &mut x, &mut working, &mut progress) {
+                    Outcome::Optimal {
+                        equality,
+                        working: multipliers,
+                    } => {
+                        solution.objective_value = qp.objective(&x);
+                        solution.equality_dual = equality;
+                        solution.inequality_dual = qp.split_dual(&working, &multipliers);
+                        progress.stats.active_set = Some(qp.active_set(&working));
+                        solution.primal = x;
+                        Status::Optimal
+                    }
+                    Outcome::Unbounded(direction) => {
+                        let slope = -dot(&qp.q, &direction);
+                        solution.objective_value = T::neg_infinity();
+                        solution.certificate = Some(Certificate::DualInfeasible {
+                            direction: direction.iter().map(|&d| d / slope).collect(),
+                        });
+                        Status::DualInfeasible
+                    }
+                    Outcome::Stopped(status) => status,
+                }
+            }
+            // Phase one is bounded below by zero.
+            Outcome::Unbounded(_) => Status::NumericalFailure,
+            Outcome::Stopped(status) => status,
+        };
+        progress.stats.solve_time = progress.timer.elapsed();
+        solution.status = status;
+        solution.iterations = progress.stats.history.len();
+        solution.stats = progress.stats;
+        solution
+    }
+}
+
+enum Outcome<T> {
+    // Multipliers of the equality rows and of the working rows, in order.
+    Optimal { equality: Vec<T>, working: Vec<T> },
+    Unbounded(Vec<T>),
+    Stopped(Status),
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Row {
+    Inequality(usize),
+    Upper(usize),
+    Lower(usize),
+}
+
+// min ½xᵀPx + qᵀx s.t. Cx = b, Gx <= h, with the finite bounds appended to
+// G as ±x_i <= ±bound rows.
+struct DenseQp<T: RealNumber> {
+    n: usize,
+    meq: usize,
+    mineq: usize,
+    has_bounds: bool,
+    p: Vec<T>,
+    q: Vec<T>,
+    c: Vec<T>,
+    b: Vec<T>,
+    g: Vec<T>,
+    h: Vec<T>,
+    kinds: Vec<Row>,
+}
+
+impl<T> DenseQp<T>
+where
+    T: RealNumber + FromPrimitive,
+{
+    fn new(
+        quadratic: Option<&CscMatrix<T>>,
+        linear: &[T],
+        equalities: Option<&EqualityConstraints<T>>,
+        inequalities: Option<&InequalityConstraints<T>>,
+        bounds: Option<&Bounds<T>>,
+    ) -> Self {
+        let n = linear.len();
+        let p = quadratic.map_or_else(|| vec![T::zero(); n * n], to_dense);
+        let (meq, c, b) = match equalities {
+            Some(eq) => (eq.matrix.nrows, to_dense(&eq.matrix), eq.rhs.clone()),
+            None => (0, Vec::new(), Vec::new()),
+        };
+        let (mineq, mut g, mut h) = match inequalities {
+            Some(ineq) => (ineq.matrix.nrows, to_dense(&ineq.matrix), ineq.rhs.clone()),
+            None => (0, Vec::new(), Vec::new()),
+        };
+        let mut kinds: Vec<Row> = (0..mineq).map(Row::Inequality).collect();
+        if let Some(bounds) = bounds {
+            for (i, (&lower, &upper)) in bounds.lower.iter().zip(&bounds.upper).enumerate() {
+                for (kind, sign, value) in [
+                    (Row::Upper(i), T::one(), upper),
+                    (Row::Lower(i), -T::one(), -lower),
+                ] {
+                    if value.is_finite() {
+                        let mut unit = vec![T::zero(); n];
+                        unit[i] = sign;
+                        g.extend(unit);
+                        h.push(value);
+                        kinds.push(kind);
+                    }
+                }
+            }
+        }
+        Self {
+            n,
+            meq,
+            mineq,
+            has_bounds: bounds.is_some(),
+            p,
+            q: linear.to_vec(),
+            c,
+            b,
+            g,
+            h,
+            kinds,
+        }
+    }
+
+    fn equality(&self, row: usize) -> &[T] {
+        &self.c[row * self.n..(row + 1) * self.n]
+    }
+
+    fn row(&self, row: usize) -> &[T] {
+        &self.g[row * self.n..(row + 1) * self.n]
+    }
+
+    fn gradient(&self, x: &[T]) -> Vec<T> {
+        let mut gradient = multiply(&self.p, self.n, x);
+        for (value, &q) in gradient.iter_mut().zip(&self.q) {
+            *value += q;
+        }
+        gradient
+    }
+
+    fn objective(&self, x: &[T]) -> T {
+        let px = multiply(&self.p, self.n, x);
+        T::from_f64(0.5).unwrap() * dot(x, &px) + dot(&self.q, x)
+    }
+
+    // Largest violation of any row at x.
+    fn violation(&self, x: &[T]) -> T {
+        let equalities = (0..self.meq).map(|row| (dot(self.equality(row), x) - self.b[row]).abs());
+        let inequalities =
+            (0..self.h.len()).map(|row| (dot(self.row(row), x) - self.h[row]).max(T::zero()));
+        equalities.chain(inequalities).fold(T::zero(), T::max)
+    }
+
+    // min t + Σ(s⁺ + s⁻) s.t. Cx - s⁺ + s⁻ = b, Gx - t <= h on the
+    // inequality rows, the bounds of x and t, s⁺, s⁻ >= 0, together with a
+    // feasible point: x at the point of its box closest to the origin and the
+    // auxiliary variables absorbing whatever that leaves violated.
+    fn phase_one(&self) -> (Self, Vec<T>) {
+        let (n, meq) = (self.n, self.meq);
+        let width = n + 1 + 2 * meq;
+        let mut x = vec![T::zero(); width];
+        for pass in 0..2 {
+            for (row, kind) in self.kinds.iter().enumerate() {
+                match (pass, *kind) {
+                    (0, Row::Upper(i)) => x[i] = x[i].min(self.h[row]),
+                    (1, Row::Lower(i)) => x[i] = x[i].max(-self.h[row]),
+                    _ => {}
+                }
+            }
+        }
+
+        let mut c = vec![T::zero(); meq * width];
+        for row in 0..meq {
+            c[row * width..row * width + n].copy_from_slice(self.equality(row));
+            c[row * width + n + 1 + row] = -T::one();
+            c[row * width + n + 1 + meq + row] = T::one();
+            let excess = self.b[row] - dot(self.equality(row), &x[..n]);
+            x[n + 1 + row] = (-excess).max(T::zero());
+            x[n + 1 + meq + row] = excess.max(T::zero());
+        }
+        let mut g = vec![T::zero(); (self.h.len() + width - n) * width];
+        let mut h = self.h.clone();
+        let mut kinds = self.kinds.clone();
+        for (row, kind) in self.kinds.iter().enumerate() {
+            g[row * width..row * width + n].copy_from_slice(self.row(row));
+            if let Row::Inequality(_) = kind {
+                g[row * width + n] = -T::one();
+                x[n] = x[n].max(dot(self.row(row), &x[..n]) - self.h[row]);
+            }
+        }
+        for (k, var) in (n..width).enumerate() {
+            let row = self.h.len() + k;
+            g[row * width + var] = -T::one();
+            h.push(T::zero());
+            kinds.push(Row::Lower(var));
+        }
+        let mut q = vec![T::zero(); width];
+        q[n..].fill(T::one());
+        let phase_one = Self {
+            n: width,
+            meq,
+            mineq: self.mineq,
+            has_bounds: true,
+            p: vec![T::zero(); width * width],
+            q,
+            c,
+            b: self.b.clone(),
+            g,
+            h,
+            kinds,
+        };
+        (phase_one, x)
+    }
+
+    fn dual_len(&self) -> usize {
+        self.mineq + if self.has_bounds { self.n } else { 0 }
+    }
+
+    // Inequality multipliers followed, when the problem has bounds, by the
+    // net multiplier upper - lower of each variable.
+    fn split_dual(&self, working: &[usize], multipliers: &[T]) -> Vec<T> {
+        let mut dual = vec![T::zero(); self.dual_len()];
+        for (&row, &value) in working.iter().zip(multipliers) {
+            match self.kinds[row] {
+                Row::Inequality(i) => dual[i] = value,
+                Row::Upper(i) => dual[self.mineq + i] += value,
+                Row::Lower(i) => dual[self.mineq + i] -= value,
+            }
+        }
+        dual
+    }
+
+    fn active_set(&self, working: &[usize]) -> ActiveSet {
+        let mut active = ActiveSet::default();
+        for &row in working {
+            match self.kinds[row] {
+                Row::Inequality(i) => active.inequalities.push(i),
+                Row::Upper(i) => active.upper_bounds.push(i),
+                Row::Lower(i) => active.lower_bounds.push(i),
+            }
+        }
+        active.inequalities.sort_unstable();
+        active.upper_bounds.sort_unstable();
+        active.lower_bounds.sort_unstable();
+        active
+    }
+}
+
+// Runs the primal active-set iteration from a feasible x. Each step
+// minimizes the objective over the null space of the equality and working
+// rows, or follows a direction of zero curvature along which it decreases,
+// until a row blocks it. Once x is optimal on the working set, the working
+// row with the most negative multiplier leaves it; after a step of zero
+// length it is the lowest such row instead, which with the lowest blocking
+// row entering is Bland's rule and keeps degenerate vertices from cycling.
+fn minimize<T: RealNumber + FromPrimitive>(
+    qp: &DenseQp<T>,
+    x: &mut [T],
+    working: &mut Vec<usize>,
+    progress: &mut Progress<'_, T>,
+) -> Outcome<T> {
+    let tolerance = T::from_f64(ACTIVE_SET_TOLERANCE).unwrap();
+    let mut degenerate = false;
+    loop {
+        if let Some(status) = progress.stopped() {
+            return Outcome::Stopped(status);
+        }
+        let gradient = qp.gradient(x);
+        let rows: Vec<&[T]> = (0..qp.meq)
+            .map(|row| qp.equality(row))
+            .chain(working.iter().map(|&row| qp.row(row)))
+            .collect();
+        let basis = Basis::new(&rows, qp.n);
+        let step = basis.step(&qp.p, &gradient);
+        progress.stats.factorizations += 1;
+        progress.stats.linear_solves += 1;
+        progress.record(
+            qp.violation(x),
+            norm_inf(&basis.project(&gradient)),
+            qp.objective(x),
+        );
+
+        let length = norm_inf(&step.direction);
+        if !step.ray && length <= tolerance * (T::one() + norm_inf(x)) {
+            let multipliers = basis.multipliers(&gradient, rows.len());
+            let threshold = -tolerance * (T::one() + norm_inf(&gradient));
+            let candidates = multipliers[qp.meq..]
+                .iter()
+                .enumerate()
+                .filter(|(_, &value)| value < threshold);
+            let leaving = if degenerate {
+                candidates.min_by_key(|&(k, _)| working[k]).map(|(k, _)| k)
+            } else {
+                candidates.min_by(|a, b| a.1.total_cmp(b.1)).map(|(k, _)| k)
+            };
+            match leaving {
+                Some(k) => {
+                    working.remove(k);
+                    continue;
+                }
+                None => {
+                    let (equality, working) = multipliers.split_at(qp.meq);
+                    return Outcome::Optimal {
+                        equality: equality.to_vec(),
+                        working: working.to_vec(),
+                    };
+                }
+            }
+        }
+
+        let mut alpha = if step.ray { T::infinity() } else { T::one() };
+        let mut blocking = None;
+        for row in 0..qp.h.len() {
+            if working.contains(&row) {
+                continue;
+            }
+            let coefficients = qp.row(row);
+            let slope = dot(coefficients, &step.direction);
+            if slope <= tolerance * norm_inf(coefficients) * length {
+                continue;
+            }
+            let limit = ((qp.h[row] - dot(coefficients, x)) / slope).max(T::zero());
+            if limit < alpha {
+                alpha = limit;
+                blocking = Some(row);
+            }
+        }
+        if alpha.is_infinite() {
+            return Outcome::Unbounded(step.direction);
+        }
+        degenerate = alpha * length <= tolerance * (T::one() + norm_inf(x));
+        axpy(alpha, &step.direction, x);
+        if let Some(row) = blocking {
+            working.push(row);
+        }
+    }
+}
+
+struct Step<T> {
+    direction: Vec<T>,
+    ray: bool,
+}
+
+// Orthonormal bases of the span of the working rows and of its complement,
+// the null space the steps move in. The kept rows equal Q R with R upper
+// triangular; rows that depend on earlier ones are skipped.
+struct Basis<T: RealNumber> {
+    n: usize,
+    range: Vec<Vec<T>>,
+    r: Vec<Vec<T>>,
+    kept: Vec<usize>,
+    null: Vec<Vec<T>>,
+}
+
+impl<T> Basis<T>
+where
+    T: RealNumber + FromPrimitive,
+{
+    fn new(rows: &[&[T]], n: usize) -> Self {
+        let tolerance = T::from_f64(ACTIVE_SET_TOLERANCE).unwrap();
+        let mut range: Vec<Vec<T>> = Vec::new();
+        let mut r = Vec::new();
+        let mut kept = Vec::new();
+        for (index, row) in rows.iter().enumerate() {
+            let mut v = row.to_vec();
+            let mut coefficients = vec![T::zero(); range.len()];
+            // Two passes of Gram–Schmidt keep the basis orthogonal.
+            for _ in 0..2 {
+                for (coefficient, q) in coefficients.iter_mut().zip(&range) {
+                    let c = dot(q, &v);
+                    *coefficient += c;
+                    axpy(-c, q, &mut v);
+                }
+            }
+            let norm = norm2(&v);
+            if norm <= tolerance * norm2(row) {
+                continue;
+            }
+            for value in v.iter_mut() {
+                *value = *value / norm;
+            }
+            coefficients.push(norm);
+            range.push(v);
+            r.push(coefficients);
+            kept.push(index);
+        }
+
+        // Pivoted Gram–Schmidt on the columns of I - QQᵀ.
+        let mut columns: Vec<Vec<T>> = (0..n)
+            .map(|i| {
+                let mut column = vec![T::zero(); n];
+                column[i] = T::one();
+                for q in &range {
+                    axpy(-q[i], q, &mut column);
+                }
+                column
+            })
+            .collect();
+        let mut null: Vec<Vec<T>> = Vec::with_capacity(n - range.len());
+        for _ in range.len()..n {
+            let (best, norm) = columns
+                .iter()
+                .enumerate()
+                .map(|(i, column)| (i, norm2(column)))
+                .max_by(|a, b| a.1.total_cmp(&b.1))
+                .unwrap();
+            if norm <= tolerance {
+                break;
+            }
+            let mut z: Vec<T> = columns[best].iter().map(|&v| v / norm).collect();
+            for q in range.iter().chain(&null) {
+                let c = dot(q, &z);
+                axpy(-c, q, &mut z);
+            }
+            let norm = norm2(&z);
+            for value in z.iter_mut() {
+                *value = *value / norm;
+            }
+            for column in columns.iter_mut() {
+                let c = dot(&z, column);
+                axpy(-c, &z, column);
+            }
+            null.push(z);
+        }
+        Self {
+            n,
+            range,
+            r,
+            kept,
+            null,
+        }
+    }
+
+    // Zᵀv.
+    fn project(&self, v: &[T]) -> Vec<T> {
+        self.null.iter().map(|z| dot(z, v)).collect()
+    }
+
+    // Least-squares multipliers with gradient + Σ λ_i a_i = 0; skipped rows
+    // get zero.
+    fn multipliers(&self, gradient: &[T], count: usize) -> Vec<T> {
+        let k = self.range.len();
+        let rhs: Vec<T> = self.range.iter().map(|q| -dot(q, gradient)).collect();
+        let mut solved = vec![T::zero(); k];
+        for t in (0..k).rev() {
+            let mut value = rhs[t];
+            for (coefficients, &later) in self.r[t + 1..].iter().zip(&solved[t + 1..]) {
+                value -= coefficients[t] * later;
+            }
+            solved[t] = value / self.r[t][t];
+        }
+        let mut multipliers = vec![T::zero(); count];
+        for (&index, &value) in self.kept.iter().zip(&solved) {
+            multipliers[index] = value;
+        }
+        multipliers
+    }
+
+    // Newton step on the reduced problem min ½vᵀHv + rᵀv, H = ZᵀPZ,
+    // r = Zᵀg. A pivoted Cholesky factorization reveals the null space of
+    // H; if r has a component there, the step is the descent direction of
+    // zero curvature it spans instead.
+    fn step(&self, p: &[T], gradient: &[T]) -> Step<T> {
+        let d = self.null.len();
+        let r = self.project(gradient);
+        let pz: Vec<Vec<T>> = self.null.iter().map(|z| multiply(p, self.n, z)).collect();
+        let mut a = vec![T::zero(); d * d];
+        for i in 0..d {
+            for j in 0..d {
+                a[i * d + j] = dot(&self.null[i], &pz[j]);
+            }
+        }
+        let (perm, rank) = pivoted_cholesky(&mut a, d);
+        let rp: Vec<T> = perm.iter().map(|&i| r[i]).collect();
+
+        let tolerance = T::from_f64(ACTIVE_SET_TOLERANCE).unwrap();
+        let mut v = vec![T::zero(); d];
+        let mut ray = false;
+        if rank < d {
+            // Null-space vectors [-L11⁻ᵀ L21ᵀ e_f; e_f] of the permuted H.
+            let mut null_vectors = Vec::with_capacity(d - rank);
+            let mut weights = Vec::with_capacity(d - rank);
+            for f in rank..d {
+                let mut w: Vec<T> = a[f * d..f * d + rank].to_vec();
+                solve_upper_transposed(&a, d, rank, &mut w);
+                let mut vector = vec![T::zero(); d];
+                for (value, &w) in vector.iter_mut().zip(&w) {
+                    *value = -w;
+                }
+                vector[f] = T::one();
+                weights.push(dot(&vector, &rp));
+                null_vectors.push(vector);
+            }
+            if norm_inf(&weights) > tolerance * (T::one() + norm_inf(gradient)) {
+                for (vector, &weight) in null_vectors.iter().zip(&weights) {
+                    axpy(-weight, vector, &mut v);
+                }
+                ray = true;
+            }
+        }
+        if !ray {
+            let mut top: Vec<T> = rp[..rank].iter().map(|&value| -value).collect();
+            solve_lower(&a, d, rank, &mut top);
+            solve_upper_transposed(&a, d, rank, &mut top);
+            v[..rank].copy_from_slice(&top);
+        }
+
+        let mut direction = vec![T::zero(); self.n];
+        for (k, &i) in perm.iter().enumerate() {
+            axpy(v[k], &self.null[i], &mut direction);
+        }
+        Step { direction, ray }
+    }
+}
+
+// Cholesky factorization with diagonal pivoting of the PSD matrix `a`, in
+// place: L is left in the lower triangle of its first `rank` columns, for
+// the rows and columns permuted by the returned order.
+fn pivoted_cholesky<T: RealNumber + FromPrimitive>(a: &mut [T], d: usize) -> (Vec<usize>, usize) {
+    let mut perm: Vec<usize> = (0..d).collect();
+    let largest = (0..d).fold(T::zero(), |acc, i| acc.max(a[i * d + i]));
+    let threshold = T::from_f64(PIVOT_TOLERANCE).unwrap() * largest;
+    let mut rank = 0;
+    for k in 0..d {
+        let (j, pivot) = (k..d)
+            .map(|j| (j, a[j * d + j]))
+            .max_by(|x, y| x.1.total_cmp(&y.1))
+            .unwrap();
+        if pivot <= threshold || pivot <= T::zero() {
+            break;
+        }
+        if j != k {
+            perm.swap(k, j);
+            for col in 0..d {
+                a.swap(k * d + col, j * d + col);
+            }
+            for row in 0..d {
+                a.swap(row * d + k, row * d + j);
+            }
+        }
+        let diagonal = a[k * d + k].sqrt();
+        a[k * d + k] = diagonal;
+        for i in k + 1..d {
+            a[i * d + k] = a[i * d + k] / diagonal;
+        }
+        for i in k + 1..d {
+            for j in k + 1..=i {
+                let value = a[i * d + j] - a[i * d + k] * a[j * d + k];
+                a[i * d + j] = value;
+                a[j * d + i] = value;
+            }
+        }
+        rank = k + 1;
+    }
+    (perm, rank)
+}
+
+// Solves L11 y = b for the leading `rank` block of the factor.
+fn solve_lower<T: RealNumber>(l: &[T], d: usize, rank: usize, b: &mut [T]) {
+    for i in 0..rank {
+        let mut value = b[i];
+        for j in 0..i {
+            value -= l[i * d + j] * b[j];
+        }
+        b[i] = value / l[i * d + i];
+    }
+}
+
+// Solves L11ᵀ y = b for the leading `rank` block of the factor.
+fn solve_upper_transposed<T: RealNumber>(l: &[T], d: usize, rank: usize, b: &mut [T]) {
+    for i in (0..rank).rev() {
+        let mut value = b[i];
+        for j in i + 1..rank {
+            value -= l[j * d + i] * b[j];
+        }
+        b[i] = value / l[i * d + i];
+    }
+}
+
+fn to_dense<T: RealNumber>(matrix: &CscMatrix<T>) -> Vec<T> {
+    let mut dense = vec![T::zero(); matrix.nrows * matrix.ncols];
+    for col in 0..matrix.ncols {
+        for idx in matrix.indptr[col]..matrix.indptr[col + 1] {
+            dense[matrix.indices[idx] * matrix.ncols + col] += matrix.data[idx];
+        }
+    }
+    dense
+}
+
+fn multiply<T: RealNumber>(matrix: &[T], n: usize, x: &[T]) -> Vec<T> {
+    matrix
+        .chunks_exact(n.max(1))
+        .take(n)
+        .map(|row| dot(row, x))
+        .collect()
+}
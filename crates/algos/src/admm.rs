@@ -1,36 +1,144 @@
-use anyhow::Result;
-use cvxrs_core::math::{dot, project_box, relative_gap, residuals_inf, RealNumber, Timer};
-use cvxrs_core::options::SolveOptions;
-use cvxrs_core::problem::{CscMatrix, ProblemLP, ProblemQP, ProblemResult, WarmStart};
-use cvxrs_core::solution::{Solution, Status};
-use cvxrs_core::stats::{IterationRecord, SolveStats};
-use cvxrs_core::traits::{KktSolver, Scaler};
-use cvxrs_linsys::dense::{DenseKktMatrix, DenseKktSolver, DensePattern};
+use crate::simplex::SimplexSolver;
+use anyhow::{bail, Result};
+use cvxrs_core::cones::project_cone;
+#[cfg(feature = "mixed-precision")]
+use cvxrs_core::math::{cast, cast_slice};
+use cvxrs_core::math::{
+    dot, norm2, norm_inf, project_box, relative_gap, residuals_inf, RealNumber, Timer,
+};
+use cvxrs_core::options::{LinsysBackend, Method, SolveOptions};
+use cvxrs_core::problem::{
+    Bounds, CheckpointSink, Cone, CscMatrix, EqualityConstraints, InequalityConstraints, Names,
+    ObjectiveSense, ProblemConic, ProblemLP, ProblemQP, ProblemQpOp, ProblemResult, SolverState,
+    WarmStart,
+};
+use cvxrs_core::solution::{Certificate, Solution, Status};
+use cvxrs_core::stats::{
+    interrupted, InterruptFlag, IterationRecord, IterationSink, KrylovStats, RegularizationStats,
+    RhoUpdate, SolveStats,
+};
+use cvxrs_core::traits::{
+    KktSolver, LinearOperator, ResidualTolerance, Scaler, StoppingCriterion, StoppingRule,
+};
+use cvxrs_linsys::cg::{CgKktSolver, CgPattern, CgSystem};
+use cvxrs_linsys::dense::{DenseKktMatrix, DenseKktSolver};
+#[cfg(feature = "gpu")]
+use cvxrs_linsys::gpu::{GpuDevice, GpuMatrix};
+use cvxrs_linsys::minres::{MinresKktSolver, MinresPattern, MinresSystem};
+use cvxrs_linsys::regularization::Regularization;
+use cvxrs_linsys::sparse::{SparseKktMatrix, SparseKktSolver, SparsePattern};
 use num_traits::FromPrimitive;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+use sprs::CsMat;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::Duration;
 
 pub type AdmmResult<T> = Solution<T>;
 
+// Keeps the (2,2) block of the equality KKT system strictly negative so
+// redundant equality rows do not produce a zero pivot.
+const EQUALITY_REGULARIZATION: f64 = 1e-10;
+
+// Rho only changes when the residual-ratio rule moves it by more than this
+// factor, so small fluctuations do not cost a refactorization.
+const RHO_UPDATE_TOLERANCE: f64 = 5.0;
+
+// Rho of the equality rows of a matrix-free solve relative to the others,
+// as in OSQP, since those rows cannot be enforced inside the x-update.
+const OPERATOR_EQUALITY_RHO_SCALE: f64 = 1e3;
+
+// Relative tolerance of the conjugate gradient solves whose products run
+// in single precision on the GPU.
+#[cfg(feature = "gpu")]
+const GPU_CG_TOLERANCE: f64 = 1e-6;
+
+// P and A uploaded to the device.
+#[cfg(feature = "gpu")]
+type GpuData<T> = (Arc<GpuMatrix<T>>, Arc<GpuMatrix<T>>);
+
+// Keeps the scaled residuals finite when the iterate or the data vanish.
+const RHO_DIVISION_FLOOR: f64 = 1e-30;
+
+// The f32 stage of a mixed-precision solve stops at this tolerance, below
+// which single precision residuals are mostly rounding noise.
+#[cfg(feature = "mixed-precision")]
+const MIXED_PRECISION_TOLERANCE: f64 = 1e-4;
+
+// Relative Tikhonov term of the Anderson least-squares problem.
+const ANDERSON_REGULARIZATION: f64 = 1e-10;
+
+// Regularization of the polishing KKT system and the refinement steps
+// against the exact one, as in OSQP.
+const POLISH_REGULARIZATION: f64 = 1e-6;
+const POLISH_REFINEMENT_STEPS: usize = 3;
+
+// Residuals below this never block a polished point.
+const POLISH_RESIDUAL_FLOOR: f64 = 1e-10;
+
+// Multiply-adds below which a dense kernel stays on the calling thread;
+// smaller problems lose more to scheduling than they gain.
+#[cfg(feature = "rayon")]
+const PARALLEL_WORK: usize = 1 << 16;
+
+// Dense P and A, the upper triangle of the KKT matrix, which holds P's
+// again next to A, and the L factor, which can fill in up to the whole lower
+// triangle of the n + m unknowns.
+pub fn workspace_bytes<T: RealNumber>(nvars: usize, nrows: usize) -> u64 {
+    let n = nvars as u64;
+    let m = nrows as u64;
+    let dim = n.saturating_add(m);
+    let square = n.saturating_mul(n);
+    let scalars = m
+        .saturating_mul(n)
+        .saturating_add(square)
+        .saturating_add((square / 2).saturating_add(m.saturating_mul(n)))
+        .saturating_add(dim.saturating_mul(dim) / 2)
+        .saturating_add(8 * n + 6 * m);
+    scalars.saturating_mul(std::mem::size_of::<T>() as u64)
+}
+
+// Rows are stacked as [equalities; inequalities; bounds; cone rows]. The
+// first `meq` rows are enforced exactly inside the x-update; the remaining
+// box rows carry the rho penalty. P and the stacked A are kept dense for the
+// iteration products and compressed for the sparse KKT matrix.
 struct AdmmWorkspace<T: RealNumber> {
     n: usize,
     m: usize,
-    p_base: Vec<T>,
-    ata: Vec<T>,
-    a_dense: Vec<T>,
+    meq: usize,
+    p_base: Arc<Vec<T>>,
+    a_dense: Arc<Vec<T>>,
+    p_sparse: CscMatrix<T>,
+    a_rows: CscMatrix<T>,
     lower: Vec<T>,
     upper: Vec<T>,
+    cones: Vec<ConeBlock<T>>,
+}
+
+// Second-order, PSD and exponential rows of a conic problem, b - Ax in K.
+struct ConeRows<T: RealNumber> {
+    matrix: CscMatrix<T>,
+    rhs: Vec<T>,
+    cones: Vec<Cone>,
+}
+
+// Cone rows keep infinite box bounds; z is projected onto rhs - K instead.
+struct ConeBlock<T: RealNumber> {
+    start: usize,
+    cone: Cone,
+    rhs: Vec<T>,
 }
 
 impl<T> AdmmWorkspace<T>
 where
     T: RealNumber + FromPrimitive,
 {
-    fn new(problem: &ProblemQP<T>) -> ProblemResult<Self> {
+    fn new(problem: &ProblemQP<T>, cone_rows: Option<&ConeRows<T>>) -> ProblemResult<Self> {
         let n = problem.nvars();
-        let mut m = 0;
+        let meq = problem.equalities.as_ref().map_or(0, |eq| eq.matrix.nrows);
+        let mut m = meq;
         let mut has_bounds = false;
-        if let Some(eq) = &problem.equalities {
-            m += eq.matrix.nrows;
-        }
         if let Some(ineq) = &problem.inequalities {
             m += ineq.matrix.nrows;
         }
@@ -38,126 +146,832 @@ where
             has_bounds = true;
             m += bounds.lower.len();
         }
+        if let Some(cone_rows) = cone_rows {
+            m += cone_rows.rhs.len();
+        }
         let mut a_dense = vec![T::zero(); m * n];
-        let mut lower = vec![T::neg_infinity(); m];
-        let mut upper = vec![T::infinity(); m];
         let mut row_offset = 0;
         if let Some(eq) = &problem.equalities {
             scatter_csc(&eq.matrix, n, row_offset, &mut a_dense);
-            for (idx, value) in eq.rhs.iter().enumerate() {
-                lower[row_offset + idx] = *value;
-                upper[row_offset + idx] = *value;
-            }
             row_offset += eq.matrix.nrows;
         }
         if let Some(ineq) = &problem.inequalities {
             scatter_csc(&ineq.matrix, n, row_offset, &mut a_dense);
-            for (idx, value) in ineq.rhs.iter().enumerate() {
-                upper[row_offset + idx] = *value;
-            }
             row_offset += ineq.matrix.nrows;
         }
         if has_bounds {
-            if let Some(bounds) = &problem.bounds {
-                for var in 0..n {
-                    let row = row_offset + var;
-                    let col = var;
-                    a_dense[row * n + col] = T::one();
-                    lower[row] = bounds.lower[var];
-                    upper[row] = bounds.upper[var];
-                }
+            for var in 0..n {
+                a_dense[(row_offset + var) * n + var] = T::one();
+            }
+            row_offset += n;
+        }
+        let mut cones = Vec::new();
+        if let Some(cone_rows) = cone_rows {
+            scatter_csc(&cone_rows.matrix, n, row_offset, &mut a_dense);
+            let mut start = 0;
+            for cone in &cone_rows.cones {
+                cones.push(ConeBlock {
+                    start: row_offset + start,
+                    cone: cone.clone(),
+                    rhs: cone_rows.rhs[start..start + cone.dim()].to_vec(),
+                });
+                start += cone.dim();
             }
         }
         let p_base = csc_to_dense(&problem.quadratic);
-        let ata = compute_ata(&a_dense, m, n);
-        Ok(Self {
+        let identity = identity_csc(n, T::one());
+        let mut blocks: Vec<&CscMatrix<T>> = Vec::new();
+        if let Some(eq) = &problem.equalities {
+            blocks.push(&eq.matrix);
+        }
+        if let Some(ineq) = &problem.inequalities {
+            blocks.push(&ineq.matrix);
+        }
+        if has_bounds {
+            blocks.push(&identity);
+        }
+        if let Some(cone_rows) = cone_rows {
+            blocks.push(&cone_rows.matrix);
+        }
+        let mut workspace = Self {
             n,
             m,
-            p_base,
-            ata,
-            a_dense,
-            lower,
-            upper,
-        })
+            meq,
+            p_base: Arc::new(p_base),
+            a_dense: Arc::new(a_dense),
+            p_sparse: problem.quadratic.clone(),
+            a_rows: stack_rows(n, &blocks),
+            lower: vec![T::neg_infinity(); m],
+            upper: vec![T::infinity(); m],
+            cones,
+        };
+        workspace.set_rhs(problem);
+        Ok(workspace)
+    }
+
+    // Row bounds of the stacked constraints, the only part of the workspace
+    // that differs between members of a parametric batch.
+    fn set_rhs(&mut self, problem: &ProblemQP<T>) {
+        let mut row = 0;
+        if let Some(eq) = &problem.equalities {
+            let rows = row..row + eq.rhs.len();
+            self.lower[rows.clone()].copy_from_slice(&eq.rhs);
+            self.upper[rows].copy_from_slice(&eq.rhs);
+            row += eq.rhs.len();
+        }
+        if let Some(ineq) = &problem.inequalities {
+            self.upper[row..row + ineq.rhs.len()].copy_from_slice(&ineq.rhs);
+            row += ineq.rhs.len();
+        }
+        if let Some(bounds) = &problem.bounds {
+            self.lower[row..row + self.n].copy_from_slice(&bounds.lower);
+            self.upper[row..row + self.n].copy_from_slice(&bounds.upper);
+        }
+    }
+
+    fn project(&self, z: &mut [T]) {
+        project_box(z, &self.lower, &self.upper);
+        for block in &self.cones {
+            let values = &mut z[block.start..block.start + block.rhs.len()];
+            let mut slack: Vec<T> = block
+                .rhs
+                .iter()
+                .zip(values.iter())
+                .map(|(&b, &v)| b - v)
+                .collect();
+            project_cone(&block.cone, &mut slack);
+            for ((value, &b), &s) in values.iter_mut().zip(&block.rhs).zip(&slack) {
+                *value = b - s;
+            }
+        }
     }
 
     fn multiply_a(&self, x: &[T], out: &mut [T]) {
         assert_eq!(x.len(), self.n);
         assert_eq!(out.len(), self.m);
-        for row in 0..self.m {
+        let row_product = |(value, coefficients): (&mut T, &[T])| {
             let mut acc = T::zero();
-            for col in 0..self.n {
-                acc += self.a_dense[row * self.n + col] * x[col];
+            for (&a, &x) in coefficients.iter().zip(x) {
+                acc += a * x;
             }
-            out[row] = acc;
+            *value = acc;
+        };
+        #[cfg(feature = "rayon")]
+        if self.m * self.n >= PARALLEL_WORK {
+            out.par_iter_mut()
+                .zip(self.a_dense.par_chunks_exact(self.n))
+                .for_each(row_product);
+            return;
         }
+        out.iter_mut()
+            .zip(self.a_dense.chunks_exact(self.n))
+            .for_each(row_product);
     }
 
     fn multiply_at(&self, dual: &[T], out: &mut [T]) {
         assert_eq!(dual.len(), self.m);
         assert_eq!(out.len(), self.n);
-        for col in 0..self.n {
+        let column_product = |(col, value): (usize, &mut T)| {
             let mut acc = T::zero();
-            for row in 0..self.m {
-                acc += self.a_dense[row * self.n + col] * dual[row];
+            for (row, &y) in dual.iter().enumerate() {
+                acc += self.a_dense[row * self.n + col] * y;
             }
-            out[col] = acc;
+            *value = acc;
+        };
+        #[cfg(feature = "rayon")]
+        if self.m * self.n >= PARALLEL_WORK {
+            out.par_iter_mut().enumerate().for_each(column_product);
+            return;
         }
+        out.iter_mut().enumerate().for_each(column_product);
     }
 }
 
+// Quasi-definite x-update system
+//   [P + σI   Cᵀ      Gᵀ  ] [x]   [σx⁻ - q ]
+//   [  C     -δI      0   ] [ν] = [   b    ]
+//   [  G      0    -I/ρ   ] [w]   [z - y/ρ ]
+// with C the equality rows, G the box rows and x⁻ the previous iterate;
+// eliminating w gives back the normal equations with P + σI + ρGᵀG. The
+// proximal σI keeps it quasi-definite even when P and G leave directions
+// free, so the unpivoted LDLᵀ factorization exists and Cx = b holds to
+// factorization accuracy at every iterate. Rho only enters the trailing
+// diagonal, so a rho update keeps the sparsity pattern and its analysis and
+// repeats just the numeric factorization.
+//
+// The conjugate gradient backend solves those normal equations instead,
+// over every row, so equalities are penalized like the box rows and their
+// multipliers follow the ordinary dual update.
 struct LinearSystem<T: RealNumber> {
     n: usize,
-    base: Vec<T>,
-    ata: Vec<T>,
-    buffer: Vec<T>,
-    solver: DenseKktSolver<T>,
+    // Rows whose multipliers come out of the solve.
+    exact_rows: usize,
+    sigma: T,
+    backend: Backend<T>,
+    rhs: Vec<T>,
+    dual: Vec<T>,
     current_rho: Option<T>,
 }
 
+enum Backend<T: RealNumber> {
+    Direct {
+        kkt: SparseKktMatrix<T>,
+        // Positions of the -1/ρ diagonal entries in the stored values.
+        rho_slots: Vec<usize>,
+        solver: Box<SparseKktSolver<T>>,
+    },
+    // The same matrix, solved by MINRES through its products.
+    Minres {
+        kkt: SparseKktMatrix<T>,
+        rho_slots: Vec<usize>,
+        solver: MinresKktSolver<T>,
+    },
+    ConjugateGradient {
+        p: Arc<Vec<T>>,
+        a: Arc<Vec<T>>,
+        // Σ_r A_ri², the diagonal of AᵀA.
+        column_norms: Vec<T>,
+        solver: CgKktSolver<T>,
+        // P and A on the device when the products run there.
+        #[cfg(feature = "gpu")]
+        gpu: Option<GpuData<T>>,
+    },
+}
+
 impl<T> LinearSystem<T>
 where
     T: RealNumber + FromPrimitive,
 {
-    fn new(base: Vec<T>, ata: Vec<T>, n: usize) -> Result<Self> {
-        let mut solver = DenseKktSolver::new();
-        solver.analyze_pattern(&DensePattern::new(n))?;
+    fn new(workspace: &AdmmWorkspace<T>, options: &SolveOptions<T>) -> Result<Self> {
+        let (n, m) = (workspace.n, workspace.m);
+        let (backend, exact_rows, dim) = match options.linsys_backend {
+            LinsysBackend::Direct => {
+                let (kkt, rho_slots) = Self::quasi_definite(workspace, options);
+                let mut solver = SparseKktSolver::new()
+                    .with_refinement(options.refinement_steps)
+                    .with_regularization(Regularization::quasi_definite(n, m));
+                solver.analyze_pattern(&SparsePattern::from_matrix(&kkt.matrix))?;
+                let backend = Backend::Direct {
+                    kkt,
+                    rho_slots,
+                    solver: Box::new(solver),
+                };
+                (backend, workspace.meq, n + m)
+            }
+            LinsysBackend::Minres => {
+                let (kkt, rho_slots) = Self::quasi_definite(workspace, options);
+                let mut solver = MinresKktSolver::new();
+                solver.analyze_pattern(&MinresPattern::new(n + m))?;
+                let backend = Backend::Minres {
+                    kkt,
+                    rho_slots,
+                    solver,
+                };
+                (backend, workspace.meq, n + m)
+            }
+            LinsysBackend::ConjugateGradient | LinsysBackend::Gpu => {
+                let mut column_norms = vec![T::zero(); n];
+                for row in workspace.a_dense.chunks_exact(n.max(1)).take(m) {
+                    for (norm, &a) in column_norms.iter_mut().zip(row) {
+                        *norm += a * a;
+                    }
+                }
+                let mut solver = CgKktSolver::new();
+                #[cfg(feature = "gpu")]
+                let gpu = if options.linsys_backend == LinsysBackend::Gpu {
+                    // The products are only single precision.
+                    solver = solver.with_tolerance(T::from_f64(GPU_CG_TOLERANCE).unwrap());
+                    let device = GpuDevice::shared()?;
+                    let p = GpuMatrix::upload(device.clone(), n, n, &workspace.p_base)?;
+                    let a = GpuMatrix::upload(device, m, n, &workspace.a_dense[..m * n])?;
+                    Some((Arc::new(p), Arc::new(a)))
+                } else {
+                    None
+                };
+                #[cfg(not(feature = "gpu"))]
+                if options.linsys_backend == LinsysBackend::Gpu {
+                    bail!("the GPU backend needs cvxrs built with the gpu feature");
+                }
+                solver.analyze_pattern(&CgPattern::new(n))?;
+                let backend = Backend::ConjugateGradient {
+                    p: workspace.p_base.clone(),
+                    a: workspace.a_dense.clone(),
+                    column_norms,
+                    solver,
+                    #[cfg(feature = "gpu")]
+                    gpu,
+                };
+                (backend, 0, n)
+            }
+        };
         Ok(Self {
             n,
-            buffer: base.clone(),
-            base,
-            ata,
-            solver,
+            exact_rows,
+            sigma: options.sigma,
+            backend,
+            rhs: vec![T::zero(); dim],
+            dual: vec![T::zero(); m],
             current_rho: None,
         })
     }
 
-    fn factor(&mut self, rho: T) -> Result<()> {
+    // The KKT matrix with every rho entry still at -δ, and where those
+    // entries are stored.
+    fn quasi_definite(
+        workspace: &AdmmWorkspace<T>,
+        options: &SolveOptions<T>,
+    ) -> (SparseKktMatrix<T>, Vec<usize>) {
+        let (n, m, meq) = (workspace.n, workspace.m, workspace.meq);
+        let delta = T::from_f64(EQUALITY_REGULARIZATION).unwrap();
+        // Upper triangle by columns; the diagonal is always stored so that
+        // the pattern does not depend on the values.
+        let mut indptr = Vec::with_capacity(n + m + 1);
+        let mut indices = Vec::new();
+        let mut data = Vec::new();
+        let mut rho_slots = Vec::with_capacity(m - meq);
+        indptr.push(0);
+        let (p, a) = (&workspace.p_sparse, &workspace.a_rows);
+        for col in 0..n {
+            let mut diagonal = options.sigma;
+            for idx in p.indptr[col]..p.indptr[col + 1] {
+                let (row, value) = (p.indices[idx], p.data[idx]);
+                if row == col {
+                    diagonal += value;
+                } else if row < col && value != T::zero() {
+                    indices.push(row);
+                    data.push(value);
+                }
+            }
+            indices.push(col);
+            data.push(diagonal);
+            indptr.push(indices.len());
+        }
+        for r in 0..m {
+            for idx in a.indptr[r]..a.indptr[r + 1] {
+                if a.data[idx] != T::zero() {
+                    indices.push(a.indices[idx]);
+                    data.push(a.data[idx]);
+                }
+            }
+            if r >= meq {
+                rho_slots.push(data.len());
+            }
+            indices.push(n + r);
+            data.push(-delta);
+            indptr.push(indices.len());
+        }
+        let kkt = CsMat::new((n + m, n + m), indptr, indices, data);
+        (SparseKktMatrix::new(kkt), rho_slots)
+    }
+
+    fn set_rho(kkt: &mut SparseKktMatrix<T>, rho_slots: &[usize], rho: T) {
+        let data = kkt.matrix.data_mut();
+        for &slot in rho_slots {
+            data[slot] = -rho.recip();
+        }
+    }
+
+    // Takes the matrices of `workspace` after their values changed. The
+    // direct backend keeps its symbolic analysis when no entry appeared or
+    // vanished; the next `factor` refactors whatever rho it is given.
+    fn update_values(
+        &mut self,
+        workspace: &AdmmWorkspace<T>,
+        options: &SolveOptions<T>,
+    ) -> Result<()> {
+        match &mut self.backend {
+            Backend::Direct {
+                kkt,
+                rho_slots,
+                solver,
+            } => {
+                let (values, slots) = Self::quasi_definite(workspace, options);
+                if values.matrix.indptr().raw_storage() != kkt.matrix.indptr().raw_storage()
+                    || values.matrix.indices() != kkt.matrix.indices()
+                {
+                    solver.analyze_pattern(&SparsePattern::from_matrix(&values.matrix))?;
+                }
+                *kkt = values;
+                *rho_slots = slots;
+            }
+            Backend::Minres { kkt, rho_slots, .. } => {
+                (*kkt, *rho_slots) = Self::quasi_definite(workspace, options);
+            }
+            Backend::ConjugateGradient { .. } => *self = Self::new(workspace, options)?,
+        }
+        self.current_rho = None;
+        Ok(())
+    }
+
+    // Returns whether a new factorization was needed.
+    fn factor(&mut self, rho: T) -> Result<bool> {
         if self
             .current_rho
             .map(|prev| (prev - rho).abs() <= T::from_f64(1e-12).unwrap() * (T::one() + rho.abs()))
             .unwrap_or(false)
         {
-            return Ok(());
+            return Ok(false);
         }
-        self.buffer.clone_from(&self.base);
-        for i in 0..self.n * self.n {
-            self.buffer[i] = self.buffer[i] + rho * self.ata[i];
+        match &mut self.backend {
+            Backend::Direct {
+                kkt,
+                rho_slots,
+                solver,
+            } => {
+                Self::set_rho(kkt, rho_slots, rho);
+                solver.factor(kkt)?;
+            }
+            Backend::Minres {
+                kkt,
+                rho_slots,
+                solver,
+            } => {
+                Self::set_rho(kkt, rho_slots, rho);
+                let diagonal = kkt.diagonal();
+                solver.factor(&MinresSystem::new(Arc::new(kkt.clone()), diagonal))?;
+            }
+            Backend::ConjugateGradient {
+                p,
+                a,
+                column_norms,
+                solver,
+                #[cfg(feature = "gpu")]
+                gpu,
+            } => {
+                let n = self.n;
+                let diagonal = column_norms
+                    .iter()
+                    .enumerate()
+                    .map(|(i, &norm)| p[i * n + i] + self.sigma + rho * norm)
+                    .collect();
+                let operator: Arc<dyn LinearOperator<T>> = Arc::new(ReducedOperator {
+                    n,
+                    m: self.dual.len(),
+                    p: p.clone(),
+                    a: a.clone(),
+                    sigma: self.sigma,
+                    rho,
+                });
+                #[cfg(feature = "gpu")]
+                let operator = match gpu {
+                    Some((p, a)) => Arc::new(GpuReducedOperator {
+                        p: p.clone(),
+                        a: a.clone(),
+                        sigma: self.sigma,
+                        rho,
+                    }),
+                    None => operator,
+                };
+                solver.factor(&CgSystem::new(operator, diagonal))?;
+            }
         }
-        let matrix = DenseKktMatrix::new(self.n, self.buffer.clone());
-        self.solver.factor(&matrix)?;
         self.current_rho = Some(rho);
+        Ok(true)
+    }
+
+    // Replaces the previous iterate `x` with the solution of the x-update.
+    fn solve(
+        &mut self,
+        workspace: &AdmmWorkspace<T>,
+        linear: &[T],
+        x: &mut [T],
+        z: &[T],
+        y: &[T],
+        rho: T,
+    ) -> Result<()> {
+        let n = self.n;
+        match &self.backend {
+            Backend::Direct { .. } | Backend::Minres { .. } => {
+                let meq = self.exact_rows;
+                for ((value, &q), &xk) in self.rhs.iter_mut().zip(linear).zip(x.iter()) {
+                    *value = self.sigma * xk - q;
+                }
+                self.rhs[n..n + meq].copy_from_slice(&workspace.lower[..meq]);
+                for i in meq..workspace.m {
+                    self.rhs[n + i] = z[i] - y[i] / rho;
+                }
+                match &self.backend {
+                    Backend::Direct { solver, .. } => solver.solve(&mut self.rhs)?,
+                    Backend::Minres { solver, .. } => solver.solve(&mut self.rhs)?,
+                    Backend::ConjugateGradient { .. } => unreachable!(),
+                }
+            }
+            Backend::ConjugateGradient {
+                solver,
+                #[cfg(feature = "gpu")]
+                gpu,
+                ..
+            } => {
+                for ((value, &z), &y) in self.dual.iter_mut().zip(z).zip(y) {
+                    *value = rho * z - y;
+                }
+                #[cfg(feature = "gpu")]
+                if let Some((_, a)) = gpu {
+                    a.apply_transpose(&self.dual, &mut self.rhs);
+                } else {
+                    workspace.multiply_at(&self.dual, &mut self.rhs);
+                }
+                #[cfg(not(feature = "gpu"))]
+                workspace.multiply_at(&self.dual, &mut self.rhs);
+                for ((value, &q), &xk) in self.rhs.iter_mut().zip(linear).zip(x.iter()) {
+                    *value += self.sigma * xk - q;
+                }
+                solver.solve(&mut self.rhs)?;
+            }
+        }
+        x.copy_from_slice(&self.rhs[..n]);
         Ok(())
     }
 
-    fn solve(&self, rhs: &mut [T]) -> Result<()> {
-        self.solver.solve(rhs)
+    // Multipliers of the first `exact_rows` rows from the last solve.
+    fn multipliers(&self) -> &[T] {
+        &self.rhs[self.n..self.n + self.exact_rows]
+    }
+
+    fn symbolic_time(&self) -> Duration {
+        match &self.backend {
+            Backend::Direct { solver, .. } => solver.symbolic_time(),
+            Backend::Minres { solver, .. } => solver.symbolic_time(),
+            Backend::ConjugateGradient { solver, .. } => solver.symbolic_time(),
+        }
+    }
+
+    fn numeric_time(&self) -> Duration {
+        match &self.backend {
+            Backend::Direct { solver, .. } => solver.numeric_time(),
+            Backend::Minres { solver, .. } => solver.numeric_time(),
+            Backend::ConjugateGradient { solver, .. } => solver.numeric_time(),
+        }
+    }
+
+    fn krylov_stats(&self) -> Option<KrylovStats> {
+        match &self.backend {
+            Backend::Direct { solver, .. } => solver.krylov_stats(),
+            Backend::Minres { solver, .. } => solver.krylov_stats(),
+            Backend::ConjugateGradient { solver, .. } => solver.krylov_stats(),
+        }
+    }
+
+    fn regularization_stats(&self) -> Option<RegularizationStats<T>> {
+        match &self.backend {
+            Backend::Direct { solver, .. } => solver.regularization_stats(),
+            Backend::Minres { solver, .. } => solver.regularization_stats(),
+            Backend::ConjugateGradient { solver, .. } => solver.regularization_stats(),
+        }
+    }
+
+    fn condition_estimate(&self) -> Option<T> {
+        match &self.backend {
+            Backend::Direct { solver, .. } => solver.condition_estimate(),
+            Backend::Minres { solver, .. } => solver.condition_estimate(),
+            Backend::ConjugateGradient { solver, .. } => solver.condition_estimate(),
+        }
+    }
+}
+
+// P + σI + ρAᵀA applied as Px + σx + ρAᵀ(Ax), without forming AᵀA.
+struct ReducedOperator<T: RealNumber> {
+    n: usize,
+    m: usize,
+    p: Arc<Vec<T>>,
+    a: Arc<Vec<T>>,
+    sigma: T,
+    rho: T,
+}
+
+impl<T: RealNumber> LinearOperator<T> for ReducedOperator<T> {
+    fn dim(&self) -> (usize, usize) {
+        (self.n, self.n)
+    }
+
+    fn apply(&self, x: &[T], y: &mut [T]) {
+        let n = self.n;
+        multiply_dense(&self.p, n, n, x, y);
+        for (value, &xi) in y.iter_mut().zip(x) {
+            *value += self.sigma * xi;
+        }
+        for row in self.a.chunks_exact(n.max(1)).take(self.m) {
+            let ax = dot(row, x) * self.rho;
+            for (value, &a) in y.iter_mut().zip(row) {
+                *value += a * ax;
+            }
+        }
+    }
+}
+
+// The rows of a matrix-free QP: A, then the identity when there are
+// bounds.
+struct StackedOperator<T: RealNumber> {
+    constraints: Option<Arc<dyn LinearOperator<T>>>,
+    n: usize,
+    m: usize,
+    bounded: bool,
+}
+
+impl<T: RealNumber> LinearOperator<T> for StackedOperator<T> {
+    fn dim(&self) -> (usize, usize) {
+        (self.m + if self.bounded { self.n } else { 0 }, self.n)
+    }
+
+    fn apply(&self, x: &[T], y: &mut [T]) {
+        let (a, identity) = y.split_at_mut(self.m);
+        if let Some(constraints) = &self.constraints {
+            constraints.apply(x, a);
+        }
+        identity.copy_from_slice(&x[..identity.len()]);
+    }
+
+    fn apply_transpose(&self, x: &[T], y: &mut [T]) {
+        let (a, identity) = x.split_at(self.m);
+        match &self.constraints {
+            Some(constraints) => constraints.apply_transpose(a, y),
+            None => y.fill(T::zero()),
+        }
+        for (value, &v) in y.iter_mut().zip(identity) {
+            *value += v;
+        }
+    }
+}
+
+// P + σI + KᵀWK for the stacked rows K and per-row weights W.
+struct NormalOperator<T: RealNumber> {
+    quadratic: Arc<dyn LinearOperator<T>>,
+    rows: Arc<StackedOperator<T>>,
+    sigma: T,
+    weights: Vec<T>,
+}
+
+impl<T: RealNumber> LinearOperator<T> for NormalOperator<T> {
+    fn dim(&self) -> (usize, usize) {
+        (self.rows.n, self.rows.n)
+    }
+
+    fn apply(&self, x: &[T], y: &mut [T]) {
+        self.quadratic.apply(x, y);
+        let mut kx = vec![T::zero(); self.weights.len()];
+        self.rows.apply(x, &mut kx);
+        for (value, &w) in kx.iter_mut().zip(&self.weights) {
+            *value *= w;
+        }
+        let mut ktwkx = vec![T::zero(); x.len()];
+        self.rows.apply_transpose(&kx, &mut ktwkx);
+        for ((value, &xi), &k) in y.iter_mut().zip(x).zip(&ktwkx) {
+            *value += self.sigma * xi + k;
+        }
+    }
+}
+
+// The same operator with P and A on the GPU, at three round trips per
+// product.
+#[cfg(feature = "gpu")]
+struct GpuReducedOperator<T: RealNumber> {
+    p: Arc<GpuMatrix<T>>,
+    a: Arc<GpuMatrix<T>>,
+    sigma: T,
+    rho: T,
+}
+
+#[cfg(feature = "gpu")]
+impl<T: RealNumber> LinearOperator<T> for GpuReducedOperator<T> {
+    fn dim(&self) -> (usize, usize) {
+        self.p.dim()
+    }
+
+    fn apply(&self, x: &[T], y: &mut [T]) {
+        self.p.apply(x, y);
+        let mut ax = vec![T::zero(); self.a.dim().0];
+        self.a.apply(x, &mut ax);
+        for value in ax.iter_mut() {
+            *value *= self.rho;
+        }
+        let mut atax = vec![T::zero(); x.len()];
+        self.a.apply_transpose(&ax, &mut atax);
+        for ((value, &xi), &v) in y.iter_mut().zip(x).zip(&atax) {
+            *value += self.sigma * xi + v;
+        }
+    }
+}
+
+// Type-II Anderson acceleration of the ADMM map u = (z, y) -> T(u). The
+// next point mixes the last `depth` images so as to minimize the fixed-point
+// residual g = T(u) - u. An extrapolated point whose own residual comes out
+// larger than the one it was built from is discarded in favour of the plain
+// image, and the history starts over.
+struct Anderson<T: RealNumber> {
+    depth: usize,
+    previous: Option<(Vec<T>, Vec<T>)>,
+    point_steps: VecDeque<Vec<T>>,
+    residual_steps: VecDeque<Vec<T>>,
+    fallback: Option<(Vec<T>, T)>,
+}
+
+impl<T> Anderson<T>
+where
+    T: RealNumber + FromPrimitive,
+{
+    fn new(depth: usize) -> Self {
+        Self {
+            depth,
+            previous: None,
+            point_steps: VecDeque::with_capacity(depth),
+            residual_steps: VecDeque::with_capacity(depth),
+            fallback: None,
+        }
+    }
+
+    fn reset(&mut self) {
+        self.previous = None;
+        self.point_steps.clear();
+        self.residual_steps.clear();
+        self.fallback = None;
+    }
+
+    // `point` is where the ADMM step started and `image` where it ended.
+    // Returns the next point and whether an accelerated step was rejected.
+    fn next(&mut self, point: &[T], image: Vec<T>) -> (Vec<T>, bool) {
+        let residual: Vec<T> = image.iter().zip(point).map(|(&f, &u)| f - u).collect();
+        let residual_norm = norm2(&residual);
+        if let Some((plain, norm)) = self.fallback.take() {
+            if residual_norm > norm {
+                self.reset();
+                return (plain, true);
+            }
+        }
+        if let Some((previous_point, previous_residual)) = self.previous.take() {
+            if self.point_steps.len() == self.depth {
+                self.point_steps.pop_front();
+                self.residual_steps.pop_front();
+            }
+            self.point_steps.push_back(
+                point
+                    .iter()
+                    .zip(&previous_point)
+                    .map(|(&a, &b)| a - b)
+                    .collect(),
+            );
+            self.residual_steps.push_back(
+                residual
+                    .iter()
+                    .zip(&previous_residual)
+                    .map(|(&a, &b)| a - b)
+                    .collect(),
+            );
+        }
+        self.previous = Some((point.to_vec(), residual.clone()));
+        let Some(weights) = self.weights(&residual) else {
+            return (image, false);
+        };
+        let mut accelerated = image.clone();
+        for ((weight, du), dg) in weights
+            .iter()
+            .zip(&self.point_steps)
+            .zip(&self.residual_steps)
+        {
+            for ((value, &u), &g) in accelerated.iter_mut().zip(du).zip(dg) {
+                *value -= *weight * (u + g);
+            }
+        }
+        self.fallback = Some((image, residual_norm));
+        (accelerated, false)
+    }
+
+    // Least-squares weights minimizing ||g - ΔG γ|| via the regularized
+    // normal equations.
+    fn weights(&self, residual: &[T]) -> Option<Vec<T>> {
+        let k = self.residual_steps.len();
+        if k == 0 {
+            return None;
+        }
+        let mut gram = vec![T::zero(); k * k];
+        for (i, gi) in self.residual_steps.iter().enumerate() {
+            for (j, gj) in self.residual_steps.iter().enumerate() {
+                gram[i * k + j] = dot(gi, gj);
+            }
+        }
+        let trace = (0..k).fold(T::zero(), |acc, i| acc + gram[i * k + i]);
+        let shift = T::from_f64(ANDERSON_REGULARIZATION).unwrap() * trace.max(T::one());
+        for i in 0..k {
+            gram[i * k + i] += shift;
+        }
+        let mut weights: Vec<T> = self
+            .residual_steps
+            .iter()
+            .map(|step| dot(step, residual))
+            .collect();
+        let mut solver = DenseKktSolver::new();
+        solver.factor(&DenseKktMatrix::new(k, gram)).ok()?;
+        solver.solve(&mut weights).ok()?;
+        weights.iter().all(|w| w.is_finite()).then_some(weights)
+    }
+}
+
+// Take the residuals and objectives of a scaled problem back to the units
+// of the original one: a factor per stacked row, per variable and for the
+// objective. Every built-in scaler unscales them entry by entry, so the
+// factors are read off the scaler once per solve.
+#[derive(Debug, Clone)]
+struct ResidualWeights<T> {
+    rows: Vec<T>,
+    columns: Vec<T>,
+    objective: T,
+}
+
+impl<T> ResidualWeights<T>
+where
+    T: RealNumber,
+{
+    fn new<S: Scaler<T>>(scaler: &S, problem: &ProblemQP<T>) -> Self {
+        let n = problem.nvars();
+        let m = problem.equalities.as_ref().map_or(0, |eq| eq.matrix.nrows)
+            + problem
+                .inequalities
+                .as_ref()
+                .map_or(0, |ineq| ineq.matrix.nrows)
+            + problem.bounds.as_ref().map_or(0, |_| n);
+        let mut rows = vec![T::one(); m];
+        let mut columns = vec![T::one(); n];
+        scaler.unscale_residuals(&mut [], &mut rows, &mut columns);
+        Self {
+            rows,
+            columns,
+            objective: scaler.unscale_objective(T::one()),
+        }
+    }
+
+    #[cfg(feature = "mixed-precision")]
+    fn cast<U: RealNumber>(&self) -> ResidualWeights<U> {
+        ResidualWeights {
+            rows: cast_slice(&self.rows),
+            columns: cast_slice(&self.columns),
+            objective: cast(self.objective),
+        }
+    }
+
+    fn rows(&self, values: &mut [T]) {
+        for (value, &weight) in values.iter_mut().zip(&self.rows) {
+            *value *= weight;
+        }
+    }
+
+    fn columns(&self, values: &mut [T]) {
+        for (value, &weight) in values.iter_mut().zip(&self.columns) {
+            *value *= weight;
+        }
     }
 }
 
 pub struct AdmmSolver<T: RealNumber> {
     options: SolveOptions<T>,
     warm_start: Option<WarmStart<T>>,
+    resume: Option<SolverState<T>>,
+    checkpoint: Option<(Duration, CheckpointSink<T>)>,
+    iteration_log: Option<IterationSink<T>>,
+    stopping: Option<StoppingRule<T>>,
+    interrupt: Option<InterruptFlag>,
+    // Primal, dual and rho a mixed-precision solve carries into its f64
+    // stage.
+    handoff: Option<(Vec<T>, Vec<T>, T)>,
+    residual_weights: Option<ResidualWeights<T>>,
 }
 
 impl<T> AdmmSolver<T>
@@ -168,6 +982,13 @@ where
         Self {
             options,
             warm_start: None,
+            resume: None,
+            checkpoint: None,
+            iteration_log: None,
+            stopping: None,
+            interrupt: None,
+            handoff: None,
+            residual_weights: None,
         }
     }
 
@@ -176,16 +997,319 @@ where
         self
     }
 
+    pub fn with_resume(mut self, state: SolverState<T>) -> Self {
+        self.resume = Some(state);
+        self
+    }
+
+    // Hands the current iterate to `sink` at most once per `every`, and once
+    // more if the solve stops on a limit, so it can be resumed later.
+    pub fn with_checkpoint(mut self, every: Duration, sink: CheckpointSink<T>) -> Self {
+        self.checkpoint = Some((every, sink));
+        self
+    }
+
+    pub fn with_iteration_log(mut self, sink: IterationSink<T>) -> Self {
+        self.iteration_log = Some(sink);
+        self
+    }
+
+    // Sees every sampled record, in the scaled space ADMM iterates in, in
+    // place of the residual tolerance test.
+    pub fn with_stopping_criterion(mut self, rule: StoppingRule<T>) -> Self {
+        self.stopping = Some(rule);
+        self
+    }
+
+    pub fn with_interrupt(mut self, flag: InterruptFlag) -> Self {
+        self.interrupt = Some(flag);
+        self
+    }
+
     pub fn solve_qp<S: Scaler<T>>(
-        self,
+        mut self,
         mut problem: ProblemQP<T>,
         scaler: &mut S,
     ) -> Result<AdmmResult<T>> {
-        problem.validate()?;
-        scaler.scale_qp(&mut problem)?;
-        let workspace = AdmmWorkspace::new(&problem)?;
-        let mut lin_sys =
-            LinearSystem::new(workspace.p_base.clone(), workspace.ata.clone(), workspace.n)?;
+        #[cfg(feature = "telemetry")]
+        let setup = tracing::debug_span!("cvxrs.setup");
+        #[cfg(not(feature = "telemetry"))]
+        let setup = tracing::Span::none();
+        setup.in_scope(|| -> Result<()> {
+            problem.validate()?;
+            problem.require_linear_constraints()?;
+            scaler.scale_qp(&mut problem)?;
+            Ok(())
+        })?;
+        self.adopt_scaling(scaler, &problem);
+        let mut solution = in_pool(self.options.num_threads, || {
+            #[cfg(feature = "mixed-precision")]
+            if self.options.mixed_precision {
+                return self.iterate_mixed(&problem, &setup);
+            }
+            self.iterate(&problem, None, &setup)
+        })?;
+
+        #[cfg(feature = "telemetry")]
+        let _unscale = tracing::debug_span!("cvxrs.unscale").entered();
+        unscale(scaler, &mut solution);
+        Ok(solution)
+    }
+
+    // Solves QPs that differ only in q, the right-hand sides and the bound
+    // values. The scaling, the assembled KKT matrix and its factorization
+    // come from the first problem and serve the whole batch; the
+    // factorization is only redone when adaptive rho left it at a different
+    // value than the next solve starts from.
+    pub fn solve_qp_batch<S: Scaler<T>>(
+        mut self,
+        problems: Vec<ProblemQP<T>>,
+        scaler: &mut S,
+    ) -> Result<Vec<AdmmResult<T>>> {
+        if self.resume.is_some() {
+            bail!("a saved solver state can only resume a single solve");
+        }
+        let mut problems = problems.into_iter();
+        let Some(mut first) = problems.next() else {
+            return Ok(Vec::new());
+        };
+        #[cfg(feature = "telemetry")]
+        let setup = tracing::debug_span!("cvxrs.setup");
+        #[cfg(not(feature = "telemetry"))]
+        let setup = tracing::Span::none();
+        let reference = first.clone();
+        let (mut workspace, mut lin_sys) = setup.in_scope(|| -> Result<_> {
+            first.validate()?;
+            first.require_linear_constraints()?;
+            self.check_options()?;
+            scaler.scale_qp(&mut first)?;
+            let workspace = AdmmWorkspace::new(&first, None)?;
+            let lin_sys = LinearSystem::new(&workspace, &self.options)?;
+            Ok((workspace, lin_sys))
+        })?;
+        self.adopt_scaling(scaler, &first);
+
+        let mut solutions = Vec::with_capacity(1 + problems.len());
+        solutions.push(self.solve_member(&first, &workspace, &mut lin_sys, scaler)?);
+        for mut problem in problems {
+            problem.validate()?;
+            problem.require_linear_constraints()?;
+            if !same_matrices(&reference, &problem) {
+                bail!(
+                    "problem {} of the batch does not share P and the constraint matrices \
+                     with the first",
+                    solutions.len()
+                );
+            }
+            scaler.rescale_qp(&mut problem)?;
+            workspace.set_rhs(&problem);
+            solutions.push(self.solve_member(&problem, &workspace, &mut lin_sys, scaler)?);
+        }
+        Ok(solutions)
+    }
+
+    fn solve_member<S: Scaler<T>>(
+        &self,
+        problem: &ProblemQP<T>,
+        workspace: &AdmmWorkspace<T>,
+        lin_sys: &mut LinearSystem<T>,
+        scaler: &S,
+    ) -> Result<AdmmResult<T>> {
+        let mut solution = in_pool(self.options.num_threads, || {
+            self.run(problem, workspace, lin_sys)
+        })?;
+        unscale(scaler, &mut solution);
+        Ok(solution)
+    }
+
+    // Scales and factors `problem` once for repeated solves as its vectors
+    // or matrix values change; see AdmmSetup.
+    pub fn setup<S: Scaler<T>>(
+        mut self,
+        problem: ProblemQP<T>,
+        mut scaler: S,
+    ) -> Result<AdmmSetup<T, S>> {
+        if self.resume.is_some() {
+            bail!("a saved solver state can only resume a single solve");
+        }
+        let mut scaled = problem.clone();
+        scaled.validate()?;
+        scaled.require_linear_constraints()?;
+        self.check_options()?;
+        scaler.scale_qp(&mut scaled)?;
+        self.adopt_scaling(&scaler, &scaled);
+        let workspace = AdmmWorkspace::new(&scaled, None)?;
+        let lin_sys = LinearSystem::new(&workspace, &self.options)?;
+        Ok(AdmmSetup {
+            solver: self,
+            problem,
+            scaler,
+            workspace,
+            lin_sys,
+            previous: None,
+            matrices_changed: false,
+        })
+    }
+
+    // A warm start comes in the units of the original problem and the
+    // iterations run on the scaled one, `problem`; the residuals they
+    // record are taken back to the original units.
+    fn adopt_scaling<S: Scaler<T>>(&mut self, scaler: &S, problem: &ProblemQP<T>) {
+        if let Some(warm) = &mut self.warm_start {
+            scaler.scale_primal(&mut warm.primal);
+        }
+        self.residual_weights = Some(ResidualWeights::new(scaler, problem));
+    }
+
+    fn iterate(
+        &self,
+        problem: &ProblemQP<T>,
+        cone_rows: Option<&ConeRows<T>>,
+        setup: &tracing::Span,
+    ) -> Result<AdmmResult<T>> {
+        self.check_options()?;
+        let (workspace, mut lin_sys) = setup.in_scope(|| -> Result<_> {
+            let workspace = AdmmWorkspace::new(problem, cone_rows)?;
+            let lin_sys = LinearSystem::new(&workspace, &self.options)?;
+            Ok((workspace, lin_sys))
+        })?;
+        self.run(problem, &workspace, &mut lin_sys)
+    }
+
+    // Iterates in f32 up to a loose tolerance, then finishes in f64 from the
+    // f32 iterate and rho. The residuals, the objective and polishing come
+    // from the f64 stage.
+    #[cfg(feature = "mixed-precision")]
+    fn iterate_mixed(
+        &self,
+        problem: &ProblemQP<T>,
+        setup: &tracing::Span,
+    ) -> Result<AdmmResult<T>> {
+        if self.resume.is_some() || self.checkpoint.is_some() || self.stopping.is_some() {
+            bail!("mixed precision does not support resuming, checkpoints or stopping rules");
+        }
+        let loose = MIXED_PRECISION_TOLERANCE as f32;
+        let mut options: SolveOptions<f32> = self.options.cast();
+        options.mixed_precision = false;
+        options.polish = false;
+        options.eps_abs = options.eps_abs.max(loose);
+        options.eps_rel = options.eps_rel.max(loose);
+        let mut low = AdmmSolver::new(options);
+        low.warm_start = self.warm_start.as_ref().map(|warm| WarmStart {
+            primal: cast_slice(&warm.primal),
+            equality_dual: cast_slice(&warm.equality_dual),
+            inequality_dual: cast_slice(&warm.inequality_dual),
+        });
+        low.interrupt = self.interrupt.clone();
+        low.residual_weights = self.residual_weights.as_ref().map(ResidualWeights::cast);
+        low.iteration_log = self.iteration_log.clone().map(|sink| {
+            Arc::new(move |record: &IterationRecord<f32>| sink(&record.cast()))
+                as IterationSink<f32>
+        });
+        let first = low.iterate(&problem.cast(), None, setup)?;
+        if matches!(
+            first.status,
+            Status::MaxTime
+                | Status::Interrupted
+                | Status::PrimalInfeasible
+                | Status::DualInfeasible
+        ) {
+            return Ok(cast_solution(first, 0));
+        }
+
+        let mut options: SolveOptions<f64> = self.options.cast();
+        options.mixed_precision = false;
+        options.max_iterations = options.max_iterations.saturating_sub(first.iterations);
+        options.max_time = options
+            .max_time
+            .map(|limit| limit.saturating_sub(first.stats.solve_time));
+        let mut high = AdmmSolver::new(options);
+        let rho = first
+            .stats
+            .rho_updates
+            .last()
+            .map_or(self.options.admm_rho.to_f64().unwrap(), |update| {
+                f64::from(update.rho)
+            });
+        high.handoff = Some((
+            cast_slice(&first.primal),
+            cast_slice(&first.inequality_dual),
+            rho,
+        ));
+        high.interrupt = self.interrupt.clone();
+        high.residual_weights = self.residual_weights.as_ref().map(ResidualWeights::cast);
+        let offset = first.iterations;
+        high.iteration_log = self.iteration_log.clone().map(|sink| {
+            Arc::new(move |record: &IterationRecord<f64>| {
+                let mut record = record.cast();
+                record.iteration += offset;
+                sink(&record)
+            }) as IterationSink<f64>
+        });
+        let second = high.iterate(&problem.cast(), None, setup)?;
+
+        let mut solution = cast_solution(second, offset);
+        let stats = cast_solution::<f32, T>(first, 0).stats;
+        solution.iterations += offset;
+        solution.stats.solve_time += stats.solve_time;
+        solution.stats.factorizations += stats.factorizations;
+        solution.stats.linear_solves += stats.linear_solves;
+        solution.stats.symbolic_time += stats.symbolic_time;
+        solution.stats.numeric_time += stats.numeric_time;
+        if let Some(mut krylov) = stats.krylov {
+            if let Some(high) = &solution.stats.krylov {
+                krylov.merge(high);
+            }
+            solution.stats.krylov = Some(krylov);
+        }
+        if let Some(mut regularization) = stats.regularization {
+            if let Some(high) = &solution.stats.regularization {
+                regularization.merge(high);
+            }
+            solution.stats.regularization = Some(regularization);
+        }
+        solution.stats.condition_estimate = solution
+            .stats
+            .condition_estimate
+            .or(stats.condition_estimate);
+        solution.stats.anderson_rejections += stats.anderson_rejections;
+        solution.stats.history.splice(0..0, stats.history);
+        solution.stats.rho_updates.splice(0..0, stats.rho_updates);
+        Ok(solution)
+    }
+
+    fn check_options(&self) -> Result<()> {
+        let alpha = self.options.admm_relaxation;
+        if !(alpha > T::zero() && alpha < T::from_f64(2.0).unwrap()) {
+            bail!(
+                "the ADMM relaxation parameter must lie in (0, 2), got {}",
+                alpha.to_f64().unwrap_or(f64::NAN)
+            );
+        }
+        if self.options.rho_update_interval == 0 {
+            bail!("the rho update interval must be positive");
+        }
+        if self.options.check_every == 0 {
+            bail!("the convergence check interval must be positive");
+        }
+        if !(self.options.rho_min > T::zero() && self.options.rho_min <= self.options.rho_max) {
+            bail!("rho_min must be positive and no larger than rho_max");
+        }
+        if !(self.options.sigma.is_finite() && self.options.sigma >= T::zero()) {
+            bail!("sigma must be finite and nonnegative");
+        }
+        Ok(())
+    }
+
+    // The iterations proper, on a workspace and factorization that may
+    // outlive the solve.
+    fn run(
+        &self,
+        problem: &ProblemQP<T>,
+        workspace: &AdmmWorkspace<T>,
+        lin_sys: &mut LinearSystem<T>,
+    ) -> Result<AdmmResult<T>> {
+        let alpha = self.options.admm_relaxation;
         let mut stats = SolveStats::new();
         let timer = Timer::start();
 
@@ -201,70 +1325,149 @@ where
         let mut ax = vec![T::zero(); workspace.m];
         workspace.multiply_a(&x, &mut ax);
         let mut z = ax.clone();
-        project_box(&mut z, &workspace.lower, &workspace.upper);
+        workspace.project(&mut z);
         let mut y = vec![T::zero(); workspace.m];
-        let mut tmp_dual = vec![T::zero(); workspace.m];
-        let mut rhs = vec![T::zero(); workspace.n];
-        let mut dual_residual_vec = vec![T::zero(); workspace.n];
 
-        let tol = self.options.tolerance;
         let mut rho = self.options.admm_rho;
         let mut status = Status::MaxIterations;
-        let mut last_objective = compute_objective(&problem, &workspace.p_base, &x);
-        let mut dual_objective = T::zero();
-
-        for iter in 0..self.options.max_iterations {
-            lin_sys.factor(rho)?;
-            stats.factorizations += 1;
-
+        let mut certificate = None;
+        let mut start = 0;
+        if let Some(state) = &self.resume {
+            if state.primal.len() != workspace.n
+                || state.slack.len() != workspace.m
+                || state.dual.len() != workspace.m
+            {
+                bail!("saved solver state does not match the problem dimensions");
+            }
+            x.copy_from_slice(&state.primal);
+            z.copy_from_slice(&state.slack);
+            y.copy_from_slice(&state.dual);
+            rho = state.rho;
+            start = state.iteration;
+        }
+        if let Some((primal, dual, handoff_rho)) = &self.handoff {
+            x.copy_from_slice(primal);
+            y.copy_from_slice(dual);
+            rho = *handoff_rho;
+            workspace.multiply_a(&x, &mut ax);
             for i in 0..workspace.m {
-                tmp_dual[i] = z[i] - y[i] / rho;
+                z[i] = ax[i] + y[i] / rho;
             }
-            workspace.multiply_at(&tmp_dual, &mut rhs);
-            for i in 0..workspace.n {
-                rhs[i] = rho * rhs[i] - problem.linear[i];
+            workspace.project(&mut z);
+        }
+        let mut anderson =
+            (self.options.anderson_memory > 0).then(|| Anderson::new(self.options.anderson_memory));
+        let mut last_checkpoint = timer.elapsed();
+        let mut completed = start;
+        let mut performed = start;
+
+        #[cfg(feature = "telemetry")]
+        let iterate = tracing::debug_span!("cvxrs.iterate").entered();
+        for iter in start..self.options.max_iterations {
+            if lin_sys.factor(rho)? {
+                stats.factorizations += 1;
             }
-            lin_sys.solve(&mut rhs)?;
-            x.copy_from_slice(&rhs);
+
+            let x_old = x.clone();
+            lin_sys.solve(workspace, &problem.linear, &mut x, &z, &y, rho)?;
             stats.linear_solves += 1;
 
             workspace.multiply_a(&x, &mut ax);
             let z_old = z.clone();
+            let y_old = y.clone();
+            // Over-relaxation: both updates see alpha Ax + (1 - alpha) z_old.
+            let relaxed: Vec<T> = ax
+                .iter()
+                .zip(&z_old)
+                .map(|(&a, &zo)| alpha * a + (T::one() - alpha) * zo)
+                .collect();
             for i in 0..workspace.m {
-                z[i] = ax[i] + y[i] / rho;
+                z[i] = relaxed[i] + y[i] / rho;
             }
-            project_box(&mut z, &workspace.lower, &workspace.upper);
+            workspace.project(&mut z);
             for i in 0..workspace.m {
-                y[i] += rho * (ax[i] - z[i]);
+                y[i] += rho * (relaxed[i] - z[i]);
             }
+            // Equality rows keep z = b; their multipliers come from the KKT solve.
+            let multipliers = lin_sys.multipliers();
+            y[..multipliers.len()].copy_from_slice(multipliers);
+            performed = iter + 1;
 
-            let primal_residual: Vec<T> = ax.iter().zip(z.iter()).map(|(a, b)| *a - *b).collect();
-            for i in 0..workspace.m {
-                tmp_dual[i] = z_old[i] - z[i];
-                tmp_dual[i] *= rho;
-            }
-            workspace.multiply_at(&tmp_dual, &mut dual_residual_vec);
-
-            let objective = compute_objective(&problem, &workspace.p_base, &x);
-            dual_objective = objective - dot(&y, &primal_residual);
-            let (pr_norm, du_norm) = residuals_inf(&primal_residual, &dual_residual_vec);
-            let gap = relative_gap(objective, dual_objective);
-            stats.push(IterationRecord::new(
-                iter,
-                pr_norm,
-                du_norm,
-                gap,
-                rho,
-                self.options.admm_relaxation,
-                objective,
-                dual_objective,
-                timer.elapsed(),
-            ));
-            last_objective = objective;
-
-            if pr_norm <= tol && du_norm <= tol && gap <= tol {
-                status = Status::Optimal;
-                break;
+            // The dual residual and the objective each cost a dense product,
+            // so convergence is only checked every `check_every` iterations
+            // and on the last one; the history holds just those samples.
+            if performed % self.options.check_every == 0 || performed == self.options.max_iterations
+            {
+                let mut primal_residual: Vec<T> =
+                    ax.iter().zip(z.iter()).map(|(a, b)| *a - *b).collect();
+                let mut objective = compute_objective(problem, &workspace.p_base, &x);
+                let mut dual_objective = objective - dot(&y, &primal_residual);
+                let (mut px, mut aty) = dual_terms(workspace, &x, &y);
+                let (mut ax, mut z, mut q) = (ax.clone(), z.clone(), problem.linear.clone());
+                if let Some(weights) = &self.residual_weights {
+                    for values in [&mut primal_residual, &mut ax, &mut z] {
+                        weights.rows(values);
+                    }
+                    for values in [&mut px, &mut aty, &mut q] {
+                        weights.columns(values);
+                    }
+                    objective *= weights.objective;
+                    dual_objective *= weights.objective;
+                }
+                // The stationarity residual Px + q + Aᵀy itself, as in OSQP;
+                // ρAᵀ(z_old - z) only measures how far the iterate moved and
+                // vanishes on an unbounded problem held by the σ term.
+                let dual_residual_vec: Vec<T> = px
+                    .iter()
+                    .zip(&aty)
+                    .zip(&q)
+                    .map(|((&p, &a), &c)| p + a + c)
+                    .collect();
+                let (pr_norm, du_norm) = residuals_inf(&primal_residual, &dual_residual_vec);
+                let gap = relative_gap(objective, dual_objective);
+                let mut record = IterationRecord::new(
+                    iter,
+                    pr_norm,
+                    du_norm,
+                    gap,
+                    rho,
+                    alpha,
+                    objective,
+                    dual_objective,
+                    timer.elapsed(),
+                );
+                (record.primal_scale, record.dual_scale) = residual_scales(&q, &ax, &z, &px, &aty);
+                if let Some(sink) = &self.iteration_log {
+                    sink(&record);
+                }
+                let converged = match &self.stopping {
+                    Some(rule) => rule.is_converged(&record, &self.options),
+                    None => ResidualTolerance.is_converged(&record, &self.options),
+                };
+                stats.push(record);
+                if converged {
+                    status = Status::Optimal;
+                    break;
+                }
+                // Cone rows have recession cones of their own, which the
+                // tests below do not know about.
+                if workspace.cones.is_empty() {
+                    let delta_x: Vec<T> = x.iter().zip(&x_old).map(|(&a, &b)| a - b).collect();
+                    let delta_y: Vec<T> = y.iter().zip(&y_old).map(|(&a, &b)| a - b).collect();
+                    let weights = self.residual_weights.as_ref();
+                    let options = &self.options;
+                    if let Some(found) = primal_infeasibility(workspace, weights, delta_y, options)
+                    {
+                        (status, certificate) = (Status::PrimalInfeasible, Some(found));
+                        break;
+                    }
+                    let q = &problem.linear;
+                    if let Some(found) = dual_infeasibility(workspace, q, weights, delta_x, options)
+                    {
+                        (status, certificate) = (Status::DualInfeasible, Some(found));
+                        break;
+                    }
+                }
             }
 
             if let Some(limit) = self.options.max_time {
@@ -273,31 +1476,305 @@ where
                     break;
                 }
             }
+            if interrupted(self.interrupt.as_ref()) {
+                status = Status::Interrupted;
+                break;
+            }
+
+            if self.options.admm_adaptive_rho && (iter + 1) % self.options.rho_update_interval == 0
+            {
+                let proposed = balanced_rho(workspace, &problem.linear, &x, &ax, &z, &y, rho)
+                    .max(self.options.rho_min)
+                    .min(self.options.rho_max);
+                let tolerance = T::from_f64(RHO_UPDATE_TOLERANCE).unwrap();
+                if proposed > rho * tolerance || proposed * tolerance < rho {
+                    stats.rho_updates.push(RhoUpdate {
+                        iteration: iter,
+                        previous: rho,
+                        rho: proposed,
+                    });
+                    rho = proposed;
+                    // The fixed-point map changes with rho.
+                    if let Some(anderson) = &mut anderson {
+                        anderson.reset();
+                    }
+                }
+            }
+
+            if let Some(anderson) = &mut anderson {
+                let point: Vec<T> = z_old.iter().chain(&y_old).copied().collect();
+                let image: Vec<T> = z.iter().chain(&y).copied().collect();
+                let (next, rejected) = anderson.next(&point, image);
+                if rejected {
+                    stats.anderson_rejections += 1;
+                }
+                let (next_z, next_y) = next.split_at(workspace.m);
+                z.copy_from_slice(next_z);
+                y.copy_from_slice(next_y);
+            }
 
-            if self.options.admm_adaptive_rho {
-                let ten = T::from_f64(10.0).unwrap();
-                let two = T::from_f64(2.0).unwrap();
-                if pr_norm > ten * du_norm {
-                    rho *= two;
-                } else if du_norm > ten * pr_norm {
-                    rho = rho / two;
+            completed = iter + 1;
+            if let Some((every, sink)) = &self.checkpoint {
+                if timer.elapsed() >= last_checkpoint + *every {
+                    last_checkpoint = timer.elapsed();
+                    save_checkpoint(sink, completed, rho, &x, &z, &y);
                 }
             }
         }
+        if let Some((_, sink)) = &self.checkpoint {
+            if matches!(
+                status,
+                Status::MaxIterations | Status::MaxTime | Status::Interrupted
+            ) {
+                save_checkpoint(sink, completed, rho, &x, &z, &y);
+            }
+        }
+
+        #[cfg(feature = "telemetry")]
+        drop(iterate);
+
+        if status == Status::Optimal && self.options.polish && workspace.cones.is_empty() {
+            let polished = polish(workspace, &problem.linear, &x, &z, &y);
+            stats.factorizations += 1;
+            stats.linear_solves += 1 + POLISH_REFINEMENT_STEPS;
+            stats.polished = Some(polished.is_some());
+            if let Some((px, py)) = polished {
+                x = px;
+                y = py;
+                workspace.multiply_a(&x, &mut z);
+                workspace.project(&mut z);
+            }
+        }
 
+        let objective_value = match status {
+            Status::PrimalInfeasible => T::infinity(),
+            Status::DualInfeasible => T::neg_infinity(),
+            _ => compute_objective(problem, &workspace.p_base, &x),
+        };
+        stats.symbolic_time = lin_sys.symbolic_time();
+        stats.numeric_time = lin_sys.numeric_time();
+        stats.krylov = lin_sys.krylov_stats();
+        stats.regularization = lin_sys.regularization_stats();
+        stats.condition_estimate = lin_sys.condition_estimate();
         stats.solve_time = timer.elapsed();
-        let mut solution = Solution {
+        Ok(Solution {
             primal: x,
             equality_dual: Vec::new(),
             inequality_dual: y,
             status,
-            objective_value: last_objective,
-            iterations: stats.history.len(),
+            objective_value,
+            iterations: performed,
             stats,
+            certificate,
+            basis: None,
+        })
+    }
+
+    // ADMM on a QP given by operators. The rows are A stacked over the
+    // identity when there are bounds, and the x-update solves
+    // (P + σI + AᵀRA)x = σx⁻ - q + Aᵀ(Rz - y) by conjugate gradients with R
+    // the per-row rho, so only products with P, A and Aᵀ are ever taken.
+    // Equality rows get a larger rho instead of an exact solve, and with no
+    // matrix to hand there is no scaling, polishing or Anderson step.
+    pub fn solve_qp_operator(self, problem: &ProblemQpOp<T>) -> Result<AdmmResult<T>> {
+        problem.validate()?;
+        self.check_options()?;
+        if self.options.sigma <= T::zero() {
+            bail!("the matrix-free x-update needs a positive sigma");
+        }
+        let (n, m, meq) = (problem.nvars(), problem.nconstraints(), problem.equalities);
+        let rows = Arc::new(StackedOperator {
+            constraints: problem.constraints.clone(),
+            n,
+            m,
+            bounded: problem.bounds.is_some(),
+        });
+        let total = rows.dim().0;
+        let mut lower = vec![T::neg_infinity(); total];
+        let mut upper = vec![T::infinity(); total];
+        lower[..meq].copy_from_slice(&problem.rhs[..meq]);
+        upper[..m].copy_from_slice(&problem.rhs);
+        if let Some(bounds) = &problem.bounds {
+            lower[m..].copy_from_slice(&bounds.lower);
+            upper[m..].copy_from_slice(&bounds.upper);
+        }
+        let project = |z: &mut [T]| {
+            for ((v, &l), &u) in z.iter_mut().zip(&lower).zip(&upper) {
+                *v = v.max(l).min(u);
+            }
         };
-        scaler.unscale_primal(&mut solution.primal);
-        scaler.unscale_stats(&mut solution.stats);
-        Ok(solution)
+        let equality_scale = T::from_f64(OPERATOR_EQUALITY_RHO_SCALE).unwrap();
+        let row_rho = |rho: T| -> Vec<T> {
+            (0..total)
+                .map(|row| if row < meq { rho * equality_scale } else { rho })
+                .collect()
+        };
+        // Jacobi diagonal of the x-update; it ignores the equality scaling
+        // of R, which the column norms do not split by row.
+        let diagonal = |rho: T| -> Vec<T> {
+            (0..n)
+                .map(|j| {
+                    let p = problem
+                        .quadratic_diagonal
+                        .as_ref()
+                        .map_or(T::zero(), |d| d[j]);
+                    let a = problem.column_norms.as_ref().map_or(T::zero(), |d| d[j]);
+                    let identity = if problem.bounds.is_some() {
+                        T::one()
+                    } else {
+                        T::zero()
+                    };
+                    p + self.options.sigma + rho * (a + identity)
+                })
+                .collect()
+        };
+
+        let alpha = self.options.admm_relaxation;
+        let q = &problem.linear;
+        let mut stats = SolveStats::new();
+        let timer = Timer::start();
+        let mut solver = CgKktSolver::new();
+        solver.analyze_pattern(&CgPattern::new(n))?;
+
+        let mut x = match &self.warm_start {
+            Some(warm) if warm.primal.len() == n => warm.primal.clone(),
+            _ => vec![T::zero(); n],
+        };
+        let mut kx = vec![T::zero(); total];
+        rows.apply(&x, &mut kx);
+        let mut z = kx.clone();
+        project(&mut z);
+        let mut y = vec![T::zero(); total];
+        let mut weighted = vec![T::zero(); total];
+        let mut rhs = vec![T::zero(); n];
+        let mut px = vec![T::zero(); n];
+        let mut kty = vec![T::zero(); n];
+
+        let mut rho = self.options.admm_rho;
+        let mut factored = None;
+        let mut status = Status::MaxIterations;
+        let mut performed = 0;
+        for iter in 0..self.options.max_iterations {
+            if factored != Some(rho) {
+                let operator = NormalOperator {
+                    quadratic: problem.quadratic.clone(),
+                    rows: rows.clone(),
+                    sigma: self.options.sigma,
+                    weights: row_rho(rho),
+                };
+                solver.factor(&CgSystem::new(Arc::new(operator), diagonal(rho)))?;
+                stats.factorizations += 1;
+                factored = Some(rho);
+            }
+            let weights = row_rho(rho);
+            for (((value, &w), &z), &y) in weighted.iter_mut().zip(&weights).zip(&z).zip(&y) {
+                *value = w * z - y;
+            }
+            rows.apply_transpose(&weighted, &mut rhs);
+            for ((value, &q), &xk) in rhs.iter_mut().zip(q).zip(&x) {
+                *value += self.options.sigma * xk - q;
+            }
+            solver.solve(&mut rhs)?;
+            stats.linear_solves += 1;
+            x.copy_from_slice(&rhs);
+
+            rows.apply(&x, &mut kx);
+            for i in 0..total {
+                let relaxed = alpha * kx[i] + (T::one() - alpha) * z[i];
+                let next = (relaxed + y[i] / weights[i]).max(lower[i]).min(upper[i]);
+                y[i] += weights[i] * (relaxed - next);
+                z[i] = next;
+            }
+            performed = iter + 1;
+
+            let sampled = performed % self.options.check_every == 0
+                || performed == self.options.max_iterations;
+            let rebalance =
+                self.options.admm_adaptive_rho && performed % self.options.rho_update_interval == 0;
+            if sampled || rebalance {
+                problem.quadratic.apply(&x, &mut px);
+                rows.apply_transpose(&y, &mut kty);
+            }
+            if sampled {
+                let primal_residual: Vec<T> = kx.iter().zip(&z).map(|(&a, &b)| a - b).collect();
+                let dual_residual: Vec<T> = px
+                    .iter()
+                    .zip(&kty)
+                    .zip(q)
+                    .map(|((&p, &k), &c)| p + k + c)
+                    .collect();
+                let objective =
+                    T::from_f64(0.5).unwrap() * dot(&x, &px) + dot(q, &x) + problem.offset;
+                let dual_objective = objective - dot(&y, &primal_residual);
+                let (pr_norm, du_norm) = residuals_inf(&primal_residual, &dual_residual);
+                let mut record = IterationRecord::new(
+                    iter,
+                    pr_norm,
+                    du_norm,
+                    relative_gap(objective, dual_objective),
+                    rho,
+                    alpha,
+                    objective,
+                    dual_objective,
+                    timer.elapsed(),
+                );
+                (record.primal_scale, record.dual_scale) = residual_scales(q, &kx, &z, &px, &kty);
+                if let Some(sink) = &self.iteration_log {
+                    sink(&record);
+                }
+                let converged = match &self.stopping {
+                    Some(rule) => rule.is_converged(&record, &self.options),
+                    None => ResidualTolerance.is_converged(&record, &self.options),
+                };
+                stats.push(record);
+                if converged {
+                    status = Status::Optimal;
+                    break;
+                }
+            }
+            if let Some(limit) = self.options.max_time {
+                if timer.elapsed() > limit {
+                    status = Status::MaxTime;
+                    break;
+                }
+            }
+            if interrupted(self.interrupt.as_ref()) {
+                status = Status::Interrupted;
+                break;
+            }
+            if rebalance {
+                let proposed = rho_balance(q, &kx, &z, &px, &kty, rho)
+                    .max(self.options.rho_min)
+                    .min(self.options.rho_max);
+                let tolerance = T::from_f64(RHO_UPDATE_TOLERANCE).unwrap();
+                if proposed > rho * tolerance || proposed * tolerance < rho {
+                    stats.rho_updates.push(RhoUpdate {
+                        iteration: iter,
+                        previous: rho,
+                        rho: proposed,
+                    });
+                    rho = proposed;
+                }
+            }
+        }
+
+        problem.quadratic.apply(&x, &mut px);
+        let objective_value =
+            T::from_f64(0.5).unwrap() * dot(&x, &px) + dot(q, &x) + problem.offset;
+        stats.numeric_time = solver.numeric_time();
+        stats.krylov = solver.krylov_stats();
+        stats.solve_time = timer.elapsed();
+        Ok(Solution {
+            primal: x,
+            equality_dual: y[..meq].to_vec(),
+            inequality_dual: y[meq..].to_vec(),
+            status,
+            objective_value,
+            iterations: performed,
+            stats,
+            certificate: None,
+            basis: None,
+        })
     }
 
     pub fn solve_lp<S: Scaler<T>>(
@@ -312,10 +1789,661 @@ where
             inequalities: problem.inequalities.clone(),
             equalities: problem.equalities.clone(),
             bounds: problem.bounds.clone(),
+            groups: problem.groups.clone(),
+            integrality: Vec::new(),
+            names: problem.names.clone(),
+            sense: problem.sense,
+            offset: problem.offset,
+            quadratic_constraints: Vec::new(),
         };
         qp.quadratic = identity_csc(n, T::zero());
-        self.solve_qp(qp, scaler)
+        let crossover = self.options.crossover;
+        let options = self.options.clone();
+        let mut solution = self.solve_qp(qp, scaler)?;
+        if crossover && solution.status == Status::Optimal {
+            let vertex = SimplexSolver::new().crossover(&problem, &solution, &options)?;
+            solution.stats.crossover_iterations = Some(vertex.iterations);
+            solution.stats.factorizations += vertex.stats.factorizations;
+            solution.stats.linear_solves += vertex.stats.linear_solves;
+            if vertex.status == Status::Optimal {
+                solution.primal = vertex.primal;
+                solution.equality_dual = vertex.equality_dual;
+                solution.inequality_dual = vertex.inequality_dual;
+                solution.objective_value = vertex.objective_value;
+                solution.basis = vertex.basis;
+            }
+        }
+        Ok(solution)
+    }
+
+    // Zero-cone rows become equalities and nonnegative rows inequalities;
+    // second-order, PSD and exponential rows are kept as cone rows. Row scaling does not
+    // preserve those cones, so the problem is solved unscaled. The stacked
+    // duals come back in the original row order.
+    pub fn solve_conic(self, problem: ProblemConic<T>) -> Result<AdmmResult<T>> {
+        #[cfg(feature = "telemetry")]
+        let setup = tracing::debug_span!("cvxrs.setup");
+        #[cfg(not(feature = "telemetry"))]
+        let setup = tracing::Span::none();
+        setup.in_scope(|| problem.validate())?;
+        let n = problem.nvars();
+        let (mut zero, mut nonnegative, mut conic) = (Vec::new(), Vec::new(), Vec::new());
+        let mut cones = Vec::new();
+        let mut start = 0;
+        for cone in &problem.cones {
+            let rows = start..start + cone.dim();
+            match cone {
+                Cone::Zero(_) => zero.extend(rows),
+                Cone::NonNegative(_) => nonnegative.extend(rows),
+                Cone::SecondOrder(_) | Cone::PositiveSemidefinite(_) | Cone::Exponential => {
+                    conic.extend(rows);
+                    cones.push(cone.clone());
+                }
+            }
+            start += cone.dim();
+        }
+        let rhs = |rows: &[usize]| rows.iter().map(|&row| problem.rhs[row]).collect();
+        let qp = ProblemQP {
+            quadratic: problem
+                .quadratic
+                .clone()
+                .unwrap_or_else(|| identity_csc(n, T::zero())),
+            linear: problem.cost.clone(),
+            equalities: (!zero.is_empty()).then(|| EqualityConstraints {
+                matrix: select_rows(&problem.matrix, &zero),
+                rhs: rhs(&zero),
+            }),
+            inequalities: (!nonnegative.is_empty()).then(|| InequalityConstraints {
+                matrix: select_rows(&problem.matrix, &nonnegative),
+                rhs: rhs(&nonnegative),
+            }),
+            bounds: None,
+            groups: Vec::new(),
+            integrality: Vec::new(),
+            names: Names::default(),
+            sense: ObjectiveSense::Minimize,
+            offset: T::zero(),
+            quadratic_constraints: Vec::new(),
+        };
+        let cone_rows = (!conic.is_empty()).then(|| ConeRows {
+            matrix: select_rows(&problem.matrix, &conic),
+            rhs: rhs(&conic),
+            cones,
+        });
+        let mut solution = in_pool(self.options.num_threads, || {
+            self.iterate(&qp, cone_rows.as_ref(), &setup)
+        })?;
+
+        let order = zero.into_iter().chain(nonnegative).chain(conic);
+        let mut dual = vec![T::zero(); problem.rhs.len()];
+        for (row, &value) in order.zip(&solution.inequality_dual) {
+            dual[row] = value;
+        }
+        solution.inequality_dual = dual;
+        Ok(solution)
+    }
+}
+
+// A QP scaled and factored once and then solved again and again as q, the
+// right-hand sides, the bounds or the values of its matrices change, as
+// with OSQP's setup, update and solve. The scaling is the one computed at
+// setup, the KKT factorization is only redone when rho or the matrix
+// values moved, and each solve starts from the previous optimum.
+pub struct AdmmSetup<T: RealNumber, S: Scaler<T>> {
+    solver: AdmmSolver<T>,
+    // As given, before scaling; the updates apply to it.
+    problem: ProblemQP<T>,
+    scaler: S,
+    workspace: AdmmWorkspace<T>,
+    lin_sys: LinearSystem<T>,
+    // Scaled primal, dual and rho of the last optimal solve.
+    previous: Option<(Vec<T>, Vec<T>, T)>,
+    matrices_changed: bool,
+}
+
+impl<T, S> AdmmSetup<T, S>
+where
+    T: RealNumber + FromPrimitive,
+    S: Scaler<T>,
+{
+    pub fn problem(&self) -> &ProblemQP<T> {
+        &self.problem
+    }
+
+    pub fn update_q(&mut self, q: Vec<T>) -> Result<()> {
+        if q.len() != self.problem.nvars() {
+            bail!(
+                "q has {} entries but the problem has {} variables",
+                q.len(),
+                self.problem.nvars()
+            );
+        }
+        self.problem.linear = q;
+        Ok(())
+    }
+
+    // New right-hand sides of the equality and inequality rows; a block the
+    // problem does not have takes an empty vector.
+    pub fn update_rhs(&mut self, equality: Vec<T>, inequality: Vec<T>) -> Result<()> {
+        let rows = |rhs: Option<&Vec<T>>| rhs.map_or(0, Vec::len);
+        let meq = rows(self.problem.equalities.as_ref().map(|eq| &eq.rhs));
+        let mineq = rows(self.problem.inequalities.as_ref().map(|ineq| &ineq.rhs));
+        if equality.len() != meq || inequality.len() != mineq {
+            bail!(
+                "got {} equality and {} inequality right-hand sides for {} and {} rows",
+                equality.len(),
+                inequality.len(),
+                meq,
+                mineq
+            );
+        }
+        if let Some(eq) = self.problem.equalities.as_mut() {
+            eq.rhs = equality;
+        }
+        if let Some(ineq) = self.problem.inequalities.as_mut() {
+            ineq.rhs = inequality;
+        }
+        Ok(())
+    }
+
+    // Bounds only take the place of bounds given at setup, whose rows are
+    // part of the factorization.
+    pub fn update_bounds(&mut self, bounds: Bounds<T>) -> Result<()> {
+        if self.problem.bounds.is_none() {
+            bail!("the problem was set up without bounds");
+        }
+        let n = self.problem.nvars();
+        if bounds.lower.len() != n || bounds.upper.len() != n {
+            bail!(
+                "bounds have {} lower and {} upper entries for {} variables",
+                bounds.lower.len(),
+                bounds.upper.len(),
+                n
+            );
+        }
+        self.problem.bounds = Some(bounds);
+        Ok(())
+    }
+
+    // New values for the stored entries of P and of the constraint
+    // matrices, in their storage order; None leaves a matrix as it is.
+    pub fn update_matrix_values(
+        &mut self,
+        quadratic: Option<&[T]>,
+        equality: Option<&[T]>,
+        inequality: Option<&[T]>,
+    ) -> Result<()> {
+        let matrices = [
+            ("P", Some(&mut self.problem.quadratic), quadratic),
+            (
+                "the equality matrix",
+                self.problem.equalities.as_mut().map(|eq| &mut eq.matrix),
+                equality,
+            ),
+            (
+                "the inequality matrix",
+                self.problem
+                    .inequalities
+                    .as_mut()
+                    .map(|ineq| &mut ineq.matrix),
+                inequality,
+            ),
+        ];
+        for (name, matrix, values) in &matrices {
+            match (matrix, values) {
+                (Some(matrix), Some(values)) if matrix.data.len() != values.len() => bail!(
+                    "{} values for the {} entries of {}",
+                    values.len(),
+                    matrix.data.len(),
+                    name
+                ),
+                (None, Some(_)) => bail!("the problem was set up without {}", name),
+                _ => {}
+            }
+        }
+        for (_, matrix, values) in matrices {
+            if let (Some(matrix), Some(values)) = (matrix, values) {
+                matrix.data.copy_from_slice(values);
+                self.matrices_changed = true;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn solve(&mut self) -> Result<AdmmResult<T>> {
+        let mut scaled = self.problem.clone();
+        scaled.validate()?;
+        self.scaler.rescale_qp(&mut scaled)?;
+        if self.matrices_changed {
+            self.workspace = AdmmWorkspace::new(&scaled, None)?;
+            self.lin_sys
+                .update_values(&self.workspace, &self.solver.options)?;
+            self.matrices_changed = false;
+        } else {
+            self.workspace.set_rhs(&scaled);
+        }
+        let start_rho = self
+            .previous
+            .as_ref()
+            .map_or(self.solver.options.admm_rho, |&(_, _, rho)| rho);
+        self.solver.handoff = self.previous.take();
+        let (solver, workspace, lin_sys) = (&self.solver, &self.workspace, &mut self.lin_sys);
+        let mut solution = in_pool(solver.options.num_threads, || {
+            solver.run(&scaled, workspace, lin_sys)
+        })?;
+        if solution.status == Status::Optimal {
+            let rho = solution
+                .stats
+                .rho_updates
+                .last()
+                .map_or(start_rho, |update| update.rho);
+            self.previous = Some((
+                solution.primal.clone(),
+                solution.inequality_dual.clone(),
+                rho,
+            ));
+        }
+        unscale(&self.scaler, &mut solution);
+        Ok(solution)
+    }
+}
+
+// OSQP's residual-ratio rule: rho moves by the square root of the ratio of
+// the primal residual ||Ax - z|| and the dual residual ||Px + q + Aᵀy||,
+// each relative to the size of the terms it is made of.
+fn balanced_rho<T: RealNumber + FromPrimitive>(
+    workspace: &AdmmWorkspace<T>,
+    q: &[T],
+    x: &[T],
+    ax: &[T],
+    z: &[T],
+    y: &[T],
+    rho: T,
+) -> T {
+    let (px, aty) = dual_terms(workspace, x, y);
+    rho_balance(q, ax, z, &px, &aty, rho)
+}
+
+// The same rule given Px and Aᵀy.
+fn rho_balance<T: RealNumber + FromPrimitive>(
+    q: &[T],
+    ax: &[T],
+    z: &[T],
+    px: &[T],
+    aty: &[T],
+    rho: T,
+) -> T {
+    let floor = T::from_f64(RHO_DIVISION_FLOOR).unwrap();
+    let primal: Vec<T> = ax.iter().zip(z).map(|(&a, &b)| a - b).collect();
+    let dual: Vec<T> = px
+        .iter()
+        .zip(aty)
+        .zip(q)
+        .map(|((&p, &a), &c)| p + a + c)
+        .collect();
+    let (primal_scale, dual_scale) = residual_scales(q, ax, z, px, aty);
+    let primal = norm_inf(&primal) / primal_scale.max(floor);
+    let dual = norm_inf(&dual) / dual_scale.max(floor);
+    rho * (primal / dual.max(floor)).sqrt()
+}
+
+// Px and Aᵀy, the terms of the dual residual besides q.
+fn dual_terms<T: RealNumber>(workspace: &AdmmWorkspace<T>, x: &[T], y: &[T]) -> (Vec<T>, Vec<T>) {
+    let n = workspace.n;
+    let mut px = vec![T::zero(); n];
+    multiply_dense(&workspace.p_base, n, n, x, &mut px);
+    let mut aty = vec![T::zero(); n];
+    workspace.multiply_at(y, &mut aty);
+    (px, aty)
+}
+
+// max(‖Ax‖, ‖z‖) and max(‖Px‖, ‖Aᵀy‖, ‖q‖), what the relative tolerance
+// and the rho balancing measure the residuals against.
+fn residual_scales<T: RealNumber>(q: &[T], ax: &[T], z: &[T], px: &[T], aty: &[T]) -> (T, T) {
+    (
+        norm_inf(ax).max(norm_inf(z)),
+        norm_inf(px).max(norm_inf(aty)).max(norm_inf(q)),
+    )
+}
+
+// OSQP's test for primal infeasibility on δy, the change of y over the last
+// iteration, after projecting it onto the polar of the recession cone of
+// [l, u]: when the problem is infeasible δy converges to a certificate with
+// uᵀmax(δy, 0) + lᵀmin(δy, 0) < 0 and Aᵀδy = 0. Both are measured in the
+// units of the original problem, relative to ‖δy‖. The certificate comes
+// back split like the duals and normalized so the support term is -1.
+fn primal_infeasibility<T: RealNumber + FromPrimitive>(
+    workspace: &AdmmWorkspace<T>,
+    weights: Option<&ResidualWeights<T>>,
+    mut delta_y: Vec<T>,
+    options: &SolveOptions<T>,
+) -> Option<Certificate<T>> {
+    for ((value, &lower), &upper) in delta_y
+        .iter_mut()
+        .zip(&workspace.lower)
+        .zip(&workspace.upper)
+    {
+        *value = match (lower.is_finite(), upper.is_finite()) {
+            (false, false) => T::zero(),
+            (true, false) => value.min(T::zero()),
+            (false, true) => value.max(T::zero()),
+            (true, true) => *value,
+        };
+    }
+    let mut support = T::zero();
+    for ((&value, &lower), &upper) in delta_y.iter().zip(&workspace.lower).zip(&workspace.upper) {
+        if value > T::zero() {
+            support += upper * value;
+        } else if value < T::zero() {
+            support += lower * value;
+        }
+    }
+    let mut original = delta_y.clone();
+    let mut aty = vec![T::zero(); workspace.n];
+    workspace.multiply_at(&delta_y, &mut aty);
+    if let Some(weights) = weights {
+        for (value, &weight) in original.iter_mut().zip(&weights.rows) {
+            *value = *value / weight * weights.objective;
+        }
+        support *= weights.objective;
+        weights.columns(&mut aty);
+    }
+    let norm = norm_inf(&original);
+    if norm <= T::from_f64(RHO_DIVISION_FLOOR).unwrap()
+        || support >= -options.eps_prim_inf * norm
+        || norm_inf(&aty) >= options.eps_prim_inf * norm
+    {
+        return None;
+    }
+    let scale = -support / weights.map_or(T::one(), |weights| weights.objective);
+    let mut inequality_dual: Vec<T> = delta_y.iter().map(|&value| value / scale).collect();
+    let equality_dual = inequality_dual.drain(..workspace.meq).collect();
+    Some(Certificate::PrimalInfeasible {
+        equality_dual,
+        inequality_dual,
+    })
+}
+
+// OSQP's test for dual infeasibility on δx, the change of x over the last
+// iteration: when the objective is unbounded below δx converges to a ray
+// with Pδx = 0, qᵀδx < 0 and Aδx in the recession cone of [l, u], each
+// measured in the units of the original problem relative to ‖δx‖. The ray
+// comes back normalized to qᵀd = -1.
+fn dual_infeasibility<T: RealNumber + FromPrimitive>(
+    workspace: &AdmmWorkspace<T>,
+    q: &[T],
+    weights: Option<&ResidualWeights<T>>,
+    delta_x: Vec<T>,
+    options: &SolveOptions<T>,
+) -> Option<Certificate<T>> {
+    let n = workspace.n;
+    let mut original = delta_x.clone();
+    let mut px = vec![T::zero(); n];
+    multiply_dense(&workspace.p_base, n, n, &delta_x, &mut px);
+    let mut ax = vec![T::zero(); workspace.m];
+    workspace.multiply_a(&delta_x, &mut ax);
+    let mut slope = dot(q, &delta_x);
+    if let Some(weights) = weights {
+        for (value, &weight) in original.iter_mut().zip(&weights.columns) {
+            *value = *value / weight * weights.objective;
+        }
+        weights.columns(&mut px);
+        weights.rows(&mut ax);
+        slope *= weights.objective;
+    }
+    let norm = norm_inf(&original);
+    let eps = options.eps_dual_inf * norm;
+    if norm <= T::from_f64(RHO_DIVISION_FLOOR).unwrap() || slope >= -eps || norm_inf(&px) >= eps {
+        return None;
     }
+    let inside =
+        ax.iter()
+            .zip(&workspace.lower)
+            .zip(&workspace.upper)
+            .all(|((&value, &lower), &upper)| {
+                (!upper.is_finite() || value <= eps) && (!lower.is_finite() || value >= -eps)
+            });
+    if !inside {
+        return None;
+    }
+    let scale = -dot(q, &delta_x);
+    Some(Certificate::DualInfeasible {
+        direction: delta_x.iter().map(|&value| value / scale).collect(),
+    })
+}
+
+// OSQP-style polishing. Equality rows and box rows whose multiplier pushes
+// against a bound form the active set; the QP with those rows as equalities
+// is solved directly. The point is kept only if the multipliers have the
+// right signs and both KKT residuals are no worse than at the ADMM point.
+fn polish<T: RealNumber + FromPrimitive>(
+    workspace: &AdmmWorkspace<T>,
+    q: &[T],
+    x: &[T],
+    z: &[T],
+    y: &[T],
+) -> Option<(Vec<T>, Vec<T>)> {
+    let (n, m) = (workspace.n, workspace.m);
+    let mut active = Vec::new();
+    for row in 0..m {
+        let (lower, upper) = (workspace.lower[row], workspace.upper[row]);
+        if row < workspace.meq || lower == upper || z[row] - lower < -y[row] {
+            active.push((row, lower));
+        } else if upper - z[row] < y[row] {
+            active.push((row, upper));
+        }
+    }
+
+    let dim = n + active.len();
+    let delta = T::from_f64(POLISH_REGULARIZATION).unwrap();
+    let mut exact = vec![T::zero(); dim * dim];
+    for i in 0..n {
+        exact[i * dim..i * dim + n].copy_from_slice(&workspace.p_base[i * n..(i + 1) * n]);
+    }
+    for (k, &(row, _)) in active.iter().enumerate() {
+        for col in 0..n {
+            let value = workspace.a_dense[row * n + col];
+            exact[(n + k) * dim + col] = value;
+            exact[col * dim + n + k] = value;
+        }
+    }
+    let mut regularized = exact.clone();
+    for i in 0..dim {
+        let shift = if i < n { delta } else { -delta };
+        regularized[i * dim + i] += shift;
+    }
+    let mut solver = DenseKktSolver::new();
+    solver.factor(&DenseKktMatrix::new(dim, regularized)).ok()?;
+    let rhs: Vec<T> = q
+        .iter()
+        .map(|&value| -value)
+        .chain(active.iter().map(|&(_, target)| target))
+        .collect();
+    let mut sol = rhs.clone();
+    solver.solve(&mut sol).ok()?;
+    for _ in 0..POLISH_REFINEMENT_STEPS {
+        let mut residual = rhs.clone();
+        for (i, value) in residual.iter_mut().enumerate() {
+            *value -= dot(&exact[i * dim..(i + 1) * dim], &sol);
+        }
+        solver.solve(&mut residual).ok()?;
+        for (value, correction) in sol.iter_mut().zip(&residual) {
+            *value += *correction;
+        }
+    }
+
+    let polished = sol[..n].to_vec();
+    let mut dual = vec![T::zero(); m];
+    // Multipliers of weakly active rows come out as roundoff of either sign.
+    let floor = T::from_f64(POLISH_RESIDUAL_FLOOR).unwrap();
+    for (&(row, target), &value) in active.iter().zip(&sol[n..]) {
+        let (lower, upper) = (workspace.lower[row], workspace.upper[row]);
+        let wrong_sign = lower != upper
+            && ((target == lower && value > floor) || (target == upper && value < -floor));
+        if wrong_sign {
+            return None;
+        }
+        dual[row] = value;
+    }
+    let (admm_primal, admm_dual) = kkt_residuals(workspace, q, x, y);
+    let (primal, dual_residual) = kkt_residuals(workspace, q, &polished, &dual);
+    (primal <= admm_primal.max(floor) && dual_residual <= admm_dual.max(floor))
+        .then_some((polished, dual))
+}
+
+// Distance of Ax from the constraint set and the stationarity residual
+// Px + q + Aᵀy, both in the infinity norm.
+fn kkt_residuals<T: RealNumber>(workspace: &AdmmWorkspace<T>, q: &[T], x: &[T], y: &[T]) -> (T, T) {
+    let n = workspace.n;
+    let mut ax = vec![T::zero(); workspace.m];
+    workspace.multiply_a(x, &mut ax);
+    let mut projected = ax.clone();
+    workspace.project(&mut projected);
+    let primal: Vec<T> = ax.iter().zip(&projected).map(|(&a, &p)| a - p).collect();
+    let mut stationarity = vec![T::zero(); n];
+    workspace.multiply_at(y, &mut stationarity);
+    let mut px = vec![T::zero(); n];
+    multiply_dense(&workspace.p_base, n, n, x, &mut px);
+    for ((value, &p), &c) in stationarity.iter_mut().zip(&px).zip(q) {
+        *value += p + c;
+    }
+    residuals_inf(&primal, &stationarity)
+}
+
+fn save_checkpoint<T: RealNumber>(
+    sink: &CheckpointSink<T>,
+    iteration: usize,
+    rho: T,
+    x: &[T],
+    z: &[T],
+    y: &[T],
+) {
+    let state = SolverState {
+        method: Method::Admm,
+        iteration,
+        rho,
+        primal: x.to_vec(),
+        slack: z.to_vec(),
+        dual: y.to_vec(),
+    };
+    // A failed save must not abort a long solve; the next interval retries.
+    if let Err(err) = sink(&state) {
+        tracing::warn!(error = %err, iteration, "failed to save checkpoint");
+    }
+}
+
+// An ADMM solution in another precision, its iteration numbers moved on by
+// `offset`.
+#[cfg(feature = "mixed-precision")]
+fn cast_solution<T: RealNumber, U: RealNumber>(
+    solution: Solution<T>,
+    offset: usize,
+) -> Solution<U> {
+    let mut stats = SolveStats::new();
+    stats.history = solution
+        .stats
+        .history
+        .iter()
+        .map(|record| {
+            let mut record = record.cast();
+            record.iteration += offset;
+            record
+        })
+        .collect();
+    stats.rho_updates = solution
+        .stats
+        .rho_updates
+        .iter()
+        .map(|update| RhoUpdate {
+            iteration: update.iteration + offset,
+            previous: cast(update.previous),
+            rho: cast(update.rho),
+        })
+        .collect();
+    stats.solve_time = solution.stats.solve_time;
+    stats.factorizations = solution.stats.factorizations;
+    stats.linear_solves = solution.stats.linear_solves;
+    stats.symbolic_time = solution.stats.symbolic_time;
+    stats.numeric_time = solution.stats.numeric_time;
+    stats.krylov = solution.stats.krylov;
+    stats.regularization = solution.stats.regularization.map(|stats| stats.cast());
+    stats.condition_estimate = solution.stats.condition_estimate.map(cast);
+    stats.polished = solution.stats.polished;
+    stats.anderson_rejections = solution.stats.anderson_rejections;
+    Solution {
+        primal: cast_slice(&solution.primal),
+        equality_dual: cast_slice(&solution.equality_dual),
+        inequality_dual: cast_slice(&solution.inequality_dual),
+        status: solution.status,
+        objective_value: cast(solution.objective_value),
+        iterations: solution.iterations,
+        stats,
+        certificate: solution.certificate.map(|certificate| match certificate {
+            Certificate::PrimalInfeasible {
+                equality_dual,
+                inequality_dual,
+            } => Certificate::PrimalInfeasible {
+                equality_dual: cast_slice(&equality_dual),
+                inequality_dual: cast_slice(&inequality_dual),
+            },
+            Certificate::DualInfeasible { direction } => Certificate::DualInfeasible {
+                direction: cast_slice(&direction),
+            },
+        }),
+        basis: None,
+    }
+}
+
+// Takes a solution of the scaled problem back to the problem as given.
+fn unscale<T: RealNumber, S: Scaler<T>>(scaler: &S, solution: &mut AdmmResult<T>) {
+    scaler.unscale_primal(&mut solution.primal);
+    scaler.unscale_dual(&mut solution.equality_dual, &mut solution.inequality_dual);
+    solution.objective_value = scaler.unscale_objective(solution.objective_value);
+    scaler.unscale_stats(&mut solution.stats);
+    // Both certificates are normalized against the scaled objective, which
+    // is the original one times the cost factor c = 1 / unscale_objective(1).
+    let cost = scaler.unscale_objective(T::one());
+    match &mut solution.certificate {
+        Some(Certificate::PrimalInfeasible {
+            equality_dual,
+            inequality_dual,
+        }) => {
+            scaler.unscale_dual(equality_dual, inequality_dual);
+            for value in equality_dual.iter_mut().chain(inequality_dual.iter_mut()) {
+                *value = *value / cost;
+            }
+        }
+        Some(Certificate::DualInfeasible { direction }) => {
+            scaler.unscale_primal(direction);
+            for value in direction.iter_mut() {
+                *value = *value / cost;
+            }
+        }
+        None => {}
+    }
+}
+
+// With `num_threads` set, the solve runs inside its own scoped pool so the
+// parallel kernels never spill onto rayon's global pool. The caller's span is
+// re-entered on the pool thread to keep the trace nested.
+#[cfg(feature = "rayon")]
+pub(crate) fn in_pool<R: Send>(
+    num_threads: Option<usize>,
+    op: impl FnOnce() -> Result<R> + Send,
+) -> Result<R> {
+    let Some(threads) = num_threads else {
+        return op();
+    };
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .build()?;
+    let span = tracing::Span::current();
+    pool.install(move || span.in_scope(op))
+}
+
+#[cfg(not(feature = "rayon"))]
+pub(crate) fn in_pool<R>(_num_threads: Option<usize>, op: impl FnOnce() -> Result<R>) -> Result<R> {
+    op()
 }
 
 fn compute_objective<T: RealNumber + FromPrimitive>(
@@ -327,7 +2455,33 @@ fn compute_objective<T: RealNumber + FromPrimitive>(
     let mut px = vec![T::zero(); problem.nvars()];
     multiply_dense(p_dense, problem.nvars(), problem.nvars(), x, &mut px);
     obj += T::from_f64(0.5).unwrap() * dot(x, &px);
-    obj
+    obj + problem.offset
+}
+
+// Whether two problems share P and every constraint matrix, so that only
+// their vectors differ.
+fn same_matrices<T: RealNumber>(a: &ProblemQP<T>, b: &ProblemQP<T>) -> bool {
+    let same = |a: Option<&CscMatrix<T>>, b: Option<&CscMatrix<T>>| match (a, b) {
+        (Some(a), Some(b)) => {
+            a.nrows == b.nrows
+                && a.ncols == b.ncols
+                && a.indptr == b.indptr
+                && a.indices == b.indices
+                && a.data == b.data
+        }
+        (None, None) => true,
+        _ => false,
+    };
+    same(Some(&a.quadratic), Some(&b.quadratic))
+        && same(
+            a.equalities.as_ref().map(|eq| &eq.matrix),
+            b.equalities.as_ref().map(|eq| &eq.matrix),
+        )
+        && same(
+            a.inequalities.as_ref().map(|ineq| &ineq.matrix),
+            b.inequalities.as_ref().map(|ineq| &ineq.matrix),
+        )
+        && a.bounds.is_some() == b.bounds.is_some()
 }
 
 fn scatter_csc<T: RealNumber>(
@@ -348,24 +2502,80 @@ fn scatter_csc<T: RealNumber>(
     }
 }
 
-fn csc_to_dense<T: RealNumber>(matrix: &CscMatrix<T>) -> Vec<T> {
-    let mut dense = vec![T::zero(); matrix.nrows * matrix.ncols];
-    scatter_csc(matrix, matrix.ncols, 0, &mut dense);
-    dense
+// The given rows of `matrix`, in that order.
+fn select_rows<T: RealNumber>(matrix: &CscMatrix<T>, rows: &[usize]) -> CscMatrix<T> {
+    let mut position = vec![None; matrix.nrows];
+    for (new, &row) in rows.iter().enumerate() {
+        position[row] = Some(new);
+    }
+    let mut indptr = Vec::with_capacity(matrix.ncols + 1);
+    let mut indices = Vec::new();
+    let mut data = Vec::new();
+    indptr.push(0);
+    for col in 0..matrix.ncols {
+        let mut column: Vec<(usize, T)> = (matrix.indptr[col]..matrix.indptr[col + 1])
+            .filter_map(|idx| position[matrix.indices[idx]].map(|row| (row, matrix.data[idx])))
+            .collect();
+        column.sort_by_key(|&(row, _)| row);
+        for (row, value) in column {
+            indices.push(row);
+            data.push(value);
+        }
+        indptr.push(indices.len());
+    }
+    CscMatrix {
+        nrows: rows.len(),
+        ncols: matrix.ncols,
+        indptr,
+        indices,
+        data,
+    }
 }
 
-fn compute_ata<T: RealNumber>(a: &[T], m: usize, n: usize) -> Vec<T> {
-    let mut ata = vec![T::zero(); n * n];
-    for i in 0..n {
-        for j in 0..n {
-            let mut acc = T::zero();
-            for row in 0..m {
-                acc += a[row * n + i] * a[row * n + j];
+// Blocks with `ncols` columns stacked one below the other and transposed:
+// column r of the result holds row r of the stack, its entries in column
+// order.
+fn stack_rows<T: RealNumber>(ncols: usize, blocks: &[&CscMatrix<T>]) -> CscMatrix<T> {
+    let nrows: usize = blocks.iter().map(|block| block.nrows).sum();
+    let mut indptr = vec![0; nrows + 1];
+    let mut base = 0;
+    for block in blocks {
+        for &row in &block.indices[..block.indptr[block.ncols]] {
+            indptr[base + row + 1] += 1;
+        }
+        base += block.nrows;
+    }
+    for row in 0..nrows {
+        indptr[row + 1] += indptr[row];
+    }
+    let mut next = indptr.clone();
+    let mut indices = vec![0; indptr[nrows]];
+    let mut data = vec![T::zero(); indptr[nrows]];
+    base = 0;
+    for block in blocks {
+        for col in 0..block.ncols {
+            for idx in block.indptr[col]..block.indptr[col + 1] {
+                let slot = &mut next[base + block.indices[idx]];
+                indices[*slot] = col;
+                data[*slot] = block.data[idx];
+                *slot += 1;
             }
-            ata[i * n + j] = acc;
         }
+        base += block.nrows;
     }
-    ata
+    CscMatrix {
+        nrows: ncols,
+        ncols: nrows,
+        indptr,
+        indices,
+        data,
+    }
+}
+
+fn csc_to_dense<T: RealNumber>(matrix: &CscMatrix<T>) -> Vec<T> {
+    let mut dense = vec![T::zero(); matrix.nrows * matrix.ncols];
+    scatter_csc(matrix, matrix.ncols, 0, &mut dense);
+    dense
 }
 
 fn multiply_dense<T: RealNumber>(matrix: &[T], rows: usize, cols: usize, x: &[T], out: &mut [T]) {
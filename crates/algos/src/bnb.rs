@@ -0,0 +1,384 @@
+use anyhow::{bail, Result};
+use cvxrs_core::math::{dot, RealNumber, Timer};
+use cvxrs_core::problem::{Bounds, ProblemLP, ProblemQP, ProblemResult};
+use cvxrs_core::solution::{Solution, Status};
+use cvxrs_core::stats::{BranchAndBoundStats, KrylovStats, RegularizationStats, SolveStats};
+use cvxrs_core::traits::LinearOperator;
+use num_traits::FromPrimitive;
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::sync::Arc;
+
+const DEFAULT_NODE_LIMIT: usize = 10_000;
+
+// Relative distance between the incumbent and the best bound at which the
+// search stops.
+const DEFAULT_GAP_TOLERANCE: f64 = 1e-6;
+
+// Relaxed values this close to an integer count as integral; first-order
+// relaxations need a looser value.
+const DEFAULT_INTEGRALITY_TOLERANCE: f64 = 1e-6;
+
+// An integral point found during the search and the node that found it.
+#[derive(Debug, Clone)]
+pub struct Incumbent<T> {
+    pub primal: Vec<T>,
+    pub objective: T,
+    pub node: usize,
+}
+
+// Receives every improving incumbent as soon as it is found.
+pub type IncumbentSink<T> = Arc<dyn Fn(&Incumbent<T>) + Send + Sync>;
+
+#[derive(Clone)]
+pub struct BranchAndBound<T: RealNumber> {
+    node_limit: usize,
+    gap_tolerance: T,
+    integrality_tolerance: T,
+    on_incumbent: Option<IncumbentSink<T>>,
+}
+
+impl<T> Default for BranchAndBound<T>
+where
+    T: RealNumber + FromPrimitive,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> BranchAndBound<T>
+where
+    T: RealNumber + FromPrimitive,
+{
+    pub fn new() -> Self {
+        Self {
+            node_limit: DEFAULT_NODE_LIMIT,
+            gap_tolerance: T::from_f64(DEFAULT_GAP_TOLERANCE).unwrap(),
+            integrality_tolerance: T::from_f64(DEFAULT_INTEGRALITY_TOLERANCE).unwrap(),
+            on_incumbent: None,
+        }
+    }
+
+    pub fn with_node_limit(mut self, nodes: usize) -> Self {
+        self.node_limit = nodes;
+        self
+    }
+
+    pub fn with_gap_tolerance(mut self, gap: T) -> Self {
+        self.gap_tolerance = gap;
+        self
+    }
+
+    pub fn with_integrality_tolerance(mut self, tolerance: T) -> Self {
+        self.integrality_tolerance = tolerance;
+        self
+    }
+
+    pub fn with_incumbent_callback(mut self, sink: IncumbentSink<T>) -> Self {
+        self.on_incumbent = Some(sink);
+        self
+    }
+
+    // `relax` solves the continuous relaxation of each node, which differs
+    // from `problem` only in its bounds.
+    pub fn solve_qp(
+        &self,
+        problem: &ProblemQP<T>,
+        relax: impl FnMut(&ProblemQP<T>) -> Result<Solution<T>>,
+    ) -> Result<Solution<T>> {
+        self.search(problem, relax)
+    }
+
+    pub fn solve_lp(
+        &self,
+        problem: &ProblemLP<T>,
+        relax: impl FnMut(&ProblemLP<T>) -> Result<Solution<T>>,
+    ) -> Result<Solution<T>> {
+        self.search(problem, relax)
+    }
+
+    // Best-bound search that branches on the most fractional variable.
+    // Nodes whose relaxation cannot beat the incumbent by more than the gap
+    // tolerance are pruned, and the search ends once the open node with the
+    // smallest bound is pruned.
+    fn search<P: Relaxation<T>>(
+        &self,
+        problem: &P,
+        mut relax: impl FnMut(&P) -> Result<Solution<T>>,
+    ) -> Result<Solution<T>> {
+        problem.validate()?;
+        let timer = Timer::start();
+        let n = problem.nvars();
+        let integer: Vec<usize> = (0..n)
+            .filter(|&j| problem.integrality().get(j).copied().unwrap_or(false))
+            .collect();
+        let mut root = problem
+            .bounds()
+            .cloned()
+            .unwrap_or_else(|| Bounds::unbounded(n));
+        for &j in &integer {
+            root.lower[j] = root.lower[j].ceil();
+            root.upper[j] = root.upper[j].floor();
+        }
+
+        let mut open = BinaryHeap::new();
+        open.push(Node {
+            bound: T::neg_infinity(),
+            id: 0,
+            bounds: root,
+        });
+        let mut next_id = 1;
+        let mut best: Option<(Incumbent<T>, Solution<T>)> = None;
+        let mut totals = SolveStats::new();
+        let (mut nodes, mut incumbents, mut iterations) = (0, 0, 0);
+        let mut limit_reached = false;
+        while let Some(node) = open.pop() {
+            if let Some((incumbent, _)) = &best {
+                if !self.improves(node.bound, incumbent.objective) {
+                    open.push(node);
+                    break;
+                }
+            }
+            if nodes >= self.node_limit {
+                open.push(node);
+                limit_reached = true;
+                break;
+            }
+            nodes += 1;
+            if node
+                .bounds
+                .lower
+                .iter()
+                .zip(&node.bounds.upper)
+                .any(|(l, u)| l > u)
+            {
+                continue;
+            }
+            let mut relaxed = problem.clone();
+            relaxed.set_bounds(node.bounds.clone());
+            let solution = relax(&relaxed)?;
+            iterations += solution.iterations;
+            totals.factorizations += solution.stats.factorizations;
+            totals.linear_solves += solution.stats.linear_solves;
+            totals.symbolic_time += solution.stats.symbolic_time;
+            totals.numeric_time += solution.stats.numeric_time;
+            if let Some(krylov) = &solution.stats.krylov {
+                totals
+                    .krylov
+                    .get_or_insert_with(KrylovStats::default)
+                    .merge(krylov);
+            }
+            if let Some(regularization) = &solution.stats.regularization {
+                totals
+                    .regularization
+                    .get_or_insert_with(RegularizationStats::new)
+                    .merge(regularization);
+            }
+            if let Some(estimate) = solution.stats.condition_estimate {
+                let worst = totals
+                    .condition_estimate
+                    .map_or(estimate, |worst| worst.max(estimate));
+                totals.condition_estimate = Some(worst);
+            }
+            match solution.status {
+                Status::Optimal => {}
+                Status::PrimalInfeasible => continue,
+                // Bounds only tighten, so this can only happen at the root.
+                Status::DualInfeasible => return Ok(solution),
+                other => bail!(
+                    "relaxation of node {} stopped with status {:?}",
+                    node.id,
+                    other
+                ),
+            }
+            if let Some((incumbent, _)) = &best {
+                if !self.improves(solution.objective_value, incumbent.objective) {
+                    continue;
+                }
+            }
+
+            let fractional = integer
+                .iter()
+                .map(|&j| (j, (solution.primal[j] - solution.primal[j].round()).abs()))
+                .filter(|&(_, distance)| distance > self.integrality_tolerance)
+                .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(Ordering::Equal));
+            let Some((j, _)) = fractional else {
+                let mut primal = solution.primal.clone();
+                for &j in &integer {
+                    primal[j] = primal[j].round();
+                }
+                let objective = problem.objective(&primal);
+                if best.as_ref().is_some_and(|(b, _)| objective >= b.objective) {
+                    continue;
+                }
+                let incumbent = Incumbent {
+                    primal,
+                    objective,
+                    node: node.id,
+                };
+                if let Some(sink) = &self.on_incumbent {
+                    sink(&incumbent);
+                }
+                incumbents += 1;
+                best = Some((incumbent, solution));
+                continue;
+            };
+            let value = solution.primal[j];
+            let mut down = node.bounds.clone();
+            down.upper[j] = value.floor();
+            let mut up = node.bounds;
+            up.lower[j] = value.ceil();
+            for bounds in [down, up] {
+                open.push(Node {
+                    bound: solution.objective_value,
+                    id: next_id,
+                    bounds,
+                });
+                next_id += 1;
+            }
+        }
+
+        let open_bound = open
+            .iter()
+            .map(|node| node.bound)
+            .fold(T::infinity(), |acc, bound| acc.min(bound));
+        let (mut solution, best_bound) = match best {
+            Some((incumbent, mut solution)) => {
+                let best_bound = open_bound.min(incumbent.objective);
+                solution.status =
+                    if !limit_reached || !self.improves(best_bound, incumbent.objective) {
+                        Status::Optimal
+                    } else {
+                        Status::MaxIterations
+                    };
+                solution.primal = incumbent.primal;
+                solution.objective_value = incumbent.objective;
+                (solution, best_bound)
+            }
+            None => {
+                let mut solution = Solution::with_capacity(n, 0, 0);
+                solution.objective_value = T::infinity();
+                solution.status = if limit_reached {
+                    Status::MaxIterations
+                } else {
+                    Status::PrimalInfeasible
+                };
+                (solution, open_bound)
+            }
+        };
+        totals.branch_and_bound = Some(BranchAndBoundStats {
+            nodes,
+            incumbents,
+            best_bound,
+        });
+        totals.solve_time = timer.elapsed();
+        solution.iterations = iterations;
+        solution.stats = totals;
+        Ok(solution)
+    }
+
+    // Whether a bound leaves room below the incumbent beyond the gap tolerance.
+    fn improves(&self, bound: T, incumbent: T) -> bool {
+        let scale = T::one().max(incumbent.abs());
+        incumbent - bound > self.gap_tolerance * scale
+    }
+}
+
+// An open node and the relaxation objective of its parent, a lower bound
+// on everything below it. The heap pops the smallest bound first and, among
+// equal bounds, the newest node.
+struct Node<T: RealNumber> {
+    bound: T,
+    id: usize,
+    bounds: Bounds<T>,
+}
+
+impl<T: RealNumber> PartialEq for Node<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<T: RealNumber> Eq for Node<T> {}
+
+impl<T: RealNumber> PartialOrd for Node<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: RealNumber> Ord for Node<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .bound
+            .partial_cmp(&self.bound)
+            .unwrap_or(Ordering::Equal)
+            .then(self.id.cmp(&other.id))
+    }
+}
+
+// The continuous problems whose bounds branch-and-bound tightens.
+trait Relaxation<T: RealNumber>: Clone {
+    fn validate(&self) -> ProblemResult<()>;
+    fn nvars(&self) -> usize;
+    fn integrality(&self) -> &[bool];
+    fn bounds(&self) -> Option<&Bounds<T>>;
+    fn set_bounds(&mut self, bounds: Bounds<T>);
+    fn objective(&self, x: &[T]) -> T;
+}
+
+impl<T: RealNumber> Relaxation<T> for ProblemQP<T> {
+    fn validate(&self) -> ProblemResult<()> {
+        ProblemQP::validate(self)
+    }
+
+    fn nvars(&self) -> usize {
+        ProblemQP::nvars(self)
+    }
+
+    fn integrality(&self) -> &[bool] {
+        &self.integrality
+    }
+
+    fn bounds(&self) -> Option<&Bounds<T>> {
+        self.bounds.as_ref()
+    }
+
+    fn set_bounds(&mut self, bounds: Bounds<T>) {
+        self.bounds = Some(bounds);
+    }
+
+    fn objective(&self, x: &[T]) -> T {
+        let mut px = vec![T::zero(); x.len()];
+        self.quadratic.apply(x, &mut px);
+        let half = T::one() / (T::one() + T::one());
+        half * dot(x, &px) + dot(&self.linear, x) + self.offset
+    }
+}
+
+impl<T: RealNumber> Relaxation<T> for ProblemLP<T> {
+    fn validate(&self) -> ProblemResult<()> {
+        ProblemLP::validate(self)
+    }
+
+    fn nvars(&self) -> usize {
+        ProblemLP::nvars(self)
+    }
+
+    fn integrality(&self) -> &[bool] {
+        &self.integrality
+    }
+
+    fn bounds(&self) -> Option<&Bounds<T>> {
+        self.bounds.as_ref()
+    }
+
+    fn set_bounds(&mut self, bounds: Bounds<T>) {
+        self.bounds = Some(bounds);
+    }
+
+    fn objective(&self, x: &[T]) -> T {
+        dot(&self.cost, x) + self.offset
+    }
+}
@@ -0,0 +1,408 @@
+use crate::admm::{in_pool, AdmmSolver};
+use anyhow::{bail, Result};
+use cvxrs_core::math::{dot, norm2, RealNumber, Timer};
+use cvxrs_core::options::SolveOptions;
+use cvxrs_core::problem::{CscMatrix, ProblemQP, WarmStart};
+use cvxrs_core::scaling::BuiltinScaler;
+use cvxrs_core::solution::{Solution, Status};
+use cvxrs_core::stats::{
+    interrupted, InterruptFlag, IterationRecord, IterationSink, KrylovStats, RegularizationStats,
+    RhoUpdate, SolveStats,
+};
+use cvxrs_core::traits::LinearOperator;
+use num_traits::FromPrimitive;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+// Ratio between the primal and dual residuals past which the penalty is
+// doubled or halved, as in Boyd et al., section 3.4.1.
+const RHO_BALANCE: f64 = 10.0;
+
+// One block of a consensus problem: its own QP and, for each shared
+// variable, the index of its local copy in that QP.
+#[derive(Debug, Clone)]
+pub struct Scenario<T: RealNumber> {
+    pub problem: ProblemQP<T>,
+    pub shared: Vec<usize>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ConsensusSolution<T: RealNumber> {
+    // The agreed values of the shared variables.
+    pub consensus: Vec<T>,
+    // One solution per scenario, its objective that of the scenario's own QP.
+    pub scenarios: Vec<Solution<T>>,
+    pub objective_value: T,
+    pub status: Status,
+    pub iterations: usize,
+    pub stats: SolveStats<T>,
+}
+
+pub struct ConsensusSolver<T: RealNumber> {
+    options: SolveOptions<T>,
+    iteration_log: Option<IterationSink<T>>,
+    interrupt: Option<InterruptFlag>,
+}
+
+// A scenario as its subproblem sees it: P carries the penalty on the shared
+// copies and `dual` holds the scaled multipliers u_i of S_i x_i = z.
+struct Local<'a, T: RealNumber> {
+    scenario: &'a Scenario<T>,
+    problem: ProblemQP<T>,
+    dual: Vec<T>,
+    solution: Option<Solution<T>>,
+}
+
+impl<T> ConsensusSolver<T>
+where
+    T: RealNumber + FromPrimitive,
+{
+    pub fn new(options: SolveOptions<T>) -> Self {
+        Self {
+            options,
+            iteration_log: None,
+            interrupt: None,
+        }
+    }
+
+    pub fn with_iteration_log(mut self, sink: IterationSink<T>) -> Self {
+        self.iteration_log = Some(sink);
+        self
+    }
+
+    pub fn with_interrupt(mut self, flag: InterruptFlag) -> Self {
+        self.interrupt = Some(flag);
+        self
+    }
+
+    // Global-variable consensus ADMM on min Σ f_i(x_i) s.t. S_i x_i = z.
+    // Every iteration solves the scenarios independently, each with the
+    // proximal term (ρ/2)‖S_i x_i - z + u_i‖², then averages the shared
+    // copies into z and updates u_i. The objectives are summed, so a
+    // stochastic program weighs each scenario's QP by its probability.
+    pub fn solve(&self, scenarios: &[Scenario<T>]) -> Result<ConsensusSolution<T>> {
+        let shared = check_scenarios(scenarios)?;
+        if self.options.rho_update_interval == 0 {
+            bail!("the rho update interval must be positive");
+        }
+        let timer = Timer::start();
+        let mut stats = SolveStats::new();
+        let count = T::from_usize(scenarios.len()).unwrap();
+        // The scenarios run in parallel, so each of their solves stays on
+        // the pool this one installs.
+        let local_options = SolveOptions {
+            num_threads: None,
+            ..self.options.clone()
+        };
+
+        let mut rho = self.options.admm_rho;
+        let mut locals: Vec<Local<'_, T>> = scenarios
+            .iter()
+            .map(|scenario| Local {
+                scenario,
+                problem: penalized(&scenario.problem, &scenario.shared, rho),
+                dual: vec![T::zero(); shared],
+                solution: None,
+            })
+            .collect();
+        let mut z = vec![T::zero(); shared];
+        let mut previous = vec![T::zero(); shared];
+        let mut status = Status::MaxIterations;
+        for iter in 0..self.options.max_iterations {
+            if self
+                .options
+                .max_time
+                .is_some_and(|limit| timer.elapsed() > limit)
+            {
+                status = Status::MaxTime;
+                break;
+            }
+            if interrupted(self.interrupt.as_ref()) {
+                status = Status::Interrupted;
+                break;
+            }
+            in_pool(self.options.num_threads, || {
+                self.solve_locals(&mut locals, &z, rho, &local_options)
+            })?;
+            let mut failed = None;
+            for local in &locals {
+                let solution = local.solution.as_ref().unwrap();
+                stats.factorizations += solution.stats.factorizations;
+                stats.linear_solves += solution.stats.linear_solves;
+                stats.symbolic_time += solution.stats.symbolic_time;
+                stats.numeric_time += solution.stats.numeric_time;
+                if let Some(krylov) = &solution.stats.krylov {
+                    stats
+                        .krylov
+                        .get_or_insert_with(KrylovStats::default)
+                        .merge(krylov);
+                }
+                if let Some(regularization) = &solution.stats.regularization {
+                    stats
+                        .regularization
+                        .get_or_insert_with(RegularizationStats::new)
+                        .merge(regularization);
+                }
+                if let Some(estimate) = solution.stats.condition_estimate {
+                    let worst = stats
+                        .condition_estimate
+                        .map_or(estimate, |worst| worst.max(estimate));
+                    stats.condition_estimate = Some(worst);
+                }
+                if matches!(
+                    solution.status,
+                    Status::PrimalInfeasible | Status::DualInfeasible | Status::NumericalFailure
+                ) {
+                    failed = Some(solution.status);
+                }
+            }
+            if let Some(failure) = failed {
+                status = failure;
+                break;
+            }
+
+            previous.copy_from_slice(&z);
+            z.fill(T::zero());
+            for local in &locals {
+                let x = &local.solution.as_ref().unwrap().primal;
+                for ((z, &j), &u) in z.iter_mut().zip(&local.scenario.shared).zip(&local.dual) {
+                    *z += x[j] + u;
+                }
+            }
+            for value in z.iter_mut() {
+                *value = *value / count;
+            }
+            let (mut primal_sq, mut copies_sq, mut dual_sq) = (T::zero(), T::zero(), T::zero());
+            for local in locals.iter_mut() {
+                let x = &local.solution.as_ref().unwrap().primal;
+                for ((u, &j), &z) in local.dual.iter_mut().zip(&local.scenario.shared).zip(&z) {
+                    let residual = x[j] - z;
+                    *u += residual;
+                    primal_sq += residual * residual;
+                    copies_sq += x[j] * x[j];
+                    dual_sq += *u * *u;
+                }
+            }
+            let moved: Vec<T> = z.iter().zip(&previous).map(|(&a, &b)| a - b).collect();
+            let primal_residual = primal_sq.sqrt();
+            let dual_residual = rho * count.sqrt() * norm2(&moved);
+            let primal_scale = copies_sq.sqrt().max(count.sqrt() * norm2(&z));
+            let dual_scale = rho * dual_sq.sqrt();
+
+            let objective = locals.iter().fold(T::zero(), |acc, local| {
+                acc + objective(
+                    &local.scenario.problem,
+                    &local.solution.as_ref().unwrap().primal,
+                )
+            });
+            stats.push(IterationRecord::new(
+                iter,
+                primal_residual / (T::one() + primal_scale),
+                dual_residual / (T::one() + dual_scale),
+                T::zero(),
+                rho,
+                T::one(),
+                objective,
+                objective,
+                timer.elapsed(),
+            ));
+            if let (Some(sink), Some(record)) = (&self.iteration_log, stats.history.last()) {
+                sink(record);
+            }
+            let solved = locals
+                .iter()
+                .all(|local| local.solution.as_ref().unwrap().status == Status::Optimal);
+            if solved
+                && primal_residual <= self.options.eps_abs + self.options.eps_rel * primal_scale
+                && dual_residual <= self.options.eps_abs + self.options.eps_rel * dual_scale
+            {
+                status = Status::Optimal;
+                break;
+            }
+
+            if !self.options.admm_adaptive_rho || (iter + 1) % self.options.rho_update_interval != 0
+            {
+                continue;
+            }
+            let balance = T::from_f64(RHO_BALANCE).unwrap();
+            let two = T::one() + T::one();
+            let proposed = if primal_residual > balance * dual_residual {
+                rho * two
+            } else if dual_residual > balance * primal_residual {
+                rho / two
+            } else {
+                rho
+            };
+            let proposed = proposed.max(self.options.rho_min).min(self.options.rho_max);
+            if proposed == rho {
+                continue;
+            }
+            // u is scaled by 1/ρ, so it follows the penalty.
+            for local in locals.iter_mut() {
+                for u in local.dual.iter_mut() {
+                    *u = *u * rho / proposed;
+                }
+                local.problem =
+                    penalized(&local.scenario.problem, &local.scenario.shared, proposed);
+            }
+            stats.rho_updates.push(RhoUpdate {
+                iteration: iter,
+                previous: rho,
+                rho: proposed,
+            });
+            rho = proposed;
+        }
+
+        let mut solutions = Vec::with_capacity(locals.len());
+        let mut objective_value = T::zero();
+        for local in locals {
+            let Some(mut solution) = local.solution else {
+                continue;
+            };
+            solution.objective_value = objective(&local.scenario.problem, &solution.primal);
+            objective_value += solution.objective_value;
+            solutions.push(solution);
+        }
+        stats.solve_time = timer.elapsed();
+        Ok(ConsensusSolution {
+            consensus: z,
+            scenarios: solutions,
+            objective_value,
+            status,
+            iterations: stats.history.len(),
+            stats,
+        })
+    }
+
+    fn solve_locals(
+        &self,
+        locals: &mut [Local<'_, T>],
+        z: &[T],
+        rho: T,
+        options: &SolveOptions<T>,
+    ) -> Result<()> {
+        let solve =
+            |local: &mut Local<'_, T>| local.solve(z, rho, options, self.interrupt.as_ref());
+        #[cfg(feature = "rayon")]
+        return locals.par_iter_mut().try_for_each(solve);
+        #[cfg(not(feature = "rayon"))]
+        locals.iter_mut().try_for_each(solve)
+    }
+}
+
+impl<T> Local<'_, T>
+where
+    T: RealNumber + FromPrimitive,
+{
+    // The proximal term adds ρ(u_i - z) to q on the shared copies; P already
+    // has ρ on their diagonal. Each solve starts from the previous x_i.
+    fn solve(
+        &mut self,
+        z: &[T],
+        rho: T,
+        options: &SolveOptions<T>,
+        interrupt: Option<&InterruptFlag>,
+    ) -> Result<()> {
+        let mut problem = self.problem.clone();
+        for ((&j, &u), &z) in self.scenario.shared.iter().zip(&self.dual).zip(z) {
+            problem.linear[j] += rho * (u - z);
+        }
+        let mut solver = AdmmSolver::new(options.clone());
+        if let Some(previous) = &self.solution {
+            solver = solver.with_warm_start(WarmStart {
+                primal: previous.primal.clone(),
+                ..WarmStart::empty()
+            });
+        }
+        if let Some(flag) = interrupt {
+            solver = solver.with_interrupt(flag.clone());
+        }
+        self.solution = Some(solver.solve_qp(problem, &mut BuiltinScaler::new(options.scaling))?);
+        Ok(())
+    }
+}
+
+// Every scenario must be a continuous QP and name the same number of
+// distinct shared variables; returns that number.
+fn check_scenarios<T: RealNumber>(scenarios: &[Scenario<T>]) -> Result<usize> {
+    let Some(first) = scenarios.first() else {
+        bail!("a consensus problem needs at least one scenario");
+    };
+    let shared = first.shared.len();
+    for (i, scenario) in scenarios.iter().enumerate() {
+        scenario.problem.validate()?;
+        if scenario.problem.integrality.contains(&true) {
+            bail!("scenario {} has integer variables", i);
+        }
+        if scenario.shared.len() != shared {
+            bail!(
+                "scenario {} shares {} variables, scenario 0 shares {}",
+                i,
+                scenario.shared.len(),
+                shared
+            );
+        }
+        let n = scenario.problem.nvars();
+        let mut seen = vec![false; n];
+        for &j in &scenario.shared {
+            if j >= n || std::mem::replace(&mut seen[j], true) {
+                bail!("scenario {} has an invalid shared variable index {}", i, j);
+            }
+        }
+    }
+    Ok(shared)
+}
+
+// The scenario's QP with `rho` added to the diagonal of P at `columns`,
+// inserting the diagonal entries it lacks.
+fn penalized<T: RealNumber>(problem: &ProblemQP<T>, columns: &[usize], rho: T) -> ProblemQP<T> {
+    let p = &problem.quadratic;
+    let mut marked = vec![false; p.ncols];
+    for &j in columns {
+        marked[j] = true;
+    }
+    let mut indptr = Vec::with_capacity(p.ncols + 1);
+    let mut indices = Vec::with_capacity(p.nnz() + columns.len());
+    let mut data = Vec::with_capacity(p.nnz() + columns.len());
+    indptr.push(0);
+    for (col, &marked) in marked.iter().enumerate() {
+        let mut pending = marked;
+        for idx in p.indptr[col]..p.indptr[col + 1] {
+            let row = p.indices[idx];
+            if pending && row >= col {
+                if row == col {
+                    indices.push(row);
+                    data.push(p.data[idx] + rho);
+                    pending = false;
+                    continue;
+                }
+                indices.push(col);
+                data.push(rho);
+                pending = false;
+            }
+            indices.push(row);
+            data.push(p.data[idx]);
+        }
+        if pending {
+            indices.push(col);
+            data.push(rho);
+        }
+        indptr.push(indices.len());
+    }
+    ProblemQP {
+        quadratic: CscMatrix {
+            nrows: p.nrows,
+            ncols: p.ncols,
+            indptr,
+            indices,
+            data,
+        },
+        ..problem.clone()
+    }
+}
+
+fn objective<T: RealNumber + FromPrimitive>(problem: &ProblemQP<T>, x: &[T]) -> T {
+    let mut px = vec![T::zero(); x.len()];
+    problem.quadratic.apply(x, &mut px);
+    T::from_f64(0.5).unwrap() * dot(x, &px) + dot(&problem.linear, x) + problem.offset
+}
@@ -1,29 +1,705 @@
 use anyhow::{bail, Result};
-use cvxrs_core::math::RealNumber;
-use cvxrs_core::options::SolveOptions;
-use cvxrs_core::problem::{ProblemLP, ProblemQP};
-use cvxrs_core::solution::Solution;
+use cvxrs_core::math::{dot, norm_inf, relative_gap, RealNumber, Timer};
+use cvxrs_core::options::{LinsysBackend, SolveOptions};
+use cvxrs_core::problem::{CscMatrix, ProblemLP, ProblemQP};
+use cvxrs_core::solution::{Certificate, Solution, Status};
+use cvxrs_core::stats::{
+    interrupted, InterruptFlag, IterationRecord, IterationSink, KrylovStats, SolveStats,
+};
+use cvxrs_core::traits::KktSolver;
+use cvxrs_linsys::dense::{DenseKktMatrix, DenseKktSolver, DensePattern, DensePivoting};
+#[cfg(feature = "gpu")]
+use cvxrs_linsys::gpu::{GpuDevice, GpuKktSolver};
+use cvxrs_linsys::minres::{MinresKktSolver, MinresPattern, MinresSystem};
+use num_traits::FromPrimitive;
+use std::sync::Arc;
+use std::time::Duration;
 
-pub struct IpmSolver;
+// Static regularization of the reduced KKT system. A few refinement steps
+// against the unregularized matrix recover the accuracy it costs.
+const KKT_REGULARIZATION: f64 = 1e-8;
+const REFINEMENT_STEPS: usize = 3;
 
-impl IpmSolver {
+// Share of the distance to the boundary of the cone taken by each step.
+const STEP_FRACTION: f64 = 0.99;
+
+// Steps shorter than this make no progress; the iterates have stalled.
+const MIN_STEP: f64 = 1e-10;
+
+pub struct IpmSolver<T: RealNumber> {
+    iteration_log: Option<IterationSink<T>>,
+    interrupt: Option<InterruptFlag>,
+}
+
+impl<T> Default for IpmSolver<T>
+where
+    T: RealNumber + FromPrimitive,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> IpmSolver<T>
+where
+    T: RealNumber + FromPrimitive,
+{
     pub fn new() -> Self {
-        Self
+        Self {
+            iteration_log: None,
+            interrupt: None,
+        }
+    }
+
+    pub fn with_iteration_log(mut self, sink: IterationSink<T>) -> Self {
+        self.iteration_log = Some(sink);
+        self
+    }
+
+    pub fn with_interrupt(mut self, flag: InterruptFlag) -> Self {
+        self.interrupt = Some(flag);
+        self
     }
 
-    pub fn solve_qp<T: RealNumber>(
+    pub fn solve_qp(
         &self,
         _problem: &ProblemQP<T>,
         _options: &SolveOptions<T>,
     ) -> Result<Solution<T>> {
-        bail!("The IPM backend is not yet implemented. Enable feature `ipm` once available.");
+        bail!("The IPM backend only supports LPs so far.");
     }
 
-    pub fn solve_lp<T: RealNumber>(
+    // Mehrotra predictor-corrector on the homogeneous self-dual embedding
+    //
+    //   Aᵀy + Gᵀz + cτ = 0,  Ax = bτ,  Gx + s = hτ,  κ = -cᵀx - bᵀy - hᵀz,
+    //
+    // with s, z, τ, κ >= 0. An optimal point is read off as (x, y, z) / τ; if
+    // τ vanishes instead, κ > 0 and (y, z) or x is an infeasibility ray.
+    pub fn solve_lp(
         &self,
-        _problem: &ProblemLP<T>,
-        _options: &SolveOptions<T>,
+        problem: &ProblemLP<T>,
+        options: &SolveOptions<T>,
     ) -> Result<Solution<T>> {
-        bail!("The IPM backend is not yet implemented.");
+        let lp = ConicLp::new(problem)?;
+        let (n, meq, m) = (lp.n, lp.meq, lp.m);
+        let tol = options.eps_abs;
+        let one = T::one();
+        let step_fraction = T::from_f64(STEP_FRACTION).unwrap();
+        let primal_scale = one + norm_inf(&lp.b).max(norm_inf(&lp.h));
+        let dual_scale = one + norm_inf(&lp.c);
+
+        let mut x = vec![T::zero(); n];
+        let mut y = vec![T::zero(); meq];
+        let mut s = vec![one; m];
+        let mut z = vec![one; m];
+        let mut tau = one;
+        let mut kappa = one;
+
+        let mut kkt = ReducedKkt::new(&lp, options.linsys_backend)?;
+        let mut stats = SolveStats::new();
+        let timer = Timer::start();
+        let mut status = Status::MaxIterations;
+        let mut certificate = None;
+        for iter in 0..options.max_iterations {
+            let ax = lp.multiply_a(&x);
+            let gx = lp.multiply_g(&x);
+            let mut rx = lp.multiply_at(&y, &z);
+            for (r, &c) in rx.iter_mut().zip(&lp.c) {
+                *r += c * tau;
+            }
+            let ry: Vec<T> = lp.b.iter().zip(&ax).map(|(&b, &a)| b * tau - a).collect();
+            let rz: Vec<T> = (0..m).map(|i| s[i] + gx[i] - lp.h[i] * tau).collect();
+            let ctx = dot(&lp.c, &x);
+            let bty = dot(&lp.b, &y) + dot(&lp.h, &z);
+            let rt = kappa + ctx + bty;
+            let mu = (dot(&s, &z) + tau * kappa) / T::from_usize(m + 1).unwrap();
+
+            let primal_residual = norm_inf(&ry).max(norm_inf(&rz)) / tau / primal_scale;
+            let dual_residual = norm_inf(&rx) / tau / dual_scale;
+            let primal_objective = ctx / tau;
+            let dual_objective = -bty / tau;
+            let gap = relative_gap(primal_objective, dual_objective);
+            // The ADMM step size and relaxation have no counterpart here.
+            stats.push(IterationRecord::new(
+                iter,
+                primal_residual,
+                dual_residual,
+                gap,
+                T::zero(),
+                T::zero(),
+                primal_objective,
+                dual_objective,
+                timer.elapsed(),
+            ));
+            if let (Some(sink), Some(record)) = (&self.iteration_log, stats.history.last()) {
+                sink(record);
+            }
+
+            if primal_residual <= tol && dual_residual <= tol && gap <= tol {
+                status = Status::Optimal;
+                break;
+            }
+            if bty < T::zero() && norm_inf(&lp.multiply_at(&y, &z)) <= options.eps_prim_inf * -bty {
+                certificate = Some(lp.farkas_certificate(&y, &z));
+                status = Status::PrimalInfeasible;
+                break;
+            }
+            if ctx < T::zero() {
+                let cone: Vec<T> = gx.iter().zip(&s).map(|(&g, &s)| g + s).collect();
+                if norm_inf(&ax).max(norm_inf(&cone)) <= options.eps_dual_inf * -ctx {
+                    certificate = Some(Certificate::DualInfeasible {
+                        direction: x.iter().map(|&v| v / -ctx).collect(),
+                    });
+                    status = Status::DualInfeasible;
+                    break;
+                }
+            }
+            if let Some(limit) = options.max_time {
+                if timer.elapsed() > limit {
+                    status = Status::MaxTime;
+                    break;
+                }
+            }
+            if interrupted(self.interrupt.as_ref()) {
+                status = Status::Interrupted;
+                break;
+            }
+
+            if kkt.factor(&s, &z).is_err() {
+                status = Status::NumericalFailure;
+                break;
+            }
+            stats.factorizations += 1;
+            let point = Point {
+                s: &s,
+                z: &z,
+                tau,
+                kappa,
+            };
+            let residuals = Residuals {
+                rx: &rx,
+                ry: &ry,
+                rz: &rz,
+                rt,
+            };
+            let neg_c: Vec<T> = lp.c.iter().map(|&c| -c).collect();
+            let Ok(base) = kkt.solve(&neg_c, &lp.b, &lp.h) else {
+                status = Status::NumericalFailure;
+                break;
+            };
+
+            // Predictor: pure Newton step towards the complementarity μ = 0.
+            let rsz: Vec<T> = s.iter().zip(&z).map(|(&s, &z)| -s * z).collect();
+            let Ok(affine) = kkt.direction(&lp, &base, &point, &residuals, one, &rsz, -tau * kappa)
+            else {
+                status = Status::NumericalFailure;
+                break;
+            };
+            let alpha = affine.max_step(&point).min(one);
+            let sigma = (one - alpha).powi(3);
+
+            // Corrector: centre at σμ and cancel the second-order term.
+            let target = sigma * mu;
+            let rsz: Vec<T> = (0..m)
+                .map(|i| -s[i] * z[i] + target - affine.ds[i] * affine.dz[i])
+                .collect();
+            let rtk = -tau * kappa + target - affine.dtau * affine.dkappa;
+            let Ok(step) = kkt.direction(&lp, &base, &point, &residuals, one - sigma, &rsz, rtk)
+            else {
+                status = Status::NumericalFailure;
+                break;
+            };
+            stats.linear_solves += 3;
+            let alpha = (step_fraction * step.max_step(&point)).min(one);
+            if alpha < T::from_f64(MIN_STEP).unwrap() {
+                status = Status::NumericalFailure;
+                break;
+            }
+            for (v, d) in x.iter_mut().zip(&step.dx) {
+                *v += alpha * *d;
+            }
+            for (v, d) in y.iter_mut().zip(&step.dy) {
+                *v += alpha * *d;
+            }
+            for (v, d) in s.iter_mut().zip(&step.ds) {
+                *v += alpha * *d;
+            }
+            for (v, d) in z.iter_mut().zip(&step.dz) {
+                *v += alpha * *d;
+            }
+            tau += alpha * step.dtau;
+            kappa += alpha * step.dkappa;
+        }
+        stats.symbolic_time = kkt.solver.symbolic_time();
+        stats.numeric_time = kkt.solver.numeric_time();
+        stats.krylov = kkt.solver.krylov_stats();
+        stats.condition_estimate = kkt.solver.condition_estimate();
+        stats.solve_time = timer.elapsed();
+
+        let mut solution = Solution::with_capacity(n, meq, 0);
+        solution.iterations = stats.history.len();
+        solution.stats = stats;
+        solution.status = status;
+        match certificate {
+            Some(certificate) => {
+                // No finite optimum: the objective is +∞ without a feasible
+                // point and -∞ along an improving ray.
+                solution.objective_value = match certificate {
+                    Certificate::PrimalInfeasible { .. } => T::infinity(),
+                    Certificate::DualInfeasible { .. } => T::neg_infinity(),
+                };
+                solution.inequality_dual = lp.split_inequality_dual(&vec![T::zero(); m]);
+                solution.certificate = Some(certificate);
+            }
+            None => {
+                solution.primal = x.iter().map(|&v| v / tau).collect();
+                solution.equality_dual = y.iter().map(|&v| v / tau).collect();
+                let z: Vec<T> = z.iter().map(|&v| v / tau).collect();
+                solution.inequality_dual = lp.split_inequality_dual(&z);
+                solution.objective_value = dot(&lp.c, &solution.primal) + problem.offset;
+            }
+        }
+        Ok(solution)
+    }
+}
+
+// The LP as min cᵀx s.t. Ax = b, Gx + s = h, s >= 0. G holds the inequality
+// rows followed by x_i <= u_i and -x_i <= -l_i for every finite bound.
+struct ConicLp<T: RealNumber> {
+    n: usize,
+    meq: usize,
+    m: usize,
+    mineq: usize,
+    c: Vec<T>,
+    a: Vec<T>,
+    b: Vec<T>,
+    g: Vec<T>,
+    h: Vec<T>,
+    // Cone rows of the upper and lower bound of each variable, if finite.
+    bound_rows: Option<Vec<(Option<usize>, Option<usize>)>>,
+}
+
+impl<T> ConicLp<T>
+where
+    T: RealNumber + FromPrimitive,
+{
+    fn new(problem: &ProblemLP<T>) -> Result<Self> {
+        problem.validate()?;
+        let n = problem.nvars();
+        let (meq, a, b) = match &problem.equalities {
+            Some(eq) => (eq.matrix.nrows, to_dense(&eq.matrix), eq.rhs.clone()),
+            None => (0, Vec::new(), Vec::new()),
+        };
+        let (mineq, mut g, mut h) = match &problem.inequalities {
+            Some(ineq) => (ineq.matrix.nrows, to_dense(&ineq.matrix), ineq.rhs.clone()),
+            None => (0, Vec::new(), Vec::new()),
+        };
+        let bound_rows = problem.bounds.as_ref().map(|bounds| {
+            let mut rows = vec![(None, None); n];
+            for (i, (&lower, &upper)) in bounds.lower.iter().zip(&bounds.upper).enumerate() {
+                for (sign, value, finite) in [
+                    (T::one(), upper, upper.is_finite()),
+                    (-T::one(), -lower, lower.is_finite()),
+                ] {
+                    if !finite {
+                        continue;
+                    }
+                    let row = h.len();
+                    let mut unit = vec![T::zero(); n];
+                    unit[i] = sign;
+                    g.extend(unit);
+                    h.push(value);
+                    if sign > T::zero() {
+                        rows[i].0 = Some(row);
+                    } else {
+                        rows[i].1 = Some(row);
+                    }
+                }
+            }
+            rows
+        });
+        Ok(Self {
+            n,
+            meq,
+            m: h.len(),
+            mineq,
+            c: problem.cost.clone(),
+            a,
+            b,
+            g,
+            h,
+            bound_rows,
+        })
+    }
+
+    fn multiply_a(&self, x: &[T]) -> Vec<T> {
+        multiply(&self.a, self.meq, self.n, x)
+    }
+
+    fn multiply_g(&self, x: &[T]) -> Vec<T> {
+        multiply(&self.g, self.m, self.n, x)
+    }
+
+    // Aᵀy + Gᵀz.
+    fn multiply_at(&self, y: &[T], z: &[T]) -> Vec<T> {
+        let mut out = vec![T::zero(); self.n];
+        for (matrix, dual) in [(&self.a, y), (&self.g, z)] {
+            for (row, &weight) in matrix.chunks_exact(self.n.max(1)).zip(dual) {
+                for (o, &value) in out.iter_mut().zip(row) {
+                    *o += value * weight;
+                }
+            }
+        }
+        out
+    }
+
+    // Inequality multipliers followed, when the problem has bounds, by the net
+    // multiplier z_upper - z_lower of each variable.
+    fn split_inequality_dual(&self, z: &[T]) -> Vec<T> {
+        let mut dual = z[..self.mineq].to_vec();
+        if let Some(rows) = &self.bound_rows {
+            dual.extend(rows.iter().map(|&(upper, lower)| {
+                upper.map_or(T::zero(), |row| z[row]) - lower.map_or(T::zero(), |row| z[row])
+            }));
+        }
+        dual
+    }
+
+    // Netting the two bound multipliers of a variable keeps Aᵀy + Gᵀz intact
+    // and can only lower uᵀw⁺ - lᵀw⁻, so the ray stays a proof once it is
+    // rescaled to bring the netted form back to -1.
+    fn farkas_certificate(&self, y: &[T], z: &[T]) -> Certificate<T> {
+        let inequality_dual = self.split_inequality_dual(z);
+        let mut value = dot(&self.b, y) + dot(&self.h[..self.mineq], &z[..self.mineq]);
+        if let Some(rows) = &self.bound_rows {
+            for (&(upper, lower), &w) in rows.iter().zip(&inequality_dual[self.mineq..]) {
+                match (upper, lower) {
+                    (Some(row), _) if w > T::zero() => value += w * self.h[row],
+                    (_, Some(row)) if w < T::zero() => value -= w * self.h[row],
+                    _ => {}
+                }
+            }
+        }
+        let scale = -value;
+        Certificate::PrimalInfeasible {
+            equality_dual: y.iter().map(|&v| v / scale).collect(),
+            inequality_dual: inequality_dual.into_iter().map(|v| v / scale).collect(),
+        }
+    }
+}
+
+struct Point<'a, T> {
+    s: &'a [T],
+    z: &'a [T],
+    tau: T,
+    kappa: T,
+}
+
+struct Residuals<'a, T> {
+    rx: &'a [T],
+    ry: &'a [T],
+    rz: &'a [T],
+    rt: T,
+}
+
+struct KktSolution<T> {
+    dx: Vec<T>,
+    dy: Vec<T>,
+    dz: Vec<T>,
+}
+
+struct Direction<T> {
+    dx: Vec<T>,
+    dy: Vec<T>,
+    ds: Vec<T>,
+    dz: Vec<T>,
+    dtau: T,
+    dkappa: T,
+}
+
+impl<T: RealNumber> Direction<T> {
+    // Largest step in (0, ∞) keeping s, z, τ and κ nonnegative.
+    fn max_step(&self, point: &Point<'_, T>) -> T {
+        let pairs = point
+            .s
+            .iter()
+            .zip(&self.ds)
+            .chain(point.z.iter().zip(&self.dz))
+            .chain([(&point.tau, &self.dtau), (&point.kappa, &self.dkappa)]);
+        pairs
+            .filter(|(_, &d)| d < T::zero())
+            .map(|(&v, &d)| -v / d)
+            .fold(T::infinity(), T::min)
+    }
+}
+
+// Solves [0 Aᵀ Gᵀ; A 0 0; G 0 -W] (dx, dy, dz) = (r1, r2, r3), W = S Z⁻¹, by
+// eliminating dz = W⁻¹(G dx - r3) and factoring the quasi-definite
+// [GᵀW⁻¹G + δI, Aᵀ; A, -δI].
+struct ReducedKkt<T: RealNumber> {
+    n: usize,
+    meq: usize,
+    m: usize,
+    a: Vec<T>,
+    g: Vec<T>,
+    w_inv: Vec<T>,
+    // GᵀW⁻¹G, kept to compute refinement residuals without δ.
+    hessian: Vec<T>,
+    solver: ReducedSolver<T>,
+}
+
+enum ReducedSolver<T: RealNumber> {
+    Direct(DenseKktSolver<T>),
+    // Products with the assembled matrix; the refinement steps below then
+    // also make up for the iterative tolerance.
+    Minres(MinresKktSolver<T>),
+    // MINRES with the matrix on the GPU.
+    #[cfg(feature = "gpu")]
+    Gpu(GpuKktSolver<T>),
+}
+
+impl<T> ReducedSolver<T>
+where
+    T: RealNumber + FromPrimitive,
+{
+    fn new(backend: LinsysBackend, dim: usize) -> Result<Self> {
+        Ok(match backend {
+            LinsysBackend::Direct => {
+                let mut solver = DenseKktSolver::new().with_pivoting(DensePivoting::BunchKaufman);
+                solver.analyze_pattern(&DensePattern::new(dim))?;
+                Self::Direct(solver)
+            }
+            LinsysBackend::Minres => {
+                let mut solver = MinresKktSolver::new();
+                solver.analyze_pattern(&MinresPattern::new(dim))?;
+                Self::Minres(solver)
+            }
+            #[cfg(feature = "gpu")]
+            LinsysBackend::Gpu => {
+                let mut solver = GpuKktSolver::new(GpuDevice::shared()?);
+                solver.analyze_pattern(&DensePattern::new(dim))?;
+                Self::Gpu(solver)
+            }
+            #[cfg(not(feature = "gpu"))]
+            LinsysBackend::Gpu => {
+                bail!("the GPU backend needs cvxrs built with the gpu feature")
+            }
+            LinsysBackend::ConjugateGradient => {
+                bail!("the IPM KKT system is indefinite; use the direct or MINRES backend")
+            }
+        })
+    }
+
+    fn factor(&mut self, matrix: DenseKktMatrix<T>) -> Result<()> {
+        match self {
+            Self::Direct(solver) => solver.factor(&matrix),
+            Self::Minres(solver) => {
+                let diagonal = matrix.diagonal();
+                solver.factor(&MinresSystem::new(Arc::new(matrix), diagonal))
+            }
+            #[cfg(feature = "gpu")]
+            Self::Gpu(solver) => solver.factor(&matrix),
+        }
+    }
+
+    fn solve(&self, rhs: &mut [T]) -> Result<()> {
+        match self {
+            Self::Direct(solver) => solver.solve(rhs),
+            Self::Minres(solver) => solver.solve(rhs),
+            #[cfg(feature = "gpu")]
+            Self::Gpu(solver) => solver.solve(rhs),
+        }
+    }
+
+    fn symbolic_time(&self) -> Duration {
+        match self {
+            Self::Direct(solver) => solver.symbolic_time(),
+            Self::Minres(solver) => solver.symbolic_time(),
+            #[cfg(feature = "gpu")]
+            Self::Gpu(solver) => solver.symbolic_time(),
+        }
+    }
+
+    fn numeric_time(&self) -> Duration {
+        match self {
+            Self::Direct(solver) => solver.numeric_time(),
+            Self::Minres(solver) => solver.numeric_time(),
+            #[cfg(feature = "gpu")]
+            Self::Gpu(solver) => solver.numeric_time(),
+        }
+    }
+
+    fn krylov_stats(&self) -> Option<KrylovStats> {
+        match self {
+            Self::Direct(solver) => solver.krylov_stats(),
+            Self::Minres(solver) => solver.krylov_stats(),
+            #[cfg(feature = "gpu")]
+            Self::Gpu(solver) => solver.krylov_stats(),
+        }
     }
+
+    fn condition_estimate(&self) -> Option<T> {
+        match self {
+            Self::Direct(solver) => solver.condition_estimate(),
+            Self::Minres(solver) => solver.condition_estimate(),
+            #[cfg(feature = "gpu")]
+            Self::Gpu(solver) => solver.condition_estimate(),
+        }
+    }
+}
+
+impl<T> ReducedKkt<T>
+where
+    T: RealNumber + FromPrimitive,
+{
+    fn new(lp: &ConicLp<T>, backend: LinsysBackend) -> Result<Self> {
+        let solver = ReducedSolver::new(backend, lp.n + lp.meq)?;
+        Ok(Self {
+            n: lp.n,
+            meq: lp.meq,
+            m: lp.m,
+            a: lp.a.clone(),
+            g: lp.g.clone(),
+            w_inv: vec![T::one(); lp.m],
+            hessian: vec![T::zero(); lp.n * lp.n],
+            solver,
+        })
+    }
+
+    fn factor(&mut self, s: &[T], z: &[T]) -> Result<()> {
+        let n = self.n;
+        for ((w, &s), &z) in self.w_inv.iter_mut().zip(s).zip(z) {
+            *w = z / s;
+        }
+        self.hessian.iter_mut().for_each(|v| *v = T::zero());
+        for (row, &w) in self.g.chunks_exact(n.max(1)).zip(&self.w_inv) {
+            for i in (0..n).filter(|&i| row[i] != T::zero()) {
+                for j in (0..n).filter(|&j| row[j] != T::zero()) {
+                    self.hessian[i * n + j] += w * row[i] * row[j];
+                }
+            }
+        }
+        let delta = T::from_f64(KKT_REGULARIZATION).unwrap();
+        let dim = n + self.meq;
+        let mut data = vec![T::zero(); dim * dim];
+        for i in 0..n {
+            data[i * dim..i * dim + n].copy_from_slice(&self.hessian[i * n..(i + 1) * n]);
+            data[i * dim + i] += delta;
+        }
+        for r in 0..self.meq {
+            for j in 0..n {
+                let value = self.a[r * n + j];
+                data[(n + r) * dim + j] = value;
+                data[j * dim + n + r] = value;
+            }
+            data[(n + r) * dim + n + r] = -delta;
+        }
+        self.solver.factor(DenseKktMatrix::new(dim, data))
+    }
+
+    fn solve(&self, r1: &[T], r2: &[T], r3: &[T]) -> Result<KktSolution<T>> {
+        let n = self.n;
+        let mut rhs = r1.to_vec();
+        for (row, (&w, &r)) in self.g.chunks_exact(n.max(1)).zip(self.w_inv.iter().zip(r3)) {
+            for (value, &g) in rhs.iter_mut().zip(row) {
+                *value += g * w * r;
+            }
+        }
+        rhs.extend_from_slice(r2);
+
+        let mut sol = rhs.clone();
+        self.solver.solve(&mut sol)?;
+        for _ in 0..REFINEMENT_STEPS {
+            let mut residual = rhs.clone();
+            self.subtract_product(&sol, &mut residual);
+            self.solver.solve(&mut residual)?;
+            for (v, d) in sol.iter_mut().zip(&residual) {
+                *v += *d;
+            }
+        }
+
+        let dy = sol.split_off(n);
+        let dx = sol;
+        let gdx = multiply(&self.g, self.m, n, &dx);
+        let dz = (0..self.m)
+            .map(|i| self.w_inv[i] * (gdx[i] - r3[i]))
+            .collect();
+        Ok(KktSolution { dx, dy, dz })
+    }
+
+    // out -= [GᵀW⁻¹G, Aᵀ; A, 0] v.
+    fn subtract_product(&self, v: &[T], out: &mut [T]) {
+        let n = self.n;
+        let (vx, vy) = v.split_at(n);
+        for (o, row) in out[..n].iter_mut().zip(self.hessian.chunks_exact(n.max(1))) {
+            *o -= dot(row, vx);
+        }
+        for r in 0..self.meq {
+            let row = &self.a[r * n..(r + 1) * n];
+            out[n + r] -= dot(row, vx);
+            for (o, &a) in out[..n].iter_mut().zip(row) {
+                *o -= a * vy[r];
+            }
+        }
+    }
+
+    // Newton direction for the embedding with the linear residuals scaled by
+    // `eta` and the complementarity targets S dz + Z ds = rsz and
+    // κ dτ + τ dκ = rtk. `base` solves the system with right-hand side
+    // (-c, b, h) and carries the dτ dependence.
+    #[allow(clippy::too_many_arguments)]
+    fn direction(
+        &self,
+        lp: &ConicLp<T>,
+        base: &KktSolution<T>,
+        point: &Point<'_, T>,
+        residuals: &Residuals<'_, T>,
+        eta: T,
+        rsz: &[T],
+        rtk: T,
+    ) -> Result<Direction<T>> {
+        let r1: Vec<T> = residuals.rx.iter().map(|&r| -eta * r).collect();
+        let r2: Vec<T> = residuals.ry.iter().map(|&r| eta * r).collect();
+        let r3: Vec<T> = (0..self.m)
+            .map(|i| -eta * residuals.rz[i] - rsz[i] / point.z[i])
+            .collect();
+        let part = self.solve(&r1, &r2, &r3)?;
+        let linear =
+            |sol: &KktSolution<T>| dot(&lp.c, &sol.dx) + dot(&lp.b, &sol.dy) + dot(&lp.h, &sol.dz);
+        let dtau = (-eta * residuals.rt - rtk / point.tau - linear(&part))
+            / (linear(base) - point.kappa / point.tau);
+        let combine =
+            |a: &[T], b: &[T]| -> Vec<T> { a.iter().zip(b).map(|(&a, &b)| a + dtau * b).collect() };
+        let dx = combine(&part.dx, &base.dx);
+        let dy = combine(&part.dy, &base.dy);
+        let dz = combine(&part.dz, &base.dz);
+        let ds = (0..self.m)
+            .map(|i| (rsz[i] - point.s[i] * dz[i]) / point.z[i])
+            .collect();
+        let dkappa = (rtk - point.kappa * dtau) / point.tau;
+        Ok(Direction {
+            dx,
+            dy,
+            ds,
+            dz,
+            dtau,
+            dkappa,
+        })
+    }
+}
+
+fn to_dense<T: RealNumber>(matrix: &CscMatrix<T>) -> Vec<T> {
+    let mut dense = vec![T::zero(); matrix.nrows * matrix.ncols];
+    for col in 0..matrix.ncols {
+        for idx in matrix.indptr[col]..matrix.indptr[col + 1] {
+            dense[matrix.indices[idx] * matrix.ncols + col] += matrix.data[idx];
+        }
+    }
+    dense
+}
+
+fn multiply<T: RealNumber>(matrix: &[T], rows: usize, cols: usize, x: &[T]) -> Vec<T> {
+    (0..rows)
+        .map(|row| dot(&matrix[row * cols..(row + 1) * cols], x))
+        .collect()
 }
@@ -1,7 +1,24 @@
 #![forbid(unsafe_code)]
 
+pub mod active_set;
 pub mod admm;
+pub mod bnb;
+pub mod consensus;
+pub mod fista;
 pub mod ipm;
+pub mod pdhg;
+mod progress;
+pub mod sensitivity;
+pub mod simplex;
+pub mod svm;
 
-pub use admm::{AdmmResult, AdmmSolver};
+pub use active_set::ActiveSetSolver;
+pub use admm::{AdmmResult, AdmmSetup, AdmmSolver};
+pub use bnb::{BranchAndBound, Incumbent, IncumbentSink};
+pub use consensus::{ConsensusSolution, ConsensusSolver, Scenario};
+pub use fista::FistaSolver;
 pub use ipm::IpmSolver;
+pub use pdhg::{OperatorLp, PdhgSolver};
+pub use sensitivity::{DataGradient, Sensitivity};
+pub use simplex::SimplexSolver;
+pub use svm::{SvmModel, SvmSolver};
@@ -0,0 +1,648 @@
+use anyhow::{bail, Result};
+use cvxrs_core::math::{dot, norm2, norm_inf, relative_gap, RealNumber, Timer};
+use cvxrs_core::options::SolveOptions;
+use cvxrs_core::problem::{Bounds, CscMatrix, ProblemLP};
+use cvxrs_core::solution::{Certificate, Solution, Status};
+use cvxrs_core::stats::{interrupted, InterruptFlag, IterationRecord, IterationSink, SolveStats};
+use cvxrs_core::traits::LinearOperator;
+use num_traits::FromPrimitive;
+use rand::{rngs::SmallRng, Rng, SeedableRng};
+
+// Power iterations on KᵀK spent estimating ‖K‖ for the step sizes.
+const POWER_ITERATIONS: usize = 64;
+
+// Keeps τσ‖K‖² below the stability limit of one.
+const STEP_SAFETY: f64 = 0.9;
+
+// Restarts are considered this often, and taken when the KKT error of the
+// candidate fell by the sufficient factor, fell by the necessary one but
+// stopped improving, or the restart period grew past the artificial share
+// of all iterations, as in PDLP.
+const RESTART_CHECK_INTERVAL: usize = 64;
+const RESTART_SUFFICIENT: f64 = 0.2;
+const RESTART_NECESSARY: f64 = 0.8;
+const RESTART_ARTIFICIAL: f64 = 0.36;
+
+// Weight of the new estimate when the primal weight is updated at a restart.
+const PRIMAL_WEIGHT_SMOOTHING: f64 = 0.5;
+
+// Movements below this leave the primal weight alone.
+const PRIMAL_WEIGHT_FLOOR: f64 = 1e-10;
+
+// Rays shorter than this carry no direction to test for infeasibility.
+const RAY_FLOOR: f64 = 1e-30;
+
+pub struct PdhgSolver<T: RealNumber> {
+    iteration_log: Option<IterationSink<T>>,
+    interrupt: Option<InterruptFlag>,
+}
+
+// An LP whose constraint matrix is only available through products with K
+// and Kᵀ: min cᵀx s.t. the first `equalities` rows of Kx equal `rhs`, the
+// others are at most `rhs`, and x stays within `bounds`.
+pub struct OperatorLp<'a, T: RealNumber> {
+    pub operator: &'a dyn LinearOperator<T>,
+    pub cost: &'a [T],
+    pub rhs: &'a [T],
+    pub equalities: usize,
+    pub bounds: Option<&'a Bounds<T>>,
+}
+
+impl<T> Default for PdhgSolver<T>
+where
+    T: RealNumber + FromPrimitive,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> PdhgSolver<T>
+where
+    T: RealNumber + FromPrimitive,
+{
+    pub fn new() -> Self {
+        Self {
+            iteration_log: None,
+            interrupt: None,
+        }
+    }
+
+    pub fn with_iteration_log(mut self, sink: IterationSink<T>) -> Self {
+        self.iteration_log = Some(sink);
+        self
+    }
+
+    pub fn with_interrupt(mut self, flag: InterruptFlag) -> Self {
+        self.interrupt = Some(flag);
+        self
+    }
+
+    pub fn solve_lp(
+        &self,
+        problem: &ProblemLP<T>,
+        options: &SolveOptions<T>,
+    ) -> Result<Solution<T>> {
+        problem.validate()?;
+        let operator = StackedRows {
+            equalities: problem.equalities.as_ref().map(|eq| &eq.matrix),
+            inequalities: problem.inequalities.as_ref().map(|ineq| &ineq.matrix),
+            ncols: problem.nvars(),
+        };
+        let rhs: Vec<T> = problem
+            .equalities
+            .iter()
+            .flat_map(|eq| eq.rhs.iter())
+            .chain(problem.inequalities.iter().flat_map(|ineq| ineq.rhs.iter()))
+            .copied()
+            .collect();
+        let mut solution = self.solve_operator(
+            &OperatorLp {
+                operator: &operator,
+                cost: &problem.cost,
+                rhs: &rhs,
+                equalities: problem.equalities.as_ref().map_or(0, |eq| eq.matrix.nrows),
+                bounds: problem.bounds.as_ref(),
+            },
+            options,
+        )?;
+        solution.objective_value += problem.offset;
+        Ok(solution)
+    }
+
+    // Restarted primal-dual hybrid gradient on the saddle point of
+    //
+    //   cᵀx + λᵀ(Kx - q),  x in bounds,  λ free on equality rows, λ >= 0 else,
+    //
+    // with one product by K and one by Kᵀ per iteration. The duals follow
+    // c + Kᵀλ + w = 0, so λ splits into y and z as in the other backends.
+    pub fn solve_operator(
+        &self,
+        lp: &OperatorLp<'_, T>,
+        options: &SolveOptions<T>,
+    ) -> Result<Solution<T>> {
+        let (m, n) = lp.operator.dim();
+        if lp.cost.len() != n || lp.rhs.len() != m || lp.equalities > m {
+            bail!(
+                "operator is {}x{} but the LP has {} costs, {} right-hand sides and {} equalities",
+                m,
+                n,
+                lp.cost.len(),
+                lp.rhs.len(),
+                lp.equalities
+            );
+        }
+        let (lower, upper) = match lp.bounds {
+            Some(bounds) => {
+                if bounds.lower.len() != n || bounds.upper.len() != n {
+                    bail!("bounds must have one entry per variable");
+                }
+                (bounds.lower.clone(), bounds.upper.clone())
+            }
+            None => (vec![T::neg_infinity(); n], vec![T::infinity(); n]),
+        };
+        let data = Data {
+            lp,
+            lower,
+            upper,
+            primal_scale: norm_inf(lp.rhs),
+            dual_scale: norm_inf(lp.cost),
+        };
+
+        let timer = Timer::start();
+        let mut stats = SolveStats::new();
+        let step = T::from_f64(STEP_SAFETY).unwrap() / data.operator_norm(options.seed);
+        let (cost_norm, rhs_norm) = (norm2(lp.cost), norm2(lp.rhs));
+        let mut weight = if cost_norm > T::zero() && rhs_norm > T::zero() {
+            cost_norm / rhs_norm
+        } else {
+            T::one()
+        };
+
+        let mut current = Iterate::new(&data, &vec![T::zero(); n], &vec![T::zero(); m]);
+        let mut average = Average::new(m, n);
+        let mut anchor = current.clone();
+        let mut checked = current.clone();
+        let mut restart_error = data.evaluate(&current).error;
+        let mut candidate_error = T::infinity();
+        let mut since_restart = 0;
+        let mut status = Status::MaxIterations;
+        let mut certificate = None;
+        for iter in 0..options.max_iterations {
+            if options
+                .max_time
+                .is_some_and(|limit| timer.elapsed() > limit)
+            {
+                status = Status::MaxTime;
+                break;
+            }
+            if interrupted(self.interrupt.as_ref()) {
+                status = Status::Interrupted;
+                break;
+            }
+            current = data.step(&current, step / weight, step * weight);
+            average.add(&current);
+            since_restart += 1;
+
+            let kkt = data.evaluate(&current);
+            stats.push(IterationRecord::new(
+                iter,
+                kkt.primal_residual / (T::one() + data.primal_scale),
+                kkt.dual_residual / (T::one() + data.dual_scale),
+                relative_gap(kkt.primal_objective, kkt.dual_objective),
+                weight,
+                T::one(),
+                kkt.primal_objective,
+                kkt.dual_objective,
+                timer.elapsed(),
+            ));
+            if let (Some(sink), Some(record)) = (&self.iteration_log, stats.history.last()) {
+                sink(record);
+            }
+            if kkt.converged(&data, options) {
+                status = Status::Optimal;
+                break;
+            }
+
+            if since_restart % RESTART_CHECK_INTERVAL != 0 {
+                continue;
+            }
+            if let Some(found) = data.infeasibility(&current, &checked, options) {
+                status = match found {
+                    Certificate::PrimalInfeasible { .. } => Status::PrimalInfeasible,
+                    Certificate::DualInfeasible { .. } => Status::DualInfeasible,
+                };
+                certificate = Some(found);
+                break;
+            }
+            checked = current.clone();
+            let mean = average.iterate();
+            let (mean_error, current_error) = (data.evaluate(&mean).error, kkt.error);
+            let (candidate, error) = if mean_error < current_error {
+                (mean, mean_error)
+            } else {
+                (current.clone(), current_error)
+            };
+            let restart = error <= T::from_f64(RESTART_SUFFICIENT).unwrap() * restart_error
+                || (error <= T::from_f64(RESTART_NECESSARY).unwrap() * restart_error
+                    && error > candidate_error)
+                || T::from_usize(since_restart).unwrap()
+                    >= T::from_f64(RESTART_ARTIFICIAL).unwrap() * T::from_usize(iter + 1).unwrap();
+            candidate_error = error;
+            if !restart {
+                continue;
+            }
+            weight = updated_weight(weight, &anchor, &candidate);
+            current = candidate;
+            anchor = current.clone();
+            checked = current.clone();
+            average = Average::new(m, n);
+            restart_error = error;
+            candidate_error = T::infinity();
+            since_restart = 0;
+        }
+
+        let Iterate { x, lambda, kty, .. } = current;
+        let mut inequality_dual = lambda[lp.equalities..].to_vec();
+        if lp.bounds.is_some() {
+            inequality_dual.extend(lp.cost.iter().zip(&kty).map(|(&c, &k)| -(c + k)));
+        }
+        stats.solve_time = timer.elapsed();
+        // No finite optimum: the objective is +∞ without a feasible point and
+        // -∞ along an improving ray.
+        let objective_value = match &certificate {
+            Some(Certificate::PrimalInfeasible { .. }) => T::infinity(),
+            Some(Certificate::DualInfeasible { .. }) => T::neg_infinity(),
+            None => dot(lp.cost, &x),
+        };
+        Ok(Solution {
+            objective_value,
+            primal: x,
+            equality_dual: lambda[..lp.equalities].to_vec(),
+            inequality_dual,
+            status,
+            iterations: stats.history.len(),
+            stats,
+            certificate,
+            basis: None,
+        })
+    }
+}
+
+struct Data<'a, T: RealNumber> {
+    lp: &'a OperatorLp<'a, T>,
+    lower: Vec<T>,
+    upper: Vec<T>,
+    primal_scale: T,
+    dual_scale: T,
+}
+
+// A primal-dual pair with its products Kx and Kᵀλ, which the next step and
+// the residuals both need.
+#[derive(Clone)]
+struct Iterate<T> {
+    x: Vec<T>,
+    lambda: Vec<T>,
+    kx: Vec<T>,
+    kty: Vec<T>,
+}
+
+impl<T> Iterate<T>
+where
+    T: RealNumber,
+{
+    fn new(data: &Data<'_, T>, x: &[T], lambda: &[T]) -> Self {
+        let mut x = x.to_vec();
+        for ((v, &l), &u) in x.iter_mut().zip(&data.lower).zip(&data.upper) {
+            *v = v.max(l).min(u);
+        }
+        let (m, n) = data.lp.operator.dim();
+        let mut kx = vec![T::zero(); m];
+        let mut kty = vec![T::zero(); n];
+        data.lp.operator.apply(&x, &mut kx);
+        data.lp.operator.apply_transpose(lambda, &mut kty);
+        Self {
+            x,
+            lambda: lambda.to_vec(),
+            kx,
+            kty,
+        }
+    }
+}
+
+// Running sums of the iterates since the last restart. The products are
+// linear, so they are averaged alongside instead of being recomputed.
+struct Average<T> {
+    sum: Iterate<T>,
+    count: usize,
+}
+
+impl<T> Average<T>
+where
+    T: RealNumber + FromPrimitive,
+{
+    fn new(m: usize, n: usize) -> Self {
+        Self {
+            sum: Iterate {
+                x: vec![T::zero(); n],
+                lambda: vec![T::zero(); m],
+                kx: vec![T::zero(); m],
+                kty: vec![T::zero(); n],
+            },
+            count: 0,
+        }
+    }
+
+    fn add(&mut self, iterate: &Iterate<T>) {
+        let pairs = [
+            (&mut self.sum.x, &iterate.x),
+            (&mut self.sum.lambda, &iterate.lambda),
+            (&mut self.sum.kx, &iterate.kx),
+            (&mut self.sum.kty, &iterate.kty),
+        ];
+        for (sum, value) in pairs {
+            for (s, &v) in sum.iter_mut().zip(value) {
+                *s += v;
+            }
+        }
+        self.count += 1;
+    }
+
+    fn iterate(&self) -> Iterate<T> {
+        let scale = T::one() / T::from_usize(self.count.max(1)).unwrap();
+        let mean = |sum: &[T]| sum.iter().map(|&s| s * scale).collect();
+        Iterate {
+            x: mean(&self.sum.x),
+            lambda: mean(&self.sum.lambda),
+            kx: mean(&self.sum.kx),
+            kty: mean(&self.sum.kty),
+        }
+    }
+}
+
+// Absolute residuals and objectives of an iterate. The dual objective
+// takes the bound multipliers that make each reduced cost vanish, and the
+// dual residual counts the reduced costs no finite bound can absorb.
+struct Kkt<T> {
+    primal_residual: T,
+    dual_residual: T,
+    primal_objective: T,
+    dual_objective: T,
+    error: T,
+}
+
+impl<T> Kkt<T>
+where
+    T: RealNumber,
+{
+    fn converged(&self, data: &Data<'_, T>, options: &SolveOptions<T>) -> bool {
+        let (abs, rel) = (options.eps_abs, options.eps_rel);
+        self.primal_residual <= abs + rel * data.primal_scale
+            && self.dual_residual <= abs + rel * data.dual_scale
+            && relative_gap(self.primal_objective, self.dual_objective) <= abs
+    }
+}
+
+impl<T> Data<'_, T>
+where
+    T: RealNumber + FromPrimitive,
+{
+    fn operator_norm(&self, seed: u64) -> T {
+        let (m, n) = self.lp.operator.dim();
+        let mut rng = SmallRng::seed_from_u64(seed);
+        let mut v: Vec<T> = (0..n)
+            .map(|_| T::from_f64(rng.gen::<f64>() + 0.5).unwrap())
+            .collect();
+        let mut kv = vec![T::zero(); m];
+        let mut estimate = T::zero();
+        for _ in 0..POWER_ITERATIONS {
+            let length = norm2(&v);
+            if length == T::zero() {
+                break;
+            }
+            for value in v.iter_mut() {
+                *value = *value / length;
+            }
+            self.lp.operator.apply(&v, &mut kv);
+            self.lp.operator.apply_transpose(&kv, &mut v);
+            estimate = norm2(&v);
+        }
+        if estimate > T::zero() {
+            estimate.sqrt()
+        } else {
+            T::one()
+        }
+    }
+
+    fn step(&self, current: &Iterate<T>, tau: T, sigma: T) -> Iterate<T> {
+        let mut x = current.x.clone();
+        for (j, value) in x.iter_mut().enumerate() {
+            let moved = *value - tau * (self.lp.cost[j] + current.kty[j]);
+            *value = moved.max(self.lower[j]).min(self.upper[j]);
+        }
+        let (m, n) = self.lp.operator.dim();
+        let mut kx = vec![T::zero(); m];
+        self.lp.operator.apply(&x, &mut kx);
+        let two = T::one() + T::one();
+        let mut lambda = current.lambda.clone();
+        for (i, value) in lambda.iter_mut().enumerate() {
+            let extrapolated = two * kx[i] - current.kx[i];
+            *value += sigma * (extrapolated - self.lp.rhs[i]);
+            if i >= self.lp.equalities {
+                *value = value.max(T::zero());
+            }
+        }
+        let mut kty = vec![T::zero(); n];
+        self.lp.operator.apply_transpose(&lambda, &mut kty);
+        Iterate { x, lambda, kx, kty }
+    }
+
+    // On an infeasible or unbounded LP the iterates diverge and their
+    // difference over a check interval converges to a certificate, as in
+    // PDLP. The difference is tested first, then the current iterate
+    // itself as a ray.
+    fn infeasibility(
+        &self,
+        current: &Iterate<T>,
+        previous: &Iterate<T>,
+        options: &SolveOptions<T>,
+    ) -> Option<Certificate<T>> {
+        let minus = |a: &[T], b: &[T]| a.iter().zip(b).map(|(&u, &v)| u - v).collect::<Vec<T>>();
+        let lambda = minus(&current.lambda, &previous.lambda);
+        let (x, kx) = (
+            minus(&current.x, &previous.x),
+            minus(&current.kx, &previous.kx),
+        );
+        self.primal_infeasibility(&lambda, options)
+            .or_else(|| self.dual_infeasibility(&x, &kx, options))
+            .or_else(|| self.primal_infeasibility(&current.lambda, options))
+            .or_else(|| self.dual_infeasibility(&current.x, &current.kx, options))
+    }
+
+    // A ray λ, with its inequality part projected onto λ >= 0, proves
+    // infeasibility when the bound multipliers w = -Kᵀλ only lean on finite
+    // bounds and qᵀλ + uᵀw⁺ - lᵀw⁻ < 0, both relative to ‖λ‖.
+    fn primal_infeasibility(&self, ray: &[T], options: &SolveOptions<T>) -> Option<Certificate<T>> {
+        let mut lambda = ray.to_vec();
+        for value in &mut lambda[self.lp.equalities..] {
+            *value = value.max(T::zero());
+        }
+        let norm = norm_inf(&lambda);
+        if norm <= T::from_f64(RAY_FLOOR).unwrap() {
+            return None;
+        }
+        let mut w = vec![T::zero(); self.lp.cost.len()];
+        self.lp.operator.apply_transpose(&lambda, &mut w);
+        let mut support = dot(self.lp.rhs, &lambda);
+        let mut residual = T::zero();
+        for ((value, &lower), &upper) in w.iter_mut().zip(&self.lower).zip(&self.upper) {
+            *value = -*value;
+            let bound = if *value > T::zero() { upper } else { lower };
+            if bound.is_finite() {
+                support += *value * bound;
+            } else {
+                residual = residual.max(value.abs());
+            }
+        }
+        let eps = options.eps_prim_inf * norm;
+        if support >= -eps || residual >= eps {
+            return None;
+        }
+        let scale = -support;
+        let mut inequality_dual: Vec<T> = lambda.iter().map(|&v| v / scale).collect();
+        let equality_dual = inequality_dual.drain(..self.lp.equalities).collect();
+        if self.lp.bounds.is_some() {
+            inequality_dual.extend(w.iter().map(|&v| v / scale));
+        }
+        Some(Certificate::PrimalInfeasible {
+            equality_dual,
+            inequality_dual,
+        })
+    }
+
+    // A ray d with Kd = 0 on equality rows, Kd <= 0 on the others and d in
+    // the recession cone of the bounds proves unboundedness when cᵀd < 0,
+    // all relative to ‖d‖.
+    fn dual_infeasibility(
+        &self,
+        x: &[T],
+        kx: &[T],
+        options: &SolveOptions<T>,
+    ) -> Option<Certificate<T>> {
+        let norm = norm_inf(x);
+        let slope = dot(self.lp.cost, x);
+        let eps = options.eps_dual_inf * norm;
+        if norm <= T::from_f64(RAY_FLOOR).unwrap() || slope >= -eps {
+            return None;
+        }
+        let mut residual = T::zero();
+        for (i, &value) in kx.iter().enumerate() {
+            residual = residual.max(if i < self.lp.equalities {
+                value.abs()
+            } else {
+                value
+            });
+        }
+        for ((&value, &lower), &upper) in x.iter().zip(&self.lower).zip(&self.upper) {
+            if upper.is_finite() {
+                residual = residual.max(value);
+            }
+            if lower.is_finite() {
+                residual = residual.max(-value);
+            }
+        }
+        if residual >= eps {
+            return None;
+        }
+        Some(Certificate::DualInfeasible {
+            direction: x.iter().map(|&v| v / -slope).collect(),
+        })
+    }
+
+    fn evaluate(&self, iterate: &Iterate<T>) -> Kkt<T> {
+        let mut primal = Vec::with_capacity(iterate.kx.len());
+        for (i, (&kx, &q)) in iterate.kx.iter().zip(self.lp.rhs).enumerate() {
+            let residual = kx - q;
+            primal.push(if i < self.lp.equalities {
+                residual
+            } else {
+                residual.max(T::zero())
+            });
+        }
+        let mut dual = Vec::with_capacity(iterate.x.len());
+        let mut dual_objective = -dot(self.lp.rhs, &iterate.lambda);
+        for (j, (&c, &k)) in self.lp.cost.iter().zip(&iterate.kty).enumerate() {
+            let reduced = c + k;
+            let bound = if reduced > T::zero() {
+                self.lower[j]
+            } else {
+                self.upper[j]
+            };
+            if bound.is_finite() {
+                dual_objective += reduced * bound;
+                dual.push(T::zero());
+            } else {
+                dual.push(reduced);
+            }
+        }
+        let primal_objective = dot(self.lp.cost, &iterate.x);
+        let (primal_norm, dual_norm) = (norm2(&primal), norm2(&dual));
+        let gap = primal_objective - dual_objective;
+        Kkt {
+            primal_residual: norm_inf(&primal),
+            dual_residual: norm_inf(&dual),
+            primal_objective,
+            dual_objective,
+            error: (primal_norm * primal_norm + dual_norm * dual_norm + gap * gap).sqrt(),
+        }
+    }
+}
+
+// Balances the primal and dual step sizes by how far each side moved since
+// the previous restart, smoothed in log space.
+fn updated_weight<T: RealNumber + FromPrimitive>(
+    weight: T,
+    anchor: &Iterate<T>,
+    candidate: &Iterate<T>,
+) -> T {
+    let distance = |a: &[T], b: &[T]| {
+        a.iter()
+            .zip(b)
+            .fold(T::zero(), |acc, (&u, &v)| acc + (u - v) * (u - v))
+            .sqrt()
+    };
+    let primal = distance(&anchor.x, &candidate.x);
+    let dual = distance(&anchor.lambda, &candidate.lambda);
+    let floor = T::from_f64(PRIMAL_WEIGHT_FLOOR).unwrap();
+    if primal <= floor || dual <= floor {
+        return weight;
+    }
+    let smoothing = T::from_f64(PRIMAL_WEIGHT_SMOOTHING).unwrap();
+    (smoothing * (dual / primal).ln() + (T::one() - smoothing) * weight.ln()).exp()
+}
+
+// Equality rows stacked over inequality rows, as the operator of an LP
+// given by sparse matrices.
+struct StackedRows<'a, T: RealNumber> {
+    equalities: Option<&'a CscMatrix<T>>,
+    inequalities: Option<&'a CscMatrix<T>>,
+    ncols: usize,
+}
+
+impl<T> LinearOperator<T> for StackedRows<'_, T>
+where
+    T: RealNumber,
+{
+    fn dim(&self) -> (usize, usize) {
+        let rows = |matrix: Option<&CscMatrix<T>>| matrix.map_or(0, |m| m.nrows);
+        (rows(self.equalities) + rows(self.inequalities), self.ncols)
+    }
+
+    fn apply(&self, x: &[T], y: &mut [T]) {
+        let split = self.equalities.map_or(0, |m| m.nrows);
+        let (top, bottom) = y.split_at_mut(split);
+        if let Some(matrix) = self.equalities {
+            matrix.apply(x, top);
+        }
+        if let Some(matrix) = self.inequalities {
+            matrix.apply(x, bottom);
+        }
+    }
+
+    fn apply_transpose(&self, x: &[T], y: &mut [T]) {
+        let split = self.equalities.map_or(0, |m| m.nrows);
+        y.fill(T::zero());
+        let mut part = vec![T::zero(); y.len()];
+        for (matrix, rows) in [
+            (self.equalities, &x[..split]),
+            (self.inequalities, &x[split..]),
+        ] {
+            if let Some(matrix) = matrix {
+                matrix.apply_transpose(rows, &mut part);
+                for (target, &value) in y.iter_mut().zip(&part) {
+                    *target += value;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,63 @@
+use cvxrs_core::math::{RealNumber, Timer};
+use cvxrs_core::options::SolveOptions;
+use cvxrs_core::solution::Status;
+use cvxrs_core::stats::{interrupted, InterruptFlag, IterationRecord, IterationSink, SolveStats};
+
+// Iteration records and limits of the pivoting solvers, shared by all their
+// phases.
+pub(crate) struct Progress<'a, T: RealNumber> {
+    pub(crate) stats: SolveStats<T>,
+    pub(crate) timer: Timer,
+    options: &'a SolveOptions<T>,
+    log: Option<&'a IterationSink<T>>,
+    interrupt: Option<&'a InterruptFlag>,
+}
+
+impl<'a, T> Progress<'a, T>
+where
+    T: RealNumber,
+{
+    pub(crate) fn new(
+        options: &'a SolveOptions<T>,
+        log: Option<&'a IterationSink<T>>,
+        interrupt: Option<&'a InterruptFlag>,
+    ) -> Self {
+        Self {
+            stats: SolveStats::new(),
+            timer: Timer::start(),
+            options,
+            log,
+            interrupt,
+        }
+    }
+
+    pub(crate) fn stopped(&self) -> Option<Status> {
+        if self.stats.history.len() >= self.options.max_iterations {
+            return Some(Status::MaxIterations);
+        }
+        match self.options.max_time {
+            Some(limit) if self.timer.elapsed() > limit => Some(Status::MaxTime),
+            _ if interrupted(self.interrupt) => Some(Status::Interrupted),
+            _ => None,
+        }
+    }
+
+    // The ADMM step size and relaxation have no counterpart here, and the
+    // iterates carry no dual estimate until the last one.
+    pub(crate) fn record(&mut self, primal_residual: T, dual_residual: T, objective: T) {
+        self.stats.push(IterationRecord::new(
+            self.stats.history.len(),
+            primal_residual,
+            dual_residual,
+            T::zero(),
+            T::zero(),
+            T::zero(),
+            objective,
+            objective,
+            self.timer.elapsed(),
+        ));
+        if let (Some(sink), Some(record)) = (self.log, self.stats.history.last()) {
+            sink(record);
+        }
+    }
+}
@@ -0,0 +1,314 @@
+use anyhow::{bail, Result};
+use cvxrs_core::math::{norm_inf, RealNumber};
+use cvxrs_core::problem::{Bounds, CscMatrix, ProblemLP, ProblemQP};
+use cvxrs_core::solution::{Solution, Status};
+use cvxrs_core::traits::KktSolver;
+use cvxrs_linsys::dense::{DenseKktMatrix, DenseKktSolver, DensePattern, DensePivoting};
+use num_traits::FromPrimitive;
+
+// Regularization of the factored KKT matrix, relative to its largest entry;
+// iterative refinement against the exact matrix removes its effect.
+const REGULARIZATION: f64 = 1e-8;
+const REFINEMENT_STEPS: usize = 10;
+
+// A row of the reduced KKT system and the data it came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ActiveRow {
+    Equality(usize),
+    Inequality(usize),
+    Lower(usize),
+    Upper(usize),
+}
+
+// One value per entry of the problem data x* depends on: q (or the LP cost),
+// the equality and inequality right-hand sides and the variable bounds.
+// `lower` and `upper` are empty when the problem has no bounds.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DataGradient<T> {
+    pub linear: Vec<T>,
+    pub equality_rhs: Vec<T>,
+    pub inequality_rhs: Vec<T>,
+    pub lower: Vec<T>,
+    pub upper: Vec<T>,
+}
+
+impl<T: RealNumber> DataGradient<T> {
+    fn zeros(n: usize, meq: usize, mineq: usize, bounded: bool) -> Self {
+        let nb = if bounded { n } else { 0 };
+        Self {
+            linear: vec![T::zero(); n],
+            equality_rhs: vec![T::zero(); meq],
+            inequality_rhs: vec![T::zero(); mineq],
+            lower: vec![T::zero(); nb],
+            upper: vec![T::zero(); nb],
+        }
+    }
+}
+
+// Derivatives of an optimal x* with respect to the problem data, from the
+// implicit function theorem on the KKT conditions of the constraints active
+// at x*:
+//
+//   [P  Aᵀ] [dx]   [-dq]
+//   [A  0 ] [dλ] = [ db]
+//
+// where A stacks the equalities, the inequality rows within `tolerance` of
+// their right-hand side and the bounds x* sits on. Constraints that are not
+// active have no first-order effect on x*. At a degenerate x*, where an
+// active constraint has a zero multiplier, this is the derivative with that
+// constraint kept active.
+pub struct Sensitivity<T: RealNumber> {
+    n: usize,
+    meq: usize,
+    mineq: usize,
+    bounded: bool,
+    rows: Vec<ActiveRow>,
+    kkt: DenseKktMatrix<T>,
+    factor: DenseKktSolver<T>,
+}
+
+impl<T> Sensitivity<T>
+where
+    T: RealNumber + FromPrimitive,
+{
+    pub fn qp(problem: &ProblemQP<T>, solution: &Solution<T>, tolerance: T) -> Result<Self> {
+        problem.validate()?;
+        problem.require_linear_constraints()?;
+        Self::new(
+            Some(&problem.quadratic),
+            problem.nvars(),
+            problem.equalities.as_ref().map(|eq| &eq.matrix),
+            problem
+                .inequalities
+                .as_ref()
+                .map(|ineq| (&ineq.matrix, ineq.rhs.as_slice())),
+            problem.bounds.as_ref(),
+            solution,
+            tolerance,
+        )
+    }
+
+    pub fn lp(problem: &ProblemLP<T>, solution: &Solution<T>, tolerance: T) -> Result<Self> {
+        problem.validate()?;
+        Self::new(
+            None,
+            problem.nvars(),
+            problem.equalities.as_ref().map(|eq| &eq.matrix),
+            problem
+                .inequalities
+                .as_ref()
+                .map(|ineq| (&ineq.matrix, ineq.rhs.as_slice())),
+            problem.bounds.as_ref(),
+            solution,
+            tolerance,
+        )
+    }
+
+    fn new(
+        quadratic: Option<&CscMatrix<T>>,
+        n: usize,
+        equalities: Option<&CscMatrix<T>>,
+        inequalities: Option<(&CscMatrix<T>, &[T])>,
+        bounds: Option<&Bounds<T>>,
+        solution: &Solution<T>,
+        tolerance: T,
+    ) -> Result<Self> {
+        if solution.status != Status::Optimal {
+            bail!(
+                "sensitivities need an optimal solution, got {:?}",
+                solution.status
+            );
+        }
+        let x = &solution.primal;
+        if x.len() != n {
+            bail!("solution has {} primal values, expected {}", x.len(), n);
+        }
+        let near = |value: T, rhs: T| (value - rhs).abs() <= tolerance * (T::one() + rhs.abs());
+
+        let mut rows = Vec::new();
+        let mut coefficients: Vec<Vec<(usize, T)>> = Vec::new();
+        let meq = equalities.map_or(0, |matrix| matrix.nrows);
+        if let Some(matrix) = equalities {
+            let stacked = row_entries(matrix);
+            rows.extend((0..meq).map(ActiveRow::Equality));
+            coefficients.extend(stacked);
+        }
+        let mineq = inequalities.map_or(0, |(matrix, _)| matrix.nrows);
+        if let Some((matrix, rhs)) = inequalities {
+            for (row, entries) in row_entries(matrix).into_iter().enumerate() {
+                let value = entries
+                    .iter()
+                    .fold(T::zero(), |acc, &(col, a)| acc + a * x[col]);
+                if near(value, rhs[row]) {
+                    rows.push(ActiveRow::Inequality(row));
+                    coefficients.push(entries);
+                }
+            }
+        }
+        if let Some(bounds) = bounds {
+            for (j, (&lower, &upper)) in bounds.lower.iter().zip(&bounds.upper).enumerate() {
+                // A fixed variable gets a single row, reported as its lower
+                // bound.
+                if lower.is_finite() && near(x[j], lower) {
+                    rows.push(ActiveRow::Lower(j));
+                } else if upper.is_finite() && near(x[j], upper) {
+                    rows.push(ActiveRow::Upper(j));
+                } else {
+                    continue;
+                }
+                coefficients.push(vec![(j, T::one())]);
+            }
+        }
+
+        // The objective sees the symmetric part of P, whichever triangles
+        // are stored.
+        let dim = n + rows.len();
+        let mut data = vec![T::zero(); dim * dim];
+        let half = T::from_f64(0.5).unwrap();
+        if let Some(p) = quadratic {
+            for col in 0..n {
+                for idx in p.indptr[col]..p.indptr[col + 1] {
+                    let row = p.indices[idx];
+                    data[row * dim + col] += half * p.data[idx];
+                    data[col * dim + row] += half * p.data[idx];
+                }
+            }
+        }
+        for (k, entries) in coefficients.iter().enumerate() {
+            for &(col, value) in entries {
+                data[(n + k) * dim + col] += value;
+                data[col * dim + n + k] += value;
+            }
+        }
+        let kkt = DenseKktMatrix::new(dim, data);
+
+        let delta = T::from_f64(REGULARIZATION).unwrap() * (T::one() + norm_inf(&kkt.data));
+        let mut regularized = kkt.clone();
+        for i in 0..dim {
+            let sign = if i < n { T::one() } else { -T::one() };
+            regularized.data[i * dim + i] += sign * delta;
+        }
+        let mut factor = DenseKktSolver::new().with_pivoting(DensePivoting::BunchKaufman);
+        factor.analyze_pattern(&DensePattern::new(dim))?;
+        factor.factor(&regularized)?;
+        Ok(Self {
+            n,
+            meq,
+            mineq,
+            bounded: bounds.is_some(),
+            rows,
+            kkt,
+            factor,
+        })
+    }
+
+    // Indices of the inequality rows treated as active.
+    pub fn active_inequalities(&self) -> Vec<usize> {
+        self.rows
+            .iter()
+            .filter_map(|row| match row {
+                ActiveRow::Inequality(i) => Some(*i),
+                _ => None,
+            })
+            .collect()
+    }
+
+    // The change in x* caused by a small change `delta` of the data.
+    pub fn forward(&self, delta: &DataGradient<T>) -> Result<Vec<T>> {
+        self.check_layout(delta)?;
+        let mut rhs: Vec<T> = delta.linear.iter().map(|&dq| -dq).collect();
+        rhs.extend(self.rows.iter().map(|row| match *row {
+            ActiveRow::Equality(i) => delta.equality_rhs[i],
+            ActiveRow::Inequality(i) => delta.inequality_rhs[i],
+            ActiveRow::Lower(j) => delta.lower[j],
+            ActiveRow::Upper(j) => delta.upper[j],
+        }));
+        let mut solution = self.solve(&rhs)?;
+        solution.truncate(self.n);
+        Ok(solution)
+    }
+
+    // The gradient of a loss with respect to the data, given its gradient
+    // `dl_dx` with respect to x*. The KKT matrix is symmetric, so one solve
+    // with [dl_dx; 0] gives every component.
+    pub fn backward(&self, dl_dx: &[T]) -> Result<DataGradient<T>> {
+        if dl_dx.len() != self.n {
+            bail!("gradient has {} entries, expected {}", dl_dx.len(), self.n);
+        }
+        let mut rhs = dl_dx.to_vec();
+        rhs.resize(self.n + self.rows.len(), T::zero());
+        let v = self.solve(&rhs)?;
+        let mut gradient = DataGradient::zeros(self.n, self.meq, self.mineq, self.bounded);
+        for (g, &v) in gradient.linear.iter_mut().zip(&v) {
+            *g = -v;
+        }
+        for (row, &v) in self.rows.iter().zip(&v[self.n..]) {
+            match *row {
+                ActiveRow::Equality(i) => gradient.equality_rhs[i] = v,
+                ActiveRow::Inequality(i) => gradient.inequality_rhs[i] = v,
+                ActiveRow::Lower(j) => gradient.lower[j] = v,
+                ActiveRow::Upper(j) => gradient.upper[j] = v,
+            }
+        }
+        Ok(gradient)
+    }
+
+    // The full Jacobian of x* with respect to q, row i holding ∂x*_i/∂q.
+    pub fn jacobian_linear(&self) -> Result<Vec<Vec<T>>> {
+        (0..self.n)
+            .map(|i| {
+                let mut unit = vec![T::zero(); self.n];
+                unit[i] = T::one();
+                Ok(self.backward(&unit)?.linear)
+            })
+            .collect()
+    }
+
+    fn check_layout(&self, delta: &DataGradient<T>) -> Result<()> {
+        let nb = if self.bounded { self.n } else { 0 };
+        if delta.linear.len() != self.n
+            || delta.equality_rhs.len() != self.meq
+            || delta.inequality_rhs.len() != self.mineq
+            || delta.lower.len() != nb
+            || delta.upper.len() != nb
+        {
+            bail!("perturbation does not match the problem dimensions");
+        }
+        Ok(())
+    }
+
+    fn solve(&self, rhs: &[T]) -> Result<Vec<T>> {
+        let dim = rhs.len();
+        let mut z = rhs.to_vec();
+        self.factor.solve(&mut z)?;
+        let mut residual = vec![T::zero(); dim];
+        for _ in 0..REFINEMENT_STEPS {
+            for (i, r) in residual.iter_mut().enumerate() {
+                let row = &self.kkt.data[i * dim..(i + 1) * dim];
+                *r = rhs[i]
+                    - row
+                        .iter()
+                        .zip(&z)
+                        .fold(T::zero(), |acc, (&k, &z)| acc + k * z);
+            }
+            if norm_inf(&residual) <= T::epsilon() * (T::one() + norm_inf(rhs)) {
+                break;
+            }
+            self.factor.solve(&mut residual)?;
+            for (z, &dz) in z.iter_mut().zip(&residual) {
+                *z += dz;
+            }
+        }
+        Ok(z)
+    }
+}
+
+fn row_entries<T: RealNumber>(matrix: &CscMatrix<T>) -> Vec<Vec<(usize, T)>> {
+    let mut rows = vec![Vec::new(); matrix.nrows];
+    for col in 0..matrix.ncols {
+        for idx in matrix.indptr[col]..matrix.indptr[col + 1] {
+            rows[matrix.indices[idx]].push((col, matrix.data[idx]));
+        }
+    }
+    rows
+}
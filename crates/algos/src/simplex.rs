@@ -0,0 +1,867 @@
+use crate::progress::Progress;
+use anyhow::{bail, Result};
+use cvxrs_core::math::{dot, norm_inf, RealNumber};
+use cvxrs_core::options::SolveOptions;
+use cvxrs_core::problem::{CscMatrix, ProblemLP};
+use cvxrs_core::solution::{Certificate, Solution, Status};
+use cvxrs_core::stats::{Basis, BasisStatus, InterruptFlag, IterationSink};
+use num_traits::FromPrimitive;
+
+// Reduced costs and bound violations below this count as zero, as do basic
+// entries of the entering column when they decide the ratio test.
+const DUAL_TOLERANCE: f64 = 1e-9;
+const PRIMAL_TOLERANCE: f64 = 1e-9;
+const PIVOT_TOLERANCE: f64 = 1e-9;
+
+// The explicit inverse is rebuilt from scratch after this many updates.
+const REFACTOR_INTERVAL: usize = 50;
+
+// During crossover, starting values this close to a bound, relative to its
+// magnitude, start nonbasic at the bound.
+const SNAP_TOLERANCE: f64 = 1e-6;
+
+// Dantzig pricing gives way to Bland's rule after this many degenerate
+// pivots in a row, until a pivot makes progress again.
+const BLAND_THRESHOLD: usize = 50;
+
+pub struct SimplexSolver<T: RealNumber> {
+    iteration_log: Option<IterationSink<T>>,
+    interrupt: Option<InterruptFlag>,
+}
+
+impl<T> Default for SimplexSolver<T>
+where
+    T: RealNumber + FromPrimitive,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> SimplexSolver<T>
+where
+    T: RealNumber + FromPrimitive,
+{
+    pub fn new() -> Self {
+        Self {
+            iteration_log: None,
+            interrupt: None,
+        }
+    }
+
+    pub fn with_iteration_log(mut self, sink: IterationSink<T>) -> Self {
+        self.iteration_log = Some(sink);
+        self
+    }
+
+    pub fn with_interrupt(mut self, flag: InterruptFlag) -> Self {
+        self.interrupt = Some(flag);
+        self
+    }
+
+    // Bounded-variable revised simplex on [C 0; G I](x, s) = (b, h), s >= 0,
+    // after a presolve that drops fixed variables, empty columns and empty
+    // rows. Phase one minimizes the artificial variables of a slack basis.
+    pub fn solve_lp(
+        &self,
+        problem: &ProblemLP<T>,
+        options: &SolveOptions<T>,
+    ) -> Result<Solution<T>> {
+        self.solve_from(problem, options, None)
+    }
+
+    // Purifies an approximate LP solution, typically from ADMM, into an
+    // optimal vertex. Variables and slacks near a bound start nonbasic there,
+    // the others start at their approximate values and are pushed to a bound
+    // once the simplex is done; phase one only has to absorb the residual.
+    pub fn crossover(
+        &self,
+        problem: &ProblemLP<T>,
+        approximate: &Solution<T>,
+        options: &SolveOptions<T>,
+    ) -> Result<Solution<T>> {
+        if approximate.primal.len() != problem.nvars() {
+            bail!(
+                "crossover expects {} primal values, got {}",
+                problem.nvars(),
+                approximate.primal.len()
+            );
+        }
+        self.solve_from(problem, options, Some(&approximate.primal))
+    }
+
+    fn solve_from(
+        &self,
+        problem: &ProblemLP<T>,
+        options: &SolveOptions<T>,
+        start: Option<&[T]>,
+    ) -> Result<Solution<T>> {
+        problem.validate()?;
+        let lp = DenseLp::new(problem);
+        let mut progress = Progress::new(
+            options,
+            self.iteration_log.as_ref(),
+            self.interrupt.as_ref(),
+        );
+        let mut solution = Solution::with_capacity(lp.n, lp.meq, lp.dual_len());
+        let status = match lp.presolve() {
+            Presolve::Infeasible => {
+                solution.objective_value = T::infinity();
+                Status::PrimalInfeasible
+            }
+            Presolve::Unbounded(direction) => {
+                solution.objective_value = T::neg_infinity();
+                solution.certificate = Some(Certificate::DualInfeasible { direction });
+                Status::DualInfeasible
+            }
+            Presolve::Reduced(reduced) => {
+                lp.solve_reduced(&reduced, start, &mut progress, &mut solution)
+            }
+        };
+        progress.stats.solve_time = progress.timer.elapsed();
+        solution.status = status;
+        solution.objective_value += problem.offset;
+        solution.iterations = progress.stats.history.len();
+        solution.stats = progress.stats;
+        Ok(solution)
+    }
+}
+
+// The LP with dense rows and explicit bounds, infinite where absent.
+struct DenseLp<T: RealNumber> {
+    n: usize,
+    meq: usize,
+    mineq: usize,
+    has_bounds: bool,
+    c: Vec<T>,
+    a: Vec<T>,
+    b: Vec<T>,
+    g: Vec<T>,
+    h: Vec<T>,
+    lower: Vec<T>,
+    upper: Vec<T>,
+}
+
+// What presolve keeps, by original index, and the values and statuses of
+// the variables it removed.
+struct Reduced<T> {
+    columns: Vec<usize>,
+    equalities: Vec<usize>,
+    inequalities: Vec<usize>,
+    values: Vec<T>,
+    statuses: Vec<BasisStatus>,
+}
+
+enum Presolve<T> {
+    Reduced(Reduced<T>),
+    Infeasible,
+    Unbounded(Vec<T>),
+}
+
+impl<T> DenseLp<T>
+where
+    T: RealNumber + FromPrimitive,
+{
+    fn new(problem: &ProblemLP<T>) -> Self {
+        let n = problem.nvars();
+        let (meq, a, b) = match &problem.equalities {
+            Some(eq) => (eq.matrix.nrows, to_dense(&eq.matrix), eq.rhs.clone()),
+            None => (0, Vec::new(), Vec::new()),
+        };
+        let (mineq, g, h) = match &problem.inequalities {
+            Some(ineq) => (ineq.matrix.nrows, to_dense(&ineq.matrix), ineq.rhs.clone()),
+            None => (0, Vec::new(), Vec::new()),
+        };
+        let (lower, upper) = match &problem.bounds {
+            Some(bounds) => (bounds.lower.clone(), bounds.upper.clone()),
+            None => (vec![T::neg_infinity(); n], vec![T::infinity(); n]),
+        };
+        Self {
+            n,
+            meq,
+            mineq,
+            has_bounds: problem.bounds.is_some(),
+            c: problem.cost.clone(),
+            a,
+            b,
+            g,
+            h,
+            lower,
+            upper,
+        }
+    }
+
+    fn dual_len(&self) -> usize {
+        self.mineq + if self.has_bounds { self.n } else { 0 }
+    }
+
+    fn column_is_empty(&self, col: usize) -> bool {
+        let rows = |matrix: &[T], count: usize| {
+            (0..count).all(|row| matrix[row * self.n + col] == T::zero())
+        };
+        rows(&self.a, self.meq) && rows(&self.g, self.mineq)
+    }
+
+    // Fixed variables and empty columns are set once and for all; rows left
+    // without any remaining variable are checked and dropped.
+    fn presolve(&self) -> Presolve<T> {
+        let tolerance = T::from_f64(PRIMAL_TOLERANCE).unwrap();
+        let mut values = vec![T::zero(); self.n];
+        let mut statuses = vec![BasisStatus::Basic; self.n];
+        let mut columns = Vec::new();
+        for col in 0..self.n {
+            let (lower, upper, cost) = (self.lower[col], self.upper[col], self.c[col]);
+            if lower == upper {
+                values[col] = lower;
+                statuses[col] = BasisStatus::AtLower;
+            } else if self.column_is_empty(col) {
+                let (value, status) = if cost > T::zero() {
+                    (lower, BasisStatus::AtLower)
+                } else if cost < T::zero() {
+                    (upper, BasisStatus::AtUpper)
+                } else {
+                    nonbasic_start(lower, upper)
+                };
+                if value.is_infinite() {
+                    let mut direction = vec![T::zero(); self.n];
+                    direction[col] = -T::one() / cost;
+                    return Presolve::Unbounded(direction);
+                }
+                values[col] = value;
+                statuses[col] = status;
+            } else {
+                columns.push(col);
+            }
+        }
+
+        let keep = |matrix: &[T], rhs: &[T], count: usize, equality: bool| {
+            let mut kept = Vec::new();
+            for row in 0..count {
+                let coefficients = &matrix[row * self.n..(row + 1) * self.n];
+                if columns.iter().any(|&col| coefficients[col] != T::zero()) {
+                    kept.push(row);
+                    continue;
+                }
+                let residual = rhs[row] - dot(coefficients, &values);
+                let scale = T::one() + rhs[row].abs();
+                if (equality && residual.abs() > tolerance * scale)
+                    || (!equality && residual < -tolerance * scale)
+                {
+                    return None;
+                }
+            }
+            Some(kept)
+        };
+        let Some(equalities) = keep(&self.a, &self.b, self.meq, true) else {
+            return Presolve::Infeasible;
+        };
+        let Some(inequalities) = keep(&self.g, &self.h, self.mineq, false) else {
+            return Presolve::Infeasible;
+        };
+        Presolve::Reduced(Reduced {
+            columns,
+            equalities,
+            inequalities,
+            values,
+            statuses,
+        })
+    }
+
+    // Runs both phases on the reduced problem and writes the primal, the
+    // duals and the basis of the original one into `solution`.
+    fn solve_reduced(
+        &self,
+        reduced: &Reduced<T>,
+        start: Option<&[T]>,
+        progress: &mut Progress<'_, T>,
+        solution: &mut Solution<T>,
+    ) -> Status {
+        let mut simplex = Simplex::new(self, reduced, start);
+        let (k, mi) = (reduced.columns.len(), reduced.inequalities.len());
+        let width = k + mi;
+        let rows = simplex.m;
+
+        let mut cost = vec![T::zero(); width + rows];
+        cost[width..].fill(T::one());
+        match simplex.run(&cost, progress) {
+            Phase::Optimal => {}
+            Phase::Stopped(status) => return status,
+            Phase::Singular | Phase::Unbounded { .. } => return Status::NumericalFailure,
+        }
+        let infeasibility = simplex.x[width..].iter().fold(T::zero(), |acc, &v| acc + v);
+        let scale = T::one() + norm_inf(&simplex.b);
+        if infeasibility > T::from_f64(PRIMAL_TOLERANCE).unwrap() * scale {
+            solution.objective_value = T::infinity();
+            return Status::PrimalInfeasible;
+        }
+        simplex.retire_artificials(width);
+        if !simplex.refactor() {
+            return Status::NumericalFailure;
+        }
+        progress.stats.factorizations += 1;
+
+        cost.fill(T::zero());
+        for (slot, &col) in cost.iter_mut().zip(&reduced.columns) {
+            *slot = self.c[col];
+        }
+        match simplex.run(&cost, progress) {
+            Phase::Optimal => {}
+            Phase::Stopped(status) => return status,
+            Phase::Singular => return Status::NumericalFailure,
+            Phase::Unbounded {
+                entering,
+                sign,
+                alpha,
+            } => {
+                let mut ray = vec![T::zero(); width + rows];
+                ray[entering] = sign;
+                for (&col, &value) in simplex.basis.iter().zip(&alpha) {
+                    ray[col] = -sign * value;
+                }
+                let mut direction = vec![T::zero(); self.n];
+                for (&col, &value) in reduced.columns.iter().zip(&ray) {
+                    direction[col] = value;
+                }
+                let slope = -dot(&self.c, &direction);
+                solution.objective_value = T::neg_infinity();
+                solution.certificate = Some(Certificate::DualInfeasible {
+                    direction: direction.iter().map(|&d| d / slope).collect(),
+                });
+                return Status::DualInfeasible;
+            }
+        }
+
+        simplex.purify(progress);
+
+        // π = B⁻ᵀc_B prices the rows of [C 0; G I]; with the sign
+        // convention c + Cᵀy + Gᵀz + w = 0, y = -π, z = -π and w = -(c + Cᵀy + Gᵀz).
+        let pi = simplex.duals(&cost);
+        let mut x = reduced.values.clone();
+        let mut variables = reduced.statuses.clone();
+        for (slot, &col) in reduced.columns.iter().enumerate() {
+            x[col] = simplex.x[slot];
+            variables[col] = simplex.status[slot];
+        }
+        let mut inequalities = vec![BasisStatus::Basic; self.mineq];
+        for (slot, &row) in reduced.inequalities.iter().enumerate() {
+            inequalities[row] = match simplex.status[k + slot] {
+                BasisStatus::AtLower => BasisStatus::AtUpper,
+                _ => BasisStatus::Basic,
+            };
+        }
+        let mut y = vec![T::zero(); self.meq];
+        for (slot, &row) in reduced.equalities.iter().enumerate() {
+            y[row] = -pi[slot];
+        }
+        let mut z = vec![T::zero(); self.mineq];
+        for (slot, &row) in reduced.inequalities.iter().enumerate() {
+            z[row] = -pi[reduced.equalities.len() + slot];
+        }
+        let mut dual = z.clone();
+        if self.has_bounds {
+            dual.extend((0..self.n).map(|col| {
+                let mut value = self.c[col];
+                for (row, &y) in y.iter().enumerate() {
+                    value += self.a[row * self.n + col] * y;
+                }
+                for (row, &z) in z.iter().enumerate() {
+                    value += self.g[row * self.n + col] * z;
+                }
+                -value
+            }));
+        }
+        solution.objective_value = dot(&self.c, &x);
+        solution.primal = x;
+        solution.equality_dual = y;
+        solution.inequality_dual = dual;
+        solution.basis = Some(Basis {
+            variables,
+            inequalities,
+        });
+        Status::Optimal
+    }
+}
+
+enum Phase<T> {
+    Optimal,
+    // The entering column, its direction and B⁻¹ times its column.
+    Unbounded {
+        entering: usize,
+        sign: T,
+        alpha: Vec<T>,
+    },
+    Stopped(Status),
+    Singular,
+}
+
+// Column-major constraint matrix of the reduced problem with its slack and
+// artificial columns, the bounds of every column and the explicit inverse
+// of the basis matrix.
+struct Simplex<T: RealNumber> {
+    m: usize,
+    a: Vec<T>,
+    b: Vec<T>,
+    lower: Vec<T>,
+    upper: Vec<T>,
+    x: Vec<T>,
+    status: Vec<BasisStatus>,
+    basis: Vec<usize>,
+    inverse: Vec<T>,
+}
+
+impl<T> Simplex<T>
+where
+    T: RealNumber + FromPrimitive,
+{
+    // Structural columns start at their lower bound if they have one, or
+    // near `start` when given. Each row starts with its slack basic when the
+    // slack can stay nonnegative, else with an artificial that absorbs the
+    // residual with the sign that keeps it nonnegative.
+    fn new(lp: &DenseLp<T>, reduced: &Reduced<T>, start: Option<&[T]>) -> Self {
+        let (k, mi) = (reduced.columns.len(), reduced.inequalities.len());
+        let rows: Vec<(&[T], T)> = reduced
+            .equalities
+            .iter()
+            .map(|&row| (&lp.a[row * lp.n..(row + 1) * lp.n], lp.b[row]))
+            .chain(
+                reduced
+                    .inequalities
+                    .iter()
+                    .map(|&row| (&lp.g[row * lp.n..(row + 1) * lp.n], lp.h[row])),
+            )
+            .collect();
+        let m = rows.len();
+        let width = k + mi + m;
+        let mut a = vec![T::zero(); width * m];
+        let mut b = Vec::with_capacity(m);
+        for (row, (coefficients, rhs)) in rows.iter().enumerate() {
+            for (slot, &col) in reduced.columns.iter().enumerate() {
+                a[slot * m + row] = coefficients[col];
+            }
+            b.push(*rhs - dot(coefficients, &reduced.values));
+        }
+        for slot in 0..mi {
+            a[(k + slot) * m + reduced.equalities.len() + slot] = T::one();
+        }
+
+        let mut lower = vec![T::zero(); width];
+        let mut upper = vec![T::infinity(); width];
+        let mut x = vec![T::zero(); width];
+        let mut status = vec![BasisStatus::AtLower; width];
+        for (slot, &col) in reduced.columns.iter().enumerate() {
+            lower[slot] = lp.lower[col];
+            upper[slot] = lp.upper[col];
+            (x[slot], status[slot]) = match start {
+                Some(start) => warm_start(start[col], lower[slot], upper[slot]),
+                None => nonbasic_start(lower[slot], upper[slot]),
+            };
+        }
+        if start.is_some() {
+            for slot in k..k + mi {
+                let row = slot - k + reduced.equalities.len();
+                let activity = (0..k).fold(T::zero(), |acc, col| acc + a[col * m + row] * x[col]);
+                (x[slot], status[slot]) = warm_start(b[row] - activity, T::zero(), T::infinity());
+            }
+        }
+        let meq = reduced.equalities.len();
+        let mut inverse = vec![T::zero(); m * m];
+        let mut basis = Vec::with_capacity(m);
+        for row in 0..m {
+            let activity = (0..k + mi).fold(T::zero(), |acc, col| acc + a[col * m + row] * x[col]);
+            let residual = b[row] - activity;
+            let col = k + mi + row;
+            // A slack that can take up the residual replaces the artificial.
+            let slack = k + row.saturating_sub(meq);
+            if row >= meq && x[slack] + residual >= T::zero() {
+                a[col * m + row] = T::one();
+                inverse[row * m + row] = T::one();
+                x[slack] += residual;
+                status[slack] = BasisStatus::Basic;
+                basis.push(slack);
+                continue;
+            }
+            let sign = if residual < T::zero() {
+                -T::one()
+            } else {
+                T::one()
+            };
+            a[col * m + row] = sign;
+            inverse[row * m + row] = sign;
+            x[col] = residual.abs();
+            status[col] = BasisStatus::Basic;
+            basis.push(col);
+        }
+        Self {
+            m,
+            a,
+            b,
+            lower,
+            upper,
+            x,
+            status,
+            basis,
+            inverse,
+        }
+    }
+
+    fn column(&self, col: usize) -> &[T] {
+        &self.a[col * self.m..(col + 1) * self.m]
+    }
+
+    // B⁻¹v.
+    fn solve_column(&self, v: &[T]) -> Vec<T> {
+        self.inverse
+            .chunks_exact(self.m.max(1))
+            .take(self.m)
+            .map(|row| dot(row, v))
+            .collect()
+    }
+
+    // π = B⁻ᵀc_B.
+    fn duals(&self, cost: &[T]) -> Vec<T> {
+        let mut pi = vec![T::zero(); self.m];
+        for (row, &col) in self.basis.iter().enumerate() {
+            let weight = cost[col];
+            for (p, &value) in pi
+                .iter_mut()
+                .zip(&self.inverse[row * self.m..(row + 1) * self.m])
+            {
+                *p += weight * value;
+            }
+        }
+        pi
+    }
+
+    // Rebuilds B⁻¹ by Gauss–Jordan elimination with partial pivoting and
+    // recomputes the basic values from the nonbasic ones.
+    fn refactor(&mut self) -> bool {
+        let m = self.m;
+        let mut work = vec![T::zero(); m * m];
+        for (slot, &col) in self.basis.iter().enumerate() {
+            for row in 0..m {
+                work[row * m + slot] = self.a[col * m + row];
+            }
+        }
+        let mut inverse = vec![T::zero(); m * m];
+        for i in 0..m {
+            inverse[i * m + i] = T::one();
+        }
+        for col in 0..m {
+            let (pivot_row, pivot) = (col..m)
+                .map(|row| (row, work[row * m + col].abs()))
+                .max_by(|a, b| a.1.total_cmp(&b.1))
+                .unwrap();
+            if pivot <= T::from_f64(PIVOT_TOLERANCE).unwrap() {
+                return false;
+            }
+            for j in 0..m {
+                work.swap(col * m + j, pivot_row * m + j);
+                inverse.swap(col * m + j, pivot_row * m + j);
+            }
+            let scale = work[col * m + col];
+            for j in 0..m {
+                work[col * m + j] = work[col * m + j] / scale;
+                inverse[col * m + j] = inverse[col * m + j] / scale;
+            }
+            for row in 0..m {
+                let factor = work[row * m + col];
+                if row == col || factor == T::zero() {
+                    continue;
+                }
+                for j in 0..m {
+                    let (w, v) = (work[col * m + j], inverse[col * m + j]);
+                    work[row * m + j] -= factor * w;
+                    inverse[row * m + j] -= factor * v;
+                }
+            }
+        }
+        self.inverse = inverse;
+
+        let mut rhs = self.b.clone();
+        for col in 0..self.lower.len() {
+            if self.status[col] == BasisStatus::Basic || self.x[col] == T::zero() {
+                continue;
+            }
+            for (r, &value) in rhs.iter_mut().zip(self.column(col)) {
+                *r -= value * self.x[col];
+            }
+        }
+        let values = self.solve_column(&rhs);
+        for (&col, value) in self.basis.iter().zip(values) {
+            self.x[col] = value;
+        }
+        true
+    }
+
+    // Product-form update of B⁻¹ after column `row` of B is replaced by the
+    // column whose image under the old inverse is `alpha`.
+    fn pivot(&mut self, row: usize, alpha: &[T]) {
+        let m = self.m;
+        let pivot = alpha[row];
+        for j in 0..m {
+            self.inverse[row * m + j] = self.inverse[row * m + j] / pivot;
+        }
+        for (i, &factor) in alpha.iter().enumerate() {
+            if i == row || factor == T::zero() {
+                continue;
+            }
+            for j in 0..m {
+                let value = self.inverse[row * m + j];
+                self.inverse[i * m + j] -= factor * value;
+            }
+        }
+    }
+
+    // Swaps artificial columns still basic at zero for structural or slack
+    // ones where the row allows it, and pins every artificial to zero. Rows
+    // whose artificial cannot leave are redundant.
+    fn retire_artificials(&mut self, width: usize) {
+        let tolerance = T::from_f64(PIVOT_TOLERANCE).unwrap();
+        for row in 0..self.m {
+            if self.basis[row] < width {
+                continue;
+            }
+            let inverse_row = self.inverse[row * self.m..(row + 1) * self.m].to_vec();
+            let candidate = (0..width)
+                .filter(|&col| self.status[col] != BasisStatus::Basic)
+                .map(|col| (col, dot(&inverse_row, self.column(col)).abs()))
+                .filter(|&(_, value)| value > tolerance)
+                .max_by(|a, b| a.1.total_cmp(&b.1));
+            if let Some((col, _)) = candidate {
+                let alpha = self.solve_column(self.column(col));
+                let leaving = self.basis[row];
+                self.status[leaving] = BasisStatus::AtLower;
+                self.x[leaving] = T::zero();
+                self.status[col] = BasisStatus::Basic;
+                self.basis[row] = col;
+                self.pivot(row, &alpha);
+            }
+        }
+        for col in width..self.lower.len() {
+            self.upper[col] = T::zero();
+            if self.status[col] != BasisStatus::Basic {
+                self.x[col] = T::zero();
+            }
+        }
+    }
+
+    fn run(&mut self, cost: &[T], progress: &mut Progress<'_, T>) -> Phase<T> {
+        let dual_tolerance = T::from_f64(DUAL_TOLERANCE).unwrap() * (T::one() + norm_inf(cost));
+        let primal_tolerance = T::from_f64(PRIMAL_TOLERANCE).unwrap();
+        let mut degenerate = 0;
+        let mut since_refactor = 0;
+        loop {
+            if let Some(status) = progress.stopped() {
+                return Phase::Stopped(status);
+            }
+            if since_refactor >= REFACTOR_INTERVAL {
+                if !self.refactor() {
+                    return Phase::Singular;
+                }
+                progress.stats.factorizations += 1;
+                since_refactor = 0;
+            }
+
+            let pi = self.duals(cost);
+            let bland = degenerate >= BLAND_THRESHOLD;
+            let mut entering: Option<(usize, T)> = None;
+            let mut best = T::zero();
+            for (col, &weight) in cost.iter().enumerate() {
+                if self.status[col] == BasisStatus::Basic || self.lower[col] == self.upper[col] {
+                    continue;
+                }
+                let reduced = weight - dot(self.column(col), &pi);
+                let sign = match self.status[col] {
+                    BasisStatus::AtLower if reduced < -dual_tolerance => T::one(),
+                    BasisStatus::AtUpper if reduced > dual_tolerance => -T::one(),
+                    BasisStatus::Free if reduced.abs() > dual_tolerance => -reduced.signum(),
+                    _ => continue,
+                };
+                if reduced.abs() > best {
+                    best = reduced.abs();
+                    entering = Some((col, sign));
+                    if bland {
+                        break;
+                    }
+                }
+            }
+            let objective = dot(cost, &self.x);
+            progress.record(T::zero(), best, objective);
+            let Some((entering, sign)) = entering else {
+                return Phase::Optimal;
+            };
+            let alpha = self.solve_column(self.column(entering));
+            progress.stats.linear_solves += 2;
+
+            let reach = if sign > T::zero() {
+                self.upper[entering] - self.x[entering]
+            } else {
+                self.x[entering] - self.lower[entering]
+            };
+            let (theta, leaving) = self.ratio_test(sign, reach, &alpha, bland);
+            if theta.is_infinite() {
+                return Phase::Unbounded {
+                    entering,
+                    sign,
+                    alpha,
+                };
+            }
+            if theta <= primal_tolerance {
+                degenerate += 1;
+            } else {
+                degenerate = 0;
+            }
+            if self.step(entering, sign, theta, leaving, &alpha) {
+                since_refactor += 1;
+            }
+        }
+    }
+
+    // The entering column may run into its own other bound, `reach` away,
+    // or push a basic column onto one of its bounds. Ties go to the larger
+    // pivot, or to the smallest column under Bland's rule.
+    fn ratio_test(
+        &self,
+        sign: T,
+        reach: T,
+        alpha: &[T],
+        bland: bool,
+    ) -> (T, Option<(usize, BasisStatus)>) {
+        let primal_tolerance = T::from_f64(PRIMAL_TOLERANCE).unwrap();
+        let pivot_tolerance = T::from_f64(PIVOT_TOLERANCE).unwrap();
+        let mut theta = reach;
+        let mut leaving: Option<(usize, BasisStatus)> = None;
+        for (row, &value) in alpha.iter().enumerate() {
+            let rate = -sign * value;
+            if rate.abs() <= pivot_tolerance {
+                continue;
+            }
+            let col = self.basis[row];
+            let (limit, bound) = if rate < T::zero() {
+                (
+                    (self.x[col] - self.lower[col]) / -rate,
+                    BasisStatus::AtLower,
+                )
+            } else {
+                ((self.upper[col] - self.x[col]) / rate, BasisStatus::AtUpper)
+            };
+            let limit = limit.max(T::zero());
+            let better = match leaving {
+                _ if limit < theta - primal_tolerance => true,
+                Some((current, _)) if (limit - theta).abs() <= primal_tolerance => {
+                    if bland {
+                        col < self.basis[current]
+                    } else {
+                        value.abs() > alpha[current].abs()
+                    }
+                }
+                _ => false,
+            };
+            if better {
+                theta = limit;
+                leaving = Some((row, bound));
+            }
+        }
+        (theta, leaving)
+    }
+
+    // Moves the entering column by `theta` and either flips it onto the
+    // bound it reached or swaps it into the basis; returns whether it pivoted.
+    fn step(
+        &mut self,
+        entering: usize,
+        sign: T,
+        theta: T,
+        leaving: Option<(usize, BasisStatus)>,
+        alpha: &[T],
+    ) -> bool {
+        self.x[entering] += sign * theta;
+        for (&col, &value) in self.basis.iter().zip(alpha) {
+            self.x[col] -= sign * theta * value;
+        }
+        let Some((row, bound)) = leaving else {
+            let (lower, upper) = (self.lower[entering], self.upper[entering]);
+            (self.x[entering], self.status[entering]) =
+                if lower.is_infinite() && upper.is_infinite() {
+                    (T::zero(), BasisStatus::Free)
+                } else if sign > T::zero() {
+                    (upper, BasisStatus::AtUpper)
+                } else {
+                    (lower, BasisStatus::AtLower)
+                };
+            return false;
+        };
+        let col = self.basis[row];
+        self.x[col] = match bound {
+            BasisStatus::AtLower => self.lower[col],
+            _ => self.upper[col],
+        };
+        self.status[col] = bound;
+        self.status[entering] = BasisStatus::Basic;
+        self.basis[row] = entering;
+        self.pivot(row, alpha);
+        true
+    }
+
+    // Pushes the nonbasic columns a crossover left between their bounds onto
+    // the nearer one, and free columns back to zero. Their reduced costs
+    // vanish at an optimum, so the objective does not move.
+    fn purify(&mut self, progress: &mut Progress<'_, T>) {
+        for col in 0..self.lower.len() {
+            if self.status[col] != BasisStatus::Free {
+                continue;
+            }
+            let (lower, upper, value) = (self.lower[col], self.upper[col], self.x[col]);
+            let (sign, reach) = if lower.is_infinite() && upper.is_infinite() {
+                if value == T::zero() {
+                    continue;
+                }
+                (-value.signum(), value.abs())
+            } else if upper - value < value - lower {
+                (T::one(), upper - value)
+            } else {
+                (-T::one(), value - lower)
+            };
+            let alpha = self.solve_column(self.column(col));
+            progress.stats.linear_solves += 1;
+            let (theta, leaving) = self.ratio_test(sign, reach, &alpha, false);
+            self.step(col, sign, theta, leaving, &alpha);
+        }
+    }
+}
+
+// A nonbasic column sits at its lower bound if it has one, else at its
+// upper bound, else free at zero.
+fn nonbasic_start<T: RealNumber>(lower: T, upper: T) -> (T, BasisStatus) {
+    if lower.is_finite() {
+        (lower, BasisStatus::AtLower)
+    } else if upper.is_finite() {
+        (upper, BasisStatus::AtUpper)
+    } else {
+        (T::zero(), BasisStatus::Free)
+    }
+}
+
+// Snaps a crossover starting value onto a nearby bound, otherwise keeps it
+// as a nonbasic column between its bounds.
+fn warm_start<T: RealNumber + FromPrimitive>(value: T, lower: T, upper: T) -> (T, BasisStatus) {
+    let snap = T::from_f64(SNAP_TOLERANCE).unwrap();
+    let value = value.max(lower).min(upper);
+    if lower.is_finite() && value - lower <= snap * (T::one() + lower.abs()) {
+        (lower, BasisStatus::AtLower)
+    } else if upper.is_finite() && upper - value <= snap * (T::one() + upper.abs()) {
+        (upper, BasisStatus::AtUpper)
+    } else {
+        (value, BasisStatus::Free)
+    }
+}
+
+fn to_dense<T: RealNumber>(matrix: &CscMatrix<T>) -> Vec<T> {
+    let mut dense = vec![T::zero(); matrix.nrows * matrix.ncols];
+    for col in 0..matrix.ncols {
+        for idx in matrix.indptr[col]..matrix.indptr[col + 1] {
+            dense[matrix.indices[idx] * matrix.ncols + col] += matrix.data[idx];
+        }
+    }
+    dense
+}
@@ -0,0 +1,300 @@
+use anyhow::{bail, Result};
+use cvxrs_core::math::{RealNumber, Timer};
+use cvxrs_core::options::SolveOptions;
+use cvxrs_core::solution::Status;
+use cvxrs_core::stats::SolveStats;
+use num_traits::FromPrimitive;
+
+// Curvature floor for non positive-definite kernels, as in LIBSVM.
+const TAU: f64 = 1e-12;
+
+// Soft-margin SVM dual
+//   minimize ½ αᵀQα - 1ᵀα  s.t.  yᵀα = 0,  0 ≤ α ≤ C,  Q_ij = y_i y_j K_ij,
+// solved by SMO with second-order working-set selection. Each step moves two
+// multipliers along the single equality and clips them to the box, so an
+// iteration costs two kernel rows and no factorization.
+pub struct SvmSolver<T: RealNumber> {
+    options: SolveOptions<T>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SvmModel<T: RealNumber> {
+    pub alpha: Vec<T>,
+    pub labels: Vec<T>,
+    pub bias: T,
+    // Primal weights, only known when trained from features (linear kernel).
+    pub weights: Option<Vec<T>>,
+    pub status: Status,
+    pub objective_value: T,
+    pub iterations: usize,
+    pub stats: SolveStats<T>,
+}
+
+impl<T> SvmModel<T>
+where
+    T: RealNumber,
+{
+    // f(x) = Σ α_i y_i K(x_i, x) + b, with `kernel_row[i] = K(x_i, x)`.
+    pub fn decision_from_kernel(&self, kernel_row: &[T]) -> T {
+        self.alpha
+            .iter()
+            .zip(&self.labels)
+            .zip(kernel_row)
+            .fold(self.bias, |acc, ((&alpha, &y), &k)| acc + alpha * y * k)
+    }
+
+    pub fn decision(&self, features: &[T]) -> Option<T> {
+        let weights = self.weights.as_ref()?;
+        Some(
+            weights
+                .iter()
+                .zip(features)
+                .fold(self.bias, |acc, (&w, &x)| acc + w * x),
+        )
+    }
+
+    pub fn support_vectors(&self) -> Vec<usize> {
+        (0..self.alpha.len())
+            .filter(|&i| self.alpha[i] > T::zero())
+            .collect()
+    }
+}
+
+impl<T> SvmSolver<T>
+where
+    T: RealNumber + FromPrimitive,
+{
+    pub fn new(options: SolveOptions<T>) -> Self {
+        Self { options }
+    }
+
+    pub fn solve_features(
+        &self,
+        features: &[Vec<T>],
+        labels: &[T],
+        penalty: T,
+    ) -> Result<SvmModel<T>> {
+        let n = features.len();
+        let d = features.first().map_or(0, Vec::len);
+        if let Some(index) = features.iter().position(|row| row.len() != d) {
+            bail!(
+                "feature row {index} has {} entries, expected {d}",
+                features[index].len()
+            );
+        }
+        let mut gram = vec![T::zero(); n * n];
+        for i in 0..n {
+            for j in i..n {
+                let mut acc = T::zero();
+                for (&a, &b) in features[i].iter().zip(&features[j]) {
+                    acc += a * b;
+                }
+                gram[i * n + j] = acc;
+                gram[j * n + i] = acc;
+            }
+        }
+        let mut model = self.solve_gram(&gram, labels, penalty)?;
+        let mut weights = vec![T::zero(); d];
+        for ((row, &alpha), &y) in features.iter().zip(&model.alpha).zip(labels) {
+            for (w, &x) in weights.iter_mut().zip(row) {
+                *w += alpha * y * x;
+            }
+        }
+        model.weights = Some(weights);
+        Ok(model)
+    }
+
+    // `gram` is the dense n×n kernel matrix in row-major order.
+    pub fn solve_gram(&self, gram: &[T], labels: &[T], penalty: T) -> Result<SvmModel<T>> {
+        let n = labels.len();
+        if gram.len() != n * n {
+            bail!("kernel matrix has {} entries for {n} labels", gram.len());
+        }
+        if let Some(index) = labels.iter().position(|&y| y != T::one() && y != -T::one()) {
+            bail!("label {index} must be +1 or -1");
+        }
+        if penalty <= T::zero() {
+            bail!("penalty must be positive");
+        }
+
+        let timer = Timer::start();
+        let tau = T::from_f64(TAU).unwrap();
+        let q = |i: usize, j: usize| labels[i] * labels[j] * gram[i * n + j];
+        let mut alpha = vec![T::zero(); n];
+        let mut gradient = vec![-T::one(); n];
+        let at_upper = |a: T| a >= penalty;
+        let at_lower = |a: T| a <= T::zero();
+        let in_up = |a: T, y: T| (y > T::zero() && !at_upper(a)) || (y < T::zero() && !at_lower(a));
+        let in_low =
+            |a: T, y: T| (y > T::zero() && !at_lower(a)) || (y < T::zero() && !at_upper(a));
+
+        let mut status = Status::MaxIterations;
+        let mut iterations = 0;
+        while iterations < self.options.max_iterations {
+            if let Some(limit) = self.options.max_time {
+                if timer.elapsed() > limit {
+                    status = Status::MaxTime;
+                    break;
+                }
+            }
+
+            let mut i = None;
+            let mut max_up = T::neg_infinity();
+            for t in 0..n {
+                let score = -labels[t] * gradient[t];
+                if in_up(alpha[t], labels[t]) && score >= max_up {
+                    max_up = score;
+                    i = Some(t);
+                }
+            }
+            let mut j = None;
+            let mut min_low = T::infinity();
+            let mut best = T::infinity();
+            if let Some(i) = i {
+                for t in 0..n {
+                    if !in_low(alpha[t], labels[t]) {
+                        continue;
+                    }
+                    let score = -labels[t] * gradient[t];
+                    min_low = min_low.min(score);
+                    let b = max_up - score;
+                    if b > T::zero() {
+                        let a =
+                            gram[i * n + i] + gram[t * n + t] - (gram[i * n + t] + gram[i * n + t]);
+                        let a = if a > T::zero() { a } else { tau };
+                        if -(b * b) / a <= best {
+                            best = -(b * b) / a;
+                            j = Some(t);
+                        }
+                    }
+                }
+            }
+            let (Some(i), Some(j)) = (i, j) else {
+                status = Status::Optimal;
+                break;
+            };
+            if max_up - min_low < self.options.eps_abs {
+                status = Status::Optimal;
+                break;
+            }
+
+            let (old_i, old_j) = (alpha[i], alpha[j]);
+            if labels[i] != labels[j] {
+                let mut quad = q(i, i) + q(j, j) + q(i, j) + q(i, j);
+                if quad <= T::zero() {
+                    quad = tau;
+                }
+                let delta = (-gradient[i] - gradient[j]) / quad;
+                let diff = alpha[i] - alpha[j];
+                alpha[i] += delta;
+                alpha[j] += delta;
+                if diff > T::zero() {
+                    if alpha[j] < T::zero() {
+                        alpha[j] = T::zero();
+                        alpha[i] = diff;
+                    }
+                } else if alpha[i] < T::zero() {
+                    alpha[i] = T::zero();
+                    alpha[j] = -diff;
+                }
+                if diff > T::zero() {
+                    if alpha[i] > penalty {
+                        alpha[i] = penalty;
+                        alpha[j] = penalty - diff;
+                    }
+                } else if alpha[j] > penalty {
+                    alpha[j] = penalty;
+                    alpha[i] = penalty + diff;
+                }
+            } else {
+                let mut quad = q(i, i) + q(j, j) - (q(i, j) + q(i, j));
+                if quad <= T::zero() {
+                    quad = tau;
+                }
+                let delta = (gradient[i] - gradient[j]) / quad;
+                let sum = alpha[i] + alpha[j];
+                alpha[i] -= delta;
+                alpha[j] += delta;
+                if sum > penalty {
+                    if alpha[i] > penalty {
+                        alpha[i] = penalty;
+                        alpha[j] = sum - penalty;
+                    }
+                    if alpha[j] > penalty {
+                        alpha[j] = penalty;
+                        alpha[i] = sum - penalty;
+                    }
+                } else {
+                    if alpha[j] < T::zero() {
+                        alpha[j] = T::zero();
+                        alpha[i] = sum;
+                    }
+                    if alpha[i] < T::zero() {
+                        alpha[i] = T::zero();
+                        alpha[j] = sum;
+                    }
+                }
+            }
+
+            let (delta_i, delta_j) = (alpha[i] - old_i, alpha[j] - old_j);
+            for (t, g) in gradient.iter_mut().enumerate() {
+                *g += q(t, i) * delta_i + q(t, j) * delta_j;
+            }
+            iterations += 1;
+        }
+
+        let two = T::from_f64(2.0).unwrap();
+        let objective_value = alpha
+            .iter()
+            .zip(&gradient)
+            .fold(T::zero(), |acc, (&a, &g)| acc + a * (g - T::one()))
+            / two;
+        let mut stats = SolveStats::new();
+        stats.solve_time = timer.elapsed();
+        Ok(SvmModel {
+            bias: bias(&alpha, labels, &gradient, penalty),
+            alpha,
+            labels: labels.to_vec(),
+            weights: None,
+            status,
+            objective_value,
+            iterations,
+            stats,
+        })
+    }
+}
+
+// Average of y_i G_i over the free multipliers, or the midpoint of the
+// feasible interval when every multiplier sits on a bound.
+fn bias<T: RealNumber + FromPrimitive>(alpha: &[T], labels: &[T], gradient: &[T], penalty: T) -> T {
+    let (mut upper, mut lower) = (T::infinity(), T::neg_infinity());
+    let (mut free, mut sum) = (0usize, T::zero());
+    for ((&a, &y), &g) in alpha.iter().zip(labels).zip(gradient) {
+        let yg = y * g;
+        let positive = y > T::zero();
+        if a >= penalty {
+            if positive {
+                lower = lower.max(yg);
+            } else {
+                upper = upper.min(yg);
+            }
+        } else if a <= T::zero() {
+            if positive {
+                upper = upper.min(yg);
+            } else {
+                lower = lower.max(yg);
+            }
+        } else {
+            free += 1;
+            sum += yg;
+        }
+    }
+    let rho = if free > 0 {
+        sum / T::from_usize(free).unwrap()
+    } else if upper.is_finite() && lower.is_finite() {
+        (upper + lower) / T::from_f64(2.0).unwrap()
+    } else {
+        T::zero()
+    };
+    -rho
+}
@@ -0,0 +1,162 @@
+use cvxrs_algos::active_set::ActiveSetSolver;
+use cvxrs_core::math::Scalar;
+use cvxrs_core::options::SolveOptions;
+use cvxrs_core::problem::{
+    Bounds, InequalityConstraints, Names, ObjectiveSense, ProblemLP, ProblemQP,
+};
+use cvxrs_core::solution::{Certificate, Status};
+use cvxrs_core::stats::ActiveSet;
+
+mod common;
+use common::{dense_csc, vertex_lp};
+
+#[test]
+#[cfg_attr(feature = "f32", ignore = "needs f64 accuracy")]
+fn qp_solution_and_active_set_are_exact() {
+    // min x0² + x1² - 3x0 with x0 + x1 <= 1 and x >= 0: x = (1, 0), with
+    // the inequality and the lower bound on x1 active.
+    let problem = ProblemQP {
+        quadratic: dense_csc(&[&[2.0, 0.0], &[0.0, 2.0]]),
+        linear: vec![-3.0, 0.0],
+        inequalities: Some(InequalityConstraints {
+            matrix: dense_csc(&[&[1.0, 1.0]]),
+            rhs: vec![1.0],
+        }),
+        equalities: None,
+        bounds: Some(Bounds {
+            lower: vec![0.0, 0.0],
+            upper: vec![Scalar::INFINITY, Scalar::INFINITY],
+        }),
+        groups: Vec::new(),
+        integrality: Vec::new(),
+        names: Names::default(),
+        sense: ObjectiveSense::Minimize,
+        offset: 0.0,
+        quadratic_constraints: Vec::new(),
+    };
+    let solution = ActiveSetSolver::new()
+        .solve_qp(&problem, &SolveOptions::default())
+        .expect("solve");
+    assert_eq!(solution.status, Status::Optimal);
+    assert!((solution.primal[0] - 1.0).abs() < 1e-12);
+    assert!(solution.primal[1].abs() < 1e-12);
+    assert!((solution.objective_value + 2.0).abs() < 1e-12);
+    assert_eq!(
+        solution.stats.active_set,
+        Some(ActiveSet {
+            inequalities: vec![0],
+            lower_bounds: vec![1],
+            upper_bounds: Vec::new(),
+        })
+    );
+    // Px + q + Gᵀz + w = 0 with z = 1 and the lower bound on x1 pushing back.
+    let (z, w) = (solution.inequality_dual[0], &solution.inequality_dual[1..]);
+    assert!((z - 1.0).abs() < 1e-12);
+    assert!(w[0].abs() < 1e-12);
+    assert!((w[1] + 1.0).abs() < 1e-12);
+}
+
+#[test]
+#[cfg_attr(feature = "f32", ignore = "needs f64 accuracy")]
+fn lp_ends_on_a_vertex() {
+    let problem = vertex_lp();
+    let solution = ActiveSetSolver::new()
+        .solve_lp(&problem, &SolveOptions::default())
+        .expect("solve");
+    assert_eq!(solution.status, Status::Optimal);
+    assert!((solution.primal[0] - 1.0).abs() < 1e-12);
+    assert!((solution.primal[1] - 2.0).abs() < 1e-12);
+    assert!((solution.objective_value + 5.0).abs() < 1e-12);
+    let active = solution.stats.active_set.expect("active set");
+    assert_eq!(active.upper_bounds, vec![1]);
+    let y = solution.equality_dual[0];
+    let w = &solution.inequality_dual[1..];
+    assert!((-1.0 + y + w[0]).abs() < 1e-12);
+    assert!((-2.0 + y + w[1]).abs() < 1e-12);
+}
+
+#[test]
+fn reports_infeasible_and_unbounded_problems() {
+    let infeasible = ProblemLP {
+        cost: vec![1.0, 1.0],
+        equalities: None,
+        inequalities: Some(InequalityConstraints {
+            matrix: dense_csc(&[&[-1.0, -1.0]]),
+            rhs: vec![-3.0],
+        }),
+        bounds: Some(Bounds {
+            lower: vec![0.0, 0.0],
+            upper: vec![1.0, 1.0],
+        }),
+        groups: Vec::new(),
+        integrality: Vec::new(),
+        names: Names::default(),
+        sense: ObjectiveSense::Minimize,
+        offset: 0.0,
+    };
+    let solver = ActiveSetSolver::new();
+    let solution = solver
+        .solve_lp(&infeasible, &SolveOptions::default())
+        .expect("solve");
+    assert_eq!(solution.status, Status::PrimalInfeasible);
+
+    // min -2x0 + x1  s.t.  x0 - 2x1 <= 1, x >= 0: the cost falls along x0 = 2x1.
+    let unbounded = ProblemLP {
+        cost: vec![-2.0, 1.0],
+        equalities: None,
+        inequalities: Some(InequalityConstraints {
+            matrix: dense_csc(&[&[1.0, -2.0]]),
+            rhs: vec![1.0],
+        }),
+        bounds: Some(Bounds {
+            lower: vec![0.0, 0.0],
+            upper: vec![Scalar::INFINITY, Scalar::INFINITY],
+        }),
+        groups: Vec::new(),
+        integrality: Vec::new(),
+        names: Names::default(),
+        sense: ObjectiveSense::Minimize,
+        offset: 0.0,
+    };
+    let solution = solver
+        .solve_lp(&unbounded, &SolveOptions::default())
+        .expect("solve");
+    assert_eq!(solution.status, Status::DualInfeasible);
+    let Some(Certificate::DualInfeasible { direction }) = solution.certificate else {
+        panic!("expected an improving ray, got {:?}", solution.certificate);
+    };
+    assert!(direction.iter().all(|&d| d >= -1e-12));
+    assert!(direction[0] - 2.0 * direction[1] <= 1e-12);
+    assert!((-2.0 * direction[0] + direction[1] + 1.0).abs() < 1e-12);
+}
+
+#[test]
+fn beales_degenerate_lp_does_not_cycle() {
+    // Beale's example: min -3/4 x0 + 20x1 - 1/2 x2 + 6x3 s.t.
+    // 1/4 x0 - 8x1 - x2 + 9x3 <= 0, 1/2 x0 - 12x1 - 1/2 x2 + 3x3 <= 0,
+    // x2 <= 1, x >= 0. The origin is degenerate and the textbook pivots
+    // cycle there; the optimum is -5/4 at (1, 0, 1, 0).
+    let problem = ProblemLP {
+        cost: vec![-0.75, 20.0, -0.5, 6.0],
+        equalities: None,
+        inequalities: Some(InequalityConstraints {
+            matrix: dense_csc(&[&[0.25, -8.0, -1.0, 9.0], &[0.5, -12.0, -0.5, 3.0]]),
+            rhs: vec![0.0, 0.0],
+        }),
+        bounds: Some(Bounds {
+            lower: vec![0.0; 4],
+            upper: vec![Scalar::INFINITY, Scalar::INFINITY, 1.0, Scalar::INFINITY],
+        }),
+        groups: Vec::new(),
+        integrality: Vec::new(),
+        names: Names::default(),
+        sense: ObjectiveSense::Minimize,
+        offset: 0.0,
+    };
+    let solution = ActiveSetSolver::new()
+        .solve_lp(&problem, &SolveOptions::default())
+        .expect("solve");
+    assert_eq!(solution.status, Status::Optimal);
+    assert!((solution.objective_value + 1.25).abs() < 1e-9);
+    assert!(solution.iterations < 100, "{}", solution.iterations);
+}
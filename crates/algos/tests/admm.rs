@@ -1,8 +1,20 @@
 use cvxrs_algos::admm::AdmmSolver;
+use cvxrs_core::cones::psd_index;
 use cvxrs_core::math::Scalar;
-use cvxrs_core::options::SolveOptions;
-use cvxrs_core::problem::{Bounds, CscMatrix, ProblemQP};
-use cvxrs_core::scaling::RuizScaler;
+use cvxrs_core::options::{LinsysBackend, Scaling, SolveOptions};
+use cvxrs_core::problem::{
+    Bounds, CheckpointSink, Cone, CscMatrix, EqualityConstraints, InequalityConstraints, Names,
+    ObjectiveSense, ProblemConic, ProblemLP, ProblemQP, ProblemQpOp, SolverState,
+};
+use cvxrs_core::robust::{robust_lp, RobustRow, Uncertainty};
+use cvxrs_core::scaling::{BuiltinScaler, RuizScaler};
+use cvxrs_core::stats::{BasisStatus, IterationRecord, IterationSink};
+use cvxrs_core::traits::{LinearOperator, ResidualTolerance, StoppingRule};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+mod common;
+use common::vertex_lp;
 
 fn diagonal(n: usize, value: Scalar) -> CscMatrix<Scalar> {
     let mut indptr = Vec::with_capacity(n + 1);
@@ -34,13 +46,1359 @@ fn solves_box_qp() {
             lower: vec![0.0, 0.0],
             upper: vec![1.0, 1.0],
         }),
+        groups: Vec::new(),
+        integrality: Vec::new(),
+        names: Names::default(),
+        sense: ObjectiveSense::Minimize,
+        offset: 0.0,
+        quadratic_constraints: Vec::new(),
     };
     let options = SolveOptions::<Scalar>::default();
-    let mut solver = AdmmSolver::new(options);
+    let solver = AdmmSolver::new(options);
     let mut scaler = RuizScaler::default();
     let solution = solver.solve_qp(problem, &mut scaler).expect("solve");
     assert_eq!(solution.status, cvxrs_core::solution::Status::Optimal);
     for &x in &solution.primal {
-        assert!(x >= -1e-6 && x <= 1.0 + 1e-6);
+        assert!((-1e-6..=1.0 + 1e-6).contains(&x));
+    }
+}
+
+#[test]
+#[cfg_attr(feature = "f32", ignore = "needs f64 accuracy")]
+fn polishing_recovers_the_exact_active_set() {
+    // min x0² + x1² - 3x0 - x1 with x0 + x1 <= 1 and x >= 0: x = (1, 0), with
+    // the inequality and the lower bound on x1 active.
+    let problem = ProblemQP {
+        quadratic: diagonal(2, 2.0),
+        linear: vec![-3.0, -1.0],
+        inequalities: Some(InequalityConstraints {
+            matrix: CscMatrix {
+                nrows: 1,
+                ncols: 2,
+                indptr: vec![0, 1, 2],
+                indices: vec![0, 0],
+                data: vec![1.0, 1.0],
+            },
+            rhs: vec![1.0],
+        }),
+        equalities: None,
+        bounds: Some(Bounds {
+            lower: vec![0.0, 0.0],
+            upper: vec![Scalar::INFINITY, Scalar::INFINITY],
+        }),
+        groups: Vec::new(),
+        integrality: Vec::new(),
+        names: Names::default(),
+        sense: ObjectiveSense::Minimize,
+        offset: 0.0,
+        quadratic_constraints: Vec::new(),
+    };
+    let solve = |polish| {
+        let options = SolveOptions::<Scalar> {
+            polish,
+            ..SolveOptions::with_tolerance(1e-4)
+        };
+        AdmmSolver::new(options)
+            .solve_qp(problem.clone(), &mut RuizScaler::default())
+            .expect("solve")
+    };
+    let plain = solve(false);
+    assert_eq!(plain.stats.polished, None);
+    let polished = solve(true);
+    assert_eq!(polished.stats.polished, Some(true));
+    assert_eq!(polished.iterations, plain.iterations);
+    assert!(
+        (polished.primal[0] - 1.0).abs() < 1e-6,
+        "{:?}",
+        polished.primal
+    );
+    assert!(polished.primal[1].abs() < 1e-6, "{:?}", polished.primal);
+    assert!((polished.objective_value + 2.0).abs() < 1e-6);
+}
+
+#[test]
+fn crossover_lands_on_the_optimal_vertex() {
+    let problem = vertex_lp();
+    let options = SolveOptions::<Scalar> {
+        crossover: true,
+        ..SolveOptions::with_tolerance(1e-4)
+    };
+    let solution = AdmmSolver::new(options)
+        .solve_lp(problem, &mut RuizScaler::default())
+        .expect("solve");
+    assert!(solution.stats.crossover_iterations.is_some());
+    assert_eq!(solution.primal, vec![1.0, 2.0]);
+    assert_eq!(solution.objective_value, -5.0);
+    let basis = solution.basis.expect("basis");
+    assert_eq!(
+        basis.variables,
+        vec![BasisStatus::Basic, BasisStatus::AtUpper]
+    );
+    assert_eq!(basis.inequalities, vec![BasisStatus::Basic]);
+}
+
+fn unbounded_lp() -> ProblemLP<Scalar> {
+    // min -x0 s.t. x1 <= 1: x0 is free to grow without end.
+    ProblemLP {
+        cost: vec![-1.0, 0.0],
+        equalities: None,
+        inequalities: Some(InequalityConstraints {
+            matrix: CscMatrix {
+                nrows: 1,
+                ncols: 2,
+                indptr: vec![0, 0, 1],
+                indices: vec![0],
+                data: vec![1.0],
+            },
+            rhs: vec![1.0],
+        }),
+        bounds: None,
+        groups: Vec::new(),
+        integrality: Vec::new(),
+        names: Names::default(),
+        sense: ObjectiveSense::Minimize,
+        offset: 0.0,
+    }
+}
+
+#[test]
+fn unbounded_lp_is_not_declared_optimal() {
+    // The σ term holds x0 near 1/σ while z stops moving, so only the
+    // stationarity residual -1 on x0 shows that nothing has converged.
+    let options = SolveOptions::<Scalar> {
+        max_iterations: 2000,
+        ..SolveOptions::default()
+    };
+    let solution = AdmmSolver::new(options)
+        .solve_lp(unbounded_lp(), &mut RuizScaler::default())
+        .expect("solve");
+    assert_ne!(solution.status, cvxrs_core::solution::Status::Optimal);
+    let last = solution.stats.history.last().expect("records");
+    assert!(last.dual_residual > 0.5, "{}", last.dual_residual);
+}
+
+#[test]
+fn infeasible_and_unbounded_problems_are_certified() {
+    use cvxrs_core::solution::{Certificate, Status};
+    let solution = AdmmSolver::new(SolveOptions::default())
+        .solve_lp(unbounded_lp(), &mut RuizScaler::default())
+        .expect("solve");
+    assert_eq!(solution.status, Status::DualInfeasible);
+    let Some(Certificate::DualInfeasible { direction }) = solution.certificate else {
+        panic!("expected an improving ray, got {:?}", solution.certificate);
+    };
+    // cᵀd = -1 and the ray keeps x1 <= 1.
+    assert!((direction[0] - 1.0).abs() < 1e-4, "{direction:?}");
+    assert!(direction[1] < 1e-4, "{direction:?}");
+
+    // x0 + x1 <= 1 and -x0 - x1 <= -3 cannot both hold.
+    let infeasible = ProblemLP::<Scalar> {
+        cost: vec![1.0, 1.0],
+        equalities: None,
+        inequalities: Some(InequalityConstraints {
+            matrix: CscMatrix {
+                nrows: 2,
+                ncols: 2,
+                indptr: vec![0, 2, 4],
+                indices: vec![0, 1, 0, 1],
+                data: vec![1.0, -1.0, 1.0, -1.0],
+            },
+            rhs: vec![1.0, -3.0],
+        }),
+        bounds: None,
+        groups: Vec::new(),
+        integrality: Vec::new(),
+        names: Names::default(),
+        sense: ObjectiveSense::Minimize,
+        offset: 0.0,
+    };
+    let solution = AdmmSolver::new(SolveOptions::default())
+        .solve_lp(infeasible, &mut RuizScaler::default())
+        .expect("solve");
+    assert_eq!(solution.status, Status::PrimalInfeasible);
+    let Some(Certificate::PrimalInfeasible {
+        inequality_dual: z, ..
+    }) = solution.certificate
+    else {
+        panic!(
+            "expected a Farkas certificate, got {:?}",
+            solution.certificate
+        );
+    };
+    // z >= 0 with Gᵀz = 0 and hᵀz = -1.
+    assert!(z.iter().all(|&value| value >= -1e-6), "{z:?}");
+    assert!((z[0] - z[1]).abs() < 1e-4, "{z:?}");
+    assert!((z[0] - 3.0 * z[1] + 1.0).abs() < 1e-4, "{z:?}");
+}
+
+#[cfg(feature = "mixed-precision")]
+#[test]
+fn mixed_precision_finishes_past_single_precision_accuracy() {
+    // The polishing problem again, in f64 whatever `Scalar` is, with a
+    // tolerance f32 residuals cannot certify on their own.
+    let problem = ProblemQP::<f64> {
+        quadratic: CscMatrix {
+            nrows: 2,
+            ncols: 2,
+            indptr: vec![0, 1, 2],
+            indices: vec![0, 1],
+            data: vec![2.0, 2.0],
+        },
+        linear: vec![-3.0, -1.0],
+        inequalities: Some(InequalityConstraints {
+            matrix: CscMatrix {
+                nrows: 1,
+                ncols: 2,
+                indptr: vec![0, 1, 2],
+                indices: vec![0, 0],
+                data: vec![1.0, 1.0],
+            },
+            rhs: vec![1.0],
+        }),
+        equalities: None,
+        bounds: Some(Bounds {
+            lower: vec![0.0, 0.0],
+            upper: vec![f64::INFINITY, f64::INFINITY],
+        }),
+        groups: Vec::new(),
+        integrality: Vec::new(),
+        names: Names::default(),
+        sense: ObjectiveSense::Minimize,
+        offset: 0.0,
+        quadratic_constraints: Vec::new(),
+    };
+    let options = SolveOptions::<f64> {
+        mixed_precision: true,
+        ..SolveOptions::with_tolerance(1e-10)
+    };
+    let solution = AdmmSolver::new(options)
+        .solve_qp(problem, &mut RuizScaler::default())
+        .expect("solve");
+    assert_eq!(solution.status, cvxrs_core::solution::Status::Optimal);
+    assert!(
+        (solution.primal[0] - 1.0).abs() < 1e-8,
+        "{:?}",
+        solution.primal
+    );
+    assert!(solution.primal[1].abs() < 1e-8, "{:?}", solution.primal);
+    // Both stages are in the history, numbered as one run.
+    let iterations: Vec<usize> = solution
+        .stats
+        .history
+        .iter()
+        .map(|record| record.iteration)
+        .collect();
+    assert!(iterations.windows(2).all(|pair| pair[0] < pair[1]));
+    assert!(iterations.last().unwrap() < &solution.iterations);
+}
+
+#[test]
+fn relaxation_outside_the_open_interval_is_rejected() {
+    let problem = ProblemQP {
+        quadratic: diagonal(2, 1.0),
+        linear: vec![-1.0, 1.0],
+        inequalities: None,
+        equalities: None,
+        bounds: Some(Bounds {
+            lower: vec![0.0, 0.0],
+            upper: vec![1.0, 1.0],
+        }),
+        groups: Vec::new(),
+        integrality: Vec::new(),
+        names: Names::default(),
+        sense: ObjectiveSense::Minimize,
+        offset: 0.0,
+        quadratic_constraints: Vec::new(),
+    };
+    let solve = |admm_relaxation| {
+        let options = SolveOptions::<Scalar> {
+            admm_relaxation,
+            ..SolveOptions::default()
+        };
+        AdmmSolver::new(options).solve_qp(problem.clone(), &mut RuizScaler::default())
+    };
+    assert!(solve(2.0).is_err());
+    assert!(solve(0.0).is_err());
+    let relaxed = solve(1.8).expect("solve");
+    let plain = solve(1.0).expect("solve");
+    assert_eq!(relaxed.status, cvxrs_core::solution::Status::Optimal);
+    assert!((relaxed.primal[0] - plain.primal[0]).abs() < 1e-5);
+}
+
+#[test]
+fn adaptive_rho_refactors_only_on_recorded_updates() {
+    let problem = ProblemQP {
+        quadratic: diagonal(3, 100.0),
+        linear: vec![-150.0, 20.0, -40.0],
+        inequalities: None,
+        equalities: None,
+        bounds: Some(Bounds {
+            lower: vec![0.0, 0.0, 0.0],
+            upper: vec![1.0, 1.0, 1.0],
+        }),
+        groups: Vec::new(),
+        integrality: Vec::new(),
+        names: Names::default(),
+        sense: ObjectiveSense::Minimize,
+        offset: 0.0,
+        quadratic_constraints: Vec::new(),
+    };
+    let options = SolveOptions::<Scalar> {
+        admm_rho: 1e-4,
+        rho_update_interval: 5,
+        ..SolveOptions::default()
+    };
+    let solution = AdmmSolver::new(options)
+        .solve_qp(problem, &mut RuizScaler::default())
+        .expect("solve");
+    assert_eq!(solution.status, cvxrs_core::solution::Status::Optimal);
+    let updates = &solution.stats.rho_updates;
+    assert!(!updates.is_empty(), "a tiny rho should be raised");
+    assert_eq!(solution.stats.factorizations, 1 + updates.len());
+    for update in updates {
+        assert_eq!((update.iteration + 1) % 5, 0);
+        let record = &solution.stats.history[update.iteration];
+        assert_eq!(record.rho, update.previous);
+        if let Some(next) = solution.stats.history.get(update.iteration + 1) {
+            assert_eq!(next.rho, update.rho);
+        }
+    }
+}
+
+#[test]
+fn sampled_convergence_checks_stop_at_the_next_sample() {
+    let problem = ProblemQP {
+        quadratic: diagonal(3, 2.0),
+        linear: vec![-1.0, 0.5, -3.0],
+        inequalities: None,
+        equalities: None,
+        bounds: Some(Bounds {
+            lower: vec![0.0, 0.0, 0.0],
+            upper: vec![1.0, 1.0, 1.0],
+        }),
+        groups: Vec::new(),
+        integrality: Vec::new(),
+        names: Names::default(),
+        sense: ObjectiveSense::Minimize,
+        offset: 0.0,
+        quadratic_constraints: Vec::new(),
+    };
+    let solve = |check_every| {
+        let options = SolveOptions::<Scalar> {
+            check_every,
+            ..SolveOptions::default()
+        };
+        AdmmSolver::new(options)
+            .solve_qp(problem.clone(), &mut RuizScaler::default())
+            .expect("solve")
+    };
+    let every = solve(1);
+    let sampled = solve(4);
+    assert_eq!(sampled.status, cvxrs_core::solution::Status::Optimal);
+    // The iterates do not depend on the checks, so the sampled solve stops
+    // at the first multiple of 4 past the point where the other one did.
+    assert_eq!(sampled.iterations, every.iterations.div_ceil(4) * 4);
+    assert_eq!(sampled.stats.history.len(), sampled.iterations / 4);
+    for record in &sampled.stats.history {
+        assert_eq!((record.iteration + 1) % 4, 0);
+    }
+    for (a, b) in sampled.primal.iter().zip(&every.primal) {
+        assert!((a - b).abs() < 1e-6);
+    }
+
+    let options = SolveOptions::<Scalar> {
+        check_every: 0,
+        ..SolveOptions::default()
+    };
+    assert!(AdmmSolver::new(options)
+        .solve_qp(problem, &mut RuizScaler::default())
+        .is_err());
+}
+
+#[test]
+fn stopping_criterion_replaces_the_tolerance_test() {
+    let problem = ProblemQP {
+        quadratic: diagonal(3, 2.0),
+        linear: vec![-1.0, 0.5, -3.0],
+        inequalities: None,
+        equalities: None,
+        bounds: Some(Bounds {
+            lower: vec![0.0, 0.0, 0.0],
+            upper: vec![1.0, 1.0, 1.0],
+        }),
+        groups: Vec::new(),
+        integrality: Vec::new(),
+        names: Names::default(),
+        sense: ObjectiveSense::Minimize,
+        offset: 0.0,
+        quadratic_constraints: Vec::new(),
+    };
+    let solve = |rule: Option<StoppingRule<Scalar>>| {
+        let mut solver = AdmmSolver::new(SolveOptions::default());
+        if let Some(rule) = rule {
+            solver = solver.with_stopping_criterion(rule);
+        }
+        solver
+            .solve_qp(problem.clone(), &mut RuizScaler::default())
+            .expect("solve")
+    };
+    let default = solve(None);
+    let explicit = solve(Some(Arc::new(ResidualTolerance)));
+    assert_eq!(explicit.iterations, default.iterations);
+    assert_eq!(explicit.primal, default.primal);
+
+    // A looser rule that ignores the dual residual and the gap.
+    let loose: StoppingRule<Scalar> = Arc::new(
+        |record: &IterationRecord<Scalar>, _: &SolveOptions<Scalar>| {
+            record.iteration >= 2 && record.primal_residual < 1e-3
+        },
+    );
+    let early = solve(Some(loose));
+    assert_eq!(early.status, cvxrs_core::solution::Status::Optimal);
+    assert!(early.iterations < default.iterations);
+    let last = early.stats.history.last().unwrap();
+    assert!(last.primal_residual < 1e-3);
+}
+
+#[test]
+#[cfg_attr(feature = "f32", ignore = "needs f64 accuracy")]
+fn anderson_acceleration_reaches_the_same_solution() {
+    // An ill-conditioned QP with a coupling inequality on which plain ADMM
+    // needs many iterations.
+    let problem = ProblemQP {
+        quadratic: CscMatrix {
+            nrows: 3,
+            ncols: 3,
+            indptr: vec![0, 1, 2, 3],
+            indices: vec![0, 1, 2],
+            data: vec![100.0, 1.0, 0.01],
+        },
+        linear: vec![-50.0, -2.0, -1.0],
+        inequalities: Some(InequalityConstraints {
+            matrix: CscMatrix {
+                nrows: 1,
+                ncols: 3,
+                indptr: vec![0, 1, 2, 3],
+                indices: vec![0, 0, 0],
+                data: vec![1.0, 1.0, 1.0],
+            },
+            rhs: vec![2.0],
+        }),
+        equalities: None,
+        bounds: Some(Bounds {
+            lower: vec![0.0, 0.0, 0.0],
+            upper: vec![5.0, 5.0, 5.0],
+        }),
+        groups: Vec::new(),
+        integrality: Vec::new(),
+        names: Names::default(),
+        sense: ObjectiveSense::Minimize,
+        offset: 0.0,
+        quadratic_constraints: Vec::new(),
+    };
+    let solve = |anderson_memory| {
+        let options = SolveOptions::<Scalar> {
+            anderson_memory,
+            admm_adaptive_rho: false,
+            ..SolveOptions::with_tolerance(1e-7)
+        };
+        AdmmSolver::new(options)
+            .solve_qp(problem.clone(), &mut RuizScaler::default())
+            .expect("solve")
+    };
+    let plain = solve(0);
+    let accelerated = solve(5);
+    assert_eq!(plain.stats.anderson_rejections, 0);
+    assert_eq!(accelerated.status, cvxrs_core::solution::Status::Optimal);
+    assert!(accelerated.iterations < plain.iterations);
+    for (a, b) in accelerated.primal.iter().zip(&plain.primal) {
+        assert!(
+            (a - b).abs() < 1e-4,
+            "{:?} vs {:?}",
+            accelerated.primal,
+            plain.primal
+        );
+    }
+}
+
+#[test]
+fn thread_limit_does_not_change_the_result() {
+    let problem = ProblemQP {
+        quadratic: diagonal(3, 2.0),
+        linear: vec![-1.0, 0.5, -3.0],
+        inequalities: None,
+        equalities: None,
+        bounds: Some(Bounds {
+            lower: vec![0.0, 0.0, 0.0],
+            upper: vec![1.0, 1.0, 1.0],
+        }),
+        groups: Vec::new(),
+        integrality: Vec::new(),
+        names: Names::default(),
+        sense: ObjectiveSense::Minimize,
+        offset: 0.0,
+        quadratic_constraints: Vec::new(),
+    };
+    let solve = |num_threads| {
+        let options = SolveOptions::<Scalar> {
+            num_threads,
+            ..SolveOptions::default()
+        };
+        AdmmSolver::new(options)
+            .solve_qp(problem.clone(), &mut RuizScaler::default())
+            .expect("solve")
+    };
+    let default = solve(None);
+    let limited = solve(Some(1));
+    assert_eq!(default.primal, limited.primal);
+    assert_eq!(default.iterations, limited.iterations);
+}
+
+#[test]
+fn wide_problems_match_the_closed_form() {
+    // Large enough for the parallel kernels of the rayon feature. The
+    // solution of min Σ (x_i - c_i)² over the unit box is the clipped c.
+    let n = 600;
+    let target: Vec<Scalar> = (0..n).map(|i| (i % 4) as Scalar * 0.5).collect();
+    let problem = ProblemQP {
+        quadratic: diagonal(n, 2.0),
+        linear: target.iter().map(|&c| -2.0 * c).collect(),
+        inequalities: None,
+        equalities: None,
+        bounds: Some(Bounds {
+            lower: vec![0.0; n],
+            upper: vec![1.0; n],
+        }),
+        groups: Vec::new(),
+        integrality: Vec::new(),
+        names: Names::default(),
+        sense: ObjectiveSense::Minimize,
+        offset: 0.0,
+        quadratic_constraints: Vec::new(),
+    };
+    let options = SolveOptions::<Scalar> {
+        num_threads: Some(2),
+        ..SolveOptions::default()
+    };
+    let solution = AdmmSolver::new(options)
+        .solve_qp(problem, &mut RuizScaler::default())
+        .expect("solve");
+    for (&x, &c) in solution.primal.iter().zip(&target) {
+        assert!((x - c.min(1.0)).abs() < 1e-3);
+    }
+}
+
+#[test]
+fn equalities_hold_from_the_first_iteration() {
+    let problem = ProblemQP {
+        quadratic: diagonal(3, 2.0),
+        linear: vec![-1.0, 0.5, -3.0],
+        inequalities: None,
+        equalities: Some(EqualityConstraints {
+            matrix: CscMatrix {
+                nrows: 1,
+                ncols: 3,
+                indptr: vec![0, 1, 2, 3],
+                indices: vec![0, 0, 0],
+                data: vec![1.0, 2.0, 1.0],
+            },
+            rhs: vec![1.5],
+        }),
+        bounds: Some(Bounds {
+            lower: vec![0.0, 0.0, 0.0],
+            upper: vec![1.0, 1.0, 1.0],
+        }),
+        groups: Vec::new(),
+        integrality: Vec::new(),
+        names: Names::default(),
+        sense: ObjectiveSense::Minimize,
+        offset: 0.0,
+        quadratic_constraints: Vec::new(),
+    };
+    let options = SolveOptions::<Scalar> {
+        max_iterations: 1,
+        ..SolveOptions::default()
+    };
+    let solution = AdmmSolver::new(options)
+        .solve_qp(problem, &mut RuizScaler::default())
+        .expect("solve");
+    let x = &solution.primal;
+    assert!((x[0] + 2.0 * x[1] + x[2] - 1.5).abs() < 1e-8);
+}
+
+#[test]
+#[cfg_attr(feature = "f32", ignore = "needs f64 accuracy")]
+fn proximal_term_handles_variables_only_the_equalities_see() {
+    // min ½x0² + x1 s.t. x0 + x1 = 1 and x0 <= 5: x1 has no curvature and
+    // no inequality row, so P + ρGᵀG is singular. The optimum is (1, 0).
+    let problem = ProblemQP {
+        quadratic: CscMatrix {
+            nrows: 2,
+            ncols: 2,
+            indptr: vec![0, 1, 1],
+            indices: vec![0],
+            data: vec![1.0],
+        },
+        linear: vec![0.0, 1.0],
+        inequalities: Some(InequalityConstraints {
+            matrix: CscMatrix {
+                nrows: 1,
+                ncols: 2,
+                indptr: vec![0, 1, 1],
+                indices: vec![0],
+                data: vec![1.0],
+            },
+            rhs: vec![5.0],
+        }),
+        equalities: Some(EqualityConstraints {
+            matrix: CscMatrix {
+                nrows: 1,
+                ncols: 2,
+                indptr: vec![0, 1, 2],
+                indices: vec![0, 0],
+                data: vec![1.0, 1.0],
+            },
+            rhs: vec![1.0],
+        }),
+        bounds: None,
+        groups: Vec::new(),
+        integrality: Vec::new(),
+        names: Names::default(),
+        sense: ObjectiveSense::Minimize,
+        offset: 0.0,
+        quadratic_constraints: Vec::new(),
+    };
+    let without = SolveOptions::<Scalar> {
+        sigma: 0.0,
+        ..SolveOptions::default()
+    };
+    // Without it the zero pivot is regularized away and refined against.
+    let solution = AdmmSolver::new(without)
+        .solve_qp(problem.clone(), &mut RuizScaler::default())
+        .expect("solve");
+    assert_eq!(solution.status, cvxrs_core::solution::Status::Optimal);
+    assert!((solution.primal[0] - 1.0).abs() < 1e-5);
+    let regularization = solution.stats.regularization.expect("regularization stats");
+    assert!(regularization.dynamic_pivots > 0);
+    assert!(regularization.max_perturbation > 0.0);
+
+    let solution = AdmmSolver::new(SolveOptions::with_tolerance(1e-8))
+        .solve_qp(problem.clone(), &mut RuizScaler::default())
+        .expect("solve");
+    assert_eq!(solution.status, cvxrs_core::solution::Status::Optimal);
+    assert!((solution.primal[0] - 1.0).abs() < 1e-5);
+    assert!(solution.primal[1].abs() < 1e-5);
+    assert!((solution.objective_value - 0.5).abs() < 1e-5);
+
+    let negative = SolveOptions::<Scalar> {
+        sigma: -1.0,
+        ..SolveOptions::default()
+    };
+    assert!(AdmmSolver::new(negative)
+        .solve_qp(problem, &mut RuizScaler::default())
+        .is_err());
+}
+
+#[test]
+#[cfg_attr(feature = "f32", ignore = "needs f64 accuracy")]
+fn iterative_backends_match_the_direct_solve() {
+    // min ½‖x‖² - x0 - 2x1 - 3x2 s.t. x0 + x1 + x2 = 1, x in [0, 1]³.
+    let problem = ProblemQP {
+        quadratic: diagonal(3, 1.0),
+        linear: vec![-1.0, -2.0, -3.0],
+        inequalities: None,
+        equalities: Some(EqualityConstraints {
+            matrix: CscMatrix {
+                nrows: 1,
+                ncols: 3,
+                indptr: vec![0, 1, 2, 3],
+                indices: vec![0, 0, 0],
+                data: vec![1.0, 1.0, 1.0],
+            },
+            rhs: vec![1.0],
+        }),
+        bounds: Some(Bounds {
+            lower: vec![0.0; 3],
+            upper: vec![1.0; 3],
+        }),
+        groups: Vec::new(),
+        integrality: Vec::new(),
+        names: Names::default(),
+        sense: ObjectiveSense::Minimize,
+        offset: 0.0,
+        quadratic_constraints: Vec::new(),
+    };
+    let solve = |backend| {
+        let options = SolveOptions::<Scalar> {
+            linsys_backend: backend,
+            ..SolveOptions::with_tolerance(1e-8)
+        };
+        AdmmSolver::new(options)
+            .solve_qp(problem.clone(), &mut RuizScaler::default())
+            .expect("solve")
+    };
+    let direct = solve(LinsysBackend::Direct);
+    assert!(direct.stats.condition_estimate.expect("condition estimate") >= 1.0);
+    for backend in [LinsysBackend::ConjugateGradient, LinsysBackend::Minres] {
+        let indirect = solve(backend);
+        assert!(indirect.stats.condition_estimate.is_none());
+        assert_eq!(indirect.status, cvxrs_core::solution::Status::Optimal);
+        for (a, b) in direct.primal.iter().zip(&indirect.primal) {
+            assert!((a - b).abs() < 1e-5);
+        }
+        assert!((indirect.primal[2] - 1.0).abs() < 1e-5);
+        let krylov = indirect.stats.krylov.expect("iteration counts");
+        assert_eq!(krylov.unconverged, 0);
+    }
+}
+
+// The problem above with x0 measured in hundredths and the equality row
+// multiplied by 1e3: x = (0, 0, 1) with objective -2.5.
+fn badly_scaled() -> ProblemQP<Scalar> {
+    ProblemQP {
+        quadratic: CscMatrix::from_triplets(3, 3, &[(0, 0, 1e-4), (1, 1, 1.0), (2, 2, 1.0)])
+            .unwrap(),
+        linear: vec![-1e-2, -2.0, -3.0],
+        inequalities: None,
+        equalities: Some(EqualityConstraints {
+            matrix: CscMatrix::from_dense(1, 3, &[10.0, 1e3, 1e3]).unwrap(),
+            rhs: vec![1e3],
+        }),
+        bounds: Some(Bounds {
+            lower: vec![0.0; 3],
+            upper: vec![100.0, 1.0, 1.0],
+        }),
+        groups: Vec::new(),
+        integrality: Vec::new(),
+        names: Names::default(),
+        sense: ObjectiveSense::Minimize,
+        offset: 0.0,
+        quadratic_constraints: Vec::new(),
+    }
+}
+
+#[test]
+#[cfg_attr(feature = "f32", ignore = "needs f64 accuracy")]
+fn every_scaling_reaches_the_same_solution() {
+    let problem = badly_scaled();
+    let solve = |scaling| {
+        let options = SolveOptions::<Scalar> {
+            scaling,
+            ..SolveOptions::with_tolerance(1e-8)
+        };
+        AdmmSolver::new(options)
+            .solve_qp(problem.clone(), &mut BuiltinScaler::new(scaling))
+            .expect("solve")
+    };
+    let ruiz = solve(Scaling::Ruiz);
+    assert_eq!(ruiz.status, cvxrs_core::solution::Status::Optimal);
+    for scaling in [Scaling::Geometric, Scaling::ModifiedRuiz] {
+        let other = solve(scaling);
+        assert_eq!(other.status, cvxrs_core::solution::Status::Optimal);
+        for (a, b) in ruiz.primal.iter().zip(&other.primal) {
+            assert!((a - b).abs() < 1e-4, "{scaling:?}: {a} vs {b}");
+        }
+        assert!((other.objective_value + 2.5).abs() < 1e-4);
+        // The equality multiplier leads the stacked duals.
+        let (a, b) = (ruiz.inequality_dual[0], other.inequality_dual[0]);
+        assert!(
+            (a - b).abs() < 1e-4 * a.abs().max(1.0),
+            "{scaling:?}: {a} vs {b}"
+        );
+    }
+}
+
+#[test]
+#[cfg_attr(feature = "f32", ignore = "needs f64 accuracy")]
+fn records_measure_the_original_problem() {
+    let problem = badly_scaled();
+    let options = SolveOptions::<Scalar> {
+        polish: false,
+        ..SolveOptions::with_tolerance(1e-8)
+    };
+    let solution = AdmmSolver::new(options)
+        .solve_qp(problem.clone(), &mut RuizScaler::default())
+        .expect("solve");
+    assert_eq!(solution.status, cvxrs_core::solution::Status::Optimal);
+    let last = solution.stats.history.last().expect("records");
+    assert!((last.primal_objective - solution.objective_value).abs() < 1e-12);
+
+    // Only the equality row and the box can be violated, and the record
+    // takes the worse of the two at the point returned.
+    let x = &solution.primal;
+    let eq = problem.equalities.as_ref().unwrap();
+    let mut ax = vec![0.0];
+    eq.matrix.apply(x, &mut ax);
+    let bounds = problem.bounds.as_ref().unwrap();
+    let outside = x
+        .iter()
+        .zip(bounds.lower.iter().zip(&bounds.upper))
+        .fold(0.0, |worst: Scalar, (&x, (&l, &u))| {
+            worst.max(l - x).max(x - u)
+        });
+    let violation = (ax[0] - eq.rhs[0]).abs().max(outside);
+    assert!(
+        (last.primal_residual - violation).abs() < 1e-12,
+        "{} vs {violation}",
+        last.primal_residual
+    );
+}
+
+#[test]
+fn relative_tolerance_follows_the_size_of_the_data() {
+    // The same box QP at a scale of 1e4: the absolute test alone asks for
+    // ten more digits than the relative one.
+    let problem = ProblemQP {
+        quadratic: diagonal(3, 1.0),
+        linear: vec![-1e4, -2e4, -3e4],
+        inequalities: None,
+        equalities: None,
+        bounds: Some(Bounds {
+            lower: vec![0.0; 3],
+            upper: vec![1.5e4; 3],
+        }),
+        groups: Vec::new(),
+        integrality: Vec::new(),
+        names: Names::default(),
+        sense: ObjectiveSense::Minimize,
+        offset: 0.0,
+        quadratic_constraints: Vec::new(),
+    };
+    let records: Arc<Mutex<Vec<IterationRecord<Scalar>>>> = Arc::default();
+    let log = Arc::clone(&records);
+    let sink: IterationSink<Scalar> =
+        Arc::new(move |record| log.lock().unwrap().push(record.clone()));
+    let relative = AdmmSolver::new(SolveOptions::with_tolerance(1e-6))
+        .with_iteration_log(sink)
+        .solve_qp(problem.clone(), &mut RuizScaler::default())
+        .expect("solve");
+    assert_eq!(relative.status, cvxrs_core::solution::Status::Optimal);
+    for (x, expected) in relative.primal.iter().zip([1e4, 1.5e4, 1.5e4]) {
+        assert!((x - expected).abs() < 1e-6 * expected * 10.0);
+    }
+    let last = records.lock().unwrap().last().unwrap().clone();
+    assert!(last.primal_scale > 1.0 && last.dual_scale > 1.0);
+
+    let absolute = SolveOptions::<Scalar> {
+        eps_rel: 0.0,
+        ..SolveOptions::with_tolerance(1e-6)
+    };
+    let absolute = AdmmSolver::new(absolute)
+        .solve_qp(problem, &mut RuizScaler::default())
+        .expect("solve");
+    assert!(absolute.iterations > relative.iterations);
+}
+
+#[test]
+fn iteration_log_sees_every_record() {
+    let problem = ProblemQP {
+        quadratic: diagonal(2, 4.0),
+        linear: vec![-1.0, -1.0],
+        inequalities: None,
+        equalities: None,
+        bounds: Some(Bounds {
+            lower: vec![0.0, 0.0],
+            upper: vec![1.0, 1.0],
+        }),
+        groups: Vec::new(),
+        integrality: Vec::new(),
+        names: Names::default(),
+        sense: ObjectiveSense::Minimize,
+        offset: 0.0,
+        quadratic_constraints: Vec::new(),
+    };
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let log = seen.clone();
+    let sink: IterationSink<Scalar> = Arc::new(move |record| {
+        log.lock().unwrap().push(record.iteration);
+    });
+    let solution = AdmmSolver::new(SolveOptions::default())
+        .with_iteration_log(sink)
+        .solve_qp(problem, &mut RuizScaler::default())
+        .expect("solve");
+    let expected: Vec<usize> = solution
+        .stats
+        .history
+        .iter()
+        .map(|record| record.iteration)
+        .collect();
+    assert_eq!(*seen.lock().unwrap(), expected);
+}
+
+#[test]
+fn resumed_solve_continues_the_interrupted_one() {
+    let problem = ProblemQP {
+        quadratic: diagonal(3, 1.0),
+        linear: vec![-2.0, 0.5, -0.3],
+        inequalities: None,
+        equalities: None,
+        bounds: Some(Bounds {
+            lower: vec![0.0, 0.0, 0.0],
+            upper: vec![1.0, 1.0, 1.0],
+        }),
+        groups: Vec::new(),
+        integrality: Vec::new(),
+        names: Names::default(),
+        sense: ObjectiveSense::Minimize,
+        offset: 0.0,
+        quadratic_constraints: Vec::new(),
+    };
+    let options = |max_iterations| SolveOptions::<Scalar> {
+        max_iterations,
+        eps_abs: 1e-12,
+        ..SolveOptions::default()
+    };
+    let full = AdmmSolver::new(options(60))
+        .solve_qp(problem.clone(), &mut RuizScaler::default())
+        .expect("solve");
+
+    for interrupted_at in [5, 10, 15] {
+        let saved: Arc<Mutex<Option<SolverState<Scalar>>>> = Arc::default();
+        let slot = Arc::clone(&saved);
+        let sink: CheckpointSink<Scalar> = Arc::new(move |state| {
+            *slot.lock().unwrap() = Some(state.clone());
+            Ok(())
+        });
+        AdmmSolver::new(options(interrupted_at))
+            .with_checkpoint(Duration::from_secs(3600), sink)
+            .solve_qp(problem.clone(), &mut RuizScaler::default())
+            .expect("interrupted solve");
+        let state = saved.lock().unwrap().take().expect("final checkpoint");
+        assert_eq!(state.iteration, interrupted_at);
+
+        let resumed = AdmmSolver::new(options(60))
+            .with_resume(state)
+            .solve_qp(problem.clone(), &mut RuizScaler::default())
+            .expect("resumed solve");
+        assert_eq!(resumed.primal, full.primal);
+        assert_eq!(resumed.iterations, full.iterations);
+    }
+}
+
+#[test]
+fn workspace_estimate_counts_dense_blocks() {
+    let scalar = std::mem::size_of::<Scalar>() as u64;
+    let small = cvxrs_algos::admm::workspace_bytes::<Scalar>(10, 20);
+    assert_eq!(
+        small,
+        (20 * 10 + 100 + (50 + 200) + 450 + 8 * 10 + 6 * 20) * scalar
+    );
+    let huge = cvxrs_algos::admm::workspace_bytes::<Scalar>(100_000, 200_000);
+    assert!(huge / scalar > 64 * 1024 * 1024 * 1024);
+}
+
+#[test]
+#[cfg_attr(feature = "f32", ignore = "needs f64 accuracy")]
+fn small_sdp_finds_the_smallest_eigenvalue() {
+    // min <C, X> s.t. trace(X) = 1, X PSD, whose value is the smallest
+    // eigenvalue of C, here 1 with eigenvector (1, -1, 0)/√2.
+    let c = [[2.0, 1.0, 0.0], [1.0, 2.0, 0.0], [0.0, 0.0, 3.0]];
+    let sqrt2 = Scalar::sqrt(2.0);
+    let mut cost = vec![0.0; 6];
+    let mut indptr = vec![0];
+    let mut indices = Vec::new();
+    let mut data = Vec::new();
+    // C is symmetric, so its rows double as columns.
+    for (col, column) in c.iter().enumerate() {
+        for (row, &value) in column.iter().enumerate().take(col + 1) {
+            let idx = psd_index(row, col);
+            cost[idx] = if row == col { value } else { sqrt2 * value };
+            // Row 0 is the trace; rows 1..7 ask for b - Ax = x in the PSD cone.
+            if row == col {
+                indices.push(0);
+                data.push(1.0);
+            }
+            indices.push(1 + idx);
+            data.push(-1.0);
+            indptr.push(indices.len());
+        }
+    }
+    // Columns were pushed in PSD order, which is the variable order.
+    let problem = ProblemConic {
+        quadratic: None,
+        cost,
+        matrix: CscMatrix {
+            nrows: 7,
+            ncols: 6,
+            indptr,
+            indices,
+            data,
+        },
+        rhs: vec![1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0],
+        cones: vec![Cone::Zero(1), Cone::PositiveSemidefinite(3)],
+    };
+    let options = SolveOptions::<Scalar>::with_tolerance(1e-7);
+    let solution = AdmmSolver::new(options)
+        .solve_conic(problem)
+        .expect("solve");
+    assert_eq!(solution.status, cvxrs_core::solution::Status::Optimal);
+    assert!((solution.objective_value - 1.0).abs() < 1e-4);
+    let x = &solution.primal;
+    assert!((x[psd_index(0, 0)] - 0.5).abs() < 1e-3);
+    assert!((x[psd_index(0, 1)] + 0.5 * sqrt2).abs() < 1e-3);
+    assert!(x[psd_index(2, 2)].abs() < 1e-3);
+    // The trace row keeps its multiplier in the original row order.
+    assert!((solution.inequality_dual[0] + 1.0).abs() < 1e-3);
+}
+
+#[test]
+fn log_sum_exp_epigraph_matches_the_closed_form() {
+    // min t s.t. e^(0 - t) <= u0, e^(1 - t) <= u1, u0 + u1 <= 1, so that
+    // t = log(e^0 + e^1). Variables (t, u0, u1); each exponential block asks
+    // for b - Ax = (a_i - t, 1, u_i) in the cone.
+    let problem = ProblemConic {
+        quadratic: None,
+        cost: vec![1.0, 0.0, 0.0],
+        matrix: CscMatrix {
+            nrows: 7,
+            ncols: 3,
+            indptr: vec![0, 2, 4, 6],
+            indices: vec![1, 4, 0, 3, 0, 6],
+            data: vec![1.0, 1.0, 1.0, -1.0, 1.0, -1.0],
+        },
+        rhs: vec![1.0, 0.0, 1.0, 0.0, 1.0, 1.0, 0.0],
+        cones: vec![Cone::NonNegative(1), Cone::Exponential, Cone::Exponential],
+    };
+    let options = SolveOptions::<Scalar>::with_tolerance(1e-7);
+    let solution = AdmmSolver::new(options)
+        .solve_conic(problem)
+        .expect("solve");
+    assert_eq!(solution.status, cvxrs_core::solution::Status::Optimal);
+    let expected = (1.0 + std::f64::consts::E as Scalar).ln();
+    assert!((solution.primal[0] - expected).abs() < 1e-4);
+    assert!((solution.primal[1] + solution.primal[2] - 1.0).abs() < 1e-4);
+}
+
+#[test]
+fn robust_counterpart_shrinks_the_feasible_set() {
+    // max x0 + x1 over x >= 0 and (1 + u0) x0 + (1 + u1) x1 <= 2 for every
+    // ‖u‖ <= 0.5; by symmetry x0 = x1 = 2 / (2 + 0.5√2).
+    let nominal = ProblemLP {
+        cost: vec![-1.0, -1.0],
+        inequalities: Some(InequalityConstraints {
+            matrix: CscMatrix {
+                nrows: 1,
+                ncols: 2,
+                indptr: vec![0, 1, 2],
+                indices: vec![0, 0],
+                data: vec![1.0, 1.0],
+            },
+            rhs: vec![2.0],
+        }),
+        equalities: None,
+        bounds: Some(Bounds {
+            lower: vec![0.0, 0.0],
+            upper: vec![Scalar::INFINITY, Scalar::INFINITY],
+        }),
+        groups: Vec::new(),
+        integrality: Vec::new(),
+        names: Names::default(),
+        sense: ObjectiveSense::Minimize,
+        offset: 0.0,
+    };
+    let problem = robust_lp(
+        &nominal,
+        &[RobustRow {
+            row: 0,
+            uncertainty: Uncertainty::Ellipsoid(diagonal(2, 0.5)),
+        }],
+    )
+    .expect("counterpart");
+    let solution = AdmmSolver::new(SolveOptions::with_tolerance(1e-7))
+        .solve_conic(problem)
+        .expect("solve");
+    assert_eq!(solution.status, cvxrs_core::solution::Status::Optimal);
+    let expected = 2.0 / (2.0 + 0.5 * Scalar::sqrt(2.0));
+    for &x in &solution.primal {
+        assert!((x - expected).abs() < 1e-4);
+    }
+}
+
+fn batch_member(linear: Vec<Scalar>, rhs: Scalar) -> ProblemQP<Scalar> {
+    ProblemQP {
+        quadratic: diagonal(3, 2.0),
+        linear,
+        inequalities: Some(InequalityConstraints {
+            matrix: CscMatrix {
+                nrows: 1,
+                ncols: 3,
+                indptr: vec![0, 1, 2, 3],
+                indices: vec![0, 0, 0],
+                data: vec![1.0, 1.0, 1.0],
+            },
+            rhs: vec![rhs],
+        }),
+        equalities: None,
+        bounds: Some(Bounds {
+            lower: vec![0.0; 3],
+            upper: vec![2.0; 3],
+        }),
+        groups: Vec::new(),
+        integrality: Vec::new(),
+        names: Names::default(),
+        sense: ObjectiveSense::Minimize,
+        offset: 0.0,
+        quadratic_constraints: Vec::new(),
+    }
+}
+
+#[test]
+#[cfg_attr(feature = "f32", ignore = "needs f64 accuracy")]
+fn batch_reuses_the_factorization_and_matches_single_solves() {
+    let problems = vec![
+        batch_member(vec![-2.0, -2.0, -2.0], 1.5),
+        batch_member(vec![-4.0, 1.0, -1.0], 1.0),
+        batch_member(vec![-1.0, -3.0, 0.5], 3.0),
+    ];
+    let options = SolveOptions::<Scalar> {
+        admm_adaptive_rho: false,
+        ..SolveOptions::with_tolerance(1e-8)
+    };
+    let batch = AdmmSolver::new(options.clone())
+        .solve_qp_batch(problems.clone(), &mut RuizScaler::default())
+        .expect("batch");
+    assert_eq!(batch.len(), problems.len());
+    for (k, (solution, problem)) in batch.iter().zip(problems).enumerate() {
+        let single = AdmmSolver::new(options.clone())
+            .solve_qp(problem, &mut RuizScaler::default())
+            .expect("solve");
+        assert_eq!(solution.status, cvxrs_core::solution::Status::Optimal);
+        // Members keep the cost factor the first q gave, so they stop at a
+        // nearby point rather than the very same one.
+        for (a, b) in solution.primal.iter().zip(&single.primal) {
+            assert!((a - b).abs() < 1e-6, "{a} vs {b}");
+        }
+        assert!((solution.objective_value - single.objective_value).abs() < 1e-6);
+        // Only the first member pays for the factorization.
+        assert_eq!(solution.stats.factorizations, usize::from(k == 0));
+    }
+}
+
+#[test]
+fn batch_rejects_members_with_other_matrices() {
+    let mut other = batch_member(vec![-1.0; 3], 1.0);
+    other.quadratic = diagonal(3, 3.0);
+    let result = AdmmSolver::new(SolveOptions::default()).solve_qp_batch(
+        vec![batch_member(vec![-1.0; 3], 1.0), other],
+        &mut RuizScaler::default(),
+    );
+    assert!(result.is_err());
+}
+
+#[test]
+fn setup_updates_match_fresh_solves() {
+    let options = SolveOptions::<Scalar> {
+        admm_adaptive_rho: false,
+        ..SolveOptions::default()
+    };
+    let single = |problem: ProblemQP<Scalar>| {
+        AdmmSolver::new(options.clone())
+            .solve_qp(problem, &mut RuizScaler::default())
+            .expect("solve")
+    };
+    let close = |a: &[Scalar], b: &[Scalar]| a.iter().zip(b).all(|(a, b)| (a - b).abs() < 1e-4);
+    let mut setup = AdmmSolver::new(options.clone())
+        .setup(
+            batch_member(vec![-2.0, -2.0, -2.0], 1.5),
+            RuizScaler::default(),
+        )
+        .expect("setup");
+    let first = setup.solve().expect("first solve");
+    assert_eq!(first.stats.factorizations, 1);
+
+    // New vectors keep the factorization and start from the last optimum.
+    setup.update_q(vec![-4.0, 1.0, -1.0]).unwrap();
+    setup.update_rhs(Vec::new(), vec![1.0]).unwrap();
+    setup
+        .update_bounds(Bounds {
+            lower: vec![0.0; 3],
+            upper: vec![0.8; 3],
+        })
+        .unwrap();
+    let second = setup.solve().expect("second solve");
+    let mut expected = batch_member(vec![-4.0, 1.0, -1.0], 1.0);
+    expected.bounds.as_mut().unwrap().upper = vec![0.8; 3];
+    assert_eq!(second.stats.factorizations, 0);
+    assert!(close(&second.primal, &single(expected.clone()).primal));
+
+    // New matrix values on the same pattern refactor once.
+    setup
+        .update_matrix_values(Some(&[4.0, 1.0, 2.0]), None, Some(&[1.0, 2.0, 1.0]))
+        .unwrap();
+    let third = setup.solve().expect("third solve");
+    expected.quadratic.data = vec![4.0, 1.0, 2.0];
+    expected.inequalities.as_mut().unwrap().matrix.data = vec![1.0, 2.0, 1.0];
+    assert_eq!(third.stats.factorizations, 1);
+    assert!(close(&third.primal, &single(expected).primal));
+
+    assert!(setup.update_q(vec![1.0]).is_err());
+    assert!(setup.update_rhs(vec![1.0], vec![1.0]).is_err());
+    assert!(setup
+        .update_matrix_values(None, Some(&[1.0]), None)
+        .is_err());
+}
+
+// A sum row followed by the first differences x_i - x_{i-1}, applied
+// without storing either.
+struct SumAndDifferences {
+    n: usize,
+}
+
+impl LinearOperator<Scalar> for SumAndDifferences {
+    fn dim(&self) -> (usize, usize) {
+        (self.n, self.n)
+    }
+
+    fn apply(&self, x: &[Scalar], y: &mut [Scalar]) {
+        y[0] = x.iter().sum();
+        for i in 1..self.n {
+            y[i] = x[i] - x[i - 1];
+        }
+    }
+
+    fn apply_transpose(&self, x: &[Scalar], y: &mut [Scalar]) {
+        y.fill(x[0]);
+        for i in 1..self.n {
+            y[i] += x[i];
+            y[i - 1] -= x[i];
+        }
+    }
+}
+
+#[test]
+#[cfg_attr(feature = "f32", ignore = "needs f64 accuracy")]
+fn matrix_free_path_matches_the_explicit_solve() {
+    // min ½‖x‖² - cᵀx s.t. Σx = 1, x_i - x_{i-1} <= 0.05, 0 <= x <= 1.
+    let n = 8;
+    let c: Vec<Scalar> = (0..n).map(|i| i as Scalar / n as Scalar).collect();
+    let linear: Vec<Scalar> = c.iter().map(|&c| -c).collect();
+    let bounds = Bounds {
+        lower: vec![0.0; n],
+        upper: vec![1.0; n],
+    };
+    let mut indptr = vec![0];
+    let (mut indices, mut data) = (Vec::new(), Vec::new());
+    for col in 0..n {
+        if col > 0 {
+            indices.push(col - 1);
+            data.push(1.0);
+        }
+        if col + 1 < n {
+            indices.push(col);
+            data.push(-1.0);
+        }
+        indptr.push(indices.len());
+    }
+    let differences = CscMatrix {
+        nrows: n - 1,
+        ncols: n,
+        indptr,
+        indices,
+        data,
+    };
+    let explicit = ProblemQP {
+        quadratic: diagonal(n, 1.0),
+        linear: linear.clone(),
+        inequalities: Some(InequalityConstraints {
+            matrix: differences,
+            rhs: vec![0.05; n - 1],
+        }),
+        equalities: Some(EqualityConstraints {
+            matrix: CscMatrix {
+                nrows: 1,
+                ncols: n,
+                indptr: (0..=n).collect(),
+                indices: vec![0; n],
+                data: vec![1.0; n],
+            },
+            rhs: vec![1.0],
+        }),
+        bounds: Some(bounds.clone()),
+        groups: Vec::new(),
+        integrality: Vec::new(),
+        names: Names::default(),
+        sense: ObjectiveSense::Minimize,
+        offset: 0.0,
+        quadratic_constraints: Vec::new(),
+    };
+    let options = SolveOptions::<Scalar>::with_tolerance(1e-7);
+    let reference = AdmmSolver::new(options.clone())
+        .solve_qp(explicit, &mut RuizScaler::default())
+        .expect("solve");
+
+    let mut rhs = vec![0.05; n];
+    rhs[0] = 1.0;
+    let mut column_norms = vec![3.0; n];
+    column_norms[0] = 2.0;
+    column_norms[n - 1] = 2.0;
+    let problem = ProblemQpOp::new(Arc::new(diagonal(n, 1.0)), linear)
+        .with_constraints(Arc::new(SumAndDifferences { n }), rhs, 1)
+        .with_bounds(bounds)
+        .with_quadratic_diagonal(vec![1.0; n])
+        .with_column_norms(column_norms);
+    let solution = AdmmSolver::new(options)
+        .solve_qp_operator(&problem)
+        .expect("solve");
+    assert_eq!(solution.status, cvxrs_core::solution::Status::Optimal);
+    for (a, b) in solution.primal.iter().zip(&reference.primal) {
+        assert!((a - b).abs() < 1e-4, "{} vs {}", a, b);
+    }
+    assert!((solution.primal.iter().sum::<Scalar>() - 1.0).abs() < 1e-4);
+    assert!((solution.objective_value - reference.objective_value).abs() < 1e-5);
+    assert_eq!(solution.equality_dual.len(), 1);
+    assert_eq!(solution.inequality_dual.len(), (n - 1) + n);
+    assert!(solution.stats.krylov.expect("CG statistics").solves > 0);
+
+    let mismatched = ProblemQpOp::new(Arc::new(diagonal(n, 1.0)), vec![0.0; n + 1]);
+    assert!(AdmmSolver::new(SolveOptions::<Scalar>::default())
+        .solve_qp_operator(&mismatched)
+        .is_err());
+}
+
+#[test]
+fn gpu_backend_needs_the_feature_and_a_device() {
+    // min ½‖x‖² - x0 - 2x1 s.t. x0 + x1 <= 1, 0 <= x <= 1: x = (0, 1).
+    let problem = ProblemQP {
+        quadratic: diagonal(2, 1.0),
+        linear: vec![-1.0, -2.0],
+        inequalities: Some(InequalityConstraints {
+            matrix: CscMatrix {
+                nrows: 1,
+                ncols: 2,
+                indptr: vec![0, 1, 2],
+                indices: vec![0, 0],
+                data: vec![1.0, 1.0],
+            },
+            rhs: vec![1.0],
+        }),
+        equalities: None,
+        bounds: Some(Bounds {
+            lower: vec![0.0; 2],
+            upper: vec![1.0; 2],
+        }),
+        groups: Vec::new(),
+        integrality: Vec::new(),
+        names: Names::default(),
+        sense: ObjectiveSense::Minimize,
+        offset: 0.0,
+        quadratic_constraints: Vec::new(),
+    };
+    let options = SolveOptions::<Scalar> {
+        linsys_backend: LinsysBackend::Gpu,
+        ..SolveOptions::with_tolerance(1e-5)
+    };
+    let result = AdmmSolver::new(options).solve_qp(problem, &mut RuizScaler::default());
+    #[cfg(not(feature = "gpu"))]
+    assert!(result.unwrap_err().to_string().contains("gpu feature"));
+    #[cfg(feature = "gpu")]
+    {
+        if cvxrs_linsys::gpu::GpuDevice::shared().is_err() {
+            return;
+        }
+        let solution = result.expect("solve");
+        assert_eq!(solution.status, cvxrs_core::solution::Status::Optimal);
+        assert!(solution.primal[0].abs() < 1e-3);
+        assert!((solution.primal[1] - 1.0).abs() < 1e-3);
+        assert!(solution.stats.krylov.expect("CG statistics").solves > 0);
     }
 }
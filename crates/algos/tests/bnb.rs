@@ -0,0 +1,141 @@
+use cvxrs_algos::active_set::ActiveSetSolver;
+use cvxrs_algos::bnb::BranchAndBound;
+use cvxrs_algos::simplex::SimplexSolver;
+use cvxrs_core::math::Scalar;
+use cvxrs_core::options::SolveOptions;
+use cvxrs_core::problem::{
+    Bounds, EqualityConstraints, InequalityConstraints, Names, ObjectiveSense, ProblemLP, ProblemQP,
+};
+use cvxrs_core::solution::Status;
+use std::sync::{Arc, Mutex};
+
+mod common;
+use common::dense_csc;
+
+// max 5x + 4y  s.t.  6x + 4y <= 24, x + 2y <= 6, x, y >= 0 integer. The
+// relaxation peaks at (3, 1.5) with 21; the integer optimum is (4, 0).
+fn knapsack_milp() -> ProblemLP<Scalar> {
+    ProblemLP {
+        cost: vec![-5.0, -4.0],
+        inequalities: Some(InequalityConstraints {
+            matrix: dense_csc(&[&[6.0, 4.0], &[1.0, 2.0]]),
+            rhs: vec![24.0, 6.0],
+        }),
+        equalities: None,
+        bounds: Some(Bounds {
+            lower: vec![0.0, 0.0],
+            upper: vec![Scalar::INFINITY, Scalar::INFINITY],
+        }),
+        groups: Vec::new(),
+        integrality: vec![true, true],
+        names: Names::default(),
+        sense: ObjectiveSense::Minimize,
+        offset: 0.0,
+    }
+}
+
+#[test]
+fn milp_reaches_the_integer_optimum() {
+    let problem = knapsack_milp();
+    let options = SolveOptions::default();
+    let simplex = SimplexSolver::new();
+    let found = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&found);
+    let solution = BranchAndBound::new()
+        .with_incumbent_callback(Arc::new(move |incumbent| {
+            sink.lock().unwrap().push(incumbent.objective);
+        }))
+        .solve_lp(&problem, |node| simplex.solve_lp(node, &options))
+        .expect("solve");
+    assert_eq!(solution.status, Status::Optimal);
+    assert_eq!(solution.primal, vec![4.0, 0.0]);
+    assert!((solution.objective_value + 20.0).abs() < 1e-9);
+
+    let search = solution.stats.branch_and_bound.expect("search stats");
+    assert!(search.nodes > 1);
+    assert!((search.best_bound + 20.0).abs() < 1e-9);
+    // Each incumbent improves on the previous one.
+    let found = found.lock().unwrap();
+    assert_eq!(found.len(), search.incumbents);
+    assert!(found.windows(2).all(|pair| pair[1] < pair[0]));
+    assert_eq!(found.last().copied(), Some(solution.objective_value));
+}
+
+#[test]
+fn node_limit_stops_the_search() {
+    let problem = knapsack_milp();
+    let options = SolveOptions::default();
+    let simplex = SimplexSolver::new();
+    let solution = BranchAndBound::new()
+        .with_node_limit(1)
+        .solve_lp(&problem, |node| simplex.solve_lp(node, &options))
+        .expect("solve");
+    // Only the fractional root was solved, so there is no incumbent.
+    assert_eq!(solution.status, Status::MaxIterations);
+    let search = solution.stats.branch_and_bound.expect("search stats");
+    assert_eq!(search.nodes, 1);
+    assert_eq!(search.incumbents, 0);
+    assert!((search.best_bound + 21.0).abs() < 1e-9);
+}
+
+#[test]
+fn integrality_can_make_a_feasible_lp_infeasible() {
+    // 2x = 1 has only the fractional solution x = 0.5.
+    let problem = ProblemLP {
+        cost: vec![1.0],
+        inequalities: None,
+        equalities: Some(EqualityConstraints {
+            matrix: dense_csc(&[&[2.0]]),
+            rhs: vec![1.0],
+        }),
+        bounds: Some(Bounds {
+            lower: vec![0.0],
+            upper: vec![5.0],
+        }),
+        groups: Vec::new(),
+        integrality: vec![true],
+        names: Names::default(),
+        sense: ObjectiveSense::Minimize,
+        offset: 0.0,
+    };
+    let options = SolveOptions::default();
+    let simplex = SimplexSolver::new();
+    let solution = BranchAndBound::new()
+        .solve_lp(&problem, |node| simplex.solve_lp(node, &options))
+        .expect("solve");
+    assert_eq!(solution.status, Status::PrimalInfeasible);
+    assert_eq!(solution.stats.branch_and_bound.unwrap().incumbents, 0);
+}
+
+#[test]
+fn miqp_rounds_through_the_constraints() {
+    // min (x - 1.6)² + (y - 0.4)² up to a constant, with x + y <= 1.5 and
+    // both variables integer in [0, 3]: (2, 0) is cut off, (1, 0) wins.
+    let problem = ProblemQP {
+        quadratic: dense_csc(&[&[2.0, 0.0], &[0.0, 2.0]]),
+        linear: vec![-3.2, -0.8],
+        inequalities: Some(InequalityConstraints {
+            matrix: dense_csc(&[&[1.0, 1.0]]),
+            rhs: vec![1.5],
+        }),
+        equalities: None,
+        bounds: Some(Bounds {
+            lower: vec![0.0, 0.0],
+            upper: vec![3.0, 3.0],
+        }),
+        groups: Vec::new(),
+        integrality: vec![true, true],
+        names: Names::default(),
+        sense: ObjectiveSense::Minimize,
+        offset: 0.0,
+        quadratic_constraints: Vec::new(),
+    };
+    let options = SolveOptions::default();
+    let active_set = ActiveSetSolver::new();
+    let solution = BranchAndBound::new()
+        .solve_qp(&problem, |node| active_set.solve_qp(node, &options))
+        .expect("solve");
+    assert_eq!(solution.status, Status::Optimal);
+    assert_eq!(solution.primal, vec![1.0, 0.0]);
+    assert!((solution.objective_value + 2.2).abs() < 1e-9);
+}
@@ -0,0 +1,39 @@
+// Helpers shared by the solver tests. Each test binary pulls in the whole
+// module but uses only part of it.
+#![allow(dead_code)]
+
+use cvxrs_core::math::Scalar;
+use cvxrs_core::problem::{
+    Bounds, CscMatrix, EqualityConstraints, InequalityConstraints, Names, ObjectiveSense, ProblemLP,
+};
+
+pub fn dense_csc(rows: &[&[Scalar]]) -> CscMatrix<Scalar> {
+    let ncols = rows.first().map_or(0, |row| row.len());
+    CscMatrix::from_dense(rows.len(), ncols, &rows.concat()).expect("rectangular rows")
+}
+
+// min -x0 - 2x1  s.t.  x0 + x1 = 3, x0 - x1 <= 1, 0 <= x <= 2. The optimum
+// (1, 2) has x0 basic and x1 at its upper bound, with objective -5 and
+// equality multiplier 1.
+pub fn vertex_lp() -> ProblemLP<Scalar> {
+    ProblemLP {
+        cost: vec![-1.0, -2.0],
+        equalities: Some(EqualityConstraints {
+            matrix: dense_csc(&[&[1.0, 1.0]]),
+            rhs: vec![3.0],
+        }),
+        inequalities: Some(InequalityConstraints {
+            matrix: dense_csc(&[&[1.0, -1.0]]),
+            rhs: vec![1.0],
+        }),
+        bounds: Some(Bounds {
+            lower: vec![0.0, 0.0],
+            upper: vec![2.0, 2.0],
+        }),
+        groups: Vec::new(),
+        integrality: Vec::new(),
+        names: Names::default(),
+        sense: ObjectiveSense::Minimize,
+        offset: 0.0,
+    }
+}
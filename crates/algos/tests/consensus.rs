@@ -0,0 +1,160 @@
+use cvxrs_algos::active_set::ActiveSetSolver;
+use cvxrs_algos::consensus::{ConsensusSolver, Scenario};
+use cvxrs_core::math::Scalar;
+use cvxrs_core::options::SolveOptions;
+use cvxrs_core::problem::{
+    Bounds, CscMatrix, InequalityConstraints, Names, ObjectiveSense, ProblemQP,
+};
+use cvxrs_core::solution::Status;
+
+fn diagonal(values: &[Scalar]) -> CscMatrix<Scalar> {
+    CscMatrix {
+        nrows: values.len(),
+        ncols: values.len(),
+        indptr: (0..=values.len()).collect(),
+        indices: (0..values.len()).collect(),
+        data: values.to_vec(),
+    }
+}
+
+const PROBABILITY: [Scalar; 3] = [0.2, 0.5, 0.3];
+const DEMAND: [Scalar; 3] = [1.0, 3.0, 5.0];
+
+// Two-stage program: buy x at 0.05x² + x, then in scenario s use y_s <= x
+// to earn 2 d_s y_s - ½y_s², weighted by the scenario probability.
+fn scenario(s: usize) -> Scenario<Scalar> {
+    let p = PROBABILITY[s];
+    Scenario {
+        problem: ProblemQP {
+            quadratic: diagonal(&[0.1 * p, p]),
+            linear: vec![p, -2.0 * DEMAND[s] * p],
+            inequalities: Some(InequalityConstraints {
+                matrix: CscMatrix {
+                    nrows: 1,
+                    ncols: 2,
+                    indptr: vec![0, 1, 2],
+                    indices: vec![0, 0],
+                    data: vec![-1.0, 1.0],
+                },
+                rhs: vec![0.0],
+            }),
+            equalities: None,
+            bounds: Some(Bounds {
+                lower: vec![0.0; 2],
+                upper: vec![10.0; 2],
+            }),
+            groups: Vec::new(),
+            integrality: Vec::new(),
+            names: Names::default(),
+            sense: ObjectiveSense::Minimize,
+            offset: 0.0,
+            quadratic_constraints: Vec::new(),
+        },
+        shared: vec![0],
+    }
+}
+
+// The same program as one QP over (x, y_0, y_1, y_2).
+fn extensive_form() -> ProblemQP<Scalar> {
+    let mut linear = vec![1.0];
+    linear.extend((0..3).map(|s| -2.0 * DEMAND[s] * PROBABILITY[s]));
+    ProblemQP {
+        quadratic: diagonal(&[0.1, PROBABILITY[0], PROBABILITY[1], PROBABILITY[2]]),
+        linear,
+        inequalities: Some(InequalityConstraints {
+            matrix: CscMatrix {
+                nrows: 3,
+                ncols: 4,
+                indptr: vec![0, 3, 4, 5, 6],
+                indices: vec![0, 1, 2, 0, 1, 2],
+                data: vec![-1.0, -1.0, -1.0, 1.0, 1.0, 1.0],
+            },
+            rhs: vec![0.0; 3],
+        }),
+        equalities: None,
+        bounds: Some(Bounds {
+            lower: vec![0.0; 4],
+            upper: vec![10.0; 4],
+        }),
+        groups: Vec::new(),
+        integrality: Vec::new(),
+        names: Names::default(),
+        sense: ObjectiveSense::Minimize,
+        offset: 0.0,
+        quadratic_constraints: Vec::new(),
+    }
+}
+
+#[test]
+#[cfg_attr(feature = "f32", ignore = "needs f64 accuracy")]
+fn stochastic_program_matches_the_extensive_form() {
+    let scenarios: Vec<_> = (0..3).map(scenario).collect();
+    let solution = ConsensusSolver::new(SolveOptions::with_tolerance(1e-7))
+        .solve(&scenarios)
+        .expect("consensus");
+    let exact = ActiveSetSolver::new()
+        .solve_qp(&extensive_form(), &SolveOptions::default())
+        .expect("extensive form");
+    assert_eq!(solution.status, Status::Optimal);
+    assert!((solution.consensus[0] - exact.primal[0]).abs() < 1e-3);
+    for (local, &y) in solution.scenarios.iter().zip(&exact.primal[1..]) {
+        assert!((local.primal[0] - exact.primal[0]).abs() < 1e-3);
+        assert!((local.primal[1] - y).abs() < 1e-3);
+    }
+    assert!((solution.objective_value - exact.objective_value).abs() < 1e-3);
+    assert_eq!(solution.iterations, solution.stats.history.len());
+}
+
+#[test]
+#[cfg_attr(feature = "f32", ignore = "needs f64 accuracy")]
+fn unconstrained_copies_agree_on_the_mean() {
+    // min Σ ½(x - a_i)² is solved by the mean of the a_i.
+    let targets = [1.0, 4.0, -2.0, 7.0];
+    let scenarios: Vec<_> = targets
+        .iter()
+        .map(|&a| Scenario {
+            problem: ProblemQP {
+                quadratic: diagonal(&[1.0]),
+                linear: vec![-a],
+                inequalities: None,
+                equalities: None,
+                bounds: None,
+                groups: Vec::new(),
+                integrality: Vec::new(),
+                names: Names::default(),
+                sense: ObjectiveSense::Minimize,
+                offset: 0.0,
+                quadratic_constraints: Vec::new(),
+            },
+            shared: vec![0],
+        })
+        .collect();
+    let options = SolveOptions::<Scalar> {
+        admm_rho: 1e-3,
+        rho_update_interval: 5,
+        ..SolveOptions::with_tolerance(1e-8)
+    };
+    let solution = ConsensusSolver::new(options)
+        .solve(&scenarios)
+        .expect("consensus");
+    assert_eq!(solution.status, Status::Optimal);
+    assert!((solution.consensus[0] - 2.5).abs() < 1e-5);
+    assert!(
+        !solution.stats.rho_updates.is_empty(),
+        "a tiny rho should be raised"
+    );
+}
+
+#[test]
+fn scenarios_must_share_the_same_variables() {
+    let mut other = scenario(1);
+    other.shared = vec![0, 1];
+    assert!(ConsensusSolver::new(SolveOptions::default())
+        .solve(&[scenario(0), other])
+        .is_err());
+    let mut repeated = scenario(1);
+    repeated.shared = vec![1, 1];
+    assert!(ConsensusSolver::new(SolveOptions::default())
+        .solve(&[repeated])
+        .is_err());
+}
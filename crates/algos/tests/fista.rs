@@ -0,0 +1,81 @@
+use cvxrs_algos::fista::FistaSolver;
+use cvxrs_core::math::Scalar;
+use cvxrs_core::options::SolveOptions;
+use cvxrs_core::problem::{
+    Bounds, CscMatrix, InequalityConstraints, Names, ObjectiveSense, ProblemQP,
+};
+use cvxrs_core::solution::Status;
+
+fn problem(bounds: Option<Bounds<Scalar>>) -> ProblemQP<Scalar> {
+    // P = [2 0.5; 0.5 1], q = (-4, 1).
+    ProblemQP {
+        quadratic: CscMatrix {
+            nrows: 2,
+            ncols: 2,
+            indptr: vec![0, 2, 4],
+            indices: vec![0, 1, 0, 1],
+            data: vec![2.0, 0.5, 0.5, 1.0],
+        },
+        linear: vec![-4.0, 1.0],
+        inequalities: None,
+        equalities: None,
+        bounds,
+        groups: Vec::new(),
+        integrality: Vec::new(),
+        names: Names::default(),
+        sense: ObjectiveSense::Minimize,
+        offset: 0.0,
+        quadratic_constraints: Vec::new(),
+    }
+}
+
+#[test]
+fn box_qp_lands_on_the_corner() {
+    // The unconstrained minimizer leaves the unit box; x = (1, 0) with the
+    // upper bound on x0 and the lower bound on x1 active.
+    let problem = problem(Some(Bounds {
+        lower: vec![0.0, 0.0],
+        upper: vec![1.0, 1.0],
+    }));
+    let solution = FistaSolver::new()
+        .solve_qp(&problem, &SolveOptions::with_tolerance(1e-9))
+        .expect("solve");
+    assert_eq!(solution.status, Status::Optimal);
+    assert_eq!(solution.stats.factorizations, 0);
+    assert!((solution.primal[0] - 1.0).abs() < 1e-9);
+    assert!(solution.primal[1].abs() < 1e-9);
+    assert!((solution.objective_value + 3.0).abs() < 1e-9);
+    // Px + q + w = 0: w pushes down at the upper bound and up at the lower.
+    assert!((solution.inequality_dual[0] - 2.0).abs() < 1e-9);
+    assert!((solution.inequality_dual[1] + 1.5).abs() < 1e-9);
+}
+
+#[test]
+#[cfg_attr(feature = "f32", ignore = "needs f64 accuracy")]
+fn unconstrained_qp_solves_the_normal_equations() {
+    let solution = FistaSolver::new()
+        .solve_qp(&problem(None), &SolveOptions::with_tolerance(1e-10))
+        .expect("solve");
+    assert_eq!(solution.status, Status::Optimal);
+    assert!((solution.primal[0] - 4.5 / 1.75).abs() < 1e-8);
+    assert!((solution.primal[1] + 4.0 / 1.75).abs() < 1e-8);
+    assert!(solution.inequality_dual.is_empty());
+}
+
+#[test]
+fn general_constraints_are_rejected() {
+    let mut problem = problem(None);
+    problem.inequalities = Some(InequalityConstraints {
+        matrix: CscMatrix {
+            nrows: 1,
+            ncols: 2,
+            indptr: vec![0, 1, 2],
+            indices: vec![0, 0],
+            data: vec![1.0, 1.0],
+        },
+        rhs: vec![1.0],
+    });
+    assert!(FistaSolver::new()
+        .solve_qp(&problem, &SolveOptions::default())
+        .is_err());
+}
@@ -0,0 +1,185 @@
+use cvxrs_algos::ipm::IpmSolver;
+use cvxrs_core::math::Scalar;
+use cvxrs_core::options::{LinsysBackend, SolveOptions};
+use cvxrs_core::problem::{
+    Bounds, EqualityConstraints, InequalityConstraints, Names, ObjectiveSense, ProblemLP,
+};
+use cvxrs_core::solution::{Certificate, Status};
+
+mod common;
+use common::{dense_csc, vertex_lp};
+
+fn solve(problem: &ProblemLP<Scalar>) -> cvxrs_core::solution::Solution<Scalar> {
+    let options = SolveOptions::with_tolerance(1e-8);
+    IpmSolver::new().solve_lp(problem, &options).expect("solve")
+}
+
+#[test]
+fn solves_a_small_lp_to_high_accuracy() {
+    let problem = vertex_lp();
+    let solution = solve(&problem);
+    assert_eq!(solution.status, Status::Optimal);
+    assert!(solution.certificate.is_none());
+    assert!((solution.primal[0] - 1.0).abs() < 1e-6);
+    assert!((solution.primal[1] - 2.0).abs() < 1e-6);
+    assert!((solution.objective_value + 5.0).abs() < 1e-6);
+    // c + Aᵀy + Gᵀz + w = 0 with the bound multipliers after the inequality.
+    let y = solution.equality_dual[0];
+    let z = solution.inequality_dual[0];
+    let w = &solution.inequality_dual[1..];
+    assert!((-1.0 + y + z + w[0]).abs() < 1e-6);
+    assert!((-2.0 + y - z + w[1]).abs() < 1e-6);
+    assert!(w[1] > 0.0, "upper bound on x1 should be active");
+}
+
+#[test]
+fn minres_backend_matches_the_factorization() {
+    // min -x0 - 2x1 - 3x2  s.t.  x0 + x1 + x2 = 2, x0 + 2x2 <= 3, 0 <= x <= 1.
+    let problem = ProblemLP {
+        cost: vec![-1.0, -2.0, -3.0],
+        equalities: Some(EqualityConstraints {
+            matrix: dense_csc(&[&[1.0, 1.0, 1.0]]),
+            rhs: vec![2.0],
+        }),
+        inequalities: Some(InequalityConstraints {
+            matrix: dense_csc(&[&[1.0, 0.0, 2.0]]),
+            rhs: vec![3.0],
+        }),
+        bounds: Some(Bounds {
+            lower: vec![0.0; 3],
+            upper: vec![1.0; 3],
+        }),
+        groups: Vec::new(),
+        integrality: Vec::new(),
+        names: Names::default(),
+        sense: ObjectiveSense::Minimize,
+        offset: 0.0,
+    };
+    let direct = solve(&problem);
+    let options = SolveOptions {
+        linsys_backend: LinsysBackend::Minres,
+        ..SolveOptions::with_tolerance(1e-8)
+    };
+    let iterative = IpmSolver::new()
+        .solve_lp(&problem, &options)
+        .expect("solve");
+    assert_eq!(iterative.status, Status::Optimal);
+    for (a, b) in direct.primal.iter().zip(&iterative.primal) {
+        assert!((a - b).abs() < 1e-6);
+    }
+    assert!(direct.stats.krylov.is_none());
+    assert!(direct.stats.condition_estimate.expect("condition estimate") >= 1.0);
+    assert!(iterative.stats.condition_estimate.is_none());
+    let krylov = iterative.stats.krylov.expect("MINRES statistics");
+    assert!(krylov.solves > 0 && krylov.iterations >= krylov.solves);
+}
+
+#[test]
+fn certifies_primal_infeasibility() {
+    // x0 + x1 >= 3 written as -x0 - x1 <= -3, with 0 <= x <= 1.
+    let problem = ProblemLP {
+        cost: vec![1.0, 1.0],
+        equalities: None,
+        inequalities: Some(InequalityConstraints {
+            matrix: dense_csc(&[&[-1.0, -1.0]]),
+            rhs: vec![-3.0],
+        }),
+        bounds: Some(Bounds {
+            lower: vec![0.0, 0.0],
+            upper: vec![1.0, 1.0],
+        }),
+        groups: Vec::new(),
+        integrality: Vec::new(),
+        names: Names::default(),
+        sense: ObjectiveSense::Minimize,
+        offset: 0.0,
+    };
+    let solution = solve(&problem);
+    assert_eq!(solution.status, Status::PrimalInfeasible);
+    let Some(Certificate::PrimalInfeasible {
+        equality_dual,
+        inequality_dual,
+    }) = solution.certificate
+    else {
+        panic!(
+            "expected a Farkas certificate, got {:?}",
+            solution.certificate
+        );
+    };
+    assert!(equality_dual.is_empty());
+    let (z, w) = (inequality_dual[0], &inequality_dual[1..]);
+    assert!(z >= 0.0);
+    for (col, &w) in w.iter().enumerate() {
+        assert!((-z + w).abs() < 1e-6, "stationarity fails in column {col}");
+    }
+    let support: Scalar = -3.0 * z + w.iter().map(|&w| w.max(0.0) * 1.0).sum::<Scalar>();
+    assert!((support + 1.0).abs() < 1e-6);
+}
+
+#[test]
+fn certifies_dual_infeasibility() {
+    // min -2x0 + x1  s.t.  x0 - 2x1 <= 1, x >= 0: the cost falls along x0 = 2x1.
+    let problem = ProblemLP {
+        cost: vec![-2.0, 1.0],
+        equalities: None,
+        inequalities: Some(InequalityConstraints {
+            matrix: dense_csc(&[&[1.0, -2.0]]),
+            rhs: vec![1.0],
+        }),
+        bounds: Some(Bounds {
+            lower: vec![0.0, 0.0],
+            upper: vec![Scalar::INFINITY, Scalar::INFINITY],
+        }),
+        groups: Vec::new(),
+        integrality: Vec::new(),
+        names: Names::default(),
+        sense: ObjectiveSense::Minimize,
+        offset: 0.0,
+    };
+    let solution = solve(&problem);
+    assert_eq!(solution.status, Status::DualInfeasible);
+    assert_eq!(solution.objective_value, Scalar::NEG_INFINITY);
+    let Some(Certificate::DualInfeasible { direction }) = solution.certificate else {
+        panic!("expected an improving ray, got {:?}", solution.certificate);
+    };
+    assert!(direction.iter().all(|&d| d >= -1e-9));
+    assert!(direction[0] - 2.0 * direction[1] <= 1e-9);
+    assert!((-2.0 * direction[0] + direction[1] + 1.0).abs() < 1e-6);
+}
+
+#[cfg(feature = "gpu")]
+#[test]
+fn gpu_backend_matches_the_factorization() {
+    if cvxrs_linsys::gpu::GpuDevice::shared().is_err() {
+        return;
+    }
+    // min -x0 - x1 s.t. x0 + 2x1 <= 2, 0 <= x <= 1: x = (1, 0.5).
+    let problem = ProblemLP {
+        cost: vec![-1.0, -1.0],
+        equalities: None,
+        inequalities: Some(InequalityConstraints {
+            matrix: dense_csc(&[&[1.0, 2.0]]),
+            rhs: vec![2.0],
+        }),
+        bounds: Some(Bounds {
+            lower: vec![0.0; 2],
+            upper: vec![1.0; 2],
+        }),
+        groups: Vec::new(),
+        integrality: Vec::new(),
+        names: Names::default(),
+        sense: ObjectiveSense::Minimize,
+        offset: 0.0,
+    };
+    let options = SolveOptions {
+        linsys_backend: LinsysBackend::Gpu,
+        ..SolveOptions::with_tolerance(1e-6)
+    };
+    let solution = IpmSolver::new()
+        .solve_lp(&problem, &options)
+        .expect("solve");
+    assert_eq!(solution.status, Status::Optimal);
+    assert!((solution.primal[0] - 1.0).abs() < 1e-4);
+    assert!((solution.primal[1] - 0.5).abs() < 1e-4);
+    assert!(solution.stats.krylov.is_some());
+}
@@ -0,0 +1,166 @@
+use cvxrs_algos::pdhg::{OperatorLp, PdhgSolver};
+use cvxrs_core::math::Scalar;
+use cvxrs_core::options::SolveOptions;
+use cvxrs_core::problem::{Bounds, InequalityConstraints, Names, ObjectiveSense, ProblemLP};
+use cvxrs_core::solution::{Certificate, Status};
+use cvxrs_core::traits::LinearOperator;
+
+mod common;
+use common::{dense_csc, vertex_lp};
+
+#[test]
+fn solves_a_small_lp() {
+    let problem = vertex_lp();
+    let solution = PdhgSolver::new()
+        .solve_lp(&problem, &SolveOptions::with_tolerance(1e-8))
+        .expect("solve");
+    assert_eq!(solution.status, Status::Optimal);
+    assert!(
+        (solution.primal[0] - 1.0).abs() < 1e-6,
+        "{:?}",
+        solution.primal
+    );
+    assert!(
+        (solution.primal[1] - 2.0).abs() < 1e-6,
+        "{:?}",
+        solution.primal
+    );
+    assert!((solution.objective_value + 5.0).abs() < 1e-6);
+    assert!((solution.equality_dual[0] - 1.0).abs() < 1e-6);
+}
+
+// Row 0 reads x0; row i > 0 reads x_i - x_{i-1}.
+struct Differences {
+    n: usize,
+}
+
+impl LinearOperator<Scalar> for Differences {
+    fn dim(&self) -> (usize, usize) {
+        (self.n, self.n)
+    }
+
+    fn apply(&self, x: &[Scalar], y: &mut [Scalar]) {
+        y[0] = x[0];
+        for i in 1..self.n {
+            y[i] = x[i] - x[i - 1];
+        }
+    }
+
+    fn apply_transpose(&self, x: &[Scalar], y: &mut [Scalar]) {
+        for i in 0..self.n {
+            let next = if i + 1 < self.n { x[i + 1] } else { 0.0 };
+            y[i] = x[i] - next;
+        }
+    }
+}
+
+#[test]
+#[cfg_attr(feature = "f32", ignore = "needs f64 accuracy")]
+fn operator_lp_needs_only_products() {
+    // max Σx  s.t.  x0 = 0, x_i - x_{i-1} <= 1, 0 <= x <= 10: x_i = min(i, 10).
+    let n = 16;
+    let operator = Differences { n };
+    let cost = vec![-1.0; n];
+    let mut rhs = vec![1.0; n];
+    rhs[0] = 0.0;
+    let bounds = Bounds {
+        lower: vec![0.0; n],
+        upper: vec![10.0; n],
+    };
+    let solution = PdhgSolver::new()
+        .solve_operator(
+            &OperatorLp {
+                operator: &operator,
+                cost: &cost,
+                rhs: &rhs,
+                equalities: 1,
+                bounds: Some(&bounds),
+            },
+            &SolveOptions::with_tolerance(1e-8),
+        )
+        .expect("solve");
+    assert_eq!(solution.status, Status::Optimal);
+    for (i, &value) in solution.primal.iter().enumerate() {
+        let expected = (i as Scalar).min(10.0);
+        assert!((value - expected).abs() < 1e-5, "{:?}", solution.primal);
+    }
+    assert_eq!(solution.inequality_dual.len(), 2 * n - 1);
+}
+
+#[test]
+fn certifies_primal_infeasibility() {
+    // x0 + x1 >= 3 written as -x0 - x1 <= -3, with 0 <= x <= 1.
+    let problem = ProblemLP {
+        cost: vec![1.0, 1.0],
+        equalities: None,
+        inequalities: Some(InequalityConstraints {
+            matrix: dense_csc(&[&[-1.0, -1.0]]),
+            rhs: vec![-3.0],
+        }),
+        bounds: Some(Bounds {
+            lower: vec![0.0, 0.0],
+            upper: vec![1.0, 1.0],
+        }),
+        groups: Vec::new(),
+        integrality: Vec::new(),
+        names: Names::default(),
+        sense: ObjectiveSense::Minimize,
+        offset: 0.0,
+    };
+    let solution = PdhgSolver::new()
+        .solve_lp(&problem, &SolveOptions::default())
+        .expect("solve");
+    assert_eq!(solution.status, Status::PrimalInfeasible);
+    assert_eq!(solution.objective_value, Scalar::INFINITY);
+    let Some(Certificate::PrimalInfeasible {
+        equality_dual,
+        inequality_dual,
+    }) = solution.certificate
+    else {
+        panic!(
+            "expected a Farkas certificate, got {:?}",
+            solution.certificate
+        );
+    };
+    assert!(equality_dual.is_empty());
+    let (z, w) = (inequality_dual[0], &inequality_dual[1..]);
+    assert!(z >= 0.0);
+    for (col, &w) in w.iter().enumerate() {
+        assert!((-z + w).abs() < 1e-6, "stationarity fails in column {col}");
+    }
+    let support: Scalar = -3.0 * z + w.iter().map(|&w| w.max(0.0)).sum::<Scalar>();
+    assert!((support + 1.0).abs() < 1e-6);
+}
+
+#[test]
+fn certifies_dual_infeasibility() {
+    // min -2x0 + x1  s.t.  x0 - 2x1 <= 1, x >= 0: the cost falls along x0 = 2x1.
+    let problem = ProblemLP {
+        cost: vec![-2.0, 1.0],
+        equalities: None,
+        inequalities: Some(InequalityConstraints {
+            matrix: dense_csc(&[&[1.0, -2.0]]),
+            rhs: vec![1.0],
+        }),
+        bounds: Some(Bounds {
+            lower: vec![0.0, 0.0],
+            upper: vec![Scalar::INFINITY, Scalar::INFINITY],
+        }),
+        groups: Vec::new(),
+        integrality: Vec::new(),
+        names: Names::default(),
+        sense: ObjectiveSense::Minimize,
+        offset: 0.0,
+    };
+    let solution = PdhgSolver::new()
+        .solve_lp(&problem, &SolveOptions::default())
+        .expect("solve");
+    assert_eq!(solution.status, Status::DualInfeasible);
+    assert_eq!(solution.objective_value, Scalar::NEG_INFINITY);
+    let Some(Certificate::DualInfeasible { direction }) = solution.certificate else {
+        panic!("expected an improving ray, got {:?}", solution.certificate);
+    };
+    assert!(direction.iter().all(|&d| d >= -1e-9));
+    assert!(direction[0] - 2.0 * direction[1] <= 1e-9);
+    assert!((-2.0 * direction[0] + direction[1] + 1.0).abs() < 1e-6);
+}
@@ -0,0 +1,188 @@
+use cvxrs_algos::active_set::ActiveSetSolver;
+use cvxrs_algos::sensitivity::{DataGradient, Sensitivity};
+use cvxrs_algos::simplex::SimplexSolver;
+use cvxrs_core::math::Scalar;
+use cvxrs_core::options::SolveOptions;
+use cvxrs_core::problem::{
+    Bounds, InequalityConstraints, Names, ObjectiveSense, ProblemLP, ProblemQP,
+};
+use cvxrs_core::solution::Status;
+
+mod common;
+use common::dense_csc;
+
+// min x0² + ½x1² + ½x2² - 4x0 - 3x1 - x2 with x0 + x1 + x2 <= 2 and
+// 0 <= x <= 10. The optimum (1, 1, 0) has the row and the lower bound on x2
+// active, both with positive multipliers.
+fn active_qp() -> ProblemQP<Scalar> {
+    ProblemQP {
+        quadratic: dense_csc(&[&[2.0, 0.0, 0.0], &[0.0, 1.0, 0.0], &[0.0, 0.0, 1.0]]),
+        linear: vec![-4.0, -3.0, -1.0],
+        inequalities: Some(InequalityConstraints {
+            matrix: dense_csc(&[&[1.0, 1.0, 1.0]]),
+            rhs: vec![2.0],
+        }),
+        equalities: None,
+        bounds: Some(Bounds {
+            lower: vec![0.0; 3],
+            upper: vec![10.0; 3],
+        }),
+        groups: Vec::new(),
+        integrality: Vec::new(),
+        names: Names::default(),
+        sense: ObjectiveSense::Minimize,
+        offset: 0.0,
+        quadratic_constraints: Vec::new(),
+    }
+}
+
+fn zero_delta() -> DataGradient<Scalar> {
+    DataGradient {
+        linear: vec![0.0; 3],
+        equality_rhs: Vec::new(),
+        inequality_rhs: vec![0.0],
+        lower: vec![0.0; 3],
+        upper: vec![0.0; 3],
+    }
+}
+
+fn perturbed(delta: &DataGradient<Scalar>, step: Scalar) -> ProblemQP<Scalar> {
+    let mut problem = active_qp();
+    for (q, d) in problem.linear.iter_mut().zip(&delta.linear) {
+        *q += step * d;
+    }
+    let ineq = problem.inequalities.as_mut().unwrap();
+    ineq.rhs[0] += step * delta.inequality_rhs[0];
+    let bounds = problem.bounds.as_mut().unwrap();
+    for (l, d) in bounds.lower.iter_mut().zip(&delta.lower) {
+        *l += step * d;
+    }
+    problem
+}
+
+#[test]
+#[cfg_attr(feature = "f32", ignore = "needs f64 accuracy")]
+fn qp_derivatives_match_finite_differences() {
+    let options = SolveOptions::default();
+    let solver = ActiveSetSolver::new();
+    let problem = active_qp();
+    let solution = solver.solve_qp(&problem, &options).unwrap();
+    assert_eq!(solution.status, Status::Optimal);
+    let sensitivity = Sensitivity::qp(&problem, &solution, 1e-9).unwrap();
+    assert_eq!(sensitivity.active_inequalities(), vec![0]);
+
+    let mut directions = Vec::new();
+    for j in 0..3 {
+        let mut delta = zero_delta();
+        delta.linear[j] = 1.0;
+        directions.push(delta);
+    }
+    let mut delta = zero_delta();
+    delta.inequality_rhs[0] = 1.0;
+    directions.push(delta);
+    let mut delta = zero_delta();
+    delta.lower[2] = 1.0;
+    directions.push(delta);
+
+    let step = 1e-6;
+    for delta in &directions {
+        let dx = sensitivity.forward(delta).unwrap();
+        let moved = solver.solve_qp(&perturbed(delta, step), &options).unwrap();
+        for ((d, &after), &before) in dx.iter().zip(&moved.primal).zip(&solution.primal) {
+            assert!((d - (after - before) / step).abs() < 1e-5);
+        }
+    }
+
+    // backward is the transpose of forward: dl/dθ · δ = dl/dx · dx.
+    let dl_dx = [1.0, -2.0, 0.5];
+    let gradient = sensitivity.backward(&dl_dx).unwrap();
+    for delta in &directions {
+        let dx = sensitivity.forward(delta).unwrap();
+        let lhs: Scalar = dl_dx.iter().zip(&dx).map(|(g, d)| g * d).sum();
+        let rhs: Scalar = gradient
+            .linear
+            .iter()
+            .zip(&delta.linear)
+            .chain(gradient.inequality_rhs.iter().zip(&delta.inequality_rhs))
+            .chain(gradient.lower.iter().zip(&delta.lower))
+            .map(|(g, d)| g * d)
+            .sum();
+        assert!((lhs - rhs).abs() < 1e-9);
+    }
+    // Inactive bounds have no effect.
+    assert_eq!(&gradient.lower[..2], &[0.0, 0.0]);
+    assert_eq!(gradient.upper, vec![0.0; 3]);
+}
+
+#[test]
+fn unconstrained_jacobian_is_the_negated_inverse() {
+    let problem = ProblemQP {
+        quadratic: dense_csc(&[&[2.0, 0.0], &[0.0, 4.0]]),
+        linear: vec![1.0, 1.0],
+        inequalities: None,
+        equalities: None,
+        bounds: None,
+        groups: Vec::new(),
+        integrality: Vec::new(),
+        names: Names::default(),
+        sense: ObjectiveSense::Minimize,
+        offset: 0.0,
+        quadratic_constraints: Vec::new(),
+    };
+    let solution = ActiveSetSolver::new()
+        .solve_qp(&problem, &SolveOptions::default())
+        .unwrap();
+    let jacobian = Sensitivity::qp(&problem, &solution, 1e-9)
+        .unwrap()
+        .jacobian_linear()
+        .unwrap();
+    let expected = [[-0.5, 0.0], [0.0, -0.25]];
+    for (row, expected) in jacobian.iter().zip(&expected) {
+        for (value, expected) in row.iter().zip(expected) {
+            assert!((value - expected).abs() < 1e-12);
+        }
+    }
+}
+
+#[test]
+#[cfg_attr(feature = "f32", ignore = "needs f64 accuracy")]
+fn lp_vertex_moves_with_the_active_rows() {
+    // max x0 + x1 s.t. x0 + 2x1 <= 4, 3x0 + x1 <= 6, x >= 0: the vertex
+    // (8/5, 6/5) solves both rows, so dx/dh is the inverse of their matrix.
+    let problem = ProblemLP {
+        cost: vec![-1.0, -1.0],
+        inequalities: Some(InequalityConstraints {
+            matrix: dense_csc(&[&[1.0, 2.0], &[3.0, 1.0]]),
+            rhs: vec![4.0, 6.0],
+        }),
+        equalities: None,
+        bounds: Some(Bounds {
+            lower: vec![0.0, 0.0],
+            upper: vec![Scalar::INFINITY, Scalar::INFINITY],
+        }),
+        groups: Vec::new(),
+        integrality: Vec::new(),
+        names: Names::default(),
+        sense: ObjectiveSense::Minimize,
+        offset: 0.0,
+    };
+    let solution = SimplexSolver::new()
+        .solve_lp(&problem, &SolveOptions::default())
+        .unwrap();
+    let sensitivity = Sensitivity::lp(&problem, &solution, 1e-9).unwrap();
+    let gradient = sensitivity.backward(&[1.0, 0.0]).unwrap();
+    assert!((gradient.inequality_rhs[0] + 0.2).abs() < 1e-9);
+    assert!((gradient.inequality_rhs[1] - 0.4).abs() < 1e-9);
+    // A vertex does not move with the cost.
+    assert!(gradient.linear.iter().all(|g| g.abs() < 1e-9));
+}
+
+#[test]
+fn requires_an_optimal_solution() {
+    let problem = active_qp();
+    let mut solution = ActiveSetSolver::new()
+        .solve_qp(&problem, &SolveOptions::default())
+        .unwrap();
+    solution.status = Status::MaxIterations;
+    assert!(Sensitivity::qp(&problem, &solution, 1e-9).is_err());
+}
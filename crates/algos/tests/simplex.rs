@@ -0,0 +1,158 @@
+use cvxrs_algos::simplex::SimplexSolver;
+use cvxrs_core::math::Scalar;
+use cvxrs_core::options::SolveOptions;
+use cvxrs_core::problem::{Bounds, InequalityConstraints, Names, ObjectiveSense, ProblemLP};
+use cvxrs_core::solution::{Certificate, Status};
+use cvxrs_core::stats::{Basis, BasisStatus};
+
+mod common;
+use common::{dense_csc, vertex_lp};
+
+#[test]
+fn lp_solution_carries_an_exact_basis() {
+    let problem = vertex_lp();
+    let solution = SimplexSolver::new()
+        .solve_lp(&problem, &SolveOptions::default())
+        .expect("solve");
+    assert_eq!(solution.status, Status::Optimal);
+    assert!((solution.primal[0] - 1.0).abs() < 1e-12);
+    assert!((solution.primal[1] - 2.0).abs() < 1e-12);
+    assert!((solution.objective_value + 5.0).abs() < 1e-12);
+    assert_eq!(
+        solution.basis,
+        Some(Basis {
+            variables: vec![BasisStatus::Basic, BasisStatus::AtUpper],
+            inequalities: vec![BasisStatus::Basic],
+        })
+    );
+    // c + Cᵀy + Gᵀz + w = 0 with x0 basic, so only the upper bound on x1
+    // carries a multiplier.
+    assert!((solution.equality_dual[0] - 1.0).abs() < 1e-12);
+    let (z, w) = (solution.inequality_dual[0], &solution.inequality_dual[1..]);
+    assert!(z.abs() < 1e-12);
+    assert!(w[0].abs() < 1e-12);
+    assert!((w[1] - 1.0).abs() < 1e-12);
+}
+
+#[test]
+fn presolve_removes_fixed_and_empty_columns() {
+    // min x0 + x1 - x2  s.t.  x0 + x2 >= 2, 0 <= x0 <= 5, x1 in [0, 4],
+    // x2 fixed at 1: x1 appears in no row and goes to its cheap bound.
+    let problem = ProblemLP {
+        cost: vec![1.0, 1.0, -1.0],
+        equalities: None,
+        inequalities: Some(InequalityConstraints {
+            matrix: dense_csc(&[&[-1.0, 0.0, -1.0]]),
+            rhs: vec![-2.0],
+        }),
+        bounds: Some(Bounds {
+            lower: vec![0.0, 0.0, 1.0],
+            upper: vec![5.0, 4.0, 1.0],
+        }),
+        groups: Vec::new(),
+        integrality: Vec::new(),
+        names: Names::default(),
+        sense: ObjectiveSense::Minimize,
+        offset: 0.0,
+    };
+    let solution = SimplexSolver::new()
+        .solve_lp(&problem, &SolveOptions::default())
+        .expect("solve");
+    assert_eq!(solution.status, Status::Optimal);
+    assert_eq!(solution.primal, vec![1.0, 0.0, 1.0]);
+    assert!(solution.objective_value.abs() < 1e-12);
+    let basis = solution.basis.expect("basis");
+    assert_eq!(basis.variables[1], BasisStatus::AtLower);
+    assert_eq!(basis.variables[2], BasisStatus::AtLower);
+    assert_eq!(basis.inequalities, vec![BasisStatus::AtUpper]);
+    assert!((solution.inequality_dual[0] - 1.0).abs() < 1e-12);
+}
+
+#[test]
+fn terminates_on_a_cycling_example() {
+    // Beale's example cycles under textbook Dantzig pricing; the optimum is
+    // -5/4 at x0 = 1, x2 = 1.
+    let problem = ProblemLP {
+        cost: vec![-0.75, 20.0, -0.5, 6.0],
+        equalities: None,
+        inequalities: Some(InequalityConstraints {
+            matrix: dense_csc(&[
+                &[0.25, -8.0, -1.0, 9.0],
+                &[0.5, -12.0, -0.5, 3.0],
+                &[0.0, 0.0, 1.0, 0.0],
+            ]),
+            rhs: vec![0.0, 0.0, 1.0],
+        }),
+        bounds: Some(Bounds {
+            lower: vec![0.0; 4],
+            upper: vec![Scalar::INFINITY; 4],
+        }),
+        groups: Vec::new(),
+        integrality: Vec::new(),
+        names: Names::default(),
+        sense: ObjectiveSense::Minimize,
+        offset: 0.0,
+    };
+    let solution = SimplexSolver::new()
+        .solve_lp(&problem, &SolveOptions::default())
+        .expect("solve");
+    assert_eq!(solution.status, Status::Optimal);
+    assert!((solution.objective_value + 1.25).abs() < 1e-12);
+    assert!((solution.primal[0] - 1.0).abs() < 1e-12);
+    assert!((solution.primal[2] - 1.0).abs() < 1e-12);
+}
+
+#[test]
+fn reports_infeasible_and_unbounded_problems() {
+    let infeasible = ProblemLP {
+        cost: vec![1.0, 1.0],
+        equalities: None,
+        inequalities: Some(InequalityConstraints {
+            matrix: dense_csc(&[&[-1.0, -1.0]]),
+            rhs: vec![-3.0],
+        }),
+        bounds: Some(Bounds {
+            lower: vec![0.0, 0.0],
+            upper: vec![1.0, 1.0],
+        }),
+        groups: Vec::new(),
+        integrality: Vec::new(),
+        names: Names::default(),
+        sense: ObjectiveSense::Minimize,
+        offset: 0.0,
+    };
+    let solver = SimplexSolver::new();
+    let solution = solver
+        .solve_lp(&infeasible, &SolveOptions::default())
+        .expect("solve");
+    assert_eq!(solution.status, Status::PrimalInfeasible);
+
+    // min -2x0 + x1  s.t.  x0 - 2x1 <= 1, x >= 0: the cost falls along x0 = 2x1.
+    let unbounded = ProblemLP {
+        cost: vec![-2.0, 1.0],
+        equalities: None,
+        inequalities: Some(InequalityConstraints {
+            matrix: dense_csc(&[&[1.0, -2.0]]),
+            rhs: vec![1.0],
+        }),
+        bounds: Some(Bounds {
+            lower: vec![0.0, 0.0],
+            upper: vec![Scalar::INFINITY, Scalar::INFINITY],
+        }),
+        groups: Vec::new(),
+        integrality: Vec::new(),
+        names: Names::default(),
+        sense: ObjectiveSense::Minimize,
+        offset: 0.0,
+    };
+    let solution = solver
+        .solve_lp(&unbounded, &SolveOptions::default())
+        .expect("solve");
+    assert_eq!(solution.status, Status::DualInfeasible);
+    let Some(Certificate::DualInfeasible { direction }) = solution.certificate else {
+        panic!("expected an improving ray, got {:?}", solution.certificate);
+    };
+    assert!(direction.iter().all(|&d| d >= -1e-12));
+    assert!(direction[0] - 2.0 * direction[1] <= 1e-12);
+    assert!((-2.0 * direction[0] + direction[1] + 1.0).abs() < 1e-12);
+}
@@ -0,0 +1,130 @@
+use crate::{stacked_rows, SolverError};
+use cvxrs_algos::fista;
+use cvxrs_core::math::RealNumber;
+use cvxrs_core::options::Method;
+use cvxrs_core::problem::{ProblemLP, ProblemQP};
+use cvxrs_core::solution::{Solution, Status};
+
+// Up to this many variables and stacked rows the dense methods are cheap and
+// give exact active-set or vertex solutions.
+const SMALL_VARIABLES: usize = 100;
+const SMALL_ROWS: usize = 300;
+// LPs with this many constraint nonzeros go to the matrix-free PDHG rather
+// than factoring a KKT system every iteration.
+const LARGE_NONZEROS: usize = 1_000_000;
+
+// Quadratic constraints and box QPs stay with ADMM, which handles the
+// first through the conic form and hands the second to FISTA.
+pub(crate) fn qp_method<T: RealNumber>(problem: &ProblemQP<T>) -> Method {
+    let rows = stacked_rows(
+        problem.equalities.as_ref(),
+        problem.inequalities.as_ref(),
+        problem.bounds.as_ref(),
+    );
+    if problem.quadratic_constraints.is_empty()
+        && !fista::is_box_qp(problem)
+        && problem.nvars() <= SMALL_VARIABLES
+        && rows <= SMALL_ROWS
+    {
+        Method::ActiveSet
+    } else {
+        Method::Admm
+    }
+}
+
+pub(crate) fn lp_method<T: RealNumber>(problem: &ProblemLP<T>) -> Method {
+    let rows = stacked_rows(
+        problem.equalities.as_ref(),
+        problem.inequalities.as_ref(),
+        problem.bounds.as_ref(),
+    );
+    let nonzeros = problem.equalities.as_ref().map_or(0, |eq| eq.matrix.nnz())
+        + problem
+            .inequalities
+            .as_ref()
+            .map_or(0, |ineq| ineq.matrix.nnz());
+    if problem.nvars() <= SMALL_VARIABLES && rows <= SMALL_ROWS {
+        Method::Simplex
+    } else if nonzeros >= LARGE_NONZEROS {
+        Method::Pdhg
+    } else {
+        Method::Ipm
+    }
+}
+
+// Whether an automatic pick broke down and ADMM should have a go. A
+// validation error comes back the same from ADMM, so retrying is harmless.
+// PDHG certifies infeasible and unbounded LPs itself, so running out of
+// iterations there means it stalled, not that the LP has no optimum.
+pub(crate) fn failed<T: RealNumber>(
+    method: Method,
+    result: &Result<Solution<T>, SolverError>,
+) -> bool {
+    match result {
+        Ok(solution) => {
+            solution.status == Status::NumericalFailure
+                || (method == Method::Pdhg && solution.status == Status::MaxIterations)
+        }
+        Err(SolverError::Unsupported(_)) | Err(SolverError::InvalidProblem(_)) => true,
+        Err(SolverError::NonConvex(_)) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cvxrs_core::problem::{Bounds, CscMatrix, InequalityConstraints, Names, ObjectiveSense};
+
+    fn lp(n: usize) -> ProblemLP<f64> {
+        ProblemLP {
+            cost: vec![1.0; n],
+            inequalities: Some(InequalityConstraints {
+                matrix: CscMatrix {
+                    nrows: 1,
+                    ncols: n,
+                    indptr: (0..=n).collect(),
+                    indices: vec![0; n],
+                    data: vec![-1.0; n],
+                },
+                rhs: vec![-1.0],
+            }),
+            equalities: None,
+            bounds: Some(Bounds {
+                lower: vec![0.0; n],
+                upper: vec![f64::INFINITY; n],
+            }),
+            groups: Vec::new(),
+            integrality: Vec::new(),
+            names: Names::default(),
+            sense: ObjectiveSense::Minimize,
+            offset: 0.0,
+        }
+    }
+
+    #[test]
+    fn size_decides_the_lp_method() {
+        assert_eq!(lp_method(&lp(10)), Method::Simplex);
+        assert_eq!(lp_method(&lp(1000)), Method::Ipm);
+    }
+
+    #[test]
+    fn numerical_failures_fall_back() {
+        let mut solution = Solution::<f64>::with_capacity(1, 0, 0);
+        assert!(failed(Method::Ipm, &Ok(solution.clone())));
+        solution.status = Status::Optimal;
+        assert!(!failed(Method::Ipm, &Ok(solution)));
+        assert!(!failed::<f64>(Method::Ipm, &Err(SolverError::NonConvex(0))));
+    }
+
+    #[test]
+    fn stalled_pdhg_falls_back_but_its_certificates_stand() {
+        let mut solution = Solution::<f64>::with_capacity(1, 0, 0);
+        solution.status = Status::MaxIterations;
+        assert!(failed(Method::Pdhg, &Ok(solution.clone())));
+        assert!(!failed(Method::Ipm, &Ok(solution.clone())));
+        for status in [Status::PrimalInfeasible, Status::DualInfeasible] {
+            solution.status = status;
+            assert!(!failed(Method::Pdhg, &Ok(solution.clone())));
+        }
+    }
+}
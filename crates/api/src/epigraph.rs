@@ -0,0 +1,357 @@
+use crate::remap::{remap_warm_start, ConstraintLayout, LayoutEdit};
+use crate::SolverError;
+use cvxrs_core::math::RealNumber;
+use cvxrs_core::problem::{Bounds, CscMatrix, InequalityConstraints, Names, WarmStart};
+use cvxrs_core::solution::{Certificate, Solution};
+use serde::{Deserialize, Serialize};
+
+// Nonsmooth objective terms on single variables, which the builders replace
+// with auxiliary variables and inequality rows.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum ObjectiveTerm<T> {
+    // weight·|x_i|, as weight·t with -t <= x_i <= t.
+    Abs { index: usize, weight: T },
+    // weight·h(x_i) for the Huber function h(r) = ½r² when |r| <= delta and
+    // delta(|r| - ½delta) beyond, as weight·(½s² + delta·t) with
+    // |x_i - s| <= t.
+    Huber { index: usize, weight: T, delta: T },
+}
+
+// Takes solutions of a problem with epigraph variables back to the
+// variables and rows that were declared. The auxiliary variables come after
+// the declared ones and their rows after the declared inequalities.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VariableMap {
+    extended: ConstraintLayout,
+    edit: LayoutEdit,
+}
+
+impl VariableMap {
+    fn identity(nvars: usize, equalities: usize, inequalities: usize, bounds: bool) -> Self {
+        let layout = ConstraintLayout {
+            nvars,
+            equalities,
+            inequalities,
+            bounds,
+        };
+        Self {
+            extended: layout,
+            edit: LayoutEdit::between(&layout, &layout),
+        }
+    }
+
+    pub fn declared_vars(&self) -> usize {
+        self.edit.variables.len()
+    }
+
+    // Drops the auxiliary entries from the primal, the duals, the
+    // certificate and the basis. The objective is already the declared one.
+    pub fn restrict<T: RealNumber>(&self, mut solution: Solution<T>) -> Solution<T> {
+        let (primal, equality_dual, inequality_dual) = self.restrict_vectors(
+            std::mem::take(&mut solution.primal),
+            std::mem::take(&mut solution.equality_dual),
+            std::mem::take(&mut solution.inequality_dual),
+        );
+        solution.primal = primal;
+        solution.equality_dual = equality_dual;
+        solution.inequality_dual = inequality_dual;
+        solution.certificate = solution.certificate.map(|certificate| match certificate {
+            Certificate::PrimalInfeasible {
+                equality_dual,
+                inequality_dual,
+            } => {
+                let (_, equality_dual, inequality_dual) =
+                    self.restrict_vectors(Vec::new(), equality_dual, inequality_dual);
+                Certificate::PrimalInfeasible {
+                    equality_dual,
+                    inequality_dual,
+                }
+            }
+            Certificate::DualInfeasible { mut direction } => {
+                direction.truncate(self.declared_vars());
+                Certificate::DualInfeasible { direction }
+            }
+        });
+        if let Some(basis) = &mut solution.basis {
+            basis.variables.truncate(self.declared_vars());
+            basis.inequalities.truncate(self.edit.inequalities.len());
+        }
+        solution
+    }
+
+    fn restrict_vectors<T: RealNumber>(
+        &self,
+        primal: Vec<T>,
+        equality_dual: Vec<T>,
+        inequality_dual: Vec<T>,
+    ) -> (Vec<T>, Vec<T>, Vec<T>) {
+        let extended = WarmStart {
+            primal,
+            equality_dual,
+            inequality_dual,
+        };
+        let (declared, _) = remap_warm_start(&extended, &self.extended, &self.edit);
+        (
+            declared.primal,
+            declared.equality_dual,
+            declared.inequality_dual,
+        )
+    }
+}
+
+// Appends the epigraph variables and rows of `terms` to a problem with
+// `linear.len()` variables and `equalities` equality rows. `quadratic` is
+// None for an LP, which only takes Abs terms.
+pub(crate) fn reformulate<T: RealNumber>(
+    terms: &[ObjectiveTerm<T>],
+    quadratic: Option<&mut CscMatrix<T>>,
+    linear: &mut Vec<T>,
+    equalities: usize,
+    inequality: &mut Option<InequalityConstraints<T>>,
+    bounds: &mut Option<Bounds<T>>,
+) -> Result<VariableMap, SolverError> {
+    let n = linear.len();
+    let declared_rows = inequality.as_ref().map_or(0, |ineq| ineq.matrix.nrows);
+
+    // Rows of the form ±x_i ∓ s - t <= 0, each over the new columns too.
+    let mut rows: Vec<Vec<(usize, T)>> = Vec::new();
+    let mut diagonal = Vec::new();
+    for term in terms {
+        let (index, weight) = match *term {
+            ObjectiveTerm::Abs { index, weight } | ObjectiveTerm::Huber { index, weight, .. } => {
+                (index, weight)
+            }
+        };
+        if index >= n {
+            return Err(SolverError::InvalidProblem(format!(
+                "objective term on variable {index} but the problem has {n}"
+            )));
+        }
+        if !(weight.is_finite() && weight >= T::zero()) {
+            return Err(SolverError::InvalidProblem(
+                "objective term weights must be finite and nonnegative".into(),
+            ));
+        }
+        match *term {
+            ObjectiveTerm::Abs { .. } => {
+                let t = linear.len();
+                linear.push(weight);
+                rows.push(vec![(index, T::one()), (t, -T::one())]);
+                rows.push(vec![(index, -T::one()), (t, -T::one())]);
+            }
+            ObjectiveTerm::Huber { delta, .. } => {
+                if quadratic.is_none() {
+                    return Err(SolverError::InvalidProblem(
+                        "Huber terms need a quadratic objective".into(),
+                    ));
+                }
+                if !(delta.is_finite() && delta > T::zero()) {
+                    return Err(SolverError::InvalidProblem(
+                        "the Huber threshold must be finite and positive".into(),
+                    ));
+                }
+                let (s, t) = (linear.len(), linear.len() + 1);
+                linear.push(T::zero());
+                linear.push(weight * delta);
+                diagonal.push((s, weight));
+                rows.push(vec![(index, T::one()), (s, -T::one()), (t, -T::one())]);
+                rows.push(vec![(index, -T::one()), (s, T::one()), (t, -T::one())]);
+            }
+        }
+    }
+    let total = linear.len();
+    if total == n {
+        // Nothing to add, and mismatched dimensions are left for validation
+        // to report.
+        return Ok(VariableMap::identity(
+            n,
+            equalities,
+            declared_rows,
+            bounds.is_some(),
+        ));
+    }
+
+    if let Some(p) = quadratic {
+        p.nrows = total;
+        p.ncols = total;
+        for col in n..total {
+            if let Some(&(_, value)) = diagonal.iter().find(|&&(s, _)| s == col) {
+                p.indices.push(col);
+                p.data.push(value);
+            }
+            p.indptr.push(p.indices.len());
+        }
+    }
+    if let Some(ineq) = inequality.as_ref() {
+        ineq.validate(n)
+            .map_err(|err| SolverError::InvalidProblem(err.to_string()))?;
+    }
+    let ineq = inequality.get_or_insert_with(|| InequalityConstraints {
+        matrix: CscMatrix {
+            nrows: 0,
+            ncols: n,
+            indptr: vec![0; n + 1],
+            indices: Vec::new(),
+            data: Vec::new(),
+        },
+        rhs: Vec::new(),
+    });
+    let mut columns: Vec<Vec<(usize, T)>> = vec![Vec::new(); total];
+    let matrix = &ineq.matrix;
+    for (col, column) in columns.iter_mut().enumerate().take(n) {
+        for idx in matrix.indptr[col]..matrix.indptr[col + 1] {
+            column.push((matrix.indices[idx], matrix.data[idx]));
+        }
+    }
+    for (k, entries) in rows.iter().enumerate() {
+        for &(col, value) in entries {
+            columns[col].push((declared_rows + k, value));
+        }
+    }
+    let mut indptr = Vec::with_capacity(total + 1);
+    let mut indices = Vec::new();
+    let mut data = Vec::new();
+    indptr.push(0);
+    for column in columns {
+        for (row, value) in column {
+            indices.push(row);
+            data.push(value);
+        }
+        indptr.push(indices.len());
+    }
+    ineq.matrix = CscMatrix {
+        nrows: declared_rows + rows.len(),
+        ncols: total,
+        indptr,
+        indices,
+        data,
+    };
+    ineq.rhs.resize(declared_rows + rows.len(), T::zero());
+    if let Some(bounds) = bounds {
+        bounds.lower.resize(total, T::neg_infinity());
+        bounds.upper.resize(total, T::infinity());
+    }
+
+    let mut map = VariableMap::identity(n, equalities, declared_rows, bounds.is_some());
+    map.extended.nvars = total;
+    map.extended.inequalities += rows.len();
+    Ok(map)
+}
+
+// Names the auxiliary variables and rows `epigraph[k]` in whichever of the
+// declared name lists were given, so they keep matching the problem.
+pub(crate) fn extend_names(names: &mut Names, nvars: usize, inequalities: usize) {
+    for (list, len) in [
+        (&mut names.variables, nvars),
+        (&mut names.inequalities, inequalities),
+    ] {
+        if list.is_empty() {
+            continue;
+        }
+        let declared = list.len();
+        list.extend((declared..len).map(|k| format!("epigraph[{}]", k - declared)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{LpBuilder, Method, QpBuilder, Solver};
+    use cvxrs_core::options::SolveOptions;
+    use cvxrs_core::problem::{Bounds, CscMatrix};
+    use cvxrs_core::solution::Status;
+
+    fn scalar(value: f64) -> CscMatrix<f64> {
+        CscMatrix {
+            nrows: 1,
+            ncols: 1,
+            indptr: vec![0, 1],
+            indices: vec![0],
+            data: vec![value],
+        }
+    }
+
+    #[test]
+    fn abs_term_soft_thresholds() {
+        // ½(x - 3)² + 2|x| is smallest at x = 1, where it is 4.
+        let (problem, map) = QpBuilder::new()
+            .p(scalar(1.0))
+            .q(vec![-3.0])
+            .offset(4.5)
+            .abs(0, 2.0)
+            .build_mapped()
+            .unwrap();
+        assert_eq!(problem.nvars(), 2);
+        let solution = Solver::new()
+            .options(SolveOptions::with_tolerance(1e-9))
+            .solve_qp(problem)
+            .unwrap();
+        let solution = map.restrict(solution);
+        assert_eq!(solution.status, Status::Optimal);
+        assert_eq!(solution.primal.len(), 1);
+        assert!((solution.primal[0] - 1.0).abs() < 1e-4);
+        assert!((solution.objective_value - 4.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn huber_term_is_linear_past_the_threshold() {
+        // h(x) + ½(x - 4)² with threshold 1: 1 + (x - 4) = 0 at x = 3, where
+        // the objective is 2.5 + 0.5.
+        let (problem, map) = QpBuilder::new()
+            .p(scalar(1.0))
+            .q(vec![-4.0])
+            .offset(8.0)
+            .huber(0, 1.0, 1.0)
+            .build_mapped()
+            .unwrap();
+        let solution = map.restrict(
+            Solver::new()
+                .method(Method::ActiveSet)
+                .solve_qp(problem)
+                .unwrap(),
+        );
+        assert!((solution.primal[0] - 3.0).abs() < 1e-8);
+        assert!((solution.objective_value - 3.0).abs() < 1e-8);
+    }
+
+    #[test]
+    fn lp_duals_keep_the_declared_layout() {
+        // min |x0| + |x1| s.t. x0 + x1 >= 1 and x0 <= 0.25.
+        let row = CscMatrix {
+            nrows: 1,
+            ncols: 2,
+            indptr: vec![0, 1, 2],
+            indices: vec![0, 0],
+            data: vec![-1.0, -1.0],
+        };
+        let (problem, map) = LpBuilder::new()
+            .c(vec![0.0, 0.0])
+            .a(row, vec![-1.0])
+            .bounds(Bounds {
+                lower: vec![f64::NEG_INFINITY; 2],
+                upper: vec![0.25, f64::INFINITY],
+            })
+            .abs(0, 1.0)
+            .abs(1, 1.0)
+            .build_mapped()
+            .unwrap();
+        assert_eq!(problem.nvars(), 4);
+        let solution = map.restrict(
+            Solver::new()
+                .method(Method::Simplex)
+                .solve_lp(problem)
+                .unwrap(),
+        );
+        assert_eq!(solution.status, Status::Optimal);
+        assert!((solution.objective_value - 1.0).abs() < 1e-9);
+        assert_eq!(solution.primal.len(), 2);
+        assert_eq!(solution.inequality_dual.len(), 3);
+    }
+
+    #[test]
+    fn terms_are_checked() {
+        let builder = || QpBuilder::new().p(scalar(1.0)).q(vec![0.0]);
+        assert!(builder().abs(1, 1.0).build().is_err());
+        assert!(builder().abs(0, -1.0).build().is_err());
+        assert!(builder().huber(0, 1.0, 0.0).build().is_err());
+    }
+}
@@ -0,0 +1,228 @@
+use crate::{Solver, SolverError};
+use cvxrs_core::math::{norm_inf, RealNumber};
+use cvxrs_core::problem::{
+    Bounds, CscMatrix, EqualityConstraints, InequalityConstraints, Names, ObjectiveSense,
+    ProblemLP, ProblemQP, WarmStart,
+};
+use cvxrs_core::solution::{Solution, Status};
+use cvxrs_core::traits::LinearOperator;
+
+// The outcome of a phase-1 solve. `violation` is the largest violation of a
+// constraint or bound at `point`, measured on the declared problem; `solution`
+// is the phase-1 LP itself, over x and the violation variables.
+#[derive(Debug, Clone)]
+pub struct Feasibility<T: RealNumber> {
+    pub feasible: bool,
+    pub point: Vec<T>,
+    pub violation: T,
+    pub solution: Solution<T>,
+}
+
+impl<T: RealNumber> Feasibility<T> {
+    // The point as a primal warm start for the declared problem.
+    pub fn warm_start(&self) -> WarmStart<T> {
+        WarmStart {
+            primal: self.point.clone(),
+            equality_dual: Vec::new(),
+            inequality_dual: Vec::new(),
+        }
+    }
+}
+
+impl<T> Solver<T>
+where
+    T: RealNumber,
+{
+    // Ignores the objective and minimizes the total violation of the rows:
+    //
+    //   min Σs + Σ(e⁺ + e⁻)  s.t.  Ax - s <= b,  Cx + e⁺ - e⁻ = d,
+    //                              s, e⁺, e⁻ >= 0,  l <= x <= u
+    //
+    // The bounds stay hard and integrality is dropped. The problem is
+    // feasible when the phase-1 optimum leaves no row violated beyond the
+    // primal tolerance.
+    pub fn find_feasible_point(
+        &mut self,
+        problem: ProblemQP<T>,
+    ) -> Result<Feasibility<T>, SolverError> {
+        problem
+            .validate()
+            .and_then(|()| problem.require_linear_constraints())
+            .map_err(|err| SolverError::InvalidProblem(err.to_string()))?;
+        let n = problem.nvars();
+        let mineq = problem
+            .inequalities
+            .as_ref()
+            .map_or(0, |ineq| ineq.matrix.nrows);
+        let meq = problem.equalities.as_ref().map_or(0, |eq| eq.matrix.nrows);
+        let total = n + mineq + 2 * meq;
+
+        let mut cost = vec![T::zero(); n];
+        cost.resize(total, T::one());
+        let mut bounds = problem
+            .bounds
+            .clone()
+            .unwrap_or_else(|| Bounds::unbounded(n));
+        bounds.lower.resize(total, T::zero());
+        bounds.upper.resize(total, T::infinity());
+        let none = |count| vec![None; count];
+        let unit = |sign: T, count| (0..count).map(move |row| Some((row, sign)));
+        let phase_one = ProblemLP {
+            cost,
+            inequalities: problem.inequalities.as_ref().map(|ineq| {
+                let mut extra: Vec<_> = unit(-T::one(), mineq).collect();
+                extra.extend(none(2 * meq));
+                InequalityConstraints {
+                    matrix: append_columns(&ineq.matrix, &extra),
+                    rhs: ineq.rhs.clone(),
+                }
+            }),
+            equalities: problem.equalities.as_ref().map(|eq| {
+                let mut extra = none(mineq);
+                extra.extend(unit(T::one(), meq));
+                extra.extend(unit(-T::one(), meq));
+                EqualityConstraints {
+                    matrix: append_columns(&eq.matrix, &extra),
+                    rhs: eq.rhs.clone(),
+                }
+            }),
+            bounds: Some(bounds),
+            groups: Vec::new(),
+            integrality: Vec::new(),
+            names: Names::default(),
+            sense: ObjectiveSense::Minimize,
+            offset: T::zero(),
+        };
+        let solution = self.solve_lp(phase_one)?;
+
+        let point = solution.primal[..n].to_vec();
+        let (violation, scale) = violation(&problem, &point);
+        let tolerance = self.options.eps_abs + self.options.eps_rel * scale;
+        Ok(Feasibility {
+            feasible: solution.status == Status::Optimal && violation <= tolerance,
+            point,
+            violation,
+            solution,
+        })
+    }
+}
+
+// `matrix` with one column appended per entry of `extra`, each holding at
+// most a single value.
+fn append_columns<T: RealNumber>(
+    matrix: &CscMatrix<T>,
+    extra: &[Option<(usize, T)>],
+) -> CscMatrix<T> {
+    let mut appended = matrix.clone();
+    appended.ncols += extra.len();
+    for &entry in extra {
+        if let Some((row, value)) = entry {
+            appended.indices.push(row);
+            appended.data.push(value);
+        }
+        appended.indptr.push(appended.indices.len());
+    }
+    appended
+}
+
+// The largest violation at x and the size of the right-hand sides and bounds
+// it is measured against.
+fn violation<T: RealNumber>(problem: &ProblemQP<T>, x: &[T]) -> (T, T) {
+    let mut worst = T::zero();
+    let mut scale = T::zero();
+    if let Some(ineq) = &problem.inequalities {
+        let mut ax = vec![T::zero(); ineq.matrix.nrows];
+        ineq.matrix.apply(x, &mut ax);
+        for (&value, &rhs) in ax.iter().zip(&ineq.rhs) {
+            worst = worst.max(value - rhs);
+        }
+        scale = scale.max(norm_inf(&ineq.rhs));
+    }
+    if let Some(eq) = &problem.equalities {
+        let mut cx = vec![T::zero(); eq.matrix.nrows];
+        eq.matrix.apply(x, &mut cx);
+        for (&value, &rhs) in cx.iter().zip(&eq.rhs) {
+            worst = worst.max((value - rhs).abs());
+        }
+        scale = scale.max(norm_inf(&eq.rhs));
+    }
+    if let Some(bounds) = &problem.bounds {
+        for ((&value, &lower), &upper) in x.iter().zip(&bounds.lower).zip(&bounds.upper) {
+            worst = worst.max(lower - value).max(value - upper);
+            for limit in [lower, upper] {
+                if limit.is_finite() {
+                    scale = scale.max(limit.abs());
+                }
+            }
+        }
+    }
+    (worst, scale)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Method, Solver};
+    use cvxrs_core::problem::{
+        Bounds, CscMatrix, InequalityConstraints, Names, ObjectiveSense, ProblemQP,
+    };
+
+    // Any objective over x0 + x1 <= rhs and 0 <= x <= 1.
+    fn problem(rhs: f64) -> ProblemQP<f64> {
+        ProblemQP {
+            quadratic: CscMatrix {
+                nrows: 2,
+                ncols: 2,
+                indptr: vec![0, 1, 2],
+                indices: vec![0, 1],
+                data: vec![1.0, 1.0],
+            },
+            linear: vec![-5.0, 3.0],
+            inequalities: Some(InequalityConstraints {
+                matrix: CscMatrix {
+                    nrows: 1,
+                    ncols: 2,
+                    indptr: vec![0, 1, 2],
+                    indices: vec![0, 0],
+                    data: vec![1.0, 1.0],
+                },
+                rhs: vec![rhs],
+            }),
+            equalities: None,
+            bounds: Some(Bounds {
+                lower: vec![0.0; 2],
+                upper: vec![1.0; 2],
+            }),
+            groups: Vec::new(),
+            integrality: Vec::new(),
+            names: Names::default(),
+            sense: ObjectiveSense::Minimize,
+            offset: 0.0,
+            quadratic_constraints: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn feasible_point_warm_starts_the_solve() {
+        let mut solver = Solver::new();
+        let found = solver.find_feasible_point(problem(0.5)).unwrap();
+        assert!(found.feasible, "{found:?}");
+        assert!(found.point.iter().sum::<f64>() <= 0.5 + 1e-5);
+        let solution = Solver::new()
+            .warm_start(found.warm_start())
+            .solve_qp(problem(0.5))
+            .unwrap();
+        assert!((solution.primal[0] - 0.5).abs() < 1e-3);
+    }
+
+    #[test]
+    fn infeasible_rows_report_the_smallest_violation() {
+        // x >= 0 keeps x0 + x1 at least 0, one past the right-hand side.
+        let found = Solver::new()
+            .method(Method::Simplex)
+            .find_feasible_point(problem(-1.0))
+            .unwrap();
+        assert!(!found.feasible);
+        assert!((found.violation - 1.0).abs() < 1e-9);
+        assert!((found.solution.objective_value - 1.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,358 @@
+use crate::{Solver, SolverError};
+use cvxrs_core::math::{dot, norm2, norm_inf, RealNumber};
+use cvxrs_core::problem::{Bounds, CscMatrix, Names, ObjectiveSense, ProblemQP};
+use cvxrs_core::solution::Solution;
+
+// A regression fit together with its residual Ax - b. The objective of
+// `solution` is the original one, penalties and the constant ½‖b‖² included.
+#[derive(Debug, Clone)]
+pub struct LeastSquares<T: RealNumber> {
+    pub solution: Solution<T>,
+    pub residual: Vec<T>,
+    pub residual_norm: T,
+    pub residual_inf: T,
+}
+
+impl<T> Solver<T>
+where
+    T: RealNumber,
+{
+    // min ½‖Ax - b‖² within `bounds`, solved through the normal equations
+    // AᵀA x = Aᵀb. With bounds alone this is a box QP, which the default
+    // method hands to FISTA.
+    pub fn solve_least_squares(
+        &mut self,
+        a: &CscMatrix<T>,
+        b: &[T],
+        bounds: Option<Bounds<T>>,
+    ) -> Result<LeastSquares<T>, SolverError> {
+        check_data(a, b)?;
+        let problem = ProblemQP {
+            quadratic: gram(a),
+            linear: negated_atb(a, b),
+            inequalities: None,
+            equalities: None,
+            bounds,
+            groups: Vec::new(),
+            integrality: Vec::new(),
+            names: Names::default(),
+            sense: ObjectiveSense::Minimize,
+            offset: T::zero(),
+            quadratic_constraints: Vec::new(),
+        };
+        let solution = self.solve_qp(problem)?;
+        Ok(fit(a, b, solution, T::zero(), T::zero()))
+    }
+
+    // min ½‖Ax - b‖² + l1‖x‖₁ + ½l2‖x‖². Splitting x = u - v with u, v >= 0
+    // turns the l1 term into the linear l1·Σ(u + v), which leaves a box QP
+    // in 2n variables; some optimum has u_j v_j = 0, so ‖x‖₁ = Σ(u + v) there.
+    pub fn solve_elastic_net(
+        &mut self,
+        a: &CscMatrix<T>,
+        b: &[T],
+        l1: T,
+        l2: T,
+    ) -> Result<LeastSquares<T>, SolverError> {
+        check_data(a, b)?;
+        if !(l1 >= T::zero() && l2 >= T::zero()) {
+            return Err(SolverError::InvalidProblem(
+                "penalties must be nonnegative".to_string(),
+            ));
+        }
+        let n = a.ncols;
+        let atb = negated_atb(a, b);
+        let linear = atb
+            .iter()
+            .map(|&g| g + l1)
+            .chain(atb.iter().map(|&g| l1 - g))
+            .collect();
+        let problem = ProblemQP {
+            quadratic: split_gram(&gram(a), l2),
+            linear,
+            inequalities: None,
+            equalities: None,
+            bounds: Some(Bounds {
+                lower: vec![T::zero(); 2 * n],
+                upper: vec![T::infinity(); 2 * n],
+            }),
+            groups: Vec::new(),
+            integrality: Vec::new(),
+            names: Names::default(),
+            sense: ObjectiveSense::Minimize,
+            offset: T::zero(),
+            quadratic_constraints: Vec::new(),
+        };
+        let mut solution = self.solve_qp(problem)?;
+        let (u, v) = solution.primal.split_at(n);
+        solution.primal = u.iter().zip(v).map(|(&u, &v)| u - v).collect();
+        // Multipliers of u, v >= 0 have no counterpart in the original
+        // problem.
+        solution.inequality_dual.clear();
+        Ok(fit(a, b, solution, l1, l2))
+    }
+}
+
+pub fn least_squares<T: RealNumber>(
+    a: &CscMatrix<T>,
+    b: &[T],
+    bounds: Option<Bounds<T>>,
+) -> Result<LeastSquares<T>, SolverError> {
+    Solver::new().solve_least_squares(a, b, bounds)
+}
+
+pub fn lasso<T: RealNumber>(
+    a: &CscMatrix<T>,
+    b: &[T],
+    l1: T,
+) -> Result<LeastSquares<T>, SolverError> {
+    Solver::new().solve_elastic_net(a, b, l1, T::zero())
+}
+
+pub fn elastic_net<T: RealNumber>(
+    a: &CscMatrix<T>,
+    b: &[T],
+    l1: T,
+    l2: T,
+) -> Result<LeastSquares<T>, SolverError> {
+    Solver::new().solve_elastic_net(a, b, l1, l2)
+}
+
+pub fn nonnegative_least_squares<T: RealNumber>(
+    a: &CscMatrix<T>,
+    b: &[T],
+) -> Result<LeastSquares<T>, SolverError> {
+    let bounds = Bounds {
+        lower: vec![T::zero(); a.ncols],
+        upper: vec![T::infinity(); a.ncols],
+    };
+    least_squares(a, b, Some(bounds))
+}
+
+fn check_data<T: RealNumber>(a: &CscMatrix<T>, b: &[T]) -> Result<(), SolverError> {
+    a.validate()
+        .map_err(|err| SolverError::InvalidProblem(err.to_string()))?;
+    if b.len() != a.nrows {
+        return Err(SolverError::InvalidProblem(format!(
+            "right-hand side has {} entries but the matrix has {} rows",
+            b.len(),
+            a.nrows
+        )));
+    }
+    Ok(())
+}
+
+// Attaches the residual of the original coefficients and replaces the QP
+// objective with ½‖Ax - b‖² + l1‖x‖₁ + ½l2‖x‖².
+fn fit<T: RealNumber>(
+    a: &CscMatrix<T>,
+    b: &[T],
+    mut solution: Solution<T>,
+    l1: T,
+    l2: T,
+) -> LeastSquares<T> {
+    let mut residual: Vec<T> = b.iter().map(|&b| -b).collect();
+    for (col, &x) in solution.primal.iter().enumerate() {
+        for idx in a.indptr[col]..a.indptr[col + 1] {
+            residual[a.indices[idx]] += a.data[idx] * x;
+        }
+    }
+    let half = T::one() / (T::one() + T::one());
+    let x = &solution.primal;
+    let l1_norm = x.iter().fold(T::zero(), |acc, &x| acc + x.abs());
+    solution.objective_value =
+        half * dot(&residual, &residual) + l1 * l1_norm + half * l2 * dot(x, x);
+    LeastSquares {
+        residual_norm: norm2(&residual),
+        residual_inf: norm_inf(&residual),
+        residual,
+        solution,
+    }
+}
+
+fn negated_atb<T: RealNumber>(a: &CscMatrix<T>, b: &[T]) -> Vec<T> {
+    let mut linear = vec![T::zero(); a.ncols];
+    for (col, value) in linear.iter_mut().enumerate() {
+        for idx in a.indptr[col]..a.indptr[col + 1] {
+            *value -= a.data[idx] * b[a.indices[idx]];
+        }
+    }
+    linear
+}
+
+// [G + l2 I, -G; -G, G + l2 I], the Hessian of the split variables (u, v).
+fn split_gram<T: RealNumber>(g: &CscMatrix<T>, l2: T) -> CscMatrix<T> {
+    let n = g.ncols;
+    let mut indptr = vec![0];
+    let mut indices = Vec::new();
+    let mut data = Vec::new();
+    let mut column = Vec::new();
+    for col in 0..2 * n {
+        let j = col % n;
+        let sign = if col < n { T::one() } else { -T::one() };
+        for idx in g.indptr[j]..g.indptr[j + 1] {
+            column.push((g.indices[idx], sign * g.data[idx]));
+            column.push((n + g.indices[idx], -sign * g.data[idx]));
+        }
+        if l2 > T::zero() {
+            column.push((col, l2));
+        }
+        column.sort_by_key(|&(row, _)| row);
+        for (row, value) in column.drain(..) {
+            if indices.len() > indptr[col] && indices.last() == Some(&row) {
+                if let Some(last) = data.last_mut() {
+                    *last += value;
+                }
+            } else {
+                indices.push(row);
+                data.push(value);
+            }
+        }
+        indptr.push(indices.len());
+    }
+    CscMatrix {
+        nrows: 2 * n,
+        ncols: 2 * n,
+        indptr,
+        indices,
+        data,
+    }
+}
+
+// AᵀA in full symmetric storage, one column at a time: column j gathers
+// a_ri a_rj over the rows r where column j is nonzero.
+fn gram<T: RealNumber>(a: &CscMatrix<T>) -> CscMatrix<T> {
+    let n = a.ncols;
+    let mut row_entries = vec![Vec::new(); a.nrows];
+    for col in 0..n {
+        for idx in a.indptr[col]..a.indptr[col + 1] {
+            row_entries[a.indices[idx]].push((col, a.data[idx]));
+        }
+    }
+    let mut work = vec![T::zero(); n];
+    let mut touched = vec![false; n];
+    let mut pattern = Vec::new();
+    let mut indptr = vec![0];
+    let mut indices = Vec::new();
+    let mut data = Vec::new();
+    for col in 0..n {
+        for idx in a.indptr[col]..a.indptr[col + 1] {
+            let value = a.data[idx];
+            for &(row, entry) in &row_entries[a.indices[idx]] {
+                if !touched[row] {
+                    touched[row] = true;
+                    pattern.push(row);
+                }
+                work[row] += entry * value;
+            }
+        }
+        pattern.sort_unstable();
+        for row in pattern.drain(..) {
+            indices.push(row);
+            data.push(work[row]);
+            work[row] = T::zero();
+            touched[row] = false;
+        }
+        indptr.push(indices.len());
+    }
+    CscMatrix {
+        nrows: n,
+        ncols: n,
+        indptr,
+        indices,
+        data,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Columns (1, 1, 1) and (0, 1, 2): fitting a line through (0, b0),
+    // (1, b1) and (2, b2).
+    fn line_design() -> CscMatrix<f64> {
+        CscMatrix {
+            nrows: 3,
+            ncols: 2,
+            indptr: vec![0, 3, 5],
+            indices: vec![0, 1, 2, 1, 2],
+            data: vec![1.0, 1.0, 1.0, 1.0, 2.0],
+        }
+    }
+
+    #[test]
+    fn gram_matches_the_dense_product() {
+        let ata = gram(&line_design());
+        assert_eq!(ata.indptr, vec![0, 2, 4]);
+        assert_eq!(ata.indices, vec![0, 1, 0, 1]);
+        assert_eq!(ata.data, vec![3.0, 3.0, 3.0, 5.0]);
+    }
+
+    #[test]
+    fn fits_a_line_and_reports_the_residual() {
+        // The least-squares line through (0, 1), (1, 2), (2, 2) is
+        // 7/6 + x/2, leaving residuals (1/6, -1/3, 1/6).
+        let fit = least_squares(&line_design(), &[1.0, 2.0, 2.0], None).unwrap();
+        let x = &fit.solution.primal;
+        assert!((x[0] - 7.0 / 6.0).abs() < 1e-4);
+        assert!((x[1] - 0.5).abs() < 1e-4);
+        assert!((fit.residual_norm - (1.0f64 / 6.0).sqrt()).abs() < 1e-4);
+        assert!((fit.residual_inf - 1.0 / 3.0).abs() < 1e-4);
+        assert!((fit.solution.objective_value - 1.0 / 12.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn nonnegativity_clips_the_slope() {
+        // A falling trend would need a negative slope; NNLS flattens it to
+        // the mean.
+        let fit = nonnegative_least_squares(&line_design(), &[3.0, 2.0, 1.0]).unwrap();
+        let x = &fit.solution.primal;
+        assert!((x[0] - 2.0).abs() < 1e-4);
+        assert!(x[1].abs() < 1e-4);
+        assert!((fit.residual_norm - 2.0f64.sqrt()).abs() < 1e-4);
+    }
+
+    fn identity() -> CscMatrix<f64> {
+        CscMatrix {
+            nrows: 2,
+            ncols: 2,
+            indptr: vec![0, 1, 2],
+            indices: vec![0, 1],
+            data: vec![1.0, 1.0],
+        }
+    }
+
+    #[test]
+    fn lasso_soft_thresholds_an_orthogonal_design() {
+        // With A = I the solution is sign(b) max(|b| - l1, 0).
+        let fit = lasso(&identity(), &[3.0, -0.5], 1.0).unwrap();
+        let x = &fit.solution.primal;
+        assert_eq!(x.len(), 2);
+        assert!((x[0] - 2.0).abs() < 1e-4);
+        assert!(x[1].abs() < 1e-4);
+        // ½(1² + 0.5²) + 1·2
+        assert!((fit.solution.objective_value - 2.625).abs() < 1e-4);
+    }
+
+    #[test]
+    fn elastic_net_shrinks_the_lasso_solution() {
+        // With A = I the l2 term divides the soft threshold by 1 + l2.
+        let fit = elastic_net(&identity(), &[-3.0, 0.5], 1.0, 1.0).unwrap();
+        let x = &fit.solution.primal;
+        assert!((x[0] + 1.0).abs() < 1e-4);
+        assert!(x[1].abs() < 1e-4);
+        assert!(fit.solution.inequality_dual.is_empty());
+    }
+
+    #[test]
+    fn rejects_negative_penalties() {
+        let err = lasso(&identity(), &[1.0, 1.0], -1.0).unwrap_err();
+        assert!(matches!(err, SolverError::InvalidProblem(_)));
+    }
+
+    #[test]
+    fn rejects_a_mismatched_right_hand_side() {
+        let err = least_squares(&line_design(), &[1.0, 2.0], None).unwrap_err();
+        assert!(matches!(err, SolverError::InvalidProblem(_)));
+    }
+}
@@ -0,0 +1,337 @@
+use crate::{Solver, SolverError};
+use cvxrs_core::math::{dot, RealNumber};
+use cvxrs_core::problem::{
+    CscMatrix, InequalityConstraints, ObjectiveSense, ProblemLP, ProblemQP, QuadraticConstraint,
+};
+use cvxrs_core::solution::{Solution, Status};
+use cvxrs_core::traits::LinearOperator;
+use serde::{Deserialize, Serialize};
+
+// ½xᵀPx + qᵀx, minimized in its turn. Once its optimum f* is known the
+// later stages keep it at most f* + slack; a positive slack trades some of
+// this objective for the ones after it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LexObjective<T> {
+    pub quadratic: Option<CscMatrix<T>>,
+    pub linear: Vec<T>,
+    pub slack: T,
+}
+
+// One stage of a lexicographic solve. `bound` is the limit the later stages
+// keep this objective under and `slack` how far below it the final point
+// leaves it.
+#[derive(Debug, Clone)]
+pub struct LexStage<T: RealNumber> {
+    pub solution: Solution<T>,
+    pub optimum: T,
+    pub bound: T,
+    pub slack: T,
+}
+
+impl<T> Solver<T>
+where
+    T: RealNumber,
+{
+    // Minimizes `objectives` in order over the constraints of `problem`,
+    // whose own objective is ignored. A linear objective is fixed with an
+    // inequality row and a quadratic one with a quadratic constraint, which
+    // only ADMM accepts. Stages that are LPs go to solve_lp, so the LP
+    // methods apply to them. The stages stop at the first one that is not
+    // solved to optimality, which is returned last.
+    pub fn solve_lexicographic(
+        &mut self,
+        problem: ProblemQP<T>,
+        objectives: &[LexObjective<T>],
+    ) -> Result<Vec<LexStage<T>>, SolverError> {
+        let n = problem.nvars();
+        for objective in objectives {
+            check_objective(objective, n)?;
+        }
+        let mut stage = problem;
+        stage.offset = T::zero();
+        stage.sense = ObjectiveSense::Minimize;
+        let mut stages: Vec<LexStage<T>> = Vec::with_capacity(objectives.len());
+        for (k, objective) in objectives.iter().enumerate() {
+            stage.quadratic = objective
+                .quadratic
+                .clone()
+                .unwrap_or_else(|| zero_matrix(n));
+            stage.linear = objective.linear.clone();
+            let solution = if stage.quadratic.nnz() == 0 && stage.quadratic_constraints.is_empty() {
+                self.solve_lp(as_lp(&stage))?
+            } else {
+                self.solve_qp(stage.clone())?
+            };
+            let optimum = solution.objective_value;
+            let bound = optimum + objective.slack;
+            let optimal = solution.status == Status::Optimal;
+            stages.push(LexStage {
+                solution,
+                optimum,
+                bound,
+                slack: T::zero(),
+            });
+            if !optimal {
+                break;
+            }
+            if k + 1 < objectives.len() {
+                fix_objective(&mut stage, objective, bound);
+            }
+        }
+
+        let x = match stages.last() {
+            Some(last) => last.solution.primal.clone(),
+            None => return Ok(stages),
+        };
+        for (stage, objective) in stages.iter_mut().zip(objectives) {
+            stage.slack = stage.bound - evaluate(objective, &x);
+        }
+        Ok(stages)
+    }
+}
+
+fn check_objective<T: RealNumber>(
+    objective: &LexObjective<T>,
+    n: usize,
+) -> Result<(), SolverError> {
+    if objective.linear.len() != n {
+        return Err(SolverError::InvalidProblem(format!(
+            "objective has {} linear terms, expected {}",
+            objective.linear.len(),
+            n
+        )));
+    }
+    if let Some(matrix) = &objective.quadratic {
+        if matrix.nrows != n || matrix.ncols != n {
+            return Err(SolverError::InvalidProblem(format!(
+                "objective matrix is {}x{}, expected {}x{}",
+                matrix.nrows, matrix.ncols, n, n
+            )));
+        }
+    }
+    if !(objective.slack.is_finite() && objective.slack >= T::zero()) {
+        return Err(SolverError::InvalidProblem(
+            "objective slacks must be finite and nonnegative".into(),
+        ));
+    }
+    Ok(())
+}
+
+fn fix_objective<T: RealNumber>(problem: &mut ProblemQP<T>, objective: &LexObjective<T>, bound: T) {
+    match &objective.quadratic {
+        Some(matrix) if matrix.nnz() > 0 => {
+            problem.quadratic_constraints.push(QuadraticConstraint {
+                matrix: matrix.clone(),
+                linear: objective.linear.clone(),
+                rhs: bound,
+            });
+        }
+        _ => {
+            let n = objective.linear.len();
+            let ineq = problem
+                .inequalities
+                .get_or_insert_with(|| InequalityConstraints {
+                    matrix: CscMatrix {
+                        nrows: 0,
+                        ncols: n,
+                        indptr: vec![0; n + 1],
+                        indices: Vec::new(),
+                        data: Vec::new(),
+                    },
+                    rhs: Vec::new(),
+                });
+            ineq.matrix = append_row(&ineq.matrix, &objective.linear);
+            ineq.rhs.push(bound);
+        }
+    }
+}
+
+// `matrix` with a dense row appended below it.
+fn append_row<T: RealNumber>(matrix: &CscMatrix<T>, row: &[T]) -> CscMatrix<T> {
+    let mut indptr = vec![0];
+    let mut indices = Vec::with_capacity(matrix.nnz() + row.len());
+    let mut data = Vec::with_capacity(matrix.nnz() + row.len());
+    for (col, &value) in row.iter().enumerate() {
+        for idx in matrix.indptr[col]..matrix.indptr[col + 1] {
+            indices.push(matrix.indices[idx]);
+            data.push(matrix.data[idx]);
+        }
+        if value != T::zero() {
+            indices.push(matrix.nrows);
+            data.push(value);
+        }
+        indptr.push(indices.len());
+    }
+    CscMatrix {
+        nrows: matrix.nrows + 1,
+        ncols: matrix.ncols,
+        indptr,
+        indices,
+        data,
+    }
+}
+
+fn as_lp<T: RealNumber>(problem: &ProblemQP<T>) -> ProblemLP<T> {
+    ProblemLP {
+        cost: problem.linear.clone(),
+        inequalities: problem.inequalities.clone(),
+        equalities: problem.equalities.clone(),
+        bounds: problem.bounds.clone(),
+        groups: problem.groups.clone(),
+        integrality: problem.integrality.clone(),
+        names: problem.names.clone(),
+        sense: problem.sense,
+        offset: problem.offset,
+    }
+}
+
+fn zero_matrix<T: RealNumber>(n: usize) -> CscMatrix<T> {
+    CscMatrix {
+        nrows: n,
+        ncols: n,
+        indptr: vec![0; n + 1],
+        indices: Vec::new(),
+        data: Vec::new(),
+    }
+}
+
+fn evaluate<T: RealNumber>(objective: &LexObjective<T>, x: &[T]) -> T {
+    let mut value = dot(&objective.linear, x);
+    if let Some(matrix) = &objective.quadratic {
+        let mut px = vec![T::zero(); x.len()];
+        matrix.apply(x, &mut px);
+        value += T::from(0.5).unwrap() * dot(x, &px);
+    }
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LexObjective;
+    use crate::{Method, Solver};
+    use cvxrs_core::options::SolveOptions;
+    use cvxrs_core::problem::{
+        Bounds, CscMatrix, InequalityConstraints, Names, ObjectiveSense, ProblemQP,
+    };
+    use cvxrs_core::solution::Status;
+
+    // x0 + x1 <= 2 with 0 <= x <= 2; the objective is left to the stages.
+    fn region() -> ProblemQP<f64> {
+        ProblemQP {
+            quadratic: CscMatrix {
+                nrows: 2,
+                ncols: 2,
+                indptr: vec![0; 3],
+                indices: Vec::new(),
+                data: Vec::new(),
+            },
+            linear: vec![0.0; 2],
+            inequalities: Some(InequalityConstraints {
+                matrix: CscMatrix {
+                    nrows: 1,
+                    ncols: 2,
+                    indptr: vec![0, 1, 2],
+                    indices: vec![0, 0],
+                    data: vec![1.0, 1.0],
+                },
+                rhs: vec![2.0],
+            }),
+            equalities: None,
+            bounds: Some(Bounds {
+                lower: vec![0.0; 2],
+                upper: vec![2.0; 2],
+            }),
+            groups: Vec::new(),
+            integrality: Vec::new(),
+            names: Names::default(),
+            sense: ObjectiveSense::Minimize,
+            offset: 0.0,
+            quadratic_constraints: Vec::new(),
+        }
+    }
+
+    fn linear(linear: Vec<f64>, slack: f64) -> LexObjective<f64> {
+        LexObjective {
+            quadratic: None,
+            linear,
+            slack,
+        }
+    }
+
+    #[test]
+    fn later_stages_keep_the_earlier_optima() {
+        // Maximizing x0 + x1 leaves the whole edge x0 + x1 = 2; the second
+        // stage then picks x1 = 2 on it.
+        let stages = Solver::new()
+            .method(Method::Simplex)
+            .solve_lexicographic(
+                region(),
+                &[linear(vec![-1.0, -1.0], 0.0), linear(vec![1.0, 0.0], 0.0)],
+            )
+            .unwrap();
+        assert_eq!(stages.len(), 2);
+        assert!((stages[0].optimum + 2.0).abs() < 1e-9);
+        let last = &stages[1].solution;
+        assert_eq!(last.status, Status::Optimal);
+        assert!(last.primal[0].abs() < 1e-9 && (last.primal[1] - 2.0).abs() < 1e-9);
+        assert!(stages.iter().all(|stage| stage.slack.abs() < 1e-9));
+    }
+
+    #[test]
+    fn slack_lets_the_next_stage_give_ground() {
+        // Allowing x0 + x1 to drop to 1.5 lets the second stage reach x0 = 0
+        // and x1 = 1.5, using the slack in full.
+        let stages = Solver::new()
+            .method(Method::Simplex)
+            .solve_lexicographic(
+                region(),
+                &[linear(vec![-1.0, -1.0], 0.5), linear(vec![1.0, 1.0], 0.0)],
+            )
+            .unwrap();
+        assert!((stages[1].optimum - 1.5).abs() < 1e-9);
+        assert!(stages[0].slack.abs() < 1e-9);
+    }
+
+    #[test]
+    fn quadratic_objectives_become_quadratic_constraints() {
+        // ½‖x - (2, 2)‖² - 4 is smallest on the edge at (1, 1). Moving t
+        // along the edge costs t², so a slack of 0.01 lets the second stage
+        // reach x0 = 0.9.
+        let squared = LexObjective {
+            quadratic: Some(CscMatrix {
+                nrows: 2,
+                ncols: 2,
+                indptr: vec![0, 1, 2],
+                indices: vec![0, 1],
+                data: vec![1.0, 1.0],
+            }),
+            linear: vec![-2.0, -2.0],
+            slack: 0.01,
+        };
+        let stages = Solver::new()
+            .options(SolveOptions {
+                max_iterations: 20_000,
+                ..SolveOptions::with_tolerance(1e-7)
+            })
+            .solve_lexicographic(region(), &[squared, linear(vec![1.0, 0.0], 0.0)])
+            .unwrap();
+        assert!((stages[0].optimum + 3.0).abs() < 1e-6);
+        let x = &stages[1].solution.primal;
+        assert!(
+            (x[0] - 0.9).abs() < 1e-2 && (x[1] - 1.1).abs() < 1e-2,
+            "{x:?}"
+        );
+        assert!(stages[0].slack.abs() < 1e-3);
+    }
+
+    #[test]
+    fn objectives_are_checked() {
+        let mut solver = Solver::new();
+        assert!(solver
+            .solve_lexicographic(region(), &[linear(vec![1.0], 0.0)])
+            .is_err());
+        assert!(solver
+            .solve_lexicographic(region(), &[linear(vec![1.0, 0.0], -1.0)])
+            .is_err());
+    }
+}
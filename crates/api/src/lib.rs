@@ -1,20 +1,72 @@
 #![forbid(unsafe_code)]
 
 use anyhow::Result;
-use cvxrs_algos::AdmmSolver;
+use cvxrs_algos::{
+    admm, fista, ActiveSetSolver, AdmmSolver, FistaSolver, IpmSolver, PdhgSolver, SimplexSolver,
+};
 use cvxrs_core::math::RealNumber;
 use cvxrs_core::options::SolveOptions;
 use cvxrs_core::problem::{
-    Bounds, CscMatrix, EqualityConstraints, InequalityConstraints, ProblemLP, ProblemQP,
+    Bounds, ConstraintGroup, CscMatrix, EqualityConstraints, InequalityConstraints, ProblemLP,
+    ProblemQP, SplitRows,
 };
-use cvxrs_core::traits::Scaler;
 use serde::{Deserialize, Serialize};
+use std::ops::ControlFlow;
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 use thiserror::Error;
 
+mod auto;
+mod epigraph;
+mod feasibility;
+mod least_squares;
+mod lexicographic;
+mod parametric;
+mod pareto;
+mod remap;
+#[cfg(feature = "telemetry")]
+mod telemetry;
+
+pub use cvxrs_algos::bnb::{BranchAndBound, Incumbent, IncumbentSink};
+pub use cvxrs_algos::consensus::{ConsensusSolution, ConsensusSolver, Scenario};
+pub use cvxrs_algos::sensitivity::{DataGradient, Sensitivity};
+pub use cvxrs_algos::svm::{SvmModel, SvmSolver};
+pub use cvxrs_core::groups::{group_report_lp, group_report_qp, GroupReport};
 pub use cvxrs_core::options::Method;
-pub use cvxrs_core::solution::{Solution, Status};
-pub use cvxrs_core::stats::SolveStats;
-pub use cvxrs_core::{problem::WarmStart, scaling::RuizScaler};
+pub use cvxrs_core::options::Scaling;
+pub use cvxrs_core::presolve::{
+    PostsolveStack, PresolvePipeline, RemoveFixedVariables, RemoveRedundantRows, ScaleProblem,
+    TightenBounds,
+};
+pub use cvxrs_core::problem::{
+    CheckpointSink, Cone, LinearConstraints, Names, ObjectiveSense, ProblemConic,
+    QuadraticConstraint, RowSense, SolverState, WarmStart,
+};
+pub use cvxrs_core::robust::{robust_lp, robust_qp, RobustRow, Uncertainty};
+pub use cvxrs_core::scaling::{
+    BuiltinScaler, GeometricScaler, IdentityScaler, ModifiedRuizScaler, RuizScaler,
+};
+pub use cvxrs_core::solution::{Certificate, Solution, Status};
+pub use cvxrs_core::stats::{
+    ActiveSet, Basis, BasisStatus, BranchAndBoundStats, InterruptFlag, IterationRecord,
+    IterationSink, PresolveStats, SolveStats,
+};
+pub use cvxrs_core::structure::ConstraintRow;
+pub use cvxrs_core::traits::{
+    Presolver, ResidualTolerance, Scaler, StoppingCriterion, StoppingRule, Transformation,
+};
+#[cfg(feature = "verify")]
+pub use cvxrs_core::verify::{verify_lp, verify_qp, Verification};
+pub use epigraph::{ObjectiveTerm, VariableMap};
+pub use feasibility::Feasibility;
+pub use least_squares::{
+    elastic_net, lasso, least_squares, nonnegative_least_squares, LeastSquares,
+};
+pub use lexicographic::{LexObjective, LexStage};
+pub use parametric::ParametricQp;
+pub use pareto::ParetoPoint;
+pub use remap::{remap_warm_start, BlockRemap, ConstraintLayout, LayoutEdit, RemapReport};
 
 #[derive(Debug, Error)]
 pub enum SolverError {
@@ -22,6 +74,25 @@ pub enum SolverError {
     InvalidProblem(String),
     #[error("unsupported method: {0:?}")]
     Unsupported(Method),
+    #[error("quadratic matrix is not positive semidefinite (pivot {0})")]
+    NonConvex(usize),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemoryEstimate {
+    pub nvars: usize,
+    pub nrows: usize,
+    pub bytes: u64,
+}
+
+impl MemoryEstimate {
+    pub fn megabytes(&self) -> f64 {
+        self.bytes as f64 / (1024.0 * 1024.0)
+    }
+
+    pub fn exceeds(&self, limit_bytes: u64) -> bool {
+        self.bytes > limit_bytes
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -30,7 +101,17 @@ pub struct QpBuilder<T: RealNumber> {
     q: Option<Vec<T>>,
     equality: Option<EqualityConstraints<T>>,
     inequality: Option<InequalityConstraints<T>>,
+    #[serde(default)]
+    rows: Option<LinearConstraints<T>>,
     bounds: Option<Bounds<T>>,
+    groups: Vec<ConstraintGroup>,
+    #[serde(default)]
+    names: Names,
+    #[serde(default)]
+    sense: ObjectiveSense,
+    offset: T,
+    #[serde(default)]
+    terms: Vec<ObjectiveTerm<T>>,
 }
 
 impl<T> Default for QpBuilder<T>
@@ -43,7 +124,13 @@ where
             q: None,
             equality: None,
             inequality: None,
+            rows: None,
             bounds: None,
+            groups: Vec::new(),
+            names: Names::default(),
+            sense: ObjectiveSense::Minimize,
+            offset: T::zero(),
+            terms: Vec::new(),
         }
     }
 }
@@ -56,8 +143,9 @@ where
         Self::default()
     }
 
-    pub fn p(mut self, matrix: CscMatrix<T>) -> Self {
-        self.p = Some(matrix);
+    // Takes a CscMatrix or a sprs CsMat.
+    pub fn p(mut self, matrix: impl Into<CscMatrix<T>>) -> Self {
+        self.p = Some(matrix.into());
         self
     }
 
@@ -66,13 +154,26 @@ where
         self
     }
 
-    pub fn c(mut self, matrix: CscMatrix<T>, rhs: Vec<T>) -> Self {
-        self.equality = Some(EqualityConstraints { matrix, rhs });
+    pub fn c(mut self, matrix: impl Into<CscMatrix<T>>, rhs: Vec<T>) -> Self {
+        self.equality = Some(EqualityConstraints {
+            matrix: matrix.into(),
+            rhs,
+        });
         self
     }
 
-    pub fn a(mut self, matrix: CscMatrix<T>, rhs: Vec<T>) -> Self {
-        self.inequality = Some(InequalityConstraints { matrix, rhs });
+    pub fn a(mut self, matrix: impl Into<CscMatrix<T>>, rhs: Vec<T>) -> Self {
+        self.inequality = Some(InequalityConstraints {
+            matrix: matrix.into(),
+            rhs,
+        });
+        self
+    }
+
+    // All linear rows in one matrix with a sense per row, in place of the
+    // separate equality and inequality parts.
+    pub fn rows(mut self, rows: LinearConstraints<T>) -> Self {
+        self.rows = Some(rows);
         self
     }
 
@@ -81,24 +182,101 @@ where
         self
     }
 
+    pub fn group(mut self, name: impl Into<String>, rows: Vec<ConstraintRow>) -> Self {
+        self.groups.push(ConstraintGroup {
+            name: name.into(),
+            rows,
+        });
+        self
+    }
+
+    // Names for the variables and rows; any list may be left empty.
+    pub fn names(mut self, names: Names) -> Self {
+        self.names = names;
+        self
+    }
+
+    pub fn offset(mut self, constant: T) -> Self {
+        self.offset = constant;
+        self
+    }
+
+    pub fn sense(mut self, sense: ObjectiveSense) -> Self {
+        self.sense = sense;
+        self
+    }
+
+    pub fn abs(mut self, index: usize, weight: T) -> Self {
+        self.terms.push(ObjectiveTerm::Abs { index, weight });
+        self
+    }
+
+    pub fn huber(mut self, index: usize, weight: T, delta: T) -> Self {
+        self.terms.push(ObjectiveTerm::Huber {
+            index,
+            weight,
+            delta,
+        });
+        self
+    }
+
+    // With abs or Huber terms the problem gains epigraph variables after the
+    // declared ones; `build_mapped` also returns the way back.
     pub fn build(self) -> Result<ProblemQP<T>, SolverError> {
-        let quadratic = self
+        self.build_mapped().map(|(problem, _)| problem)
+    }
+
+    pub fn build_mapped(self) -> Result<(ProblemQP<T>, VariableMap), SolverError> {
+        let mut quadratic = self
             .p
             .ok_or_else(|| SolverError::InvalidProblem("quadratic matrix missing".into()))?;
-        let linear = self
+        let mut linear = self
             .q
             .ok_or_else(|| SolverError::InvalidProblem("linear term missing".into()))?;
+        // The epigraph terms are penalties in either sense, so they join the
+        // minimized form, which a maximization is turned back from.
+        let mut offset = self.offset;
+        if self.sense == ObjectiveSense::Maximize {
+            negate(&mut quadratic.data);
+            negate(&mut linear);
+            offset = -offset;
+        }
+        let (equality, mut inequality) = split_rows(self.rows, self.equality, self.inequality)?;
+        let mut bounds = self.bounds;
+        let map = epigraph::reformulate(
+            &self.terms,
+            Some(&mut quadratic),
+            &mut linear,
+            equality.as_ref().map_or(0, |eq| eq.matrix.nrows),
+            &mut inequality,
+            &mut bounds,
+        )?;
+        let mut names = self.names;
+        epigraph::extend_names(
+            &mut names,
+            linear.len(),
+            inequality.as_ref().map_or(0, |ineq| ineq.matrix.nrows),
+        );
         let mut problem = ProblemQP {
             quadratic,
             linear,
-            inequalities: self.inequality,
-            equalities: self.equality,
-            bounds: self.bounds,
+            inequalities: inequality,
+            equalities: equality,
+            bounds,
+            groups: self.groups,
+            integrality: Vec::new(),
+            names,
+            sense: ObjectiveSense::Minimize,
+            offset,
+            quadratic_constraints: Vec::new(),
         };
+        if self.sense == ObjectiveSense::Maximize {
+            problem.flip_sense();
+        }
         problem
             .validate()
             .map_err(|err| SolverError::InvalidProblem(err.to_string()))?;
-        Ok(problem)
+        Ok((problem, map))
     }
 }
 
@@ -107,7 +285,17 @@ pub struct LpBuilder<T: RealNumber> {
     cost: Option<Vec<T>>,
     equality: Option<EqualityConstraints<T>>,
     inequality: Option<InequalityConstraints<T>>,
+    #[serde(default)]
+    rows: Option<LinearConstraints<T>>,
     bounds: Option<Bounds<T>>,
+    groups: Vec<ConstraintGroup>,
+    #[serde(default)]
+    names: Names,
+    #[serde(default)]
+    sense: ObjectiveSense,
+    offset: T,
+    #[serde(default)]
+    terms: Vec<ObjectiveTerm<T>>,
 }
 
 impl<T> Default for LpBuilder<T>
@@ -119,7 +307,13 @@ where
             cost: None,
             equality: None,
             inequality: None,
+            rows: None,
             bounds: None,
+            groups: Vec::new(),
+            names: Names::default(),
+            sense: ObjectiveSense::Minimize,
+            offset: T::zero(),
+            terms: Vec::new(),
         }
     }
 }
@@ -137,13 +331,26 @@ where
         self
     }
 
-    pub fn c_eq(mut self, matrix: CscMatrix<T>, rhs: Vec<T>) -> Self {
-        self.equality = Some(EqualityConstraints { matrix, rhs });
+    pub fn c_eq(mut self, matrix: impl Into<CscMatrix<T>>, rhs: Vec<T>) -> Self {
+        self.equality = Some(EqualityConstraints {
+            matrix: matrix.into(),
+            rhs,
+        });
+        self
+    }
+
+    pub fn a(mut self, matrix: impl Into<CscMatrix<T>>, rhs: Vec<T>) -> Self {
+        self.inequality = Some(InequalityConstraints {
+            matrix: matrix.into(),
+            rhs,
+        });
         self
     }
 
-    pub fn a(mut self, matrix: CscMatrix<T>, rhs: Vec<T>) -> Self {
-        self.inequality = Some(InequalityConstraints { matrix, rhs });
+    // All linear rows in one matrix with a sense per row, in place of the
+    // separate equality and inequality parts.
+    pub fn rows(mut self, rows: LinearConstraints<T>) -> Self {
+        self.rows = Some(rows);
         self
     }
 
@@ -152,28 +359,105 @@ where
         self
     }
 
+    pub fn group(mut self, name: impl Into<String>, rows: Vec<ConstraintRow>) -> Self {
+        self.groups.push(ConstraintGroup {
+            name: name.into(),
+            rows,
+        });
+        self
+    }
+
+    // Names for the variables and rows; any list may be left empty.
+    pub fn names(mut self, names: Names) -> Self {
+        self.names = names;
+        self
+    }
+
+    pub fn offset(mut self, constant: T) -> Self {
+        self.offset = constant;
+        self
+    }
+
+    pub fn sense(mut self, sense: ObjectiveSense) -> Self {
+        self.sense = sense;
+        self
+    }
+
+    pub fn abs(mut self, index: usize, weight: T) -> Self {
+        self.terms.push(ObjectiveTerm::Abs { index, weight });
+        self
+    }
+
     pub fn build(self) -> Result<ProblemLP<T>, SolverError> {
-        let cost = self
+        self.build_mapped().map(|(problem, _)| problem)
+    }
+
+    pub fn build_mapped(self) -> Result<(ProblemLP<T>, VariableMap), SolverError> {
+        let mut cost = self
             .cost
             .ok_or_else(|| SolverError::InvalidProblem("objective vector missing".into()))?;
+        let mut offset = self.offset;
+        if self.sense == ObjectiveSense::Maximize {
+            negate(&mut cost);
+            offset = -offset;
+        }
+        let (equality, mut inequality) = split_rows(self.rows, self.equality, self.inequality)?;
+        let mut bounds = self.bounds;
+        let map = epigraph::reformulate(
+            &self.terms,
+            None,
+            &mut cost,
+            equality.as_ref().map_or(0, |eq| eq.matrix.nrows),
+            &mut inequality,
+            &mut bounds,
+        )?;
+        let mut names = self.names;
+        epigraph::extend_names(
+            &mut names,
+            cost.len(),
+            inequality.as_ref().map_or(0, |ineq| ineq.matrix.nrows),
+        );
         let mut problem = ProblemLP {
             cost,
-            inequalities: self.inequality,
-            equalities: self.equality,
-            bounds: self.bounds,
+            inequalities: inequality,
+            equalities: equality,
+            bounds,
+            groups: self.groups,
+            integrality: Vec::new(),
+            names,
+            sense: ObjectiveSense::Minimize,
+            offset,
         };
+        if self.sense == ObjectiveSense::Maximize {
+            problem.flip_sense();
+        }
         problem
             .validate()
             .map_err(|err| SolverError::InvalidProblem(err.to_string()))?;
-        Ok(problem)
+        Ok((problem, map))
     }
 }
 
+// A scaler with no factors yet, made afresh for every solve.
+pub type BoxedScaler<T> = Box<dyn Scaler<T> + Send + Sync>;
+
 pub struct Solver<T: RealNumber> {
     method: Method,
     options: SolveOptions<T>,
-    scaler: RuizScaler<T>,
     warm_start: Option<WarmStart<T>>,
+    resume: Option<SolverState<T>>,
+    checkpoint: Option<(Duration, CheckpointSink<T>)>,
+    iteration_log: Option<IterationSink<T>>,
+    iteration_hook: Option<IterationSink<T>>,
+    // Raised by the hook; every solve starts with it lowered.
+    interrupt: InterruptFlag,
+    stopping: Option<StoppingRule<T>>,
+    // Drives problems with integer variables; each node goes to `method`.
+    branch_and_bound: BranchAndBound<T>,
+    // Runs before the solve when the options ask for presolve.
+    presolve: PresolvePipeline<T>,
+    // Takes the place of the one SolveOptions::scaling names.
+    scaler: Option<Arc<dyn Fn() -> BoxedScaler<T> + Send + Sync>>,
 }
 
 impl<T> Solver<T>
@@ -184,8 +468,16 @@ where
         Self {
             method: Method::Admm,
             options: SolveOptions::default(),
-            scaler: RuizScaler::default(),
             warm_start: None,
+            resume: None,
+            checkpoint: None,
+            iteration_log: None,
+            iteration_hook: None,
+            interrupt: InterruptFlag::default(),
+            stopping: None,
+            branch_and_bound: BranchAndBound::new(),
+            presolve: PresolvePipeline::default(),
+            scaler: None,
         }
     }
 
@@ -204,34 +496,492 @@ where
         self
     }
 
-    pub fn solve_qp(&mut self, problem: ProblemQP<T>) -> Result<Solution<T>, SolverError> {
+    pub fn branch_and_bound(mut self, search: BranchAndBound<T>) -> Self {
+        self.branch_and_bound = search;
+        self
+    }
+
+    // Replaces the default presolve steps; they still only run when
+    // SolveOptions::presolve is set.
+    pub fn presolvers(mut self, pipeline: PresolvePipeline<T>) -> Self {
+        self.presolve = pipeline;
+        self
+    }
+
+    // Equilibrates every ADMM solve with a copy of `scaler` in place of the
+    // one SolveOptions::scaling names; IdentityScaler turns scaling off.
+    pub fn scaler<S>(mut self, scaler: S) -> Self
+    where
+        S: Scaler<T> + Clone + Send + Sync + 'static,
+    {
+        self.scaler = Some(Arc::new(move || Box::new(scaler.clone()) as BoxedScaler<T>));
+        self
+    }
+
+    pub fn resume(mut self, state: SolverState<T>) -> Self {
+        self.resume = Some(state);
+        self
+    }
+
+    pub fn checkpoint(mut self, every: Duration, sink: CheckpointSink<T>) -> Self {
+        self.checkpoint = Some((every, sink));
+        self
+    }
+
+    pub fn log_iterations(mut self, sink: IterationSink<T>) -> Self {
+        self.iteration_log = Some(sink);
+        self
+    }
+
+    // Called with every record, after the iteration log; returning Break
+    // stops the solve after the current iteration with Status::Interrupted.
+    pub fn on_iteration<F>(mut self, hook: F) -> Self
+    where
+        F: FnMut(&IterationRecord<T>) -> ControlFlow<()> + Send + 'static,
+    {
+        let hook = Mutex::new(hook);
+        let interrupt = self.interrupt.clone();
+        self.iteration_hook = Some(Arc::new(move |record| {
+            let mut hook = hook.lock().unwrap_or_else(|err| err.into_inner());
+            if hook(record).is_break() {
+                interrupt.store(true, Ordering::Relaxed);
+            }
+        }));
+        self
+    }
+
+    // Only ADMM consults the rule.
+    pub fn stopping_criterion(mut self, rule: StoppingRule<T>) -> Self {
+        self.stopping = Some(rule);
+        self
+    }
+
+    pub fn estimate_qp(&self, problem: &ProblemQP<T>) -> Result<MemoryEstimate, SolverError> {
+        self.estimate(
+            problem.nvars(),
+            stacked_rows(
+                problem.equalities.as_ref(),
+                problem.inequalities.as_ref(),
+                problem.bounds.as_ref(),
+            ),
+        )
+    }
+
+    pub fn estimate_lp(&self, problem: &ProblemLP<T>) -> Result<MemoryEstimate, SolverError> {
+        self.estimate(
+            problem.nvars(),
+            stacked_rows(
+                problem.equalities.as_ref(),
+                problem.inequalities.as_ref(),
+                problem.bounds.as_ref(),
+            ),
+        )
+    }
+
+    fn estimate(&self, nvars: usize, nrows: usize) -> Result<MemoryEstimate, SolverError> {
         match self.method {
-            Method::Admm => {
-                let options = self.options.clone();
-                let mut admm = AdmmSolver::new(options);
-                if let Some(warm) = self.warm_start.clone() {
-                    admm = admm.with_warm_start(warm);
+            Method::Admm => Ok(MemoryEstimate {
+                nvars,
+                nrows,
+                bytes: admm::workspace_bytes::<T>(nvars, nrows),
+            }),
+            method => Err(SolverError::Unsupported(method)),
+        }
+    }
+
+    pub fn solve_qp(&mut self, problem: ProblemQP<T>) -> Result<Solution<T>, SolverError> {
+        #[cfg(feature = "telemetry")]
+        let _span = tracing::info_span!(
+            "cvxrs.solve",
+            kind = "qp",
+            method = ?self.method,
+            nvars = problem.nvars()
+        )
+        .entered();
+        self.interrupt.store(false, Ordering::Relaxed);
+        let sense = problem.sense;
+        let problem = problem.into_minimization();
+        let result = check_finite_qp(&problem)
+            .and_then(|()| check_convexity(&problem))
+            .and_then(|()| {
+                if problem.integrality.contains(&true) {
+                    let search = self.branch_and_bound.clone();
+                    search
+                        .solve_qp(&problem, |node| Ok(self.relax_qp(node.clone())?))
+                        .map_err(solver_error)
+                } else {
+                    self.relax_qp(problem)
                 }
-                admm.solve_qp(problem, &mut self.scaler)
+            })
+            .map(|solution| solution.in_sense(sense));
+        #[cfg(feature = "telemetry")]
+        telemetry::record(self.method, "qp", &result);
+        result
+    }
+
+    // Solves QPs that share P and the constraint matrices, e.g. a parametric
+    // family swept over q, the right-hand sides or the bounds. ADMM scales
+    // with the configured scaler and factors once for the whole batch; other
+    // methods, box QPs, integer problems and presolved solves handle the
+    // members one by one, since presolve may reduce each one differently.
+    pub fn solve_qp_batch(
+        &mut self,
+        problems: Vec<ProblemQP<T>>,
+    ) -> Result<Vec<Solution<T>>, SolverError> {
+        let shared = self.method == Method::Admm
+            && self.resume.is_none()
+            && !self.presolves()
+            && problems
+                .first()
+                .is_some_and(|first| !fista::is_box_qp(first))
+            && problems.iter().all(|problem| {
+                !problem.integrality.contains(&true) && problem.quadratic_constraints.is_empty()
+            });
+        if !shared {
+            return problems
+                .into_iter()
+                .map(|problem| self.solve_qp(problem))
+                .collect();
+        }
+        #[cfg(feature = "telemetry")]
+        let _span = tracing::info_span!(
+            "cvxrs.solve_batch",
+            kind = "qp",
+            method = ?self.method,
+            problems = problems.len()
+        )
+        .entered();
+        self.interrupt.store(false, Ordering::Relaxed);
+        let senses: Vec<_> = problems.iter().map(|problem| problem.sense).collect();
+        let problems: Vec<_> = problems
+            .into_iter()
+            .map(ProblemQP::into_minimization)
+            .collect();
+        for problem in &problems {
+            check_finite_qp(problem)?;
+        }
+        check_convexity(&problems[0])?;
+        let solutions = self
+            .admm()
+            .solve_qp_batch(problems, &mut self.fresh_scaler())
+            .map_err(|err| SolverError::InvalidProblem(err.to_string()))?;
+        Ok(solutions
+            .into_iter()
+            .zip(senses)
+            .map(|(solution, sense)| solution.in_sense(sense))
+            .collect())
+    }
+
+    fn relax_qp(&mut self, problem: ProblemQP<T>) -> Result<Solution<T>, SolverError> {
+        if !self.presolves() {
+            return self.pick_qp(problem);
+        }
+        let (reduced, stack) = self.presolve.qp(&problem);
+        if let Some(solution) = stack.infeasible() {
+            return Ok(solution);
+        }
+        self.pick_qp(reduced)
+            .map(|solution| stack.postsolve(solution))
+    }
+
+    fn pick_qp(&mut self, problem: ProblemQP<T>) -> Result<Solution<T>, SolverError> {
+        match self.method {
+            Method::Auto => {
+                let method = self.auto_pick(auto::qp_method(&problem));
+                self.with_fallback(method, problem, Self::run_qp)
+            }
+            method => self.run_qp(method, problem),
+        }
+    }
+
+    fn run_qp(
+        &mut self,
+        method: Method,
+        problem: ProblemQP<T>,
+    ) -> Result<Solution<T>, SolverError> {
+        match method {
+            // Quadratic constraints become second-order cones, which only
+            // ADMM takes; the duals follow the rows of the conic form.
+            Method::Admm if !problem.quadratic_constraints.is_empty() => {
+                let conic = ProblemConic::from_qp(&problem)
+                    .map_err(|err| SolverError::InvalidProblem(err.to_string()))?;
+                let mut solution = self
+                    .admm()
+                    .solve_conic(conic)
+                    .map_err(|err| SolverError::InvalidProblem(err.to_string()))?;
+                solution.objective_value += problem.offset;
+                Ok(solution)
+            }
+            method if !problem.quadratic_constraints.is_empty() => {
+                Err(SolverError::Unsupported(method))
+            }
+            // Box-constrained QPs skip the KKT factorization altogether,
+            // unless an ADMM iterate has to be resumed or checkpointed or
+            // ADMM's records have to be judged by a custom rule.
+            Method::Admm
+                if fista::is_box_qp(&problem)
+                    && self.resume.is_none()
+                    && self.checkpoint.is_none()
+                    && self.stopping.is_none() =>
+            {
+                self.fista()
+                    .solve_qp(&problem, &self.options)
                     .map_err(|err| SolverError::InvalidProblem(err.to_string()))
             }
+            Method::Admm => self
+                .admm()
+                .solve_qp(problem, &mut self.fresh_scaler())
+                .map_err(|err| SolverError::InvalidProblem(err.to_string())),
             Method::Ipm => Err(SolverError::Unsupported(Method::Ipm)),
+            Method::ActiveSet => self
+                .active_set()
+                .solve_qp(&problem, &self.options)
+                .map_err(|err| SolverError::InvalidProblem(err.to_string())),
+            method @ (Method::Simplex | Method::Pdhg | Method::Auto) => {
+                Err(SolverError::Unsupported(method))
+            }
         }
     }
 
     pub fn solve_lp(&mut self, problem: ProblemLP<T>) -> Result<Solution<T>, SolverError> {
-        match self.method {
-            Method::Admm => {
-                let options = self.options.clone();
-                let mut admm = AdmmSolver::new(options);
-                if let Some(warm) = self.warm_start.clone() {
-                    admm = admm.with_warm_start(warm);
+        #[cfg(feature = "telemetry")]
+        let _span = tracing::info_span!(
+            "cvxrs.solve",
+            kind = "lp",
+            method = ?self.method,
+            nvars = problem.nvars()
+        )
+        .entered();
+        self.interrupt.store(false, Ordering::Relaxed);
+        let sense = problem.sense;
+        let problem = problem.into_minimization();
+        let result = check_finite_lp(&problem)
+            .and_then(|()| {
+                if problem.integrality.contains(&true) {
+                    let search = self.branch_and_bound.clone();
+                    search
+                        .solve_lp(&problem, |node| Ok(self.relax_lp(node.clone())?))
+                        .map_err(solver_error)
+                } else {
+                    self.relax_lp(problem)
                 }
-                admm.solve_lp(problem, &mut self.scaler)
-                    .map_err(|err| SolverError::InvalidProblem(err.to_string()))
+            })
+            .map(|solution| solution.in_sense(sense));
+        #[cfg(feature = "telemetry")]
+        telemetry::record(self.method, "lp", &result);
+        result
+    }
+
+    fn relax_lp(&mut self, problem: ProblemLP<T>) -> Result<Solution<T>, SolverError> {
+        if !self.presolves() {
+            return self.pick_lp(problem);
+        }
+        let (reduced, stack) = self.presolve.lp(&problem);
+        if let Some(solution) = stack.infeasible() {
+            return Ok(solution);
+        }
+        self.pick_lp(reduced)
+            .map(|solution| stack.postsolve(solution))
+    }
+
+    fn pick_lp(&mut self, problem: ProblemLP<T>) -> Result<Solution<T>, SolverError> {
+        match self.method {
+            Method::Auto => {
+                let method = self.auto_pick(auto::lp_method(&problem));
+                self.with_fallback(method, problem, Self::run_lp)
             }
-            Method::Ipm => Err(SolverError::Unsupported(Method::Ipm)),
+            method => self.run_lp(method, problem),
+        }
+    }
+
+    fn run_lp(
+        &mut self,
+        method: Method,
+        problem: ProblemLP<T>,
+    ) -> Result<Solution<T>, SolverError> {
+        match method {
+            Method::Admm => self
+                .admm()
+                .solve_lp(problem, &mut self.fresh_scaler())
+                .map_err(|err| SolverError::InvalidProblem(err.to_string())),
+            Method::Ipm => self
+                .ipm()
+                .solve_lp(&problem, &self.options)
+                .map_err(|err| SolverError::InvalidProblem(err.to_string())),
+            Method::ActiveSet => self
+                .active_set()
+                .solve_lp(&problem, &self.options)
+                .map_err(|err| SolverError::InvalidProblem(err.to_string())),
+            Method::Simplex => self
+                .simplex()
+                .solve_lp(&problem, &self.options)
+                .map_err(|err| SolverError::InvalidProblem(err.to_string())),
+            Method::Pdhg => self
+                .pdhg()
+                .solve_lp(&problem, &self.options)
+                .map_err(|err| SolverError::InvalidProblem(err.to_string())),
+            Method::Auto => Err(SolverError::Unsupported(Method::Auto)),
+        }
+    }
+
+    // Warm starts, resumed iterates and checkpoints live in the space of the
+    // original problem, so they turn presolve off.
+    fn presolves(&self) -> bool {
+        self.options.presolve
+            && self.warm_start.is_none()
+            && self.resume.is_none()
+            && self.checkpoint.is_none()
+    }
+
+    // Resuming, checkpointing and custom stopping rules are ADMM's alone, so
+    // they override the automatic pick.
+    fn auto_pick(&self, method: Method) -> Method {
+        if self.resume.is_some() || self.checkpoint.is_some() || self.stopping.is_some() {
+            Method::Admm
+        } else {
+            method
+        }
+    }
+
+    // Runs the automatic pick and, when it breaks down, ADMM on the
+    // same problem, recording which of them answered.
+    fn with_fallback<P: Clone>(
+        &mut self,
+        method: Method,
+        problem: P,
+        run: fn(&mut Self, Method, P) -> Result<Solution<T>, SolverError>,
+    ) -> Result<Solution<T>, SolverError> {
+        let (method, result) = if method == Method::Admm {
+            (method, run(self, method, problem))
+        } else {
+            let result = run(self, method, problem.clone());
+            if auto::failed(method, &result) {
+                (Method::Admm, run(self, Method::Admm, problem))
+            } else {
+                (method, result)
+            }
+        };
+        result.map(|mut solution| {
+            solution.stats.backend = Some(method);
+            solution
+        })
+    }
+
+    pub fn solve_conic(&mut self, problem: ProblemConic<T>) -> Result<Solution<T>, SolverError> {
+        #[cfg(feature = "telemetry")]
+        let _span = tracing::info_span!(
+            "cvxrs.solve",
+            kind = "conic",
+            method = ?self.method,
+            nvars = problem.nvars()
+        )
+        .entered();
+        self.interrupt.store(false, Ordering::Relaxed);
+        let result = match self.method {
+            Method::Admm => self
+                .admm()
+                .solve_conic(problem)
+                .map_err(|err| SolverError::InvalidProblem(err.to_string())),
+            // Only ADMM takes cones.
+            Method::Auto => self
+                .admm()
+                .solve_conic(problem)
+                .map(|mut solution| {
+                    solution.stats.backend = Some(Method::Admm);
+                    solution
+                })
+                .map_err(|err| SolverError::InvalidProblem(err.to_string())),
+            method => Err(SolverError::Unsupported(method)),
+        };
+        #[cfg(feature = "telemetry")]
+        telemetry::record(self.method, "conic", &result);
+        result
+    }
+
+    // The iteration log and the hook, as the single sink the solvers take.
+    fn sink(&self) -> Option<IterationSink<T>> {
+        match (&self.iteration_log, &self.iteration_hook) {
+            (Some(log), Some(hook)) => {
+                let (log, hook) = (log.clone(), hook.clone());
+                Some(Arc::new(move |record| {
+                    log(record);
+                    hook(record);
+                }))
+            }
+            (log, hook) => log.clone().or_else(|| hook.clone()),
+        }
+    }
+
+    // The scaler set on the solver, or else one of the kind the options
+    // name, with no factors yet.
+    fn fresh_scaler(&self) -> BoxedScaler<T> {
+        match &self.scaler {
+            Some(make) => make(),
+            None => Box::new(BuiltinScaler::new(self.options.scaling)),
+        }
+    }
+
+    fn admm(&self) -> AdmmSolver<T> {
+        let mut admm = AdmmSolver::new(self.options.clone()).with_interrupt(self.interrupt.clone());
+        if let Some(warm) = self.warm_start.clone() {
+            admm = admm.with_warm_start(warm);
+        }
+        if let Some(state) = self.resume.clone() {
+            admm = admm.with_resume(state);
+        }
+        if let Some((every, sink)) = &self.checkpoint {
+            admm = admm.with_checkpoint(*every, sink.clone());
+        }
+        if let Some(sink) = self.sink() {
+            admm = admm.with_iteration_log(sink);
+        }
+        if let Some(rule) = &self.stopping {
+            admm = admm.with_stopping_criterion(rule.clone());
+        }
+        admm
+    }
+
+    fn fista(&self) -> FistaSolver<T> {
+        let mut solver = FistaSolver::new().with_interrupt(self.interrupt.clone());
+        if let Some(warm) = self.warm_start.clone() {
+            solver = solver.with_warm_start(warm);
+        }
+        if let Some(sink) = self.sink() {
+            solver = solver.with_iteration_log(sink);
+        }
+        solver
+    }
+
+    fn ipm(&self) -> IpmSolver<T> {
+        let mut ipm = IpmSolver::new().with_interrupt(self.interrupt.clone());
+        if let Some(sink) = self.sink() {
+            ipm = ipm.with_iteration_log(sink);
+        }
+        ipm
+    }
+
+    fn active_set(&self) -> ActiveSetSolver<T> {
+        let mut solver = ActiveSetSolver::new().with_interrupt(self.interrupt.clone());
+        if let Some(sink) = self.sink() {
+            solver = solver.with_iteration_log(sink);
+        }
+        solver
+    }
+
+    fn simplex(&self) -> SimplexSolver<T> {
+        let mut solver = SimplexSolver::new().with_interrupt(self.interrupt.clone());
+        if let Some(sink) = self.sink() {
+            solver = solver.with_iteration_log(sink);
         }
+        solver
+    }
+
+    fn pdhg(&self) -> PdhgSolver<T> {
+        let mut solver = PdhgSolver::new().with_interrupt(self.interrupt.clone());
+        if let Some(sink) = self.sink() {
+            solver = solver.with_iteration_log(sink);
+        }
+        solver
     }
 }
 
@@ -244,6 +994,132 @@ where
     }
 }
 
+// Errors of the relaxations come back through branch-and-bound unchanged.
+fn solver_error(err: anyhow::Error) -> SolverError {
+    match err.downcast::<SolverError>() {
+        Ok(err) => err,
+        Err(err) => SolverError::InvalidProblem(err.to_string()),
+    }
+}
+
+fn stacked_rows<T: RealNumber>(
+    equalities: Option<&EqualityConstraints<T>>,
+    inequalities: Option<&InequalityConstraints<T>>,
+    bounds: Option<&Bounds<T>>,
+) -> usize {
+    equalities.map_or(0, |eq| eq.matrix.nrows)
+        + inequalities.map_or(0, |ineq| ineq.matrix.nrows)
+        + bounds.map_or(0, |bounds| bounds.lower.len())
+}
+
+fn negate<T: RealNumber>(values: &mut [T]) {
+    for value in values {
+        *value = -*value;
+    }
+}
+
+// A builder's `rows` block split into the parts the problems hold; given
+// alongside `c` or `a` it is ambiguous which rows were meant.
+fn split_rows<T: RealNumber>(
+    rows: Option<LinearConstraints<T>>,
+    equality: Option<EqualityConstraints<T>>,
+    inequality: Option<InequalityConstraints<T>>,
+) -> Result<SplitRows<T>, SolverError> {
+    let Some(rows) = rows else {
+        return Ok((equality, inequality));
+    };
+    if equality.is_some() || inequality.is_some() {
+        return Err(SolverError::InvalidProblem(
+            "rows replace the equality and inequality parts; give one or the other".into(),
+        ));
+    }
+    rows.split()
+        .map_err(|err| SolverError::InvalidProblem(err.to_string()))
+}
+
+// Every method assumes a convex objective; an indefinite P would otherwise
+// surface as a bad pivot deep inside a factorization, or as no error at all.
+fn check_convexity<T: RealNumber>(problem: &ProblemQP<T>) -> Result<(), SolverError> {
+    problem
+        .validate()
+        .map_err(|err| SolverError::InvalidProblem(err.to_string()))?;
+    match problem.nonconvex_pivot() {
+        Some(pivot) => Err(SolverError::NonConvex(pivot)),
+        None => Ok(()),
+    }
+}
+
+// NaN or infinite data would poison every iterate, so it is rejected before
+// any method runs. Right-hand sides of inequalities and bounds may be
+// infinite, which leaves the row or variable free, but not NaN.
+fn check_finite_qp<T: RealNumber>(problem: &ProblemQP<T>) -> Result<(), SolverError> {
+    require_finite("quadratic matrix", &problem.quadratic.data, false)?;
+    require_finite("linear term", &problem.linear, false)?;
+    for constraint in &problem.quadratic_constraints {
+        require_finite(
+            "quadratic constraint matrix",
+            &constraint.matrix.data,
+            false,
+        )?;
+        require_finite(
+            "quadratic constraint linear term",
+            &constraint.linear,
+            false,
+        )?;
+        require_finite("quadratic constraint rhs", &[constraint.rhs], true)?;
+    }
+    check_finite_rows(
+        problem.equalities.as_ref(),
+        problem.inequalities.as_ref(),
+        problem.bounds.as_ref(),
+    )
+}
+
+fn check_finite_lp<T: RealNumber>(problem: &ProblemLP<T>) -> Result<(), SolverError> {
+    require_finite("cost", &problem.cost, false)?;
+    check_finite_rows(
+        problem.equalities.as_ref(),
+        problem.inequalities.as_ref(),
+        problem.bounds.as_ref(),
+    )
+}
+
+fn check_finite_rows<T: RealNumber>(
+    equalities: Option<&EqualityConstraints<T>>,
+    inequalities: Option<&InequalityConstraints<T>>,
+    bounds: Option<&Bounds<T>>,
+) -> Result<(), SolverError> {
+    if let Some(eq) = equalities {
+        require_finite("equality matrix", &eq.matrix.data, false)?;
+        require_finite("equality rhs", &eq.rhs, false)?;
+    }
+    if let Some(ineq) = inequalities {
+        require_finite("inequality matrix", &ineq.matrix.data, false)?;
+        require_finite("inequality rhs", &ineq.rhs, true)?;
+    }
+    if let Some(bounds) = bounds {
+        require_finite("lower bounds", &bounds.lower, true)?;
+        require_finite("upper bounds", &bounds.upper, true)?;
+    }
+    Ok(())
+}
+
+fn require_finite<T: RealNumber>(
+    what: &str,
+    values: &[T],
+    infinite_allowed: bool,
+) -> Result<(), SolverError> {
+    let bad = values
+        .iter()
+        .position(|value| value.is_nan() || (!infinite_allowed && value.is_infinite()));
+    match bad {
+        Some(index) => Err(SolverError::InvalidProblem(format!(
+            "{what} entry {index} is not a finite number"
+        ))),
+        None => Ok(()),
+    }
+}
+
 pub fn solve_qp<T: RealNumber>(
     problem: ProblemQP<T>,
     options: SolveOptions<T>,
@@ -257,3 +1133,701 @@ pub fn solve_lp<T: RealNumber>(
 ) -> Result<Solution<T>, SolverError> {
     Solver::new().options(options).solve_lp(problem)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cvxrs_core::problem::ProblemResult;
+
+    // min ½‖x‖² - x₀ - x₁  s.t.  x₀ + x₁ = 1.
+    fn split_qp() -> ProblemQP<f64> {
+        ProblemQP {
+            quadratic: CscMatrix {
+                nrows: 2,
+                ncols: 2,
+                indptr: vec![0, 1, 2],
+                indices: vec![0, 1],
+                data: vec![1.0, 1.0],
+            },
+            linear: vec![-1.0, -1.0],
+            equalities: Some(EqualityConstraints {
+                matrix: CscMatrix {
+                    nrows: 1,
+                    ncols: 2,
+                    indptr: vec![0, 1, 2],
+                    indices: vec![0, 0],
+                    data: vec![1.0, 1.0],
+                },
+                rhs: vec![1.0],
+            }),
+            inequalities: None,
+            bounds: None,
+            groups: Vec::new(),
+            integrality: Vec::new(),
+            names: Names::default(),
+            sense: ObjectiveSense::Minimize,
+            offset: 0.0,
+            quadratic_constraints: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn builders_take_sprs_matrices() {
+        let reference = split_qp();
+        let row = reference.equalities.as_ref().unwrap().matrix.clone();
+        let problem = QpBuilder::new()
+            .p(reference.quadratic.to_csmat().unwrap().to_csr())
+            .q(vec![-1.0, -1.0])
+            .c(row.to_csmat().unwrap(), vec![1.0])
+            .build()
+            .unwrap();
+        assert_eq!(problem.quadratic.indptr, reference.quadratic.indptr);
+        assert_eq!(problem.quadratic.data, reference.quadratic.data);
+        assert_eq!(problem.equalities.unwrap().matrix.indices, row.indices);
+    }
+
+    #[test]
+    fn rows_with_senses_solve_and_report_a_dual_per_row() {
+        // min ½‖x‖² - 2x₀ - 2x₁ over 0.5 <= x₀ + x₁ <= 1 and x₀ >= 0.75 is
+        // solved by x = (0.75, 0.25), where both rows hold.
+        let matrix =
+            CscMatrix::from_triplets(2, 2, &[(0, 0, 1.0), (0, 1, 1.0), (1, 0, 1.0)]).unwrap();
+        let rows =
+            LinearConstraints::new(matrix, vec![RowSense::Range(0.5, 1.0), RowSense::Ge(0.75)]);
+        let builder = QpBuilder::new()
+            .p(split_qp().quadratic)
+            .q(vec![-2.0, -2.0])
+            .rows(rows.clone());
+        assert!(matches!(
+            builder
+                .clone()
+                .c(split_qp().equalities.unwrap().matrix, vec![1.0])
+                .build(),
+            Err(SolverError::InvalidProblem(_))
+        ));
+        let problem = builder.build().unwrap();
+        assert!(problem.equalities.is_none());
+        assert_eq!(
+            problem.inequalities.as_ref().unwrap().rhs,
+            vec![1.0, -0.5, -0.75]
+        );
+
+        let solution = Solver::new()
+            .options(SolveOptions::with_tolerance(1e-8))
+            .solve_qp(problem)
+            .unwrap();
+        assert!((solution.primal[0] - 0.75).abs() < 1e-5);
+        assert!((solution.primal[1] - 0.25).abs() < 1e-5);
+        // Stationarity: x - 2 + d₀(1, 1) + d₁(1, 0) = 0, the lower side of
+        // the second row being active.
+        let duals = rows.row_duals(&solution.equality_dual, &solution.inequality_dual);
+        assert!((duals[0] - 1.75).abs() < 1e-4, "{duals:?}");
+        assert!((duals[1] + 0.5).abs() < 1e-4, "{duals:?}");
+    }
+
+    #[test]
+    fn maximizations_report_the_objective_they_were_given() {
+        // max 3x - ½|x| + 2y + 1 over x + y <= 4, x + 3y <= 6 and x, y >= 0
+        // is reached at the vertex (4, 0). The abs term stays a penalty; were
+        // it added to the maximized objective, the LP would be unbounded.
+        let rows = CscMatrix::from_dense(2, 2, &[1.0, 1.0, 1.0, 3.0]).unwrap();
+        let lp = LpBuilder::new()
+            .c(vec![3.0, 2.0])
+            .a(rows, vec![4.0, 6.0])
+            .bounds(Bounds {
+                lower: vec![0.0; 2],
+                upper: vec![f64::INFINITY; 2],
+            })
+            .abs(0, 0.5)
+            .offset(1.0)
+            .sense(ObjectiveSense::Maximize)
+            .build()
+            .unwrap();
+        assert_eq!(lp.sense, ObjectiveSense::Maximize);
+        assert_eq!(lp.cost, vec![3.0, 2.0, -0.5]);
+        let mut solver = Solver::new().options(SolveOptions::with_tolerance(1e-8));
+        let solution = solver.solve_lp(lp).unwrap();
+        assert_eq!(solution.status, Status::Optimal);
+        assert!((solution.primal[0] - 4.0).abs() < 1e-6);
+        assert!((solution.objective_value - 11.0).abs() < 1e-6);
+
+        // max -x² + 2x over x <= 0.5, a concave objective, stops at the bound.
+        let qp = QpBuilder::new()
+            .p(CscMatrix::from_dense(1, 1, &[-2.0]).unwrap())
+            .q(vec![2.0])
+            .bounds(Bounds {
+                lower: vec![f64::NEG_INFINITY],
+                upper: vec![0.5],
+            })
+            .sense(ObjectiveSense::Maximize)
+            .build()
+            .unwrap();
+        assert!(solver.setup(qp.clone()).is_err());
+        let solution = solver.solve_qp(qp).unwrap();
+        assert!((solution.primal[0] - 0.5).abs() < 1e-5);
+        assert!((solution.objective_value - 0.75).abs() < 1e-5);
+    }
+
+    #[test]
+    fn builder_names_cover_the_epigraph_rows() {
+        let row = split_qp().equalities.unwrap().matrix;
+        let problem = LpBuilder::new()
+            .c(vec![1.0, 0.0])
+            .a(row, vec![1.0])
+            .abs(1, 1.0)
+            .names(Names {
+                variables: vec!["buy".into(), "sell".into()],
+                equalities: Vec::new(),
+                inequalities: vec!["budget".into()],
+            })
+            .build()
+            .unwrap();
+        assert_eq!(problem.names.variables, vec!["buy", "sell", "epigraph[0]"]);
+        assert_eq!(
+            problem.names.inequalities,
+            vec!["budget", "epigraph[0]", "epigraph[1]"]
+        );
+        assert_eq!(
+            problem.names.row_index("budget"),
+            Some(ConstraintRow::Inequality(0))
+        );
+
+        let mismatched = LpBuilder::new().c(vec![1.0]).names(Names {
+            variables: vec!["buy".into(), "sell".into()],
+            ..Names::default()
+        });
+        assert!(mismatched.build().is_err());
+    }
+
+    #[test]
+    fn offset_shifts_the_objective_of_every_method() {
+        let row = split_qp().equalities.unwrap().matrix;
+        let problem = QpBuilder::new()
+            .p(split_qp().quadratic)
+            .q(vec![-1.0, -1.0])
+            .c(row.clone(), vec![1.0])
+            .offset(2.0)
+            .build()
+            .unwrap();
+        for method in [Method::Admm, Method::ActiveSet] {
+            let solution = Solver::new()
+                .method(method)
+                .solve_qp(problem.clone())
+                .unwrap();
+            // ½(0.25 + 0.25) - 1 + 2 at x = (0.5, 0.5).
+            assert!((solution.objective_value - 1.25).abs() < 1e-4);
+        }
+        let lp = LpBuilder::new()
+            .c(vec![1.0, 2.0])
+            .c_eq(row, vec![1.0])
+            .bounds(Bounds {
+                lower: vec![0.0, 0.0],
+                upper: vec![f64::INFINITY, f64::INFINITY],
+            })
+            .offset(-4.0)
+            .build()
+            .unwrap();
+        for method in [Method::Simplex, Method::Ipm, Method::Pdhg] {
+            let solution = Solver::new().method(method).solve_lp(lp.clone()).unwrap();
+            assert!((solution.objective_value + 3.0).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn presolve_matches_the_full_solve() {
+        // The split QP with a third variable fixed at 0.5 that enters the
+        // equality and is coupled to x₀ through P.
+        let problem = QpBuilder::new()
+            .p(CscMatrix::from_triplets(
+                3,
+                3,
+                &[
+                    (0, 0, 1.0),
+                    (1, 1, 1.0),
+                    (2, 2, 2.0),
+                    (0, 2, 0.5),
+                    (2, 0, 0.5),
+                ],
+            )
+            .unwrap())
+            .q(vec![-1.0, -1.0, 3.0])
+            .c(
+                CscMatrix::from_dense(1, 3, &[1.0, 1.0, 1.0]).unwrap(),
+                vec![1.5],
+            )
+            .bounds(Bounds {
+                lower: vec![-10.0, -10.0, 0.5],
+                upper: vec![10.0, 10.0, 0.5],
+            })
+            .build()
+            .unwrap();
+        let solve = |method, presolve| {
+            let options = SolveOptions {
+                presolve,
+                ..SolveOptions::<f64>::with_tolerance(1e-9)
+            };
+            Solver::new()
+                .method(method)
+                .options(options)
+                .solve_qp(problem.clone())
+                .unwrap()
+        };
+        for method in [Method::Admm, Method::ActiveSet] {
+            let (full, reduced) = (solve(method, false), solve(method, true));
+            assert_eq!(reduced.status, Status::Optimal);
+            assert_eq!(reduced.primal.len(), 3);
+            assert_eq!(reduced.primal[2], 0.5);
+            for (a, b) in full.primal.iter().zip(&reduced.primal) {
+                assert!((a - b).abs() < 1e-5, "{method:?}: {a} vs {b}");
+            }
+            assert!((full.objective_value - reduced.objective_value).abs() < 1e-5);
+            assert_eq!(full.inequality_dual.len(), reduced.inequality_dual.len());
+            assert_eq!(full.equality_dual.len(), reduced.equality_dual.len());
+            // The bound multiplier of x₂ is whatever keeps it stationary:
+            // -(½x₀ + 2x₂ + 3 + y) = -4.5625 at y = 0.375.
+            let mut duals = reduced.equality_dual.clone();
+            duals.extend(&reduced.inequality_dual);
+            assert!((duals[0] - 0.375).abs() < 1e-5, "{method:?}: {duals:?}");
+            if duals.len() == 4 {
+                assert!((duals[3] + 4.5625).abs() < 1e-5, "{method:?}: {duals:?}");
+            }
+        }
+
+        let lp = LpBuilder::new()
+            .c(vec![1.0, 2.0, -1.0])
+            .c_eq(
+                CscMatrix::from_dense(1, 3, &[1.0, 1.0, 1.0]).unwrap(),
+                vec![2.0],
+            )
+            .bounds(Bounds {
+                lower: vec![0.0, 0.0, 0.5],
+                upper: vec![f64::INFINITY, f64::INFINITY, 0.5],
+            })
+            .build()
+            .unwrap();
+        let solve = |presolve| {
+            Solver::new()
+                .method(Method::Simplex)
+                .options(SolveOptions {
+                    presolve,
+                    ..SolveOptions::default()
+                })
+                .solve_lp(lp.clone())
+                .unwrap()
+        };
+        let (full, reduced) = (solve(false), solve(true));
+        assert_eq!(reduced.primal, full.primal);
+        assert!((reduced.objective_value - full.objective_value).abs() < 1e-12);
+        for (a, b) in full.inequality_dual.iter().zip(&reduced.inequality_dual) {
+            assert!((a - b).abs() < 1e-12, "{a} vs {b}");
+        }
+    }
+
+    #[test]
+    fn presolve_drops_duplicate_and_redundant_rows() {
+        // x₀ + x₁ ≤ 1 is the only row that matters: the second is a looser
+        // copy and the third cannot bind within the bounds.
+        let lp = LpBuilder::new()
+            .c(vec![-1.0, -2.0])
+            .a(
+                CscMatrix::from_dense(3, 2, &[1.0, 1.0, 2.0, 2.0, 1.0, 0.0]).unwrap(),
+                vec![1.0, 3.0, 5.0],
+            )
+            .bounds(Bounds {
+                lower: vec![0.0, 0.0],
+                upper: vec![2.0, 2.0],
+            })
+            .build()
+            .unwrap();
+        for method in [Method::Simplex, Method::Ipm] {
+            let solve = |presolve| {
+                Solver::new()
+                    .method(method)
+                    .options(SolveOptions {
+                        presolve,
+                        ..SolveOptions::<f64>::with_tolerance(1e-9)
+                    })
+                    .solve_lp(lp.clone())
+                    .unwrap()
+            };
+            let (full, reduced) = (solve(false), solve(true));
+            assert_eq!(reduced.status, Status::Optimal);
+            assert!(full.stats.presolve.is_none());
+            let stats = reduced.stats.presolve.unwrap();
+            assert_eq!((stats.duplicate_rows, stats.redundant_rows), (1, 1));
+            assert!((full.objective_value - reduced.objective_value).abs() < 1e-6);
+            assert_eq!(full.inequality_dual.len(), reduced.inequality_dual.len());
+            for (a, b) in full.inequality_dual.iter().zip(&reduced.inequality_dual) {
+                assert!((a - b).abs() < 1e-5, "{method:?}: {a} vs {b}");
+            }
+        }
+    }
+
+    #[test]
+    fn presolve_tightens_bounds_and_catches_infeasibility() {
+        // x₀ + x₁ ≤ 1 caps both variables at 1, where x₁ ends up.
+        let lp = |rhs: f64| {
+            LpBuilder::new()
+                .c(vec![-1.0, -2.0])
+                .a(
+                    CscMatrix::from_dense(2, 2, &[1.0, 1.0, -1.0, -1.0]).unwrap(),
+                    vec![1.0, rhs],
+                )
+                .bounds(Bounds {
+                    lower: vec![0.0, 0.0],
+                    upper: vec![2.0, 2.0],
+                })
+                .build()
+                .unwrap()
+        };
+        let solve = |problem: ProblemLP<f64>, presolve| {
+            Solver::new()
+                .method(Method::Simplex)
+                .options(SolveOptions {
+                    presolve,
+                    ..SolveOptions::default()
+                })
+                .solve_lp(problem)
+                .unwrap()
+        };
+        let (full, reduced) = (solve(lp(0.0), false), solve(lp(0.0), true));
+        assert_eq!(reduced.status, Status::Optimal);
+        assert_eq!(reduced.stats.presolve.unwrap().tightened_bounds, 2);
+        assert_eq!(reduced.primal, full.primal);
+        assert_eq!(reduced.basis, full.basis);
+        for (a, b) in full.inequality_dual.iter().zip(&reduced.inequality_dual) {
+            assert!((a - b).abs() < 1e-12, "{a} vs {b}");
+        }
+
+        // x₀ + x₁ ≥ 1.5 on top of it cannot hold.
+        let reduced = solve(lp(-1.5), true);
+        assert_eq!(reduced.status, Status::PrimalInfeasible);
+        assert_eq!(reduced.iterations, 0);
+        assert!(matches!(
+            reduced.certificate,
+            Some(Certificate::PrimalInfeasible { .. })
+        ));
+
+        // Without the tightening step the simplex has to find out itself.
+        let solution = Solver::new()
+            .method(Method::Simplex)
+            .options(SolveOptions {
+                presolve: true,
+                ..SolveOptions::default()
+            })
+            .presolvers(PresolvePipeline::new().with(RemoveRedundantRows))
+            .solve_lp(lp(-1.5))
+            .unwrap();
+        assert_eq!(solution.status, Status::PrimalInfeasible);
+        assert_eq!(solution.stats.presolve.unwrap().tightened_bounds, 0);
+    }
+
+    #[test]
+    fn indefinite_objectives_are_rejected_before_solving() {
+        let mut problem = split_qp();
+        problem.quadratic.data[1] = -1.0;
+        for method in [Method::Admm, Method::ActiveSet] {
+            let err = Solver::new().method(method).solve_qp(problem.clone());
+            assert!(matches!(err, Err(SolverError::NonConvex(1))));
+        }
+    }
+
+    #[test]
+    fn batch_follows_the_right_hand_side() {
+        let problems: Vec<_> = [1.0, 2.0, -3.0]
+            .iter()
+            .map(|&rhs| {
+                let mut problem = split_qp();
+                problem.equalities.as_mut().unwrap().rhs[0] = rhs;
+                problem
+            })
+            .collect();
+        for method in [Method::Admm, Method::ActiveSet] {
+            let solutions = Solver::new()
+                .method(method)
+                .options(SolveOptions::with_tolerance(1e-8))
+                .solve_qp_batch(problems.clone())
+                .unwrap();
+            for (solution, rhs) in solutions.iter().zip([1.0, 2.0, -3.0]) {
+                assert_eq!(solution.status, Status::Optimal);
+                for &x in &solution.primal {
+                    assert!((x - rhs / 2.0).abs() < 1e-5);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn batch_runs_the_configured_presolve_and_scaler() {
+        // x₁ is fixed at 0.5, so presolve leaves x₀ = rhs - 0.5.
+        let problems: Vec<_> = [1.0, 2.0, 3.0]
+            .iter()
+            .map(|&rhs| {
+                let mut problem = split_qp();
+                problem.equalities.as_mut().unwrap().rhs[0] = rhs;
+                problem.bounds = Some(Bounds {
+                    lower: vec![-10.0, 0.5],
+                    upper: vec![10.0, 0.5],
+                });
+                problem
+            })
+            .collect();
+        let solutions = Solver::new()
+            .options(SolveOptions {
+                presolve: true,
+                ..SolveOptions::with_tolerance(1e-8)
+            })
+            .solve_qp_batch(problems.clone())
+            .unwrap();
+        for (solution, rhs) in solutions.iter().zip([1.0, 2.0, 3.0]) {
+            assert_eq!(solution.status, Status::Optimal);
+            assert_eq!(solution.stats.presolve.as_ref().unwrap().fixed_variables, 1);
+            assert_eq!(solution.primal[1], 0.5);
+            assert!((solution.primal[0] - (rhs - 0.5)).abs() < 1e-5);
+        }
+
+        // Without presolve the batch shares one scaling, made by the scaler
+        // set on the solver.
+        let counting = CountingScaler::default();
+        let solutions = Solver::new()
+            .options(SolveOptions::with_tolerance(1e-8))
+            .scaler(counting.clone())
+            .solve_qp_batch(problems)
+            .unwrap();
+        assert_eq!(counting.scaled.load(Ordering::Relaxed), 1);
+        for (solution, rhs) in solutions.iter().zip([1.0, 2.0, 3.0]) {
+            assert!(solution.stats.presolve.is_none());
+            assert!((solution.primal[0] - (rhs - 0.5)).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn setup_handle_follows_updates() {
+        let mut solver = Solver::new().options(SolveOptions::with_tolerance(1e-8));
+        let mut handle = solver.setup(split_qp()).unwrap();
+        for rhs in [1.0, 2.0, -3.0] {
+            handle.update_rhs(vec![rhs], Vec::new()).unwrap();
+            let solution = handle.solve().unwrap();
+            assert_eq!(solution.status, Status::Optimal);
+            for &x in &solution.primal {
+                assert!((x - rhs / 2.0).abs() < 1e-5);
+            }
+        }
+        // x₀ + 3x₁ = 2 with x = 1 + ν(1, 3) gives ν = -0.2.
+        handle
+            .update_matrix_values(None, Some(&[1.0, 3.0]), None)
+            .unwrap();
+        handle.update_rhs(vec![2.0], Vec::new()).unwrap();
+        let solution = handle.solve().unwrap();
+        assert!((solution.primal[0] - 0.8).abs() < 1e-5);
+        assert!((solution.primal[1] - 0.4).abs() < 1e-5);
+
+        assert!(matches!(
+            handle.update_matrix_values(Some(&[1.0, -1.0]), None, None),
+            Err(SolverError::NonConvex(1))
+        ));
+        assert_eq!(handle.problem().quadratic.data, vec![1.0, 1.0]);
+        assert!(matches!(
+            Solver::new().method(Method::Simplex).setup(split_qp()),
+            Err(SolverError::Unsupported(Method::Simplex))
+        ));
+    }
+
+    // Ruiz scaling that counts the problems it scales.
+    #[derive(Clone, Default)]
+    struct CountingScaler {
+        inner: RuizScaler<f64>,
+        scaled: Arc<std::sync::atomic::AtomicUsize>,
+    }
+
+    impl Scaler<f64> for CountingScaler {
+        fn scale_lp(&mut self, problem: &mut ProblemLP<f64>) -> ProblemResult<()> {
+            self.scaled.fetch_add(1, Ordering::Relaxed);
+            self.inner.scale_lp(problem)
+        }
+
+        fn scale_qp(&mut self, problem: &mut ProblemQP<f64>) -> ProblemResult<()> {
+            self.scaled.fetch_add(1, Ordering::Relaxed);
+            self.inner.scale_qp(problem)
+        }
+
+        fn rescale_qp(&self, problem: &mut ProblemQP<f64>) -> ProblemResult<()> {
+            self.inner.rescale_qp(problem)
+        }
+
+        fn unscale_primal(&self, primal: &mut [f64]) {
+            self.inner.unscale_primal(primal);
+        }
+
+        fn unscale_dual(&self, equality: &mut [f64], inequality: &mut [f64]) {
+            self.inner.unscale_dual(equality, inequality);
+        }
+
+        fn unscale_objective(&self, objective: f64) -> f64 {
+            self.inner.unscale_objective(objective)
+        }
+
+        fn unscale_stats(&self, stats: &mut SolveStats<f64>) {
+            self.inner.unscale_stats(stats);
+        }
+    }
+
+    #[test]
+    fn scaling_can_be_replaced_or_turned_off() {
+        let options = SolveOptions::with_tolerance(1e-8);
+        let reference = Solver::new()
+            .options(options.clone())
+            .solve_qp(split_qp())
+            .unwrap();
+        let unscaled = Solver::new()
+            .options(SolveOptions {
+                scaling: Scaling::None,
+                ..options.clone()
+            })
+            .solve_qp(split_qp())
+            .unwrap();
+        let counting = CountingScaler::default();
+        let mut solver = Solver::new().options(options).scaler(counting.clone());
+        for solution in [
+            unscaled,
+            solver.solve_qp(split_qp()).unwrap(),
+            solver.solve_qp(split_qp()).unwrap(),
+        ] {
+            assert_eq!(solution.status, Status::Optimal);
+            for (a, b) in solution.primal.iter().zip(&reference.primal) {
+                assert!((a - b).abs() < 1e-6);
+            }
+            let (y, reference_y) = (solution.inequality_dual[0], reference.inequality_dual[0]);
+            assert!((y - reference_y).abs() < 1e-6);
+        }
+        // Each solve and each setup scales a fresh copy.
+        solver.setup(split_qp()).unwrap().solve().unwrap();
+        assert_eq!(counting.scaled.load(Ordering::Relaxed), 3);
+    }
+
+    #[test]
+    fn quadratic_constraints_go_through_the_conic_form() {
+        // max x0 + x1 s.t. ½(x0² + x1²) <= 1, reached at (1, 1).
+        let mut problem = split_qp();
+        let identity = std::mem::replace(
+            &mut problem.quadratic,
+            CscMatrix {
+                nrows: 2,
+                ncols: 2,
+                indptr: vec![0; 3],
+                indices: Vec::new(),
+                data: Vec::new(),
+            },
+        );
+        problem.equalities = None;
+        problem.quadratic_constraints = vec![QuadraticConstraint {
+            matrix: identity,
+            linear: vec![0.0, 0.0],
+            rhs: 1.0,
+        }];
+        let solution = Solver::new()
+            .options(SolveOptions::with_tolerance(1e-8))
+            .solve_qp(problem.clone())
+            .unwrap();
+        assert_eq!(solution.status, Status::Optimal);
+        for &x in &solution.primal {
+            assert!((x - 1.0).abs() < 1e-4);
+        }
+        assert!((solution.objective_value + 2.0).abs() < 1e-4);
+        assert!(matches!(
+            Solver::new().method(Method::ActiveSet).solve_qp(problem),
+            Err(SolverError::Unsupported(Method::ActiveSet))
+        ));
+    }
+
+    #[test]
+    fn auto_records_the_backend() {
+        let solution = Solver::new()
+            .method(Method::Auto)
+            .solve_qp(split_qp())
+            .unwrap();
+        assert_eq!(solution.status, Status::Optimal);
+        assert_eq!(solution.stats.backend, Some(Method::ActiveSet));
+        for &x in &solution.primal {
+            assert!((x - 0.5).abs() < 1e-9);
+        }
+        let explicit = Solver::new().solve_qp(split_qp()).unwrap();
+        assert_eq!(explicit.stats.backend, None);
+    }
+
+    #[test]
+    fn iteration_hook_can_stop_the_solve() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let log = Arc::clone(&seen);
+        let mut solver = Solver::new()
+            .method(Method::Admm)
+            .options(SolveOptions::with_tolerance(1e-12))
+            .on_iteration(move |record| {
+                let mut log = log.lock().unwrap();
+                log.push(record.iteration);
+                if log.len() == 3 {
+                    ControlFlow::Break(())
+                } else {
+                    ControlFlow::Continue(())
+                }
+            });
+        let solution = solver.solve_qp(split_qp()).unwrap();
+        assert_eq!(solution.status, Status::Interrupted);
+        assert_eq!(solution.iterations, 3);
+        assert_eq!(*seen.lock().unwrap(), vec![0, 1, 2]);
+
+        // The flag is cleared for the next solve, where the hook keeps going.
+        let solution = solver.solve_qp(split_qp()).unwrap();
+        assert_ne!(solution.status, Status::Interrupted);
+        assert!(solution.iterations > 3);
+    }
+
+    #[test]
+    fn continuing_hook_leaves_the_solve_alone() {
+        let solution = Solver::new()
+            .method(Method::Admm)
+            .on_iteration(|_| ControlFlow::Continue(()))
+            .solve_qp(split_qp())
+            .unwrap();
+        assert_eq!(solution.status, Status::Optimal);
+        assert!((solution.primal[0] - 0.5).abs() < 1e-4);
+        assert!((solution.primal[1] - 0.5).abs() < 1e-4);
+    }
+
+    #[test]
+    fn nan_data_is_rejected_before_any_method_runs() {
+        let mut qp = split_qp();
+        qp.linear[1] = f64::NAN;
+        for method in [Method::Admm, Method::ActiveSet] {
+            let result = Solver::new().method(method).solve_qp(qp.clone());
+            assert!(matches!(result, Err(SolverError::InvalidProblem(_))));
+        }
+        // The shared ADMM batch checks every member, not just the first.
+        let result = Solver::new()
+            .method(Method::Admm)
+            .solve_qp_batch(vec![split_qp(), qp]);
+        assert!(matches!(result, Err(SolverError::InvalidProblem(_))));
+
+        let rows = CscMatrix::from_dense(1, 2, &[1.0, f64::NAN]).unwrap();
+        let lp = LpBuilder::new()
+            .c(vec![-1.0, -1.0])
+            .a(rows, vec![1.0])
+            .build()
+            .unwrap();
+        for method in [Method::Simplex, Method::ActiveSet] {
+            let result = Solver::new().method(method).solve_lp(lp.clone());
+            assert!(matches!(result, Err(SolverError::InvalidProblem(_))));
+        }
+
+        // An infinite bound only leaves the variable free.
+        let lp = LpBuilder::new()
+            .c(vec![1.0])
+            .bounds(Bounds {
+                lower: vec![0.0],
+                upper: vec![f64::INFINITY],
+            })
+            .build()
+            .unwrap();
+        assert!(Solver::new().solve_lp(lp).is_ok());
+    }
+}
@@ -0,0 +1,103 @@
+use crate::{check_convexity, BoxedScaler, Solver, SolverError};
+use cvxrs_algos::AdmmSetup;
+use cvxrs_core::math::RealNumber;
+use cvxrs_core::options::Method;
+use cvxrs_core::problem::{Bounds, ObjectiveSense, ProblemQP};
+use cvxrs_core::solution::Solution;
+use cvxrs_core::stats::InterruptFlag;
+use std::sync::atomic::Ordering;
+
+// A QP set up for repeated solves, e.g. one MPC step after another: the
+// vectors and the values of the matrices change between solves while the
+// scaling, the workspace and the KKT factorization carry over. Only ADMM
+// keeps that state, and presolve never runs, since it could drop different
+// rows from one update to the next.
+pub struct ParametricQp<T: RealNumber> {
+    setup: AdmmSetup<T, BoxedScaler<T>>,
+    interrupt: InterruptFlag,
+}
+
+impl<T> ParametricQp<T>
+where
+    T: RealNumber,
+{
+    pub fn problem(&self) -> &ProblemQP<T> {
+        self.setup.problem()
+    }
+
+    pub fn update_q(&mut self, q: Vec<T>) -> Result<(), SolverError> {
+        self.setup.update_q(q).map_err(invalid)
+    }
+
+    // Both blocks at once; a block the problem does not have takes an empty
+    // vector.
+    pub fn update_rhs(&mut self, equality: Vec<T>, inequality: Vec<T>) -> Result<(), SolverError> {
+        self.setup.update_rhs(equality, inequality).map_err(invalid)
+    }
+
+    pub fn update_bounds(&mut self, bounds: Bounds<T>) -> Result<(), SolverError> {
+        self.setup.update_bounds(bounds).map_err(invalid)
+    }
+
+    // New values for the stored entries of P, C and A, in storage order and
+    // on the sparsity pattern given at setup. A P that is no longer convex
+    // is turned away and leaves the problem as it was.
+    pub fn update_matrix_values(
+        &mut self,
+        quadratic: Option<&[T]>,
+        equality: Option<&[T]>,
+        inequality: Option<&[T]>,
+    ) -> Result<(), SolverError> {
+        if let Some(values) = quadratic {
+            let mut candidate = self.setup.problem().clone();
+            if candidate.quadratic.data.len() == values.len() {
+                candidate.quadratic.data.copy_from_slice(values);
+                check_convexity(&candidate)?;
+            }
+        }
+        self.setup
+            .update_matrix_values(quadratic, equality, inequality)
+            .map_err(invalid)
+    }
+
+    pub fn solve(&mut self) -> Result<Solution<T>, SolverError> {
+        self.interrupt.store(false, Ordering::Relaxed);
+        self.setup.solve().map_err(invalid)
+    }
+}
+
+impl<T> Solver<T>
+where
+    T: RealNumber,
+{
+    // Scales and factors `problem` once with this solver's ADMM settings and
+    // hands back the handle that updates and re-solves it.
+    pub fn setup(&mut self, problem: ProblemQP<T>) -> Result<ParametricQp<T>, SolverError> {
+        if !matches!(self.method, Method::Admm | Method::Auto) {
+            return Err(SolverError::Unsupported(self.method));
+        }
+        check_convexity(&problem)?;
+        if problem.integrality.contains(&true) {
+            return Err(SolverError::InvalidProblem(
+                "integer problems cannot be set up for repeated solves".into(),
+            ));
+        }
+        if problem.sense == ObjectiveSense::Maximize {
+            return Err(SolverError::InvalidProblem(
+                "maximizations cannot be set up for repeated solves; negate P and q instead".into(),
+            ));
+        }
+        let setup = self
+            .admm()
+            .setup(problem, self.fresh_scaler())
+            .map_err(invalid)?;
+        Ok(ParametricQp {
+            setup,
+            interrupt: self.interrupt.clone(),
+        })
+    }
+}
+
+fn invalid(err: anyhow::Error) -> SolverError {
+    SolverError::InvalidProblem(err.to_string())
+}
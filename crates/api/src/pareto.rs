@@ -0,0 +1,188 @@
+use crate::{Solver, SolverError};
+use cvxrs_core::math::{dot, RealNumber};
+use cvxrs_core::problem::{CscMatrix, ProblemQP, WarmStart};
+use cvxrs_core::solution::Solution;
+use cvxrs_core::traits::LinearOperator;
+
+// The weights (1 - λ, λ), the two objectives at the solution and the
+// solution of the weighted problem.
+pub type ParetoPoint<T> = ([T; 2], [T; 2], Solution<T>);
+
+impl<T> Solver<T>
+where
+    T: RealNumber,
+{
+    // Traces the trade-off between the objective f₁ of `problem` and
+    // f₂ = ½xᵀP₂x + q₂ᵀx by minimizing (1 - λ)f₁ + λf₂ for each λ in
+    // `weights`, or maximizing it when `problem` is a maximization. Each
+    // point warm-starts the next, so the weights are best given in order.
+    // Points that are not optimal are kept; only their status tells them
+    // apart.
+    pub fn sweep(
+        &mut self,
+        problem: ProblemQP<T>,
+        quadratic: CscMatrix<T>,
+        linear: Vec<T>,
+        weights: &[T],
+    ) -> Result<Vec<ParetoPoint<T>>, SolverError> {
+        let n = problem.nvars();
+        if linear.len() != n || quadratic.nrows != n || quadratic.ncols != n {
+            return Err(SolverError::InvalidProblem(format!(
+                "second objective does not have {n} variables"
+            )));
+        }
+        quadratic
+            .validate()
+            .map_err(|err| SolverError::InvalidProblem(err.to_string()))?;
+        if weights
+            .iter()
+            .any(|&lambda| !(lambda >= T::zero() && lambda <= T::one()))
+        {
+            return Err(SolverError::InvalidProblem(
+                "trade-off weights must lie in [0, 1]".into(),
+            ));
+        }
+
+        let declared = self.warm_start.clone();
+        let mut frontier = Vec::with_capacity(weights.len());
+        for &lambda in weights {
+            let keep = T::one() - lambda;
+            let mut weighted = problem.clone();
+            weighted.quadratic = weighted_sum(&problem.quadratic, keep, &quadratic, lambda);
+            weighted.linear = problem
+                .linear
+                .iter()
+                .zip(&linear)
+                .map(|(&first, &second)| keep * first + lambda * second)
+                .collect();
+            weighted.offset = keep * problem.offset;
+            let result = self.solve_qp(weighted);
+            let solution = match result {
+                Ok(solution) => solution,
+                Err(err) => {
+                    self.warm_start = declared;
+                    return Err(err);
+                }
+            };
+            self.warm_start = Some(WarmStart {
+                primal: solution.primal.clone(),
+                equality_dual: solution.equality_dual.clone(),
+                inequality_dual: solution.inequality_dual.clone(),
+            });
+            let x = &solution.primal;
+            let objectives = [
+                evaluate(&problem.quadratic, &problem.linear, x) + problem.offset,
+                evaluate(&quadratic, &linear, x),
+            ];
+            frontier.push(([keep, lambda], objectives, solution));
+        }
+        self.warm_start = declared;
+        Ok(frontier)
+    }
+}
+
+// wa·A + wb·B for matrices of the same shape, each column sorted by row.
+fn weighted_sum<T: RealNumber>(a: &CscMatrix<T>, wa: T, b: &CscMatrix<T>, wb: T) -> CscMatrix<T> {
+    let mut indptr = vec![0];
+    let mut indices = Vec::with_capacity(a.nnz() + b.nnz());
+    let mut data = Vec::with_capacity(a.nnz() + b.nnz());
+    for col in 0..a.ncols {
+        let mut column: Vec<(usize, T)> = (a.indptr[col]..a.indptr[col + 1])
+            .map(|idx| (a.indices[idx], wa * a.data[idx]))
+            .chain((b.indptr[col]..b.indptr[col + 1]).map(|idx| (b.indices[idx], wb * b.data[idx])))
+            .collect();
+        column.sort_by_key(|&(row, _)| row);
+        for (row, value) in column {
+            if indices.len() > indptr[col] && indices.last() == Some(&row) {
+                *data.last_mut().unwrap() += value;
+            } else {
+                indices.push(row);
+                data.push(value);
+            }
+        }
+        indptr.push(indices.len());
+    }
+    CscMatrix {
+        nrows: a.nrows,
+        ncols: a.ncols,
+        indptr,
+        indices,
+        data,
+    }
+}
+
+fn evaluate<T: RealNumber>(quadratic: &CscMatrix<T>, linear: &[T], x: &[T]) -> T {
+    let mut px = vec![T::zero(); x.len()];
+    quadratic.apply(x, &mut px);
+    T::from(0.5).unwrap() * dot(x, &px) + dot(linear, x)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Method, Solver};
+    use cvxrs_core::problem::{CscMatrix, Names, ObjectiveSense, ProblemQP};
+
+    fn identity(n: usize) -> CscMatrix<f64> {
+        CscMatrix {
+            nrows: n,
+            ncols: n,
+            indptr: (0..=n).collect(),
+            indices: (0..n).collect(),
+            data: vec![1.0; n],
+        }
+    }
+
+    #[test]
+    fn frontier_moves_between_the_two_minimizers() {
+        // ½‖x‖² against ½‖x - (1, 2)‖², whose weighted optimum is λ(1, 2).
+        let problem = ProblemQP {
+            quadratic: identity(2),
+            linear: vec![0.0; 2],
+            inequalities: None,
+            equalities: None,
+            bounds: None,
+            groups: Vec::new(),
+            integrality: Vec::new(),
+            names: Names::default(),
+            sense: ObjectiveSense::Minimize,
+            offset: 0.0,
+            quadratic_constraints: Vec::new(),
+        };
+        let weights = [0.0, 0.25, 0.5, 0.75, 1.0];
+        let frontier = Solver::new()
+            .method(Method::ActiveSet)
+            .sweep(problem, identity(2), vec![-1.0, -2.0], &weights)
+            .unwrap();
+        assert_eq!(frontier.len(), weights.len());
+        for (([keep, lambda], [first, second], solution), &expected) in
+            frontier.iter().zip(&weights)
+        {
+            assert_eq!(*lambda, expected);
+            assert_eq!(keep + lambda, 1.0);
+            assert!((solution.primal[0] - lambda).abs() < 1e-9);
+            assert!((solution.primal[1] - 2.0 * lambda).abs() < 1e-9);
+            assert!((first - 2.5 * lambda * lambda).abs() < 1e-9);
+            // f₂ leaves out the constant ½‖(1, 2)‖².
+            assert!((second + 2.5 - 2.5 * keep * keep).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn weights_outside_the_unit_interval_are_rejected() {
+        let problem = ProblemQP {
+            quadratic: identity(1),
+            linear: vec![0.0],
+            inequalities: None,
+            equalities: None,
+            bounds: None,
+            groups: Vec::new(),
+            integrality: Vec::new(),
+            names: Names::default(),
+            sense: ObjectiveSense::Minimize,
+            offset: 0.0,
+            quadratic_constraints: Vec::new(),
+        };
+        let result = Solver::new().sweep(problem, identity(1), vec![0.0], &[0.5, 1.5]);
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,244 @@
+use cvxrs_core::math::RealNumber;
+use cvxrs_core::problem::{ProblemLP, ProblemQP, WarmStart};
+
+// Shape of a problem as far as warm starts are concerned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConstraintLayout {
+    pub nvars: usize,
+    pub equalities: usize,
+    pub inequalities: usize,
+    pub bounds: bool,
+}
+
+impl ConstraintLayout {
+    pub fn of_qp<T: RealNumber>(problem: &ProblemQP<T>) -> Self {
+        Self {
+            nvars: problem.nvars(),
+            equalities: problem.equalities.as_ref().map_or(0, |eq| eq.matrix.nrows),
+            inequalities: problem
+                .inequalities
+                .as_ref()
+                .map_or(0, |ineq| ineq.matrix.nrows),
+            bounds: problem.bounds.is_some(),
+        }
+    }
+
+    pub fn of_lp<T: RealNumber>(problem: &ProblemLP<T>) -> Self {
+        Self {
+            nvars: problem.nvars(),
+            equalities: problem.equalities.as_ref().map_or(0, |eq| eq.matrix.nrows),
+            inequalities: problem
+                .inequalities
+                .as_ref()
+                .map_or(0, |ineq| ineq.matrix.nrows),
+            bounds: problem.bounds.is_some(),
+        }
+    }
+
+    fn bound_rows(&self) -> usize {
+        if self.bounds {
+            self.nvars
+        } else {
+            0
+        }
+    }
+
+    fn stacked_rows(&self) -> usize {
+        self.equalities + self.inequalities + self.bound_rows()
+    }
+}
+
+// How an edited problem relates to the original one: for every variable and
+// row of the edited problem, its index in the original, or None if it is new.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LayoutEdit {
+    pub variables: Vec<Option<usize>>,
+    pub equalities: Vec<Option<usize>>,
+    pub inequalities: Vec<Option<usize>>,
+    pub bounds: bool,
+}
+
+impl LayoutEdit {
+    // Rows and variables were appended or removed at the end of each block.
+    pub fn between(from: &ConstraintLayout, to: &ConstraintLayout) -> Self {
+        let prefix = |old: usize, new: usize| (0..new).map(|i| (i < old).then_some(i)).collect();
+        Self {
+            variables: prefix(from.nvars, to.nvars),
+            equalities: prefix(from.equalities, to.equalities),
+            inequalities: prefix(from.inequalities, to.inequalities),
+            bounds: to.bounds,
+        }
+    }
+
+    pub fn layout(&self) -> ConstraintLayout {
+        ConstraintLayout {
+            nvars: self.variables.len(),
+            equalities: self.equalities.len(),
+            inequalities: self.inequalities.len(),
+            bounds: self.bounds,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BlockRemap {
+    pub kept: usize,
+    pub padded: usize,
+    pub dropped: usize,
+}
+
+impl BlockRemap {
+    pub fn is_exact(&self) -> bool {
+        self.padded == 0 && self.dropped == 0
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RemapReport {
+    pub primal: BlockRemap,
+    pub equalities: BlockRemap,
+    pub inequalities: BlockRemap,
+    pub bounds: BlockRemap,
+    // The duals did not match the original layout and were discarded.
+    pub duals_discarded: bool,
+}
+
+impl RemapReport {
+    pub fn is_exact(&self) -> bool {
+        !self.duals_discarded
+            && self.primal.is_exact()
+            && self.equalities.is_exact()
+            && self.inequalities.is_exact()
+            && self.bounds.is_exact()
+    }
+}
+
+// Carries a warm start over to an edited problem. New entries start at zero
+// and entries without a counterpart are dropped; the report counts both.
+// Duals are accepted in the stacked ADMM form (everything in
+// `inequality_dual`) or split by constraint kind, with bound multipliers
+// optionally after the inequalities, and are returned in the same form.
+pub fn remap_warm_start<T: RealNumber>(
+    warm: &WarmStart<T>,
+    from: &ConstraintLayout,
+    edit: &LayoutEdit,
+) -> (WarmStart<T>, RemapReport) {
+    let mut report = RemapReport::default();
+    let primal = if warm.primal.len() == from.nvars {
+        remap_block(&warm.primal, &edit.variables, &mut report.primal)
+    } else {
+        report.primal.padded = edit.variables.len();
+        vec![T::zero(); edit.variables.len()]
+    };
+
+    let (eq, ineq) = (&warm.equality_dual, &warm.inequality_dual);
+    let (meq, mineq) = (from.equalities, from.inequalities);
+    let mut equality_dual = Vec::new();
+    let mut inequality_dual = Vec::new();
+    if eq.is_empty() && ineq.is_empty() {
+        // Primal-only warm start; nothing to carry over.
+    } else if eq.is_empty() && ineq.len() == from.stacked_rows() {
+        inequality_dual = remap_block(&ineq[..meq], &edit.equalities, &mut report.equalities);
+        inequality_dual.extend(remap_block(
+            &ineq[meq..meq + mineq],
+            &edit.inequalities,
+            &mut report.inequalities,
+        ));
+        inequality_dual.extend(remap_bounds(&ineq[meq + mineq..], edit, &mut report.bounds));
+    } else if eq.len() == meq && (ineq.len() == mineq || ineq.len() == mineq + from.bound_rows()) {
+        equality_dual = remap_block(eq, &edit.equalities, &mut report.equalities);
+        inequality_dual = remap_block(&ineq[..mineq], &edit.inequalities, &mut report.inequalities);
+        if ineq.len() > mineq {
+            inequality_dual.extend(remap_bounds(&ineq[mineq..], edit, &mut report.bounds));
+        }
+    } else {
+        report.duals_discarded = true;
+    }
+    (
+        WarmStart {
+            primal,
+            equality_dual,
+            inequality_dual,
+        },
+        report,
+    )
+}
+
+fn remap_block<T: RealNumber>(old: &[T], map: &[Option<usize>], report: &mut BlockRemap) -> Vec<T> {
+    let mut used = vec![false; old.len()];
+    let values = map
+        .iter()
+        .map(|source| match source.filter(|&index| index < old.len()) {
+            Some(index) => {
+                used[index] = true;
+                report.kept += 1;
+                old[index]
+            }
+            None => {
+                report.padded += 1;
+                T::zero()
+            }
+        })
+        .collect();
+    report.dropped = used.iter().filter(|&&used| !used).count();
+    values
+}
+
+fn remap_bounds<T: RealNumber>(old: &[T], edit: &LayoutEdit, report: &mut BlockRemap) -> Vec<T> {
+    if !edit.bounds {
+        report.dropped = old.len();
+        Vec::new()
+    } else if old.is_empty() {
+        report.padded = edit.variables.len();
+        vec![T::zero(); edit.variables.len()]
+    } else {
+        remap_block(old, &edit.variables, report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stacked_duals_follow_added_rows_and_dropped_bounds() {
+        let from = ConstraintLayout {
+            nvars: 2,
+            equalities: 1,
+            inequalities: 2,
+            bounds: true,
+        };
+        let warm = WarmStart {
+            primal: vec![1.0, 2.0],
+            equality_dual: Vec::new(),
+            inequality_dual: vec![0.5, 1.5, 2.5, 3.5, 4.5],
+        };
+        // Second inequality removed, a new one appended, bounds switched off.
+        let edit = LayoutEdit {
+            variables: vec![Some(0), Some(1)],
+            equalities: vec![Some(0)],
+            inequalities: vec![Some(0), None],
+            bounds: false,
+        };
+        let (remapped, report) = remap_warm_start(&warm, &from, &edit);
+        assert_eq!(remapped.primal, vec![1.0, 2.0]);
+        assert_eq!(remapped.inequality_dual, vec![0.5, 1.5, 0.0]);
+        assert_eq!(
+            report.inequalities,
+            BlockRemap {
+                kept: 1,
+                padded: 1,
+                dropped: 1
+            }
+        );
+        assert_eq!(report.bounds.dropped, 2);
+        assert!(!report.is_exact());
+
+        let grown = ConstraintLayout { nvars: 3, ..from };
+        let (remapped, report) =
+            remap_warm_start(&warm, &from, &LayoutEdit::between(&from, &grown));
+        assert_eq!(remapped.primal, vec![1.0, 2.0, 0.0]);
+        assert_eq!(remapped.inequality_dual, vec![0.5, 1.5, 2.5, 3.5, 4.5, 0.0]);
+        assert_eq!(report.primal.padded, 1);
+    }
+}
@@ -0,0 +1,52 @@
+use cvxrs_core::math::RealNumber;
+use metrics::{counter, histogram};
+
+use crate::{Method, Solution, SolverError};
+
+// Solve-level metrics, reported through whatever `metrics` recorder the
+// embedding application installs (Prometheus, OpenTelemetry, statsd, ...).
+pub(crate) fn record<T: RealNumber>(
+    method: Method,
+    kind: &'static str,
+    result: &Result<Solution<T>, SolverError>,
+) {
+    let method = method_label(method);
+    let solution = match result {
+        Ok(solution) => solution,
+        Err(err) => {
+            let reason = match err {
+                SolverError::InvalidProblem(_) => "invalid_problem",
+                SolverError::Unsupported(_) => "unsupported",
+                SolverError::NonConvex(_) => "nonconvex",
+            };
+            counter!("cvxrs_solve_errors_total", "method" => method, "kind" => kind, "reason" => reason)
+                .increment(1);
+            return;
+        }
+    };
+    let status = format!("{:?}", solution.status);
+    counter!("cvxrs_solves_total", "method" => method, "kind" => kind, "status" => status)
+        .increment(1);
+    histogram!("cvxrs_solve_duration_seconds", "method" => method, "kind" => kind)
+        .record(solution.stats.solve_time.as_secs_f64());
+    histogram!("cvxrs_solve_iterations", "method" => method, "kind" => kind)
+        .record(solution.iterations as f64);
+    if let Some(last) = solution.stats.history.last() {
+        let value = |value: T| value.to_f64().unwrap_or(f64::NAN);
+        histogram!("cvxrs_primal_residual", "method" => method, "kind" => kind)
+            .record(value(last.primal_residual));
+        histogram!("cvxrs_dual_residual", "method" => method, "kind" => kind)
+            .record(value(last.dual_residual));
+    }
+}
+
+fn method_label(method: Method) -> &'static str {
+    match method {
+        Method::Admm => "admm",
+        Method::Ipm => "ipm",
+        Method::ActiveSet => "active_set",
+        Method::Simplex => "simplex",
+        Method::Pdhg => "pdhg",
+        Method::Auto => "auto",
+    }
+}
@@ -0,0 +1,146 @@
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use cvxrs_core::math::Scalar;
+use cvxrs_core::traits::KktSolver;
+use cvxrs_linsys::{
+    DenseKktMatrix, DenseKktSolver, DensePattern, SparseKktMatrix, SparseKktSolver, SparsePattern,
+};
+use rand::{rngs::SmallRng, Rng, SeedableRng};
+use sprs::TriMat;
+
+const SIZES: [usize; 3] = [50, 100, 200];
+const DENSITIES: [f64; 2] = [0.05, 0.25];
+
+// Quasi-definite KKT matrix [P + sigma I, A'; A, -I / rho] with m = n / 2 rows in A,
+// stored as symmetric (row, col, value) entries with row >= col.
+struct KktEntries {
+    dimension: usize,
+    lower: Vec<(usize, usize, Scalar)>,
+}
+
+fn random_kkt(n: usize, density: f64, rng: &mut SmallRng) -> KktEntries {
+    let m = n / 2;
+    let mut lower = Vec::new();
+    for col in 0..n {
+        let mut diagonal = 1e-6;
+        for row in (col + 1)..n {
+            if rng.gen::<f64>() < density {
+                let value = rng.gen::<Scalar>() - 0.5;
+                diagonal += value.abs();
+                lower.push((row, col, value));
+            }
+        }
+        lower.push((col, col, diagonal + 1.0));
+    }
+    for row in 0..m {
+        for col in 0..n {
+            if rng.gen::<f64>() < density {
+                lower.push((n + row, col, rng.gen::<Scalar>() - 0.5));
+            }
+        }
+        lower.push((n + row, n + row, -1.0 / 0.1));
+    }
+    KktEntries {
+        dimension: n + m,
+        lower,
+    }
+}
+
+fn dense_matrix(entries: &KktEntries) -> DenseKktMatrix<Scalar> {
+    let dim = entries.dimension;
+    let mut data = vec![0.0; dim * dim];
+    for &(row, col, value) in &entries.lower {
+        data[row * dim + col] = value;
+        data[col * dim + row] = value;
+    }
+    DenseKktMatrix::new(dim, data)
+}
+
+fn sparse_matrix(entries: &KktEntries) -> SparseKktMatrix<Scalar> {
+    let dim = entries.dimension;
+    let mut triplets = TriMat::new((dim, dim));
+    for &(row, col, value) in &entries.lower {
+        triplets.add_triplet(row, col, value);
+    }
+    SparseKktMatrix::new(triplets.to_csc())
+}
+
+fn bench_backend<S, M>(
+    c: &mut Criterion,
+    name: &str,
+    solver: impl Fn(usize) -> S,
+    matrix: impl Fn(&KktEntries) -> M,
+) where
+    S: KktSolver<Scalar, Matrix = M>,
+{
+    let mut rng = SmallRng::seed_from_u64(7);
+    let cases: Vec<(usize, f64, KktEntries)> = SIZES
+        .iter()
+        .flat_map(|&n| DENSITIES.iter().map(move |&density| (n, density)))
+        .map(|(n, density)| (n, density, random_kkt(n, density, &mut rng)))
+        .collect();
+
+    let mut group = c.benchmark_group(format!("{}/factor", name));
+    for (n, density, entries) in &cases {
+        let kkt = matrix(entries);
+        group.bench_with_input(
+            BenchmarkId::new(format!("density={}", density), n),
+            &kkt,
+            |b, kkt| {
+                b.iter_batched(
+                    || solver(entries.dimension),
+                    |mut solver| solver.factor(kkt).unwrap(),
+                    BatchSize::SmallInput,
+                );
+            },
+        );
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group(format!("{}/solve", name));
+    for (n, density, entries) in &cases {
+        let mut factored = solver(entries.dimension);
+        factored.factor(&matrix(entries)).unwrap();
+        let rhs: Vec<Scalar> = (0..entries.dimension).map(|i| (i % 7) as Scalar).collect();
+        group.bench_with_input(
+            BenchmarkId::new(format!("density={}", density), n),
+            &rhs,
+            |b, rhs| {
+                b.iter_batched(
+                    || rhs.clone(),
+                    |mut rhs| factored.solve(&mut rhs).unwrap(),
+                    BatchSize::SmallInput,
+                );
+            },
+        );
+    }
+    group.finish();
+}
+
+fn dense_kkt(c: &mut Criterion) {
+    bench_backend(
+        c,
+        "dense_kkt",
+        |dim| {
+            let mut solver = DenseKktSolver::new();
+            solver.analyze_pattern(&DensePattern::new(dim)).unwrap();
+            solver
+        },
+        dense_matrix,
+    );
+}
+
+fn sparse_kkt(c: &mut Criterion) {
+    bench_backend(
+        c,
+        "sparse_kkt",
+        |dim| {
+            let mut solver = SparseKktSolver::new();
+            solver.analyze_pattern(&SparsePattern::new(dim)).unwrap();
+            solver
+        },
+        sparse_matrix,
+    );
+}
+
+criterion_group!(benches, dense_kkt, sparse_kkt);
+criterion_main!(benches);
@@ -29,7 +29,7 @@ fn random_constraints(m: usize, n: usize, rng: &mut SmallRng) -> CscMatrix<Scala
     let mut indices = Vec::new();
     let mut data = Vec::new();
     indptr.push(0);
-    for col in 0..n {
+    for _ in 0..n {
         for row in 0..m {
             indices.push(row);
             data.push(rng.gen::<Scalar>() * 0.5 - 0.25);
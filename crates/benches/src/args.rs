@@ -0,0 +1,190 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use clap::{Args, ValueEnum};
+use cvxrs_api::Method;
+
+use crate::baseline::{compare, print_table, read_results, Thresholds};
+use crate::profile::{log_tau_grid, performance_profile};
+use crate::suite::{
+    discover, expected_runs, parse_references, read_baseline, read_expected, read_references,
+    regressions, run_suite, solve_counts, write_expected, write_records, RunRecord, SuiteConfig,
+};
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum MethodArg {
+    Admm,
+    Ipm,
+    ActiveSet,
+    Simplex,
+    Pdhg,
+    Auto,
+}
+
+impl From<MethodArg> for Method {
+    fn from(arg: MethodArg) -> Method {
+        match arg {
+            MethodArg::Admm => Method::Admm,
+            MethodArg::Ipm => Method::Ipm,
+            MethodArg::ActiveSet => Method::ActiveSet,
+            MethodArg::Simplex => Method::Simplex,
+            MethodArg::Pdhg => Method::Pdhg,
+            MethodArg::Auto => Method::Auto,
+        }
+    }
+}
+
+#[derive(Args)]
+pub struct SuiteArgs {
+    #[arg(long)]
+    pub dir: PathBuf,
+    #[arg(long)]
+    pub references: Option<PathBuf>,
+    #[arg(long)]
+    pub baseline: Vec<PathBuf>,
+    #[arg(long, value_delimiter = ',', default_value = "admm,ipm")]
+    pub methods: Vec<MethodArg>,
+    #[arg(long)]
+    pub out: Option<PathBuf>,
+    #[arg(long)]
+    pub filter: Option<String>,
+    #[arg(long)]
+    pub tol: Option<f64>,
+    #[arg(long)]
+    pub max_iters: Option<usize>,
+    #[arg(long)]
+    pub time_limit: Option<u64>,
+    #[arg(long, default_value_t = 1e-4)]
+    pub objective_tol: f64,
+    #[arg(long, default_value_t = 2048)]
+    pub max_memory_mb: u64,
+    #[arg(long, default_value_t = 64.0)]
+    pub max_tau: f64,
+    #[arg(long)]
+    pub expected: Option<PathBuf>,
+    #[arg(long)]
+    pub update_expected: bool,
+    #[arg(long)]
+    pub save_baseline: Option<PathBuf>,
+    #[arg(long)]
+    pub compare: Option<PathBuf>,
+    #[arg(long, default_value_t = 0.10)]
+    pub threshold: f64,
+}
+
+impl SuiteArgs {
+    pub fn config(&self) -> SuiteConfig {
+        let mut config = SuiteConfig {
+            objective_tolerance: self.objective_tol as _,
+            max_memory_bytes: self.max_memory_mb * 1024 * 1024,
+            ..SuiteConfig::default()
+        };
+        if let Some(tol) = self.tol {
+            config.options.eps_abs = tol as _;
+            config.options.eps_rel = tol as _;
+        }
+        if let Some(iters) = self.max_iters {
+            config.options.max_iterations = iters;
+        }
+        if let Some(limit) = self.time_limit {
+            config.options.max_time = Some(Duration::from_secs(limit));
+        }
+        config
+    }
+
+    pub fn run(
+        &self,
+        suite: &str,
+        extensions: &[&str],
+        bundled_references: &str,
+    ) -> Result<Vec<RunRecord>> {
+        let mut instances = discover(&self.dir, extensions)?;
+        if let Some(filter) = &self.filter {
+            let filter = filter.to_ascii_uppercase();
+            instances.retain(|instance| instance.name.contains(&filter));
+        }
+        if instances.is_empty() {
+            bail!("no {} instances found in {:?}", suite, self.dir);
+        }
+        let mut references = parse_references(bundled_references)?;
+        if let Some(path) = &self.references {
+            references.extend(read_references(path)?);
+        }
+        let methods: Vec<Method> = self.methods.iter().map(|&arg| arg.into()).collect();
+        let mut records = run_suite(&instances, &methods, &references, &self.config());
+
+        let out = self
+            .out
+            .clone()
+            .unwrap_or_else(|| Path::new("target").join(suite));
+        fs::create_dir_all(&out).with_context(|| format!("failed to create {:?}", out))?;
+        write_records(out.join("results.csv"), &records)?;
+        if let Some(path) = &self.save_baseline {
+            write_records(path, &records)?;
+            println!("baseline saved to {:?}", path);
+        }
+        let gate = self.gate(&records);
+        let comparison = self.compare_with_baseline(&records);
+
+        for path in &self.baseline {
+            records.extend(read_baseline(path)?);
+        }
+        let profile = performance_profile(&records, &log_tau_grid(self.max_tau, 49));
+        profile.write_csv(out.join("profile.csv"))?;
+
+        for (solver, solved, total) in solve_counts(&records) {
+            println!("{}: solved {}/{}", solver, solved, total);
+        }
+        println!("results written to {:?}", out);
+        gate?;
+        comparison?;
+        Ok(records)
+    }
+
+    fn compare_with_baseline(&self, records: &[RunRecord]) -> Result<()> {
+        let Some(path) = &self.compare else {
+            return Ok(());
+        };
+        let thresholds = Thresholds {
+            time: self.threshold,
+            iterations: self.threshold,
+            ..Thresholds::default()
+        };
+        let comparisons = compare(&read_results(path)?, records, &thresholds);
+        print_table(&comparisons, false);
+        let regressed = comparisons.iter().filter(|c| c.is_regression()).count();
+        if regressed > 0 {
+            bail!("{} runs regressed against {:?}", regressed, path);
+        }
+        Ok(())
+    }
+
+    fn gate(&self, records: &[RunRecord]) -> Result<()> {
+        let Some(path) = &self.expected else {
+            return Ok(());
+        };
+        if self.update_expected {
+            write_expected(path, records)?;
+            println!("expected solves updated in {:?}", path);
+            return Ok(());
+        }
+        let mut expected = read_expected(path)?;
+        if self.filter.is_some() {
+            expected = expected_runs(expected, records);
+        }
+        let missing = regressions(&expected, records);
+        for (solver, instance) in &missing {
+            println!("regression: {} no longer solves {}", solver, instance);
+        }
+        if !missing.is_empty() {
+            bail!(
+                "{} of {} expected solves regressed",
+                missing.len(),
+                expected.len()
+            );
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,229 @@
+use std::fs;
+use std::path::Path;
+
+use anyhow::{anyhow, bail, Context, Result};
+use cvxrs_api::Status;
+use cvxrs_core::math::Scalar;
+
+use crate::suite::{Outcome, RunRecord};
+
+#[derive(Debug, Clone, Copy)]
+pub struct Thresholds {
+    pub time: f64,
+    pub iterations: f64,
+    pub objective: Scalar,
+    pub min_seconds: f64,
+}
+
+impl Default for Thresholds {
+    fn default() -> Self {
+        Self {
+            time: 0.10,
+            iterations: 0.10,
+            objective: 1e-6,
+            min_seconds: 0.01,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Comparison {
+    pub instance: String,
+    pub solver: String,
+    pub old: Option<RunRecord>,
+    pub new: Option<RunRecord>,
+    pub flags: Vec<&'static str>,
+}
+
+impl Comparison {
+    pub fn is_regression(&self) -> bool {
+        !self.flags.is_empty()
+    }
+}
+
+pub fn read_results<P: AsRef<Path>>(path: P) -> Result<Vec<RunRecord>> {
+    let path = path.as_ref();
+    let contents =
+        fs::read_to_string(path).with_context(|| format!("failed to read {:?}", path))?;
+    let mut records = Vec::new();
+    for (number, line) in contents.lines().enumerate().skip(1) {
+        if line.trim().is_empty() {
+            continue;
+        }
+        records.push(
+            parse_result_line(line)
+                .with_context(|| format!("{:?} line {}: malformed result", path, number + 1))?,
+        );
+    }
+    Ok(records)
+}
+
+fn parse_result_line(line: &str) -> Result<RunRecord> {
+    let fields: Vec<&str> = line.splitn(9, ',').collect();
+    let [instance, solver, outcome, status, objective, reference, seconds, iterations, message] =
+        fields[..]
+    else {
+        bail!("expected 9 columns");
+    };
+    let optional = |field: &str| -> Result<Option<Scalar>> {
+        if field.is_empty() {
+            Ok(None)
+        } else {
+            field
+                .parse::<Scalar>()
+                .map(Some)
+                .map_err(|_| anyhow!("invalid number {}", field))
+        }
+    };
+    Ok(RunRecord {
+        instance: instance.to_owned(),
+        solver: solver.to_owned(),
+        outcome: match outcome {
+            "solved" => Outcome::Solved,
+            "inaccurate" => Outcome::Inaccurate,
+            "skipped" => Outcome::Skipped,
+            _ => Outcome::Failed,
+        },
+        status: parse_status(status),
+        objective: optional(objective)?,
+        reference: optional(reference)?,
+        seconds: seconds
+            .parse()
+            .map_err(|_| anyhow!("invalid time {}", seconds))?,
+        iterations: iterations
+            .parse()
+            .map_err(|_| anyhow!("invalid iteration count {}", iterations))?,
+        message: message.trim_matches('"').to_owned(),
+    })
+}
+
+fn parse_status(field: &str) -> Option<Status> {
+    match field {
+        "Optimal" => Some(Status::Optimal),
+        "PrimalInfeasible" => Some(Status::PrimalInfeasible),
+        "DualInfeasible" => Some(Status::DualInfeasible),
+        "MaxIterations" => Some(Status::MaxIterations),
+        "MaxTime" => Some(Status::MaxTime),
+        "NumericalFailure" => Some(Status::NumericalFailure),
+        "Interrupted" => Some(Status::Interrupted),
+        _ => None,
+    }
+}
+
+pub fn compare(old: &[RunRecord], new: &[RunRecord], thresholds: &Thresholds) -> Vec<Comparison> {
+    let mut keys: Vec<(&str, &str)> = old
+        .iter()
+        .chain(new.iter())
+        .map(|r| (r.instance.as_str(), r.solver.as_str()))
+        .collect();
+    keys.sort_unstable();
+    keys.dedup();
+    keys.into_iter()
+        .map(|(instance, solver)| {
+            let find = |records: &[RunRecord]| {
+                records
+                    .iter()
+                    .find(|r| r.instance == instance && r.solver == solver)
+                    .cloned()
+            };
+            let old = find(old);
+            let new = find(new);
+            let flags = match (&old, &new) {
+                (Some(old), Some(new)) => regression_flags(old, new, thresholds),
+                (Some(old), None) if old.outcome == Outcome::Solved => vec!["missing"],
+                _ => Vec::new(),
+            };
+            Comparison {
+                instance: instance.to_owned(),
+                solver: solver.to_owned(),
+                old,
+                new,
+                flags,
+            }
+        })
+        .collect()
+}
+
+fn regression_flags(
+    old: &RunRecord,
+    new: &RunRecord,
+    thresholds: &Thresholds,
+) -> Vec<&'static str> {
+    let mut flags = Vec::new();
+    if old.outcome == Outcome::Solved && new.outcome != Outcome::Solved {
+        flags.push("outcome");
+    }
+    if new.seconds > thresholds.min_seconds && new.seconds > old.seconds * (1.0 + thresholds.time) {
+        flags.push("time");
+    }
+    if new.iterations as f64 > old.iterations as f64 * (1.0 + thresholds.iterations) {
+        flags.push("iterations");
+    }
+    if let (Some(old), Some(new)) = (old.objective, new.objective) {
+        let scale = old.abs().max(1.0);
+        if (new - old).abs() / scale > thresholds.objective {
+            flags.push("objective");
+        }
+    }
+    flags
+}
+
+pub fn print_table(comparisons: &[Comparison], only_regressions: bool) {
+    println!(
+        "{:<14} {:<8} {:>10} {:>10} {:>8} {:>8} {:>8}  flags",
+        "instance", "solver", "old s", "new s", "ratio", "old it", "new it"
+    );
+    for comparison in comparisons {
+        if only_regressions && !comparison.is_regression() {
+            continue;
+        }
+        let seconds = |record: &Option<RunRecord>| {
+            record
+                .as_ref()
+                .map(|r| format!("{:.3}", r.seconds))
+                .unwrap_or_else(|| String::from("-"))
+        };
+        let iterations = |record: &Option<RunRecord>| {
+            record
+                .as_ref()
+                .map(|r| r.iterations.to_string())
+                .unwrap_or_else(|| String::from("-"))
+        };
+        let ratio = match (&comparison.old, &comparison.new) {
+            (Some(old), Some(new)) if old.seconds > 0.0 => {
+                format!("{:.2}x", new.seconds / old.seconds)
+            }
+            _ => String::from("-"),
+        };
+        println!(
+            "{:<14} {:<8} {:>10} {:>10} {:>8} {:>8} {:>8}  {}",
+            comparison.instance,
+            comparison.solver,
+            seconds(&comparison.old),
+            seconds(&comparison.new),
+            ratio,
+            iterations(&comparison.old),
+            iterations(&comparison.new),
+            comparison.flags.join(",")
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flags_slower_and_lost_solves() {
+        let old = parse_result_line("A,admm,solved,Optimal,1.5,,0.500000,100,\"\"").unwrap();
+        let slower = parse_result_line("A,admm,solved,Optimal,1.5,,0.800000,104,\"\"").unwrap();
+        let lost = parse_result_line("A,admm,failed,MaxIterations,2.0,,0.400000,100,\"\"").unwrap();
+        let thresholds = Thresholds::default();
+        assert_eq!(regression_flags(&old, &slower, &thresholds), vec!["time"]);
+        assert_eq!(
+            regression_flags(&old, &lost, &thresholds),
+            vec!["outcome", "objective"]
+        );
+        assert!(regression_flags(&old, &old, &thresholds).is_empty());
+    }
+}
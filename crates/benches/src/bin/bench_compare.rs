@@ -0,0 +1,45 @@
+#![forbid(unsafe_code)]
+
+use std::path::PathBuf;
+
+use anyhow::{bail, Result};
+use clap::Parser;
+use cvxrs_benches::baseline::{compare, print_table, read_results, Thresholds};
+
+#[derive(Parser)]
+#[command(name = "bench-compare")]
+#[command(about = "Compare two benchmark result files and flag regressions")]
+struct Cli {
+    baseline: PathBuf,
+    current: PathBuf,
+    #[arg(long, default_value_t = 0.10)]
+    threshold: f64,
+    #[arg(long)]
+    min_seconds: Option<f64>,
+    #[arg(long)]
+    only_regressions: bool,
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let mut thresholds = Thresholds {
+        time: cli.threshold,
+        iterations: cli.threshold,
+        ..Thresholds::default()
+    };
+    if let Some(min_seconds) = cli.min_seconds {
+        thresholds.min_seconds = min_seconds;
+    }
+    let comparisons = compare(
+        &read_results(&cli.baseline)?,
+        &read_results(&cli.current)?,
+        &thresholds,
+    );
+    print_table(&comparisons, cli.only_regressions);
+    let regressed = comparisons.iter().filter(|c| c.is_regression()).count();
+    if regressed > 0 {
+        bail!("{} of {} runs regressed", regressed, comparisons.len());
+    }
+    println!("no regressions across {} runs", comparisons.len());
+    Ok(())
+}
@@ -0,0 +1,20 @@
+#![forbid(unsafe_code)]
+
+use anyhow::Result;
+use clap::Parser;
+use cvxrs_benches::args::SuiteArgs;
+
+#[derive(Parser)]
+#[command(name = "maros-meszaros")]
+#[command(about = "Run the Maros-Meszaros QP set and emit performance profiles")]
+struct Cli {
+    #[command(flatten)]
+    suite: SuiteArgs,
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    cli.suite
+        .run("maros-meszaros", &["qps", "sif", "mps"], "")?;
+    Ok(())
+}
@@ -0,0 +1,21 @@
+#![forbid(unsafe_code)]
+
+use anyhow::Result;
+use clap::Parser;
+use cvxrs_benches::args::SuiteArgs;
+
+const NETLIB_REFERENCES: &str = include_str!("../../data/netlib_reference.csv");
+
+#[derive(Parser)]
+#[command(name = "netlib")]
+#[command(about = "Run the Netlib LP set and check objectives against published optima")]
+struct Cli {
+    #[command(flatten)]
+    suite: SuiteArgs,
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    cli.suite.run("netlib", &["mps"], NETLIB_REFERENCES)?;
+    Ok(())
+}
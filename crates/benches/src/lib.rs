@@ -1 +1,6 @@
 #![forbid(unsafe_code)]
+
+pub mod args;
+pub mod baseline;
+pub mod profile;
+pub mod suite;
@@ -0,0 +1,134 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use anyhow::{Context, Result};
+
+use crate::suite::{Outcome, RunRecord};
+
+#[derive(Debug, Clone)]
+pub struct PerformanceProfile {
+    pub solvers: Vec<String>,
+    pub taus: Vec<f64>,
+    pub fractions: Vec<Vec<f64>>,
+}
+
+pub fn performance_ratios(records: &[RunRecord]) -> BTreeMap<String, Vec<f64>> {
+    let instances: BTreeSet<&str> = records.iter().map(|r| r.instance.as_str()).collect();
+    let solvers: BTreeSet<&str> = records.iter().map(|r| r.solver.as_str()).collect();
+    let mut ratios: BTreeMap<String, Vec<f64>> = solvers
+        .iter()
+        .map(|solver| ((*solver).to_owned(), Vec::with_capacity(instances.len())))
+        .collect();
+    for instance in &instances {
+        let time_of = |solver: &str| {
+            records
+                .iter()
+                .find(|r| r.instance == *instance && r.solver == solver)
+                .filter(|r| r.outcome == Outcome::Solved)
+                .map(|r| r.seconds.max(f64::MIN_POSITIVE))
+        };
+        let best = solvers
+            .iter()
+            .filter_map(|solver| time_of(solver))
+            .fold(f64::INFINITY, f64::min);
+        for solver in &solvers {
+            let ratio = match time_of(solver) {
+                Some(time) => time / best,
+                None => f64::INFINITY,
+            };
+            ratios
+                .get_mut(*solver)
+                .expect("solver registered")
+                .push(ratio);
+        }
+    }
+    ratios
+}
+
+pub fn log_tau_grid(max_tau: f64, points: usize) -> Vec<f64> {
+    let points = points.max(2);
+    let log_max = max_tau.max(1.0).log2();
+    (0..points)
+        .map(|idx| (log_max * idx as f64 / (points - 1) as f64).exp2())
+        .collect()
+}
+
+pub fn performance_profile(records: &[RunRecord], taus: &[f64]) -> PerformanceProfile {
+    let ratios = performance_ratios(records);
+    let mut solvers = Vec::with_capacity(ratios.len());
+    let mut fractions = Vec::with_capacity(ratios.len());
+    for (solver, ratios) in ratios {
+        let total = ratios.len().max(1) as f64;
+        fractions.push(
+            taus.iter()
+                .map(|&tau| ratios.iter().filter(|&&ratio| ratio <= tau).count() as f64 / total)
+                .collect(),
+        );
+        solvers.push(solver);
+    }
+    PerformanceProfile {
+        solvers,
+        taus: taus.to_vec(),
+        fractions,
+    }
+}
+
+impl PerformanceProfile {
+    pub fn write_csv<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        let file = File::create(path).with_context(|| format!("failed to create {:?}", path))?;
+        let mut writer = BufWriter::new(file);
+        writeln!(writer, "tau,{}", self.solvers.join(","))?;
+        for (idx, tau) in self.taus.iter().enumerate() {
+            let row: Vec<String> = self
+                .fractions
+                .iter()
+                .map(|fractions| format!("{:.4}", fractions[idx]))
+                .collect();
+            writeln!(writer, "{:.4},{}", tau, row.join(","))?;
+        }
+        writer
+            .flush()
+            .with_context(|| format!("failed to write {:?}", path))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(instance: &str, solver: &str, seconds: f64, solved: bool) -> RunRecord {
+        RunRecord {
+            instance: instance.to_owned(),
+            solver: solver.to_owned(),
+            outcome: if solved {
+                Outcome::Solved
+            } else {
+                Outcome::Failed
+            },
+            status: None,
+            objective: None,
+            reference: None,
+            seconds,
+            iterations: 0,
+            message: String::new(),
+        }
+    }
+
+    #[test]
+    fn profile_counts_ratios_to_best_solver() {
+        let records = vec![
+            record("A", "admm", 2.0, true),
+            record("A", "ipm", 1.0, true),
+            record("B", "admm", 1.0, true),
+            record("B", "ipm", 4.0, false),
+        ];
+        let profile = performance_profile(&records, &[1.0, 2.0, 8.0]);
+        assert_eq!(profile.solvers, vec!["admm", "ipm"]);
+        assert_eq!(profile.fractions[0], vec![0.5, 1.0, 1.0]);
+        assert_eq!(profile.fractions[1], vec![0.5, 0.5, 0.5]);
+    }
+}
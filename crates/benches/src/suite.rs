@@ -0,0 +1,426 @@
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use anyhow::{anyhow, bail, Context, Result};
+use cvxrs_api::{Method, Solver, Status};
+use cvxrs_core::math::Scalar;
+use cvxrs_core::options::SolveOptions;
+use cvxrs_io::{read_mps_problem, JsonProblem};
+
+#[derive(Debug, Clone)]
+pub struct Instance {
+    pub name: String,
+    pub path: PathBuf,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Solved,
+    Inaccurate,
+    Failed,
+    Skipped,
+}
+
+impl Outcome {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Outcome::Solved => "solved",
+            Outcome::Inaccurate => "inaccurate",
+            Outcome::Failed => "failed",
+            Outcome::Skipped => "skipped",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RunRecord {
+    pub instance: String,
+    pub solver: String,
+    pub outcome: Outcome,
+    pub status: Option<Status>,
+    pub objective: Option<Scalar>,
+    pub reference: Option<Scalar>,
+    pub seconds: f64,
+    pub iterations: usize,
+    pub message: String,
+}
+
+impl RunRecord {
+    fn new(instance: &Instance, solver: &str, outcome: Outcome, message: String) -> Self {
+        Self {
+            instance: instance.name.clone(),
+            solver: solver.to_owned(),
+            outcome,
+            status: None,
+            objective: None,
+            reference: None,
+            seconds: 0.0,
+            iterations: 0,
+            message,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SuiteConfig {
+    pub options: SolveOptions<Scalar>,
+    pub objective_tolerance: Scalar,
+    pub max_memory_bytes: u64,
+}
+
+impl Default for SuiteConfig {
+    fn default() -> Self {
+        Self {
+            options: SolveOptions::default(),
+            objective_tolerance: 1e-4,
+            max_memory_bytes: 2 * 1024 * 1024 * 1024,
+        }
+    }
+}
+
+pub fn method_name(method: Method) -> &'static str {
+    match method {
+        Method::Admm => "admm",
+        Method::Ipm => "ipm",
+        Method::ActiveSet => "active-set",
+        Method::Simplex => "simplex",
+        Method::Pdhg => "pdhg",
+        Method::Auto => "auto",
+    }
+}
+
+pub fn discover(dir: &Path, extensions: &[&str]) -> Result<Vec<Instance>> {
+    let mut instances = Vec::new();
+    for entry in fs::read_dir(dir).with_context(|| format!("failed to list {:?}", dir))? {
+        let path = entry?.path();
+        let matches = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| extensions.iter().any(|want| ext.eq_ignore_ascii_case(want)))
+            .unwrap_or(false);
+        if !matches {
+            continue;
+        }
+        let name = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or_default()
+            .to_ascii_uppercase();
+        instances.push(Instance { name, path });
+    }
+    instances.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(instances)
+}
+
+pub fn read_references<P: AsRef<Path>>(path: P) -> Result<HashMap<String, Scalar>> {
+    let path = path.as_ref();
+    let contents =
+        fs::read_to_string(path).with_context(|| format!("failed to read {:?}", path))?;
+    parse_references(&contents).with_context(|| format!("invalid references in {:?}", path))
+}
+
+pub fn parse_references(contents: &str) -> Result<HashMap<String, Scalar>> {
+    let mut references = HashMap::new();
+    for (number, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let mut fields = line.split(',').map(str::trim);
+        let (Some(name), Some(value)) = (fields.next(), fields.next()) else {
+            bail!("line {}: expected name,objective", number + 1);
+        };
+        match value.parse::<Scalar>() {
+            Ok(value) => {
+                references.insert(name.to_ascii_uppercase(), value);
+            }
+            Err(_) if name == "name" => continue,
+            Err(_) => bail!("line {}: invalid objective {}", number + 1, value),
+        }
+    }
+    Ok(references)
+}
+
+pub fn read_expected<P: AsRef<Path>>(path: P) -> Result<Vec<(String, String)>> {
+    let path = path.as_ref();
+    let contents =
+        fs::read_to_string(path).with_context(|| format!("failed to read {:?}", path))?;
+    let mut expected = Vec::new();
+    for line in contents.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let Some((solver, instance)) = line.split_once(',') else {
+            bail!("{:?}: expected solver,instance but found {}", path, line);
+        };
+        expected.push((
+            solver.trim().to_owned(),
+            instance.trim().to_ascii_uppercase(),
+        ));
+    }
+    Ok(expected)
+}
+
+pub fn write_expected<P: AsRef<Path>>(path: P, records: &[RunRecord]) -> Result<()> {
+    let path = path.as_ref();
+    let mut contents = String::from("# solver,instance pairs that must keep solving\n");
+    for record in records.iter().filter(|r| r.outcome == Outcome::Solved) {
+        contents.push_str(&format!("{},{}\n", record.solver, record.instance));
+    }
+    fs::write(path, contents).with_context(|| format!("failed to write {:?}", path))
+}
+
+// Drops expected pairs whose instance has no record, e.g. ones a `--filter`
+// left out: they never ran, so they cannot have regressed.
+pub fn expected_runs(
+    mut expected: Vec<(String, String)>,
+    records: &[RunRecord],
+) -> Vec<(String, String)> {
+    expected.retain(|(_, instance)| records.iter().any(|r| r.instance == *instance));
+    expected
+}
+
+pub fn regressions<'a>(
+    expected: &'a [(String, String)],
+    records: &[RunRecord],
+) -> Vec<&'a (String, String)> {
+    expected
+        .iter()
+        .filter(|(solver, instance)| {
+            !records.iter().any(|r| {
+                r.solver == *solver && r.instance == *instance && r.outcome == Outcome::Solved
+            })
+        })
+        .collect()
+}
+
+pub fn read_baseline<P: AsRef<Path>>(path: P) -> Result<Vec<RunRecord>> {
+    let path = path.as_ref();
+    let contents =
+        fs::read_to_string(path).with_context(|| format!("failed to read {:?}", path))?;
+    let mut records = Vec::new();
+    for (number, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || (number == 0 && line.starts_with("solver")) {
+            continue;
+        }
+        let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+        let [solver, instance, seconds, solved] = fields[..] else {
+            bail!(
+                "{:?} line {}: expected solver,instance,seconds,solved",
+                path,
+                number + 1
+            );
+        };
+        let seconds = seconds
+            .parse::<f64>()
+            .map_err(|_| anyhow!("{:?} line {}: invalid time", path, number + 1))?;
+        let solved = matches!(solved, "1" | "true" | "solved");
+        records.push(RunRecord {
+            instance: instance.to_ascii_uppercase(),
+            solver: solver.to_owned(),
+            outcome: if solved {
+                Outcome::Solved
+            } else {
+                Outcome::Failed
+            },
+            status: None,
+            objective: None,
+            reference: None,
+            seconds,
+            iterations: 0,
+            message: String::from("baseline"),
+        });
+    }
+    Ok(records)
+}
+
+pub fn run_instance(
+    instance: &Instance,
+    method: Method,
+    reference: Option<Scalar>,
+    config: &SuiteConfig,
+) -> RunRecord {
+    let solver_name = method_name(method);
+    let model = match read_mps_problem(&instance.path) {
+        Ok(model) => model,
+        Err(err) => return RunRecord::new(instance, solver_name, Outcome::Failed, err.to_string()),
+    };
+    let mut solver = Solver::<Scalar>::new()
+        .method(method)
+        .options(config.options.clone());
+    let estimate = match &model.problem {
+        JsonProblem::Qp { problem } => solver.estimate_qp(problem),
+        JsonProblem::Lp { problem } => solver.estimate_lp(problem),
+    };
+    match estimate {
+        Ok(estimate) if estimate.exceeds(config.max_memory_bytes) => {
+            return RunRecord::new(
+                instance,
+                solver_name,
+                Outcome::Skipped,
+                format!("needs about {:.0} MB", estimate.megabytes()),
+            );
+        }
+        Ok(_) => {}
+        Err(err) => return RunRecord::new(instance, solver_name, Outcome::Failed, err.to_string()),
+    }
+
+    let start = Instant::now();
+    let result = match model.problem {
+        JsonProblem::Qp { problem } => solver.solve_qp(problem),
+        JsonProblem::Lp { problem } => solver.solve_lp(problem),
+    };
+    let seconds = start.elapsed().as_secs_f64();
+    let solution = match result {
+        Ok(solution) => solution,
+        Err(err) => {
+            let mut record =
+                RunRecord::new(instance, solver_name, Outcome::Failed, err.to_string());
+            record.seconds = seconds;
+            return record;
+        }
+    };
+
+    let objective = solution.objective_value;
+    let accurate = reference
+        .map(|reference| {
+            (objective - reference).abs() <= config.objective_tolerance * reference.abs().max(1.0)
+        })
+        .unwrap_or(true);
+    let outcome = match (solution.status, accurate) {
+        (Status::Optimal, true) => Outcome::Solved,
+        (Status::Optimal, false) => Outcome::Inaccurate,
+        _ => Outcome::Failed,
+    };
+    RunRecord {
+        instance: instance.name.clone(),
+        solver: solver_name.to_owned(),
+        outcome,
+        status: Some(solution.status),
+        objective: Some(objective),
+        reference,
+        seconds,
+        iterations: solution.iterations,
+        message: String::new(),
+    }
+}
+
+pub fn run_suite(
+    instances: &[Instance],
+    methods: &[Method],
+    references: &HashMap<String, Scalar>,
+    config: &SuiteConfig,
+) -> Vec<RunRecord> {
+    let mut records = Vec::with_capacity(instances.len() * methods.len());
+    for instance in instances {
+        for &method in methods {
+            let record = run_instance(
+                instance,
+                method,
+                references.get(&instance.name).copied(),
+                config,
+            );
+            println!(
+                "{:<12} {:<6} {:<10} {:>10.3}s {}",
+                record.instance,
+                record.solver,
+                record.outcome.as_str(),
+                record.seconds,
+                record.message
+            );
+            records.push(record);
+        }
+    }
+    records
+}
+
+pub fn solve_counts(records: &[RunRecord]) -> Vec<(String, usize, usize)> {
+    let mut counts: Vec<(String, usize, usize)> = Vec::new();
+    for record in records {
+        let idx = match counts
+            .iter()
+            .position(|(solver, _, _)| *solver == record.solver)
+        {
+            Some(idx) => idx,
+            None => {
+                counts.push((record.solver.clone(), 0, 0));
+                counts.len() - 1
+            }
+        };
+        counts[idx].2 += 1;
+        if record.outcome == Outcome::Solved {
+            counts[idx].1 += 1;
+        }
+    }
+    counts
+}
+
+pub fn write_records<P: AsRef<Path>>(path: P, records: &[RunRecord]) -> Result<()> {
+    let path = path.as_ref();
+    let file = File::create(path).with_context(|| format!("failed to create {:?}", path))?;
+    let mut writer = BufWriter::new(file);
+    writeln!(
+        writer,
+        "instance,solver,outcome,status,objective,reference,seconds,iterations,message"
+    )?;
+    for record in records {
+        writeln!(
+            writer,
+            "{},{},{},{},{},{},{:.6},{},\"{}\"",
+            record.instance,
+            record.solver,
+            record.outcome.as_str(),
+            record
+                .status
+                .map(|status| format!("{:?}", status))
+                .unwrap_or_default(),
+            record
+                .objective
+                .map(|value| value.to_string())
+                .unwrap_or_default(),
+            record
+                .reference
+                .map(|value| value.to_string())
+                .unwrap_or_default(),
+            record.seconds,
+            record.iterations,
+            record.message.replace('"', "'")
+        )?;
+    }
+    writer
+        .flush()
+        .with_context(|| format!("failed to write {:?}", path))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn filtered_runs_only_answer_for_their_instances() {
+        let instance = Instance {
+            name: String::from("AFIRO"),
+            path: PathBuf::from("AFIRO.SIF"),
+        };
+        let records = vec![RunRecord::new(
+            &instance,
+            "admm",
+            Outcome::Solved,
+            String::new(),
+        )];
+        let expected = vec![
+            (String::from("admm"), String::from("AFIRO")),
+            (String::from("admm"), String::from("ADLITTLE")),
+            (String::from("ipm"), String::from("AFIRO")),
+        ];
+        assert_eq!(regressions(&expected, &records).len(), 2);
+        let expected = expected_runs(expected, &records);
+        assert_eq!(expected.len(), 2);
+        assert_eq!(regressions(&expected, &records), [&expected[1]]);
+    }
+}
@@ -0,0 +1,236 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use cvxrs_api::{Method, Solution, Solver, SolverError};
+use cvxrs_core::math::Scalar;
+use cvxrs_core::options::SolveOptions;
+use cvxrs_core::problem::{Bounds, CscMatrix, Names, ObjectiveSense, ProblemQP};
+use cvxrs_io::JsonProblem;
+use serde::Deserialize;
+
+const METHODS: [Method; 5] = [
+    Method::Admm,
+    Method::Ipm,
+    Method::ActiveSet,
+    Method::Simplex,
+    Method::Pdhg,
+];
+const OBJECTIVE_TOLERANCE: Scalar = 1e-4;
+const PRIMAL_TOLERANCE: Scalar = 1e-3;
+const RESIDUAL_TOLERANCE: Scalar = 1e-4;
+
+// Reference multipliers follow P x + q + C'y_eq + G'y_ineq + y_bound = 0, with
+// y_ineq >= 0 and y_bound positive on active upper bounds, negative on lower ones.
+#[derive(Deserialize)]
+struct Reference {
+    objective: Scalar,
+    primal: Vec<Scalar>,
+    equality_dual: Vec<Scalar>,
+    inequality_dual: Vec<Scalar>,
+    bound_dual: Vec<Scalar>,
+}
+
+// `source` says where the reference comes from: "hand-derived" for points
+// whose KKT conditions were solved by hand, or the solver that exported it.
+// Only hand-derived references are bundled so far.
+#[derive(Deserialize)]
+struct Fixture {
+    source: String,
+    problem: JsonProblem,
+    reference: Reference,
+}
+
+fn fixtures() -> Vec<PathBuf> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("data/accuracy");
+    let mut paths: Vec<PathBuf> = fs::read_dir(&dir)
+        .expect("accuracy fixtures")
+        .map(|entry| entry.expect("fixture entry").path())
+        .filter(|path| path.extension().map(|ext| ext == "json").unwrap_or(false))
+        .collect();
+    paths.sort();
+    paths
+}
+
+fn as_qp(problem: &JsonProblem) -> ProblemQP<Scalar> {
+    match problem {
+        JsonProblem::Qp { problem } => problem.clone(),
+        JsonProblem::Lp { problem } => {
+            let n = problem.nvars();
+            ProblemQP {
+                quadratic: CscMatrix {
+                    nrows: n,
+                    ncols: n,
+                    indptr: vec![0; n + 1],
+                    indices: Vec::new(),
+                    data: Vec::new(),
+                },
+                linear: problem.cost.clone(),
+                inequalities: problem.inequalities.clone(),
+                equalities: problem.equalities.clone(),
+                bounds: problem.bounds.clone(),
+                groups: Vec::new(),
+                integrality: Vec::new(),
+                names: Names::default(),
+                sense: ObjectiveSense::Minimize,
+                offset: 0.0,
+                quadratic_constraints: Vec::new(),
+            }
+        }
+    }
+}
+
+fn solve(method: Method, problem: &JsonProblem) -> Result<Solution<Scalar>, SolverError> {
+    let mut solver = Solver::<Scalar>::new()
+        .method(method)
+        .options(SolveOptions::default());
+    match problem {
+        JsonProblem::Qp { problem } => solver.solve_qp(problem.clone()),
+        JsonProblem::Lp { problem } => solver.solve_lp(problem.clone()),
+    }
+}
+
+fn multiply(matrix: &CscMatrix<Scalar>, x: &[Scalar]) -> Vec<Scalar> {
+    let mut out = vec![0.0; matrix.nrows];
+    for (col, value) in x.iter().enumerate().take(matrix.ncols) {
+        for idx in matrix.indptr[col]..matrix.indptr[col + 1] {
+            out[matrix.indices[idx]] += matrix.data[idx] * value;
+        }
+    }
+    out
+}
+
+fn multiply_transpose(matrix: &CscMatrix<Scalar>, y: &[Scalar], out: &mut [Scalar]) {
+    for (col, value) in out.iter_mut().enumerate().take(matrix.ncols) {
+        for idx in matrix.indptr[col]..matrix.indptr[col + 1] {
+            *value += matrix.data[idx] * y[matrix.indices[idx]];
+        }
+    }
+}
+
+fn objective(problem: &ProblemQP<Scalar>, x: &[Scalar]) -> Scalar {
+    let px = multiply(&problem.quadratic, x);
+    x.iter()
+        .zip(px.iter().zip(&problem.linear))
+        .map(|(x, (px, q))| x * (0.5 * px + q))
+        .sum()
+}
+
+fn primal_residual(problem: &ProblemQP<Scalar>, x: &[Scalar]) -> Scalar {
+    let mut residual: Scalar = 0.0;
+    if let Some(eq) = &problem.equalities {
+        for (ax, b) in multiply(&eq.matrix, x).iter().zip(&eq.rhs) {
+            residual = residual.max((ax - b).abs());
+        }
+    }
+    if let Some(ineq) = &problem.inequalities {
+        for (gx, h) in multiply(&ineq.matrix, x).iter().zip(&ineq.rhs) {
+            residual = residual.max(gx - h);
+        }
+    }
+    if let Some(Bounds { lower, upper }) = &problem.bounds {
+        for ((x, l), u) in x.iter().zip(lower).zip(upper) {
+            residual = residual.max(l - x).max(x - u);
+        }
+    }
+    residual
+}
+
+// Stationarity of the Lagrangian at the computed primal, using the reference
+// multipliers: it vanishes only when x satisfies the reference KKT system.
+fn stationarity_residual(
+    problem: &ProblemQP<Scalar>,
+    x: &[Scalar],
+    reference: &Reference,
+) -> Scalar {
+    let mut gradient = multiply(&problem.quadratic, x);
+    for (g, q) in gradient.iter_mut().zip(&problem.linear) {
+        *g += q;
+    }
+    if let Some(eq) = &problem.equalities {
+        multiply_transpose(&eq.matrix, &reference.equality_dual, &mut gradient);
+    }
+    if let Some(ineq) = &problem.inequalities {
+        multiply_transpose(&ineq.matrix, &reference.inequality_dual, &mut gradient);
+    }
+    for (g, y) in gradient.iter_mut().zip(&reference.bound_dual) {
+        *g += y;
+    }
+    gradient.iter().fold(0.0, |acc: Scalar, g| acc.max(g.abs()))
+}
+
+fn check(
+    name: &str,
+    method: Method,
+    problem: &ProblemQP<Scalar>,
+    reference: &Reference,
+    solution: &Solution<Scalar>,
+) -> Vec<String> {
+    let mut failures = Vec::new();
+    let label = format!("{} ({:?})", name, method);
+    if solution.status != cvxrs_api::Status::Optimal {
+        failures.push(format!("{}: status {:?}", label, solution.status));
+    }
+    let x = &solution.primal;
+    let value = objective(problem, x);
+    let objective_error = (value - reference.objective).abs() / reference.objective.abs().max(1.0);
+    if objective_error > OBJECTIVE_TOLERANCE {
+        failures.push(format!(
+            "{}: objective {} but reference is {}",
+            label, value, reference.objective
+        ));
+    }
+    let distance = x
+        .iter()
+        .zip(&reference.primal)
+        .fold(0.0, |acc: Scalar, (x, r)| acc.max((x - r).abs()));
+    if distance > PRIMAL_TOLERANCE {
+        failures.push(format!(
+            "{}: primal {:?} is {:.2e} away from reference {:?}",
+            label, x, distance, reference.primal
+        ));
+    }
+    let primal = primal_residual(problem, x);
+    if primal > RESIDUAL_TOLERANCE {
+        failures.push(format!("{}: primal residual {:.2e}", label, primal));
+    }
+    let dual = stationarity_residual(problem, x, reference);
+    if dual > RESIDUAL_TOLERANCE {
+        failures.push(format!("{}: stationarity residual {:.2e}", label, dual));
+    }
+    failures
+}
+
+#[test]
+#[cfg_attr(feature = "f32", ignore = "needs f64 accuracy")]
+fn bundled_references_agree() {
+    let paths = fixtures();
+    assert!(!paths.is_empty(), "no accuracy fixtures bundled");
+    let mut failures = Vec::new();
+    let mut solved = 0;
+    for path in &paths {
+        let name = path.file_stem().unwrap().to_string_lossy().into_owned();
+        let contents = fs::read_to_string(path).expect("read fixture");
+        let fixture: Fixture = serde_json::from_str(&contents)
+            .unwrap_or_else(|err| panic!("{}: invalid fixture: {}", name, err));
+        assert!(!fixture.source.is_empty(), "{}: missing source", name);
+        let problem = as_qp(&fixture.problem);
+        for method in METHODS {
+            match solve(method, &fixture.problem) {
+                Ok(solution) => {
+                    solved += 1;
+                    failures.extend(check(
+                        &name,
+                        method,
+                        &problem,
+                        &fixture.reference,
+                        &solution,
+                    ));
+                }
+                Err(SolverError::Unsupported(_)) => {}
+                Err(err) => failures.push(format!("{} ({:?}): {}", name, method, err)),
+            }
+        }
+    }
+    assert!(solved > 0, "no method solved any fixture");
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
@@ -0,0 +1,410 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use anyhow::{bail, Result};
+use cvxrs_core::math::Scalar;
+use cvxrs_core::problem::{Bounds, CscMatrix, Names, ObjectiveSense};
+use cvxrs_core::structure::ConstraintRow;
+use cvxrs_io::JsonProblem;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Block {
+    Equality,
+    Inequality,
+}
+
+impl Block {
+    fn name(self) -> &'static str {
+        match self {
+            Block::Equality => "eq",
+            Block::Inequality => "ineq",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct RowRef {
+    pub block: Block,
+    pub index: usize,
+}
+
+// Rows are named eq:<i> and ineq:<i>, matching the order in the problem file.
+pub fn parse_row(text: &str) -> Result<RowRef, String> {
+    let (block, index) = text
+        .split_once(':')
+        .ok_or_else(|| format!("expected eq:<index> or ineq:<index>, got '{}'", text))?;
+    let block = match block {
+        "eq" => Block::Equality,
+        "ineq" => Block::Inequality,
+        other => return Err(format!("unknown row block '{}'", other)),
+    };
+    let index = index
+        .parse()
+        .map_err(|_| format!("invalid row index '{}'", index))?;
+    Ok(RowRef { block, index })
+}
+
+pub struct Filter {
+    pub rows: Vec<RowRef>,
+    pub vars: Vec<usize>,
+    pub max_rows: usize,
+    pub max_terms: usize,
+}
+
+impl Filter {
+    fn is_empty(&self) -> bool {
+        self.rows.is_empty() && self.vars.is_empty()
+    }
+}
+
+struct Parts<'a> {
+    kind: &'static str,
+    quadratic: Option<&'a CscMatrix<Scalar>>,
+    linear: &'a [Scalar],
+    sense: ObjectiveSense,
+    equalities: Option<(&'a CscMatrix<Scalar>, &'a [Scalar])>,
+    inequalities: Option<(&'a CscMatrix<Scalar>, &'a [Scalar])>,
+    bounds: Option<&'a Bounds<Scalar>>,
+    names: &'a Names,
+}
+
+impl<'a> Parts<'a> {
+    fn new(problem: &'a JsonProblem) -> Self {
+        match problem {
+            JsonProblem::Qp { problem } => Self {
+                kind: "QP",
+                quadratic: Some(&problem.quadratic),
+                linear: &problem.linear,
+                sense: problem.sense,
+                equalities: problem
+                    .equalities
+                    .as_ref()
+                    .map(|eq| (&eq.matrix, eq.rhs.as_slice())),
+                inequalities: problem
+                    .inequalities
+                    .as_ref()
+                    .map(|ineq| (&ineq.matrix, ineq.rhs.as_slice())),
+                bounds: problem.bounds.as_ref(),
+                names: &problem.names,
+            },
+            JsonProblem::Lp { problem } => Self {
+                kind: "LP",
+                quadratic: None,
+                linear: &problem.cost,
+                sense: problem.sense,
+                equalities: problem
+                    .equalities
+                    .as_ref()
+                    .map(|eq| (&eq.matrix, eq.rhs.as_slice())),
+                inequalities: problem
+                    .inequalities
+                    .as_ref()
+                    .map(|ineq| (&ineq.matrix, ineq.rhs.as_slice())),
+                bounds: problem.bounds.as_ref(),
+                names: &problem.names,
+            },
+        }
+    }
+
+    fn block(&self, block: Block) -> Option<(&'a CscMatrix<Scalar>, &'a [Scalar])> {
+        match block {
+            Block::Equality => self.equalities,
+            Block::Inequality => self.inequalities,
+        }
+    }
+}
+
+pub fn explain(problem: &JsonProblem, filter: &Filter) -> Result<String> {
+    let parts = Parts::new(problem);
+    let n = parts.linear.len();
+    for &var in &filter.vars {
+        if var >= n {
+            bail!("variable x[{}] is out of range (problem has {})", var, n);
+        }
+    }
+    for row in &filter.rows {
+        let rows = parts
+            .block(row.block)
+            .map(|(_, rhs)| rhs.len())
+            .unwrap_or(0);
+        if row.index >= rows {
+            bail!(
+                "row {} is out of range (block has {} rows)",
+                row_label(row.block, row.index),
+                rows
+            );
+        }
+    }
+
+    let mut out = String::new();
+    let rows = |block| parts.block(block).map(|(_, rhs)| rhs.len()).unwrap_or(0);
+    let _ = writeln!(
+        out,
+        "{} with {} variables, {} equality rows, {} inequality rows",
+        parts.kind,
+        n,
+        rows(Block::Equality),
+        rows(Block::Inequality)
+    );
+
+    out.push_str(match parts.sense {
+        ObjectiveSense::Minimize => "\nminimize\n  ",
+        ObjectiveSense::Maximize => "\nmaximize\n  ",
+    });
+    out.push_str(&objective(&parts, filter));
+    out.push('\n');
+
+    let mut constraints = String::new();
+    for block in [Block::Equality, Block::Inequality] {
+        if let Some((matrix, rhs)) = parts.block(block) {
+            constraint_block(&mut constraints, block, matrix, rhs, parts.names, filter);
+        }
+    }
+    if !constraints.is_empty() {
+        out.push_str("\nsubject to\n");
+        out.push_str(&constraints);
+    }
+
+    if let Some(bounds) = parts.bounds {
+        let bounds = bound_lines(bounds, parts.names, filter);
+        if !bounds.is_empty() {
+            out.push_str("\nbounds\n");
+            out.push_str(&bounds);
+        }
+    }
+    Ok(out)
+}
+
+fn objective(parts: &Parts, filter: &Filter) -> String {
+    let keep = |var: usize| filter.vars.is_empty() || filter.vars.contains(&var);
+    let label = |var| parts.names.variable_label(var);
+    let mut terms = Vec::new();
+    if let Some(quadratic) = parts.quadratic {
+        let mut products: BTreeMap<(usize, usize), Scalar> = BTreeMap::new();
+        for (row, col, value) in entries(quadratic) {
+            *products.entry((row.min(col), row.max(col))).or_insert(0.0) += 0.5 * value;
+        }
+        for ((i, j), value) in products {
+            if !keep(i) && !keep(j) {
+                continue;
+            }
+            let monomial = if i == j {
+                format!("{}^2", label(i))
+            } else {
+                format!("{}*{}", label(i), label(j))
+            };
+            terms.push((value, monomial));
+        }
+    }
+    for (var, &value) in parts.linear.iter().enumerate() {
+        if keep(var) {
+            terms.push((value, label(var)));
+        }
+    }
+    let limit = if filter.vars.is_empty() {
+        filter.max_terms
+    } else {
+        usize::MAX
+    };
+    expression(&terms, limit)
+}
+
+fn constraint_block(
+    out: &mut String,
+    block: Block,
+    matrix: &CscMatrix<Scalar>,
+    rhs: &[Scalar],
+    names: &Names,
+    filter: &Filter,
+) {
+    let mut rows: Vec<Vec<(Scalar, String)>> = vec![Vec::new(); matrix.nrows];
+    for (row, col, value) in entries(matrix) {
+        rows[row].push((value, names.variable_label(col)));
+    }
+    let relation = match block {
+        Block::Equality => "=",
+        Block::Inequality => "<=",
+    };
+    let selected: Vec<usize> = if filter.is_empty() {
+        (0..rows.len()).collect()
+    } else {
+        // Filtered rows are the ones asked for plus every row touching a
+        // selected variable, always printed in full.
+        let mut touched = vec![false; rows.len()];
+        for &var in &filter.vars {
+            for idx in matrix.indptr[var]..matrix.indptr[var + 1] {
+                touched[matrix.indices[idx]] = true;
+            }
+        }
+        (0..rows.len())
+            .filter(|&row| touched[row] || filter.rows.contains(&RowRef { block, index: row }))
+            .collect()
+    };
+    let (limit, max_terms) = if filter.is_empty() {
+        (filter.max_rows, filter.max_terms)
+    } else {
+        (usize::MAX, usize::MAX)
+    };
+    for &row in selected.iter().take(limit) {
+        let _ = writeln!(
+            out,
+            "  {:<10} {} {} {}",
+            format!("{}:", named_row_label(names, block, row)),
+            expression(&rows[row], max_terms),
+            relation,
+            rhs[row]
+        );
+    }
+    if selected.len() > limit {
+        let _ = writeln!(
+            out,
+            "  ... {} more {} rows (use --row to show them)",
+            selected.len() - limit,
+            block.name()
+        );
+    }
+}
+
+fn bound_lines(bounds: &Bounds<Scalar>, names: &Names, filter: &Filter) -> String {
+    let mut out = String::new();
+    let mut free = 0;
+    let mut shown = 0;
+    let mut hidden = 0;
+    for (var, (&lower, &upper)) in bounds.lower.iter().zip(&bounds.upper).enumerate() {
+        if filter.vars.is_empty() {
+            if !lower.is_finite() && !upper.is_finite() {
+                free += 1;
+                continue;
+            }
+            if shown >= filter.max_rows {
+                hidden += 1;
+                continue;
+            }
+        } else if !filter.vars.contains(&var) {
+            continue;
+        }
+        shown += 1;
+        let var = names.variable_label(var);
+        let line = match (lower.is_finite(), upper.is_finite()) {
+            (false, false) => format!("{} free", var),
+            _ if lower == upper => format!("{} = {}", var, lower),
+            (true, false) => format!("{} >= {}", var, lower),
+            (false, true) => format!("{} <= {}", var, upper),
+            (true, true) => format!("{} <= {} <= {}", lower, var, upper),
+        };
+        let _ = writeln!(out, "  {}", line);
+    }
+    if hidden > 0 {
+        let _ = writeln!(out, "  ... {} more bounded variables", hidden);
+    }
+    if free > 0 && filter.vars.is_empty() {
+        let _ = writeln!(out, "  {} free variables", free);
+    }
+    out
+}
+
+fn expression(terms: &[(Scalar, String)], limit: usize) -> String {
+    let mut out = String::new();
+    for (value, monomial) in terms.iter().filter(|(value, _)| *value != 0.0).take(limit) {
+        let magnitude = value.abs();
+        match (out.is_empty(), *value < 0.0) {
+            (true, false) => {}
+            (true, true) => out.push('-'),
+            (false, false) => out.push_str(" + "),
+            (false, true) => out.push_str(" - "),
+        }
+        if magnitude != 1.0 {
+            let _ = write!(out, "{} ", magnitude);
+        }
+        out.push_str(monomial);
+    }
+    let nonzero = terms.iter().filter(|(value, _)| *value != 0.0).count();
+    if nonzero > limit {
+        let _ = write!(out, " + ... ({} more)", nonzero - limit);
+    }
+    if out.is_empty() {
+        out.push('0');
+    }
+    out
+}
+
+fn entries(matrix: &CscMatrix<Scalar>) -> impl Iterator<Item = (usize, usize, Scalar)> + '_ {
+    (0..matrix.ncols).flat_map(move |col| {
+        (matrix.indptr[col]..matrix.indptr[col + 1])
+            .map(move |idx| (matrix.indices[idx], col, matrix.data[idx]))
+    })
+}
+
+fn row_label(block: Block, index: usize) -> String {
+    format!("{}:{}", block.name(), index)
+}
+
+// The row's name when the problem gives one, otherwise its eq:<i> or
+// ineq:<i> label.
+fn named_row_label(names: &Names, block: Block, index: usize) -> String {
+    let row = match block {
+        Block::Equality => ConstraintRow::Equality(index),
+        Block::Inequality => ConstraintRow::Inequality(index),
+    };
+    match names.row(row) {
+        Some(name) => name.to_owned(),
+        None => row_label(block, index),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cvxrs_core::problem::{InequalityConstraints, Names, ProblemLP};
+
+    fn problem() -> JsonProblem {
+        JsonProblem::Lp {
+            problem: ProblemLP {
+                cost: vec![1.0, -2.0, 0.5],
+                inequalities: Some(InequalityConstraints {
+                    matrix: CscMatrix {
+                        nrows: 3,
+                        ncols: 3,
+                        indptr: vec![0, 2, 3, 5],
+                        indices: vec![0, 1, 1, 0, 2],
+                        data: vec![1.0, 1.0, -1.0, 3.0, 1.0],
+                    },
+                    rhs: vec![4.0, 0.0, 2.0],
+                }),
+                equalities: None,
+                bounds: None,
+                groups: Vec::new(),
+                integrality: Vec::new(),
+                names: Names::default(),
+                sense: ObjectiveSense::Minimize,
+                offset: 0.0,
+            },
+        }
+    }
+
+    #[test]
+    fn truncates_and_filters_rows() {
+        let filter = Filter {
+            rows: Vec::new(),
+            vars: Vec::new(),
+            max_rows: 2,
+            max_terms: 1,
+        };
+        let text = explain(&problem(), &filter).unwrap();
+        assert!(text.contains("  x[0] + ... (2 more)\n"));
+        assert!(text.contains("ineq:0:    x[0] + ... (1 more) <= 4\n"));
+        assert!(text.contains("  ... 1 more ineq rows"));
+
+        let filter = Filter {
+            rows: vec![parse_row("ineq:2").unwrap()],
+            vars: vec![1],
+            ..filter
+        };
+        let text = explain(&problem(), &filter).unwrap();
+        assert!(text.contains("  -2 x[1]\n"));
+        assert!(text.contains("ineq:1:    x[0] - x[1] <= 0\n"));
+        assert!(text.contains("ineq:2:    x[2] <= 2\n"));
+        assert!(!text.contains("ineq:0:"));
+        assert!(parse_row("row:1").is_err());
+    }
+}
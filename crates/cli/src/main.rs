@@ -1,15 +1,28 @@
 #![forbid(unsafe_code)]
 
-use anyhow::{Context, Result};
-use clap::{Parser, Subcommand, ValueEnum};
-use cvxrs_api::{Method, Solver};
+mod explain;
+#[cfg(feature = "serve")]
+mod serve;
+
+use anyhow::{bail, Context, Result};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use cvxrs_api::{
+    group_report_lp, group_report_qp, CheckpointSink, GroupReport, IterationSink, Method, Names,
+    Solver, Status,
+};
 use cvxrs_core::math::Scalar;
-use cvxrs_core::options::SolveOptions;
-use cvxrs_core::solution::Solution;
-use cvxrs_io::{read_json_problem, write_solution, JsonProblem};
-use serde_json;
+use cvxrs_core::options::{Scaling, SolveOptions};
+use cvxrs_core::solution::{Certificate, Solution};
+use cvxrs_io::{
+    problem_fingerprint, read_checkpoint, read_json_problem_with, read_mps_problem_with,
+    read_tabular_problem_with, to_latex, write_checkpoint, write_solution, Checkpoint, JsonProblem,
+    ParseOptions,
+};
+use explain::{Filter, RowRef};
+use std::fs::File;
 use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use tracing_subscriber::EnvFilter;
 
@@ -19,6 +32,9 @@ use tracing_subscriber::EnvFilter;
 struct Cli {
     #[arg(long)]
     log_json: bool,
+    // Reject problems that would otherwise be repaired while parsing.
+    #[arg(long, global = true)]
+    strict: bool,
     #[command(subcommand)]
     command: Commands,
 }
@@ -30,8 +46,8 @@ enum Commands {
         problem: PathBuf,
         #[arg(long, default_value = "admm")]
         method: MethodArg,
-        #[arg(long)]
-        tol: Option<f64>,
+        #[command(flatten)]
+        tolerances: ToleranceArgs,
         #[arg(long)]
         max_iters: Option<usize>,
         #[arg(long)]
@@ -40,18 +56,114 @@ enum Commands {
         output: Option<PathBuf>,
         #[arg(long)]
         log_json: bool,
+        // Re-solves the active constraints exactly after ADMM converges.
+        #[arg(long)]
+        polish: bool,
+        // Moves an ADMM LP solution to an optimal vertex with the simplex.
+        #[arg(long)]
+        crossover: bool,
+        // Removes fixed variables and redundant rows and tightens bounds
+        // before the solve, and restores the original problem after.
+        #[arg(long)]
+        presolve: bool,
+        // How ADMM equilibrates the problem before iterating.
+        #[arg(long, default_value = "ruiz")]
+        scaling: ScalingArg,
+        // Streams one JSON object per iteration to this file, or stderr for "-".
+        #[arg(long)]
+        log_iterations: Option<PathBuf>,
+        #[command(flatten)]
+        checkpoint: CheckpointArgs,
     },
     Check {
         #[arg(long)]
         problem: PathBuf,
     },
+    Explain {
+        #[arg(long)]
+        problem: PathBuf,
+        #[arg(long, value_parser = explain::parse_row)]
+        row: Vec<RowRef>,
+        #[arg(long)]
+        var: Vec<usize>,
+        #[arg(long, default_value_t = 20)]
+        max_rows: usize,
+        #[arg(long, default_value_t = 8)]
+        max_terms: usize,
+        #[arg(long)]
+        latex: bool,
+    },
+    #[cfg(feature = "serve")]
+    Serve {
+        #[arg(long, default_value = "127.0.0.1:8080")]
+        addr: String,
+        #[arg(long, default_value_t = 2)]
+        workers: usize,
+        #[arg(long, default_value = "admm")]
+        method: MethodArg,
+        #[command(flatten)]
+        tolerances: ToleranceArgs,
+        #[arg(long)]
+        max_iters: Option<usize>,
+        #[arg(long)]
+        time_limit: Option<u64>,
+    },
     Bench {},
 }
 
+#[derive(Args)]
+struct ToleranceArgs {
+    // Sets both the absolute and the relative tolerance.
+    #[arg(long)]
+    tol: Option<f64>,
+    #[arg(long)]
+    eps_abs: Option<f64>,
+    #[arg(long)]
+    eps_rel: Option<f64>,
+    #[arg(long)]
+    eps_prim_inf: Option<f64>,
+    #[arg(long)]
+    eps_dual_inf: Option<f64>,
+}
+
+#[derive(Args)]
+struct CheckpointArgs {
+    // Periodically saves the solver state so a preempted solve can resume.
+    #[arg(long)]
+    checkpoint: Option<PathBuf>,
+    #[arg(long, default_value_t = 60, requires = "checkpoint")]
+    checkpoint_every: u64,
+    #[arg(long)]
+    resume: Option<PathBuf>,
+}
+
 #[derive(Clone, Copy, ValueEnum)]
 enum MethodArg {
     Admm,
     Ipm,
+    ActiveSet,
+    Simplex,
+    Pdhg,
+    Auto,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ScalingArg {
+    Ruiz,
+    Geometric,
+    ModifiedRuiz,
+    None,
+}
+
+impl From<ScalingArg> for Scaling {
+    fn from(arg: ScalingArg) -> Scaling {
+        match arg {
+            ScalingArg::Ruiz => Scaling::Ruiz,
+            ScalingArg::Geometric => Scaling::Geometric,
+            ScalingArg::ModifiedRuiz => Scaling::ModifiedRuiz,
+            ScalingArg::None => Scaling::None,
+        }
+    }
 }
 
 impl From<MethodArg> for Method {
@@ -59,6 +171,10 @@ impl From<MethodArg> for Method {
         match arg {
             MethodArg::Admm => Method::Admm,
             MethodArg::Ipm => Method::Ipm,
+            MethodArg::ActiveSet => Method::ActiveSet,
+            MethodArg::Simplex => Method::Simplex,
+            MethodArg::Pdhg => Method::Pdhg,
+            MethodArg::Auto => Method::Auto,
         }
     }
 }
@@ -66,25 +182,75 @@ impl From<MethodArg> for Method {
 fn main() -> Result<()> {
     let cli = Cli::parse();
     initialize_tracing(cli.log_json)?;
+    let parse = if cli.strict {
+        ParseOptions::strict()
+    } else {
+        ParseOptions::lenient()
+    };
     match cli.command {
         Commands::Solve {
             problem,
             method,
-            tol,
+            tolerances,
             max_iters,
             time_limit,
             output,
             log_json,
+            polish,
+            crossover,
+            presolve,
+            scaling,
+            log_iterations,
+            checkpoint,
         } => solve_command(
             problem,
+            &parse,
             method.into(),
-            tol,
-            max_iters,
-            time_limit,
+            SolveOptions {
+                polish,
+                crossover,
+                presolve,
+                scaling: scaling.into(),
+                ..solve_options(&tolerances, max_iters, time_limit)
+            },
             output,
             log_json,
+            log_iterations,
+            checkpoint,
         ),
-        Commands::Check { problem } => check_command(problem),
+        Commands::Check { problem } => check_command(problem, &parse),
+        Commands::Explain {
+            problem,
+            row,
+            var,
+            max_rows,
+            max_terms,
+            latex,
+        } => explain_command(
+            problem,
+            &parse,
+            Filter {
+                rows: row,
+                vars: var,
+                max_rows,
+                max_terms,
+            },
+            latex,
+        ),
+        #[cfg(feature = "serve")]
+        Commands::Serve {
+            addr,
+            workers,
+            method,
+            tolerances,
+            max_iters,
+            time_limit,
+        } => serve::serve(serve::ServeOptions {
+            addr,
+            workers,
+            method: method.into(),
+            options: solve_options(&tolerances, max_iters, time_limit),
+        }),
         Commands::Bench {} => {
             println!("Benchmarks are available via `cargo bench -p cvxrs-benches`.");
             Ok(())
@@ -108,18 +274,126 @@ fn initialize_tracing(log_json: bool) -> Result<()> {
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 fn solve_command(
     path: PathBuf,
+    parse: &ParseOptions,
     method: Method,
-    tol: Option<f64>,
-    max_iters: Option<usize>,
-    time_limit: Option<u64>,
+    options: SolveOptions<Scalar>,
     output: Option<PathBuf>,
     output_json: bool,
+    log_iterations: Option<PathBuf>,
+    checkpoint: CheckpointArgs,
 ) -> Result<()> {
+    let tolerance = options.eps_abs;
+    let mut solver = Solver::<Scalar>::new().method(method).options(options);
+    if let Some(target) = &log_iterations {
+        solver = solver.log_iterations(iteration_log(target)?);
+    }
+    let problem = read_problem(&path, parse)?;
+    let fingerprint = problem_fingerprint(&problem);
+    if let Some(resume) = &checkpoint.resume {
+        let saved = read_checkpoint(resume)?;
+        if saved.fingerprint != fingerprint {
+            bail!("checkpoint {:?} was saved for a different problem", resume);
+        }
+        if saved.state.method != method {
+            bail!(
+                "checkpoint {:?} was saved by the {:?} method",
+                resume,
+                saved.state.method
+            );
+        }
+        tracing::info!(
+            iteration = saved.state.iteration,
+            "resuming from checkpoint"
+        );
+        solver = solver.resume(saved.state);
+    }
+    if let Some(target) = checkpoint.checkpoint.clone() {
+        let sink: CheckpointSink<Scalar> = Arc::new(move |state| {
+            write_checkpoint(
+                &target,
+                &Checkpoint {
+                    fingerprint,
+                    state: state.clone(),
+                },
+            )
+        });
+        solver = solver.checkpoint(Duration::from_secs(checkpoint.checkpoint_every), sink);
+    }
+
+    let grouped = match &problem {
+        JsonProblem::Qp { problem } => !problem.groups.is_empty(),
+        JsonProblem::Lp { problem } => !problem.groups.is_empty(),
+    }
+    .then(|| problem.clone());
+    let names = match &problem {
+        JsonProblem::Qp { problem } => problem.names.clone(),
+        JsonProblem::Lp { problem } => problem.names.clone(),
+    };
+    let solution = match problem {
+        JsonProblem::Qp { problem } => solver.solve_qp(problem)?,
+        JsonProblem::Lp { problem } => solver.solve_lp(problem)?,
+    };
+    let groups = match &grouped {
+        Some(JsonProblem::Qp { problem }) => group_report_qp(problem, &solution, tolerance),
+        Some(JsonProblem::Lp { problem }) => group_report_lp(problem, &solution, tolerance),
+        None => Vec::new(),
+    };
+    // Only runs stopped by a limit are worth resuming.
+    if let Some(target) = &checkpoint.checkpoint {
+        if !matches!(solution.status, Status::MaxIterations | Status::MaxTime) && target.exists() {
+            std::fs::remove_file(target)
+                .with_context(|| format!("failed to remove checkpoint {:?}", target))?;
+        }
+    }
+    emit_solution(solution, &groups, &names, output, output_json)
+}
+
+// One JSON object per line, flushed as it is written so the log can be tailed
+// while the solve runs.
+fn iteration_log(target: &Path) -> Result<IterationSink<Scalar>> {
+    let writer: Box<dyn Write + Send> = if target == Path::new("-") {
+        Box::new(std::io::stderr())
+    } else {
+        Box::new(
+            File::create(target)
+                .with_context(|| format!("failed to create iteration log {:?}", target))?,
+        )
+    };
+    let writer = Mutex::new(writer);
+    Ok(Arc::new(move |record| {
+        let mut writer = writer.lock().unwrap_or_else(|err| err.into_inner());
+        let written = serde_json::to_writer(&mut *writer, record)
+            .map_err(std::io::Error::from)
+            .and_then(|()| writer.write_all(b"\n"))
+            .and_then(|()| writer.flush());
+        if let Err(err) = written {
+            tracing::warn!(error = %err, "failed to write iteration log");
+        }
+    }))
+}
+
+fn solve_options(
+    tolerances: &ToleranceArgs,
+    max_iters: Option<usize>,
+    time_limit: Option<u64>,
+) -> SolveOptions<Scalar> {
     let mut options = SolveOptions::<Scalar>::default();
-    if let Some(tolerance) = tol {
-        options.tolerance = tolerance as Scalar;
+    if let Some(tolerance) = tolerances.tol {
+        options.eps_abs = tolerance as Scalar;
+        options.eps_rel = tolerance as Scalar;
+    }
+    for (value, target) in [
+        (tolerances.eps_abs, &mut options.eps_abs),
+        (tolerances.eps_rel, &mut options.eps_rel),
+        (tolerances.eps_prim_inf, &mut options.eps_prim_inf),
+        (tolerances.eps_dual_inf, &mut options.eps_dual_inf),
+    ] {
+        if let Some(value) = value {
+            *target = value as Scalar;
+        }
     }
     if let Some(iters) = max_iters {
         options.max_iterations = iters;
@@ -127,37 +401,34 @@ fn solve_command(
     if let Some(limit) = time_limit {
         options.max_time = Some(Duration::from_secs(limit));
     }
+    options
+}
 
+fn read_problem(path: &Path, options: &ParseOptions) -> Result<JsonProblem> {
     let extension = path
         .extension()
         .and_then(|ext| ext.to_str())
         .unwrap_or_default()
         .to_ascii_lowercase();
-
-    let mut solver = Solver::<Scalar>::new().method(method).options(options);
-    match extension.as_str() {
-        "json" => match read_json_problem::<Scalar, _>(&path)? {
-            JsonProblem::Qp { problem } => {
-                let solution = solver.solve_qp(problem)?;
-                emit_solution(solution, output, output_json)?;
-            }
-            JsonProblem::Lp { problem } => {
-                let solution = solver.solve_lp(problem)?;
-                emit_solution(solution, output, output_json)?;
-            }
-        },
+    let (problem, fixes) = match extension.as_str() {
+        "json" => read_json_problem_with(path, options)?,
         "mps" => {
-            anyhow::bail!("MPS parsing is not implemented yet.");
-        }
-        _ => {
-            anyhow::bail!("Unsupported file extension: {}", extension);
+            let (model, fixes) = read_mps_problem_with(path, options)?;
+            (model.problem, fixes)
         }
+        "csv" | "xlsx" | "xlsm" | "xls" | "ods" => read_tabular_problem_with(path, options)?,
+        _ => anyhow::bail!("Unsupported file extension: {}", extension),
+    };
+    for fix in fixes {
+        eprintln!("warning: {fix}");
     }
-    Ok(())
+    Ok(problem)
 }
 
 fn emit_solution(
     solution: Solution<Scalar>,
+    groups: &[GroupReport<Scalar>],
+    names: &Names,
     output: Option<PathBuf>,
     output_json: bool,
 ) -> Result<()> {
@@ -172,6 +443,65 @@ fn emit_solution(
             "status: {:?}\nobjective: {:.6}\niters: {}",
             solution.status, solution.objective_value, solution.iterations
         );
+        if let Some(certificate) = &solution.certificate {
+            let kind = match certificate {
+                Certificate::PrimalInfeasible { .. } => "primal infeasibility",
+                Certificate::DualInfeasible { .. } => "dual infeasibility",
+            };
+            println!("certificate: {kind}");
+        }
+        if let Some(backend) = solution.stats.backend {
+            println!("backend: {backend:?}");
+        }
+        if let Some(polished) = solution.stats.polished {
+            println!("polish: {}", if polished { "applied" } else { "rejected" });
+        }
+        if let Some(iterations) = solution.stats.crossover_iterations {
+            match solution.basis {
+                Some(_) => println!("crossover: vertex after {iterations} iterations"),
+                None => println!("crossover: failed after {iterations} iterations"),
+            }
+        }
+        if let Some(presolve) = &solution.stats.presolve {
+            println!(
+                "presolve: {} fixed variables, {} duplicate and {} redundant rows removed, \
+                 {} bounds tightened",
+                presolve.fixed_variables,
+                presolve.duplicate_rows,
+                presolve.redundant_rows,
+                presolve.tightened_bounds
+            );
+        }
+        if let Some(search) = &solution.stats.branch_and_bound {
+            println!(
+                "branch and bound: {} nodes, best bound {:.6}",
+                search.nodes, search.best_bound
+            );
+        }
+        if !groups.is_empty() {
+            println!("groups:");
+        }
+        for group in groups {
+            let worst = match group.worst_row {
+                Some(row) => format!("{:.3e} at {}", group.worst_violation, names.row_label(row)),
+                None => "-".to_string(),
+            };
+            let dual = group
+                .dual_contribution
+                .map_or("-".to_string(), |value| format!("{value:.6}"));
+            println!(
+                "  {}: rows {}, {}, worst violation {}, dual contribution {}",
+                group.name,
+                group.rows,
+                if group.feasible {
+                    "feasible"
+                } else {
+                    "infeasible"
+                },
+                worst,
+                dual
+            );
+        }
     }
     if let Some(path) = output {
         write_solution(path, &solution)?;
@@ -179,16 +509,26 @@ fn emit_solution(
     Ok(())
 }
 
-fn check_command(path: PathBuf) -> Result<()> {
-    match read_json_problem::<Scalar, _>(&path)? {
-        JsonProblem::Qp { mut problem } => {
+fn check_command(path: PathBuf, parse: &ParseOptions) -> Result<()> {
+    match read_problem(&path, parse)? {
+        JsonProblem::Qp { problem } => {
             problem.validate().context("QP validation failed")?;
             println!("QP validation succeeded.");
         }
-        JsonProblem::Lp { mut problem } => {
+        JsonProblem::Lp { problem } => {
             problem.validate().context("LP validation failed")?;
             println!("LP validation succeeded.");
         }
     }
     Ok(())
 }
+
+fn explain_command(path: PathBuf, parse: &ParseOptions, filter: Filter, latex: bool) -> Result<()> {
+    let problem = read_problem(&path, parse)?;
+    if latex {
+        print!("{}", to_latex(&problem));
+    } else {
+        print!("{}", explain::explain(&problem, &filter)?);
+    }
+    Ok(())
+}
@@ -0,0 +1,157 @@
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use anyhow::{anyhow, Context, Result};
+use cvxrs_api::{Method, Solver, SolverError};
+use cvxrs_core::math::Scalar;
+use cvxrs_core::options::SolveOptions;
+use cvxrs_io::JsonProblem;
+use metrics::gauge;
+use metrics_exporter_prometheus::{Matcher, PrometheusBuilder, PrometheusHandle};
+use tiny_http::{Header, Method as HttpMethod, Request, Response, Server};
+
+const QUEUE_DEPTH: &str = "cvxrs_queue_depth";
+const DURATION_BUCKETS: [f64; 10] = [0.001, 0.005, 0.01, 0.05, 0.1, 0.5, 1.0, 5.0, 10.0, 60.0];
+const ITERATION_BUCKETS: [f64; 8] = [10.0, 25.0, 50.0, 100.0, 250.0, 500.0, 1000.0, 5000.0];
+
+pub struct ServeOptions {
+    pub addr: String,
+    pub workers: usize,
+    pub method: Method,
+    pub options: SolveOptions<Scalar>,
+}
+
+// POST /solve takes a JSON problem (optionally ?method=admm|ipm) and answers
+// with the solution; GET /metrics exposes the solve counters and histograms
+// recorded by cvxrs-api in the Prometheus text format.
+pub fn serve(options: ServeOptions) -> Result<()> {
+    let metrics = install_recorder()?;
+    let server = Server::http(&options.addr)
+        .map_err(|err| anyhow!("failed to listen on {}: {}", options.addr, err))?;
+    tracing::info!(addr = %options.addr, workers = options.workers, "serving");
+
+    let (sender, receiver) = mpsc::channel::<Request>();
+    let receiver = Arc::new(Mutex::new(receiver));
+    let mut workers = Vec::with_capacity(options.workers);
+    for _ in 0..options.workers.max(1) {
+        let receiver = Arc::clone(&receiver);
+        let method = options.method;
+        let solve_options = options.options.clone();
+        workers.push(thread::spawn(move || loop {
+            let request = match receiver.lock().expect("queue lock poisoned").recv() {
+                Ok(request) => request,
+                Err(_) => break,
+            };
+            gauge!(QUEUE_DEPTH).decrement(1.0);
+            handle_solve(request, method, &solve_options);
+        }));
+    }
+
+    for request in server.incoming_requests() {
+        let path = request
+            .url()
+            .split('?')
+            .next()
+            .unwrap_or_default()
+            .to_owned();
+        match (request.method(), path.as_str()) {
+            (HttpMethod::Get, "/metrics") => {
+                respond(request, 200, "text/plain; version=0.0.4", metrics.render())
+            }
+            (HttpMethod::Get, "/health") => respond(request, 200, "text/plain", "ok".into()),
+            (HttpMethod::Post, "/solve") => {
+                gauge!(QUEUE_DEPTH).increment(1.0);
+                sender
+                    .send(request)
+                    .map_err(|_| anyhow!("solve workers stopped unexpectedly"))?;
+            }
+            _ => respond(request, 404, "text/plain", "not found".into()),
+        }
+    }
+
+    drop(sender);
+    for worker in workers {
+        let _ = worker.join();
+    }
+    Ok(())
+}
+
+fn install_recorder() -> Result<PrometheusHandle> {
+    PrometheusBuilder::new()
+        .set_buckets_for_metric(
+            Matcher::Full("cvxrs_solve_duration_seconds".into()),
+            &DURATION_BUCKETS,
+        )?
+        .set_buckets_for_metric(
+            Matcher::Full("cvxrs_solve_iterations".into()),
+            &ITERATION_BUCKETS,
+        )?
+        .install_recorder()
+        .context("failed to install the Prometheus recorder")
+}
+
+fn handle_solve(mut request: Request, default_method: Method, options: &SolveOptions<Scalar>) {
+    let method = match query_method(request.url()) {
+        Ok(method) => method.unwrap_or(default_method),
+        Err(message) => return respond_error(request, 400, message),
+    };
+    let mut body = String::new();
+    if let Err(err) = request.as_reader().read_to_string(&mut body) {
+        return respond_error(request, 400, format!("failed to read body: {}", err));
+    }
+    let problem: JsonProblem = match serde_json::from_str(&body) {
+        Ok(problem) => problem,
+        Err(err) => return respond_error(request, 400, format!("invalid problem: {}", err)),
+    };
+
+    let mut solver = Solver::<Scalar>::new()
+        .method(method)
+        .options(options.clone());
+    let result = match problem {
+        JsonProblem::Qp { problem } => solver.solve_qp(problem),
+        JsonProblem::Lp { problem } => solver.solve_lp(problem),
+    };
+    match result {
+        Ok(solution) => match serde_json::to_string(&solution) {
+            Ok(json) => respond(request, 200, "application/json", json),
+            Err(err) => respond_error(request, 500, err.to_string()),
+        },
+        Err(err @ SolverError::Unsupported(_)) => respond_error(request, 501, err.to_string()),
+        Err(err) => respond_error(request, 422, err.to_string()),
+    }
+}
+
+fn query_method(url: &str) -> Result<Option<Method>, String> {
+    let Some((_, query)) = url.split_once('?') else {
+        return Ok(None);
+    };
+    for pair in query.split('&') {
+        match pair.split_once('=') {
+            Some(("method", "admm")) => return Ok(Some(Method::Admm)),
+            Some(("method", "ipm")) => return Ok(Some(Method::Ipm)),
+            Some(("method", "active-set")) => return Ok(Some(Method::ActiveSet)),
+            Some(("method", "simplex")) => return Ok(Some(Method::Simplex)),
+            Some(("method", "pdhg")) => return Ok(Some(Method::Pdhg)),
+            Some(("method", "auto")) => return Ok(Some(Method::Auto)),
+            Some(("method", other)) => return Err(format!("unknown method '{}'", other)),
+            _ => {}
+        }
+    }
+    Ok(None)
+}
+
+fn respond_error(request: Request, status: u16, message: String) {
+    let body = serde_json::json!({ "error": message }).to_string();
+    respond(request, status, "application/json", body);
+}
+
+fn respond(request: Request, status: u16, content_type: &str, body: String) {
+    let header = Header::from_bytes("Content-Type", content_type).expect("valid header");
+    let response = Response::from_string(body)
+        .with_status_code(status)
+        .with_header(header);
+    if let Err(err) = request.respond(response) {
+        tracing::warn!(error = %err, "failed to send response");
+    }
+}
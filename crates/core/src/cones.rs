@@ -0,0 +1,264 @@
+use crate::math::{norm2, RealNumber};
+use crate::problem::Cone;
+
+// Cyclic Jacobi stops after this many sweeps even if the off-diagonal mass
+// has not dropped below the tolerance; small PSD blocks need far fewer.
+const MAX_JACOBI_SWEEPS: usize = 100;
+
+// Iteration caps and tolerances of the exponential cone projection, whose
+// bisection and inner Newton solve both converge well within them.
+const EXP_MAX_ITERATIONS: usize = 200;
+const EXP_TOLERANCE: f64 = 1e-12;
+
+// Euclidean projection of `values` onto `cone`, in place.
+pub fn project_cone<T: RealNumber>(cone: &Cone, values: &mut [T]) {
+    match cone {
+        Cone::Zero(_) => values.fill(T::zero()),
+        Cone::NonNegative(_) => {
+            for value in values.iter_mut() {
+                *value = value.max(T::zero());
+            }
+        }
+        Cone::SecondOrder(_) => project_second_order(values),
+        Cone::PositiveSemidefinite(side) => project_psd(*side, values),
+        Cone::Exponential => project_exponential(values),
+    }
+}
+
+// Position of entry (row, col), row <= col, in a PSD block.
+pub fn psd_index(row: usize, col: usize) -> usize {
+    debug_assert!(row <= col);
+    col * (col + 1) / 2 + row
+}
+
+fn project_second_order<T: RealNumber>(values: &mut [T]) {
+    let Some((t, x)) = values.split_first_mut() else {
+        return;
+    };
+    let norm = norm2(x);
+    if norm <= *t {
+        return;
+    }
+    if norm <= -*t {
+        *t = T::zero();
+        x.fill(T::zero());
+        return;
+    }
+    let half = (*t + norm) / (T::one() + T::one());
+    *t = half;
+    for value in x.iter_mut() {
+        *value = *value * half / norm;
+    }
+}
+
+// Parikh and Boyd's projection, as in SCS: after the cases with a closed
+// form, bisect on the multiplier ρ of the boundary constraint, solving for
+// the boundary point at each ρ with a one-dimensional Newton iteration.
+fn project_exponential<T: RealNumber>(values: &mut [T]) {
+    let (r, s, t) = (values[0], values[1], values[2]);
+    let zero = T::zero();
+    let threshold = T::from_f64(EXP_TOLERANCE).unwrap();
+    // Already in the cone.
+    if (s > zero && s * (r / s).exp() - t <= threshold) || (r <= zero && s == zero && t >= zero) {
+        return;
+    }
+    // In the polar cone, -K*.
+    if (r > zero && r * (s / r).exp() + T::one().exp() * t <= threshold)
+        || (r == zero && s <= zero && t <= zero)
+    {
+        values.fill(zero);
+        return;
+    }
+    if r < zero && s < zero {
+        values[1] = zero;
+        values[2] = t.max(zero);
+        return;
+    }
+
+    let mut lower = zero;
+    let mut upper = T::from_f64(0.125).unwrap();
+    let mut point = [r, s, t];
+    for _ in 0..EXP_MAX_ITERATIONS {
+        if exponential_gradient(&mut point, r, s, t, upper) <= zero {
+            break;
+        }
+        lower = upper;
+        upper += upper;
+    }
+    for _ in 0..EXP_MAX_ITERATIONS {
+        let rho = (lower + upper) / (T::one() + T::one());
+        if exponential_gradient(&mut point, r, s, t, rho) > zero {
+            lower = rho;
+        } else {
+            upper = rho;
+        }
+        if upper - lower < threshold {
+            break;
+        }
+    }
+    values.copy_from_slice(&point);
+}
+
+// Boundary point for the multiplier ρ, written to `point`, and the
+// derivative of the dual function there.
+fn exponential_gradient<T: RealNumber>(point: &mut [T; 3], r: T, s: T, t: T, rho: T) -> T {
+    let zero = T::zero();
+    let threshold = T::from_f64(EXP_TOLERANCE).unwrap();
+    let mut w = (-t).max(T::from_f64(1e-6).unwrap());
+    let mut z = zero;
+    for _ in 0..EXP_MAX_ITERATIONS {
+        let f = w * (w + t) / rho / rho - s / rho + (w / rho).ln() + T::one();
+        let fp = (w + w + t) / rho / rho + T::one() / w;
+        w -= f / fp;
+        if w <= -t {
+            z = zero;
+            break;
+        }
+        if w <= zero {
+            z = t;
+            break;
+        }
+        z = w + t;
+        if f.abs() < threshold {
+            break;
+        }
+    }
+    point[2] = z;
+    point[1] = (z - t) * z / rho;
+    point[0] = r - rho;
+    if point[1] <= threshold {
+        point[0]
+    } else {
+        point[0] + point[1] * (point[1] / point[2]).ln()
+    }
+}
+
+// Clips the negative eigenvalues of the matrix the block stands for.
+fn project_psd<T: RealNumber>(side: usize, values: &mut [T]) {
+    let sqrt2 = (T::one() + T::one()).sqrt();
+    let mut matrix = vec![T::zero(); side * side];
+    for col in 0..side {
+        for row in 0..=col {
+            let mut value = values[psd_index(row, col)];
+            if row != col {
+                value = value / sqrt2;
+            }
+            matrix[row * side + col] = value;
+            matrix[col * side + row] = value;
+        }
+    }
+    let (eigenvalues, vectors) = symmetric_eigen(side, matrix);
+    for col in 0..side {
+        for row in 0..=col {
+            let mut value = T::zero();
+            for (k, &lambda) in eigenvalues.iter().enumerate() {
+                if lambda > T::zero() {
+                    value += lambda * vectors[row * side + k] * vectors[col * side + k];
+                }
+            }
+            if row != col {
+                value *= sqrt2;
+            }
+            values[psd_index(row, col)] = value;
+        }
+    }
+}
+
+// Eigenvalues and eigenvectors (as the columns of a row-major matrix) of a
+// symmetric matrix by cyclic Jacobi rotations.
+fn symmetric_eigen<T: RealNumber>(n: usize, mut a: Vec<T>) -> (Vec<T>, Vec<T>) {
+    let mut v = vec![T::zero(); n * n];
+    for i in 0..n {
+        v[i * n + i] = T::one();
+    }
+    let two = T::one() + T::one();
+    let scale = norm2(&a);
+    for _ in 0..MAX_JACOBI_SWEEPS {
+        let off: T = (0..n)
+            .flat_map(|i| (0..n).filter(move |&j| j != i).map(move |j| (i, j)))
+            .fold(T::zero(), |acc, (i, j)| acc + a[i * n + j] * a[i * n + j]);
+        if off.sqrt() <= T::epsilon() * scale {
+            break;
+        }
+        for p in 0..n {
+            for q in p + 1..n {
+                let apq = a[p * n + q];
+                if apq == T::zero() {
+                    continue;
+                }
+                let theta = (a[q * n + q] - a[p * n + p]) / (two * apq);
+                let t = theta.signum() / (theta.abs() + (theta * theta + T::one()).sqrt());
+                let c = T::one() / (t * t + T::one()).sqrt();
+                let s = t * c;
+                for k in 0..n {
+                    let (akp, akq) = (a[k * n + p], a[k * n + q]);
+                    a[k * n + p] = c * akp - s * akq;
+                    a[k * n + q] = s * akp + c * akq;
+                }
+                for k in 0..n {
+                    let (apk, aqk) = (a[p * n + k], a[q * n + k]);
+                    a[p * n + k] = c * apk - s * aqk;
+                    a[q * n + k] = s * apk + c * aqk;
+                }
+                for k in 0..n {
+                    let (vkp, vkq) = (v[k * n + p], v[k * n + q]);
+                    v[k * n + p] = c * vkp - s * vkq;
+                    v[k * n + q] = s * vkp + c * vkq;
+                }
+            }
+        }
+    }
+    ((0..n).map(|i| a[i * n + i]).collect(), v)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn psd_projection_clips_the_negative_eigenvalue() {
+        // [[1, 2], [2, 1]] has eigenvalues 3 and -1; its projection is
+        // 3/2 [[1, 1], [1, 1]].
+        let sqrt2 = 2f64.sqrt();
+        let mut block: Vec<f64> = vec![1.0, 2.0 * sqrt2, 1.0];
+        project_cone(&Cone::PositiveSemidefinite(2), &mut block);
+        let expected = [1.5, 1.5 * sqrt2, 1.5];
+        for (value, expected) in block.iter().zip(expected) {
+            assert!((value - expected).abs() < 1e-12, "{block:?}");
+        }
+
+        let mut inside: Vec<f64> = vec![2.0, 0.5, 3.0];
+        project_cone(&Cone::PositiveSemidefinite(2), &mut inside);
+        assert!((inside[1] - 0.5).abs() < 1e-12);
+
+        let mut cone = vec![1.0, 3.0, 4.0];
+        project_cone(&Cone::SecondOrder(3), &mut cone);
+        assert_eq!(cone, vec![3.0, 1.8, 2.4]);
+    }
+
+    #[test]
+    fn exponential_projection_matches_the_closed_forms() {
+        let project = |mut v: [f64; 3]| {
+            project_cone(&Cone::Exponential, &mut v);
+            v
+        };
+        // Inside the cone, since e <= 3.
+        assert_eq!(project([1.0, 1.0, 3.0]), [1.0, 1.0, 3.0]);
+        // In the polar cone, since 1 + e (-1) <= 0.
+        assert_eq!(project([1.0, 0.0, -1.0]), [0.0, 0.0, 0.0]);
+        // r, s < 0 projects onto the face {(x, 0, z) : x <= 0, z >= 0}.
+        assert_eq!(project([-1.0, -2.0, 3.0]), [-1.0, 0.0, 3.0]);
+        assert_eq!(project([-1.0, -2.0, -3.0]), [-1.0, 0.0, 0.0]);
+
+        // Otherwise the Moreau decomposition v = p + d holds with p on the
+        // boundary, d in the polar cone and p ⊥ d.
+        let v = [1.0, 1.0, 1.0];
+        let p = project(v);
+        let d = [v[0] - p[0], v[1] - p[1], v[2] - p[2]];
+        assert!(p[1] > 0.0);
+        assert!((p[1] * (p[0] / p[1]).exp() - p[2]).abs() < 1e-8, "{p:?}");
+        assert!(d[0] > 0.0);
+        assert!(d[0] * (d[1] / d[0]).exp() + std::f64::consts::E * d[2] <= 1e-8);
+        assert!((p[0] * d[0] + p[1] * d[1] + p[2] * d[2]).abs() < 1e-8);
+    }
+}
@@ -0,0 +1,191 @@
+use crate::math::RealNumber;
+use crate::problem::{
+    ConstraintGroup, CscMatrix, EqualityConstraints, InequalityConstraints, ProblemLP, ProblemQP,
+};
+use crate::solution::Solution;
+use crate::structure::ConstraintRow;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GroupReport<T> {
+    pub name: String,
+    pub rows: usize,
+    pub feasible: bool,
+    pub worst_violation: T,
+    pub worst_row: Option<ConstraintRow>,
+    // -Σ y_i b_i over the group: its share of the rhs term in the dual
+    // objective. None when the solution carries no usable multipliers.
+    pub dual_contribution: Option<T>,
+}
+
+pub fn group_report_qp<T: RealNumber>(
+    problem: &ProblemQP<T>,
+    solution: &Solution<T>,
+    tolerance: T,
+) -> Vec<GroupReport<T>> {
+    report(
+        &problem.groups,
+        &problem.equalities,
+        &problem.inequalities,
+        problem
+            .bounds
+            .as_ref()
+            .map_or(0, |bounds| bounds.lower.len()),
+        solution,
+        tolerance,
+    )
+}
+
+pub fn group_report_lp<T: RealNumber>(
+    problem: &ProblemLP<T>,
+    solution: &Solution<T>,
+    tolerance: T,
+) -> Vec<GroupReport<T>> {
+    report(
+        &problem.groups,
+        &problem.equalities,
+        &problem.inequalities,
+        problem
+            .bounds
+            .as_ref()
+            .map_or(0, |bounds| bounds.lower.len()),
+        solution,
+        tolerance,
+    )
+}
+
+fn report<T: RealNumber>(
+    groups: &[ConstraintGroup],
+    equalities: &Option<EqualityConstraints<T>>,
+    inequalities: &Option<InequalityConstraints<T>>,
+    nbounds: usize,
+    solution: &Solution<T>,
+    tolerance: T,
+) -> Vec<GroupReport<T>> {
+    let x = &solution.primal;
+    let eq_activity = equalities.as_ref().map(|eq| multiply(&eq.matrix, x));
+    let ineq_activity = inequalities.as_ref().map(|ineq| multiply(&ineq.matrix, x));
+    let meq = equalities.as_ref().map_or(0, |eq| eq.rhs.len());
+    let mineq = inequalities.as_ref().map_or(0, |ineq| ineq.rhs.len());
+    // Same dual layouts as the verifier: stacked by ADMM, split otherwise.
+    let (eq_dual, ineq_dual) = {
+        let (eq, ineq) = (&solution.equality_dual, &solution.inequality_dual);
+        if eq.is_empty() && ineq.len() == meq + mineq + nbounds {
+            (Some(&ineq[..meq]), Some(&ineq[meq..meq + mineq]))
+        } else if eq.len() == meq && ineq.len() >= mineq {
+            (Some(&eq[..]), Some(&ineq[..mineq]))
+        } else {
+            (None, None)
+        }
+    };
+
+    groups
+        .iter()
+        .map(|group| {
+            let mut worst_violation = T::zero();
+            let mut worst_row = None;
+            let mut dual = Some(T::zero());
+            for &row in &group.rows {
+                let (violation, multiplier, rhs) = match row {
+                    ConstraintRow::Equality(i) => {
+                        let rhs = equalities.as_ref().map_or(T::zero(), |eq| eq.rhs[i]);
+                        let activity = eq_activity.as_ref().map_or(T::zero(), |a| a[i]);
+                        ((activity - rhs).abs(), eq_dual.map(|y| y[i]), rhs)
+                    }
+                    ConstraintRow::Inequality(i) => {
+                        let rhs = inequalities.as_ref().map_or(T::zero(), |ineq| ineq.rhs[i]);
+                        let activity = ineq_activity.as_ref().map_or(T::zero(), |a| a[i]);
+                        (
+                            (activity - rhs).max(T::zero()),
+                            ineq_dual.map(|y| y[i]),
+                            rhs,
+                        )
+                    }
+                };
+                if worst_row.is_none() || violation > worst_violation {
+                    worst_violation = violation;
+                    worst_row = Some(row);
+                }
+                dual = dual.zip(multiplier).map(|(acc, y)| acc - y * rhs);
+            }
+            GroupReport {
+                name: group.name.clone(),
+                rows: group.rows.len(),
+                feasible: worst_violation <= tolerance,
+                worst_violation,
+                worst_row,
+                dual_contribution: dual,
+            }
+        })
+        .collect()
+}
+
+fn multiply<T: RealNumber>(matrix: &CscMatrix<T>, x: &[T]) -> Vec<T> {
+    let mut out = vec![T::zero(); matrix.nrows];
+    for (col, &xj) in x.iter().enumerate().take(matrix.ncols) {
+        for idx in matrix.indptr[col]..matrix.indptr[col + 1] {
+            out[matrix.indices[idx]] += matrix.data[idx] * xj;
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::problem::Names;
+    use crate::problem::ObjectiveSense;
+
+    fn column_vector(values: &[f64]) -> CscMatrix<f64> {
+        CscMatrix {
+            nrows: values.len(),
+            ncols: 1,
+            indptr: vec![0, values.len()],
+            indices: (0..values.len()).collect(),
+            data: values.to_vec(),
+        }
+    }
+
+    #[test]
+    fn aggregates_violations_and_duals_per_group() {
+        // x = 2 against x = 2, x ≤ 3 and 2x ≤ 3.
+        let problem = ProblemLP {
+            cost: vec![1.0],
+            equalities: Some(EqualityConstraints {
+                matrix: column_vector(&[1.0]),
+                rhs: vec![2.0],
+            }),
+            inequalities: Some(InequalityConstraints {
+                matrix: column_vector(&[1.0, 2.0]),
+                rhs: vec![3.0, 3.0],
+            }),
+            bounds: None,
+            groups: vec![
+                ConstraintGroup {
+                    name: "balance".into(),
+                    rows: vec![ConstraintRow::Equality(0), ConstraintRow::Inequality(0)],
+                },
+                ConstraintGroup {
+                    name: "capacity".into(),
+                    rows: vec![ConstraintRow::Inequality(1)],
+                },
+            ],
+            integrality: Vec::new(),
+            names: Names::default(),
+            sense: ObjectiveSense::Minimize,
+            offset: 0.0,
+        };
+        let mut solution = Solution::with_capacity(1, 1, 2);
+        solution.primal = vec![2.0];
+        solution.equality_dual = vec![-1.5];
+        solution.inequality_dual = vec![0.0, 0.5];
+
+        let report = group_report_lp(&problem, &solution, 1e-9);
+        assert!(report[0].feasible);
+        assert_eq!(report[0].dual_contribution, Some(3.0));
+        assert!(!report[1].feasible);
+        assert_eq!(report[1].worst_violation, 1.0);
+        assert_eq!(report[1].worst_row, Some(ConstraintRow::Inequality(1)));
+        assert_eq!(report[1].dual_contribution, Some(-1.5));
+    }
+}
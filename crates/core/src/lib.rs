@@ -1,17 +1,33 @@
 #![forbid(unsafe_code)]
 
+pub mod cones;
+pub mod groups;
 pub mod math;
+#[cfg(feature = "nalgebra")]
+mod nalgebra_interop;
+#[cfg(feature = "ndarray")]
+mod ndarray_interop;
 pub mod options;
+pub mod presolve;
 pub mod problem;
+pub mod robust;
 pub mod scaling;
 pub mod solution;
 pub mod stats;
+pub mod structure;
 pub mod traits;
+#[cfg(feature = "verify")]
+pub mod verify;
 
+pub use cones::*;
+pub use groups::*;
 pub use math::*;
 pub use options::*;
+pub use presolve::*;
 pub use problem::*;
+pub use robust::*;
 pub use scaling::*;
 pub use solution::*;
 pub use stats::*;
+pub use structure::*;
 pub use traits::*;
@@ -1,14 +1,25 @@
+use num_traits::float::TotalOrder;
 use num_traits::{Float as NumFloat, FromPrimitive};
 use std::ops::{AddAssign, MulAssign, SubAssign};
 use std::time::{Duration, Instant};
 
+// TotalOrder gives `total_cmp`, so pivot searches order values without
+// panicking on NaN.
 pub trait RealNumber:
-    NumFloat + FromPrimitive + Send + Sync + AddAssign + SubAssign + MulAssign + 'static
+    NumFloat + TotalOrder + FromPrimitive + Send + Sync + AddAssign + SubAssign + MulAssign + 'static
 {
 }
 
 impl<T> RealNumber for T where
-    T: NumFloat + FromPrimitive + Send + Sync + AddAssign + SubAssign + MulAssign + 'static
+    T: NumFloat
+        + TotalOrder
+        + FromPrimitive
+        + Send
+        + Sync
+        + AddAssign
+        + SubAssign
+        + MulAssign
+        + 'static
 {
 }
 
@@ -18,6 +29,22 @@ pub type Scalar = f64;
 #[cfg(feature = "f32")]
 pub type Scalar = f32;
 
+// Converts between precisions; values beyond the range of U saturate to an
+// infinity of the same sign.
+pub fn cast<T: RealNumber, U: RealNumber>(value: T) -> U {
+    U::from(value).unwrap_or_else(|| {
+        if value > T::zero() {
+            U::infinity()
+        } else {
+            U::neg_infinity()
+        }
+    })
+}
+
+pub fn cast_slice<T: RealNumber, U: RealNumber>(values: &[T]) -> Vec<U> {
+    values.iter().map(|&value| cast(value)).collect()
+}
+
 pub fn dot<T: RealNumber>(lhs: &[T], rhs: &[T]) -> T {
     assert_eq!(lhs.len(), rhs.len(), "dot product dimension mismatch");
     lhs.iter()
@@ -0,0 +1,72 @@
+use crate::math::RealNumber;
+use crate::problem::CscMatrix;
+use crate::solution::Solution;
+use nalgebra::{DMatrix, DVector};
+
+// The nonzeros of a dense matrix; explicit zeros are left out as in
+// `CscMatrix::from_dense`.
+impl<T: RealNumber + nalgebra::Scalar> From<&DMatrix<T>> for CscMatrix<T> {
+    fn from(matrix: &DMatrix<T>) -> Self {
+        let (nrows, ncols) = matrix.shape();
+        let mut indptr = Vec::with_capacity(ncols + 1);
+        let mut indices = Vec::new();
+        let mut data = Vec::new();
+        indptr.push(0);
+        for column in matrix.column_iter() {
+            for (row, &value) in column.iter().enumerate() {
+                if value != T::zero() {
+                    indices.push(row);
+                    data.push(value);
+                }
+            }
+            indptr.push(indices.len());
+        }
+        Self {
+            nrows,
+            ncols,
+            indptr,
+            indices,
+            data,
+        }
+    }
+}
+
+impl<T: RealNumber + nalgebra::Scalar> From<DMatrix<T>> for CscMatrix<T> {
+    fn from(matrix: DMatrix<T>) -> Self {
+        (&matrix).into()
+    }
+}
+
+impl<T: RealNumber + nalgebra::Scalar> Solution<T> {
+    pub fn primal_vector(&self) -> DVector<T> {
+        DVector::from_column_slice(&self.primal)
+    }
+
+    pub fn equality_dual_vector(&self) -> DVector<T> {
+        DVector::from_column_slice(&self.equality_dual)
+    }
+
+    pub fn inequality_dual_vector(&self) -> DVector<T> {
+        DVector::from_column_slice(&self.inequality_dual)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dense_matrices_convert() {
+        let matrix = DMatrix::from_row_slice(2, 3, &[1.0, 0.0, 2.0, 0.0, 0.0, 3.0]);
+        let converted = CscMatrix::from(matrix);
+        let expected = CscMatrix::from_dense(2, 3, &[1.0, 0.0, 2.0, 0.0, 0.0, 3.0]).unwrap();
+        assert_eq!(converted.indptr, expected.indptr);
+        assert_eq!(converted.indices, expected.indices);
+        assert_eq!(converted.data, expected.data);
+
+        let mut solution = Solution::<f64>::with_capacity(2, 0, 1);
+        solution.primal = vec![1.5, -2.0];
+        assert_eq!(solution.primal_vector(), DVector::from_vec(vec![1.5, -2.0]));
+        assert_eq!(solution.inequality_dual_vector().len(), 1);
+    }
+}
@@ -0,0 +1,78 @@
+use crate::math::RealNumber;
+use crate::problem::CscMatrix;
+use crate::solution::Solution;
+use ndarray::{Array1, Array2, ArrayView2};
+
+// The nonzeros of a two-dimensional array in any memory layout; explicit
+// zeros are left out as in `CscMatrix::from_dense`.
+impl<T: RealNumber> From<ArrayView2<'_, T>> for CscMatrix<T> {
+    fn from(array: ArrayView2<'_, T>) -> Self {
+        let (nrows, ncols) = array.dim();
+        let mut indptr = Vec::with_capacity(ncols + 1);
+        let mut indices = Vec::new();
+        let mut data = Vec::new();
+        indptr.push(0);
+        for column in array.columns() {
+            for (row, &value) in column.iter().enumerate() {
+                if value != T::zero() {
+                    indices.push(row);
+                    data.push(value);
+                }
+            }
+            indptr.push(indices.len());
+        }
+        Self {
+            nrows,
+            ncols,
+            indptr,
+            indices,
+            data,
+        }
+    }
+}
+
+impl<T: RealNumber> From<&Array2<T>> for CscMatrix<T> {
+    fn from(array: &Array2<T>) -> Self {
+        array.view().into()
+    }
+}
+
+impl<T: RealNumber> Solution<T> {
+    pub fn primal_array(&self) -> Array1<T> {
+        Array1::from(self.primal.clone())
+    }
+
+    pub fn equality_dual_array(&self) -> Array1<T> {
+        Array1::from(self.equality_dual.clone())
+    }
+
+    pub fn inequality_dual_array(&self) -> Array1<T> {
+        Array1::from(self.inequality_dual.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::array;
+
+    #[test]
+    fn arrays_convert_in_either_layout() {
+        let array = array![[1.0, 0.0, 2.0], [0.0, 0.0, 3.0]];
+        let expected = CscMatrix::from_dense(2, 3, &[1.0, 0.0, 2.0, 0.0, 0.0, 3.0]).unwrap();
+        for matrix in [
+            CscMatrix::from(&array),
+            CscMatrix::from(array.t().t()),
+            CscMatrix::from(array.reversed_axes().t()),
+        ] {
+            assert_eq!(matrix.indptr, expected.indptr);
+            assert_eq!(matrix.indices, expected.indices);
+            assert_eq!(matrix.data, expected.data);
+        }
+
+        let mut solution = Solution::<f64>::with_capacity(2, 1, 0);
+        solution.primal = vec![1.5, -2.0];
+        assert_eq!(solution.primal_array(), array![1.5, -2.0]);
+        assert_eq!(solution.equality_dual_array().len(), 1);
+    }
+}
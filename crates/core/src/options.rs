@@ -1,23 +1,120 @@
-use crate::math::RealNumber;
-use serde::{Deserialize, Serialize};
+use crate::math::{cast, RealNumber};
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::time::Duration;
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 pub enum Method {
     Admm,
     Ipm,
+    ActiveSet,
+    Simplex,
+    Pdhg,
+    // Picks one of the above from the problem, falling back to ADMM when
+    // the pick fails numerically.
+    Auto,
 }
 
+// How the solvers treat their KKT systems.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum LinsysBackend {
+    // LDLᵀ factorization of the quasi-definite KKT matrix.
+    #[default]
+    Direct,
+    // MINRES on that same matrix, preconditioned by its absolute diagonal.
+    // Iteration counts are reported in the solve statistics.
+    Minres,
+    // ADMM only: preconditioned conjugate gradients on the reduced
+    // P + σI + ρAᵀA, through matrix-vector products only. Equality rows are
+    // then penalized like the other rows rather than enforced exactly.
+    ConjugateGradient,
+    // ADMM's conjugate gradients and IPM's MINRES with the dense products
+    // on the GPU, in single precision. Needs the gpu feature.
+    Gpu,
+}
+
+// How the problem is equilibrated before ADMM iterates on it.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum Scaling {
+    // Row and column factors from the infinity norms of [P Aᵀ; A 0], then
+    // a cost factor for the objective.
+    #[default]
+    Ruiz,
+    // Row and column factors from the geometric mean of the largest and
+    // smallest entry of each row and column of [P Aᵀ; A 0].
+    Geometric,
+    // As Ruiz, with the cost factor updated after every pass as in OSQP.
+    ModifiedRuiz,
+    // The problem as given, for data that is already well balanced.
+    None,
+}
+
+// The derives only read and write the fields; the Serialize and Deserialize
+// impls below wrap them so that older saved options still load.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(remote = "Self")]
 pub struct SolveOptions<T: RealNumber> {
-    pub tolerance: T,
+    // Absolute residual tolerance.
+    pub eps_abs: T,
+    // Relative part of the residual tolerance, scaled by the size of the
+    // terms each residual is made of.
+    #[serde(default = "default_eps_rel")]
+    pub eps_rel: T,
+    // How close to a certificate of primal or dual infeasibility an iterate
+    // has to come before a solver reports it.
+    #[serde(default = "default_eps_inf")]
+    pub eps_prim_inf: T,
+    #[serde(default = "default_eps_inf")]
+    pub eps_dual_inf: T,
     pub max_iterations: usize,
     pub max_time: Option<Duration>,
     pub admm_rho: T,
     pub admm_relaxation: T,
     pub admm_adaptive_rho: bool,
+    // Adaptive rho is reconsidered every this many iterations and kept
+    // within [rho_min, rho_max].
+    #[serde(default = "default_rho_update_interval")]
+    pub rho_update_interval: usize,
+    #[serde(default = "default_rho_min")]
+    pub rho_min: T,
+    #[serde(default = "default_rho_max")]
+    pub rho_max: T,
+    // Proximal weight of the ADMM x-update, which adds σI to P + ρAᵀA so
+    // that rank-deficient problems still factor.
+    #[serde(default = "default_sigma")]
+    pub sigma: T,
     pub check_every: usize,
     pub seed: u64,
+    // Caps the worker threads of the solver's own pool; None leaves it to rayon.
+    #[serde(default)]
+    pub num_threads: Option<usize>,
+    // Number of past ADMM steps Anderson acceleration mixes; 0 disables it.
+    #[serde(default)]
+    pub anderson_memory: usize,
+    // Iterative refinement steps after every KKT solve; one or two help on
+    // ill-conditioned problems at the cost of a matrix product each.
+    #[serde(default)]
+    pub refinement_steps: usize,
+    #[serde(default)]
+    pub linsys_backend: LinsysBackend,
+    #[serde(default)]
+    pub scaling: Scaling,
+    // Re-solve the active constraints exactly once ADMM reports Optimal.
+    #[serde(default)]
+    pub polish: bool,
+    // Purify an Optimal ADMM LP point into a simplex vertex; the duals then
+    // follow the simplex layout.
+    #[serde(default)]
+    pub crossover: bool,
+    // Reduce the problem before the solve and map the solution back after
+    // it; see the presolve module for the reductions.
+    #[serde(default)]
+    pub presolve: bool,
+    // ADMM factors and iterates in f32 up to a loose tolerance, then
+    // finishes in f64, where the residuals and the objective are measured.
+    #[cfg(feature = "mixed-precision")]
+    #[serde(default)]
+    pub mixed_precision: bool,
 }
 
 impl<T> SolveOptions<T>
@@ -26,10 +123,69 @@ where
 {
     pub fn with_tolerance(tolerance: T) -> Self {
         Self {
-            tolerance,
+            eps_abs: tolerance,
+            eps_rel: tolerance,
             ..Self::default()
         }
     }
+
+    // The same options in another precision.
+    pub fn cast<U: RealNumber>(&self) -> SolveOptions<U> {
+        SolveOptions {
+            eps_abs: cast(self.eps_abs),
+            eps_rel: cast(self.eps_rel),
+            eps_prim_inf: cast(self.eps_prim_inf),
+            eps_dual_inf: cast(self.eps_dual_inf),
+            max_iterations: self.max_iterations,
+            max_time: self.max_time,
+            admm_rho: cast(self.admm_rho),
+            admm_relaxation: cast(self.admm_relaxation),
+            admm_adaptive_rho: self.admm_adaptive_rho,
+            rho_update_interval: self.rho_update_interval,
+            rho_min: cast(self.rho_min),
+            rho_max: cast(self.rho_max),
+            sigma: cast(self.sigma),
+            check_every: self.check_every,
+            seed: self.seed,
+            num_threads: self.num_threads,
+            anderson_memory: self.anderson_memory,
+            refinement_steps: self.refinement_steps,
+            linsys_backend: self.linsys_backend,
+            scaling: self.scaling,
+            polish: self.polish,
+            crossover: self.crossover,
+            presolve: self.presolve,
+            #[cfg(feature = "mixed-precision")]
+            mixed_precision: self.mixed_precision,
+        }
+    }
+}
+
+impl<T> Serialize for SolveOptions<T>
+where
+    T: RealNumber + Serialize,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SolveOptions::serialize(self, serializer)
+    }
+}
+
+// Older versions saved a single `tolerance`, which stood for both parts of
+// the residual test just as with_tolerance still sets them.
+impl<'de, T> Deserialize<'de> for SolveOptions<T>
+where
+    T: RealNumber + Deserialize<'de>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut value = serde_json::Value::deserialize(deserializer)?;
+        if let Some(fields) = value.as_object_mut() {
+            if let Some(tolerance) = fields.remove("tolerance") {
+                fields.entry("eps_abs").or_insert_with(|| tolerance.clone());
+                fields.entry("eps_rel").or_insert(tolerance);
+            }
+        }
+        SolveOptions::deserialize(value).map_err(D::Error::custom)
+    }
 }
 
 impl<T> Default for SolveOptions<T>
@@ -38,14 +194,81 @@ where
 {
     fn default() -> Self {
         Self {
-            tolerance: T::from(1e-6).unwrap(),
+            eps_abs: T::from(1e-6).unwrap(),
+            eps_rel: default_eps_rel(),
+            eps_prim_inf: default_eps_inf(),
+            eps_dual_inf: default_eps_inf(),
             max_iterations: 10_000,
             max_time: None,
             admm_rho: T::from(1.0).unwrap(),
             admm_relaxation: T::from(1.5).unwrap(),
             admm_adaptive_rho: true,
+            rho_update_interval: default_rho_update_interval(),
+            rho_min: default_rho_min(),
+            rho_max: default_rho_max(),
+            sigma: default_sigma(),
             check_every: 1,
             seed: 42,
+            num_threads: None,
+            anderson_memory: 0,
+            refinement_steps: 0,
+            linsys_backend: LinsysBackend::Direct,
+            scaling: Scaling::Ruiz,
+            polish: false,
+            crossover: false,
+            presolve: false,
+            #[cfg(feature = "mixed-precision")]
+            mixed_precision: false,
         }
     }
 }
+
+fn default_eps_rel<T: RealNumber>() -> T {
+    T::from(1e-6).unwrap()
+}
+
+fn default_eps_inf<T: RealNumber>() -> T {
+    T::from(1e-6).unwrap()
+}
+
+fn default_rho_update_interval() -> usize {
+    25
+}
+
+fn default_rho_min<T: RealNumber>() -> T {
+    T::from(1e-6).unwrap()
+}
+
+fn default_rho_max<T: RealNumber>() -> T {
+    T::from(1e6).unwrap()
+}
+
+fn default_sigma<T: RealNumber>() -> T {
+    T::from(1e-6).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn older_options_keep_their_tolerance() {
+        let mut value = serde_json::to_value(SolveOptions::<f64>::with_tolerance(1e-4)).unwrap();
+        let fields = value.as_object_mut().unwrap();
+        let tolerance = fields.remove("eps_abs").unwrap();
+        fields.insert("tolerance".into(), tolerance);
+        for field in ["eps_rel", "eps_prim_inf", "eps_dual_inf"] {
+            fields.remove(field);
+        }
+        let options: SolveOptions<f64> = serde_json::from_value(value).unwrap();
+        assert_eq!(options.eps_abs, 1e-4);
+        assert_eq!(options.eps_rel, 1e-4);
+        assert_eq!(options.eps_prim_inf, 1e-6);
+        assert_eq!(options.eps_dual_inf, 1e-6);
+
+        let saved = serde_json::to_string(&options).unwrap();
+        assert!(!saved.contains("tolerance"));
+        let options: SolveOptions<f64> = serde_json::from_str(&saved).unwrap();
+        assert_eq!((options.eps_abs, options.eps_rel), (1e-4, 1e-4));
+    }
+}
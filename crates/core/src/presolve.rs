@@ -0,0 +1,1843 @@
+use crate::math::RealNumber;
+use crate::problem::{
+    Bounds, ConstraintGroup, CscMatrix, EqualityConstraints, InequalityConstraints, Names,
+    ProblemLP, ProblemQP,
+};
+use crate::solution::{Certificate, Solution, Status};
+use crate::stats::{Basis, BasisStatus, PresolveStats};
+use crate::structure::ConstraintRow;
+use crate::traits::{Presolver, Scaler, Transformation};
+use std::collections::{HashMap, HashSet};
+
+// Passes over the rows before bound tightening gives up on converging.
+const MAX_TIGHTENING_PASSES: usize = 10;
+// A bound is only tightened by more than this share of its magnitude, so
+// that two rows cannot trade ever smaller improvements.
+const MIN_TIGHTENING: f64 = 1e-6;
+// Violations of a row or of crossed bounds up to this share of their
+// magnitude are put down to rounding rather than infeasibility.
+const FEASIBILITY_TOLERANCE: f64 = 1e-9;
+
+// Where a solution keeps its multipliers. ADMM stacks those of the
+// equalities, the inequalities and the bounds in `inequality_dual`; the
+// other methods keep the equalities apart and append the bound multipliers
+// to the inequality ones, when they report them at all.
+#[derive(Debug, Clone, Copy)]
+struct DualLayout {
+    stacked: bool,
+    bounds: bool,
+}
+
+impl DualLayout {
+    fn of<T>(
+        equality: &[T],
+        inequality: &[T],
+        meq: usize,
+        mineq: usize,
+        nbounds: usize,
+    ) -> Option<Self> {
+        if equality.is_empty() && inequality.len() == meq + mineq + nbounds {
+            Some(Self {
+                stacked: true,
+                bounds: nbounds > 0,
+            })
+        } else if equality.len() == meq && inequality.len() == mineq + nbounds {
+            Some(Self {
+                stacked: false,
+                bounds: nbounds > 0,
+            })
+        } else if equality.len() == meq && inequality.len() == mineq {
+            Some(Self {
+                stacked: false,
+                bounds: false,
+            })
+        } else {
+            None
+        }
+    }
+
+    // The equality, inequality and bound multipliers, in that order.
+    fn split<T>(
+        &self,
+        equality: Vec<T>,
+        mut inequality: Vec<T>,
+        meq: usize,
+        mineq: usize,
+    ) -> [Vec<T>; 3] {
+        let (y, mut z) = if self.stacked {
+            let z = inequality.split_off(meq);
+            (inequality, z)
+        } else {
+            (equality, inequality)
+        };
+        let w = if self.bounds {
+            z.split_off(mineq)
+        } else {
+            Vec::new()
+        };
+        [y, z, w]
+    }
+
+    fn join<T>(&self, [mut y, mut z, w]: [Vec<T>; 3]) -> (Vec<T>, Vec<T>) {
+        if self.bounds {
+            z.extend(w);
+        }
+        if self.stacked {
+            y.extend(z);
+            (Vec::new(), y)
+        } else {
+            (y, z)
+        }
+    }
+}
+
+// A variable fixed by its bounds, with what its bound multiplier is
+// recovered from: its row of the objective gradient and its columns of the
+// constraint matrices.
+#[derive(Debug, Clone)]
+struct FixedColumn<T> {
+    index: usize,
+    value: T,
+    linear: T,
+    quadratic: Vec<(usize, T)>,
+    equality: Vec<(usize, T)>,
+    inequality: Vec<(usize, T)>,
+}
+
+impl<T: RealNumber> FixedColumn<T> {
+    // Minus the j-th entry of Px + q + Cᵀy + Gᵀz, the bound multiplier
+    // that makes the fixed variable stationary; without the objective it
+    // completes a Farkas certificate instead.
+    fn multiplier(&self, x: &[T], y: &[T], z: &[T], objective: bool) -> T {
+        let mut gradient = T::zero();
+        if objective {
+            gradient = self.linear;
+            for &(i, value) in &self.quadratic {
+                gradient += value * x[i];
+            }
+        }
+        for &(row, value) in &self.equality {
+            gradient += value * y[row];
+        }
+        for &(row, value) in &self.inequality {
+            gradient += value * z[row];
+        }
+        -gradient
+    }
+}
+
+// Variables whose lower and upper bounds coincide, substituted out of the
+// objective and the constraints before a solve and put back into its
+// solution afterwards.
+#[derive(Debug, Clone)]
+pub struct FixedVariables<T> {
+    nvars: usize,
+    meq: usize,
+    mineq: usize,
+    // Original index of every variable left in the reduced problem.
+    kept: Vec<usize>,
+    fixed: Vec<FixedColumn<T>>,
+}
+
+impl<T> FixedVariables<T>
+where
+    T: RealNumber,
+{
+    fn detect(
+        nvars: usize,
+        bounds: Option<&Bounds<T>>,
+        equalities: Option<&EqualityConstraints<T>>,
+        inequalities: Option<&InequalityConstraints<T>>,
+    ) -> Self {
+        let mut kept = Vec::with_capacity(nvars);
+        let mut fixed = Vec::new();
+        for index in 0..nvars {
+            match bounds {
+                Some(bounds) if bounds.lower[index] == bounds.upper[index] => {
+                    fixed.push(FixedColumn {
+                        index,
+                        value: bounds.lower[index],
+                        linear: T::zero(),
+                        quadratic: Vec::new(),
+                        equality: Vec::new(),
+                        inequality: Vec::new(),
+                    })
+                }
+                _ => kept.push(index),
+            }
+        }
+        Self {
+            nvars,
+            meq: equalities.map_or(0, |eq| eq.rhs.len()),
+            mineq: inequalities.map_or(0, |ineq| ineq.rhs.len()),
+            kept,
+            fixed,
+        }
+    }
+
+    // The QP over the remaining variables; its offset carries the
+    // objective of the fixed ones.
+    pub fn qp(problem: &ProblemQP<T>) -> (ProblemQP<T>, Self) {
+        // Quadratic constraints would need the substitution as well; their
+        // variables are left alone.
+        let bounds = problem
+            .bounds
+            .as_ref()
+            .filter(|_| problem.quadratic_constraints.is_empty());
+        let mut presolve = Self::detect(
+            problem.nvars(),
+            bounds,
+            problem.equalities.as_ref(),
+            problem.inequalities.as_ref(),
+        );
+        if presolve.fixed.is_empty() {
+            return (problem.clone(), presolve);
+        }
+        let x = presolve.values();
+        let position = presolve.positions();
+        let mut linear = presolve.keep(&problem.linear);
+        let mut offset = problem.offset;
+        for column in &mut presolve.fixed {
+            column.linear = problem.linear[column.index];
+            offset += column.linear * column.value;
+        }
+
+        // ½xᵀPx over the stored entries, whatever triangle P is stored in.
+        let half = T::from_f64(0.5).unwrap();
+        let p = &problem.quadratic;
+        let mut quadratic: Vec<(usize, usize, T)> = Vec::new();
+        let mut rows: Vec<Vec<(usize, T)>> = vec![Vec::new(); presolve.fixed.len()];
+        let fixed_slot = presolve.fixed_slots();
+        for col in 0..p.ncols {
+            for idx in p.indptr[col]..p.indptr[col + 1] {
+                let (row, value) = (p.indices[idx], p.data[idx]);
+                match (position[row], position[col]) {
+                    (Some(r), Some(c)) => quadratic.push((r, c, value)),
+                    (Some(r), None) => linear[r] += half * value * x[col],
+                    (None, Some(c)) => linear[c] += half * value * x[row],
+                    (None, None) => offset += half * value * x[row] * x[col],
+                }
+                if let Some(slot) = fixed_slot[col] {
+                    rows[slot].push((row, half * value));
+                }
+                if let Some(slot) = fixed_slot[row] {
+                    rows[slot].push((col, half * value));
+                }
+            }
+        }
+        for (column, row) in presolve.fixed.iter_mut().zip(rows) {
+            column.quadratic = row;
+        }
+        let k = presolve.kept.len();
+        let quadratic = CscMatrix::from_triplets(k, k, &quadratic)
+            .expect("kept entries stay inside the reduced matrix");
+
+        let (equalities, inequalities) = presolve.reduce_constraints(
+            problem.equalities.as_ref(),
+            problem.inequalities.as_ref(),
+            &x,
+        );
+        let reduced = ProblemQP {
+            quadratic,
+            linear,
+            inequalities,
+            equalities,
+            bounds: problem
+                .bounds
+                .as_ref()
+                .map(|bounds| presolve.keep_bounds(bounds)),
+            groups: problem.groups.clone(),
+            integrality: presolve.keep_integrality(&problem.integrality),
+            names: presolve.keep_names(&problem.names),
+            sense: problem.sense,
+            offset,
+            quadratic_constraints: Vec::new(),
+        };
+        (reduced, presolve)
+    }
+
+    pub fn lp(problem: &ProblemLP<T>) -> (ProblemLP<T>, Self) {
+        let mut presolve = Self::detect(
+            problem.nvars(),
+            problem.bounds.as_ref(),
+            problem.equalities.as_ref(),
+            problem.inequalities.as_ref(),
+        );
+        if presolve.fixed.is_empty() {
+            return (problem.clone(), presolve);
+        }
+        let x = presolve.values();
+        let mut offset = problem.offset;
+        for column in &mut presolve.fixed {
+            column.linear = problem.cost[column.index];
+            offset += column.linear * column.value;
+        }
+        let (equalities, inequalities) = presolve.reduce_constraints(
+            problem.equalities.as_ref(),
+            problem.inequalities.as_ref(),
+            &x,
+        );
+        let reduced = ProblemLP {
+            cost: presolve.keep(&problem.cost),
+            inequalities,
+            equalities,
+            bounds: problem
+                .bounds
+                .as_ref()
+                .map(|bounds| presolve.keep_bounds(bounds)),
+            groups: problem.groups.clone(),
+            integrality: presolve.keep_integrality(&problem.integrality),
+            names: presolve.keep_names(&problem.names),
+            sense: problem.sense,
+            offset,
+        };
+        (reduced, presolve)
+    }
+
+    // Number of variables substituted out.
+    pub fn eliminated(&self) -> usize {
+        self.fixed.len()
+    }
+
+    // The solution of the original problem from that of the reduced one:
+    // the fixed values go back into the primal, and each fixed variable
+    // gets the bound multiplier that keeps it stationary.
+    pub fn postsolve(&self, mut solution: Solution<T>) -> Solution<T> {
+        solution
+            .stats
+            .presolve
+            .get_or_insert_with(PresolveStats::default)
+            .fixed_variables = self.fixed.len();
+        if self.fixed.is_empty() {
+            return solution;
+        }
+        let mut x = vec![T::zero(); self.nvars];
+        for (&index, &value) in self.kept.iter().zip(&solution.primal) {
+            x[index] = value;
+        }
+        for column in &self.fixed {
+            x[column.index] = column.value;
+        }
+        if solution.primal.len() == self.kept.len() {
+            solution.primal = x.clone();
+        }
+        let (equality, inequality) = self.restore_duals(
+            std::mem::take(&mut solution.equality_dual),
+            std::mem::take(&mut solution.inequality_dual),
+            &x,
+            true,
+        );
+        solution.equality_dual = equality;
+        solution.inequality_dual = inequality;
+        solution.certificate = solution.certificate.map(|certificate| match certificate {
+            Certificate::PrimalInfeasible {
+                equality_dual,
+                inequality_dual,
+            } => {
+                let (equality_dual, inequality_dual) =
+                    self.restore_duals(equality_dual, inequality_dual, &x, false);
+                Certificate::PrimalInfeasible {
+                    equality_dual,
+                    inequality_dual,
+                }
+            }
+            Certificate::DualInfeasible { direction } => Certificate::DualInfeasible {
+                direction: self.expand(&direction, T::zero()),
+            },
+        });
+        if let Some(basis) = &mut solution.basis {
+            if basis.variables.len() == self.kept.len() {
+                let mut variables = vec![BasisStatus::AtLower; self.nvars];
+                for (&index, &status) in self.kept.iter().zip(&basis.variables) {
+                    variables[index] = status;
+                }
+                basis.variables = variables;
+            }
+        }
+        solution
+    }
+
+    fn restore_duals(
+        &self,
+        equality: Vec<T>,
+        inequality: Vec<T>,
+        x: &[T],
+        objective: bool,
+    ) -> (Vec<T>, Vec<T>) {
+        let Some(layout) = DualLayout::of(
+            &equality,
+            &inequality,
+            self.meq,
+            self.mineq,
+            self.kept.len(),
+        ) else {
+            return (equality, inequality);
+        };
+        let [y, z, w] = layout.split(equality, inequality, self.meq, self.mineq);
+        let w = if layout.bounds {
+            let mut full = self.expand(&w, T::zero());
+            for column in &self.fixed {
+                full[column.index] = column.multiplier(x, &y, &z, objective);
+            }
+            full
+        } else {
+            w
+        };
+        layout.join([y, z, w])
+    }
+
+    fn values(&self) -> Vec<T> {
+        let mut x = vec![T::zero(); self.nvars];
+        for column in &self.fixed {
+            x[column.index] = column.value;
+        }
+        x
+    }
+
+    fn positions(&self) -> Vec<Option<usize>> {
+        let mut position = vec![None; self.nvars];
+        for (k, &index) in self.kept.iter().enumerate() {
+            position[index] = Some(k);
+        }
+        position
+    }
+
+    fn fixed_slots(&self) -> Vec<Option<usize>> {
+        let mut slot = vec![None; self.nvars];
+        for (k, column) in self.fixed.iter().enumerate() {
+            slot[column.index] = Some(k);
+        }
+        slot
+    }
+
+    fn keep(&self, values: &[T]) -> Vec<T> {
+        self.kept.iter().map(|&index| values[index]).collect()
+    }
+
+    fn expand(&self, values: &[T], fill: T) -> Vec<T> {
+        let mut full = vec![fill; self.nvars];
+        for (&index, &value) in self.kept.iter().zip(values) {
+            full[index] = value;
+        }
+        full
+    }
+
+    fn keep_bounds(&self, bounds: &Bounds<T>) -> Bounds<T> {
+        Bounds {
+            lower: self.keep(&bounds.lower),
+            upper: self.keep(&bounds.upper),
+        }
+    }
+
+    fn keep_names(&self, names: &Names) -> Names {
+        let variables = if names.variables.is_empty() {
+            Vec::new()
+        } else {
+            self.kept
+                .iter()
+                .map(|&index| names.variables[index].clone())
+                .collect()
+        };
+        Names {
+            variables,
+            ..names.clone()
+        }
+    }
+
+    fn keep_integrality(&self, integrality: &[bool]) -> Vec<bool> {
+        if integrality.is_empty() {
+            return Vec::new();
+        }
+        self.kept.iter().map(|&index| integrality[index]).collect()
+    }
+
+    // The constraint matrices without the fixed columns and the right-hand
+    // sides less their contribution, with the columns kept for postsolve.
+    fn reduce_constraints(
+        &mut self,
+        equalities: Option<&EqualityConstraints<T>>,
+        inequalities: Option<&InequalityConstraints<T>>,
+        x: &[T],
+    ) -> (
+        Option<EqualityConstraints<T>>,
+        Option<InequalityConstraints<T>>,
+    ) {
+        let equalities = equalities.map(|eq| {
+            for column in &mut self.fixed {
+                column.equality = entries(&eq.matrix, column.index);
+            }
+            let (matrix, rhs) = self.reduce_matrix(&eq.matrix, &eq.rhs, x);
+            EqualityConstraints { matrix, rhs }
+        });
+        let inequalities = inequalities.map(|ineq| {
+            for column in &mut self.fixed {
+                column.inequality = entries(&ineq.matrix, column.index);
+            }
+            let (matrix, rhs) = self.reduce_matrix(&ineq.matrix, &ineq.rhs, x);
+            InequalityConstraints { matrix, rhs }
+        });
+        (equalities, inequalities)
+    }
+
+    fn reduce_matrix(&self, matrix: &CscMatrix<T>, rhs: &[T], x: &[T]) -> (CscMatrix<T>, Vec<T>) {
+        let mut reduced = CscMatrix {
+            nrows: matrix.nrows,
+            ncols: self.kept.len(),
+            indptr: vec![0],
+            indices: Vec::new(),
+            data: Vec::new(),
+        };
+        for &col in &self.kept {
+            let range = matrix.indptr[col]..matrix.indptr[col + 1];
+            reduced
+                .indices
+                .extend_from_slice(&matrix.indices[range.clone()]);
+            reduced.data.extend_from_slice(&matrix.data[range]);
+            reduced.indptr.push(reduced.indices.len());
+        }
+        let mut rhs = rhs.to_vec();
+        for column in &self.fixed {
+            for (row, value) in entries(matrix, column.index) {
+                rhs[row] -= value * x[column.index];
+            }
+        }
+        (reduced, rhs)
+    }
+}
+
+// A constraint row as its nonzero entries, in column order.
+type SparseRow<T> = Vec<(usize, T)>;
+
+// Rows that can go without changing the feasible set: scaled copies of a
+// row that is at least as tight, and inequalities that hold everywhere
+// within the bounds. Their multipliers are zero in postsolve, which keeps
+// the kept rows' multipliers optimal.
+#[derive(Debug, Clone)]
+pub struct RedundantRows {
+    meq: usize,
+    mineq: usize,
+    nbounds: usize,
+    // Original index of every row left in the reduced problem.
+    equalities: Vec<usize>,
+    inequalities: Vec<usize>,
+    duplicates: usize,
+    redundant: usize,
+}
+
+impl RedundantRows {
+    fn detect<T: RealNumber>(
+        nvars: usize,
+        bounds: Option<&Bounds<T>>,
+        equalities: Option<&EqualityConstraints<T>>,
+        inequalities: Option<&InequalityConstraints<T>>,
+    ) -> Self {
+        let eq_rows = equalities.map_or_else(Vec::new, |eq| sparse_rows(&eq.matrix));
+        let ineq_rows = inequalities.map_or_else(Vec::new, |ineq| sparse_rows(&ineq.matrix));
+        let eq_rhs = equalities.map_or(&[][..], |eq| &eq.rhs[..]);
+        let ineq_rhs = inequalities.map_or(&[][..], |ineq| &ineq.rhs[..]);
+        let mut keep_eq = vec![true; eq_rows.len()];
+        let mut keep_ineq = vec![true; ineq_rows.len()];
+        let (mut duplicates, mut redundant) = (0, 0);
+
+        for (i, row) in eq_rows.iter().enumerate() {
+            if row.is_empty() && eq_rhs[i] == T::zero() {
+                keep_eq[i] = false;
+                redundant += 1;
+            }
+        }
+        for (i, row) in ineq_rows.iter().enumerate() {
+            if max_activity(row, bounds) <= ineq_rhs[i] {
+                keep_ineq[i] = false;
+                redundant += 1;
+            }
+        }
+
+        // Rows can only be parallel when they share a pattern; equalities
+        // go first so that an inequality is always weighed against them.
+        let tolerance = T::epsilon() * T::from_f64(16.0).unwrap();
+        let mut kept: HashMap<Vec<usize>, Vec<ConstraintRow>> = HashMap::new();
+        let candidates: Vec<_> = (0..eq_rows.len())
+            .filter(|&i| keep_eq[i])
+            .map(ConstraintRow::Equality)
+            .chain(
+                (0..ineq_rows.len())
+                    .filter(|&i| keep_ineq[i])
+                    .map(ConstraintRow::Inequality),
+            )
+            .collect();
+        for candidate in candidates {
+            let (row, rhs) = match candidate {
+                ConstraintRow::Equality(i) => (&eq_rows[i], eq_rhs[i]),
+                ConstraintRow::Inequality(i) => (&ineq_rows[i], ineq_rhs[i]),
+            };
+            let pattern: Vec<usize> = row.iter().map(|&(col, _)| col).collect();
+            let representatives = kept.entry(pattern).or_default();
+            let mut duplicate = false;
+            for representative in representatives.iter_mut() {
+                let (base, base_rhs) = match *representative {
+                    ConstraintRow::Equality(k) => (&eq_rows[k], eq_rhs[k]),
+                    ConstraintRow::Inequality(k) => (&ineq_rows[k], ineq_rhs[k]),
+                };
+                let Some(ratio) = parallel(row, base, tolerance) else {
+                    continue;
+                };
+                let scaled = ratio * base_rhs;
+                match (*representative, candidate) {
+                    // An inconsistent pair is left for the solve to report.
+                    (ConstraintRow::Equality(_), ConstraintRow::Equality(i))
+                        if (rhs - scaled).abs() <= tolerance * rhs.abs().max(scaled.abs()) =>
+                    {
+                        keep_eq[i] = false;
+                        duplicate = true;
+                    }
+                    (ConstraintRow::Equality(_), ConstraintRow::Inequality(i)) if scaled <= rhs => {
+                        keep_ineq[i] = false;
+                        duplicate = true;
+                    }
+                    // Opposite signs bound the row from both sides instead.
+                    (ConstraintRow::Inequality(k), ConstraintRow::Inequality(i))
+                        if ratio > T::zero() =>
+                    {
+                        if rhs / ratio >= base_rhs {
+                            keep_ineq[i] = false;
+                        } else {
+                            keep_ineq[k] = false;
+                            *representative = candidate;
+                        }
+                        duplicate = true;
+                    }
+                    _ => {}
+                }
+                if duplicate {
+                    break;
+                }
+            }
+            if duplicate {
+                duplicates += 1;
+            } else {
+                representatives.push(candidate);
+            }
+        }
+
+        let kept_rows = |keep: Vec<bool>| (0..keep.len()).filter(|&i| keep[i]).collect();
+        Self {
+            meq: eq_rows.len(),
+            mineq: ineq_rows.len(),
+            nbounds: bounds.map_or(0, |_| nvars),
+            equalities: kept_rows(keep_eq),
+            inequalities: kept_rows(keep_ineq),
+            duplicates,
+            redundant,
+        }
+    }
+
+    pub fn qp<T: RealNumber>(problem: &ProblemQP<T>) -> (ProblemQP<T>, Self) {
+        let presolve = Self::detect(
+            problem.nvars(),
+            problem.bounds.as_ref(),
+            problem.equalities.as_ref(),
+            problem.inequalities.as_ref(),
+        );
+        let mut reduced = problem.clone();
+        if presolve.removed() > 0 {
+            reduced.equalities = presolve.keep_equalities(problem.equalities.as_ref());
+            reduced.inequalities = presolve.keep_inequalities(problem.inequalities.as_ref());
+            reduced.groups = presolve.keep_groups(&problem.groups);
+            reduced.names = presolve.keep_names(&problem.names);
+        }
+        (reduced, presolve)
+    }
+
+    pub fn lp<T: RealNumber>(problem: &ProblemLP<T>) -> (ProblemLP<T>, Self) {
+        let presolve = Self::detect(
+            problem.nvars(),
+            problem.bounds.as_ref(),
+            problem.equalities.as_ref(),
+            problem.inequalities.as_ref(),
+        );
+        let mut reduced = problem.clone();
+        if presolve.removed() > 0 {
+            reduced.equalities = presolve.keep_equalities(problem.equalities.as_ref());
+            reduced.inequalities = presolve.keep_inequalities(problem.inequalities.as_ref());
+            reduced.groups = presolve.keep_groups(&problem.groups);
+            reduced.names = presolve.keep_names(&problem.names);
+        }
+        (reduced, presolve)
+    }
+
+    // Number of rows taken out.
+    pub fn removed(&self) -> usize {
+        self.duplicates + self.redundant
+    }
+
+    // The solution of the original problem from that of the reduced one:
+    // the removed rows get zero multipliers and, in a basis, a basic slack.
+    pub fn postsolve<T: RealNumber>(&self, mut solution: Solution<T>) -> Solution<T> {
+        let stats = solution
+            .stats
+            .presolve
+            .get_or_insert_with(PresolveStats::default);
+        stats.duplicate_rows = self.duplicates;
+        stats.redundant_rows = self.redundant;
+        if self.removed() == 0 {
+            return solution;
+        }
+        let (equality, inequality) = self.restore_duals(
+            std::mem::take(&mut solution.equality_dual),
+            std::mem::take(&mut solution.inequality_dual),
+        );
+        solution.equality_dual = equality;
+        solution.inequality_dual = inequality;
+        if let Some(Certificate::PrimalInfeasible {
+            equality_dual,
+            inequality_dual,
+        }) = &mut solution.certificate
+        {
+            let (equality, inequality) = self.restore_duals(
+                std::mem::take(equality_dual),
+                std::mem::take(inequality_dual),
+            );
+            *equality_dual = equality;
+            *inequality_dual = inequality;
+        }
+        if let Some(basis) = &mut solution.basis {
+            if basis.inequalities.len() == self.inequalities.len() {
+                basis.inequalities = expand_rows(
+                    &basis.inequalities,
+                    &self.inequalities,
+                    self.mineq,
+                    BasisStatus::Basic,
+                );
+            }
+        }
+        if let Some(active) = &mut solution.stats.active_set {
+            for row in &mut active.inequalities {
+                *row = self.inequalities[*row];
+            }
+        }
+        solution
+    }
+
+    fn restore_duals<T: RealNumber>(
+        &self,
+        equality: Vec<T>,
+        inequality: Vec<T>,
+    ) -> (Vec<T>, Vec<T>) {
+        let (meq, mineq) = (self.equalities.len(), self.inequalities.len());
+        let Some(layout) = DualLayout::of(&equality, &inequality, meq, mineq, self.nbounds) else {
+            return (equality, inequality);
+        };
+        let [y, z, w] = layout.split(equality, inequality, meq, mineq);
+        layout.join([
+            expand_rows(&y, &self.equalities, self.meq, T::zero()),
+            expand_rows(&z, &self.inequalities, self.mineq, T::zero()),
+            w,
+        ])
+    }
+
+    fn keep_equalities<T: RealNumber>(
+        &self,
+        equalities: Option<&EqualityConstraints<T>>,
+    ) -> Option<EqualityConstraints<T>> {
+        equalities.map(|eq| {
+            let (matrix, rhs) = keep_rows(&eq.matrix, &eq.rhs, &self.equalities);
+            EqualityConstraints { matrix, rhs }
+        })
+    }
+
+    fn keep_inequalities<T: RealNumber>(
+        &self,
+        inequalities: Option<&InequalityConstraints<T>>,
+    ) -> Option<InequalityConstraints<T>> {
+        inequalities.map(|ineq| {
+            let (matrix, rhs) = keep_rows(&ineq.matrix, &ineq.rhs, &self.inequalities);
+            InequalityConstraints { matrix, rhs }
+        })
+    }
+
+    fn keep_names(&self, names: &Names) -> Names {
+        let keep = |names: &[String], kept: &[usize]| -> Vec<String> {
+            if names.is_empty() {
+                return Vec::new();
+            }
+            kept.iter().map(|&row| names[row].clone()).collect()
+        };
+        Names {
+            variables: names.variables.clone(),
+            equalities: keep(&names.equalities, &self.equalities),
+            inequalities: keep(&names.inequalities, &self.inequalities),
+        }
+    }
+
+    // Groups name the rows they kept, renumbered.
+    fn keep_groups(&self, groups: &[ConstraintGroup]) -> Vec<ConstraintGroup> {
+        let equality = positions(&self.equalities, self.meq);
+        let inequality = positions(&self.inequalities, self.mineq);
+        groups
+            .iter()
+            .map(|group| ConstraintGroup {
+                name: group.name.clone(),
+                rows: group
+                    .rows
+                    .iter()
+                    .filter_map(|&row| match row {
+                        ConstraintRow::Equality(i) => equality[i].map(ConstraintRow::Equality),
+                        ConstraintRow::Inequality(i) => {
+                            inequality[i].map(ConstraintRow::Inequality)
+                        }
+                    })
+                    .collect(),
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Side {
+    Lower,
+    Upper,
+}
+
+// A bound of `variable` implied by a row, read as a'x ≤ h' with a' and h'
+// the row negated when `negated`: a'ⱼxⱼ is at most h' less the smallest
+// value the other terms take within the bounds.
+#[derive(Debug, Clone, Copy)]
+struct Implication<T> {
+    variable: usize,
+    side: Side,
+    row: ConstraintRow,
+    negated: bool,
+    coefficient: T,
+}
+
+// Bounds tightened to those the rows imply, one row at a time, until they
+// stop moving. The feasible set does not change, but ADMM projects onto a
+// smaller box, and a row that cannot be met within the bounds shows the
+// problem infeasible before any factorization.
+#[derive(Debug, Clone)]
+pub struct ImpliedBounds<T> {
+    nvars: usize,
+    equalities: Vec<SparseRow<T>>,
+    inequalities: Vec<SparseRow<T>>,
+    implications: Vec<Implication<T>>,
+    // Farkas multipliers of the original problem, split as in a solution
+    // with separate equality multipliers, when the tightening found it
+    // infeasible.
+    certificate: Option<(Vec<T>, Vec<T>)>,
+}
+
+impl<T> ImpliedBounds<T>
+where
+    T: RealNumber,
+{
+    fn detect(
+        nvars: usize,
+        bounds: Option<&Bounds<T>>,
+        equalities: Option<&EqualityConstraints<T>>,
+        inequalities: Option<&InequalityConstraints<T>>,
+    ) -> (Option<Bounds<T>>, Self) {
+        let mut presolve = Self {
+            nvars,
+            equalities: equalities.map_or_else(Vec::new, |eq| sparse_rows(&eq.matrix)),
+            inequalities: inequalities.map_or_else(Vec::new, |ineq| sparse_rows(&ineq.matrix)),
+            implications: Vec::new(),
+            certificate: None,
+        };
+        // Without bounds only singleton rows imply any, and adding a box
+        // would give ADMM more rows rather than fewer.
+        let Some(bounds) = bounds else {
+            return (None, presolve);
+        };
+        let mut bounds = bounds.clone();
+        let eq_rhs = equalities.map_or(&[][..], |eq| &eq.rhs[..]);
+        let ineq_rhs = inequalities.map_or(&[][..], |ineq| &ineq.rhs[..]);
+        let senses: Vec<(ConstraintRow, bool, T)> = (0..eq_rhs.len())
+            .flat_map(|i| {
+                [
+                    (ConstraintRow::Equality(i), false, eq_rhs[i]),
+                    (ConstraintRow::Equality(i), true, -eq_rhs[i]),
+                ]
+            })
+            .chain((0..ineq_rhs.len()).map(|i| (ConstraintRow::Inequality(i), false, ineq_rhs[i])))
+            .collect();
+        for _ in 0..MAX_TIGHTENING_PASSES {
+            let mut changed = false;
+            for &(row, negated, rhs) in &senses {
+                if let Err(conflict) =
+                    presolve.tighten(&mut bounds, row, negated, rhs, &mut changed)
+                {
+                    presolve.certificate = Some(presolve.farkas(conflict));
+                    return (Some(bounds), presolve);
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+        (Some(bounds), presolve)
+    }
+
+    // Tightens the bounds with one sense of one row; each bound it moves is
+    // on the side the row's smallest activity does not use, so the
+    // activity stays valid throughout. On infeasibility, returns the
+    // reduced problem's Farkas multipliers split as (y, z, w⁻, w⁺), scaled
+    // to a value of -1.
+    fn tighten(
+        &mut self,
+        bounds: &mut Bounds<T>,
+        row: ConstraintRow,
+        negated: bool,
+        rhs: T,
+        changed: &mut bool,
+    ) -> Result<(), [Vec<T>; 4]> {
+        let sign = if negated { -T::one() } else { T::one() };
+        let entries = match row {
+            ConstraintRow::Equality(i) => &self.equalities[i],
+            ConstraintRow::Inequality(i) => &self.inequalities[i],
+        };
+        // Finite part of the smallest activity, and the terms that are
+        // unbounded below.
+        let mut activity = T::zero();
+        let mut unbounded = Vec::new();
+        for &(col, value) in entries {
+            let value = sign * value;
+            let bound = if value > T::zero() {
+                bounds.lower[col]
+            } else {
+                bounds.upper[col]
+            };
+            if bound.is_infinite() {
+                unbounded.push(col);
+            } else {
+                activity += value * bound;
+            }
+        }
+        let tolerance = T::from_f64(FEASIBILITY_TOLERANCE).unwrap();
+        if unbounded.is_empty() && activity - rhs > tolerance * rhs.abs().max(T::one()) {
+            let scale = (activity - rhs).recip();
+            let mut conflict = self.zero_multipliers();
+            match row {
+                ConstraintRow::Equality(i) => conflict[0][i] = sign * scale,
+                ConstraintRow::Inequality(i) => conflict[1][i] = scale,
+            }
+            for &(col, value) in entries {
+                let value = sign * value;
+                let side = if value > T::zero() { 2 } else { 3 };
+                conflict[side][col] = -value * scale;
+            }
+            return Err(conflict);
+        }
+        if unbounded.len() > 1 {
+            return Ok(());
+        }
+
+        let step = T::from_f64(MIN_TIGHTENING).unwrap();
+        for &(col, value) in entries {
+            let value = sign * value;
+            let rest = match unbounded[..] {
+                [] if value > T::zero() => activity - value * bounds.lower[col],
+                [] => activity - value * bounds.upper[col],
+                [only] if only == col => activity,
+                _ => continue,
+            };
+            let implied = (rhs - rest) / value;
+            let (side, current, opposite) = if value > T::zero() {
+                (Side::Upper, bounds.upper[col], bounds.lower[col])
+            } else {
+                (Side::Lower, bounds.lower[col], bounds.upper[col])
+            };
+            let gain = match side {
+                Side::Upper => current - implied,
+                Side::Lower => implied - current,
+            };
+            if !implied.is_finite() || gain <= step * implied.abs().max(T::one()) {
+                continue;
+            }
+            let crossing = match side {
+                Side::Upper => opposite - implied,
+                Side::Lower => implied - opposite,
+            };
+            let conflict = crossing > tolerance * opposite.abs().max(T::one());
+            // Bounds crossed by no more than rounding meet instead.
+            let implied = if crossing > T::zero() && !conflict {
+                opposite
+            } else {
+                implied
+            };
+            match side {
+                Side::Upper => bounds.upper[col] = implied,
+                Side::Lower => bounds.lower[col] = implied,
+            }
+            self.implications.push(Implication {
+                variable: col,
+                side,
+                row,
+                negated,
+                coefficient: value,
+            });
+            *changed = true;
+            if conflict {
+                let scale = crossing.recip();
+                let mut multipliers = self.zero_multipliers();
+                multipliers[2][col] = -scale;
+                multipliers[3][col] = scale;
+                return Err(multipliers);
+            }
+        }
+        Ok(())
+    }
+
+    fn zero_multipliers(&self) -> [Vec<T>; 4] {
+        [
+            vec![T::zero(); self.equalities.len()],
+            vec![T::zero(); self.inequalities.len()],
+            vec![T::zero(); self.nvars],
+            vec![T::zero(); self.nvars],
+        ]
+    }
+
+    fn farkas(&self, [mut y, mut z, mut lower, mut upper]: [Vec<T>; 4]) -> (Vec<T>, Vec<T>) {
+        self.unwind(&mut y, &mut z, &mut lower, &mut upper);
+        z.extend(
+            lower
+                .iter()
+                .zip(&upper)
+                .map(|(&lower, &upper)| lower + upper),
+        );
+        (y, z)
+    }
+
+    // Hands the multiplier of every implied bound back to the row that
+    // implied it, latest first. A multiplier m on xⱼ's bound becomes m / a'ⱼ
+    // on the row, and the row's other variables take up the rest of its
+    // gradient on the bounds its smallest activity used. Stationarity and
+    // the value of a Farkas certificate carry over unchanged.
+    fn unwind(&self, y: &mut [T], z: &mut [T], lower: &mut [T], upper: &mut [T]) {
+        for implication in self.implications.iter().rev() {
+            let j = implication.variable;
+            let multiplier = match implication.side {
+                Side::Lower => std::mem::replace(&mut lower[j], T::zero()),
+                Side::Upper => std::mem::replace(&mut upper[j], T::zero()),
+            };
+            if multiplier == T::zero() {
+                continue;
+            }
+            let delta = multiplier / implication.coefficient;
+            let sign = if implication.negated {
+                -T::one()
+            } else {
+                T::one()
+            };
+            let entries = match implication.row {
+                ConstraintRow::Equality(i) => {
+                    y[i] += sign * delta;
+                    &self.equalities[i]
+                }
+                ConstraintRow::Inequality(i) => {
+                    z[i] += delta;
+                    &self.inequalities[i]
+                }
+            };
+            for &(col, value) in entries {
+                let value = sign * value;
+                if col == j {
+                    continue;
+                }
+                if value > T::zero() {
+                    lower[col] -= value * delta;
+                } else {
+                    upper[col] -= value * delta;
+                }
+            }
+        }
+    }
+
+    pub fn qp(problem: &ProblemQP<T>) -> (ProblemQP<T>, Self) {
+        // Quadratic constraint multipliers are not carried through the
+        // unwinding, so those problems keep their bounds.
+        let bounds = problem
+            .bounds
+            .as_ref()
+            .filter(|_| problem.quadratic_constraints.is_empty());
+        let (tightened, presolve) = Self::detect(
+            problem.nvars(),
+            bounds,
+            problem.equalities.as_ref(),
+            problem.inequalities.as_ref(),
+        );
+        let mut reduced = problem.clone();
+        if tightened.is_some() {
+            reduced.bounds = tightened;
+        }
+        (reduced, presolve)
+    }
+
+    pub fn lp(problem: &ProblemLP<T>) -> (ProblemLP<T>, Self) {
+        let (bounds, presolve) = Self::detect(
+            problem.nvars(),
+            problem.bounds.as_ref(),
+            problem.equalities.as_ref(),
+            problem.inequalities.as_ref(),
+        );
+        let mut reduced = problem.clone();
+        reduced.bounds = bounds;
+        (reduced, presolve)
+    }
+
+    // Number of variable bounds moved, counting each side once.
+    pub fn tightened(&self) -> usize {
+        self.implications
+            .iter()
+            .map(|implication| (implication.variable, implication.side))
+            .collect::<HashSet<_>>()
+            .len()
+    }
+
+    // The solution to report without solving when tightening showed the
+    // problem infeasible.
+    pub fn infeasible(&self) -> Option<Solution<T>> {
+        let (equality_dual, inequality_dual) = self.certificate.clone()?;
+        let mut solution =
+            Solution::with_capacity(self.nvars, equality_dual.len(), inequality_dual.len());
+        solution.status = Status::PrimalInfeasible;
+        solution.objective_value = T::infinity();
+        solution.certificate = Some(Certificate::PrimalInfeasible {
+            equality_dual,
+            inequality_dual,
+        });
+        solution.stats.presolve = Some(PresolveStats {
+            tightened_bounds: self.tightened(),
+            ..PresolveStats::default()
+        });
+        Some(solution)
+    }
+
+    // The solution of the original problem from that of the tightened one:
+    // the primal is unchanged, the multipliers on implied bounds move back
+    // onto their rows, and a variable resting on an implied bound trades
+    // places in the basis with the slack of the row behind it.
+    pub fn postsolve(&self, mut solution: Solution<T>) -> Solution<T> {
+        solution
+            .stats
+            .presolve
+            .get_or_insert_with(PresolveStats::default)
+            .tightened_bounds = self.tightened();
+        if self.implications.is_empty() {
+            return solution;
+        }
+        let (equality, inequality) = self.restore_duals(
+            std::mem::take(&mut solution.equality_dual),
+            std::mem::take(&mut solution.inequality_dual),
+        );
+        solution.equality_dual = equality;
+        solution.inequality_dual = inequality;
+        if let Some(Certificate::PrimalInfeasible {
+            equality_dual,
+            inequality_dual,
+        }) = &mut solution.certificate
+        {
+            let (equality, inequality) = self.restore_duals(
+                std::mem::take(equality_dual),
+                std::mem::take(inequality_dual),
+            );
+            *equality_dual = equality;
+            *inequality_dual = inequality;
+        }
+        if let Some(basis) = solution.basis.take() {
+            solution.basis = self.restore_basis(basis);
+        }
+        solution
+    }
+
+    fn restore_duals(&self, equality: Vec<T>, inequality: Vec<T>) -> (Vec<T>, Vec<T>) {
+        let (meq, mineq) = (self.equalities.len(), self.inequalities.len());
+        let layout = DualLayout::of(&equality, &inequality, meq, mineq, self.nvars);
+        let Some(layout) = layout.filter(|layout| layout.bounds) else {
+            return (equality, inequality);
+        };
+        let [mut y, mut z, w] = layout.split(equality, inequality, meq, mineq);
+        let mut lower: Vec<T> = w.iter().map(|&w| w.min(T::zero())).collect();
+        let mut upper: Vec<T> = w.iter().map(|&w| w.max(T::zero())).collect();
+        self.unwind(&mut y, &mut z, &mut lower, &mut upper);
+        let w = lower
+            .iter()
+            .zip(&upper)
+            .map(|(&lower, &upper)| lower + upper)
+            .collect();
+        layout.join([y, z, w])
+    }
+
+    // A variable resting on an implied bound enters the basis in place of
+    // the slack of the row behind it or, the row being tight, of a variable
+    // of the row resting on one of its own bounds. None when neither is
+    // basic, as no vertex of the original problem then matches the basis.
+    fn restore_basis(&self, mut basis: Basis) -> Option<Basis> {
+        if basis.variables.len() != self.nvars
+            || basis.inequalities.len() != self.inequalities.len()
+        {
+            return Some(basis);
+        }
+        let tightened: HashSet<_> = self
+            .implications
+            .iter()
+            .map(|implication| (implication.variable, implication.side))
+            .collect();
+        let mut seen = HashSet::new();
+        for implication in self.implications.iter().rev() {
+            let j = implication.variable;
+            if !seen.insert((j, implication.side))
+                || basis.variables[j] != resting(implication.side)
+            {
+                continue;
+            }
+            let sign = if implication.negated {
+                -T::one()
+            } else {
+                T::one()
+            };
+            let entries = match implication.row {
+                ConstraintRow::Inequality(i) if basis.inequalities[i] == BasisStatus::Basic => {
+                    basis.variables[j] = BasisStatus::Basic;
+                    basis.inequalities[i] = BasisStatus::AtUpper;
+                    continue;
+                }
+                ConstraintRow::Equality(i) => &self.equalities[i],
+                ConstraintRow::Inequality(i) => &self.inequalities[i],
+            };
+            let leaving = entries.iter().find_map(|&(col, value)| {
+                let side = if sign * value > T::zero() {
+                    Side::Lower
+                } else {
+                    Side::Upper
+                };
+                (col != j
+                    && basis.variables[col] == BasisStatus::Basic
+                    && !tightened.contains(&(col, side)))
+                .then_some((col, side))
+            });
+            let (col, side) = leaving?;
+            basis.variables[col] = resting(side);
+            basis.variables[j] = BasisStatus::Basic;
+        }
+        Some(basis)
+    }
+}
+
+fn resting(side: Side) -> BasisStatus {
+    match side {
+        Side::Lower => BasisStatus::AtLower,
+        Side::Upper => BasisStatus::AtUpper,
+    }
+}
+
+// Presolve steps run in order, each on the problem the previous one left.
+pub struct PresolvePipeline<T> {
+    presolvers: Vec<Box<dyn Presolver<T>>>,
+}
+
+impl<T> PresolvePipeline<T>
+where
+    T: RealNumber,
+{
+    // A pipeline without steps, which leaves problems alone.
+    pub fn new() -> Self {
+        Self {
+            presolvers: Vec::new(),
+        }
+    }
+
+    pub fn with(mut self, presolver: impl Presolver<T> + 'static) -> Self {
+        self.presolvers.push(Box::new(presolver));
+        self
+    }
+
+    pub fn qp(&self, problem: &ProblemQP<T>) -> (ProblemQP<T>, PostsolveStack<T>) {
+        let mut problem = problem.clone();
+        let mut stack = PostsolveStack::new();
+        for presolver in &self.presolvers {
+            let (reduced, step) = presolver.presolve_qp(&problem);
+            problem = reduced;
+            if stack.push(step) {
+                break;
+            }
+        }
+        (problem, stack)
+    }
+
+    pub fn lp(&self, problem: &ProblemLP<T>) -> (ProblemLP<T>, PostsolveStack<T>) {
+        let mut problem = problem.clone();
+        let mut stack = PostsolveStack::new();
+        for presolver in &self.presolvers {
+            let (reduced, step) = presolver.presolve_lp(&problem);
+            problem = reduced;
+            if stack.push(step) {
+                break;
+            }
+        }
+        (problem, stack)
+    }
+}
+
+// Fixed variables out, then bounds tightened, then the rows that became
+// redundant out.
+impl<T> Default for PresolvePipeline<T>
+where
+    T: RealNumber,
+{
+    fn default() -> Self {
+        Self::new()
+            .with(RemoveFixedVariables)
+            .with(TightenBounds)
+            .with(RemoveRedundantRows)
+    }
+}
+
+// The transformations a pipeline applied, first to last, undone last to
+// first.
+pub struct PostsolveStack<T> {
+    steps: Vec<Box<dyn Transformation<T>>>,
+}
+
+impl<T> PostsolveStack<T>
+where
+    T: RealNumber,
+{
+    pub fn new() -> Self {
+        Self { steps: Vec::new() }
+    }
+
+    // Returns whether the step proved the problem infeasible, after which
+    // nothing should be pushed.
+    pub fn push(&mut self, step: Box<dyn Transformation<T>>) -> bool {
+        let infeasible = step.infeasible().is_some();
+        self.steps.push(step);
+        infeasible
+    }
+
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    // The solution of the original problem from that of the last reduced
+    // one.
+    pub fn postsolve(&self, solution: Solution<T>) -> Solution<T> {
+        undo(&self.steps, solution)
+    }
+
+    // The proof of infeasibility the last step found, mapped back through
+    // the steps before it.
+    pub fn infeasible(&self) -> Option<Solution<T>> {
+        let (last, earlier) = self.steps.split_last()?;
+        Some(undo(earlier, last.infeasible()?))
+    }
+}
+
+impl<T> Default for PostsolveStack<T>
+where
+    T: RealNumber,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn undo<T: RealNumber>(steps: &[Box<dyn Transformation<T>>], solution: Solution<T>) -> Solution<T> {
+    steps
+        .iter()
+        .rev()
+        .fold(solution, |solution, step| step.postsolve(solution))
+}
+
+// Substitutes out the variables whose bounds coincide; see FixedVariables.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RemoveFixedVariables;
+
+impl<T: RealNumber> Presolver<T> for RemoveFixedVariables {
+    fn presolve_qp(&self, problem: &ProblemQP<T>) -> (ProblemQP<T>, Box<dyn Transformation<T>>) {
+        let (reduced, step) = FixedVariables::qp(problem);
+        (reduced, Box::new(step))
+    }
+
+    fn presolve_lp(&self, problem: &ProblemLP<T>) -> (ProblemLP<T>, Box<dyn Transformation<T>>) {
+        let (reduced, step) = FixedVariables::lp(problem);
+        (reduced, Box::new(step))
+    }
+}
+
+// Tightens the bounds to those the rows imply; see ImpliedBounds.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TightenBounds;
+
+impl<T: RealNumber> Presolver<T> for TightenBounds {
+    fn presolve_qp(&self, problem: &ProblemQP<T>) -> (ProblemQP<T>, Box<dyn Transformation<T>>) {
+        let (reduced, step) = ImpliedBounds::qp(problem);
+        (reduced, Box::new(step))
+    }
+
+    fn presolve_lp(&self, problem: &ProblemLP<T>) -> (ProblemLP<T>, Box<dyn Transformation<T>>) {
+        let (reduced, step) = ImpliedBounds::lp(problem);
+        (reduced, Box::new(step))
+    }
+}
+
+// Drops duplicate rows and those the bounds satisfy; see RedundantRows.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RemoveRedundantRows;
+
+impl<T: RealNumber> Presolver<T> for RemoveRedundantRows {
+    fn presolve_qp(&self, problem: &ProblemQP<T>) -> (ProblemQP<T>, Box<dyn Transformation<T>>) {
+        let (reduced, step) = RedundantRows::qp(problem);
+        (reduced, Box::new(step))
+    }
+
+    fn presolve_lp(&self, problem: &ProblemLP<T>) -> (ProblemLP<T>, Box<dyn Transformation<T>>) {
+        let (reduced, step) = RedundantRows::lp(problem);
+        (reduced, Box::new(step))
+    }
+}
+
+// Runs a scaler as a presolve step, on a copy of it so every problem gets
+// its own factors; the scaler's unscaling maps the solution back.
+#[derive(Debug, Clone, Default)]
+pub struct ScaleProblem<S>(pub S);
+
+impl<T, S> Presolver<T> for ScaleProblem<S>
+where
+    T: RealNumber,
+    S: Scaler<T> + Clone + Send + Sync + 'static,
+{
+    fn presolve_qp(&self, problem: &ProblemQP<T>) -> (ProblemQP<T>, Box<dyn Transformation<T>>) {
+        let mut scaler = self.0.clone();
+        let mut scaled = problem.clone();
+        match scaler.scale_qp(&mut scaled) {
+            Ok(()) => (scaled, Box::new(Unscale(scaler))),
+            Err(_) => (problem.clone(), Box::new(Unchanged)),
+        }
+    }
+
+    fn presolve_lp(&self, problem: &ProblemLP<T>) -> (ProblemLP<T>, Box<dyn Transformation<T>>) {
+        let mut scaler = self.0.clone();
+        let mut scaled = problem.clone();
+        match scaler.scale_lp(&mut scaled) {
+            Ok(()) => (scaled, Box::new(Unscale(scaler))),
+            Err(_) => (problem.clone(), Box::new(Unchanged)),
+        }
+    }
+}
+
+struct Unscale<S>(S);
+
+impl<T: RealNumber, S: Scaler<T>> Transformation<T> for Unscale<S> {
+    fn postsolve(&self, mut solution: Solution<T>) -> Solution<T> {
+        self.0.unscale_primal(&mut solution.primal);
+        self.0
+            .unscale_dual(&mut solution.equality_dual, &mut solution.inequality_dual);
+        solution.objective_value = self.0.unscale_objective(solution.objective_value);
+        self.0.unscale_stats(&mut solution.stats);
+        match &mut solution.certificate {
+            Some(Certificate::PrimalInfeasible {
+                equality_dual,
+                inequality_dual,
+            }) => self.0.unscale_dual(equality_dual, inequality_dual),
+            Some(Certificate::DualInfeasible { direction }) => self.0.unscale_primal(direction),
+            None => {}
+        }
+        solution
+    }
+}
+
+struct Unchanged;
+
+impl<T: RealNumber> Transformation<T> for Unchanged {
+    fn postsolve(&self, solution: Solution<T>) -> Solution<T> {
+        solution
+    }
+}
+
+impl<T: RealNumber> Transformation<T> for FixedVariables<T> {
+    fn postsolve(&self, solution: Solution<T>) -> Solution<T> {
+        FixedVariables::postsolve(self, solution)
+    }
+}
+
+impl<T: RealNumber> Transformation<T> for ImpliedBounds<T> {
+    fn postsolve(&self, solution: Solution<T>) -> Solution<T> {
+        ImpliedBounds::postsolve(self, solution)
+    }
+
+    fn infeasible(&self) -> Option<Solution<T>> {
+        ImpliedBounds::infeasible(self)
+    }
+}
+
+impl<T: RealNumber> Transformation<T> for RedundantRows {
+    fn postsolve(&self, solution: Solution<T>) -> Solution<T> {
+        RedundantRows::postsolve(self, solution)
+    }
+}
+
+fn sparse_rows<T: RealNumber>(matrix: &CscMatrix<T>) -> Vec<SparseRow<T>> {
+    let csr = matrix.to_csr();
+    (0..csr.nrows)
+        .map(|row| {
+            (csr.indptr[row]..csr.indptr[row + 1])
+                .filter(|&idx| csr.data[idx] != T::zero())
+                .map(|idx| (csr.indices[idx], csr.data[idx]))
+                .collect()
+        })
+        .collect()
+}
+
+// The largest value the row takes within the bounds, infinite without them.
+fn max_activity<T: RealNumber>(row: &[(usize, T)], bounds: Option<&Bounds<T>>) -> T {
+    let mut activity = T::zero();
+    for &(col, value) in row {
+        let bound = match bounds {
+            Some(bounds) if value > T::zero() => bounds.upper[col],
+            Some(bounds) => bounds.lower[col],
+            None => return T::infinity(),
+        };
+        activity += value * bound;
+    }
+    activity
+}
+
+// The ratio r with row = r·base when the two rows share a pattern.
+fn parallel<T: RealNumber>(row: &[(usize, T)], base: &[(usize, T)], tolerance: T) -> Option<T> {
+    let ratio = row.first()?.1 / base.first()?.1;
+    row.iter()
+        .zip(base)
+        .all(|(&(_, a), &(_, b))| (a - ratio * b).abs() <= tolerance * a.abs())
+        .then_some(ratio)
+}
+
+fn positions(kept: &[usize], len: usize) -> Vec<Option<usize>> {
+    let mut position = vec![None; len];
+    for (k, &row) in kept.iter().enumerate() {
+        position[row] = Some(k);
+    }
+    position
+}
+
+fn expand_rows<V: Copy>(values: &[V], kept: &[usize], len: usize, fill: V) -> Vec<V> {
+    let mut full = vec![fill; len];
+    for (&row, &value) in kept.iter().zip(values) {
+        full[row] = value;
+    }
+    full
+}
+
+fn keep_rows<T: RealNumber>(
+    matrix: &CscMatrix<T>,
+    rhs: &[T],
+    kept: &[usize],
+) -> (CscMatrix<T>, Vec<T>) {
+    let position = positions(kept, matrix.nrows);
+    let mut reduced = CscMatrix {
+        nrows: kept.len(),
+        ncols: matrix.ncols,
+        indptr: vec![0],
+        indices: Vec::new(),
+        data: Vec::new(),
+    };
+    for col in 0..matrix.ncols {
+        for idx in matrix.indptr[col]..matrix.indptr[col + 1] {
+            if let Some(row) = position[matrix.indices[idx]] {
+                reduced.indices.push(row);
+                reduced.data.push(matrix.data[idx]);
+            }
+        }
+        reduced.indptr.push(reduced.indices.len());
+    }
+    (reduced, kept.iter().map(|&row| rhs[row]).collect())
+}
+
+fn entries<T: RealNumber>(matrix: &CscMatrix<T>, col: usize) -> Vec<(usize, T)> {
+    (matrix.indptr[col]..matrix.indptr[col + 1])
+        .map(|idx| (matrix.indices[idx], matrix.data[idx]))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::problem::ObjectiveSense;
+    use crate::scaling::RuizScaler;
+    use crate::traits::LinearOperator;
+
+    fn objective(problem: &ProblemQP<f64>, x: &[f64]) -> f64 {
+        let mut px = vec![0.0; x.len()];
+        problem.quadratic.apply(x, &mut px);
+        let quadratic: f64 = x.iter().zip(&px).map(|(x, px)| 0.5 * x * px).sum();
+        let linear: f64 = x.iter().zip(&problem.linear).map(|(x, q)| x * q).sum();
+        quadratic + linear + problem.offset
+    }
+
+    // Three variables with x1 fixed at 2, P stored as its upper triangle.
+    fn problem() -> ProblemQP<f64> {
+        ProblemQP {
+            quadratic: CscMatrix::from_triplets(
+                3,
+                3,
+                &[
+                    (0, 0, 2.0),
+                    (0, 1, 1.0),
+                    (1, 1, 3.0),
+                    (1, 2, -1.0),
+                    (2, 2, 1.0),
+                ],
+            )
+            .unwrap(),
+            linear: vec![1.0, -1.0, 0.5],
+            inequalities: Some(InequalityConstraints {
+                matrix: CscMatrix::from_dense(1, 3, &[1.0, 1.0, 1.0]).unwrap(),
+                rhs: vec![4.0],
+            }),
+            equalities: Some(EqualityConstraints {
+                matrix: CscMatrix::from_dense(1, 3, &[1.0, -2.0, 0.0]).unwrap(),
+                rhs: vec![0.0],
+            }),
+            bounds: Some(Bounds {
+                lower: vec![-5.0, 2.0, -5.0],
+                upper: vec![5.0, 2.0, 5.0],
+            }),
+            groups: Vec::new(),
+            integrality: vec![false, true, false],
+            names: Names::default(),
+            sense: ObjectiveSense::Minimize,
+            offset: 0.25,
+            quadratic_constraints: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn fixed_variables_leave_the_objective_and_rows_unchanged() {
+        let mut original = problem();
+        original.names.variables = vec!["stock".into(), "order".into(), "sale".into()];
+        let (reduced, fixed) = FixedVariables::qp(&original);
+        assert_eq!(fixed.eliminated(), 1);
+        assert_eq!(reduced.nvars(), 2);
+        assert!(reduced.validate().is_ok());
+        assert_eq!(reduced.integrality, vec![false, false]);
+        assert_eq!(reduced.names.variables, vec!["stock", "sale"]);
+        for (a, c) in [(0.3, -1.2), (1.0, 4.0), (-2.5, 0.0)] {
+            let (x, z) = ([a, 2.0, c], [a, c]);
+            assert!((objective(&original, &x) - objective(&reduced, &z)).abs() < 1e-12);
+            let row = |problem: &ProblemQP<f64>, x: &[f64]| {
+                let ineq = problem.inequalities.as_ref().unwrap();
+                let mut gx = vec![0.0];
+                ineq.matrix.apply(x, &mut gx);
+                gx[0] - ineq.rhs[0]
+            };
+            assert!((row(&original, &x) - row(&reduced, &z)).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn postsolve_restores_the_fixed_values_and_multipliers() {
+        let original = problem();
+        let (_, fixed) = FixedVariables::qp(&original);
+        // Stacked multipliers of the reduced problem: one equality, one
+        // inequality, two bounds.
+        let mut solution = Solution::with_capacity(2, 0, 4);
+        solution.primal = vec![4.0, -1.0];
+        solution.inequality_dual = vec![0.5, 0.0, 0.0, 0.0];
+        let solution = fixed.postsolve(solution);
+        assert_eq!(solution.primal, vec![4.0, 2.0, -1.0]);
+        assert_eq!(solution.inequality_dual.len(), 5);
+        // (Px + q + Cᵀy)₁ with the symmetric part of P: ½·4 + 3·2 - ½·(-1)
+        // - 1 - 2·0.5 = 6.5.
+        assert!((solution.inequality_dual[3] + 6.5).abs() < 1e-12);
+        assert_eq!(solution.inequality_dual[..3], [0.5, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn parallel_and_bounded_rows_are_removed() {
+        let groups = vec![ConstraintGroup {
+            name: "caps".into(),
+            rows: vec![ConstraintRow::Inequality(0), ConstraintRow::Inequality(1)],
+        }];
+        let problem = ProblemLP {
+            cost: vec![1.0, 1.0, 1.0],
+            equalities: Some(EqualityConstraints {
+                matrix: CscMatrix::from_dense(2, 3, &[1.0, 1.0, 0.0, 2.0, 2.0, 0.0]).unwrap(),
+                rhs: vec![1.0, 2.0],
+            }),
+            // The second row is a tighter copy of the first, the third
+            // cannot reach its bound and the fourth follows from the first
+            // equality.
+            inequalities: Some(InequalityConstraints {
+                matrix: CscMatrix::from_dense(
+                    4,
+                    3,
+                    &[
+                        1.0, 0.0, 1.0, //
+                        2.0, 0.0, 2.0, //
+                        0.0, 1.0, 0.0, //
+                        -1.0, -1.0, 0.0,
+                    ],
+                )
+                .unwrap(),
+                rhs: vec![3.0, 4.0, 20.0, -1.0],
+            }),
+            bounds: Some(Bounds {
+                lower: vec![0.0; 3],
+                upper: vec![10.0; 3],
+            }),
+            groups,
+            integrality: Vec::new(),
+            names: Names {
+                variables: Vec::new(),
+                equalities: vec!["total".into(), "double total".into()],
+                inequalities: ["cap", "tight cap", "loose", "implied"]
+                    .map(String::from)
+                    .to_vec(),
+            },
+            sense: ObjectiveSense::Minimize,
+            offset: 0.0,
+        };
+        let (reduced, rows) = RedundantRows::lp(&problem);
+        assert!(reduced.validate().is_ok());
+        assert_eq!(rows.removed(), 4);
+        assert_eq!(reduced.equalities.as_ref().unwrap().rhs, vec![1.0]);
+        assert_eq!(reduced.inequalities.as_ref().unwrap().rhs, vec![4.0]);
+        assert_eq!(reduced.groups[0].rows, vec![ConstraintRow::Inequality(0)]);
+        assert_eq!(reduced.names.equalities, vec!["total"]);
+        assert_eq!(reduced.names.inequalities, vec!["tight cap"]);
+
+        let mut solution = Solution::with_capacity(3, 1, 4);
+        solution.equality_dual = vec![-1.0];
+        solution.inequality_dual = vec![0.5, 0.0, 0.0, 0.0];
+        let solution = rows.postsolve(solution);
+        assert_eq!(solution.equality_dual, vec![-1.0, 0.0]);
+        assert_eq!(
+            solution.inequality_dual,
+            vec![0.0, 0.5, 0.0, 0.0, 0.0, 0.0, 0.0]
+        );
+        let stats = solution.stats.presolve.unwrap();
+        assert_eq!((stats.duplicate_rows, stats.redundant_rows), (3, 1));
+    }
+
+    // x₀ + x₁ = 3 over [0, 5]², with rows x₀ ≤ 1 and x₁ - x₀ ≤ `gap`.
+    fn chained(gap: f64) -> ProblemLP<f64> {
+        ProblemLP {
+            cost: vec![1.0, 1.0],
+            equalities: Some(EqualityConstraints {
+                matrix: CscMatrix::from_dense(1, 2, &[1.0, 1.0]).unwrap(),
+                rhs: vec![3.0],
+            }),
+            inequalities: Some(InequalityConstraints {
+                matrix: CscMatrix::from_dense(2, 2, &[1.0, 0.0, -1.0, 1.0]).unwrap(),
+                rhs: vec![1.0, gap],
+            }),
+            bounds: Some(Bounds {
+                lower: vec![0.0; 2],
+                upper: vec![5.0; 2],
+            }),
+            groups: Vec::new(),
+            integrality: Vec::new(),
+            names: Names::default(),
+            sense: ObjectiveSense::Minimize,
+            offset: 0.0,
+        }
+    }
+
+    #[test]
+    fn implied_bounds_hand_their_multipliers_back() {
+        // x₀ ≤ 1 from the first row, then x₁ ≥ 2 from the equality, which
+        // the second row cannot meet.
+        let (reduced, implied) = ImpliedBounds::lp(&chained(0.0));
+        assert!(implied.infeasible().is_some());
+        assert!(reduced.validate().is_ok());
+
+        // With room for it, x₁ ≤ 2.5 and x₀ ≥ 0.5 follow as well.
+        let (reduced, implied) = ImpliedBounds::lp(&chained(1.5));
+        assert!(implied.infeasible().is_none());
+        let bounds = reduced.bounds.as_ref().unwrap();
+        assert_eq!((bounds.lower[0], bounds.upper[0]), (0.5, 1.0));
+        assert_eq!((bounds.lower[1], bounds.upper[1]), (2.0, 2.5));
+        assert_eq!(implied.tightened(), 4);
+
+        // Multipliers of the tightened LP that rest on its bounds alone.
+        let mut solution = Solution::with_capacity(2, 1, 4);
+        solution.equality_dual = vec![-2.0];
+        solution.inequality_dual = vec![0.0, 0.0, 1.0, 1.0];
+        let solution = implied.postsolve(solution);
+        let (y, z) = (&solution.equality_dual, &solution.inequality_dual);
+        // Stationarity 1 + y + z₀ - z₁ + w₀ = 0 and 1 + y + z₁ + w₁ = 0.
+        assert!(
+            (1.0 + y[0] + z[0] - z[1] + z[2]).abs() < 1e-12,
+            "{y:?} {z:?}"
+        );
+        assert!((1.0 + y[0] + z[1] + z[3]).abs() < 1e-12, "{y:?} {z:?}");
+        assert!(z[0] > 0.0 && z[1] >= 0.0, "{z:?}");
+    }
+
+    #[test]
+    fn infeasible_tightening_comes_with_a_certificate() {
+        let problem = chained(0.0);
+        let solution = ImpliedBounds::lp(&problem).1.infeasible().unwrap();
+        assert_eq!(solution.status, Status::PrimalInfeasible);
+        let Some(Certificate::PrimalInfeasible {
+            equality_dual: y,
+            inequality_dual: z,
+        }) = solution.certificate
+        else {
+            panic!("no certificate");
+        };
+        let (eq, ineq) = (
+            problem.equalities.as_ref().unwrap(),
+            problem.inequalities.as_ref().unwrap(),
+        );
+        let bounds = problem.bounds.as_ref().unwrap();
+        let (z, w) = z.split_at(2);
+        let mut residual = w.to_vec();
+        eq.matrix.apply_transpose(&y, &mut residual);
+        let mut gz = vec![0.0; 2];
+        ineq.matrix.apply_transpose(z, &mut gz);
+        for (r, g) in residual.iter_mut().zip(&gz) {
+            *r += g;
+        }
+        assert!(residual.iter().all(|r| r.abs() < 1e-12), "{residual:?}");
+        assert!(z.iter().all(|&z| z >= 0.0));
+        let value: f64 = eq.rhs.iter().zip(&y).map(|(b, y)| b * y).sum::<f64>()
+            + ineq.rhs.iter().zip(z).map(|(h, z)| h * z).sum::<f64>()
+            + w.iter()
+                .enumerate()
+                .map(|(j, &w)| w.max(0.0) * bounds.upper[j] + w.min(0.0) * bounds.lower[j])
+                .sum::<f64>();
+        assert!((value + 1.0).abs() < 1e-12, "{value}");
+    }
+
+    #[test]
+    fn pipelines_undo_their_steps_last_to_first() {
+        let original = problem();
+        let pipeline = PresolvePipeline::new()
+            .with(RemoveFixedVariables)
+            .with(ScaleProblem(RuizScaler::default()));
+        let (reduced, stack) = pipeline.qp(&original);
+        assert_eq!(stack.len(), 2);
+        assert!(stack.infeasible().is_none());
+        let mut solution = Solution::with_capacity(2, 0, 4);
+        solution.primal = vec![0.7, -1.3];
+        // The scaled objective carries the cost factor, which postsolve
+        // takes off again.
+        solution.objective_value = objective(&reduced, &solution.primal);
+        let solution = stack.postsolve(solution);
+        assert_eq!(solution.primal[1], 2.0);
+        let value = objective(&original, &solution.primal);
+        assert!((solution.objective_value - value).abs() < 1e-12);
+
+        // Tightening proves chained(0) infeasible, and the rows step after
+        // it never runs.
+        let (_, stack) = PresolvePipeline::default().lp(&chained(0.0));
+        assert_eq!(stack.len(), 2);
+        let solution = stack.infeasible().unwrap();
+        assert_eq!(solution.status, Status::PrimalInfeasible);
+        assert_eq!(solution.stats.presolve.unwrap().fixed_variables, 0);
+    }
+}
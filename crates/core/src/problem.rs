@@ -1,7 +1,11 @@
-use crate::math::RealNumber;
+use crate::math::{cast, cast_slice, RealNumber};
+use crate::options::Method;
+use crate::structure::ConstraintRow;
+use crate::traits::LinearOperator;
 use serde::{Deserialize, Serialize};
 use sprs::CsMat;
 use std::fmt;
+use std::sync::Arc;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -37,60 +41,463 @@ where
         }
     }
 
-    pub fn nnz(&self) -> usize {
-        self.data.len()
+    // The matrix with the given (row, col, value) entries in any order;
+    // repeated positions are summed, as in the usual COO convention.
+    pub fn from_triplets(
+        nrows: usize,
+        ncols: usize,
+        triplets: &[(usize, usize, T)],
+    ) -> ProblemResult<Self> {
+        if let Some(&(row, col, _)) = triplets
+            .iter()
+            .find(|&&(row, col, _)| row >= nrows || col >= ncols)
+        {
+            return Err(ProblemError::InvalidStructure(format!(
+                "entry ({row}, {col}) out of bounds for a {nrows}x{ncols} matrix"
+            )));
+        }
+        let mut sorted = triplets.to_vec();
+        sorted.sort_by_key(|&(row, col, _)| (col, row));
+        let mut indptr = vec![0; ncols + 1];
+        let mut indices: Vec<usize> = Vec::with_capacity(sorted.len());
+        let mut data: Vec<T> = Vec::with_capacity(sorted.len());
+        let mut last = None;
+        for (row, col, value) in sorted {
+            if last == Some((row, col)) {
+                *data.last_mut().unwrap() += value;
+                continue;
+            }
+            last = Some((row, col));
+            indices.push(row);
+            data.push(value);
+            indptr[col + 1] += 1;
+        }
+        for col in 0..ncols {
+            indptr[col + 1] += indptr[col];
+        }
+        let matrix = Self {
+            nrows,
+            ncols,
+            indptr,
+            indices,
+            data,
+        };
+        matrix.validate()?;
+        Ok(matrix)
     }
 
-    pub fn to_csmat(&self) -> ProblemResult<CsMat<T>> {
-        if self.indptr.len() != self.ncols + 1 {
+    // The nonzeros of a row-major `nrows` x `ncols` array; explicit zeros
+    // are left out of the structure.
+    pub fn from_dense(nrows: usize, ncols: usize, values: &[T]) -> ProblemResult<Self> {
+        if values.len() != nrows * ncols {
             return Err(ProblemError::DimensionMismatch(format!(
-                "indptr length {} != ncols + 1 ({})",
-                self.indptr.len(),
-                self.ncols + 1
+                "{} values for a {nrows}x{ncols} matrix",
+                values.len()
             )));
         }
-        if self.indices.len() != self.data.len() {
-            return Err(ProblemError::DimensionMismatch(format!(
-                "indices length {} != data length {}",
-                self.indices.len(),
-                self.data.len()
-            )));
+        let mut matrix = Self {
+            nrows,
+            ncols,
+            indptr: Vec::with_capacity(ncols + 1),
+            indices: Vec::new(),
+            data: Vec::new(),
+        };
+        matrix.indptr.push(0);
+        for col in 0..ncols {
+            for row in 0..nrows {
+                let value = values[row * ncols + col];
+                if value != T::zero() {
+                    matrix.indices.push(row);
+                    matrix.data.push(value);
+                }
+            }
+            matrix.indptr.push(matrix.indices.len());
+        }
+        Ok(matrix)
+    }
+
+    // The nonzeros of the given dense columns, which must all have the
+    // same length.
+    pub fn from_columns(columns: &[Vec<T>]) -> ProblemResult<Self> {
+        let nrows = columns.first().map_or(0, Vec::len);
+        let mut matrix = Self {
+            nrows,
+            ncols: columns.len(),
+            indptr: Vec::with_capacity(columns.len() + 1),
+            indices: Vec::new(),
+            data: Vec::new(),
+        };
+        matrix.indptr.push(0);
+        for (col, column) in columns.iter().enumerate() {
+            if column.len() != nrows {
+                return Err(ProblemError::DimensionMismatch(format!(
+                    "column {col} has {} entries, expected {nrows}",
+                    column.len()
+                )));
+            }
+            for (row, &value) in column.iter().enumerate() {
+                if value != T::zero() {
+                    matrix.indices.push(row);
+                    matrix.data.push(value);
+                }
+            }
+            matrix.indptr.push(matrix.indices.len());
+        }
+        Ok(matrix)
+    }
+
+    pub fn nnz(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn cast<U: RealNumber>(&self) -> CscMatrix<U> {
+        CscMatrix {
+            nrows: self.nrows,
+            ncols: self.ncols,
+            indptr: self.indptr.clone(),
+            indices: self.indices.clone(),
+            data: cast_slice(&self.data),
         }
-        Ok(CsmatBuilder::build(self))
+    }
+
+    pub fn to_csmat(&self) -> ProblemResult<CsMat<T>> {
+        self.validate()?;
+        CsmatBuilder::build(self)
+    }
+
+    // Moves the arrays into a sprs matrix without copying them.
+    pub fn into_csmat(self) -> ProblemResult<CsMat<T>> {
+        self.validate()?;
+        CsMat::try_new_csc(
+            (self.nrows, self.ncols),
+            self.indptr,
+            self.indices,
+            self.data,
+        )
+        .map_err(|(_, _, _, err)| ProblemError::InvalidStructure(err.to_string()))
     }
 
     pub fn validate(&self) -> ProblemResult<()> {
-        if self.indptr.len() != self.ncols + 1 {
-            return Err(ProblemError::DimensionMismatch(format!(
-                "indptr length {} != ncols + 1 ({})",
-                self.indptr.len(),
-                self.ncols + 1
+        validate_compressed(
+            Orientation::Columns,
+            (self.ncols, self.nrows),
+            &self.indptr,
+            &self.indices,
+            self.data.len(),
+        )
+    }
+
+    // The same matrix stored by rows.
+    pub fn to_csr(&self) -> CsrMatrix<T> {
+        let (indptr, indices, data) =
+            transpose_compressed(self.nrows, &self.indptr, &self.indices, &self.data);
+        CsrMatrix {
+            nrows: self.nrows,
+            ncols: self.ncols,
+            indptr,
+            indices,
+            data,
+        }
+    }
+}
+
+// Row-compressed storage, as scipy's csr_matrix and row-by-row constraint
+// generation produce it. Solvers take CscMatrix; convert with `to_csc`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CsrMatrix<T> {
+    pub nrows: usize,
+    pub ncols: usize,
+    pub indptr: Vec<usize>,
+    pub indices: Vec<usize>,
+    pub data: Vec<T>,
+}
+
+impl<T> CsrMatrix<T>
+where
+    T: RealNumber,
+{
+    // Repeated positions are summed, as for `CscMatrix::from_triplets`.
+    pub fn from_triplets(
+        nrows: usize,
+        ncols: usize,
+        triplets: &[(usize, usize, T)],
+    ) -> ProblemResult<Self> {
+        Ok(CscMatrix::from_triplets(nrows, ncols, triplets)?.to_csr())
+    }
+
+    pub fn nnz(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn validate(&self) -> ProblemResult<()> {
+        validate_compressed(
+            Orientation::Rows,
+            (self.nrows, self.ncols),
+            &self.indptr,
+            &self.indices,
+            self.data.len(),
+        )
+    }
+
+    pub fn to_csc(&self) -> CscMatrix<T> {
+        let (indptr, indices, data) =
+            transpose_compressed(self.ncols, &self.indptr, &self.indices, &self.data);
+        CscMatrix {
+            nrows: self.nrows,
+            ncols: self.ncols,
+            indptr,
+            indices,
+            data,
+        }
+    }
+}
+
+// Takes a CSC matrix's arrays as they are; a CSR one is converted first.
+impl<T: RealNumber> From<CsMat<T>> for CscMatrix<T> {
+    fn from(matrix: CsMat<T>) -> Self {
+        let (nrows, ncols) = matrix.shape();
+        let csr = matrix.is_csr();
+        let (indptr, indices, data) = matrix.into_raw_storage();
+        if csr {
+            return CsrMatrix {
+                nrows,
+                ncols,
+                indptr,
+                indices,
+                data,
+            }
+            .to_csc();
+        }
+        Self {
+            nrows,
+            ncols,
+            indptr,
+            indices,
+            data,
+        }
+    }
+}
+
+impl<T: RealNumber> TryFrom<CscMatrix<T>> for CsMat<T> {
+    type Error = ProblemError;
+
+    fn try_from(matrix: CscMatrix<T>) -> ProblemResult<Self> {
+        matrix.into_csmat()
+    }
+}
+
+impl<T: RealNumber> From<&CscMatrix<T>> for CsrMatrix<T> {
+    fn from(matrix: &CscMatrix<T>) -> Self {
+        matrix.to_csr()
+    }
+}
+
+impl<T: RealNumber> From<&CsrMatrix<T>> for CscMatrix<T> {
+    fn from(matrix: &CsrMatrix<T>) -> Self {
+        matrix.to_csc()
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Orientation {
+    Columns,
+    Rows,
+}
+
+impl Orientation {
+    // The count, outer and inner names used in the error messages.
+    fn names(self) -> (&'static str, &'static str, &'static str) {
+        match self {
+            Orientation::Columns => ("ncols", "column", "row"),
+            Orientation::Rows => ("nrows", "row", "column"),
+        }
+    }
+}
+
+fn validate_compressed(
+    orientation: Orientation,
+    (nouter, ninner): (usize, usize),
+    indptr: &[usize],
+    indices: &[usize],
+    nnz: usize,
+) -> ProblemResult<()> {
+    let (count, outer_name, inner_name) = orientation.names();
+    if indptr.len() != nouter + 1 {
+        return Err(ProblemError::DimensionMismatch(format!(
+            "indptr length {} != {count} + 1 ({})",
+            indptr.len(),
+            nouter + 1
+        )));
+    }
+    if indices.len() != nnz {
+        return Err(ProblemError::DimensionMismatch(format!(
+            "indices length {} != data length {}",
+            indices.len(),
+            nnz
+        )));
+    }
+    if indptr[0] != 0 {
+        return Err(ProblemError::InvalidStructure(format!(
+            "indptr must start at 0, found {}",
+            indptr[0]
+        )));
+    }
+    if indptr[nouter] != indices.len() {
+        return Err(ProblemError::InvalidStructure(format!(
+            "indptr must end at nnz {}, found {}",
+            indices.len(),
+            indptr[nouter]
+        )));
+    }
+    // Every pointer is checked before any slice is taken; a middle entry
+    // past nnz would otherwise panic in the slice for its column.
+    for (outer, pair) in indptr.windows(2).enumerate() {
+        if pair[1] > indices.len() {
+            return Err(ProblemError::InvalidStructure(format!(
+                "indptr entry {} at {outer_name} {} exceeds nnz {}",
+                pair[1],
+                outer + 1,
+                indices.len()
             )));
         }
-        if self.indices.len() != self.data.len() {
-            return Err(ProblemError::DimensionMismatch(format!(
-                "indices length {} != data length {}",
-                self.indices.len(),
-                self.data.len()
+        if pair[0] > pair[1] {
+            return Err(ProblemError::InvalidStructure(format!(
+                "indptr decreases at {outer_name} {outer}"
             )));
         }
-        Ok(())
+    }
+    for outer in 0..nouter {
+        let inner = &indices[indptr[outer]..indptr[outer + 1]];
+        for (offset, &index) in inner.iter().enumerate() {
+            if index >= ninner {
+                return Err(ProblemError::InvalidStructure(format!(
+                    "{inner_name} index {index} out of bounds for {ninner} {inner_name}s in {outer_name} {outer}"
+                )));
+            }
+            if offset > 0 && inner[offset - 1] >= index {
+                return Err(ProblemError::InvalidStructure(format!(
+                    "{inner_name} indices in {outer_name} {outer} must be strictly increasing"
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
+// Swaps compressed columns for compressed rows or back by a counting sort
+// over the `ninner` inner indices; the output's inner indices come out
+// sorted.
+fn transpose_compressed<T: Copy>(
+    ninner: usize,
+    indptr: &[usize],
+    indices: &[usize],
+    data: &[T],
+) -> (Vec<usize>, Vec<usize>, Vec<T>) {
+    let mut transposed_ptr = vec![0; ninner + 1];
+    for &inner in indices {
+        transposed_ptr[inner + 1] += 1;
+    }
+    for i in 0..ninner {
+        transposed_ptr[i + 1] += transposed_ptr[i];
+    }
+    let mut next = transposed_ptr.clone();
+    let mut transposed_indices = vec![0; indices.len()];
+    let mut transposed_data = data.to_vec();
+    for outer in 0..indptr.len().saturating_sub(1) {
+        for p in indptr[outer]..indptr[outer + 1] {
+            let slot = &mut next[indices[p]];
+            transposed_indices[*slot] = outer;
+            transposed_data[*slot] = data[p];
+            *slot += 1;
+        }
+    }
+    (transposed_ptr, transposed_indices, transposed_data)
+}
+
+impl<T> LinearOperator<T> for CscMatrix<T>
+where
+    T: RealNumber,
+{
+    fn dim(&self) -> (usize, usize) {
+        (self.nrows, self.ncols)
+    }
+
+    fn apply(&self, x: &[T], y: &mut [T]) {
+        y.fill(T::zero());
+        for (col, &value) in x.iter().enumerate().take(self.ncols) {
+            for idx in self.indptr[col]..self.indptr[col + 1] {
+                y[self.indices[idx]] += self.data[idx] * value;
+            }
+        }
+    }
+
+    fn apply_transpose(&self, x: &[T], y: &mut [T]) {
+        for (col, target) in y.iter_mut().enumerate().take(self.ncols) {
+            *target = (self.indptr[col]..self.indptr[col + 1]).fold(T::zero(), |acc, idx| {
+                acc + self.data[idx] * x[self.indices[idx]]
+            });
+        }
+    }
+}
+
+// Collects entries one at a time for `CscMatrix::from_triplets`.
+#[derive(Debug, Clone)]
+pub struct TripletBuilder<T> {
+    nrows: usize,
+    ncols: usize,
+    triplets: Vec<(usize, usize, T)>,
+}
+
+impl<T> TripletBuilder<T>
+where
+    T: RealNumber,
+{
+    pub fn new(nrows: usize, ncols: usize) -> Self {
+        Self {
+            nrows,
+            ncols,
+            triplets: Vec::new(),
+        }
+    }
+
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.triplets.reserve(capacity);
+        self
+    }
+
+    // Adds `value` at (row, col); bounds are checked when building.
+    pub fn push(&mut self, row: usize, col: usize, value: T) -> &mut Self {
+        self.triplets.push((row, col, value));
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.triplets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.triplets.is_empty()
+    }
+
+    pub fn build(&self) -> ProblemResult<CscMatrix<T>> {
+        CscMatrix::from_triplets(self.nrows, self.ncols, &self.triplets)
     }
 }
 
 struct CsmatBuilder;
 
 impl CsmatBuilder {
-    fn build<T>(matrix: &CscMatrix<T>) -> CsMat<T>
+    fn build<T>(matrix: &CscMatrix<T>) -> ProblemResult<CsMat<T>>
     where
         T: RealNumber,
     {
-        CsMat::new_csc(
+        CsMat::try_new_csc(
             (matrix.nrows, matrix.ncols),
             matrix.indptr.clone(),
             matrix.indices.clone(),
             matrix.data.clone(),
         )
+        .map_err(|(_, _, _, err)| ProblemError::InvalidStructure(err.to_string()))
     }
 }
 
@@ -112,6 +519,10 @@ where
     }
 
     pub fn validate(&self) -> ProblemResult<()> {
+        self.validate_named(&Names::default())
+    }
+
+    fn validate_named(&self, names: &Names) -> ProblemResult<()> {
         if self.lower.len() != self.upper.len() {
             return Err(ProblemError::DimensionMismatch(format!(
                 "lower len {} != upper len {}",
@@ -121,8 +532,12 @@ where
         }
         for (i, (lo, hi)) in self.lower.iter().zip(self.upper.iter()).enumerate() {
             if lo > hi {
+                let at = match names.variable(i) {
+                    Some(name) => format!("of `{name}`"),
+                    None => format!("at index {i}"),
+                };
                 return Err(ProblemError::InvalidStructure(format!(
-                    "lower bound exceeds upper bound at index {i}"
+                    "lower bound exceeds upper bound {at}"
                 )));
             }
         }
@@ -140,7 +555,7 @@ impl<T> EqualityConstraints<T>
 where
     T: RealNumber,
 {
-    fn validate(&self, nvars: usize) -> ProblemResult<()> {
+    pub fn validate(&self, nvars: usize) -> ProblemResult<()> {
         self.matrix.validate()?;
         if self.matrix.ncols != nvars {
             return Err(ProblemError::DimensionMismatch(format!(
@@ -169,7 +584,7 @@ impl<T> InequalityConstraints<T>
 where
     T: RealNumber,
 {
-    fn validate(&self, nvars: usize) -> ProblemResult<()> {
+    pub fn validate(&self, nvars: usize) -> ProblemResult<()> {
         self.matrix.validate()?;
         if self.matrix.ncols != nvars {
             return Err(ProblemError::DimensionMismatch(format!(
@@ -188,105 +603,1191 @@ where
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ProblemLP<T> {
-    pub cost: Vec<T>,
-    pub inequalities: Option<InequalityConstraints<T>>,
-    pub equalities: Option<EqualityConstraints<T>>,
-    pub bounds: Option<Bounds<T>>,
+// The side or sides of one row of a `LinearConstraints` block.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum RowSense<T> {
+    // aᵀx <= value.
+    Le(T),
+    // aᵀx >= value.
+    Ge(T),
+    // aᵀx = value.
+    Eq(T),
+    // lower <= aᵀx <= upper.
+    Range(T, T),
 }
 
-impl<T> ProblemLP<T>
+impl<T> RowSense<T>
 where
     T: RealNumber,
 {
-    pub fn nvars(&self) -> usize {
-        self.cost.len()
+    // The sense of lower <= aᵀx <= upper, either side possibly infinite.
+    pub fn between(lower: T, upper: T) -> Self {
+        match (lower.is_finite(), upper.is_finite()) {
+            _ if lower == upper => Self::Eq(lower),
+            (true, false) => Self::Ge(lower),
+            (false, true) => Self::Le(upper),
+            _ => Self::Range(lower, upper),
+        }
     }
 
-    pub fn validate(&self) -> ProblemResult<()> {
-        let n = self.nvars();
-        if let Some(bounds) = &self.bounds {
-            if bounds.lower.len() != n {
-                return Err(ProblemError::DimensionMismatch(format!(
-                    "bounds size {} != nvars {n}",
-                    bounds.lower.len()
-                )));
-            }
-            bounds.validate()?;
-        }
-        if let Some(eq) = &self.equalities {
-            eq.validate(n)?;
-        }
-        if let Some(ineq) = &self.inequalities {
-            ineq.validate(n)?;
+    // The row as lower <= aᵀx <= upper.
+    pub fn range(self) -> (T, T) {
+        match self {
+            Self::Le(upper) => (T::neg_infinity(), upper),
+            Self::Ge(lower) => (lower, T::infinity()),
+            Self::Eq(value) => (value, value),
+            Self::Range(lower, upper) => (lower, upper),
         }
-        Ok(())
     }
 }
 
+// Linear rows given in one matrix, each with its own sense, for callers
+// that would otherwise split A into equality and inequality parts by hand.
+// `split` produces those parts and `row_duals` maps their multipliers back.
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ProblemQP<T> {
-    pub quadratic: CscMatrix<T>,
-    pub linear: Vec<T>,
-    pub inequalities: Option<InequalityConstraints<T>>,
-    pub equalities: Option<EqualityConstraints<T>>,
-    pub bounds: Option<Bounds<T>>,
+pub struct LinearConstraints<T> {
+    pub matrix: CscMatrix<T>,
+    pub senses: Vec<RowSense<T>>,
 }
 
-impl<T> ProblemQP<T>
+// The equality and inequality parts `LinearConstraints::split` makes.
+pub type SplitRows<T> = (
+    Option<EqualityConstraints<T>>,
+    Option<InequalityConstraints<T>>,
+);
+
+// Where `split` puts a row of the block.
+#[derive(Debug, Clone, Copy)]
+enum Placement {
+    Equality(usize),
+    Inequality {
+        upper: Option<usize>,
+        lower: Option<usize>,
+    },
+}
+
+impl<T> LinearConstraints<T>
 where
     T: RealNumber,
 {
-    pub fn nvars(&self) -> usize {
-        self.linear.len()
+    pub fn new(matrix: CscMatrix<T>, senses: Vec<RowSense<T>>) -> Self {
+        Self { matrix, senses }
     }
 
-    pub fn validate(&self) -> ProblemResult<()> {
-        let n = self.nvars();
-        self.quadratic.validate()?;
-        if self.quadratic.ncols != n || self.quadratic.nrows != n {
-            return Err(ProblemError::DimensionMismatch(format!(
-                "quadratic matrix must be square and match variable dimension {n}"
-            )));
+    // The rows of both structs stacked, equalities first.
+    pub fn from_parts(
+        equality: Option<EqualityConstraints<T>>,
+        inequality: Option<InequalityConstraints<T>>,
+        nvars: usize,
+    ) -> ProblemResult<Self> {
+        let mut triplets = Vec::new();
+        let mut senses = Vec::new();
+        if let Some(eq) = equality {
+            eq.validate(nvars)?;
+            push_rows(&mut triplets, senses.len(), &eq.matrix);
+            senses.extend(eq.rhs.into_iter().map(RowSense::Eq));
         }
-        if let Some(bounds) = &self.bounds {
-            if bounds.lower.len() != n {
-                return Err(ProblemError::DimensionMismatch(format!(
-                    "bounds size {} != nvars {n}",
-                    bounds.lower.len()
-                )));
-            }
-            bounds.validate()?;
+        if let Some(ineq) = inequality {
+            ineq.validate(nvars)?;
+            push_rows(&mut triplets, senses.len(), &ineq.matrix);
+            senses.extend(ineq.rhs.into_iter().map(RowSense::Le));
         }
-        if let Some(eq) = &self.equalities {
-            eq.validate(n)?;
+        let matrix = CscMatrix::from_triplets(senses.len(), nvars, &triplets)?;
+        Ok(Self { matrix, senses })
+    }
+
+    pub fn validate(&self, nvars: usize) -> ProblemResult<()> {
+        self.matrix.validate()?;
+        if self.matrix.ncols != nvars {
+            return Err(ProblemError::DimensionMismatch(format!(
+                "constraint matrix columns {} != nvars {}",
+                self.matrix.ncols, nvars
+            )));
         }
-        if let Some(ineq) = &self.inequalities {
-            ineq.validate(n)?;
+        if self.matrix.nrows != self.senses.len() {
+            return Err(ProblemError::DimensionMismatch(format!(
+                "constraint rows {} != senses len {}",
+                self.matrix.nrows,
+                self.senses.len()
+            )));
         }
         Ok(())
     }
-}
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub enum Cone {
-    Zero(usize),
-    NonNegative(usize),
-    SecondOrder(usize),
-}
+    // Equal sides make an equality row. Otherwise each finite side makes an
+    // inequality row, aᵀx <= u before -aᵀx <= -l, and a row with no finite
+    // side is left out.
+    pub fn split(&self) -> ProblemResult<SplitRows<T>> {
+        self.validate(self.matrix.ncols)?;
+        let placements = self.placements();
+        let (mut eq_rhs, mut ineq_rhs) = (Vec::new(), Vec::new());
+        for (sense, placement) in self.senses.iter().zip(&placements) {
+            let (lower, upper) = sense.range();
+            match placement {
+                Placement::Equality(_) => eq_rhs.push(lower),
+                Placement::Inequality { upper: u, lower: l } => {
+                    if u.is_some() {
+                        ineq_rhs.push(upper);
+                    }
+                    if l.is_some() {
+                        ineq_rhs.push(-lower);
+                    }
+                }
+            }
+        }
 
-impl fmt::Display for Cone {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            Cone::Zero(n) => write!(f, "Zero({n})"),
-            Cone::NonNegative(n) => write!(f, "NonNegative({n})"),
-            Cone::SecondOrder(n) => write!(f, "SecondOrder({n})"),
+        let (mut eq, mut ineq) = (Vec::new(), Vec::new());
+        for col in 0..self.matrix.ncols {
+            for idx in self.matrix.indptr[col]..self.matrix.indptr[col + 1] {
+                let value = self.matrix.data[idx];
+                match placements[self.matrix.indices[idx]] {
+                    Placement::Equality(row) => eq.push((row, col, value)),
+                    Placement::Inequality { upper, lower } => {
+                        if let Some(row) = upper {
+                            ineq.push((row, col, value));
+                        }
+                        if let Some(row) = lower {
+                            ineq.push((row, col, -value));
+                        }
+                    }
+                }
+            }
         }
+
+        let ncols = self.matrix.ncols;
+        let equalities = if eq_rhs.is_empty() {
+            None
+        } else {
+            Some(EqualityConstraints {
+                matrix: CscMatrix::from_triplets(eq_rhs.len(), ncols, &eq)?,
+                rhs: eq_rhs,
+            })
+        };
+        let inequalities = if ineq_rhs.is_empty() {
+            None
+        } else {
+            Some(InequalityConstraints {
+                matrix: CscMatrix::from_triplets(ineq_rhs.len(), ncols, &ineq)?,
+                rhs: ineq_rhs,
+            })
+        };
+        Ok((equalities, inequalities))
     }
-}
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+    // One multiplier per row of the block from those of the problem `split`
+    // made, in either dual layout: the equality duals apart, or stacked in
+    // front of the inequality ones. A two-sided row gets z_upper - z_lower,
+    // so a multiplier is negative where the lower side is the active one.
+    pub fn row_duals(&self, equality: &[T], inequality: &[T]) -> Vec<T> {
+        let placements = self.placements();
+        let meq = placements
+            .iter()
+            .filter(|placement| matches!(placement, Placement::Equality(_)))
+            .count();
+        let (equality, inequality) = if equality.is_empty() && meq > 0 {
+            inequality.split_at(meq.min(inequality.len()))
+        } else {
+            (equality, inequality)
+        };
+        let at = |duals: &[T], row: Option<usize>| {
+            row.and_then(|row| duals.get(row).copied())
+                .unwrap_or_else(T::zero)
+        };
+        placements
+            .iter()
+            .map(|placement| match *placement {
+                Placement::Equality(row) => at(equality, Some(row)),
+                Placement::Inequality { upper, lower } => {
+                    at(inequality, upper) - at(inequality, lower)
+                }
+            })
+            .collect()
+    }
+
+    fn placements(&self) -> Vec<Placement> {
+        let (mut meq, mut mineq) = (0, 0);
+        let mut next = |finite: bool| {
+            finite.then(|| {
+                mineq += 1;
+                mineq - 1
+            })
+        };
+        self.senses
+            .iter()
+            .map(|sense| {
+                let (lower, upper) = sense.range();
+                if lower == upper {
+                    meq += 1;
+                    return Placement::Equality(meq - 1);
+                }
+                let upper = next(upper.is_finite());
+                let lower = next(lower.is_finite());
+                Placement::Inequality { upper, lower }
+            })
+            .collect()
+    }
+}
+
+// The entries of `matrix` as triplets, its rows moved down by `offset`.
+fn push_rows<T: Copy>(triplets: &mut Vec<(usize, usize, T)>, offset: usize, matrix: &CscMatrix<T>) {
+    for col in 0..matrix.ncols {
+        for idx in matrix.indptr[col]..matrix.indptr[col + 1] {
+            triplets.push((offset + matrix.indices[idx], col, matrix.data[idx]));
+        }
+    }
+}
+
+impl<T> From<EqualityConstraints<T>> for LinearConstraints<T> {
+    fn from(equality: EqualityConstraints<T>) -> Self {
+        Self {
+            senses: equality.rhs.into_iter().map(RowSense::Eq).collect(),
+            matrix: equality.matrix,
+        }
+    }
+}
+
+impl<T> From<InequalityConstraints<T>> for LinearConstraints<T> {
+    fn from(inequality: InequalityConstraints<T>) -> Self {
+        Self {
+            senses: inequality.rhs.into_iter().map(RowSense::Le).collect(),
+            matrix: inequality.matrix,
+        }
+    }
+}
+
+// Largest P whose LDLᵀ factorization the convexity check attempts; larger
+// ones only get their diagonal inspected.
+const CONVEXITY_CHECK_LIMIT: usize = 2000;
+
+// Pivots below minus this fraction of P's largest entry mark P as
+// indefinite; anything closer to zero is rounding on a singular P.
+const CONVEXITY_TOLERANCE: f64 = 1e-9;
+
+// Labels for the variables and the constraint rows, for reports and
+// messages; a list left empty leaves that kind of item unnamed.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Names {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub variables: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub equalities: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub inequalities: Vec<String>,
+}
+
+impl Names {
+    pub fn is_empty(&self) -> bool {
+        self.variables.is_empty() && self.equalities.is_empty() && self.inequalities.is_empty()
+    }
+
+    pub fn variable(&self, index: usize) -> Option<&str> {
+        self.variables.get(index).map(String::as_str)
+    }
+
+    pub fn row(&self, row: ConstraintRow) -> Option<&str> {
+        match row {
+            ConstraintRow::Equality(i) => self.equalities.get(i),
+            ConstraintRow::Inequality(i) => self.inequalities.get(i),
+        }
+        .map(String::as_str)
+    }
+
+    pub fn variable_index(&self, name: &str) -> Option<usize> {
+        self.variables
+            .iter()
+            .position(|candidate| candidate == name)
+    }
+
+    pub fn row_index(&self, name: &str) -> Option<ConstraintRow> {
+        self.equalities
+            .iter()
+            .position(|candidate| candidate == name)
+            .map(ConstraintRow::Equality)
+            .or_else(|| {
+                self.inequalities
+                    .iter()
+                    .position(|candidate| candidate == name)
+                    .map(ConstraintRow::Inequality)
+            })
+    }
+
+    // The variable's name, or x[index] without one.
+    pub fn variable_label(&self, index: usize) -> String {
+        match self.variable(index) {
+            Some(name) => name.to_owned(),
+            None => format!("x[{index}]"),
+        }
+    }
+
+    // The row's name, or its kind and index without one.
+    pub fn row_label(&self, row: ConstraintRow) -> String {
+        match (self.row(row), row) {
+            (Some(name), _) => name.to_owned(),
+            (None, ConstraintRow::Equality(i)) => format!("equality row {i}"),
+            (None, ConstraintRow::Inequality(i)) => format!("inequality row {i}"),
+        }
+    }
+
+    fn validate(&self, nvars: usize, meq: usize, mineq: usize) -> ProblemResult<()> {
+        for (kind, names, len) in [
+            ("variable", &self.variables, nvars),
+            ("equality", &self.equalities, meq),
+            ("inequality", &self.inequalities, mineq),
+        ] {
+            if !names.is_empty() && names.len() != len {
+                return Err(ProblemError::DimensionMismatch(format!(
+                    "{} {kind} names for {len} {kind} items",
+                    names.len()
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+// Named set of constraint rows (e.g. "supply", "demand") reported together.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConstraintGroup {
+    pub name: String,
+    pub rows: Vec<ConstraintRow>,
+}
+
+fn zero<T: RealNumber>() -> T {
+    T::zero()
+}
+
+fn is_zero<T: RealNumber>(value: &T) -> bool {
+    value.is_zero()
+}
+
+fn validate_offset<T: RealNumber>(offset: T) -> ProblemResult<()> {
+    if !offset.is_finite() {
+        return Err(ProblemError::InvalidStructure(
+            "objective offset must be finite".into(),
+        ));
+    }
+    Ok(())
+}
+
+fn validate_integrality(integrality: &[bool], nvars: usize) -> ProblemResult<()> {
+    if !integrality.is_empty() && integrality.len() != nvars {
+        return Err(ProblemError::DimensionMismatch(format!(
+            "integrality size {} != nvars {nvars}",
+            integrality.len()
+        )));
+    }
+    Ok(())
+}
+
+fn validate_names<T>(
+    names: &Names,
+    nvars: usize,
+    equalities: &Option<EqualityConstraints<T>>,
+    inequalities: &Option<InequalityConstraints<T>>,
+) -> ProblemResult<()> {
+    names.validate(
+        nvars,
+        equalities.as_ref().map_or(0, |eq| eq.matrix.nrows),
+        inequalities.as_ref().map_or(0, |ineq| ineq.matrix.nrows),
+    )
+}
+
+fn validate_groups<T>(
+    groups: &[ConstraintGroup],
+    equalities: &Option<EqualityConstraints<T>>,
+    inequalities: &Option<InequalityConstraints<T>>,
+) -> ProblemResult<()> {
+    let meq = equalities.as_ref().map_or(0, |eq| eq.matrix.nrows);
+    let mineq = inequalities.as_ref().map_or(0, |ineq| ineq.matrix.nrows);
+    for group in groups {
+        for row in &group.rows {
+            let in_range = match *row {
+                ConstraintRow::Equality(i) => i < meq,
+                ConstraintRow::Inequality(i) => i < mineq,
+            };
+            if !in_range {
+                return Err(ProblemError::InvalidStructure(format!(
+                    "group `{}` refers to missing row {row:?}",
+                    group.name
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
+// Whether a problem's objective is minimized or maximized. The solvers of
+// cvxrs-algos take minimizations; `Solver` in cvxrs-api turns a
+// maximization into the minimization of the negated objective and reports
+// the objective of the original, leaving the duals those of the
+// minimization.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ObjectiveSense {
+    #[default]
+    Minimize,
+    Maximize,
+}
+
+impl ObjectiveSense {
+    pub fn is_minimize(&self) -> bool {
+        *self == Self::Minimize
+    }
+
+    fn flipped(self) -> Self {
+        match self {
+            Self::Minimize => Self::Maximize,
+            Self::Maximize => Self::Minimize,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProblemLP<T> {
+    pub cost: Vec<T>,
+    pub inequalities: Option<InequalityConstraints<T>>,
+    pub equalities: Option<EqualityConstraints<T>>,
+    pub bounds: Option<Bounds<T>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub groups: Vec<ConstraintGroup>,
+    // Marks the variables that must take integer values; empty when all
+    // are continuous.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub integrality: Vec<bool>,
+    #[serde(default, skip_serializing_if = "Names::is_empty")]
+    pub names: Names,
+    #[serde(default, skip_serializing_if = "ObjectiveSense::is_minimize")]
+    pub sense: ObjectiveSense,
+    // Constant added to the objective; it moves objective_value but never
+    // the solution.
+    #[serde(
+        default = "zero",
+        skip_serializing_if = "is_zero",
+        bound(
+            serialize = "T: Serialize + RealNumber",
+            deserialize = "T: Deserialize<'de> + RealNumber"
+        )
+    )]
+    pub offset: T,
+}
+
+impl<T> ProblemLP<T>
+where
+    T: RealNumber,
+{
+    pub fn nvars(&self) -> usize {
+        self.cost.len()
+    }
+
+    // The same problem with the other sense: the objective and the offset
+    // negated.
+    pub fn flip_sense(&mut self) {
+        for c in &mut self.cost {
+            *c = -*c;
+        }
+        self.offset = -self.offset;
+        self.sense = self.sense.flipped();
+    }
+
+    pub fn into_minimization(mut self) -> Self {
+        if self.sense == ObjectiveSense::Maximize {
+            self.flip_sense();
+        }
+        self
+    }
+
+    pub fn validate(&self) -> ProblemResult<()> {
+        let n = self.nvars();
+        if let Some(bounds) = &self.bounds {
+            if bounds.lower.len() != n {
+                return Err(ProblemError::DimensionMismatch(format!(
+                    "bounds size {} != nvars {n}",
+                    bounds.lower.len()
+                )));
+            }
+            bounds.validate_named(&self.names)?;
+        }
+        if let Some(eq) = &self.equalities {
+            eq.validate(n)?;
+        }
+        if let Some(ineq) = &self.inequalities {
+            ineq.validate(n)?;
+        }
+        validate_integrality(&self.integrality, n)?;
+        validate_offset(self.offset)?;
+        validate_names(&self.names, n, &self.equalities, &self.inequalities)?;
+        validate_groups(&self.groups, &self.equalities, &self.inequalities)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProblemQP<T> {
+    pub quadratic: CscMatrix<T>,
+    pub linear: Vec<T>,
+    pub inequalities: Option<InequalityConstraints<T>>,
+    pub equalities: Option<EqualityConstraints<T>>,
+    pub bounds: Option<Bounds<T>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub groups: Vec<ConstraintGroup>,
+    // Marks the variables that must take integer values; empty when all
+    // are continuous.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub integrality: Vec<bool>,
+    #[serde(default, skip_serializing_if = "Names::is_empty")]
+    pub names: Names,
+    #[serde(default, skip_serializing_if = "ObjectiveSense::is_minimize")]
+    pub sense: ObjectiveSense,
+    // Constant added to the objective; it moves objective_value but never
+    // the solution.
+    #[serde(
+        default = "zero",
+        skip_serializing_if = "is_zero",
+        bound(
+            serialize = "T: Serialize + RealNumber",
+            deserialize = "T: Deserialize<'de> + RealNumber"
+        )
+    )]
+    pub offset: T,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub quadratic_constraints: Vec<QuadraticConstraint<T>>,
+}
+
+// ½xᵀQx + aᵀx <= rhs with Q positive semidefinite, stored like P.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuadraticConstraint<T> {
+    pub matrix: CscMatrix<T>,
+    pub linear: Vec<T>,
+    pub rhs: T,
+}
+
+impl<T> ProblemQP<T>
+where
+    T: RealNumber,
+{
+    pub fn nvars(&self) -> usize {
+        self.linear.len()
+    }
+
+    // The same problem with the other sense: P, q and the offset negated.
+    pub fn flip_sense(&mut self) {
+        for value in self.quadratic.data.iter_mut().chain(&mut self.linear) {
+            *value = -*value;
+        }
+        self.offset = -self.offset;
+        self.sense = self.sense.flipped();
+    }
+
+    pub fn into_minimization(mut self) -> Self {
+        if self.sense == ObjectiveSense::Maximize {
+            self.flip_sense();
+        }
+        self
+    }
+
+    // The same problem in another precision.
+    pub fn cast<U: RealNumber>(&self) -> ProblemQP<U> {
+        ProblemQP {
+            quadratic: self.quadratic.cast(),
+            linear: cast_slice(&self.linear),
+            inequalities: self
+                .inequalities
+                .as_ref()
+                .map(|ineq| InequalityConstraints {
+                    matrix: ineq.matrix.cast(),
+                    rhs: cast_slice(&ineq.rhs),
+                }),
+            equalities: self.equalities.as_ref().map(|eq| EqualityConstraints {
+                matrix: eq.matrix.cast(),
+                rhs: cast_slice(&eq.rhs),
+            }),
+            bounds: self.bounds.as_ref().map(|bounds| Bounds {
+                lower: cast_slice(&bounds.lower),
+                upper: cast_slice(&bounds.upper),
+            }),
+            groups: self.groups.clone(),
+            integrality: self.integrality.clone(),
+            names: self.names.clone(),
+            sense: self.sense,
+            offset: cast(self.offset),
+            quadratic_constraints: self
+                .quadratic_constraints
+                .iter()
+                .map(|constraint| QuadraticConstraint {
+                    matrix: constraint.matrix.cast(),
+                    linear: cast_slice(&constraint.linear),
+                    rhs: cast(constraint.rhs),
+                })
+                .collect(),
+        }
+    }
+
+    pub fn validate(&self) -> ProblemResult<()> {
+        let n = self.nvars();
+        self.quadratic.validate()?;
+        if self.quadratic.ncols != n || self.quadratic.nrows != n {
+            return Err(ProblemError::DimensionMismatch(format!(
+                "quadratic matrix must be square and match variable dimension {n}"
+            )));
+        }
+        if let Some(bounds) = &self.bounds {
+            if bounds.lower.len() != n {
+                return Err(ProblemError::DimensionMismatch(format!(
+                    "bounds size {} != nvars {n}",
+                    bounds.lower.len()
+                )));
+            }
+            bounds.validate_named(&self.names)?;
+        }
+        if let Some(eq) = &self.equalities {
+            eq.validate(n)?;
+        }
+        if let Some(ineq) = &self.inequalities {
+            ineq.validate(n)?;
+        }
+        for constraint in &self.quadratic_constraints {
+            constraint.validate(n)?;
+        }
+        validate_integrality(&self.integrality, n)?;
+        validate_offset(self.offset)?;
+        validate_names(&self.names, n, &self.equalities, &self.inequalities)?;
+        validate_groups(&self.groups, &self.equalities, &self.inequalities)
+    }
+
+    // The first pivot at which the symmetric part of P, the matrix the
+    // objective actually sees, fails to be positive semidefinite: a negative
+    // diagonal entry, or an LDLᵀ pivot that is negative or zero above a
+    // nonzero column. P must have passed `validate`.
+    pub fn nonconvex_pivot(&self) -> Option<usize> {
+        let n = self.nvars();
+        let p = &self.quadratic;
+        let mut diagonal = vec![T::zero(); n];
+        for (col, entry) in diagonal.iter_mut().enumerate() {
+            for idx in p.indptr[col]..p.indptr[col + 1] {
+                if p.indices[idx] == col {
+                    *entry += p.data[idx];
+                }
+            }
+        }
+        let tolerance = convexity_tolerance(p);
+        if let Some(pivot) = diagonal.iter().position(|&d| d < -tolerance) {
+            return Some(pivot);
+        }
+        if n > CONVEXITY_CHECK_LIMIT {
+            return None;
+        }
+        let mut a = symmetric_dense(p);
+        semidefinite_ldl(&mut a, n, tolerance).err()
+    }
+
+    // Quadratic constraints have no place in the linear constraint blocks
+    // the solvers work with; such problems go through their conic form.
+    pub fn require_linear_constraints(&self) -> ProblemResult<()> {
+        if self.quadratic_constraints.is_empty() {
+            Ok(())
+        } else {
+            Err(ProblemError::InvalidStructure(
+                "quadratic constraints need the conic form, see ProblemConic::from_qp".into(),
+            ))
+        }
+    }
+}
+
+impl<T> QuadraticConstraint<T>
+where
+    T: RealNumber,
+{
+    pub fn validate(&self, nvars: usize) -> ProblemResult<()> {
+        self.matrix.validate()?;
+        if self.matrix.nrows != nvars || self.matrix.ncols != nvars {
+            return Err(ProblemError::DimensionMismatch(format!(
+                "quadratic constraint matrix must be {nvars}x{nvars}"
+            )));
+        }
+        if self.linear.len() != nvars {
+            return Err(ProblemError::DimensionMismatch(format!(
+                "quadratic constraint linear term len {} != nvars {nvars}",
+                self.linear.len()
+            )));
+        }
+        if self.rhs.is_nan() {
+            return Err(ProblemError::InvalidStructure(
+                "quadratic constraint rhs is NaN".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    // Rows of F with Q = FᵀF, from an LDLᵀ factorization that skips zero
+    // pivots; None when Q is not positive semidefinite.
+    pub fn factor(&self) -> Option<Vec<Vec<(usize, T)>>> {
+        let n = self.matrix.ncols;
+        let tolerance = convexity_tolerance(&self.matrix);
+        let mut a = symmetric_dense(&self.matrix);
+        semidefinite_ldl(&mut a, n, tolerance).ok()?;
+        // Column k below the pivot holds d_k·L_ik, so row k of F is that
+        // column over √d_k.
+        let mut rows = Vec::new();
+        for k in 0..n {
+            let pivot = a[k * n + k];
+            if pivot <= tolerance {
+                continue;
+            }
+            let root = pivot.sqrt();
+            rows.push(
+                (k..n)
+                    .map(|i| (i, a[i * n + k] / root))
+                    .filter(|&(_, value)| value != T::zero())
+                    .collect(),
+            );
+        }
+        Some(rows)
+    }
+}
+
+fn convexity_tolerance<T: RealNumber>(matrix: &CscMatrix<T>) -> T {
+    let scale = matrix
+        .data
+        .iter()
+        .fold(T::zero(), |acc, &v| acc.max(v.abs()));
+    T::from_f64(CONVEXITY_TOLERANCE).unwrap() * scale
+}
+
+// The symmetric part of a matrix stored like P, dense and row-major.
+fn symmetric_dense<T: RealNumber>(matrix: &CscMatrix<T>) -> Vec<T> {
+    let n = matrix.ncols;
+    let half = T::one() / (T::one() + T::one());
+    let mut a = vec![T::zero(); n * n];
+    for col in 0..n {
+        for idx in matrix.indptr[col]..matrix.indptr[col + 1] {
+            let (row, value) = (matrix.indices[idx], matrix.data[idx] * half);
+            a[row * n + col] += value;
+            a[col * n + row] += value;
+        }
+    }
+    a
+}
+
+// In-place LDLᵀ of a dense symmetric matrix, updating the lower triangle.
+// Fails with the first pivot that is negative, or zero above a nonzero
+// column; a zero pivot is fine on a singular matrix as long as nothing
+// couples it to the remaining variables.
+fn semidefinite_ldl<T: RealNumber>(a: &mut [T], n: usize, tolerance: T) -> Result<(), usize> {
+    for k in 0..n {
+        let pivot = a[k * n + k];
+        if pivot < -tolerance {
+            return Err(k);
+        }
+        if pivot <= tolerance {
+            if (k + 1..n).any(|i| a[i * n + k].abs() > tolerance) {
+                return Err(k);
+            }
+            continue;
+        }
+        for i in k + 1..n {
+            let factor = a[i * n + k] / pivot;
+            if factor == T::zero() {
+                continue;
+            }
+            for j in k + 1..=i {
+                let update = factor * a[j * n + k];
+                a[i * n + j] -= update;
+            }
+        }
+    }
+    Ok(())
+}
+
+// A QP whose P and constraint matrix A are only available through their
+// products, so structured matrices never need explicit storage:
+// min ½xᵀPx + qᵀx + offset s.t. the first `equalities` rows of Ax equal
+// `rhs`, the others are at most `rhs`, and x stays within `bounds`. A needs
+// `apply_transpose` as well as `apply`.
+#[derive(Clone)]
+pub struct ProblemQpOp<T: RealNumber> {
+    pub quadratic: Arc<dyn LinearOperator<T>>,
+    pub linear: Vec<T>,
+    pub constraints: Option<Arc<dyn LinearOperator<T>>>,
+    pub rhs: Vec<T>,
+    pub equalities: usize,
+    pub bounds: Option<Bounds<T>>,
+    pub offset: T,
+    // diag(P) and the squared column norms of A, when known; the iterative
+    // solves use them to precondition and go without otherwise.
+    pub quadratic_diagonal: Option<Vec<T>>,
+    pub column_norms: Option<Vec<T>>,
+}
+
+impl<T> ProblemQpOp<T>
+where
+    T: RealNumber,
+{
+    pub fn new(quadratic: Arc<dyn LinearOperator<T>>, linear: Vec<T>) -> Self {
+        Self {
+            quadratic,
+            linear,
+            constraints: None,
+            rhs: Vec::new(),
+            equalities: 0,
+            bounds: None,
+            offset: T::zero(),
+            quadratic_diagonal: None,
+            column_norms: None,
+        }
+    }
+
+    pub fn with_constraints(
+        mut self,
+        constraints: Arc<dyn LinearOperator<T>>,
+        rhs: Vec<T>,
+        equalities: usize,
+    ) -> Self {
+        self.constraints = Some(constraints);
+        self.rhs = rhs;
+        self.equalities = equalities;
+        self
+    }
+
+    pub fn with_bounds(mut self, bounds: Bounds<T>) -> Self {
+        self.bounds = Some(bounds);
+        self
+    }
+
+    pub fn with_offset(mut self, offset: T) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    pub fn with_quadratic_diagonal(mut self, diagonal: Vec<T>) -> Self {
+        self.quadratic_diagonal = Some(diagonal);
+        self
+    }
+
+    pub fn with_column_norms(mut self, norms: Vec<T>) -> Self {
+        self.column_norms = Some(norms);
+        self
+    }
+
+    pub fn nvars(&self) -> usize {
+        self.linear.len()
+    }
+
+    pub fn nconstraints(&self) -> usize {
+        self.rhs.len()
+    }
+
+    pub fn validate(&self) -> ProblemResult<()> {
+        let n = self.nvars();
+        if self.quadratic.dim() != (n, n) {
+            let (rows, cols) = self.quadratic.dim();
+            return Err(ProblemError::DimensionMismatch(format!(
+                "quadratic operator is {rows}x{cols} but there are {n} variables"
+            )));
+        }
+        let m = self.nconstraints();
+        match &self.constraints {
+            Some(constraints) if constraints.dim() != (m, n) => {
+                let (rows, cols) = constraints.dim();
+                return Err(ProblemError::DimensionMismatch(format!(
+                    "constraint operator is {rows}x{cols} but there are {m} right-hand sides and {n} variables"
+                )));
+            }
+            None if m > 0 => {
+                return Err(ProblemError::InvalidStructure(
+                    "right-hand sides given without a constraint operator".into(),
+                ));
+            }
+            _ => {}
+        }
+        if self.equalities > m {
+            return Err(ProblemError::DimensionMismatch(format!(
+                "{} equalities among {m} constraint rows",
+                self.equalities
+            )));
+        }
+        if let Some(bounds) = &self.bounds {
+            if bounds.lower.len() != n {
+                return Err(ProblemError::DimensionMismatch(format!(
+                    "bounds size {} != nvars {n}",
+                    bounds.lower.len()
+                )));
+            }
+            bounds.validate()?;
+        }
+        if self
+            .quadratic_diagonal
+            .as_ref()
+            .is_some_and(|d| d.len() != n)
+        {
+            return Err(ProblemError::DimensionMismatch(format!(
+                "quadratic diagonal must have {n} entries"
+            )));
+        }
+        if self.column_norms.as_ref().is_some_and(|d| d.len() != n) {
+            return Err(ProblemError::DimensionMismatch(format!(
+                "column norms must have {n} entries"
+            )));
+        }
+        validate_offset(self.offset)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Cone {
+    Zero(usize),
+    NonNegative(usize),
+    SecondOrder(usize),
+    // Symmetric matrices of the given side, stored as their upper triangle
+    // column by column with off-diagonal entries scaled by √2.
+    PositiveSemidefinite(usize),
+    // Triples (x, y, z) with y e^(x/y) <= z, y > 0, and their closure.
+    Exponential,
+}
+
+impl fmt::Display for Cone {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Cone::Zero(n) => write!(f, "Zero({n})"),
+            Cone::NonNegative(n) => write!(f, "NonNegative({n})"),
+            Cone::SecondOrder(n) => write!(f, "SecondOrder({n})"),
+            Cone::PositiveSemidefinite(n) => write!(f, "PositiveSemidefinite({n})"),
+            Cone::Exponential => write!(f, "Exponential"),
+        }
+    }
+}
+
+impl Cone {
+    pub fn dim(&self) -> usize {
+        match self {
+            Cone::Zero(n) | Cone::NonNegative(n) | Cone::SecondOrder(n) => *n,
+            Cone::PositiveSemidefinite(side) => side * (side + 1) / 2,
+            Cone::Exponential => 3,
+        }
+    }
+}
+
+// minimize 1/2 x'Px + c'x subject to Ax + s = b, s in the product of `cones`
+// taken in order over the rows of A.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProblemConic<T> {
+    pub quadratic: Option<CscMatrix<T>>,
+    pub cost: Vec<T>,
+    pub matrix: CscMatrix<T>,
+    pub rhs: Vec<T>,
+    pub cones: Vec<Cone>,
+}
+
+impl<T> ProblemConic<T>
+where
+    T: RealNumber,
+{
+    pub fn nvars(&self) -> usize {
+        self.cost.len()
+    }
+
+    pub fn validate(&self) -> ProblemResult<()> {
+        let n = self.nvars();
+        if let Some(quadratic) = &self.quadratic {
+            quadratic.validate()?;
+            if quadratic.ncols != n || quadratic.nrows != n {
+                return Err(ProblemError::DimensionMismatch(format!(
+                    "quadratic matrix must be square and match variable dimension {n}"
+                )));
+            }
+        }
+        self.matrix.validate()?;
+        if self.matrix.ncols != n {
+            return Err(ProblemError::DimensionMismatch(format!(
+                "constraint matrix columns {} != nvars {n}",
+                self.matrix.ncols
+            )));
+        }
+        if self.matrix.nrows != self.rhs.len() {
+            return Err(ProblemError::DimensionMismatch(format!(
+                "constraint rows {} != rhs len {}",
+                self.matrix.nrows,
+                self.rhs.len()
+            )));
+        }
+        let cone_rows: usize = self.cones.iter().map(Cone::dim).sum();
+        if cone_rows != self.rhs.len() {
+            return Err(ProblemError::DimensionMismatch(format!(
+                "cones cover {cone_rows} rows but the constraint matrix has {}",
+                self.rhs.len()
+            )));
+        }
+        if let Some(cone) = self
+            .cones
+            .iter()
+            .find(|cone| matches!(cone, Cone::SecondOrder(0) | Cone::PositiveSemidefinite(0)))
+        {
+            return Err(ProblemError::InvalidStructure(format!(
+                "{cone} must have at least one row"
+            )));
+        }
+        Ok(())
+    }
+
+    pub fn from_qp(problem: &ProblemQP<T>) -> ProblemResult<Self> {
+        problem.validate()?;
+        let mut conic = Self::from_constraints(
+            problem.linear.clone(),
+            problem.equalities.as_ref(),
+            problem.inequalities.as_ref(),
+            problem.bounds.as_ref(),
+        );
+        conic.quadratic = Some(problem.quadratic.clone());
+        for (k, constraint) in problem.quadratic_constraints.iter().enumerate() {
+            let factor = constraint.factor().ok_or_else(|| {
+                ProblemError::InvalidStructure(format!("quadratic constraint {k} is not convex"))
+            })?;
+            conic.push_quadratic_constraint(constraint, &factor);
+        }
+        Ok(conic)
+    }
+
+    // ½‖Fx‖² + aᵀx <= r as the rotated cone ‖(Fx, t - ½)‖ <= t + ½ over
+    // t = r - aᵀx, i.e. the block s = (t + ½, Fx, t - ½).
+    fn push_quadratic_constraint(
+        &mut self,
+        constraint: &QuadraticConstraint<T>,
+        factor: &[Vec<(usize, T)>],
+    ) {
+        let half = T::one() / (T::one() + T::one());
+        let linear: Vec<(usize, T)> = constraint
+            .linear
+            .iter()
+            .enumerate()
+            .filter(|&(_, &a)| a != T::zero())
+            .map(|(col, &a)| (col, a))
+            .collect();
+        let mut rows = vec![linear.clone()];
+        self.rhs.push(constraint.rhs + half);
+        for row in factor {
+            rows.push(row.iter().map(|&(col, f)| (col, -f)).collect());
+            self.rhs.push(T::zero());
+        }
+        rows.push(linear);
+        self.rhs.push(constraint.rhs - half);
+
+        let m = &self.matrix;
+        let mut columns: Vec<Vec<(usize, T)>> = (0..m.ncols)
+            .map(|col| {
+                (m.indptr[col]..m.indptr[col + 1])
+                    .map(|idx| (m.indices[idx], m.data[idx]))
+                    .collect()
+            })
+            .collect();
+        for (k, row) in rows.iter().enumerate() {
+            for &(col, value) in row {
+                columns[col].push((m.nrows + k, value));
+            }
+        }
+        let mut indptr = Vec::with_capacity(m.ncols + 1);
+        let mut indices = Vec::new();
+        let mut data = Vec::new();
+        indptr.push(0);
+        for column in columns {
+            for (row, value) in column {
+                indices.push(row);
+                data.push(value);
+            }
+            indptr.push(indices.len());
+        }
+        self.matrix = CscMatrix {
+            nrows: m.nrows + rows.len(),
+            ncols: m.ncols,
+            indptr,
+            indices,
+            data,
+        };
+        self.cones.push(Cone::SecondOrder(rows.len()));
+    }
+
+    pub fn from_lp(problem: &ProblemLP<T>) -> ProblemResult<Self> {
+        problem.validate()?;
+        Ok(Self::from_constraints(
+            problem.cost.clone(),
+            problem.equalities.as_ref(),
+            problem.inequalities.as_ref(),
+            problem.bounds.as_ref(),
+        ))
+    }
+
+    // Equalities go to a zero cone; inequalities and finite bounds (as x <= u
+    // and -x <= -l rows) go to one nonnegative cone.
+    fn from_constraints(
+        cost: Vec<T>,
+        equalities: Option<&EqualityConstraints<T>>,
+        inequalities: Option<&InequalityConstraints<T>>,
+        bounds: Option<&Bounds<T>>,
+    ) -> Self {
+        let n = cost.len();
+        let mut columns: Vec<Vec<(usize, T)>> = vec![Vec::new(); n];
+        let mut rhs = Vec::new();
+        let mut append = |matrix: &CscMatrix<T>, values: &[T], rhs: &mut Vec<T>| {
+            let offset = rhs.len();
+            for (col, column) in columns.iter_mut().enumerate() {
+                for idx in matrix.indptr[col]..matrix.indptr[col + 1] {
+                    column.push((offset + matrix.indices[idx], matrix.data[idx]));
+                }
+            }
+            rhs.extend_from_slice(values);
+        };
+        if let Some(eq) = equalities {
+            append(&eq.matrix, &eq.rhs, &mut rhs);
+        }
+        let zero_rows = rhs.len();
+        if let Some(ineq) = inequalities {
+            append(&ineq.matrix, &ineq.rhs, &mut rhs);
+        }
+        if let Some(bounds) = bounds {
+            for (col, (&lower, &upper)) in bounds.lower.iter().zip(&bounds.upper).enumerate() {
+                if upper.is_finite() {
+                    columns[col].push((rhs.len(), T::one()));
+                    rhs.push(upper);
+                }
+                if lower.is_finite() {
+                    columns[col].push((rhs.len(), -T::one()));
+                    rhs.push(-lower);
+                }
+            }
+        }
+
+        let mut indptr = Vec::with_capacity(n + 1);
+        let mut indices = Vec::new();
+        let mut data = Vec::new();
+        indptr.push(0);
+        for mut column in columns {
+            column.sort_by_key(|&(row, _)| row);
+            for (row, value) in column {
+                indices.push(row);
+                data.push(value);
+            }
+            indptr.push(indices.len());
+        }
+        let mut cones = Vec::new();
+        if zero_rows > 0 {
+            cones.push(Cone::Zero(zero_rows));
+        }
+        if rhs.len() > zero_rows {
+            cones.push(Cone::NonNegative(rhs.len() - zero_rows));
+        }
+        Self {
+            quadratic: None,
+            cost,
+            matrix: CscMatrix {
+                nrows: rhs.len(),
+                ncols: n,
+                indptr,
+                indices,
+                data,
+            },
+            rhs,
+            cones,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WarmStart<T> {
     pub primal: Vec<T>,
     pub equality_dual: Vec<T>,
@@ -306,6 +1807,20 @@ where
     }
 }
 
+// Iterate of an interrupted solve in the solver's scaled space, enough to
+// continue exactly where it stopped on the same problem.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SolverState<T> {
+    pub method: Method,
+    pub iteration: usize,
+    pub rho: T,
+    pub primal: Vec<T>,
+    pub slack: Vec<T>,
+    pub dual: Vec<T>,
+}
+
+pub type CheckpointSink<T> = Arc<dyn Fn(&SolverState<T>) -> anyhow::Result<()> + Send + Sync>;
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -329,6 +1844,116 @@ mod tests {
         }
     }
 
+    #[test]
+    fn triplets_are_sorted_and_summed() {
+        let matrix = CscMatrix::from_triplets(
+            3,
+            2,
+            &[
+                (2, 1, 4.0),
+                (0, 0, 1.0),
+                (1, 1, -1.0),
+                (2, 1, 0.5),
+                (2, 0, 3.0),
+            ],
+        )
+        .unwrap();
+        assert_eq!(matrix.indptr, vec![0, 2, 4]);
+        assert_eq!(matrix.indices, vec![0, 2, 1, 2]);
+        assert_eq!(matrix.data, vec![1.0, 3.0, -1.0, 4.5]);
+
+        let mut builder = TripletBuilder::new(3, 2);
+        builder.push(0, 0, 1.0).push(2, 0, 3.0);
+        builder.push(1, 1, -1.0).push(2, 1, 4.5);
+        let built = builder.build().unwrap();
+        assert_eq!(built.indptr, matrix.indptr);
+        assert_eq!(built.indices, matrix.indices);
+        assert_eq!(built.data, matrix.data);
+
+        assert!(CscMatrix::from_triplets(2, 2, &[(2, 0, 1.0)]).is_err());
+        assert!(TripletBuilder::<f64>::new(0, 3)
+            .build()
+            .unwrap()
+            .validate()
+            .is_ok());
+    }
+
+    #[test]
+    fn dense_input_drops_explicit_zeros() {
+        // [1 0 2; 0 0 3]
+        let matrix = CscMatrix::from_dense(2, 3, &[1.0, 0.0, 2.0, 0.0, 0.0, 3.0]).unwrap();
+        assert_eq!(matrix.indptr, vec![0, 1, 1, 3]);
+        assert_eq!(matrix.indices, vec![0, 0, 1]);
+        assert_eq!(matrix.data, vec![1.0, 2.0, 3.0]);
+        let columns =
+            CscMatrix::from_columns(&[vec![1.0, 0.0], vec![0.0, 0.0], vec![2.0, 3.0]]).unwrap();
+        assert_eq!(columns.nrows, 2);
+        assert_eq!(columns.indptr, matrix.indptr);
+        assert_eq!(columns.indices, matrix.indices);
+        assert_eq!(columns.data, matrix.data);
+
+        assert!(CscMatrix::from_dense(2, 2, &[1.0; 3]).is_err());
+        assert!(CscMatrix::from_columns(&[vec![1.0], vec![1.0, 2.0]]).is_err());
+    }
+
+    #[test]
+    fn sprs_matrices_convert_both_ways() {
+        let mut triplets = sprs::TriMat::new((3, 2));
+        triplets.add_triplet(2, 0, 1.0);
+        triplets.add_triplet(0, 1, -2.0);
+        triplets.add_triplet(1, 0, 3.0);
+        for csmat in [triplets.to_csc(), triplets.to_csr()] {
+            let matrix = CscMatrix::from(csmat);
+            assert!(matrix.validate().is_ok());
+            assert_eq!(matrix.indptr, vec![0, 2, 3]);
+            assert_eq!(matrix.indices, vec![1, 2, 0]);
+            assert_eq!(matrix.data, vec![3.0, 1.0, -2.0]);
+            let back = CsMat::try_from(matrix).unwrap();
+            assert_eq!(back, triplets.to_csc());
+        }
+        assert!(CsMat::try_from(CscMatrix::<f64> {
+            nrows: 1,
+            ncols: 1,
+            indptr: vec![0, 1],
+            indices: vec![1],
+            data: vec![1.0],
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn csr_and_csc_round_trip() {
+        // [1 0 2; 0 0 3; 4 5 0]
+        let csr = CsrMatrix {
+            nrows: 3,
+            ncols: 3,
+            indptr: vec![0, 2, 3, 5],
+            indices: vec![0, 2, 2, 0, 1],
+            data: vec![1.0, 2.0, 3.0, 4.0, 5.0],
+        };
+        assert!(csr.validate().is_ok());
+        let csc = csr.to_csc();
+        assert!(csc.validate().is_ok());
+        assert_eq!(csc.indptr, vec![0, 2, 3, 5]);
+        assert_eq!(csc.indices, vec![0, 2, 2, 0, 1]);
+        assert_eq!(csc.data, vec![1.0, 4.0, 5.0, 2.0, 3.0]);
+        let back = CsrMatrix::from(&csc);
+        assert_eq!(back.indptr, csr.indptr);
+        assert_eq!(back.indices, csr.indices);
+        assert_eq!(back.data, csr.data);
+
+        let triplets = CsrMatrix::from_triplets(3, 3, &[(2, 1, 5.0), (0, 2, 2.0)]).unwrap();
+        assert_eq!(triplets.indptr, vec![0, 1, 1, 2]);
+        assert_eq!(triplets.indices, vec![2, 1]);
+
+        let unsorted = CsrMatrix {
+            indices: vec![2, 0, 2, 0, 1],
+            ..csr
+        };
+        let message = unsorted.validate().unwrap_err().to_string();
+        assert!(message.contains("column indices in row 0"), "{message}");
+    }
+
     #[test]
     fn qp_validation_passes() {
         let n = 3;
@@ -341,10 +1966,192 @@ mod tests {
                 lower: vec![0.0; n],
                 upper: vec![1.0; n],
             }),
+            groups: Vec::new(),
+            integrality: Vec::new(),
+            names: Names::default(),
+            sense: ObjectiveSense::Minimize,
+            offset: 0.0,
+            quadratic_constraints: Vec::new(),
         };
         assert!(qp.validate().is_ok());
     }
 
+    fn qp_with(quadratic: CscMatrix<f64>) -> ProblemQP<f64> {
+        ProblemQP {
+            linear: vec![0.0; quadratic.ncols],
+            quadratic,
+            inequalities: None,
+            equalities: None,
+            bounds: None,
+            groups: Vec::new(),
+            integrality: Vec::new(),
+            names: Names::default(),
+            sense: ObjectiveSense::Minimize,
+            offset: 0.0,
+            quadratic_constraints: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn convexity_check_accepts_singular_and_triangular_p() {
+        // [1 1; 1 1] only in its upper triangle: the objective sees
+        // [1 ½; ½ 1], which is definite.
+        let upper = CscMatrix {
+            nrows: 2,
+            ncols: 2,
+            indptr: vec![0, 1, 3],
+            indices: vec![0, 0, 1],
+            data: vec![1.0, 1.0, 1.0],
+        };
+        assert_eq!(qp_with(upper).nonconvex_pivot(), None);
+        // The rank-one [1 1 0; 1 1 0; 0 0 0] has two zero pivots.
+        let singular = CscMatrix {
+            nrows: 3,
+            ncols: 3,
+            indptr: vec![0, 2, 4, 4],
+            indices: vec![0, 1, 0, 1],
+            data: vec![1.0, 1.0, 1.0, 1.0],
+        };
+        assert_eq!(qp_with(singular).nonconvex_pivot(), None);
+        assert_eq!(qp_with(CscMatrix::empty()).nonconvex_pivot(), None);
+    }
+
+    #[test]
+    fn convexity_check_reports_the_failing_pivot() {
+        let mut negative = diagonal(3);
+        negative.data[2] = -1.0;
+        assert_eq!(qp_with(negative).nonconvex_pivot(), Some(2));
+        // [1 2; 2 1] has eigenvalues 3 and -1; the second pivot is -3.
+        let indefinite = CscMatrix {
+            nrows: 2,
+            ncols: 2,
+            indptr: vec![0, 2, 4],
+            indices: vec![0, 1, 0, 1],
+            data: vec![1.0, 2.0, 2.0, 1.0],
+        };
+        assert_eq!(qp_with(indefinite).nonconvex_pivot(), Some(1));
+        // [0 1; 1 0] has a zero first pivot coupled to the second variable.
+        let saddle = CscMatrix {
+            nrows: 2,
+            ncols: 2,
+            indptr: vec![0, 1, 2],
+            indices: vec![1, 0],
+            data: vec![1.0, 1.0],
+        };
+        assert_eq!(qp_with(saddle).nonconvex_pivot(), Some(0));
+    }
+
+    #[test]
+    fn csc_rejects_malformed_indices() {
+        let mut matrix = diagonal(3);
+        matrix.indices[1] = 7;
+        assert!(matrix.validate().is_err());
+        assert!(matrix.to_csmat().is_err());
+
+        let mut matrix = diagonal(3);
+        matrix.indptr = vec![0, 2, 1, 3];
+        assert!(matrix.validate().is_err());
+
+        let mut matrix = diagonal(4);
+        matrix.indptr = vec![0, 1, 9_999_999_999_992, 3, 4];
+        assert!(matrix.validate().is_err());
+        assert!(matrix.to_csmat().is_err());
+
+        let mut matrix = diagonal(2);
+        matrix.indptr = vec![0, 2, 2];
+        matrix.indices = vec![1, 0];
+        assert!(matrix.validate().is_err());
+        assert!(diagonal(3).to_csmat().is_ok());
+    }
+
+    #[test]
+    fn conic_form_stacks_equalities_then_nonnegative_rows() {
+        let qp = ProblemQP {
+            quadratic: diagonal(2),
+            linear: vec![1.0, -1.0],
+            inequalities: Some(InequalityConstraints {
+                matrix: CscMatrix {
+                    nrows: 1,
+                    ncols: 2,
+                    indptr: vec![0, 1, 2],
+                    indices: vec![0, 0],
+                    data: vec![1.0, 2.0],
+                },
+                rhs: vec![3.0],
+            }),
+            equalities: Some(EqualityConstraints {
+                matrix: CscMatrix {
+                    nrows: 1,
+                    ncols: 2,
+                    indptr: vec![0, 1, 1],
+                    indices: vec![0],
+                    data: vec![1.0],
+                },
+                rhs: vec![0.5],
+            }),
+            bounds: Some(Bounds {
+                lower: vec![0.0, f64::NEG_INFINITY],
+                upper: vec![f64::INFINITY, 4.0],
+            }),
+            groups: Vec::new(),
+            integrality: Vec::new(),
+            names: Names::default(),
+            sense: ObjectiveSense::Minimize,
+            offset: 0.0,
+            quadratic_constraints: Vec::new(),
+        };
+        let conic = ProblemConic::from_qp(&qp).unwrap();
+        assert!(conic.validate().is_ok());
+        assert_eq!(conic.rhs, vec![0.5, 3.0, -0.0, 4.0]);
+        assert_eq!(conic.matrix.indptr, vec![0, 3, 5]);
+        assert_eq!(conic.matrix.indices, vec![0, 1, 2, 1, 3]);
+        assert_eq!(conic.matrix.data, vec![1.0, 1.0, -1.0, 2.0, 1.0]);
+        assert!(matches!(
+            conic.cones[..],
+            [Cone::Zero(1), Cone::NonNegative(3)]
+        ));
+    }
+
+    #[test]
+    fn quadratic_constraints_become_rotated_cones() {
+        // x0 + ½(x0² + x1²) <= 1, with x0 + s0 = 1.5, -x = s1..2 and
+        // x0 + s3 = 0.5.
+        let mut qp = ProblemQP {
+            quadratic: CscMatrix {
+                nrows: 2,
+                ncols: 2,
+                indptr: vec![0; 3],
+                indices: Vec::new(),
+                data: Vec::new(),
+            },
+            linear: vec![0.0, 1.0],
+            inequalities: None,
+            equalities: None,
+            bounds: None,
+            groups: Vec::new(),
+            integrality: Vec::new(),
+            names: Names::default(),
+            sense: ObjectiveSense::Minimize,
+            offset: 0.0,
+            quadratic_constraints: vec![QuadraticConstraint {
+                matrix: diagonal(2),
+                linear: vec![1.0, 0.0],
+                rhs: 1.0,
+            }],
+        };
+        assert!(qp.require_linear_constraints().is_err());
+        let conic = ProblemConic::from_qp(&qp).unwrap();
+        assert!(conic.validate().is_ok());
+        assert!(matches!(conic.cones[..], [Cone::SecondOrder(4)]));
+        assert_eq!(conic.rhs, vec![1.5, 0.0, 0.0, 0.5]);
+        assert_eq!(conic.matrix.indptr, vec![0, 3, 4]);
+        assert_eq!(conic.matrix.indices, vec![0, 1, 3, 2]);
+        assert_eq!(conic.matrix.data, vec![1.0, -1.0, 1.0, -1.0]);
+
+        qp.quadratic_constraints[0].matrix.data[1] = -1.0;
+        assert!(ProblemConic::from_qp(&qp).is_err());
+    }
+
     #[test]
     fn lp_detects_mismatch() {
         let lp = ProblemLP {
@@ -355,7 +2162,116 @@ mod tests {
                 lower: vec![0.0],
                 upper: vec![1.0],
             }),
+            groups: Vec::new(),
+            integrality: Vec::new(),
+            names: Names::default(),
+            sense: ObjectiveSense::Minimize,
+            offset: 0.0,
         };
         assert!(lp.validate().is_err());
     }
+
+    #[test]
+    fn names_are_checked_and_survive_json() {
+        let mut qp = qp_with(diagonal(2));
+        qp.bounds = Some(Bounds {
+            lower: vec![0.0, 2.0],
+            upper: vec![1.0, 1.0],
+        });
+        qp.names.variables = vec!["stock".into(), "budget".into()];
+        let err = qp.validate().unwrap_err().to_string();
+        assert!(err.contains("`budget`"), "{err}");
+
+        qp.bounds = None;
+        qp.names.inequalities = vec!["capacity".into()];
+        assert!(qp.validate().is_err());
+        qp.names.inequalities.clear();
+        assert!(qp.validate().is_ok());
+
+        let json = serde_json::to_string(&qp).unwrap();
+        assert!(!json.contains("equalities\":[]"));
+        let back: ProblemQP<f64> = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.names, qp.names);
+        assert_eq!(back.names.variable_index("budget"), Some(1));
+        assert_eq!(back.names.variable_label(1), "budget");
+        assert_eq!(
+            back.names.row_label(ConstraintRow::Inequality(0)),
+            "inequality row 0"
+        );
+    }
+
+    #[test]
+    fn row_senses_split_into_both_parts_and_map_duals_back() {
+        // Rows x0 <= 4, x0 + x1 >= 1, x1 = 2, -1 <= x0 - x1 <= 3 and a row
+        // with no finite side.
+        let matrix = CscMatrix::from_triplets(
+            5,
+            2,
+            &[
+                (0, 0, 1.0),
+                (1, 0, 1.0),
+                (1, 1, 1.0),
+                (2, 1, 1.0),
+                (3, 0, 1.0),
+                (3, 1, -1.0),
+                (4, 0, 1.0),
+            ],
+        )
+        .unwrap();
+        let block = LinearConstraints::new(
+            matrix,
+            vec![
+                RowSense::Le(4.0),
+                RowSense::Ge(1.0),
+                RowSense::Eq(2.0),
+                RowSense::Range(-1.0, 3.0),
+                RowSense::between(f64::NEG_INFINITY, f64::INFINITY),
+            ],
+        );
+        let (eq, ineq) = block.split().unwrap();
+        let (eq, ineq) = (eq.unwrap(), ineq.unwrap());
+        assert_eq!(eq.rhs, vec![2.0]);
+        assert_eq!(eq.matrix.to_csr().data, vec![1.0]);
+        assert_eq!(ineq.rhs, vec![4.0, -1.0, 3.0, 1.0]);
+        let csr = ineq.matrix.to_csr();
+        assert_eq!(csr.indptr, vec![0, 1, 3, 5, 7]);
+        assert_eq!(csr.data, vec![1.0, -1.0, -1.0, 1.0, -1.0, -1.0, 1.0]);
+
+        let expected = vec![0.5, -2.0, 7.0, 3.0 - 4.0, 0.0];
+        let separate = block.row_duals(&[7.0], &[0.5, 2.0, 3.0, 4.0]);
+        assert_eq!(separate, expected);
+        let stacked = block.row_duals(&[], &[7.0, 0.5, 2.0, 3.0, 4.0, 9.0, 9.0]);
+        assert_eq!(stacked, expected);
+
+        let back = LinearConstraints::from_parts(Some(eq), Some(ineq), 2).unwrap();
+        assert_eq!(back.senses[0], RowSense::Eq(2.0));
+        assert_eq!(back.senses[2], RowSense::Le(-1.0));
+        assert_eq!(back.matrix.to_csr().data[..3], [1.0, 1.0, -1.0]);
+        assert!(LinearConstraints::new(diagonal(2), vec![RowSense::Le(1.0)])
+            .split()
+            .is_err());
+    }
+
+    #[test]
+    fn flipping_the_sense_negates_the_objective_and_survives_json() {
+        let mut qp = qp_with(diagonal(2));
+        qp.linear = vec![1.0, -2.0];
+        qp.offset = 0.5;
+        let json = serde_json::to_string(&qp).unwrap();
+        assert!(!json.contains("sense"));
+
+        qp.flip_sense();
+        assert_eq!(qp.sense, ObjectiveSense::Maximize);
+        assert_eq!(qp.quadratic.data, vec![-1.0, -1.0]);
+        assert_eq!((qp.linear.clone(), qp.offset), (vec![-1.0, 2.0], -0.5));
+        let back: ProblemQP<f64> =
+            serde_json::from_str(&serde_json::to_string(&qp).unwrap()).unwrap();
+        assert_eq!(back.sense, ObjectiveSense::Maximize);
+
+        let min = back.into_minimization();
+        assert_eq!(min.sense, ObjectiveSense::Minimize);
+        assert_eq!(min.quadratic.data, vec![1.0, 1.0]);
+        assert_eq!((min.linear.clone(), min.offset), (vec![1.0, -2.0], 0.5));
+        assert_eq!(min.clone().into_minimization().linear, min.linear);
+    }
 }
@@ -0,0 +1,365 @@
+use crate::math::RealNumber;
+use crate::problem::{
+    Cone, CscMatrix, ProblemConic, ProblemError, ProblemLP, ProblemQP, ProblemResult,
+};
+use serde::{Deserialize, Serialize};
+
+// Uncertainty in the coefficients a of one inequality row aᵀx <= b, around
+// the nominal row stored in the problem.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Uncertainty<T> {
+    // Each a_j may move by up to radius[j] either way.
+    Box(Vec<T>),
+    // a = ā + Lu for every ‖u‖₂ <= 1, with L given as an n×k matrix.
+    Ellipsoid(CscMatrix<T>),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RobustRow<T> {
+    // Index into the inequality rows.
+    pub row: usize,
+    pub uncertainty: Uncertainty<T>,
+}
+
+// The robust counterpart of `problem`, in which every listed row holds for
+// all coefficients in its set. A box row becomes āᵀx + δᵀt <= b with
+// t >= |x| in variables appended after x; an ellipsoid row becomes the
+// second-order cone constraint ‖Lᵀx‖₂ <= b - āᵀx. As with
+// `ProblemConic::from_qp`, the objective offset is left out.
+pub fn robust_qp<T: RealNumber>(
+    problem: &ProblemQP<T>,
+    rows: &[RobustRow<T>],
+) -> ProblemResult<ProblemConic<T>> {
+    reject_integers(&problem.integrality)?;
+    let conic = ProblemConic::from_qp(problem)?;
+    counterpart(
+        conic,
+        problem.equalities.as_ref().map_or(0, |eq| eq.matrix.nrows),
+        problem
+            .inequalities
+            .as_ref()
+            .map_or(0, |ineq| ineq.matrix.nrows),
+        rows,
+    )
+}
+
+pub fn robust_lp<T: RealNumber>(
+    problem: &ProblemLP<T>,
+    rows: &[RobustRow<T>],
+) -> ProblemResult<ProblemConic<T>> {
+    reject_integers(&problem.integrality)?;
+    let conic = ProblemConic::from_lp(problem)?;
+    counterpart(
+        conic,
+        problem.equalities.as_ref().map_or(0, |eq| eq.matrix.nrows),
+        problem
+            .inequalities
+            .as_ref()
+            .map_or(0, |ineq| ineq.matrix.nrows),
+        rows,
+    )
+}
+
+fn reject_integers(integrality: &[bool]) -> ProblemResult<()> {
+    if integrality.contains(&true) {
+        return Err(ProblemError::InvalidStructure(
+            "robust counterparts of integer problems are not supported".into(),
+        ));
+    }
+    Ok(())
+}
+
+// `conic` comes from `ProblemConic::from_constraints`: `meq` zero rows, then
+// the `mineq` inequality rows and the bound rows in one nonnegative cone.
+fn counterpart<T: RealNumber>(
+    conic: ProblemConic<T>,
+    meq: usize,
+    mineq: usize,
+    robust: &[RobustRow<T>],
+) -> ProblemResult<ProblemConic<T>> {
+    let n = conic.nvars();
+    let mut uncertainty = vec![None; mineq];
+    for entry in robust {
+        let slot = uncertainty.get_mut(entry.row).ok_or_else(|| {
+            ProblemError::DimensionMismatch(format!(
+                "robust row {} but the problem has {mineq} inequality rows",
+                entry.row
+            ))
+        })?;
+        if slot.replace(&entry.uncertainty).is_some() {
+            return Err(ProblemError::InvalidStructure(format!(
+                "inequality row {} has more than one uncertainty set",
+                entry.row
+            )));
+        }
+        match &entry.uncertainty {
+            Uncertainty::Box(radius) => {
+                if radius.len() != n {
+                    return Err(ProblemError::DimensionMismatch(format!(
+                        "box radius has {} entries, expected {n}",
+                        radius.len()
+                    )));
+                }
+                if radius.iter().any(|&r| !r.is_finite() || r < T::zero()) {
+                    return Err(ProblemError::InvalidStructure(format!(
+                        "box radius of row {} must be finite and nonnegative",
+                        entry.row
+                    )));
+                }
+            }
+            Uncertainty::Ellipsoid(shape) => {
+                shape.validate()?;
+                if shape.nrows != n {
+                    return Err(ProblemError::DimensionMismatch(format!(
+                        "ellipsoid shape has {} rows, expected {n}",
+                        shape.nrows
+                    )));
+                }
+            }
+        }
+    }
+
+    // One t_j per variable that some box row lets vary.
+    let mut absolute = vec![None; n];
+    let mut extra = 0;
+    for set in uncertainty.iter().flatten() {
+        if let Uncertainty::Box(radius) = set {
+            for (slot, &r) in absolute.iter_mut().zip(radius) {
+                if r > T::zero() && slot.is_none() {
+                    *slot = Some(n + extra);
+                    extra += 1;
+                }
+            }
+        }
+    }
+
+    let nominal = row_entries(&conic.matrix);
+    let mut rows = Vec::with_capacity(nominal.len() + 2 * extra);
+    let mut rhs = Vec::with_capacity(nominal.len() + 2 * extra);
+    let mut ellipsoids = Vec::new();
+    for (i, (mut entries, b)) in nominal.into_iter().zip(conic.rhs).enumerate() {
+        match i
+            .checked_sub(meq)
+            .and_then(|row| uncertainty.get(row))
+            .copied()
+            .flatten()
+        {
+            Some(Uncertainty::Box(radius)) => {
+                for (slot, &r) in absolute.iter().zip(radius) {
+                    if let Some(t) = *slot {
+                        if r > T::zero() {
+                            entries.push((t, r));
+                        }
+                    }
+                }
+            }
+            Some(Uncertainty::Ellipsoid(shape)) => {
+                ellipsoids.push((entries, b, shape));
+                continue;
+            }
+            None => {}
+        }
+        rows.push(entries);
+        rhs.push(b);
+    }
+    // x_j - t_j <= 0 and -x_j - t_j <= 0.
+    for (j, slot) in absolute.iter().enumerate() {
+        if let Some(t) = *slot {
+            for sign in [T::one(), -T::one()] {
+                rows.push(vec![(j, sign), (t, -T::one())]);
+                rhs.push(T::zero());
+            }
+        }
+    }
+    let mut cones = Vec::new();
+    if meq > 0 {
+        cones.push(Cone::Zero(meq));
+    }
+    if rows.len() > meq {
+        cones.push(Cone::NonNegative(rows.len() - meq));
+    }
+    // s = (b - āᵀx, Lᵀx): the leading row is the nominal one and the rest
+    // hold -Lᵀ against a zero right-hand side.
+    for (entries, b, shape) in ellipsoids {
+        rows.push(entries);
+        rhs.push(b);
+        for col in 0..shape.ncols {
+            rows.push(
+                (shape.indptr[col]..shape.indptr[col + 1])
+                    .map(|idx| (shape.indices[idx], -shape.data[idx]))
+                    .collect(),
+            );
+            rhs.push(T::zero());
+        }
+        cones.push(Cone::SecondOrder(1 + shape.ncols));
+    }
+
+    let ncols = n + extra;
+    let mut cost = conic.cost;
+    cost.resize(ncols, T::zero());
+    let quadratic = conic.quadratic.map(|mut p| {
+        p.nrows = ncols;
+        p.ncols = ncols;
+        let end = p.indices.len();
+        p.indptr.resize(ncols + 1, end);
+        p
+    });
+    Ok(ProblemConic {
+        quadratic,
+        cost,
+        matrix: from_rows(&rows, ncols),
+        rhs,
+        cones,
+    })
+}
+
+fn row_entries<T: RealNumber>(matrix: &CscMatrix<T>) -> Vec<Vec<(usize, T)>> {
+    let mut rows = vec![Vec::new(); matrix.nrows];
+    for col in 0..matrix.ncols {
+        for idx in matrix.indptr[col]..matrix.indptr[col + 1] {
+            rows[matrix.indices[idx]].push((col, matrix.data[idx]));
+        }
+    }
+    rows
+}
+
+fn from_rows<T: RealNumber>(rows: &[Vec<(usize, T)>], ncols: usize) -> CscMatrix<T> {
+    let mut columns: Vec<Vec<(usize, T)>> = vec![Vec::new(); ncols];
+    for (row, entries) in rows.iter().enumerate() {
+        for &(col, value) in entries {
+            columns[col].push((row, value));
+        }
+    }
+    let mut indptr = Vec::with_capacity(ncols + 1);
+    let mut indices = Vec::new();
+    let mut data = Vec::new();
+    indptr.push(0);
+    for column in columns {
+        for (row, value) in column {
+            indices.push(row);
+            data.push(value);
+        }
+        indptr.push(indices.len());
+    }
+    CscMatrix {
+        nrows: rows.len(),
+        ncols,
+        indptr,
+        indices,
+        data,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cones::project_cone;
+    use crate::problem::{Bounds, InequalityConstraints, Names, ObjectiveSense};
+
+    // max x0 + x1 over x0 + x1 <= 2, x >= 0.
+    fn simplex_lp() -> ProblemLP<f64> {
+        ProblemLP {
+            cost: vec![-1.0, -1.0],
+            inequalities: Some(InequalityConstraints {
+                matrix: CscMatrix {
+                    nrows: 1,
+                    ncols: 2,
+                    indptr: vec![0, 1, 2],
+                    indices: vec![0, 0],
+                    data: vec![1.0, 1.0],
+                },
+                rhs: vec![2.0],
+            }),
+            equalities: None,
+            bounds: Some(Bounds {
+                lower: vec![0.0, 0.0],
+                upper: vec![f64::INFINITY, f64::INFINITY],
+            }),
+            groups: Vec::new(),
+            integrality: Vec::new(),
+            names: Names::default(),
+            sense: ObjectiveSense::Minimize,
+            offset: 0.0,
+        }
+    }
+
+    // Whether b - Ax lies in the cones of `conic`.
+    fn feasible(conic: &ProblemConic<f64>, x: &[f64]) -> bool {
+        let mut slack = conic.rhs.clone();
+        for (col, &value) in x.iter().enumerate() {
+            for idx in conic.matrix.indptr[col]..conic.matrix.indptr[col + 1] {
+                slack[conic.matrix.indices[idx]] -= conic.matrix.data[idx] * value;
+            }
+        }
+        let mut start = 0;
+        conic.cones.iter().all(|cone| {
+            let block = &slack[start..start + cone.dim()];
+            start += cone.dim();
+            let mut projected = block.to_vec();
+            project_cone(cone, &mut projected);
+            block
+                .iter()
+                .zip(&projected)
+                .all(|(a, b)| (a - b).abs() < 1e-12)
+        })
+    }
+
+    #[test]
+    fn box_rows_pay_the_radius_on_every_variable() {
+        let robust = robust_lp(
+            &simplex_lp(),
+            &[RobustRow {
+                row: 0,
+                uncertainty: Uncertainty::Box(vec![0.5, 0.0]),
+            }],
+        )
+        .unwrap();
+        robust.validate().unwrap();
+        // Only x0 is uncertain, so one t is added.
+        assert_eq!(robust.nvars(), 3);
+        // 1.5 x0 + x1 <= 2 in the worst case.
+        assert!(feasible(&robust, &[1.0, 0.5, 1.0]));
+        assert!(!feasible(&robust, &[1.0, 0.6, 1.0]));
+        // t must cover |x0|.
+        assert!(!feasible(&robust, &[1.0, 0.0, 0.9]));
+    }
+
+    #[test]
+    fn ellipsoid_rows_become_second_order_cones() {
+        // a = (1, 1) + u with ‖u‖ <= 0.5: x + 0.5‖x‖ <= 2 summed over x.
+        let shape = CscMatrix {
+            nrows: 2,
+            ncols: 2,
+            indptr: vec![0, 1, 2],
+            indices: vec![0, 1],
+            data: vec![0.5, 0.5],
+        };
+        let robust = robust_lp(
+            &simplex_lp(),
+            &[RobustRow {
+                row: 0,
+                uncertainty: Uncertainty::Ellipsoid(shape),
+            }],
+        )
+        .unwrap();
+        robust.validate().unwrap();
+        assert!(matches!(robust.cones.last(), Some(Cone::SecondOrder(3))));
+        // At x = (c, c): 2c + 0.5√2 c <= 2.
+        let c = 2.0 / (2.0 + 0.5 * 2f64.sqrt());
+        assert!(feasible(&robust, &[c - 1e-9, c - 1e-9]));
+        assert!(!feasible(&robust, &[c + 1e-6, c + 1e-6]));
+    }
+
+    #[test]
+    fn rows_are_checked() {
+        let row = |row, radius: Vec<f64>| RobustRow {
+            row,
+            uncertainty: Uncertainty::Box(radius),
+        };
+        let lp = simplex_lp();
+        assert!(robust_lp(&lp, &[row(1, vec![0.0; 2])]).is_err());
+        assert!(robust_lp(&lp, &[row(0, vec![0.0; 3])]).is_err());
+        assert!(robust_lp(&lp, &[row(0, vec![-1.0, 0.0])]).is_err());
+        assert!(robust_lp(&lp, &[row(0, vec![0.0; 2]), row(0, vec![0.0; 2])]).is_err());
+    }
+}
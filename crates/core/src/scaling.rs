@@ -1,32 +1,19 @@
 use crate::math::RealNumber;
-use crate::problem::{Bounds, CscMatrix, ProblemLP, ProblemQP, ProblemResult};
+use crate::options::Scaling;
+use crate::problem::{
+    Bounds, CscMatrix, EqualityConstraints, InequalityConstraints, ProblemError, ProblemLP,
+    ProblemQP, ProblemResult,
+};
 use crate::stats::SolveStats;
 use crate::traits::Scaler;
-use num_traits::One;
-
-fn equilibrate_columns<T: RealNumber>(matrix: &CscMatrix<T>, scaling: &mut [T]) {
-    for col in 0..matrix.ncols {
-        let start = matrix.indptr[col];
-        let end = matrix.indptr[col + 1];
-        let mut max_val = T::zero();
-        for idx in start..end {
-            let value = matrix.data[idx].abs();
-            if value > max_val {
-                max_val = value;
-            }
-        }
-        if max_val > T::zero() {
-            let factor = max_val.sqrt();
-            if factor > T::zero() {
-                scaling[col] = scaling[col] / factor;
-            }
-        }
-    }
-}
 
+// Ruiz equilibration of [P Aᵀ; A 0]: each pass divides every row and
+// column by the square root of its infinity norm, which drives all of them
+// towards one. A single cost factor c then brings the objective to unit
+// size, so x = Dx̃, the multipliers are Eỹ/c and the objective f̃/c.
 #[derive(Debug, Clone)]
 pub struct RuizScaler<T: RealNumber> {
-    column_scaling: Vec<T>,
+    factors: Equilibration<T>,
     iterations: usize,
 }
 
@@ -36,131 +23,928 @@ where
 {
     pub fn new(iterations: usize) -> Self {
         Self {
-            column_scaling: Vec::new(),
+            factors: Equilibration::identity(),
             iterations,
         }
     }
 
-    fn apply_column_scaling(&self, matrix: &mut CscMatrix<T>, scaling: &[T]) {
-        for col in 0..matrix.ncols {
-            let start = matrix.indptr[col];
-            let end = matrix.indptr[col + 1];
-            let col_scale = scaling[col];
-            if col_scale == T::zero() {
-                continue;
-            }
-            let inv_col = T::one() / col_scale;
-            for idx in start..end {
-                let row = matrix.indices[idx];
-                let inv_row = if row < scaling.len() {
-                    T::one() / scaling[row]
-                } else {
-                    T::one()
+    // The cost factor c of the last scaling.
+    pub fn cost_scaling(&self) -> T {
+        self.factors.cost
+    }
+}
+
+impl<T> Default for RuizScaler<T>
+where
+    T: RealNumber,
+{
+    fn default() -> Self {
+        Self::new(10)
+    }
+}
+
+impl<T> Scaler<T> for RuizScaler<T>
+where
+    T: RealNumber,
+{
+    fn scale_lp(&mut self, problem: &mut ProblemLP<T>) -> ProblemResult<()> {
+        self.factors = Equilibration::compute(
+            None,
+            &problem.cost,
+            problem.equalities.as_ref().map(|eq| &eq.matrix),
+            problem.inequalities.as_ref().map(|ineq| &ineq.matrix),
+            Measure::InfinityNorm,
+            self.iterations,
+            CostScaling::Once,
+        );
+        self.factors.apply_lp(problem)
+    }
+
+    fn scale_qp(&mut self, problem: &mut ProblemQP<T>) -> ProblemResult<()> {
+        self.factors = Equilibration::compute(
+            Some(&problem.quadratic),
+            &problem.linear,
+            problem.equalities.as_ref().map(|eq| &eq.matrix),
+            problem.inequalities.as_ref().map(|ineq| &ineq.matrix),
+            Measure::InfinityNorm,
+            self.iterations,
+            CostScaling::Once,
+        );
+        self.factors.apply_qp(problem)
+    }
+
+    fn rescale_qp(&self, problem: &mut ProblemQP<T>) -> ProblemResult<()> {
+        self.factors.apply_qp(problem)
+    }
+
+    fn scale_primal(&self, primal: &mut [T]) {
+        self.factors.scale_primal(primal);
+    }
+
+    fn unscale_primal(&self, primal: &mut [T]) {
+        self.factors.unscale_primal(primal);
+    }
+
+    fn unscale_dual(&self, equality: &mut [T], inequality: &mut [T]) {
+        self.factors.unscale_dual(equality, inequality);
+    }
+
+    fn unscale_objective(&self, objective: T) -> T {
+        objective / self.factors.cost
+    }
+
+    fn unscale_residuals(&self, equality: &mut [T], inequality: &mut [T], dual: &mut [T]) {
+        self.factors.unscale_residuals(equality, inequality, dual);
+    }
+
+    fn unscale_stats(&self, _stats: &mut SolveStats<T>) {}
+}
+
+// Row and column factors are kept within this range, as in OSQP, so that
+// nearly empty rows and columns do not blow the scaled problem up.
+const MIN_SCALING: f64 = 1e-4;
+const MAX_SCALING: f64 = 1e4;
+
+// What a pass of symmetric equilibration drives towards one in each row and
+// column of [P Aᵀ; A 0].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Measure {
+    InfinityNorm,
+    GeometricMean,
+}
+
+// When the cost factor c is set: never, once the rows and columns are
+// equilibrated, or after every pass so that the next pass sees cP.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CostScaling {
+    Off,
+    Once,
+    EveryPass,
+}
+
+// Largest and smallest nonzero magnitude of a row or column.
+#[derive(Debug, Clone, Copy)]
+struct Extent<T> {
+    largest: T,
+    smallest: T,
+}
+
+impl<T: RealNumber> Extent<T> {
+    fn empty() -> Self {
+        Self {
+            largest: T::zero(),
+            smallest: T::infinity(),
+        }
+    }
+
+    fn include(&mut self, magnitude: T) {
+        if magnitude > T::zero() {
+            self.largest = self.largest.max(magnitude);
+            self.smallest = self.smallest.min(magnitude);
+        }
+    }
+
+    // One for an empty line, so that it is left as it is.
+    fn measure(&self, measure: Measure) -> T {
+        if self.largest == T::zero() {
+            return T::one();
+        }
+        let value = match measure {
+            Measure::InfinityNorm => self.largest,
+            Measure::GeometricMean => (self.largest * self.smallest).sqrt(),
+        };
+        clamp_scaling(value)
+    }
+}
+
+fn clamp_scaling<T: RealNumber>(value: T) -> T {
+    value
+        .max(T::from(MIN_SCALING).unwrap())
+        .min(T::from(MAX_SCALING).unwrap())
+}
+
+fn triplets<T: RealNumber>(matrix: &CscMatrix<T>) -> impl Iterator<Item = (usize, usize, T)> + '_ {
+    (0..matrix.ncols).flat_map(move |col| {
+        (matrix.indptr[col]..matrix.indptr[col + 1])
+            .map(move |idx| (matrix.indices[idx], col, matrix.data[idx]))
+    })
+}
+
+// Column factors D, row factors E and a cost factor c. The scaled problem is
+//
+//   min ½x̃ᵀ(cDPD)x̃ + (cDq)ᵀx̃  s.t.  EADx̃ <= Eb,  D⁻¹l <= x̃ <= D⁻¹u
+//
+// so x = Dx̃, the row multipliers are y = Eỹ/c, the bound multipliers
+// w = D⁻¹w̃/c and the objective is the scaled one over c.
+#[derive(Debug, Clone)]
+struct Equilibration<T> {
+    columns: Vec<T>,
+    equality_rows: Vec<T>,
+    inequality_rows: Vec<T>,
+    cost: T,
+}
+
+impl<T> Equilibration<T>
+where
+    T: RealNumber,
+{
+    fn identity() -> Self {
+        Self {
+            columns: Vec::new(),
+            equality_rows: Vec::new(),
+            inequality_rows: Vec::new(),
+            cost: T::one(),
+        }
+    }
+
+    // Each pass divides every row and column of [P Aᵀ; A 0] by the square
+    // root of its measure, so that P and the constraint rows meet the column
+    // factors from both sides. c brings the mean column norm of P or the
+    // largest entry of q, whichever is larger, to one.
+    fn compute(
+        quadratic: Option<&CscMatrix<T>>,
+        linear: &[T],
+        equality: Option<&CscMatrix<T>>,
+        inequality: Option<&CscMatrix<T>>,
+        measure: Measure,
+        iterations: usize,
+        cost: CostScaling,
+    ) -> Self {
+        let n = linear.len();
+        let rows = |matrix: Option<&CscMatrix<T>>| vec![T::one(); matrix.map_or(0, |m| m.nrows)];
+        let mut factors = Self {
+            columns: vec![T::one(); n],
+            equality_rows: rows(equality),
+            inequality_rows: rows(inequality),
+            cost: T::one(),
+        };
+        for _ in 0..iterations {
+            let mut columns = vec![Extent::empty(); n];
+            if let Some(p) = quadratic {
+                for (i, j, value) in triplets(p) {
+                    let value =
+                        (factors.cost * factors.columns[i] * value * factors.columns[j]).abs();
+                    columns[i].include(value);
+                    columns[j].include(value);
+                }
+            }
+            for (matrix, row_factors) in [
+                (equality, &mut factors.equality_rows),
+                (inequality, &mut factors.inequality_rows),
+            ] {
+                let Some(matrix) = matrix else {
+                    continue;
                 };
-                matrix.data[idx] = matrix.data[idx] * inv_row * inv_col;
+                let mut rows = vec![Extent::empty(); matrix.nrows];
+                for (i, j, value) in triplets(matrix) {
+                    let value = (row_factors[i] * value * factors.columns[j]).abs();
+                    rows[i].include(value);
+                    columns[j].include(value);
+                }
+                for (factor, extent) in row_factors.iter_mut().zip(&rows) {
+                    *factor = *factor / extent.measure(measure).sqrt();
+                }
+            }
+            for (factor, extent) in factors.columns.iter_mut().zip(&columns) {
+                *factor = *factor / extent.measure(measure).sqrt();
+            }
+
+            if cost == CostScaling::EveryPass {
+                factors.cost = factors.cost_factor(quadratic, linear);
             }
         }
+        if cost == CostScaling::Once {
+            factors.cost = factors.cost_factor(quadratic, linear);
+        }
+        factors
     }
 
-    fn apply_vector_scaling(&self, vector: &mut [T], scaling: &[T]) {
-        for (value, &scale) in vector.iter_mut().zip(scaling.iter()) {
-            if scale != T::zero() {
-                *value = *value / scale;
+    fn cost_factor(&self, quadratic: Option<&CscMatrix<T>>, linear: &[T]) -> T {
+        let n = linear.len();
+        if n == 0 {
+            return T::one();
+        }
+        let mut norms = vec![T::zero(); n];
+        if let Some(p) = quadratic {
+            for (i, j, value) in triplets(p) {
+                let value = (self.columns[i] * value * self.columns[j]).abs();
+                norms[i] = norms[i].max(value);
+                norms[j] = norms[j].max(value);
             }
         }
+        let mean = norms.iter().fold(T::zero(), |acc, &norm| acc + norm) / T::from(n).unwrap();
+        let largest = linear
+            .iter()
+            .zip(&self.columns)
+            .fold(T::zero(), |acc, (&q, &d)| acc.max((q * d).abs()));
+        let scale = mean.max(largest);
+        if scale > T::zero() {
+            clamp_scaling(scale).recip()
+        } else {
+            T::one()
+        }
     }
 
-    fn scale_bounds(&self, bounds: &mut Bounds<T>, scaling: &[T]) {
-        for ((lower, upper), &scale) in bounds
-            .lower
-            .iter_mut()
-            .zip(bounds.upper.iter_mut())
-            .zip(scaling.iter())
+    fn check(&self, nvars: usize, meq: usize, mineq: usize) -> ProblemResult<()> {
+        if self.columns.len() != nvars
+            || self.equality_rows.len() != meq
+            || self.inequality_rows.len() != mineq
         {
-            if scale != T::zero() {
-                *lower = *lower * scale;
-                *upper = *upper * scale;
+            return Err(ProblemError::DimensionMismatch(format!(
+                "scaling covers {} variables and {} + {} rows but the problem has {} and {} + {}",
+                self.columns.len(),
+                self.equality_rows.len(),
+                self.inequality_rows.len(),
+                nvars,
+                meq,
+                mineq
+            )));
+        }
+        Ok(())
+    }
+
+    fn scale_rows(&self, matrix: &mut CscMatrix<T>, rhs: &mut [T], rows: &[T]) {
+        for col in 0..matrix.ncols {
+            for idx in matrix.indptr[col]..matrix.indptr[col + 1] {
+                matrix.data[idx] *= rows[matrix.indices[idx]] * self.columns[col];
+            }
+        }
+        for (value, &row) in rhs.iter_mut().zip(rows) {
+            *value *= row;
+        }
+    }
+
+    fn scale_common(
+        &self,
+        linear: &mut [T],
+        equality: Option<&mut EqualityConstraints<T>>,
+        inequality: Option<&mut InequalityConstraints<T>>,
+        bounds: Option<&mut Bounds<T>>,
+        offset: &mut T,
+    ) {
+        for (value, &d) in linear.iter_mut().zip(&self.columns) {
+            *value *= self.cost * d;
+        }
+        if let Some(eq) = equality {
+            self.scale_rows(&mut eq.matrix, &mut eq.rhs, &self.equality_rows);
+        }
+        if let Some(ineq) = inequality {
+            self.scale_rows(&mut ineq.matrix, &mut ineq.rhs, &self.inequality_rows);
+        }
+        if let Some(bounds) = bounds {
+            for ((lower, upper), &d) in bounds
+                .lower
+                .iter_mut()
+                .zip(bounds.upper.iter_mut())
+                .zip(&self.columns)
+            {
+                *lower = *lower / d;
+                *upper = *upper / d;
+            }
+        }
+        *offset *= self.cost;
+    }
+
+    fn apply_qp(&self, problem: &mut ProblemQP<T>) -> ProblemResult<()> {
+        if !problem.quadratic_constraints.is_empty() {
+            return Err(ProblemError::InvalidStructure(
+                "row scaling does not apply to quadratic constraints".into(),
+            ));
+        }
+        self.check(
+            problem.nvars(),
+            problem.equalities.as_ref().map_or(0, |eq| eq.matrix.nrows),
+            problem
+                .inequalities
+                .as_ref()
+                .map_or(0, |ineq| ineq.matrix.nrows),
+        )?;
+        let p = &mut problem.quadratic;
+        for col in 0..p.ncols {
+            for idx in p.indptr[col]..p.indptr[col + 1] {
+                p.data[idx] *= self.cost * self.columns[p.indices[idx]] * self.columns[col];
+            }
+        }
+        self.scale_common(
+            &mut problem.linear,
+            problem.equalities.as_mut(),
+            problem.inequalities.as_mut(),
+            problem.bounds.as_mut(),
+            &mut problem.offset,
+        );
+        Ok(())
+    }
+
+    fn apply_lp(&self, problem: &mut ProblemLP<T>) -> ProblemResult<()> {
+        self.check(
+            problem.nvars(),
+            problem.equalities.as_ref().map_or(0, |eq| eq.matrix.nrows),
+            problem
+                .inequalities
+                .as_ref()
+                .map_or(0, |ineq| ineq.matrix.nrows),
+        )?;
+        self.scale_common(
+            &mut problem.cost,
+            problem.equalities.as_mut(),
+            problem.inequalities.as_mut(),
+            problem.bounds.as_mut(),
+            &mut problem.offset,
+        );
+        Ok(())
+    }
+
+    fn scale_primal(&self, primal: &mut [T]) {
+        if primal.len() == self.columns.len() {
+            for (x, &d) in primal.iter_mut().zip(&self.columns) {
+                *x = *x / d;
+            }
+        }
+    }
+
+    fn unscale_primal(&self, primal: &mut [T]) {
+        if primal.len() == self.columns.len() {
+            for (x, &d) in primal.iter_mut().zip(&self.columns) {
+                *x *= d;
+            }
+        }
+    }
+
+    // Splits row vectors into their equality, inequality and bound parts.
+    // Takes both layouts the methods report them in: the equality rows
+    // apart, or stacked ahead of the inequality ones as ADMM does, with the
+    // bound rows after the inequality ones in either case.
+    fn split_rows<'a>(
+        &self,
+        equality: &'a mut [T],
+        inequality: &'a mut [T],
+    ) -> Option<(&'a mut [T], &'a mut [T], &'a mut [T])> {
+        let (meq, mineq, n) = (
+            self.equality_rows.len(),
+            self.inequality_rows.len(),
+            self.columns.len(),
+        );
+        let stacked = equality.is_empty()
+            && meq > 0
+            && (inequality.len() == meq + mineq || inequality.len() == meq + mineq + n);
+        let (equality, inequality) = if stacked {
+            inequality.split_at_mut(meq)
+        } else {
+            (equality, inequality)
+        };
+        if equality.len() != meq || (inequality.len() != mineq && inequality.len() != mineq + n) {
+            return None;
+        }
+        let (rows, bounds) = inequality.split_at_mut(mineq);
+        Some((equality, rows, bounds))
+    }
+
+    fn unscale_dual(&self, equality: &mut [T], inequality: &mut [T]) {
+        let Some((equality, inequality, bounds)) = self.split_rows(equality, inequality) else {
+            return;
+        };
+        for (y, &e) in equality.iter_mut().zip(&self.equality_rows) {
+            *y = *y * e / self.cost;
+        }
+        for (z, &e) in inequality.iter_mut().zip(&self.inequality_rows) {
+            *z = *z * e / self.cost;
+        }
+        for (w, &d) in bounds.iter_mut().zip(&self.columns) {
+            *w = *w / (d * self.cost);
+        }
+    }
+
+    // The scaled primal residual is E(Ax - z) on the rows and D⁻¹(x - z)
+    // on the bounds; the scaled dual residual is cD(Px + q + Aᵀy).
+    fn unscale_residuals(&self, equality: &mut [T], inequality: &mut [T], dual: &mut [T]) {
+        if let Some((equality, inequality, bounds)) = self.split_rows(equality, inequality) {
+            for (r, &e) in equality.iter_mut().zip(&self.equality_rows) {
+                *r = *r / e;
+            }
+            for (r, &e) in inequality.iter_mut().zip(&self.inequality_rows) {
+                *r = *r / e;
+            }
+            for (r, &d) in bounds.iter_mut().zip(&self.columns) {
+                *r *= d;
+            }
+        }
+        if dual.len() == self.columns.len() {
+            for (r, &d) in dual.iter_mut().zip(&self.columns) {
+                *r = *r / (d * self.cost);
             }
         }
     }
 }
 
-impl<T> Default for RuizScaler<T>
+// Symmetric geometric-mean scaling of [P Aᵀ; A 0]: each pass divides every
+// row and column by the square root of the geometric mean of its largest and
+// smallest entry. It evens out rows whose entries span many orders of
+// magnitude, which infinity-norm scaling leaves as they are.
+#[derive(Debug, Clone)]
+pub struct GeometricScaler<T: RealNumber> {
+    factors: Equilibration<T>,
+    iterations: usize,
+}
+
+impl<T> GeometricScaler<T>
+where
+    T: RealNumber,
+{
+    pub fn new(iterations: usize) -> Self {
+        Self {
+            factors: Equilibration::identity(),
+            iterations,
+        }
+    }
+}
+
+impl<T> Default for GeometricScaler<T>
 where
     T: RealNumber,
 {
     fn default() -> Self {
-        Self::new(5)
+        Self::new(4)
     }
 }
 
-impl<T> Scaler<T> for RuizScaler<T>
+impl<T> Scaler<T> for GeometricScaler<T>
 where
-    T: RealNumber + One,
+    T: RealNumber,
 {
     fn scale_lp(&mut self, problem: &mut ProblemLP<T>) -> ProblemResult<()> {
-        let n = problem.nvars();
-        if self.column_scaling.len() != n {
-            self.column_scaling = vec![T::one(); n];
-        }
-        for _ in 0..self.iterations {
-            if let Some(ineq) = &problem.inequalities {
-                equilibrate_columns(&ineq.matrix, &mut self.column_scaling);
-            }
-            if let Some(eq) = &problem.equalities {
-                equilibrate_columns(&eq.matrix, &mut self.column_scaling);
-            }
+        self.factors = Equilibration::compute(
+            None,
+            &problem.cost,
+            problem.equalities.as_ref().map(|eq| &eq.matrix),
+            problem.inequalities.as_ref().map(|ineq| &ineq.matrix),
+            Measure::GeometricMean,
+            self.iterations,
+            CostScaling::Off,
+        );
+        self.factors.apply_lp(problem)
+    }
+
+    fn scale_qp(&mut self, problem: &mut ProblemQP<T>) -> ProblemResult<()> {
+        self.factors = Equilibration::compute(
+            Some(&problem.quadratic),
+            &problem.linear,
+            problem.equalities.as_ref().map(|eq| &eq.matrix),
+            problem.inequalities.as_ref().map(|ineq| &ineq.matrix),
+            Measure::GeometricMean,
+            self.iterations,
+            CostScaling::Off,
+        );
+        self.factors.apply_qp(problem)
+    }
+
+    fn rescale_qp(&self, problem: &mut ProblemQP<T>) -> ProblemResult<()> {
+        self.factors.apply_qp(problem)
+    }
+
+    fn scale_primal(&self, primal: &mut [T]) {
+        self.factors.scale_primal(primal);
+    }
+
+    fn unscale_primal(&self, primal: &mut [T]) {
+        self.factors.unscale_primal(primal);
+    }
+
+    fn unscale_dual(&self, equality: &mut [T], inequality: &mut [T]) {
+        self.factors.unscale_dual(equality, inequality);
+    }
+
+    fn unscale_residuals(&self, equality: &mut [T], inequality: &mut [T], dual: &mut [T]) {
+        self.factors.unscale_residuals(equality, inequality, dual);
+    }
+
+    fn unscale_stats(&self, _stats: &mut SolveStats<T>) {}
+}
+
+// Modified Ruiz equilibration as in OSQP: the passes of RuizScaler, with
+// the cost factor c updated after each one so that the next pass balances
+// cP rather than P against the constraint rows.
+#[derive(Debug, Clone)]
+pub struct ModifiedRuizScaler<T: RealNumber> {
+    factors: Equilibration<T>,
+    iterations: usize,
+}
+
+impl<T> ModifiedRuizScaler<T>
+where
+    T: RealNumber,
+{
+    pub fn new(iterations: usize) -> Self {
+        Self {
+            factors: Equilibration::identity(),
+            iterations,
         }
-        if let Some(ineq) = problem.inequalities.as_mut() {
-            self.apply_column_scaling(&mut ineq.matrix, &self.column_scaling);
+    }
+
+    // The cost factor c of the last scaling.
+    pub fn cost_scaling(&self) -> T {
+        self.factors.cost
+    }
+}
+
+impl<T> Default for ModifiedRuizScaler<T>
+where
+    T: RealNumber,
+{
+    fn default() -> Self {
+        Self::new(10)
+    }
+}
+
+impl<T> Scaler<T> for ModifiedRuizScaler<T>
+where
+    T: RealNumber,
+{
+    fn scale_lp(&mut self, problem: &mut ProblemLP<T>) -> ProblemResult<()> {
+        self.factors = Equilibration::compute(
+            None,
+            &problem.cost,
+            problem.equalities.as_ref().map(|eq| &eq.matrix),
+            problem.inequalities.as_ref().map(|ineq| &ineq.matrix),
+            Measure::InfinityNorm,
+            self.iterations,
+            CostScaling::EveryPass,
+        );
+        self.factors.apply_lp(problem)
+    }
+
+    fn scale_qp(&mut self, problem: &mut ProblemQP<T>) -> ProblemResult<()> {
+        self.factors = Equilibration::compute(
+            Some(&problem.quadratic),
+            &problem.linear,
+            problem.equalities.as_ref().map(|eq| &eq.matrix),
+            problem.inequalities.as_ref().map(|ineq| &ineq.matrix),
+            Measure::InfinityNorm,
+            self.iterations,
+            CostScaling::EveryPass,
+        );
+        self.factors.apply_qp(problem)
+    }
+
+    fn rescale_qp(&self, problem: &mut ProblemQP<T>) -> ProblemResult<()> {
+        self.factors.apply_qp(problem)
+    }
+
+    fn scale_primal(&self, primal: &mut [T]) {
+        self.factors.scale_primal(primal);
+    }
+
+    fn unscale_primal(&self, primal: &mut [T]) {
+        self.factors.unscale_primal(primal);
+    }
+
+    fn unscale_dual(&self, equality: &mut [T], inequality: &mut [T]) {
+        self.factors.unscale_dual(equality, inequality);
+    }
+
+    fn unscale_objective(&self, objective: T) -> T {
+        objective / self.factors.cost
+    }
+
+    fn unscale_residuals(&self, equality: &mut [T], inequality: &mut [T], dual: &mut [T]) {
+        self.factors.unscale_residuals(equality, inequality, dual);
+    }
+
+    fn unscale_stats(&self, _stats: &mut SolveStats<T>) {}
+}
+
+// Leaves the problem as it is; every unscaling is the identity.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct IdentityScaler;
+
+impl<T> Scaler<T> for IdentityScaler
+where
+    T: RealNumber,
+{
+    fn scale_lp(&mut self, _problem: &mut ProblemLP<T>) -> ProblemResult<()> {
+        Ok(())
+    }
+
+    fn scale_qp(&mut self, _problem: &mut ProblemQP<T>) -> ProblemResult<()> {
+        Ok(())
+    }
+
+    fn rescale_qp(&self, _problem: &mut ProblemQP<T>) -> ProblemResult<()> {
+        Ok(())
+    }
+
+    fn unscale_stats(&self, _stats: &mut SolveStats<T>) {}
+}
+
+// The scaler SolveOptions::scaling names.
+#[derive(Debug, Clone)]
+pub enum BuiltinScaler<T: RealNumber> {
+    Ruiz(RuizScaler<T>),
+    Geometric(GeometricScaler<T>),
+    ModifiedRuiz(ModifiedRuizScaler<T>),
+    Identity(IdentityScaler),
+}
+
+impl<T> BuiltinScaler<T>
+where
+    T: RealNumber,
+{
+    pub fn new(scaling: Scaling) -> Self {
+        match scaling {
+            Scaling::Ruiz => Self::Ruiz(RuizScaler::default()),
+            Scaling::Geometric => Self::Geometric(GeometricScaler::default()),
+            Scaling::ModifiedRuiz => Self::ModifiedRuiz(ModifiedRuizScaler::default()),
+            Scaling::None => Self::Identity(IdentityScaler),
         }
-        if let Some(eq) = problem.equalities.as_mut() {
-            self.apply_column_scaling(&mut eq.matrix, &self.column_scaling);
+    }
+
+    fn inner(&self) -> &dyn Scaler<T> {
+        match self {
+            Self::Ruiz(scaler) => scaler,
+            Self::Geometric(scaler) => scaler,
+            Self::ModifiedRuiz(scaler) => scaler,
+            Self::Identity(scaler) => scaler,
         }
-        self.apply_vector_scaling(&mut problem.cost, &self.column_scaling);
-        if let Some(bounds) = problem.bounds.as_mut() {
-            self.scale_bounds(bounds, &self.column_scaling);
+    }
+
+    fn inner_mut(&mut self) -> &mut dyn Scaler<T> {
+        match self {
+            Self::Ruiz(scaler) => scaler,
+            Self::Geometric(scaler) => scaler,
+            Self::ModifiedRuiz(scaler) => scaler,
+            Self::Identity(scaler) => scaler,
         }
-        Ok(())
+    }
+}
+
+impl<T> Default for BuiltinScaler<T>
+where
+    T: RealNumber,
+{
+    fn default() -> Self {
+        Self::new(Scaling::default())
+    }
+}
+
+impl<T> Scaler<T> for BuiltinScaler<T>
+where
+    T: RealNumber,
+{
+    fn scale_lp(&mut self, problem: &mut ProblemLP<T>) -> ProblemResult<()> {
+        self.inner_mut().scale_lp(problem)
     }
 
     fn scale_qp(&mut self, problem: &mut ProblemQP<T>) -> ProblemResult<()> {
-        let n = problem.nvars();
-        if self.column_scaling.len() != n {
-            self.column_scaling = vec![T::one(); n];
+        self.inner_mut().scale_qp(problem)
+    }
+
+    fn rescale_qp(&self, problem: &mut ProblemQP<T>) -> ProblemResult<()> {
+        self.inner().rescale_qp(problem)
+    }
+
+    fn scale_primal(&self, primal: &mut [T]) {
+        self.inner().scale_primal(primal);
+    }
+
+    fn unscale_primal(&self, primal: &mut [T]) {
+        self.inner().unscale_primal(primal);
+    }
+
+    fn unscale_dual(&self, equality: &mut [T], inequality: &mut [T]) {
+        self.inner().unscale_dual(equality, inequality);
+    }
+
+    fn unscale_objective(&self, objective: T) -> T {
+        self.inner().unscale_objective(objective)
+    }
+
+    fn unscale_residuals(&self, equality: &mut [T], inequality: &mut [T], dual: &mut [T]) {
+        self.inner().unscale_residuals(equality, inequality, dual);
+    }
+
+    fn unscale_stats(&self, stats: &mut SolveStats<T>) {
+        self.inner().unscale_stats(stats);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::problem::Names;
+    use crate::problem::ObjectiveSense;
+    use crate::traits::LinearOperator;
+
+    // Rows and columns that differ by up to eight orders of magnitude.
+    fn problem() -> ProblemQP<f64> {
+        ProblemQP {
+            quadratic: CscMatrix::from_triplets(2, 2, &[(0, 0, 4e4), (0, 1, 1.0), (1, 1, 2e-3)])
+                .unwrap(),
+            linear: vec![1e3, -1e-2],
+            inequalities: Some(InequalityConstraints {
+                matrix: CscMatrix::from_dense(2, 2, &[1e4, 1.0, 1e-3, 1e-4]).unwrap(),
+                rhs: vec![1e4, 1e-3],
+            }),
+            equalities: Some(EqualityConstraints {
+                matrix: CscMatrix::from_dense(1, 2, &[2.0, 3e2]).unwrap(),
+                rhs: vec![5.0],
+            }),
+            bounds: Some(Bounds {
+                lower: vec![-1e2, -1e3],
+                upper: vec![1e2, 1e3],
+            }),
+            groups: Vec::new(),
+            integrality: Vec::new(),
+            names: Names::default(),
+            sense: ObjectiveSense::Minimize,
+            offset: 3.0,
+            quadratic_constraints: Vec::new(),
         }
-        for _ in 0..self.iterations {
-            equilibrate_columns(&problem.quadratic, &mut self.column_scaling);
-            if let Some(ineq) = &problem.inequalities {
-                equilibrate_columns(&ineq.matrix, &mut self.column_scaling);
-            }
-            if let Some(eq) = &problem.equalities {
-                equilibrate_columns(&eq.matrix, &mut self.column_scaling);
-            }
+    }
+
+    fn objective(problem: &ProblemQP<f64>, x: &[f64]) -> f64 {
+        let mut px = vec![0.0; x.len()];
+        problem.quadratic.apply(x, &mut px);
+        let quadratic: f64 = x.iter().zip(&px).map(|(x, px)| 0.5 * x * px).sum();
+        let linear: f64 = x.iter().zip(&problem.linear).map(|(x, q)| x * q).sum();
+        quadratic + linear + problem.offset
+    }
+
+    // Largest over smallest infinity norm among the columns and the
+    // constraint rows.
+    fn spread(problem: &ProblemQP<f64>) -> f64 {
+        let mut columns = vec![0.0f64; problem.nvars()];
+        let mut rows = Vec::new();
+        for (i, j, value) in triplets(&problem.quadratic) {
+            columns[i] = columns[i].max(value.abs());
+            columns[j] = columns[j].max(value.abs());
         }
-        self.apply_column_scaling(&mut problem.quadratic, &self.column_scaling);
-        self.apply_vector_scaling(&mut problem.linear, &self.column_scaling);
-        if let Some(ineq) = problem.inequalities.as_mut() {
-            self.apply_column_scaling(&mut ineq.matrix, &self.column_scaling);
+        let eq = problem.equalities.as_ref().map(|eq| &eq.matrix);
+        let ineq = problem.inequalities.as_ref().map(|ineq| &ineq.matrix);
+        for matrix in [eq, ineq].into_iter().flatten() {
+            let mut norms = vec![0.0f64; matrix.nrows];
+            for (i, j, value) in triplets(matrix) {
+                norms[i] = norms[i].max(value.abs());
+                columns[j] = columns[j].max(value.abs());
+            }
+            rows.extend(norms);
         }
-        if let Some(eq) = problem.equalities.as_mut() {
-            self.apply_column_scaling(&mut eq.matrix, &self.column_scaling);
+        let norms = columns.iter().chain(&rows);
+        norms.clone().fold(0.0, |a: f64, &b| a.max(b)) / norms.fold(f64::MAX, |a, &b| a.min(b))
+    }
+
+    #[test]
+    fn equilibration_evens_out_rows_and_columns() {
+        let original = problem();
+        let mut geometric = original.clone();
+        GeometricScaler::default().scale_qp(&mut geometric).unwrap();
+        let mut modified = original.clone();
+        ModifiedRuizScaler::default()
+            .scale_qp(&mut modified)
+            .unwrap();
+        let mut ruiz = original.clone();
+        RuizScaler::default().scale_qp(&mut ruiz).unwrap();
+        assert!(spread(&original) > 1e7);
+        assert!(spread(&ruiz) < 10.0, "{}", spread(&ruiz));
+        assert!(spread(&geometric) < 1e3, "{}", spread(&geometric));
+        assert!(spread(&modified) < 10.0, "{}", spread(&modified));
+    }
+
+    #[test]
+    fn equilibration_maps_points_and_multipliers_back() {
+        let original = problem();
+        let mut scaler = ModifiedRuizScaler::default();
+        let mut scaled = original.clone();
+        scaler.scale_qp(&mut scaled).unwrap();
+        assert!(scaler.cost_scaling() < 1.0);
+
+        // x = Dx̃, so unscaling the ones gives D.
+        let mut columns = vec![1.0; 2];
+        scaler.unscale_primal(&mut columns);
+        let x = [0.3, -7.0];
+        let tilde: Vec<f64> = x.iter().zip(&columns).map(|(x, d)| x / d).collect();
+        let mut back = tilde.clone();
+        scaler.unscale_primal(&mut back);
+        for (a, b) in back.iter().zip(&x) {
+            assert!((a - b).abs() < 1e-12);
         }
-        if let Some(bounds) = problem.bounds.as_mut() {
-            self.scale_bounds(bounds, &self.column_scaling);
+        let value = scaler.unscale_objective(objective(&scaled, &tilde));
+        assert!((value - objective(&original, &x)).abs() < 1e-9 * value.abs());
+
+        // Row activities keep their sign against the right-hand sides.
+        let ineq = original.inequalities.as_ref().unwrap();
+        let scaled_ineq = scaled.inequalities.as_ref().unwrap();
+        let mut ax = vec![0.0; 2];
+        ineq.matrix.apply(&x, &mut ax);
+        let mut ax_tilde = vec![0.0; 2];
+        scaled_ineq.matrix.apply(&tilde, &mut ax_tilde);
+        for i in 0..2 {
+            let (gap, scaled_gap) = (ax[i] - ineq.rhs[i], ax_tilde[i] - scaled_ineq.rhs[i]);
+            assert_eq!(gap.signum(), scaled_gap.signum());
         }
-        Ok(())
+
+        // Stacked and separate multipliers unscale alike.
+        let (mut equality, mut inequality) = (vec![1.0], vec![1.0, 1.0, 1.0, 1.0]);
+        scaler.unscale_dual(&mut equality, &mut inequality);
+        let mut stacked = vec![1.0; 5];
+        scaler.unscale_dual(&mut [], &mut stacked);
+        assert_eq!(stacked[0], equality[0]);
+        assert_eq!(&stacked[1..], &inequality[..]);
+        assert!((inequality[2] * columns[0] * scaler.cost_scaling() - 1.0).abs() < 1e-12);
     }
 
-    fn unscale_primal(&self, primal: &mut [T]) {
-        if primal.len() == self.column_scaling.len() {
-            for (x, &scale) in primal.iter_mut().zip(self.column_scaling.iter()) {
-                if scale != T::zero() {
-                    *x = *x / scale;
+    #[test]
+    fn ruiz_residuals_come_back_in_original_units() {
+        let original = problem();
+        let mut scaler = RuizScaler::default();
+        let mut scaled = original.clone();
+        scaler.scale_qp(&mut scaled).unwrap();
+        assert!(scaler.cost_scaling() < 1.0);
+
+        let mut columns = vec![1.0; 2];
+        scaler.unscale_primal(&mut columns);
+        let x = [-2.0, 0.5];
+        let tilde: Vec<f64> = x.iter().zip(&columns).map(|(x, d)| x / d).collect();
+        let value = objective(&scaled, &tilde);
+        assert!((scaler.unscale_objective(value) - objective(&original, &x)).abs() < 1e-9);
+
+        // Equality, inequality and lower-bound rows of Ax - b, stacked as
+        // ADMM keeps them, and the stationarity residual Px + q + Aᵀy + w
+        // for multipliers stacked the same way.
+        let residuals = |problem: &ProblemQP<f64>, x: &[f64], y: &[f64]| {
+            let mut rows = Vec::new();
+            let mut dual = vec![0.0; x.len()];
+            problem.quadratic.apply(x, &mut dual);
+            let eq = problem.equalities.as_ref().unwrap();
+            let ineq = problem.inequalities.as_ref().unwrap();
+            let mut start = 0;
+            for (matrix, rhs) in [(&eq.matrix, &eq.rhs), (&ineq.matrix, &ineq.rhs)] {
+                let mut ax = vec![0.0; rhs.len()];
+                matrix.apply(x, &mut ax);
+                rows.extend(ax.iter().zip(rhs).map(|(ax, b)| ax - b));
+                let mut aty = vec![0.0; x.len()];
+                matrix.apply_transpose(&y[start..start + rhs.len()], &mut aty);
+                start += rhs.len();
+                for (r, v) in dual.iter_mut().zip(aty) {
+                    *r += v;
                 }
             }
+            let lower = &problem.bounds.as_ref().unwrap().lower;
+            rows.extend(x.iter().zip(lower).map(|(x, l)| x - l));
+            for ((r, q), w) in dual.iter_mut().zip(&problem.linear).zip(&y[start..]) {
+                *r += q + w;
+            }
+            (rows, dual)
+        };
+        let tilde_y = [0.5, -1.5, 2.0, 0.25, -4.0];
+        let mut y = tilde_y.to_vec();
+        scaler.unscale_dual(&mut [], &mut y);
+        let (expected_rows, expected_dual) = residuals(&original, &x, &y);
+        let (mut rows, mut dual) = residuals(&scaled, &tilde, &tilde_y);
+        scaler.unscale_residuals(&mut [], &mut rows, &mut dual);
+        for (a, b) in rows.iter().zip(&expected_rows) {
+            assert!((a - b).abs() < 1e-9 * b.abs().max(1.0), "{a} vs {b}");
+        }
+        for (a, b) in dual.iter().zip(&expected_dual) {
+            assert!((a - b).abs() < 1e-9 * b.abs().max(1.0), "{a} vs {b}");
         }
     }
-
-    fn unscale_stats(&self, _stats: &mut SolveStats<T>) {}
 }
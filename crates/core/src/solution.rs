@@ -1,5 +1,6 @@
 use crate::math::RealNumber;
-use crate::stats::SolveStats;
+use crate::problem::ObjectiveSense;
+use crate::stats::{Basis, SolveStats};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
@@ -10,6 +11,26 @@ pub enum Status {
     MaxIterations,
     MaxTime,
     NumericalFailure,
+    // Stopped early through an interrupt flag, e.g. by an iteration callback.
+    Interrupted,
+}
+
+// Proof that a problem has no optimal solution, scaled so that the offending
+// linear form equals -1.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum Certificate<T> {
+    // Multipliers with Aᵀy + Gᵀz + w = 0, z >= 0 and
+    // bᵀy + hᵀz + uᵀw⁺ - lᵀw⁻ = -1, where the bound multipliers w follow the
+    // inequality ones in `inequality_dual` when the problem has bounds.
+    PrimalInfeasible {
+        equality_dual: Vec<T>,
+        inequality_dual: Vec<T>,
+    },
+    // A ray d with Ad = 0, Gd <= 0, d inside the recession cone of the bounds
+    // and cᵀd = -1.
+    DualInfeasible {
+        direction: Vec<T>,
+    },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -21,6 +42,11 @@ pub struct Solution<T: RealNumber> {
     pub objective_value: T,
     pub iterations: usize,
     pub stats: SolveStats<T>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub certificate: Option<Certificate<T>>,
+    // Basic/nonbasic status at a vertex, from the simplex or a crossover.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub basis: Option<Basis>,
 }
 
 impl<T> Solution<T>
@@ -36,6 +62,26 @@ where
             objective_value: T::zero(),
             iterations: 0,
             stats: SolveStats::new(),
+            certificate: None,
+            basis: None,
+        }
+    }
+
+    // The figures of a maximization solved as the minimization of its
+    // negated objective, in the terms of the maximization: the objective
+    // values change sign while the iterate and the multipliers stay.
+    pub fn in_sense(mut self, sense: ObjectiveSense) -> Self {
+        if sense == ObjectiveSense::Minimize {
+            return self;
+        }
+        self.objective_value = -self.objective_value;
+        for record in &mut self.stats.history {
+            record.primal_objective = -record.primal_objective;
+            record.dual_objective = -record.dual_objective;
+        }
+        if let Some(search) = &mut self.stats.branch_and_bound {
+            search.best_bound = -search.best_bound;
         }
+        self
     }
 }
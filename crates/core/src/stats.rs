@@ -1,5 +1,8 @@
-use crate::math::RealNumber;
+use crate::math::{cast, RealNumber};
+use crate::options::Method;
 use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 use std::time::Duration;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -13,6 +16,22 @@ pub struct IterationRecord<T: RealNumber> {
     pub primal_objective: T,
     pub dual_objective: T,
     pub elapsed: Duration,
+    // Sizes of the terms each residual is made of, which the relative
+    // tolerance scales with; zero when the solver reports none.
+    #[serde(default = "zero")]
+    pub primal_scale: T,
+    #[serde(default = "zero")]
+    pub dual_scale: T,
+}
+
+// Receives every iteration record as soon as it is produced.
+pub type IterationSink<T> = Arc<dyn Fn(&IterationRecord<T>) + Send + Sync>;
+
+// Raised from anywhere to stop a solve after its current iteration.
+pub type InterruptFlag = Arc<AtomicBool>;
+
+pub fn interrupted(flag: Option<&InterruptFlag>) -> bool {
+    flag.is_some_and(|flag| flag.load(Ordering::Relaxed))
 }
 
 impl<T> IterationRecord<T>
@@ -41,7 +60,175 @@ where
             primal_objective,
             dual_objective,
             elapsed,
+            primal_scale: T::zero(),
+            dual_scale: T::zero(),
+        }
+    }
+
+    pub fn cast<U: RealNumber>(&self) -> IterationRecord<U> {
+        IterationRecord {
+            iteration: self.iteration,
+            primal_residual: cast(self.primal_residual),
+            dual_residual: cast(self.dual_residual),
+            relative_gap: cast(self.relative_gap),
+            rho: cast(self.rho),
+            relaxation: cast(self.relaxation),
+            primal_objective: cast(self.primal_objective),
+            dual_objective: cast(self.dual_objective),
+            elapsed: self.elapsed,
+            primal_scale: cast(self.primal_scale),
+            dual_scale: cast(self.dual_scale),
+        }
+    }
+}
+
+fn zero<T: RealNumber>() -> T {
+    T::zero()
+}
+
+// A change of the ADMM penalty, recorded at the end of `iteration`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct RhoUpdate<T: RealNumber> {
+    pub iteration: usize,
+    pub previous: T,
+    pub rho: T,
+}
+
+// Summary of a branch-and-bound search. The best bound is the smallest
+// relaxation objective among the nodes still open when the search stopped.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct BranchAndBoundStats<T: RealNumber> {
+    pub nodes: usize,
+    pub incumbents: usize,
+    pub best_bound: T,
+}
+
+// Pivot perturbations applied by a regularized KKT factorization, over
+// every factorization of a solve.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct RegularizationStats<T: RealNumber> {
+    // Pivots replaced because they were tiny or of the wrong sign.
+    pub dynamic_pivots: usize,
+    // Largest change made to a single pivot, the static shift included.
+    pub max_perturbation: T,
+}
+
+impl<T> RegularizationStats<T>
+where
+    T: RealNumber,
+{
+    pub fn new() -> Self {
+        Self {
+            dynamic_pivots: 0,
+            max_perturbation: T::zero(),
+        }
+    }
+
+    pub fn merge(&mut self, other: &RegularizationStats<T>) {
+        self.dynamic_pivots += other.dynamic_pivots;
+        self.max_perturbation = self.max_perturbation.max(other.max_perturbation);
+    }
+
+    pub fn cast<U: RealNumber>(&self) -> RegularizationStats<U> {
+        RegularizationStats {
+            dynamic_pivots: self.dynamic_pivots,
+            max_perturbation: cast(self.max_perturbation),
+        }
+    }
+}
+
+impl<T> Default for RegularizationStats<T>
+where
+    T: RealNumber,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Why an iterative linear solve stopped.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum KrylovTermination {
+    Converged,
+    IterationLimit,
+}
+
+// Iterations of the Krylov solves behind an iterative KKT solver, summed
+// over every solve.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct KrylovStats {
+    pub solves: usize,
+    pub iterations: usize,
+    // The most iterations any single solve took.
+    pub max_iterations: usize,
+    // Solves that hit the iteration limit before the tolerance.
+    pub unconverged: usize,
+    pub last_termination: Option<KrylovTermination>,
+}
+
+impl KrylovStats {
+    pub fn record(&mut self, iterations: usize, termination: KrylovTermination) {
+        self.solves += 1;
+        self.iterations += iterations;
+        self.max_iterations = self.max_iterations.max(iterations);
+        if termination == KrylovTermination::IterationLimit {
+            self.unconverged += 1;
         }
+        self.last_termination = Some(termination);
+    }
+
+    pub fn merge(&mut self, other: &KrylovStats) {
+        self.solves += other.solves;
+        self.iterations += other.iterations;
+        self.max_iterations = self.max_iterations.max(other.max_iterations);
+        self.unconverged += other.unconverged;
+        self.last_termination = other.last_termination.or(self.last_termination);
+    }
+}
+
+// Constraints that hold with equality at an active-set solution, by index
+// into the inequality rows and the variables.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ActiveSet {
+    pub inequalities: Vec<usize>,
+    pub lower_bounds: Vec<usize>,
+    pub upper_bounds: Vec<usize>,
+}
+
+// Where a variable or row sits at a simplex vertex. Nonbasic free variables
+// stay at zero.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum BasisStatus {
+    Basic,
+    AtLower,
+    AtUpper,
+    Free,
+}
+
+// Status of every variable and of every inequality row, the latter in terms
+// of the row activity Gx, so a tight row is AtUpper.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Basis {
+    pub variables: Vec<BasisStatus>,
+    pub inequalities: Vec<BasisStatus>,
+}
+
+// What presolve took out of the problem before the solve.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PresolveStats {
+    pub fixed_variables: usize,
+    // Rows parallel to a kept row that is at least as tight.
+    pub duplicate_rows: usize,
+    // Inequality rows that no point within the bounds can violate.
+    pub redundant_rows: usize,
+    // Variable bounds moved to those the rows imply, each side counted once.
+    #[serde(default)]
+    pub tightened_bounds: usize,
+}
+
+impl PresolveStats {
+    pub fn removed_rows(&self) -> usize {
+        self.duplicate_rows + self.redundant_rows
     }
 }
 
@@ -51,6 +238,44 @@ pub struct SolveStats<T: RealNumber> {
     pub solve_time: Duration,
     pub factorizations: usize,
     pub linear_solves: usize,
+    // Time the KKT solver spent analysing sparsity patterns and computing
+    // numeric factors; a pattern is analysed once per solve while the
+    // numeric phase repeats with every rho update.
+    #[serde(default)]
+    pub symbolic_time: Duration,
+    #[serde(default)]
+    pub numeric_time: Duration,
+    // Set when the KKT systems were solved iteratively.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub krylov: Option<KrylovStats>,
+    // Set when the factorizations were regularized.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub regularization: Option<RegularizationStats<T>>,
+    // Estimated 1-norm condition number of the last KKT matrix factored;
+    // None for the iterative backends and the methods without a KKT solve.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub condition_estimate: Option<T>,
+    // Whether polishing replaced the iterate; None when it was not attempted.
+    #[serde(default)]
+    pub polished: Option<bool>,
+    #[serde(default)]
+    pub rho_updates: Vec<RhoUpdate<T>>,
+    // Accelerated steps thrown away because they increased the residual.
+    #[serde(default)]
+    pub anderson_rejections: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub active_set: Option<ActiveSet>,
+    // Simplex iterations a crossover took; None when it was not attempted.
+    #[serde(default)]
+    pub crossover_iterations: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch_and_bound: Option<BranchAndBoundStats<T>>,
+    // The method that produced the solution when Method::Auto chose it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backend: Option<Method>,
+    // Set when the problem went through presolve.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub presolve: Option<PresolveStats>,
 }
 
 impl<T> SolveStats<T>
@@ -63,6 +288,19 @@ where
             solve_time: Duration::ZERO,
             factorizations: 0,
             linear_solves: 0,
+            symbolic_time: Duration::ZERO,
+            numeric_time: Duration::ZERO,
+            krylov: None,
+            regularization: None,
+            condition_estimate: None,
+            polished: None,
+            rho_updates: Vec::new(),
+            anderson_rejections: 0,
+            active_set: None,
+            crossover_iterations: None,
+            branch_and_bound: None,
+            backend: None,
+            presolve: None,
         }
     }
 
@@ -70,3 +308,12 @@ where
         self.history.push(record);
     }
 }
+
+impl<T> Default for SolveStats<T>
+where
+    T: RealNumber,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
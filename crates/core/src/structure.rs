@@ -0,0 +1,276 @@
+use crate::math::RealNumber;
+use crate::problem::{CscMatrix, ProblemLP, ProblemQP};
+use serde::{Deserialize, Serialize};
+
+// At most this share of the rows may be declared linking; past that the
+// problem is reported as a single block.
+const MAX_LINKING_FRACTION: f64 = 0.2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum ConstraintRow {
+    Equality(usize),
+    Inequality(usize),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StructureBlock {
+    pub variables: Vec<usize>,
+    pub rows: Vec<ConstraintRow>,
+}
+
+// Block-angular view of a problem: independent variable groups, each with the
+// rows that only touch it, plus the linking rows that couple several groups.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProblemStructure {
+    pub blocks: Vec<StructureBlock>,
+    pub linking_rows: Vec<ConstraintRow>,
+}
+
+impl ProblemStructure {
+    pub fn is_decomposable(&self) -> bool {
+        self.blocks.len() > 1
+    }
+
+    pub fn block_of(&self, variable: usize) -> Option<usize> {
+        self.blocks
+            .iter()
+            .position(|block| block.variables.binary_search(&variable).is_ok())
+    }
+}
+
+pub fn detect_structure_qp<T: RealNumber>(problem: &ProblemQP<T>) -> ProblemStructure {
+    let mut rows = Vec::new();
+    if let Some(eq) = &problem.equalities {
+        collect_rows(&eq.matrix, ConstraintRow::Equality, &mut rows);
+    }
+    if let Some(ineq) = &problem.inequalities {
+        collect_rows(&ineq.matrix, ConstraintRow::Inequality, &mut rows);
+    }
+    // Off-diagonal quadratic terms couple variables whatever rows are cut.
+    let mut couplings = Vec::new();
+    let p = &problem.quadratic;
+    for col in 0..p.ncols {
+        for idx in p.indptr[col]..p.indptr[col + 1] {
+            if p.indices[idx] != col && p.data[idx] != T::zero() {
+                couplings.push((p.indices[idx], col));
+            }
+        }
+    }
+    detect(problem.nvars(), rows, &couplings)
+}
+
+pub fn detect_structure_lp<T: RealNumber>(problem: &ProblemLP<T>) -> ProblemStructure {
+    let mut rows = Vec::new();
+    if let Some(eq) = &problem.equalities {
+        collect_rows(&eq.matrix, ConstraintRow::Equality, &mut rows);
+    }
+    if let Some(ineq) = &problem.inequalities {
+        collect_rows(&ineq.matrix, ConstraintRow::Inequality, &mut rows);
+    }
+    detect(problem.nvars(), rows, &[])
+}
+
+fn collect_rows<T: RealNumber>(
+    matrix: &CscMatrix<T>,
+    label: fn(usize) -> ConstraintRow,
+    rows: &mut Vec<(ConstraintRow, Vec<usize>)>,
+) {
+    let mut columns = vec![Vec::new(); matrix.nrows];
+    for col in 0..matrix.ncols {
+        for idx in matrix.indptr[col]..matrix.indptr[col + 1] {
+            if matrix.data[idx] != T::zero() {
+                columns[matrix.indices[idx]].push(col);
+            }
+        }
+    }
+    rows.extend(
+        columns
+            .into_iter()
+            .enumerate()
+            .map(|(row, vars)| (label(row), vars)),
+    );
+}
+
+// Greedy partition: linking rows are the densest rows, and the cut is the
+// smallest such set whose removal splits the variable graph. Rows are merged
+// sparsest first with a union-find, recording the component count after each
+// one, so every candidate cut is evaluated in a single pass.
+fn detect(
+    nvars: usize,
+    mut rows: Vec<(ConstraintRow, Vec<usize>)>,
+    couplings: &[(usize, usize)],
+) -> ProblemStructure {
+    rows.sort_by(|a, b| a.1.len().cmp(&b.1.len()).then(a.0.cmp(&b.0)));
+    let mut sets = DisjointSets::new(nvars);
+    for &(a, b) in couplings {
+        sets.union(a, b);
+    }
+    let mut components = Vec::with_capacity(rows.len() + 1);
+    components.push(sets.count);
+    for (_, vars) in &rows {
+        for pair in vars.windows(2) {
+            sets.union(pair[0], pair[1]);
+        }
+        components.push(sets.count);
+    }
+
+    let max_linking = (rows.len() as f64 * MAX_LINKING_FRACTION).floor() as usize;
+    let kept = (rows.len().saturating_sub(max_linking)..=rows.len())
+        .rev()
+        .find(|&kept| components[kept] > 1)
+        .unwrap_or(rows.len());
+
+    let mut sets = DisjointSets::new(nvars);
+    for &(a, b) in couplings {
+        sets.union(a, b);
+    }
+    for (_, vars) in &rows[..kept] {
+        for pair in vars.windows(2) {
+            sets.union(pair[0], pair[1]);
+        }
+    }
+    let mut block_index = vec![usize::MAX; nvars];
+    let mut blocks: Vec<StructureBlock> = Vec::new();
+    for var in 0..nvars {
+        let root = sets.find(var);
+        if block_index[root] == usize::MAX {
+            block_index[root] = blocks.len();
+            blocks.push(StructureBlock {
+                variables: Vec::new(),
+                rows: Vec::new(),
+            });
+        }
+        blocks[block_index[root]].variables.push(var);
+    }
+    let mut linking_rows = Vec::new();
+    for (row, vars) in &rows {
+        match vars.first() {
+            // A dense row past the cut may still sit inside a single block.
+            Some(&var) => {
+                let root = sets.find(var);
+                if vars.iter().all(|&other| sets.find(other) == root) {
+                    blocks[block_index[root]].rows.push(*row);
+                } else {
+                    linking_rows.push(*row);
+                }
+            }
+            // Empty rows constrain nothing; keep them with the first block.
+            None => {
+                if let Some(block) = blocks.first_mut() {
+                    block.rows.push(*row);
+                }
+            }
+        }
+    }
+    for block in &mut blocks {
+        block.rows.sort();
+    }
+    linking_rows.sort();
+    ProblemStructure {
+        blocks,
+        linking_rows,
+    }
+}
+
+struct DisjointSets {
+    parent: Vec<usize>,
+    count: usize,
+}
+
+impl DisjointSets {
+    fn new(n: usize) -> Self {
+        Self {
+            parent: (0..n).collect(),
+            count: n,
+        }
+    }
+
+    fn find(&mut self, mut x: usize) -> usize {
+        while self.parent[x] != x {
+            self.parent[x] = self.parent[self.parent[x]];
+            x = self.parent[x];
+        }
+        x
+    }
+
+    fn union(&mut self, a: usize, b: usize) {
+        let (ra, rb) = (self.find(a), self.find(b));
+        if ra != rb {
+            self.parent[ra.max(rb)] = ra.min(rb);
+            self.count -= 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::problem::{EqualityConstraints, InequalityConstraints, Names, ObjectiveSense};
+
+    fn csc(nrows: usize, ncols: usize, entries: &[(usize, usize)]) -> CscMatrix<f64> {
+        let mut indptr = vec![0];
+        let mut indices = Vec::new();
+        for col in 0..ncols {
+            indices.extend(entries.iter().filter(|e| e.1 == col).map(|e| e.0));
+            indptr.push(indices.len());
+        }
+        CscMatrix {
+            nrows,
+            ncols,
+            indptr,
+            data: vec![1.0; indices.len()],
+            indices,
+        }
+    }
+
+    #[test]
+    fn finds_two_blocks_and_the_linking_budget_row() {
+        // Blocks {0,1,2} and {3,4,5}; the equality row is a shared budget.
+        let inequalities = csc(
+            5,
+            6,
+            &[
+                (0, 0),
+                (0, 1),
+                (1, 1),
+                (1, 2),
+                (2, 3),
+                (2, 4),
+                (3, 4),
+                (3, 5),
+                (4, 2),
+            ],
+        );
+        let problem = ProblemLP {
+            cost: vec![1.0; 6],
+            equalities: Some(EqualityConstraints {
+                matrix: csc(1, 6, &[(0, 0), (0, 1), (0, 2), (0, 3), (0, 4), (0, 5)]),
+                rhs: vec![1.0],
+            }),
+            inequalities: Some(InequalityConstraints {
+                matrix: inequalities,
+                rhs: vec![1.0; 5],
+            }),
+            bounds: None,
+            groups: Vec::new(),
+            integrality: Vec::new(),
+            names: Names::default(),
+            sense: ObjectiveSense::Minimize,
+            offset: 0.0,
+        };
+        let structure = detect_structure_lp(&problem);
+        assert!(structure.is_decomposable());
+        assert_eq!(structure.linking_rows, vec![ConstraintRow::Equality(0)]);
+        assert_eq!(structure.blocks[0].variables, vec![0, 1, 2]);
+        assert_eq!(
+            structure.blocks[0].rows,
+            vec![
+                ConstraintRow::Inequality(0),
+                ConstraintRow::Inequality(1),
+                ConstraintRow::Inequality(4)
+            ]
+        );
+        assert_eq!(structure.blocks[1].variables, vec![3, 4, 5]);
+        assert_eq!(structure.block_of(4), Some(1));
+    }
+}
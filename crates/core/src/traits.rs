@@ -1,8 +1,11 @@
 use crate::math::RealNumber;
 use crate::options::SolveOptions;
 use crate::problem::{ProblemLP, ProblemQP, ProblemResult};
-use crate::stats::{IterationRecord, SolveStats};
+use crate::solution::Solution;
+use crate::stats::{IterationRecord, KrylovStats, RegularizationStats, SolveStats};
 use anyhow::Result;
+use std::sync::Arc;
+use std::time::Duration;
 
 pub trait LinearOperator<T: RealNumber>: Send + Sync {
     fn dim(&self) -> (usize, usize);
@@ -24,20 +27,160 @@ pub trait KktSolver<T: RealNumber>: Send {
     fn factor(&mut self, matrix: &Self::Matrix) -> Result<()>;
 
     fn solve(&self, rhs: &mut [T]) -> Result<()>;
+
+    // Time spent so far in `analyze_pattern` and the symbolic part of
+    // `factor`, and in the numeric part of `factor`.
+    fn symbolic_time(&self) -> Duration {
+        Duration::ZERO
+    }
+
+    fn numeric_time(&self) -> Duration {
+        Duration::ZERO
+    }
+
+    // Iteration counts of an iterative solver; None for factorizations.
+    fn krylov_stats(&self) -> Option<KrylovStats> {
+        None
+    }
+
+    // Pivot perturbations so far; None unless regularization is enabled.
+    fn regularization_stats(&self) -> Option<RegularizationStats<T>> {
+        None
+    }
+
+    // Estimate of the 1-norm condition number of the matrix last factored,
+    // at the price of a few solves; None when the solver cannot give one.
+    fn condition_estimate(&self) -> Option<T> {
+        None
+    }
 }
 
 pub trait StoppingCriterion<T: RealNumber> {
     fn is_converged(&self, record: &IterationRecord<T>, options: &SolveOptions<T>) -> bool;
 }
 
+// Replaces a solver's own convergence test; a record it accepts ends the
+// solve as Optimal.
+pub type StoppingRule<T> = Arc<dyn StoppingCriterion<T> + Send + Sync>;
+
+// The default test, OSQP's: each residual within eps_abs + eps_rel times
+// the size of its terms, and the gap within eps_abs.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ResidualTolerance;
+
+impl<T: RealNumber> StoppingCriterion<T> for ResidualTolerance {
+    fn is_converged(&self, record: &IterationRecord<T>, options: &SolveOptions<T>) -> bool {
+        let (abs, rel) = (options.eps_abs, options.eps_rel);
+        record.primal_residual <= abs + rel * record.primal_scale
+            && record.dual_residual <= abs + rel * record.dual_scale
+            && record.relative_gap <= abs
+    }
+}
+
+impl<T, F> StoppingCriterion<T> for F
+where
+    T: RealNumber,
+    F: Fn(&IterationRecord<T>, &SolveOptions<T>) -> bool,
+{
+    fn is_converged(&self, record: &IterationRecord<T>, options: &SolveOptions<T>) -> bool {
+        self(record, options)
+    }
+}
+
 pub trait Scaler<T: RealNumber> {
     fn scale_lp(&mut self, problem: &mut ProblemLP<T>) -> ProblemResult<()>;
 
     fn scale_qp(&mut self, problem: &mut ProblemQP<T>) -> ProblemResult<()>;
 
+    // Applies the scaling the last `scale_qp` computed to a problem with the
+    // same matrices, e.g. the next member of a parametric batch.
+    fn rescale_qp(&self, problem: &mut ProblemQP<T>) -> ProblemResult<()>;
+
+    // The inverse of unscale_primal, for a point given in the original
+    // units such as a warm start.
+    fn scale_primal(&self, _primal: &mut [T]) {}
+
     fn unscale_primal(&self, _primal: &mut [T]) {}
 
     fn unscale_dual(&self, _equality: &mut [T], _inequality: &mut [T]) {}
 
+    // The objective of the original problem from that of the scaled one;
+    // only a scaler that scales the cost changes it.
+    fn unscale_objective(&self, objective: T) -> T {
+        objective
+    }
+
+    // Primal residuals in the layout of the multipliers and the dual
+    // residual, taken from the scaled problem to the original one. Solvers
+    // apply it as they iterate, so that the iteration records and the
+    // termination tests are in the units of the data the caller gave.
+    fn unscale_residuals(&self, _equality: &mut [T], _inequality: &mut [T], _dual: &mut [T]) {}
+
+    // Whatever else in the statistics the solver measured on the scaled
+    // problem; the iteration records are already in the original units.
     fn unscale_stats(&self, stats: &mut SolveStats<T>);
 }
+
+// A boxed scaler, so that a solver can hold one chosen at run time.
+impl<T, S> Scaler<T> for Box<S>
+where
+    T: RealNumber,
+    S: Scaler<T> + ?Sized,
+{
+    fn scale_lp(&mut self, problem: &mut ProblemLP<T>) -> ProblemResult<()> {
+        (**self).scale_lp(problem)
+    }
+
+    fn scale_qp(&mut self, problem: &mut ProblemQP<T>) -> ProblemResult<()> {
+        (**self).scale_qp(problem)
+    }
+
+    fn rescale_qp(&self, problem: &mut ProblemQP<T>) -> ProblemResult<()> {
+        (**self).rescale_qp(problem)
+    }
+
+    fn scale_primal(&self, primal: &mut [T]) {
+        (**self).scale_primal(primal);
+    }
+
+    fn unscale_primal(&self, primal: &mut [T]) {
+        (**self).unscale_primal(primal);
+    }
+
+    fn unscale_dual(&self, equality: &mut [T], inequality: &mut [T]) {
+        (**self).unscale_dual(equality, inequality);
+    }
+
+    fn unscale_objective(&self, objective: T) -> T {
+        (**self).unscale_objective(objective)
+    }
+
+    fn unscale_residuals(&self, equality: &mut [T], inequality: &mut [T], dual: &mut [T]) {
+        (**self).unscale_residuals(equality, inequality, dual);
+    }
+
+    fn unscale_stats(&self, stats: &mut SolveStats<T>) {
+        (**self).unscale_stats(stats);
+    }
+}
+
+// One presolve step: maps a problem to a reduced one and returns the record
+// that maps the reduced problem's solutions back to it.
+pub trait Presolver<T: RealNumber>: Send + Sync {
+    fn presolve_qp(&self, problem: &ProblemQP<T>) -> (ProblemQP<T>, Box<dyn Transformation<T>>);
+
+    fn presolve_lp(&self, problem: &ProblemLP<T>) -> (ProblemLP<T>, Box<dyn Transformation<T>>);
+}
+
+// What a presolve step did, kept to undo it on the solution: the primal,
+// both kinds of multipliers, certificates and bases of the reduced problem
+// become those of the problem the step was given.
+pub trait Transformation<T: RealNumber> {
+    fn postsolve(&self, solution: Solution<T>) -> Solution<T>;
+
+    // Set when the step proved the problem infeasible by itself; the
+    // solution then belongs to the problem the step was given.
+    fn infeasible(&self) -> Option<Solution<T>> {
+        None
+    }
+}
@@ -0,0 +1,333 @@
+use dashu_float::FBig;
+
+use crate::math::RealNumber;
+use crate::problem::{Bounds, CscMatrix, ProblemLP, ProblemQP};
+use crate::solution::{Solution, Status};
+
+// KKT residuals of a returned solution, evaluated once exactly (every f64 is
+// a dyadic rational, so sums and products are kept at unlimited precision and
+// rounded only at the end) and once in plain f64 for comparison.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Verification {
+    pub primal_residual: f64,
+    pub dual_residual: Option<f64>,
+    pub objective: f64,
+    pub rounded_primal_residual: f64,
+    pub rounded_dual_residual: Option<f64>,
+    pub rounded_objective: f64,
+    pub reliable: bool,
+}
+
+pub fn verify_qp<T: RealNumber>(
+    problem: &ProblemQP<T>,
+    solution: &Solution<T>,
+    tolerance: T,
+) -> Verification {
+    let view = View {
+        quadratic: Some(&problem.quadratic),
+        linear: &problem.linear,
+        offset: problem.offset,
+        equalities: problem
+            .equalities
+            .as_ref()
+            .map(|eq| (&eq.matrix, eq.rhs.as_slice())),
+        inequalities: problem
+            .inequalities
+            .as_ref()
+            .map(|ineq| (&ineq.matrix, ineq.rhs.as_slice())),
+        bounds: problem.bounds.as_ref(),
+    };
+    verify(&view, solution, to_f64(tolerance))
+}
+
+pub fn verify_lp<T: RealNumber>(
+    problem: &ProblemLP<T>,
+    solution: &Solution<T>,
+    tolerance: T,
+) -> Verification {
+    let view = View {
+        quadratic: None,
+        linear: &problem.cost,
+        offset: problem.offset,
+        equalities: problem
+            .equalities
+            .as_ref()
+            .map(|eq| (&eq.matrix, eq.rhs.as_slice())),
+        inequalities: problem
+            .inequalities
+            .as_ref()
+            .map(|ineq| (&ineq.matrix, ineq.rhs.as_slice())),
+        bounds: problem.bounds.as_ref(),
+    };
+    verify(&view, solution, to_f64(tolerance))
+}
+
+struct View<'a, T: RealNumber> {
+    quadratic: Option<&'a CscMatrix<T>>,
+    linear: &'a [T],
+    offset: T,
+    equalities: Option<(&'a CscMatrix<T>, &'a [T])>,
+    inequalities: Option<(&'a CscMatrix<T>, &'a [T])>,
+    bounds: Option<&'a Bounds<T>>,
+}
+
+struct Duals<'a, T> {
+    equality: &'a [T],
+    inequality: &'a [T],
+    bound: &'a [T],
+}
+
+trait Arithmetic: Clone {
+    fn lift(value: f64) -> Self;
+    fn add(&self, rhs: &Self) -> Self;
+    fn sub(&self, rhs: &Self) -> Self;
+    fn mul(&self, rhs: &Self) -> Self;
+    fn round(&self) -> f64;
+}
+
+impl Arithmetic for f64 {
+    fn lift(value: f64) -> Self {
+        value
+    }
+
+    fn add(&self, rhs: &Self) -> Self {
+        self + rhs
+    }
+
+    fn sub(&self, rhs: &Self) -> Self {
+        self - rhs
+    }
+
+    fn mul(&self, rhs: &Self) -> Self {
+        self * rhs
+    }
+
+    fn round(&self) -> f64 {
+        *self
+    }
+}
+
+#[derive(Clone)]
+struct Exact(FBig);
+
+impl Arithmetic for Exact {
+    fn lift(value: f64) -> Self {
+        // Operands must all carry unlimited precision, otherwise dashu rounds
+        // results to the largest finite precision involved.
+        let value = FBig::try_from(value).expect("finite value");
+        Exact(value.with_precision(0).value())
+    }
+
+    fn add(&self, rhs: &Self) -> Self {
+        Exact(&self.0 + &rhs.0)
+    }
+
+    fn sub(&self, rhs: &Self) -> Self {
+        Exact(&self.0 - &rhs.0)
+    }
+
+    fn mul(&self, rhs: &Self) -> Self {
+        Exact(&self.0 * &rhs.0)
+    }
+
+    fn round(&self) -> f64 {
+        self.0.to_f64().value()
+    }
+}
+
+struct Residuals {
+    primal: f64,
+    dual: Option<f64>,
+    objective: f64,
+}
+
+fn verify<T: RealNumber>(view: &View<T>, solution: &Solution<T>, tolerance: f64) -> Verification {
+    let exact = residuals::<Exact, T>(view, solution);
+    let rounded = residuals::<f64, T>(view, solution);
+    let discrepancy = |exact: Option<f64>, rounded: Option<f64>| match (exact, rounded) {
+        (Some(exact), Some(rounded)) => (exact - rounded).abs(),
+        _ => 0.0,
+    };
+    // An Optimal claim is unreliable when the exact residuals miss the
+    // tolerance, or when f64 rounding alone is as large as the tolerance.
+    let reliable = solution.status != Status::Optimal
+        || (exact.primal <= tolerance
+            && exact.dual.map_or(true, |dual| dual <= tolerance)
+            && discrepancy(Some(exact.primal), Some(rounded.primal)) < tolerance
+            && discrepancy(exact.dual, rounded.dual) < tolerance);
+    Verification {
+        primal_residual: exact.primal,
+        dual_residual: exact.dual,
+        objective: exact.objective,
+        rounded_primal_residual: rounded.primal,
+        rounded_dual_residual: rounded.dual,
+        rounded_objective: rounded.objective,
+        reliable,
+    }
+}
+
+fn residuals<A: Arithmetic, T: RealNumber>(view: &View<T>, solution: &Solution<T>) -> Residuals {
+    let x: Vec<A> = solution
+        .primal
+        .iter()
+        .map(|&v| A::lift(to_f64(v)))
+        .collect();
+    let zero = A::lift(0.0);
+
+    let mut primal: f64 = 0.0;
+    if let Some((matrix, rhs)) = view.equalities {
+        for (ax, &b) in multiply(matrix, &x).iter().zip(rhs) {
+            primal = primal.max(ax.sub(&A::lift(to_f64(b))).round().abs());
+        }
+    }
+    if let Some((matrix, rhs)) = view.inequalities {
+        for (gx, &h) in multiply(matrix, &x).iter().zip(rhs) {
+            primal = primal.max(gx.sub(&A::lift(to_f64(h))).round());
+        }
+    }
+    if let Some(bounds) = view.bounds {
+        for ((value, &lower), &upper) in x.iter().zip(&bounds.lower).zip(&bounds.upper) {
+            let (lower, upper) = (to_f64(lower), to_f64(upper));
+            if lower.is_finite() {
+                primal = primal.max(A::lift(lower).sub(value).round());
+            }
+            if upper.is_finite() {
+                primal = primal.max(value.sub(&A::lift(upper)).round());
+            }
+        }
+    }
+
+    let px = match view.quadratic {
+        Some(matrix) => multiply(matrix, &x),
+        None => vec![zero.clone(); x.len()],
+    };
+    let mut objective = A::lift(to_f64(view.offset));
+    for ((xi, pxi), &qi) in x.iter().zip(&px).zip(view.linear) {
+        let term = A::lift(0.5).mul(pxi).add(&A::lift(to_f64(qi)));
+        objective = objective.add(&xi.mul(&term));
+    }
+
+    let dual = duals(view, solution).map(|duals| {
+        let mut gradient: Vec<A> = px
+            .iter()
+            .zip(view.linear)
+            .map(|(pxi, &qi)| pxi.add(&A::lift(to_f64(qi))))
+            .collect();
+        if let Some((matrix, _)) = view.equalities {
+            multiply_transpose(matrix, duals.equality, &mut gradient);
+        }
+        if let Some((matrix, _)) = view.inequalities {
+            multiply_transpose(matrix, duals.inequality, &mut gradient);
+        }
+        for (g, &y) in gradient.iter_mut().zip(duals.bound) {
+            *g = g.add(&A::lift(to_f64(y)));
+        }
+        gradient
+            .iter()
+            .fold(0.0, |acc: f64, g| acc.max(g.round().abs()))
+    });
+
+    Residuals {
+        primal,
+        dual,
+        objective: objective.round(),
+    }
+}
+
+// ADMM reports one multiplier per stacked row (equalities, inequalities,
+// bounds) in `inequality_dual`; other methods fill the two vectors separately.
+fn duals<'a, T: RealNumber>(view: &View<T>, solution: &'a Solution<T>) -> Option<Duals<'a, T>> {
+    let meq = view.equalities.map_or(0, |(matrix, _)| matrix.nrows);
+    let mineq = view.inequalities.map_or(0, |(matrix, _)| matrix.nrows);
+    let nbounds = view.bounds.map_or(0, |bounds| bounds.lower.len());
+    let (eq, ineq) = (&solution.equality_dual, &solution.inequality_dual);
+    if eq.is_empty() && ineq.len() == meq + mineq + nbounds {
+        Some(Duals {
+            equality: &ineq[..meq],
+            inequality: &ineq[meq..meq + mineq],
+            bound: &ineq[meq + mineq..],
+        })
+    } else if eq.len() == meq && ineq.len() == mineq && nbounds == 0 {
+        Some(Duals {
+            equality: eq,
+            inequality: ineq,
+            bound: &[],
+        })
+    } else {
+        None
+    }
+}
+
+fn multiply<A: Arithmetic, T: RealNumber>(matrix: &CscMatrix<T>, x: &[A]) -> Vec<A> {
+    let mut out = vec![A::lift(0.0); matrix.nrows];
+    for (col, xj) in x.iter().enumerate().take(matrix.ncols) {
+        for idx in matrix.indptr[col]..matrix.indptr[col + 1] {
+            let row = matrix.indices[idx];
+            out[row] = out[row].add(&A::lift(to_f64(matrix.data[idx])).mul(xj));
+        }
+    }
+    out
+}
+
+fn multiply_transpose<A: Arithmetic, T: RealNumber>(matrix: &CscMatrix<T>, y: &[T], out: &mut [A]) {
+    for (col, value) in out.iter_mut().enumerate().take(matrix.ncols) {
+        for idx in matrix.indptr[col]..matrix.indptr[col + 1] {
+            let term =
+                A::lift(to_f64(matrix.data[idx])).mul(&A::lift(to_f64(y[matrix.indices[idx]])));
+            *value = value.add(&term);
+        }
+    }
+}
+
+fn to_f64<T: RealNumber>(value: T) -> f64 {
+    value.to_f64().unwrap_or(f64::NAN)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::problem::{EqualityConstraints, Names, ObjectiveSense};
+    use crate::stats::SolveStats;
+
+    #[test]
+    fn exact_residual_catches_cancellation() {
+        // x0 + x1 + x2 = 2 at x = (1e16, 1, -1e16): f64 drops the 1 when
+        // adding it to 1e16 and reports a residual of 2 instead of 1.
+        let problem = ProblemLP {
+            cost: vec![0.0, 0.0, 0.0],
+            equalities: Some(EqualityConstraints {
+                matrix: CscMatrix {
+                    nrows: 1,
+                    ncols: 3,
+                    indptr: vec![0, 1, 2, 3],
+                    indices: vec![0, 0, 0],
+                    data: vec![1.0, 1.0, 1.0],
+                },
+                rhs: vec![2.0],
+            }),
+            inequalities: None,
+            bounds: None,
+            groups: Vec::new(),
+            integrality: Vec::new(),
+            names: Names::default(),
+            sense: ObjectiveSense::Minimize,
+            offset: 0.0,
+        };
+        let solution = Solution {
+            primal: vec![1e16, 1.0, -1e16],
+            equality_dual: vec![0.0],
+            inequality_dual: Vec::new(),
+            status: Status::Optimal,
+            objective_value: 0.0,
+            iterations: 1,
+            stats: SolveStats::new(),
+            certificate: None,
+            basis: None,
+        };
+        let verification = verify_lp(&problem, &solution, 1e-6);
+        assert_eq!(verification.rounded_primal_residual, 2.0);
+        assert_eq!(verification.primal_residual, 1.0);
+        assert_eq!(verification.dual_residual, Some(0.0));
+        assert!(!verification.reliable);
+    }
+}
@@ -0,0 +1,91 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use anyhow::Result;
+use cvxrs_api::CheckpointSink;
+use cvxrs_core::math::Scalar;
+use cvxrs_io::{problem_fingerprint, read_checkpoint, write_checkpoint, Checkpoint};
+
+pub const AUTOSAVE_INTERVAL: Duration = Duration::from_secs(30);
+
+#[derive(Clone, Debug)]
+pub struct Unfinished {
+    pub problem_path: PathBuf,
+    pub fingerprint: u64,
+    pub iteration: usize,
+}
+
+// Checkpoints are keyed by the problem fingerprint, so editing the problem
+// file silently invalidates the saved run. A sibling file remembers which
+// problem each checkpoint came from, to offer it again on startup.
+fn directory() -> PathBuf {
+    env::var_os("LOCALAPPDATA")
+        .map(PathBuf::from)
+        .unwrap_or_else(env::temp_dir)
+        .join("cvxrs-studio")
+        .join("checkpoints")
+}
+
+fn state_path(fingerprint: u64) -> PathBuf {
+    directory().join(format!("{:016x}.ckpt", fingerprint))
+}
+
+fn origin_path(fingerprint: u64) -> PathBuf {
+    directory().join(format!("{:016x}.origen", fingerprint))
+}
+
+pub fn sink(problem_path: &Path, fingerprint: u64) -> CheckpointSink<Scalar> {
+    let problem_path = problem_path.to_path_buf();
+    Arc::new(move |state| {
+        fs::create_dir_all(directory())?;
+        fs::write(
+            origin_path(fingerprint),
+            problem_path.to_string_lossy().as_bytes(),
+        )?;
+        write_checkpoint(
+            state_path(fingerprint),
+            &Checkpoint {
+                fingerprint,
+                state: state.clone(),
+            },
+        )
+    })
+}
+
+pub fn load(fingerprint: u64) -> Result<Checkpoint> {
+    read_checkpoint(state_path(fingerprint))
+}
+
+pub fn discard(fingerprint: u64) {
+    let _ = fs::remove_file(state_path(fingerprint));
+    let _ = fs::remove_file(origin_path(fingerprint));
+}
+
+pub fn find(problem_path: &Path) -> Option<Unfinished> {
+    let problem = crate::load_problem(problem_path).ok()?;
+    let fingerprint = problem_fingerprint(&problem);
+    let checkpoint = load(fingerprint).ok()?;
+    Some(Unfinished {
+        problem_path: problem_path.to_path_buf(),
+        fingerprint,
+        iteration: checkpoint.state.iteration,
+    })
+}
+
+// Most recent unfinished run whose problem file still exists unchanged.
+pub fn latest() -> Option<Unfinished> {
+    let mut origins: Vec<(SystemTime, PathBuf)> = fs::read_dir(directory())
+        .ok()?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "origen"))
+        .filter_map(|path| Some((fs::metadata(&path).ok()?.modified().ok()?, path)))
+        .collect();
+    origins.sort_by_key(|(modified, _)| std::cmp::Reverse(*modified));
+    origins.into_iter().find_map(|(_, origin)| {
+        let problem_path = PathBuf::from(fs::read_to_string(origin).ok()?);
+        find(&problem_path)
+    })
+}
@@ -0,0 +1,96 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{anyhow, Result};
+
+pub const CAMERA_AVAILABLE: bool = cfg!(feature = "camera");
+
+pub fn clipboard_image(dir: &Path) -> Result<PathBuf> {
+    let mut clipboard = arboard::Clipboard::new()
+        .map_err(|err| anyhow!("No se pudo acceder al portapapeles: {}", err))?;
+    match clipboard.get_image() {
+        Ok(image) => write_png(
+            dir,
+            "portapapeles",
+            image.width as u32,
+            image.height as u32,
+            png::ColorType::Rgba,
+            &image.bytes,
+        ),
+        Err(_) => {
+            // Some file managers copy the file itself, which arrives as a path.
+            let text = clipboard.get_text().unwrap_or_default();
+            let candidate = PathBuf::from(text.trim().trim_start_matches("file://"));
+            if !text.trim().is_empty() && candidate.is_file() {
+                Ok(candidate)
+            } else {
+                Err(anyhow!(
+                    "El portapapeles no contiene una imagen. Copia una foto o captura e intenta de nuevo."
+                ))
+            }
+        }
+    }
+}
+
+#[cfg(feature = "camera")]
+pub fn camera_frame(dir: &Path) -> Result<PathBuf> {
+    use nokhwa::pixel_format::RgbFormat;
+    use nokhwa::utils::{CameraIndex, RequestedFormat, RequestedFormatType};
+    use nokhwa::Camera;
+
+    let format = RequestedFormat::new::<RgbFormat>(RequestedFormatType::AbsoluteHighestResolution);
+    let mut camera = Camera::new(CameraIndex::Index(0), format)
+        .map_err(|err| anyhow!("No se encontro una camara disponible: {}", err))?;
+    camera
+        .open_stream()
+        .map_err(|err| anyhow!("No se pudo abrir la camara: {}", err))?;
+    // The first frames are often dark while the sensor adjusts its exposure.
+    let mut frame = camera.frame();
+    for _ in 0..5 {
+        frame = camera.frame();
+    }
+    let _ = camera.stop_stream();
+    let image = frame
+        .and_then(|frame| frame.decode_image::<RgbFormat>())
+        .map_err(|err| anyhow!("No se pudo capturar la imagen de la camara: {}", err))?;
+    write_png(
+        dir,
+        "camara",
+        image.width(),
+        image.height(),
+        png::ColorType::Rgb,
+        image.as_raw(),
+    )
+}
+
+#[cfg(not(feature = "camera"))]
+pub fn camera_frame(_dir: &Path) -> Result<PathBuf> {
+    Err(anyhow!(
+        "Esta version se compilo sin soporte de camara (activa la feature \"camera\")."
+    ))
+}
+
+fn write_png(
+    dir: &Path,
+    prefix: &str,
+    width: u32,
+    height: u32,
+    color: png::ColorType,
+    pixels: &[u8],
+) -> Result<PathBuf> {
+    fs::create_dir_all(dir)?;
+    let stamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis())
+        .unwrap_or_default();
+    let path = dir.join(format!("{}-{}.png", prefix, stamp));
+    let file = fs::File::create(&path)?;
+    let mut encoder = png::Encoder::new(std::io::BufWriter::new(file), width, height);
+    encoder.set_color(color);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(pixels)?;
+    writer.finish()?;
+    Ok(path)
+}
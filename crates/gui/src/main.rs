@@ -1,27 +1,42 @@
 #![forbid(unsafe_code)]
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod autosave;
+mod capture;
+mod panels;
+mod templates;
+
 use std::env;
 use std::fs;
+use std::ops::ControlFlow;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, bail, Result};
+use autosave::Unfinished;
 use base64::engine::general_purpose::STANDARD as BASE64_STANDARD;
 use base64::Engine as _;
-use cvxrs_api::{Method, Solver};
+use cvxrs_api::{group_report_lp, group_report_qp, GroupReport, MemoryEstimate, Method, Solver};
 use cvxrs_core::math::Scalar;
 use cvxrs_core::options::SolveOptions;
 use cvxrs_core::solution::{Solution, Status};
-use cvxrs_io::{read_json_problem, write_solution, JsonProblem};
+use cvxrs_io::{
+    parse_json_problem, parse_json_problem_with, problem_fingerprint, read_json_problem,
+    read_tabular_problem, to_latex, write_json_problem, write_solution, JsonDiagnostic,
+    JsonProblem, ParseOptions,
+};
+use eframe::egui::text::{LayoutJob, TextFormat};
 use eframe::egui::{
     self, Align, Color32, FontData, FontDefinitions, FontFamily, FontId, Margin, RichText, Stroke,
     TextStyle,
 };
 use eframe::{App, CreationContext, Frame, NativeOptions};
+use panels::PanelRegistry;
 use reqwest::blocking::Client;
 use rfd::FileDialog;
+use templates::{TemplateGallery, TemplateKind};
 
 const SAMPLE_QP_JSON: &str = r#"
 {
@@ -73,6 +88,7 @@ La respuesta DEBE ser unicamente el objeto JSON crudo: comienza en `{` y termina
 const GEMINI_USER_PROMPT: &str =
     "Analiza la imagen adjunta, interpreta el problema matematico y responde UNICAMENTE con JSON valido listo para cvxrs.";
 
+const MEMORY_WARNING_BYTES: u64 = 1024 * 1024 * 1024;
 const FONT_REGULAR: &str = "plus-jakarta-regular";
 const FONT_SEMIBOLD: &str = "plus-jakarta-semibold";
 
@@ -314,6 +330,22 @@ impl SectionStyle {
             accent: Palette::accent_mint(),
         }
     }
+
+    fn editor() -> Self {
+        Self {
+            fill: Palette::surface(),
+            border: Palette::border_soft(),
+            accent: Palette::accent_azure(),
+        }
+    }
+
+    fn templates() -> Self {
+        Self {
+            fill: Palette::surface(),
+            border: Palette::border_soft(),
+            accent: Palette::accent_gold_soft(),
+        }
+    }
 }
 
 fn section_card<R>(
@@ -349,40 +381,30 @@ fn section_card<R>(
     inner
 }
 
-#[derive(Clone)]
+#[derive(Clone, Default)]
 enum TaskState {
+    #[default]
     Idle,
     Running,
-    Success(SolveSummary),
+    Success(Box<SolveSummary>),
     Failure(String),
 }
 
-impl Default for TaskState {
-    fn default() -> Self {
-        Self::Idle
-    }
-}
-
 impl TaskState {
     fn is_running(&self) -> bool {
         matches!(self, TaskState::Running)
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, Default)]
 enum GeminiState {
+    #[default]
     Idle,
     Running,
     Success(String),
     Failure(String),
 }
 
-impl Default for GeminiState {
-    fn default() -> Self {
-        GeminiState::Idle
-    }
-}
-
 impl GeminiState {
     fn is_running(&self) -> bool {
         matches!(self, GeminiState::Running)
@@ -396,23 +418,39 @@ struct SolveSummary {
     output_path: Option<PathBuf>,
     solution: Solution<Scalar>,
     solution_json: Option<String>,
+    groups: Vec<GroupReport<Scalar>>,
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
 enum MethodChoice {
     Admm,
     Ipm,
+    ActiveSet,
+    Simplex,
+    Pdhg,
+    Auto,
 }
 
 impl MethodChoice {
-    fn all() -> [MethodChoice; 2] {
-        [MethodChoice::Admm, MethodChoice::Ipm]
+    fn all() -> [MethodChoice; 6] {
+        [
+            MethodChoice::Admm,
+            MethodChoice::Ipm,
+            MethodChoice::ActiveSet,
+            MethodChoice::Simplex,
+            MethodChoice::Pdhg,
+            MethodChoice::Auto,
+        ]
     }
 
     fn display_name(self) -> &'static str {
         match self {
             MethodChoice::Admm => "ADMM (robust & warm-start friendly)",
             MethodChoice::Ipm => "IPM (fast interior-point method)",
+            MethodChoice::ActiveSet => "Active set (exact, small dense QPs)",
+            MethodChoice::Simplex => "Simplex (vertex solutions for LPs)",
+            MethodChoice::Pdhg => "PDHG (matrix-free, very large LPs)",
+            MethodChoice::Auto => "Auto (picks a method, ADMM as fallback)",
         }
     }
 
@@ -420,6 +458,10 @@ impl MethodChoice {
         match self {
             MethodChoice::Admm => "ADMM",
             MethodChoice::Ipm => "IPM",
+            MethodChoice::ActiveSet => "Active set",
+            MethodChoice::Simplex => "Simplex",
+            MethodChoice::Pdhg => "PDHG",
+            MethodChoice::Auto => "Auto",
         }
     }
 
@@ -427,6 +469,10 @@ impl MethodChoice {
         match self {
             MethodChoice::Admm => Method::Admm,
             MethodChoice::Ipm => Method::Ipm,
+            MethodChoice::ActiveSet => Method::ActiveSet,
+            MethodChoice::Simplex => Method::Simplex,
+            MethodChoice::Pdhg => Method::Pdhg,
+            MethodChoice::Auto => Method::Auto,
         }
     }
 }
@@ -462,26 +508,137 @@ impl BannerKind {
     }
 }
 
-struct CvxrsApp {
+struct ProblemTab {
+    id: u64,
     problem_path: Option<PathBuf>,
     problem_input: String,
     output_path: Option<PathBuf>,
     output_input: String,
     method: MethodChoice,
     tolerance_input: String,
+    eps_rel_input: String,
+    prim_inf_input: String,
+    dual_inf_input: String,
     max_iters_input: String,
     time_limit_input: String,
     write_solution: bool,
     log_json: bool,
+    problem_text: String,
+    diagnostic: Option<JsonDiagnostic>,
+    memory_warning: Option<MemoryEstimate>,
+    memory_confirmed: bool,
+    resume_offer: Option<Unfinished>,
+    task_state: Arc<Mutex<TaskState>>,
+    progress: Arc<SolveProgress>,
+}
+
+// Shared with the solve thread: the iterations it has reported so far and
+// whether the user asked it to stop.
+#[derive(Default)]
+struct SolveProgress {
+    iterations: AtomicUsize,
+    stop: AtomicBool,
+}
+
+impl ProblemTab {
+    fn new(id: u64) -> Self {
+        Self {
+            id,
+            problem_path: None,
+            problem_input: String::new(),
+            output_path: None,
+            output_input: String::new(),
+            method: MethodChoice::Admm,
+            tolerance_input: String::new(),
+            eps_rel_input: String::new(),
+            prim_inf_input: String::new(),
+            dual_inf_input: String::new(),
+            max_iters_input: String::new(),
+            time_limit_input: String::new(),
+            write_solution: false,
+            log_json: false,
+            problem_text: String::new(),
+            diagnostic: None,
+            memory_warning: None,
+            memory_confirmed: false,
+            resume_offer: None,
+            task_state: Arc::new(Mutex::new(TaskState::Idle)),
+            progress: Arc::default(),
+        }
+    }
+
+    fn tolerances(&self) -> Result<ToleranceInputs, String> {
+        let parse = |input: &str, label: &str| {
+            parse_optional_f64(input).map_err(|err| format!("{} invalida: {}", label, err))
+        };
+        Ok(ToleranceInputs {
+            tolerance: parse(&self.tolerance_input, "Tolerancia")?,
+            eps_rel: parse(&self.eps_rel_input, "Tolerancia relativa")?,
+            prim_inf: parse(&self.prim_inf_input, "Tolerancia de infactibilidad primal")?,
+            dual_inf: parse(&self.dual_inf_input, "Tolerancia de infactibilidad dual")?,
+        })
+    }
+
+    fn duplicate(&self, id: u64) -> Self {
+        Self {
+            id,
+            problem_path: self.problem_path.clone(),
+            problem_input: self.problem_input.clone(),
+            output_path: None,
+            output_input: String::new(),
+            method: self.method,
+            tolerance_input: self.tolerance_input.clone(),
+            eps_rel_input: self.eps_rel_input.clone(),
+            prim_inf_input: self.prim_inf_input.clone(),
+            dual_inf_input: self.dual_inf_input.clone(),
+            max_iters_input: self.max_iters_input.clone(),
+            time_limit_input: self.time_limit_input.clone(),
+            write_solution: false,
+            log_json: self.log_json,
+            problem_text: self.problem_text.clone(),
+            diagnostic: self.diagnostic.clone(),
+            memory_warning: None,
+            memory_confirmed: false,
+            resume_offer: None,
+            task_state: Arc::new(Mutex::new(TaskState::Idle)),
+            progress: Arc::default(),
+        }
+    }
+
+    fn title(&self) -> String {
+        self.problem_path
+            .as_ref()
+            .and_then(|path| path.file_name())
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| format!("Problema {}", self.id))
+    }
+
+    fn state(&self) -> TaskState {
+        self.task_state.lock().expect("task state poisoned").clone()
+    }
+
+    fn is_busy(&self) -> bool {
+        matches!(
+            *self.task_state.lock().expect("task state poisoned"),
+            TaskState::Running
+        )
+    }
+}
+
+struct CvxrsApp {
+    tabs: Vec<ProblemTab>,
+    active_tab: usize,
+    next_tab_id: u64,
     last_problem_dir: Option<PathBuf>,
     last_output_dir: Option<PathBuf>,
     banner: Option<BannerMessage>,
-    task_state: Arc<Mutex<TaskState>>,
     gemini_image_path: Option<PathBuf>,
     gemini_image_input: String,
     gemini_last_image_dir: Option<PathBuf>,
     gemini_state: Arc<Mutex<GeminiState>>,
     gemini_last_export_dir: Option<PathBuf>,
+    templates: TemplateGallery,
+    panels: PanelRegistry,
 }
 
 impl CvxrsApp {
@@ -490,17 +647,10 @@ impl CvxrsApp {
 
         let default_dir = env::current_dir().ok();
 
-        Self {
-            problem_path: None,
-            problem_input: String::new(),
-            output_path: None,
-            output_input: String::new(),
-            method: MethodChoice::Admm,
-            tolerance_input: String::new(),
-            max_iters_input: String::new(),
-            time_limit_input: String::new(),
-            write_solution: false,
-            log_json: false,
+        let mut app = Self {
+            tabs: vec![ProblemTab::new(1)],
+            active_tab: 0,
+            next_tab_id: 2,
             last_problem_dir: default_dir.clone(),
             last_output_dir: default_dir.clone(),
             banner: Some(BannerMessage {
@@ -508,20 +658,76 @@ impl CvxrsApp {
                     .into(),
                 kind: BannerKind::Info,
             }),
-            task_state: Arc::new(Mutex::new(TaskState::Idle)),
             gemini_image_path: None,
             gemini_image_input: String::new(),
             gemini_last_image_dir: default_dir.clone(),
             gemini_state: Arc::new(Mutex::new(GeminiState::Idle)),
             gemini_last_export_dir: default_dir,
+            templates: TemplateGallery::default(),
+            panels: PanelRegistry::with_builtin(),
+        };
+        if let Some(unfinished) = autosave::latest() {
+            app.on_problem_selected(unfinished.problem_path.clone());
+            app.set_banner(
+                BannerKind::Info,
+                format!(
+                    "Se encontro una solucion sin terminar de {:?} (iteracion {}). Puedes reanudarla desde la seccion del problema.",
+                    unfinished.problem_path.file_name().unwrap_or_default(),
+                    unfinished.iteration
+                ),
+            );
         }
+        app
+    }
+
+    fn tab(&self) -> &ProblemTab {
+        &self.tabs[self.active_tab]
+    }
+
+    fn tab_mut(&mut self) -> &mut ProblemTab {
+        &mut self.tabs[self.active_tab]
     }
 
     fn is_busy(&self) -> bool {
-        matches!(
-            *self.task_state.lock().expect("task state poisoned"),
-            TaskState::Running
-        )
+        self.tab().is_busy()
+    }
+
+    fn open_tab(&mut self, tab: ProblemTab) {
+        self.next_tab_id = self.next_tab_id.max(tab.id + 1);
+        self.tabs.push(tab);
+        self.active_tab = self.tabs.len() - 1;
+    }
+
+    fn new_tab(&mut self) {
+        let tab = ProblemTab::new(self.next_tab_id);
+        self.open_tab(tab);
+    }
+
+    fn duplicate_tab(&mut self) {
+        let tab = self.tab().duplicate(self.next_tab_id);
+        self.open_tab(tab);
+        self.set_banner(
+            BannerKind::Info,
+            "Pestana duplicada: ajusta el modelo u opciones y compara los resultados.",
+        );
+    }
+
+    fn close_tab(&mut self, index: usize) {
+        if self.tabs[index].is_busy() {
+            self.set_banner(
+                BannerKind::Error,
+                "Espera a que termine la solucion antes de cerrar esta pestana.",
+            );
+            return;
+        }
+        self.tabs.remove(index);
+        if self.tabs.is_empty() {
+            self.tabs.push(ProblemTab::new(self.next_tab_id));
+            self.next_tab_id += 1;
+        }
+        if self.active_tab > index || self.active_tab >= self.tabs.len() {
+            self.active_tab = self.active_tab.saturating_sub(1);
+        }
     }
 
     fn is_gemini_busy(&self) -> bool {
@@ -531,18 +737,19 @@ impl CvxrsApp {
         )
     }
 
-    fn start_solve(&mut self, ctx: egui::Context) {
+    fn start_solve(&mut self, ctx: egui::Context, resume: bool) {
         if self.is_busy() {
             return;
         }
 
-        if self.problem_path.is_none() {
-            if let Some(candidate) = Self::path_from_input(&self.problem_input) {
-                self.problem_path = Some(candidate);
+        if self.tab().problem_path.is_none() {
+            if let Some(candidate) = Self::path_from_input(&self.tab().problem_input) {
+                self.tab_mut().problem_path = Some(candidate);
+                self.reload_problem_text();
             }
         }
 
-        let problem_path = match self.problem_path.clone() {
+        let problem_path = match self.tab().problem_path.clone() {
             Some(path) => path,
             None => {
                 self.set_failure("Selecciona un archivo de problema antes de resolver.");
@@ -551,16 +758,45 @@ impl CvxrsApp {
             }
         };
 
-        let tolerance = match parse_optional_f64(&self.tolerance_input) {
+        let problem = match self.check_problem_file(&problem_path) {
+            Ok(problem) => problem,
+            Err(message) => {
+                self.set_failure(message);
+                ctx.request_repaint();
+                return;
+            }
+        };
+
+        if let Some(estimate) = problem.and_then(|problem| self.memory_estimate(&problem)) {
+            let tab = self.tab_mut();
+            if estimate.exceeds(MEMORY_WARNING_BYTES) && !tab.memory_confirmed {
+                tab.memory_warning = Some(estimate);
+                self.set_banner(
+                    BannerKind::Error,
+                    format!(
+                        "El problema necesitaria unos {:.0} MB de memoria. Confirma antes de resolver.",
+                        estimate.megabytes()
+                    ),
+                );
+                ctx.request_repaint();
+                return;
+            }
+        }
+        let tab = self.tab_mut();
+        tab.memory_warning = None;
+        tab.memory_confirmed = false;
+        tab.resume_offer = None;
+
+        let tolerances = match self.tab().tolerances() {
             Ok(value) => value,
             Err(err) => {
-                self.set_failure(format!("Tolerancia invalida: {}", err));
+                self.set_failure(err);
                 ctx.request_repaint();
                 return;
             }
         };
 
-        let max_iters = match parse_optional_usize(&self.max_iters_input) {
+        let max_iters = match parse_optional_usize(&self.tab().max_iters_input) {
             Ok(value) => value,
             Err(err) => {
                 self.set_failure(format!("Iteraciones maximas invalidas: {}", err));
@@ -569,7 +805,7 @@ impl CvxrsApp {
             }
         };
 
-        let time_limit = match parse_optional_u64(&self.time_limit_input) {
+        let time_limit = match parse_optional_u64(&self.tab().time_limit_input) {
             Ok(value) => value,
             Err(err) => {
                 self.set_failure(format!("Limite de tiempo invalido: {}", err));
@@ -578,14 +814,14 @@ impl CvxrsApp {
             }
         };
 
-        let output_path = if self.write_solution {
-            if self.output_path.is_none() {
-                let trimmed = self.output_input.trim();
+        let output_path = if self.tab().write_solution {
+            if self.tab().output_path.is_none() {
+                let trimmed = self.tab().output_input.trim().to_owned();
                 if !trimmed.is_empty() {
-                    self.output_path = Some(PathBuf::from(trimmed));
+                    self.tab_mut().output_path = Some(PathBuf::from(trimmed));
                 }
             }
-            match self.output_path.clone() {
+            match self.tab().output_path.clone() {
                 Some(path) => Some(path),
                 None => {
                     self.set_failure("Selecciona un archivo de salida para guardar la solucion.");
@@ -598,7 +834,7 @@ impl CvxrsApp {
         };
 
         {
-            let mut state = self.task_state.lock().expect("task state poisoned");
+            let mut state = self.tab().task_state.lock().expect("task state poisoned");
             *state = TaskState::Running;
         }
 
@@ -607,14 +843,18 @@ impl CvxrsApp {
             format!(
                 "Resolviendo {:?} con el metodo {}...",
                 problem_path.file_name().unwrap_or_default(),
-                self.method.short_name()
+                self.tab().method.short_name()
             ),
         );
         ctx.request_repaint();
 
-        let task_state = self.task_state.clone();
-        let method = self.method;
-        let log_json = self.log_json;
+        let task_state = self.tab().task_state.clone();
+        let progress = self.tab().progress.clone();
+        progress.iterations.store(0, Ordering::Relaxed);
+        progress.stop.store(false, Ordering::Relaxed);
+        let method = self.tab().method;
+        let log_json = self.tab().log_json;
+        let options = solve_options(tolerances, max_iters, time_limit);
         let problem_path_clone = problem_path.clone();
         let output_path_clone = output_path.clone();
 
@@ -622,22 +862,25 @@ impl CvxrsApp {
             let result = solve_problem(
                 method,
                 problem_path_clone.clone(),
-                tolerance,
-                max_iters,
-                time_limit,
+                options,
                 output_path_clone.clone(),
                 log_json,
+                resume,
+                progress,
             );
 
             let mut state = task_state.lock().expect("task state poisoned");
             *state = match result {
-                Ok((solution, solution_json)) => TaskState::Success(SolveSummary {
-                    method,
-                    problem_path: problem_path_clone,
-                    output_path: output_path_clone,
-                    solution,
-                    solution_json,
-                }),
+                Ok((solution, solution_json, groups)) => {
+                    TaskState::Success(Box::new(SolveSummary {
+                        method,
+                        problem_path: problem_path_clone,
+                        output_path: output_path_clone,
+                        solution,
+                        solution_json,
+                        groups,
+                    }))
+                }
                 Err(err) => TaskState::Failure(err.to_string()),
             };
             drop(state);
@@ -701,7 +944,7 @@ impl CvxrsApp {
     fn set_failure(&mut self, message: impl Into<String>) {
         let message = message.into();
         {
-            let mut state = self.task_state.lock().expect("task state poisoned");
+            let mut state = self.tab().task_state.lock().expect("task state poisoned");
             *state = TaskState::Failure(message.clone());
         }
         self.set_banner(BannerKind::Error, message);
@@ -743,9 +986,17 @@ impl CvxrsApp {
                 .filter_map(|file| file.path.clone())
                 .collect()
         });
-        if let Some(path) = dropped.into_iter().next() {
+        let count = dropped.len();
+        for path in dropped {
+            if self.tab().problem_path.is_some() || self.is_busy() {
+                self.new_tab();
+            }
             self.on_problem_selected(path);
-            self.set_success("Problema cargado desde un archivo arrastrado.");
+        }
+        match count {
+            0 => {}
+            1 => self.set_success("Problema cargado desde un archivo arrastrado."),
+            n => self.set_success(format!("{} problemas abiertos en pestanas nuevas.", n)),
         }
     }
 
@@ -756,7 +1007,10 @@ impl CvxrsApp {
         }
         dialog
             .add_filter("Problemas JSON", &["json", "JSON"])
-            .set_title("Selecciona un problema en formato JSON")
+            .add_filter("Hojas de calculo", &["csv", "xlsx", "xlsm", "xls", "ods"])
+            .set_title(
+                "Selecciona un problema (JSON o variables y restricciones en hoja de calculo)",
+            )
             .pick_file()
     }
 
@@ -805,9 +1059,129 @@ impl CvxrsApp {
 
     fn on_problem_selected(&mut self, path: PathBuf) {
         if path.exists() {
-            self.problem_input = path.display().to_string();
-            self.problem_path = Some(path.clone());
+            self.tab_mut().problem_input = path.display().to_string();
+            self.tab_mut().problem_path = Some(path.clone());
             self.last_problem_dir = path.parent().map(|p| p.to_path_buf());
+            self.reload_problem_text();
+            self.tab_mut().resume_offer = autosave::find(&path);
+        }
+    }
+
+    fn memory_estimate(&self, problem: &JsonProblem) -> Option<MemoryEstimate> {
+        let solver = Solver::<Scalar>::new().method(self.tab().method.to_method());
+        match problem {
+            JsonProblem::Qp { problem } => solver.estimate_qp(problem).ok(),
+            JsonProblem::Lp { problem } => solver.estimate_lp(problem).ok(),
+        }
+    }
+
+    fn check_problem_file(&mut self, path: &Path) -> Result<Option<JsonProblem>, String> {
+        if is_tabular_path(path) {
+            return read_tabular_problem(path)
+                .map(Some)
+                .map_err(|err| format!("No se pudo importar la hoja de calculo: {:#}", err));
+        }
+        if !is_json_path(path) {
+            return Ok(None);
+        }
+        let contents = fs::read_to_string(path)
+            .map_err(|err| format!("No se pudo leer {}: {}", path.display(), err))?;
+        let tab = self.tab_mut();
+        if tab.problem_text != contents {
+            return Err(String::from(
+                "Hay cambios sin guardar en el editor JSON. Guardalos o descartalos antes de resolver.",
+            ));
+        }
+        match parse_json_problem(&contents) {
+            Ok(problem) => {
+                tab.diagnostic = None;
+                Ok(Some(problem))
+            }
+            Err(diagnostic) => {
+                let message = format!(
+                    "El problema tiene un error en la linea {}, columna {} ({}). Revisa el editor JSON.",
+                    diagnostic.line, diagnostic.column, diagnostic.message
+                );
+                tab.diagnostic = Some(diagnostic);
+                Err(message)
+            }
+        }
+    }
+
+    fn reload_problem_text(&mut self) {
+        let tab = self.tab_mut();
+        tab.memory_warning = None;
+        tab.memory_confirmed = false;
+        let Some(path) = tab.problem_path.clone() else {
+            return;
+        };
+        if !is_json_path(&path) {
+            tab.problem_text.clear();
+            tab.diagnostic = None;
+            return;
+        }
+        match fs::read_to_string(&path) {
+            Ok(contents) => {
+                tab.diagnostic = parse_json_problem(&contents).err();
+                tab.problem_text = contents;
+            }
+            Err(_) => {
+                tab.problem_text.clear();
+                tab.diagnostic = None;
+            }
+        }
+    }
+
+    fn validate_problem_text(&mut self) {
+        let tab = self.tab_mut();
+        match parse_json_problem_with(&tab.problem_text, &ParseOptions::lenient()) {
+            Ok((_, fixes)) => {
+                tab.diagnostic = None;
+                if fixes.is_empty() {
+                    self.set_success("El JSON es valido.");
+                } else {
+                    let details: Vec<String> = fixes.iter().map(ToString::to_string).collect();
+                    self.set_banner(
+                        BannerKind::Info,
+                        format!(
+                            "El JSON es valido; al cargarlo se aplicaran {} correcciones: {}",
+                            fixes.len(),
+                            details.join("; ")
+                        ),
+                    );
+                }
+            }
+            Err(diagnostic) => {
+                tab.diagnostic = Some(diagnostic.clone());
+                self.set_banner(
+                    BannerKind::Error,
+                    format!(
+                        "El JSON tiene un error en la linea {}, columna {}.",
+                        diagnostic.line, diagnostic.column
+                    ),
+                );
+            }
+        }
+    }
+
+    fn save_problem_text(&mut self) {
+        let Some(path) = self.tab().problem_path.clone() else {
+            self.set_failure("Selecciona un archivo de problema antes de guardar.");
+            return;
+        };
+        if let Err(err) = fs::write(&path, &self.tab().problem_text) {
+            self.set_failure(format!("No se pudo guardar {}: {}", path.display(), err));
+            return;
+        }
+        let tab = self.tab_mut();
+        tab.diagnostic = parse_json_problem(&tab.problem_text).err();
+        if self.tab().diagnostic.is_some() {
+            self.set_banner(
+                BannerKind::Error,
+                "Cambios guardados, pero el JSON todavia tiene errores.",
+            );
+        } else {
+            self.set_success(format!("Cambios guardados en {}.", path.display()));
         }
     }
 
@@ -817,8 +1191,8 @@ impl CvxrsApp {
                 self.last_output_dir = Some(parent.to_path_buf());
             }
         }
-        self.output_input = path.display().to_string();
-        self.output_path = Some(path);
+        self.tab_mut().output_input = path.display().to_string();
+        self.tab_mut().output_path = Some(path);
     }
 
     fn on_gemini_image_selected(&mut self, path: PathBuf) {
@@ -829,6 +1203,30 @@ impl CvxrsApp {
         }
     }
 
+    fn use_captured_image(&mut self, capture: Result<PathBuf>, source: &str) {
+        match capture {
+            Ok(path) => {
+                self.gemini_image_input = path.display().to_string();
+                self.gemini_image_path = Some(path);
+                self.set_success(format!(
+                    "Imagen tomada desde {}. Pulsa \"Convertir a JSON\" para analizarla.",
+                    source
+                ));
+            }
+            Err(err) => self.set_banner(BannerKind::Error, err.to_string()),
+        }
+    }
+
+    fn paste_gemini_image(&mut self) {
+        let capture = capture::clipboard_image(&env::temp_dir().join("cvxrs-studio"));
+        self.use_captured_image(capture, "el portapapeles");
+    }
+
+    fn capture_gemini_image(&mut self) {
+        let capture = capture::camera_frame(&env::temp_dir().join("cvxrs-studio"));
+        self.use_captured_image(capture, "la camara");
+    }
+
     fn save_gemini_json(&mut self, json: &str) {
         let mut dialog = FileDialog::new()
             .add_filter("JSON", &["json", "JSON"])
@@ -856,12 +1254,35 @@ impl CvxrsApp {
         }
     }
 
+    fn export_latex_report(&mut self, summary: &SolveSummary) {
+        let report = match latex_report(summary) {
+            Ok(report) => report,
+            Err(err) => {
+                self.set_failure(format!("No se pudo generar el informe: {}", err));
+                return;
+            }
+        };
+        let mut dialog = FileDialog::new()
+            .add_filter("LaTeX", &["tex"])
+            .set_title("Guardar informe LaTeX")
+            .set_file_name("informe.tex");
+        if let Some(dir) = &self.last_output_dir {
+            dialog = dialog.set_directory(dir);
+        }
+        if let Some(path) = dialog.save_file() {
+            match fs::write(&path, report) {
+                Ok(_) => self.set_success(format!("Informe guardado en {}", path.display())),
+                Err(err) => self.set_failure(format!("No se pudo guardar el informe: {}", err)),
+            }
+        }
+    }
+
     fn load_example_problem(&mut self) {
         match self.write_example_problem() {
             Ok(path) => {
                 self.on_problem_selected(path.clone());
                 self.set_success("Ejemplo de prueba listo. Puedes resolverlo de inmediato.");
-                if let Ok(mut state) = self.task_state.lock() {
+                if let Ok(mut state) = self.tab().task_state.lock() {
                     if !matches!(*state, TaskState::Running) {
                         *state = TaskState::Idle;
                     }
@@ -871,6 +1292,34 @@ impl CvxrsApp {
         }
     }
 
+    fn load_template_problem(&mut self) {
+        let kind = self.templates.selected;
+        match self.write_template_problem(kind) {
+            Ok(path) => {
+                self.on_problem_selected(path);
+                self.set_success(format!(
+                    "Plantilla \"{}\" generada. Revisa las opciones y pulsa Resolver.",
+                    kind.display_name()
+                ));
+                if let Ok(mut state) = self.tab().task_state.lock() {
+                    if !matches!(*state, TaskState::Running) {
+                        *state = TaskState::Idle;
+                    }
+                }
+            }
+            Err(err) => self.set_failure(format!("No se pudo generar la plantilla: {}", err)),
+        }
+    }
+
+    fn write_template_problem(&mut self, kind: TemplateKind) -> Result<PathBuf> {
+        let problem = self.templates.build_selected()?;
+        let dir = env::temp_dir().join("cvxrs-studio");
+        fs::create_dir_all(&dir)?;
+        let path = dir.join(format!("{}.json", kind.file_stem()));
+        write_json_problem(&path, &problem)?;
+        Ok(path)
+    }
+
     fn write_example_problem(&self) -> Result<PathBuf> {
         serde_json::from_str::<JsonProblem>(SAMPLE_QP_JSON)
             .map_err(|err| anyhow!("Ejemplo interno invalido: {}", err))?;
@@ -923,6 +1372,65 @@ impl CvxrsApp {
         }
     }
 
+    fn render_tab_bar(&mut self, ui: &mut egui::Ui) {
+        let mut select = None;
+        let mut close = None;
+        egui::Frame::none()
+            .fill(Palette::top_panel())
+            .stroke(Stroke::new(1.0, Palette::border_soft()))
+            .rounding(10.0)
+            .inner_margin(Margin::symmetric(12.0, 8.0))
+            .show(ui, |ui| {
+                ui.set_width(ui.available_width());
+                ui.horizontal_wrapped(|ui| {
+                    for (index, tab) in self.tabs.iter().enumerate() {
+                        let marker = match tab.state() {
+                            TaskState::Running => " (resolviendo)",
+                            TaskState::Failure(_) => " (error)",
+                            _ => "",
+                        };
+                        let active = index == self.active_tab;
+                        let label =
+                            RichText::new(format!("{}{}", tab.title(), marker)).color(if active {
+                                Palette::accent_gold()
+                            } else {
+                                Palette::text_secondary()
+                            });
+                        if ui.selectable_label(active, label).clicked() {
+                            select = Some(index);
+                        }
+                        if ui
+                            .small_button("x")
+                            .on_hover_text("Cerrar pestana")
+                            .clicked()
+                        {
+                            close = Some(index);
+                        }
+                        ui.separator();
+                    }
+                    if ui
+                        .button(RichText::new("+ Nueva pestana").color(Palette::text_primary()))
+                        .clicked()
+                    {
+                        self.new_tab();
+                    }
+                    if ui
+                        .button(RichText::new("Duplicar").color(Palette::text_primary()))
+                        .on_hover_text("Copia el problema y las opciones para comparar variantes")
+                        .clicked()
+                    {
+                        self.duplicate_tab();
+                    }
+                });
+            });
+        if let Some(index) = select {
+            self.active_tab = index;
+        }
+        if let Some(index) = close {
+            self.close_tab(index);
+        }
+    }
+
     fn render_problem_section(&mut self, ctx: &egui::Context, ui: &mut egui::Ui, busy: bool) {
         section_card(ui, SectionStyle::problem(), |ui| {
             ui.vertical(|ui| {
@@ -939,10 +1447,10 @@ impl CvxrsApp {
                 );
                 ui.add_space(10.0);
 
-                let display_text = if self.problem_input.trim().is_empty() {
+                let display_text = if self.tab().problem_input.trim().is_empty() {
                     String::from("Selecciona un archivo JSON...")
                     } else {
-                        self.problem_input.clone()
+                        self.tab().problem_input.clone()
                     };
 
                     let mut open_problem_dialog = false;
@@ -1004,22 +1512,24 @@ impl CvxrsApp {
                     ui.collapsing("Editar ruta manualmente", |ui| {
                         if ui
                             .add(
-                                egui::TextEdit::singleline(&mut self.problem_input)
+                                egui::TextEdit::singleline(&mut self.tab_mut().problem_input)
                                     .hint_text("Ej: C:\\datos\\mi_problema.json")
                                     .desired_width(f32::INFINITY),
                             )
                             .changed()
                         {
-                            self.problem_path = Self::path_from_input(&self.problem_input);
+                            let tab = self.tab_mut();
+                            tab.problem_path = Self::path_from_input(&tab.problem_input);
+                            self.reload_problem_text();
                         }
                     });
 
-                    if let Some(path) = &self.problem_path {
+                    if let Some(path) = &self.tab().problem_path {
                         ui.label(
                             RichText::new(format!("Seleccionado: {}", path.display()))
                                 .color(Palette::text_secondary()),
                         );
-                    } else if !self.problem_input.trim().is_empty() {
+                    } else if !self.tab().problem_input.trim().is_empty() {
                         ui.colored_label(
                             Palette::status_error(),
                             "No encontramos ese archivo. Revisa la ruta o usa Examinar.",
@@ -1035,11 +1545,11 @@ impl CvxrsApp {
 
                     egui::ComboBox::from_id_source("method_combo")
                         .width(260.0)
-                        .selected_text(self.method.display_name())
+                        .selected_text(self.tab().method.display_name())
                         .show_ui(ui, |combo| {
                             for option in MethodChoice::all() {
                                 combo.selectable_value(
-                                    &mut self.method,
+                                    &mut self.tab_mut().method,
                                     option,
                                     option.display_name(),
                                 );
@@ -1047,9 +1557,21 @@ impl CvxrsApp {
                         });
 
                     ui.label(
-                        RichText::new(match self.method {
+                        RichText::new(match self.tab().method {
                             MethodChoice::Admm => "ADMM: estable y admite warm-start.",
                             MethodChoice::Ipm => "IPM: rapido en problemas bien condicionados.",
+                            MethodChoice::ActiveSet => {
+                                "Conjunto activo: solucion exacta en QPs densos pequenos."
+                            }
+                            MethodChoice::Simplex => {
+                                "Simplex: solucion en un vertice con base exacta (solo LP)."
+                            }
+                            MethodChoice::Pdhg => {
+                                "PDHG: sin factorizaciones, para LPs muy grandes."
+                            }
+                            MethodChoice::Auto => {
+                                "Auto: elige el metodo segun el problema y recurre a ADMM si falla."
+                            }
                         })
                         .size(14.0)
                         .color(Palette::text_muted()),
@@ -1060,39 +1582,64 @@ impl CvxrsApp {
                         columns[0].vertical(|ui| {
                             ui.label("Tolerancia");
                             ui.add(
-                                egui::TextEdit::singleline(&mut self.tolerance_input)
+                                egui::TextEdit::singleline(&mut self.tab_mut().tolerance_input)
                                     .hint_text("Ej: 1e-6"),
                             );
                         });
                         columns[1].vertical(|ui| {
                             ui.label("Iteraciones maximas");
                             ui.add(
-                                egui::TextEdit::singleline(&mut self.max_iters_input)
+                                egui::TextEdit::singleline(&mut self.tab_mut().max_iters_input)
                                     .hint_text("Ej: 1000"),
                             );
                         });
                         columns[2].vertical(|ui| {
                             ui.label("Tiempo max (s)");
                             ui.add(
-                                egui::TextEdit::singleline(&mut self.time_limit_input)
+                                egui::TextEdit::singleline(&mut self.tab_mut().time_limit_input)
                                     .hint_text("Ej: 60"),
                             );
                         });
                     });
 
+                    ui.add_space(8.0);
+                    ui.columns(3, |columns| {
+                        columns[0].vertical(|ui| {
+                            ui.label("Tolerancia relativa");
+                            ui.add(
+                                egui::TextEdit::singleline(&mut self.tab_mut().eps_rel_input)
+                                    .hint_text("Por defecto, la tolerancia"),
+                            );
+                        });
+                        columns[1].vertical(|ui| {
+                            ui.label("Infactibilidad primal");
+                            ui.add(
+                                egui::TextEdit::singleline(&mut self.tab_mut().prim_inf_input)
+                                    .hint_text("Ej: 1e-6"),
+                            );
+                        });
+                        columns[2].vertical(|ui| {
+                            ui.label("Infactibilidad dual");
+                            ui.add(
+                                egui::TextEdit::singleline(&mut self.tab_mut().dual_inf_input)
+                                    .hint_text("Ej: 1e-6"),
+                            );
+                        });
+                    });
+
                     ui.add_space(12.0);
                     ui.horizontal(|ui| {
-                        ui.checkbox(&mut self.log_json, "Mostrar solucion como JSON");
-                        ui.checkbox(&mut self.write_solution, "Guardar solucion en archivo");
+                        ui.checkbox(&mut self.tab_mut().log_json, "Mostrar solucion como JSON");
+                        ui.checkbox(&mut self.tab_mut().write_solution, "Guardar solucion en archivo");
                     });
 
-                    if self.write_solution {
+                    if self.tab().write_solution {
                         ui.add_space(6.0);
 
-                        let output_display = if self.output_input.trim().is_empty() {
+                        let output_display = if self.tab().output_input.trim().is_empty() {
                             String::from("Selecciona un archivo para guardar...")
                         } else {
-                            self.output_input.clone()
+                            self.tab().output_input.clone()
                         };
 
                         let mut open_output_dialog = false;
@@ -1132,17 +1679,18 @@ impl CvxrsApp {
                         ui.collapsing("Editar ruta de salida manualmente", |ui| {
                             if ui
                                 .add(
-                                    egui::TextEdit::singleline(&mut self.output_input)
+                                    egui::TextEdit::singleline(&mut self.tab_mut().output_input)
                                         .hint_text("Ej: C:\\datos\\solucion.json")
                                         .desired_width(f32::INFINITY),
                                 )
                                 .changed()
                             {
-                                let trimmed = self.output_input.trim();
+                                let tab = self.tab_mut();
+                                let trimmed = tab.output_input.trim();
                                 if trimmed.is_empty() {
-                                    self.output_path = None;
+                                    tab.output_path = None;
                                 } else {
-                                    self.output_path = Some(PathBuf::from(trimmed));
+                                    tab.output_path = Some(PathBuf::from(trimmed));
                                 }
                             }
                         });
@@ -1169,16 +1717,71 @@ impl CvxrsApp {
                     .rounding(12.0);
 
                     if ui.add_enabled(!busy, button).clicked() {
-                        self.start_solve(ctx.clone());
+                        self.start_solve(ctx.clone(), false);
                     }
 
-                    if busy {
-                        ui.add_space(6.0);
-                        ui.spinner();
+                    if let Some(unfinished) = self.tab().resume_offer.clone().filter(|_| !busy) {
+                        ui.add_space(10.0);
                         ui.label(
-                            RichText::new("Ejecutando solver...")
-                                .color(Palette::text_secondary()),
+                            RichText::new(format!(
+                                "Hay una solucion sin terminar guardada en la iteracion {}.",
+                                unfinished.iteration
+                            ))
+                            .color(Palette::text_secondary()),
                         );
+                        ui.horizontal(|ui| {
+                            if ui.button("Reanudar solucion anterior").clicked() {
+                                self.start_solve(ctx.clone(), true);
+                            }
+                            if ui.button("Descartar").clicked() {
+                                autosave::discard(unfinished.fingerprint);
+                                self.tab_mut().resume_offer = None;
+                            }
+                        });
+                    }
+
+                    if let Some(estimate) = self.tab().memory_warning {
+                        ui.add_space(10.0);
+                        ui.colored_label(
+                            Palette::status_error(),
+                            format!(
+                                "El backend denso reservaria unos {:.0} MB ({} variables, {} filas de restricciones). \
+                                 Esto puede congelar la aplicacion o agotar la memoria; considera reducir o dividir el problema.",
+                                estimate.megabytes(),
+                                estimate.nvars,
+                                estimate.nrows
+                            ),
+                        );
+                        ui.horizontal(|ui| {
+                            if ui.button("Resolver de todos modos").clicked() {
+                                let tab = self.tab_mut();
+                                tab.memory_warning = None;
+                                tab.memory_confirmed = true;
+                                self.start_solve(ctx.clone(), false);
+                            }
+                            if ui.button("Cancelar").clicked() {
+                                self.tab_mut().memory_warning = None;
+                                self.clear_banner();
+                            }
+                        });
+                    }
+
+                    if busy {
+                        ui.add_space(6.0);
+                        let progress = self.tab().progress.clone();
+                        ui.horizontal(|ui| {
+                            ui.spinner();
+                            let iterations = progress.iterations.load(Ordering::Relaxed);
+                            let text = if iterations == 0 {
+                                "Ejecutando solver...".to_owned()
+                            } else {
+                                format!("Ejecutando solver... iteracion {}", iterations)
+                            };
+                            ui.label(RichText::new(text).color(Palette::text_secondary()));
+                            if ui.button("Detener").clicked() {
+                                progress.stop.store(true, Ordering::Relaxed);
+                            }
+                        });
                     }
                 });
         });
@@ -1254,6 +1857,39 @@ impl CvxrsApp {
                         self.browse_gemini_image();
                     }
 
+                    ui.add_space(8.0);
+                    ui.horizontal_wrapped(|ui| {
+                        if ui
+                            .add_enabled(
+                                !busy,
+                                egui::Button::new(
+                                    RichText::new("Pegar imagen del portapapeles")
+                                        .color(Palette::accent_azure()),
+                                )
+                                .stroke(Stroke::new(1.0, Palette::border_soft()))
+                                .rounding(10.0),
+                            )
+                            .clicked()
+                        {
+                            self.paste_gemini_image();
+                        }
+                        if capture::CAMERA_AVAILABLE
+                            && ui
+                                .add_enabled(
+                                    !busy,
+                                    egui::Button::new(
+                                        RichText::new("Tomar foto con la camara")
+                                            .color(Palette::accent_azure()),
+                                    )
+                                    .stroke(Stroke::new(1.0, Palette::border_soft()))
+                                    .rounding(10.0),
+                                )
+                                .clicked()
+                        {
+                            self.capture_gemini_image();
+                        }
+                    });
+
                     ui.add_space(10.0);
                     ui.with_layout(egui::Layout::left_to_right(Align::Center), |ui| {
                         ui.add(
@@ -1386,12 +2022,180 @@ impl CvxrsApp {
         });
     }
 
+    fn render_json_editor(&mut self, ui: &mut egui::Ui, busy: bool) {
+        section_card(ui, SectionStyle::editor(), |ui| {
+            ui.vertical(|ui| {
+                ui.heading(
+                    RichText::new("Editor JSON")
+                        .color(Palette::text_primary())
+                        .size(21.0),
+                );
+                match &self.tab().diagnostic {
+                    Some(diagnostic) => {
+                        ui.colored_label(
+                            Palette::status_error(),
+                            format!(
+                                "Linea {}, columna {} ({}): {}",
+                                diagnostic.line,
+                                diagnostic.column,
+                                if diagnostic.pointer.is_empty() {
+                                    "/"
+                                } else {
+                                    diagnostic.pointer.as_str()
+                                },
+                                diagnostic.message
+                            ),
+                        );
+                    }
+                    None => {
+                        ui.colored_label(Palette::status_optimal(), "El JSON es valido.");
+                    }
+                }
+                ui.add_space(8.0);
+
+                let error_at = self
+                    .tab()
+                    .diagnostic
+                    .as_ref()
+                    .map(|diagnostic| (diagnostic.line, diagnostic.column));
+                let mut layouter = |ui: &egui::Ui, text: &str, wrap_width: f32| {
+                    let mut job = highlight_json_error(text, error_at);
+                    job.wrap.max_width = wrap_width;
+                    ui.fonts(|fonts| fonts.layout_job(job))
+                };
+                egui::ScrollArea::vertical()
+                    .id_source("json_editor_scroll")
+                    .max_height(320.0)
+                    .show(ui, |ui| {
+                        ui.add_enabled(
+                            !busy,
+                            egui::TextEdit::multiline(&mut self.tab_mut().problem_text)
+                                .code_editor()
+                                .desired_rows(14)
+                                .desired_width(f32::INFINITY)
+                                .layouter(&mut layouter),
+                        );
+                    });
+
+                ui.add_space(8.0);
+                ui.horizontal_wrapped(|ui| {
+                    if ui
+                        .add_enabled(!busy, egui::Button::new("Validar"))
+                        .clicked()
+                    {
+                        self.validate_problem_text();
+                    }
+                    if ui
+                        .add_enabled(!busy, egui::Button::new("Guardar cambios"))
+                        .clicked()
+                    {
+                        self.save_problem_text();
+                    }
+                    if ui
+                        .add_enabled(!busy, egui::Button::new("Descartar cambios"))
+                        .clicked()
+                    {
+                        self.reload_problem_text();
+                    }
+                });
+            });
+        });
+    }
+
+    fn render_template_gallery(&mut self, ui: &mut egui::Ui, busy: bool) {
+        section_card(ui, SectionStyle::templates(), |ui| {
+            ui.vertical(|ui| {
+                ui.heading(
+                    RichText::new("Galeria de plantillas")
+                        .color(Palette::text_primary())
+                        .size(21.0),
+                );
+                ui.label(
+                    RichText::new(
+                        "Elige un modelo clasico, completa algunos datos y genera el problema sin escribir JSON.",
+                    )
+                    .color(Palette::text_secondary()),
+                );
+                ui.add_space(10.0);
+
+                let previous = self.templates.selected;
+                egui::ComboBox::from_id_source("template_combo")
+                    .width(300.0)
+                    .selected_text(self.templates.selected.display_name())
+                    .show_ui(ui, |combo| {
+                        for option in TemplateKind::all() {
+                            combo.selectable_value(
+                                &mut self.templates.selected,
+                                option,
+                                option.display_name(),
+                            );
+                        }
+                    });
+                if previous != self.templates.selected {
+                    tracing::info!(template = ?self.templates.selected, "template selected");
+                }
+
+                ui.label(
+                    RichText::new(self.templates.selected.description())
+                        .size(14.0)
+                        .color(Palette::text_muted()),
+                );
+
+                ui.add_space(8.0);
+                egui::Grid::new("template_fields")
+                    .num_columns(2)
+                    .spacing(egui::vec2(16.0, 10.0))
+                    .show(ui, |ui| {
+                        for field in self.templates.fields_mut() {
+                            ui.label(field.label);
+                            ui.add(
+                                egui::TextEdit::singleline(&mut field.value)
+                                    .desired_width(360.0)
+                                    .font(TextStyle::Monospace),
+                            );
+                            ui.end_row();
+                        }
+                    });
+
+                ui.add_space(12.0);
+                ui.horizontal_wrapped(|ui| {
+                    let generate = egui::Button::new(
+                        RichText::new("Generar problema")
+                            .color(Palette::accent_gold_soft())
+                            .text_style(TextStyle::Button),
+                    )
+                    .fill(Color32::from_rgba_unmultiplied(224, 192, 128, 45))
+                    .stroke(Stroke::new(1.0, Palette::accent_gold_soft()))
+                    .min_size(egui::vec2(178.0, 36.0));
+                    if ui.add_enabled(!busy, generate).clicked() {
+                        self.load_template_problem();
+                    }
+                    if ui
+                        .add(
+                            egui::Button::new(
+                                RichText::new("Restaurar valores")
+                                    .color(Palette::text_primary())
+                                    .text_style(TextStyle::Button),
+                            )
+                            .stroke(Stroke::new(1.0, Palette::border_soft()))
+                            .min_size(egui::vec2(156.0, 36.0)),
+                        )
+                        .clicked()
+                    {
+                        self.templates.reset_selected();
+                    }
+                });
+            });
+        });
+    }
+
     fn render_quick_start(&mut self, ui: &mut egui::Ui) {
         section_card(ui, SectionStyle::quick_start(), |ui| {
             ui.vertical(|ui| {
                 ui.heading(RichText::new("Guia rapida").color(Palette::text_primary()));
                 ui.label("- Exporta tu problema en JSON usando el esquema de cvxrs.");
                 ui.label("- Carga el archivo desde Examinar o arrastralo sobre la ventana.");
+                ui.label("- O genera un modelo desde la galeria de plantillas.");
                 ui.label("- Ajusta tolerancia y limites solo si lo necesitas.");
                 ui.label("- Pulsa Resolver y revisa el resumen inferior.");
 
@@ -1432,6 +2236,25 @@ impl CvxrsApp {
             }
             TaskState::Success(summary) => {
                 render_solution_summary(ui, summary);
+                ui.add_space(8.0);
+                ui.horizontal(|ui| {
+                    if ui.button("Exportar informe LaTeX").clicked() {
+                        self.export_latex_report(summary);
+                    }
+                    // Runs stopped by a limit or by the user leave their last
+                    // state autosaved.
+                    if matches!(
+                        summary.solution.status,
+                        Status::MaxIterations | Status::MaxTime | Status::Interrupted
+                    ) && ui.button("Reanudar solucion anterior").clicked()
+                    {
+                        self.start_solve(ui.ctx().clone(), true);
+                    }
+                });
+                if !self.panels.is_empty() {
+                    ui.add_space(12.0);
+                    self.panels.render_all(ui, summary);
+                }
             }
         });
     }
@@ -1441,10 +2264,6 @@ impl App for CvxrsApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut Frame) {
         self.handle_file_drops(ctx);
 
-        let state = self.task_state.lock().expect("task state poisoned").clone();
-
-        let busy = state.is_running();
-
         egui::TopBottomPanel::top("app_header")
             .frame(egui::Frame::none().fill(Palette::top_panel()))
             .show(ctx, |ui| {
@@ -1468,6 +2287,10 @@ impl App for CvxrsApp {
                     .auto_shrink([false, false])
                     .show(ui, |ui| {
                         ui.add_space(12.0);
+                        self.render_tab_bar(ui);
+                        ui.add_space(12.0);
+                        let state = self.tab().state();
+                        let busy = state.is_running();
                         if self.banner.is_some() {
                             self.render_banner(ui);
                             ui.add_space(12.0);
@@ -1476,6 +2299,12 @@ impl App for CvxrsApp {
                         ui.add_space(12.0);
                         self.render_problem_section(ctx, ui, busy);
                         ui.add_space(12.0);
+                        if self.tab().problem_path.as_deref().is_some_and(is_json_path) {
+                            self.render_json_editor(ui, busy);
+                            ui.add_space(12.0);
+                        }
+                        self.render_template_gallery(ui, busy);
+                        ui.add_space(12.0);
                         self.render_quick_start(ui);
                         ui.add_space(12.0);
                         self.render_status(ui, &state);
@@ -1541,6 +2370,100 @@ fn configure_style(ctx: &egui::Context) {
     ctx.set_style(style);
 }
 
+fn is_json_path(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.eq_ignore_ascii_case("json"))
+        .unwrap_or(false)
+}
+
+fn is_tabular_path(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| {
+            ["csv", "xlsx", "xlsm", "xls", "ods"]
+                .iter()
+                .any(|known| ext.eq_ignore_ascii_case(known))
+        })
+        .unwrap_or(false)
+}
+
+fn highlight_json_error(text: &str, error_at: Option<(usize, usize)>) -> LayoutJob {
+    let font_id = TextStyle::Monospace.resolve(&egui::Style::default());
+    let normal = TextFormat::simple(font_id.clone(), Palette::text_primary());
+    let mut job = LayoutJob::default();
+    let Some((line, column)) = error_at else {
+        job.append(text, 0.0, normal);
+        return job;
+    };
+    let line_format = TextFormat {
+        background: Palette::banner_error_bg(),
+        ..TextFormat::simple(font_id, Palette::banner_error_fg())
+    };
+    let marker_format = TextFormat {
+        underline: Stroke::new(2.0, Palette::status_error()),
+        ..line_format.clone()
+    };
+    for (index, segment) in text.split_inclusive('\n').enumerate() {
+        if index + 1 != line {
+            job.append(segment, 0.0, normal.clone());
+            continue;
+        }
+        let start = segment
+            .char_indices()
+            .nth(column.saturating_sub(1))
+            .map(|(offset, _)| offset)
+            .unwrap_or(segment.len());
+        let end = segment[start..]
+            .char_indices()
+            .nth(1)
+            .map(|(offset, _)| start + offset)
+            .unwrap_or(segment.len());
+        job.append(&segment[..start], 0.0, line_format.clone());
+        job.append(&segment[start..end], 0.0, marker_format.clone());
+        job.append(&segment[end..], 0.0, line_format.clone());
+    }
+    job
+}
+
+fn render_group_reports(ui: &mut egui::Ui, groups: &[GroupReport<Scalar>]) {
+    egui::Grid::new("group_reports")
+        .num_columns(5)
+        .striped(true)
+        .spacing([16.0, 6.0])
+        .show(ui, |ui| {
+            for header in [
+                "Grupo",
+                "Filas",
+                "Estado",
+                "Peor violacion",
+                "Contribucion dual",
+            ] {
+                ui.label(RichText::new(header).strong());
+            }
+            ui.end_row();
+            for group in groups {
+                ui.label(&group.name);
+                ui.label(group.rows.to_string());
+                if group.feasible {
+                    ui.label(RichText::new("Factible").color(Palette::status_optimal()));
+                } else {
+                    ui.label(RichText::new("Violado").color(Palette::status_error()));
+                }
+                ui.label(match group.worst_row {
+                    Some(row) => format!("{:.3e} ({:?})", group.worst_violation, row),
+                    None => String::from("-"),
+                });
+                ui.label(
+                    group
+                        .dual_contribution
+                        .map_or_else(|| String::from("-"), |value| format!("{value:.6}")),
+                );
+                ui.end_row();
+            }
+        });
+}
+
 fn render_solution_summary(ui: &mut egui::Ui, summary: &SolveSummary) {
     let solution = &summary.solution;
     let card_width = ui.available_width();
@@ -1555,7 +2478,9 @@ fn render_solution_summary(ui: &mut egui::Ui, summary: &SolveSummary) {
             ui.vertical(|ui| {
                 let status_color = match solution.status {
                     Status::Optimal => Palette::status_optimal(),
-                    Status::MaxIterations | Status::MaxTime => Palette::status_warning(),
+                    Status::MaxIterations | Status::MaxTime | Status::Interrupted => {
+                        Palette::status_warning()
+                    }
                     _ => Palette::status_error(),
                 };
                 ui.heading(
@@ -1638,6 +2563,13 @@ fn render_solution_summary(ui: &mut egui::Ui, summary: &SolveSummary) {
                             });
                     });
 
+                if !summary.groups.is_empty() {
+                    ui.add_space(12.0);
+                    egui::CollapsingHeader::new("Grupos de restricciones")
+                        .default_open(true)
+                        .show(ui, |ui| render_group_reports(ui, &summary.groups));
+                }
+
                 if let Some(json) = &summary.solution_json {
                     ui.add_space(12.0);
                     egui::CollapsingHeader::new("JSON de la solucion")
@@ -1666,25 +2598,34 @@ fn render_solution_summary(ui: &mut egui::Ui, summary: &SolveSummary) {
         });
 }
 
-fn solve_problem(
-    method: MethodChoice,
-    problem_path: PathBuf,
+// Tolerances typed in the options panel; empty fields keep the defaults. The
+// plain tolerance sets the relative one too unless that is given.
+#[derive(Clone, Copy)]
+struct ToleranceInputs {
     tolerance: Option<f64>,
+    eps_rel: Option<f64>,
+    prim_inf: Option<f64>,
+    dual_inf: Option<f64>,
+}
+
+fn solve_options(
+    tolerances: ToleranceInputs,
     max_iters: Option<usize>,
     time_limit: Option<u64>,
-    output_path: Option<PathBuf>,
-    log_json: bool,
-) -> Result<(Solution<Scalar>, Option<String>)> {
-    tracing::info!(
-        ?problem_path,
-        ?output_path,
-        ?method,
-        "starting solve from GUI"
-    );
-
+) -> SolveOptions<Scalar> {
     let mut options = SolveOptions::<Scalar>::default();
-    if let Some(tol) = tolerance {
-        options.tolerance = tol as Scalar;
+    if let Some(tol) = tolerances.tolerance {
+        options.eps_abs = tol as Scalar;
+        options.eps_rel = tol as Scalar;
+    }
+    if let Some(tol) = tolerances.eps_rel {
+        options.eps_rel = tol as Scalar;
+    }
+    if let Some(tol) = tolerances.prim_inf {
+        options.eps_prim_inf = tol as Scalar;
+    }
+    if let Some(tol) = tolerances.dual_inf {
+        options.eps_dual_inf = tol as Scalar;
     }
     if let Some(iters) = max_iters {
         options.max_iterations = iters;
@@ -1692,33 +2633,84 @@ fn solve_problem(
     if let Some(limit) = time_limit {
         options.max_time = Some(Duration::from_secs(limit));
     }
+    options
+}
 
-    let extension = problem_path
-        .extension()
-        .and_then(|ext| ext.to_str())
-        .unwrap_or_default()
-        .to_ascii_lowercase();
+// Solution, its JSON when requested, and the per-group report.
+type SolveOutput = (Solution<Scalar>, Option<String>, Vec<GroupReport<Scalar>>);
+
+fn solve_problem(
+    method: MethodChoice,
+    problem_path: PathBuf,
+    options: SolveOptions<Scalar>,
+    output_path: Option<PathBuf>,
+    log_json: bool,
+    resume: bool,
+    progress: Arc<SolveProgress>,
+) -> Result<SolveOutput> {
+    tracing::info!(
+        ?problem_path,
+        ?output_path,
+        ?method,
+        resume,
+        "starting solve from GUI"
+    );
 
+    let problem = load_problem(&problem_path)?;
+    let fingerprint = problem_fingerprint(&problem);
+    let tolerance = options.eps_abs;
     let mut solver = Solver::<Scalar>::new()
         .method(method.to_method())
-        .options(options);
-    let solution = match extension.as_str() {
-        "json" => match read_json_problem(&problem_path)? {
-            JsonProblem::Qp { problem } => solver.solve_qp(problem)?,
-            JsonProblem::Lp { problem } => solver.solve_lp(problem)?,
-        },
-        "mps" => {
-            return Err(anyhow!(
-                "El formato MPS todavía no está soportado por la interfaz gráfica."
-            ));
+        .options(options)
+        .checkpoint(
+            autosave::AUTOSAVE_INTERVAL,
+            autosave::sink(&problem_path, fingerprint),
+        )
+        .on_iteration(move |record| {
+            progress
+                .iterations
+                .store(record.iteration + 1, Ordering::Relaxed);
+            if progress.stop.load(Ordering::Relaxed) {
+                ControlFlow::Break(())
+            } else {
+                ControlFlow::Continue(())
+            }
+        });
+    if resume {
+        let saved = autosave::load(fingerprint)
+            .map_err(|_| anyhow!("No se encontro una solucion guardada para este problema."))?;
+        if saved.state.method != method.to_method() {
+            bail!(
+                "La solucion guardada se obtuvo con el metodo {:?}; seleccionalo para reanudarla.",
+                saved.state.method
+            );
         }
-        other => {
-            return Err(anyhow!(
-                "Extensión de archivo desconocida: {}. Usa JSON o MPS.",
-                other
-            ));
+        solver = solver.resume(saved.state);
+    }
+    let (solution, groups) = match problem {
+        JsonProblem::Qp { problem } => {
+            let groups = (!problem.groups.is_empty()).then(|| problem.clone());
+            let solution = solver.solve_qp(problem)?;
+            let report = groups.map_or_else(Vec::new, |problem| {
+                group_report_qp(&problem, &solution, tolerance)
+            });
+            (solution, report)
+        }
+        JsonProblem::Lp { problem } => {
+            let groups = (!problem.groups.is_empty()).then(|| problem.clone());
+            let solution = solver.solve_lp(problem)?;
+            let report = groups.map_or_else(Vec::new, |problem| {
+                group_report_lp(&problem, &solution, tolerance)
+            });
+            (solution, report)
         }
     };
+    if !matches!(
+        solution.status,
+        Status::MaxIterations | Status::MaxTime | Status::Interrupted
+    ) {
+        autosave::discard(fingerprint);
+    }
 
     if let Some(path) = &output_path {
         write_solution(path, &solution)?;
@@ -1739,7 +2731,46 @@ fn solve_problem(
         "solver finished"
     );
 
-    Ok((solution, solution_json))
+    Ok((solution, solution_json, groups))
+}
+
+fn load_problem(problem_path: &Path) -> Result<JsonProblem> {
+    let extension = problem_path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    match extension.as_str() {
+        "json" => read_json_problem(problem_path),
+        "csv" | "xlsx" | "xlsm" | "xls" | "ods" => read_tabular_problem(problem_path),
+        "mps" => Err(anyhow!(
+            "El formato MPS todavía no está soportado por la interfaz gráfica."
+        )),
+        other => Err(anyhow!(
+            "Extensión de archivo desconocida: {}. Usa JSON, CSV, Excel o MPS.",
+            other
+        )),
+    }
+}
+
+fn latex_report(summary: &SolveSummary) -> Result<String> {
+    let problem = load_problem(&summary.problem_path)?;
+    let solution = &summary.solution;
+    let mut report = String::from(
+        "\\documentclass{article}\n\\usepackage{amsmath,amssymb}\n\\begin{document}\n\n",
+    );
+    report.push_str("\\section*{Problema}\n");
+    report.push_str(&to_latex(&problem));
+    report.push_str("\n\\section*{Solucion}\n\\begin{tabular}{ll}\n");
+    report.push_str(&format!(
+        "Metodo & {} \\\\\nEstado & {:?} \\\\\nObjetivo & {:.6} \\\\\nIteraciones & {} \\\\\n",
+        summary.method.short_name(),
+        solution.status,
+        solution.objective_value,
+        solution.iterations
+    ));
+    report.push_str("\\end{tabular}\n\n\\end{document}\n");
+    Ok(report)
 }
 
 fn convert_image_with_gemini(image_path: &Path) -> Result<String> {
@@ -0,0 +1,116 @@
+use eframe::egui::{self, Color32, RichText, Stroke};
+
+use cvxrs_core::math::Scalar;
+
+use crate::{Palette, SolveSummary};
+
+pub trait ResultPanel {
+    fn name(&self) -> &str;
+    fn render(&mut self, ui: &mut egui::Ui, summary: &SolveSummary);
+}
+
+#[derive(Default)]
+pub struct PanelRegistry {
+    panels: Vec<Box<dyn ResultPanel>>,
+}
+
+impl PanelRegistry {
+    pub fn with_builtin() -> Self {
+        let mut registry = Self::default();
+        register_panels(&mut registry);
+        registry
+    }
+
+    pub fn register(&mut self, panel: impl ResultPanel + 'static) {
+        self.panels.push(Box::new(panel));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.panels.is_empty()
+    }
+
+    pub fn render_all(&mut self, ui: &mut egui::Ui, summary: &SolveSummary) {
+        for (idx, panel) in self.panels.iter_mut().enumerate() {
+            egui::CollapsingHeader::new(panel.name())
+                .id_source(("result_panel", idx))
+                .default_open(false)
+                .show(ui, |ui| panel.render(ui, summary));
+        }
+    }
+}
+
+// Custom visualizations are compiled in by registering them here.
+pub fn register_panels(registry: &mut PanelRegistry) {
+    registry.register(PrimalBarsPanel { limit: 24 });
+}
+
+struct PrimalBarsPanel {
+    limit: usize,
+}
+
+impl ResultPanel for PrimalBarsPanel {
+    fn name(&self) -> &str {
+        "Grafico de la solucion primal"
+    }
+
+    fn render(&mut self, ui: &mut egui::Ui, summary: &SolveSummary) {
+        let primal = &summary.solution.primal;
+        if primal.is_empty() {
+            ui.label("La solucion no tiene variables que graficar.");
+            return;
+        }
+        let shown = &primal[..primal.len().min(self.limit)];
+        let scale = shown
+            .iter()
+            .fold(0.0, |acc: Scalar, value| acc.max(value.abs()))
+            .max(Scalar::EPSILON);
+        let row_height = 18.0;
+        let label_width = 56.0;
+        for (idx, value) in shown.iter().enumerate() {
+            let value = *value;
+            let (rect, _) = ui.allocate_exact_size(
+                egui::vec2(ui.available_width(), row_height),
+                egui::Sense::hover(),
+            );
+            let painter = ui.painter_at(rect);
+            painter.text(
+                rect.left_center(),
+                egui::Align2::LEFT_CENTER,
+                format!("x[{}]", idx),
+                egui::FontId::monospace(12.0),
+                Palette::text_secondary(),
+            );
+            let track = egui::Rect::from_min_max(
+                egui::pos2(rect.left() + label_width, rect.top() + 3.0),
+                egui::pos2(rect.right() - 90.0, rect.bottom() - 3.0),
+            );
+            painter.rect_stroke(track, 3.0, Stroke::new(1.0, Palette::border_soft()));
+            let zero = track.center().x;
+            let end = zero + (value / scale) as f32 * track.width() / 2.0;
+            let bar = egui::Rect::from_x_y_ranges(zero.min(end)..=zero.max(end), track.y_range());
+            let color = if value >= 0.0 {
+                Palette::accent_azure()
+            } else {
+                Color32::from_rgb(214, 120, 120)
+            };
+            painter.rect_filled(bar, 3.0, color);
+            painter.text(
+                egui::pos2(rect.right(), rect.center().y),
+                egui::Align2::RIGHT_CENTER,
+                format!("{:.4}", value),
+                egui::FontId::monospace(12.0),
+                Palette::text_primary(),
+            );
+        }
+        if primal.len() > shown.len() {
+            ui.label(
+                RichText::new(format!(
+                    "Mostrando {} de {} variables.",
+                    shown.len(),
+                    primal.len()
+                ))
+                .color(Palette::text_muted()),
+            );
+        }
+    }
+}
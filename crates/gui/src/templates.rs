@@ -0,0 +1,381 @@
+use anyhow::{anyhow, bail, Result};
+use cvxrs_api::{LpBuilder, QpBuilder};
+use cvxrs_core::math::Scalar;
+use cvxrs_core::problem::{Bounds, CscMatrix};
+use cvxrs_io::JsonProblem;
+
+// JSON has no infinity, so flows are capped far above any supply instead of
+// left unbounded, as in examples/flow_lp.json.
+const FLOW_CAP: Scalar = 1e9;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TemplateKind {
+    Portfolio,
+    TransportFlow,
+    BoxQp,
+    LeastSquares,
+}
+
+impl TemplateKind {
+    pub fn all() -> [TemplateKind; 4] {
+        [
+            TemplateKind::Portfolio,
+            TemplateKind::TransportFlow,
+            TemplateKind::BoxQp,
+            TemplateKind::LeastSquares,
+        ]
+    }
+
+    pub fn display_name(self) -> &'static str {
+        match self {
+            TemplateKind::Portfolio => "Portafolio de Markowitz (QP)",
+            TemplateKind::TransportFlow => "Flujo de transporte (LP)",
+            TemplateKind::BoxQp => "QP con limites de caja",
+            TemplateKind::LeastSquares => "Minimos cuadrados",
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            TemplateKind::Portfolio => {
+                "Minimiza la varianza del portafolio con pesos en [0, 1] que suman 1 y alcanzan el retorno objetivo."
+            }
+            TemplateKind::TransportFlow => {
+                "Envia mercancia desde origenes con oferta limitada hasta destinos con demanda fija al menor costo."
+            }
+            TemplateKind::BoxQp => {
+                "Minimiza 0.5 x'Px + q'x con P diagonal y cada variable acotada entre un limite inferior y superior."
+            }
+            TemplateKind::LeastSquares => {
+                "Minimiza 0.5 ||Ax - b||^2; escribe las filas de A separadas por punto y coma."
+            }
+        }
+    }
+
+    pub fn file_stem(self) -> &'static str {
+        match self {
+            TemplateKind::Portfolio => "plantilla_portafolio",
+            TemplateKind::TransportFlow => "plantilla_transporte",
+            TemplateKind::BoxQp => "plantilla_qp_caja",
+            TemplateKind::LeastSquares => "plantilla_minimos_cuadrados",
+        }
+    }
+
+    fn default_fields(self) -> Vec<TemplateField> {
+        match self {
+            TemplateKind::Portfolio => vec![
+                TemplateField::new("Retornos esperados", "0.12, 0.10, 0.07, 0.03"),
+                TemplateField::new("Varianzas", "0.05, 0.02, 0.01, 0.005"),
+                TemplateField::new("Retorno objetivo", "0.08"),
+            ],
+            TemplateKind::TransportFlow => vec![
+                TemplateField::new("Oferta por origen", "80, 65, 75"),
+                TemplateField::new("Demanda por destino", "70, 60, 90"),
+                TemplateField::new(
+                    "Costos (filas = origenes)",
+                    "2, 3, 1.5; 2.5, 4, 3.5; 3, 2, 1",
+                ),
+            ],
+            TemplateKind::BoxQp => vec![
+                TemplateField::new("Diagonal de P", "2, 4, 6"),
+                TemplateField::new("Vector q", "-2, -5, -3"),
+                TemplateField::new("Limite inferior", "0"),
+                TemplateField::new("Limite superior", "1"),
+            ],
+            TemplateKind::LeastSquares => vec![
+                TemplateField::new("Matriz A", "1, 1; 1, 2; 1, 3; 1, 4"),
+                TemplateField::new("Vector b", "6, 5, 7, 10"),
+            ],
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct TemplateField {
+    pub label: &'static str,
+    pub value: String,
+}
+
+impl TemplateField {
+    fn new(label: &'static str, value: &str) -> Self {
+        Self {
+            label,
+            value: value.to_owned(),
+        }
+    }
+}
+
+pub struct TemplateGallery {
+    pub selected: TemplateKind,
+    forms: Vec<(TemplateKind, Vec<TemplateField>)>,
+}
+
+impl Default for TemplateGallery {
+    fn default() -> Self {
+        Self {
+            selected: TemplateKind::Portfolio,
+            forms: TemplateKind::all()
+                .into_iter()
+                .map(|kind| (kind, kind.default_fields()))
+                .collect(),
+        }
+    }
+}
+
+impl TemplateGallery {
+    pub fn fields_mut(&mut self) -> &mut [TemplateField] {
+        let selected = self.selected;
+        self.forms
+            .iter_mut()
+            .find(|(kind, _)| *kind == selected)
+            .map(|(_, fields)| fields.as_mut_slice())
+            .expect("every template kind has a form")
+    }
+
+    pub fn reset_selected(&mut self) {
+        let defaults = self.selected.default_fields();
+        self.fields_mut().clone_from_slice(&defaults);
+    }
+
+    pub fn build_selected(&mut self) -> Result<JsonProblem> {
+        let kind = self.selected;
+        let fields = self.fields_mut();
+        match kind {
+            TemplateKind::Portfolio => portfolio(
+                &parse_list(&fields[0])?,
+                &parse_list(&fields[1])?,
+                parse_scalar(&fields[2])?,
+            ),
+            TemplateKind::TransportFlow => transport_flow(
+                &parse_list(&fields[0])?,
+                &parse_list(&fields[1])?,
+                &parse_rows(&fields[2])?,
+            ),
+            TemplateKind::BoxQp => box_qp(
+                &parse_list(&fields[0])?,
+                &parse_list(&fields[1])?,
+                parse_scalar(&fields[2])?,
+                parse_scalar(&fields[3])?,
+            ),
+            TemplateKind::LeastSquares => {
+                least_squares(&parse_rows(&fields[0])?, &parse_list(&fields[1])?)
+            }
+        }
+    }
+}
+
+fn portfolio(returns: &[Scalar], variances: &[Scalar], target: Scalar) -> Result<JsonProblem> {
+    let n = returns.len();
+    if variances.len() != n {
+        bail!(
+            "Se esperaban {} varianzas (una por activo) y se recibieron {}.",
+            n,
+            variances.len()
+        );
+    }
+    if variances.iter().any(|&v| v < 0.0) {
+        bail!("Las varianzas deben ser no negativas.");
+    }
+    let budget: Vec<Vec<Scalar>> = returns.iter().map(|&ret| vec![1.0, ret]).collect();
+    let equality = columns_to_csc(2, &budget);
+    let problem = QpBuilder::new()
+        .p(diagonal_csc(variances))
+        .q(vec![0.0; n])
+        .c(equality, vec![1.0, target])
+        .bounds(Bounds {
+            lower: vec![0.0; n],
+            upper: vec![1.0; n],
+        })
+        .build()?;
+    Ok(JsonProblem::Qp { problem })
+}
+
+fn transport_flow(
+    supplies: &[Scalar],
+    demands: &[Scalar],
+    costs: &[Vec<Scalar>],
+) -> Result<JsonProblem> {
+    let sources = supplies.len();
+    let sinks = demands.len();
+    if costs.len() != sources || costs.iter().any(|row| row.len() != sinks) {
+        bail!(
+            "La matriz de costos debe tener {} filas (origenes) de {} valores (destinos).",
+            sources,
+            sinks
+        );
+    }
+    let mut supply_columns = Vec::with_capacity(sources * sinks);
+    let mut demand_columns = Vec::with_capacity(sources * sinks);
+    let mut cost = Vec::with_capacity(sources * sinks);
+    for (source, row) in costs.iter().enumerate() {
+        for (sink, &value) in row.iter().enumerate() {
+            let mut supply = vec![0.0; sources];
+            supply[source] = 1.0;
+            supply_columns.push(supply);
+            let mut demand = vec![0.0; sinks];
+            demand[sink] = 1.0;
+            demand_columns.push(demand);
+            cost.push(value);
+        }
+    }
+    let nvars = cost.len();
+    let problem = LpBuilder::new()
+        .c(cost)
+        .a(columns_to_csc(sources, &supply_columns), supplies.to_vec())
+        .c_eq(columns_to_csc(sinks, &demand_columns), demands.to_vec())
+        .bounds(Bounds {
+            lower: vec![0.0; nvars],
+            upper: vec![FLOW_CAP; nvars],
+        })
+        .build()?;
+    Ok(JsonProblem::Lp { problem })
+}
+
+fn box_qp(
+    diagonal: &[Scalar],
+    linear: &[Scalar],
+    lower: Scalar,
+    upper: Scalar,
+) -> Result<JsonProblem> {
+    if diagonal.len() != linear.len() {
+        bail!(
+            "La diagonal de P tiene {} valores pero q tiene {}.",
+            diagonal.len(),
+            linear.len()
+        );
+    }
+    let n = diagonal.len();
+    let problem = QpBuilder::new()
+        .p(diagonal_csc(diagonal))
+        .q(linear.to_vec())
+        .bounds(Bounds {
+            lower: vec![lower; n],
+            upper: vec![upper; n],
+        })
+        .build()?;
+    Ok(JsonProblem::Qp { problem })
+}
+
+fn least_squares(rows: &[Vec<Scalar>], rhs: &[Scalar]) -> Result<JsonProblem> {
+    let m = rows.len();
+    let n = rows.first().map(|row| row.len()).unwrap_or(0);
+    if m == 0 || n == 0 {
+        bail!("La matriz A no puede estar vacia.");
+    }
+    if rows.iter().any(|row| row.len() != n) {
+        bail!("Todas las filas de A deben tener {} columnas.", n);
+    }
+    if rhs.len() != m {
+        bail!("b debe tener {} valores (uno por fila de A).", m);
+    }
+    let mut gram = vec![vec![0.0; n]; n];
+    let mut linear = vec![0.0; n];
+    for (row, &b) in rows.iter().zip(rhs.iter()) {
+        for i in 0..n {
+            linear[i] -= row[i] * b;
+            for j in 0..n {
+                gram[j][i] += row[i] * row[j];
+            }
+        }
+    }
+    let problem = QpBuilder::new()
+        .p(columns_to_csc(n, &gram))
+        .q(linear)
+        .build()?;
+    Ok(JsonProblem::Qp { problem })
+}
+
+fn diagonal_csc(diag: &[Scalar]) -> CscMatrix<Scalar> {
+    let columns: Vec<Vec<Scalar>> = diag
+        .iter()
+        .enumerate()
+        .map(|(idx, &value)| {
+            let mut column = vec![0.0; diag.len()];
+            column[idx] = value;
+            column
+        })
+        .collect();
+    columns_to_csc(diag.len(), &columns)
+}
+
+fn columns_to_csc(nrows: usize, columns: &[Vec<Scalar>]) -> CscMatrix<Scalar> {
+    let mut indptr = Vec::with_capacity(columns.len() + 1);
+    let mut indices = Vec::new();
+    let mut data = Vec::new();
+    indptr.push(0);
+    for column in columns {
+        for (row, &value) in column.iter().enumerate() {
+            if value != 0.0 {
+                indices.push(row);
+                data.push(value);
+            }
+        }
+        indptr.push(indices.len());
+    }
+    CscMatrix {
+        nrows,
+        ncols: columns.len(),
+        indptr,
+        indices,
+        data,
+    }
+}
+
+fn parse_scalar(field: &TemplateField) -> Result<Scalar> {
+    let trimmed = field.value.trim();
+    trimmed
+        .parse::<Scalar>()
+        .map_err(|_| anyhow!("{}: '{}' no es un numero valido.", field.label, trimmed))
+}
+
+fn parse_list(field: &TemplateField) -> Result<Vec<Scalar>> {
+    let values = parse_values(field.label, &field.value)?;
+    if values.is_empty() {
+        bail!("{}: escribe al menos un valor.", field.label);
+    }
+    Ok(values)
+}
+
+fn parse_rows(field: &TemplateField) -> Result<Vec<Vec<Scalar>>> {
+    let rows = field
+        .value
+        .split(';')
+        .filter(|row| !row.trim().is_empty())
+        .map(|row| parse_values(field.label, row))
+        .collect::<Result<Vec<_>>>()?;
+    if rows.is_empty() {
+        bail!("{}: escribe al menos una fila.", field.label);
+    }
+    Ok(rows)
+}
+
+fn parse_values(label: &str, input: &str) -> Result<Vec<Scalar>> {
+    input
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|token| !token.is_empty())
+        .map(|token| {
+            token
+                .parse::<Scalar>()
+                .map_err(|_| anyhow!("{}: '{}' no es un numero valido.", label, token))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cvxrs_io::parse_json_problem;
+
+    #[test]
+    fn every_template_reads_back_from_json() {
+        let mut gallery = TemplateGallery::default();
+        for kind in TemplateKind::all() {
+            gallery.selected = kind;
+            let problem = gallery.build_selected().expect("default fields build");
+            let written = serde_json::to_string_pretty(&problem).expect("serialize");
+            if let Err(err) = parse_json_problem(&written) {
+                panic!("{}: {}", kind.display_name(), err);
+            }
+        }
+    }
+}
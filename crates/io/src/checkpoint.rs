@@ -0,0 +1,49 @@
+use anyhow::{bail, Context, Result};
+use cvxrs_core::math::Scalar;
+use cvxrs_core::problem::SolverState;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+use crate::JsonProblem;
+
+const MAGIC: &[u8; 8] = b"CVXRSCK1";
+
+// Saved solver state plus a fingerprint of the problem it belongs to, so a
+// resume against a different (or edited) problem is refused.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub fingerprint: u64,
+    pub state: SolverState<Scalar>,
+}
+
+// FNV-1a over the problem's JSON form; stable across runs and platforms.
+pub fn problem_fingerprint(problem: &JsonProblem) -> u64 {
+    let bytes = serde_json::to_vec(problem).unwrap_or_default();
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+// Writes through a sibling temporary file and renames it into place, so a
+// preempted process never leaves a truncated checkpoint behind.
+pub fn write_checkpoint<P: AsRef<Path>>(path: P, checkpoint: &Checkpoint) -> Result<()> {
+    let path = path.as_ref();
+    let mut bytes = MAGIC.to_vec();
+    bincode::serialize_into(&mut bytes, checkpoint).context("failed to serialise checkpoint")?;
+    let mut temporary = path.as_os_str().to_owned();
+    temporary.push(".tmp");
+    fs::write(&temporary, &bytes).with_context(|| format!("failed to write {:?}", temporary))?;
+    fs::rename(&temporary, path)
+        .with_context(|| format!("failed to move checkpoint into {:?}", path))?;
+    Ok(())
+}
+
+pub fn read_checkpoint<P: AsRef<Path>>(path: P) -> Result<Checkpoint> {
+    let path = path.as_ref();
+    let bytes = fs::read(path).with_context(|| format!("failed to read {:?}", path))?;
+    let Some(payload) = bytes.strip_prefix(MAGIC.as_slice()) else {
+        bail!("{:?} is not a cvxrs checkpoint", path);
+    };
+    bincode::deserialize(payload).with_context(|| format!("corrupt checkpoint {:?}", path))
+}
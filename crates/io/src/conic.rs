@@ -0,0 +1,597 @@
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use cvxrs_core::cones::psd_index;
+use cvxrs_core::math::Scalar;
+use cvxrs_core::problem::{Cone, CscMatrix, ProblemConic};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::mps::{csc_from_columns, symmetric_columns};
+
+// Field names follow the ScsMatrix, ScsData and ScsCone structs of SCS 3.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScsMatrix {
+    pub x: Vec<Scalar>,
+    pub i: Vec<usize>,
+    pub p: Vec<usize>,
+    pub m: usize,
+    pub n: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScsData {
+    pub m: usize,
+    pub n: usize,
+    #[serde(rename = "A")]
+    pub a: ScsMatrix,
+    #[serde(rename = "P", default, skip_serializing_if = "Option::is_none")]
+    pub p: Option<ScsMatrix>,
+    pub b: Vec<Scalar>,
+    pub c: Vec<Scalar>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScsCone {
+    pub z: usize,
+    pub l: usize,
+    pub q: Vec<usize>,
+    pub s: Vec<usize>,
+    pub ep: usize,
+    pub ed: usize,
+    pub p: Vec<Scalar>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScsProblem {
+    pub data: ScsData,
+    pub cone: ScsCone,
+}
+
+// Layout written by Clarabel's `write_to_file` JSON export.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClarabelMatrix {
+    pub m: usize,
+    pub n: usize,
+    pub colptr: Vec<usize>,
+    pub rowval: Vec<usize>,
+    pub nzval: Vec<Scalar>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ClarabelCone {
+    ZeroConeT(usize),
+    NonnegativeConeT(usize),
+    SecondOrderConeT(usize),
+    ExponentialConeT(),
+    PowerConeT(Scalar),
+    PSDTriangleConeT(usize),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClarabelProblem {
+    #[serde(rename = "P")]
+    pub p: ClarabelMatrix,
+    pub q: Vec<Scalar>,
+    #[serde(rename = "A")]
+    pub a: ClarabelMatrix,
+    pub b: Vec<Scalar>,
+    pub cones: Vec<ClarabelCone>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub settings: Option<serde_json::Value>,
+}
+
+// Primal-dual point as reported by SCS (x, y, s) or Clarabel (x, z, s).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConicSolution {
+    pub x: Vec<Scalar>,
+    #[serde(alias = "z")]
+    pub y: Vec<Scalar>,
+    pub s: Vec<Scalar>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ConicResiduals {
+    pub primal: Scalar,
+    pub dual: Scalar,
+    pub objective: Scalar,
+}
+
+pub fn to_scs(problem: &ProblemConic<Scalar>) -> Result<ScsProblem> {
+    problem.validate()?;
+    // SCS expects rows grouped as zero, nonnegative, second-order, PSD and
+    // then exponential cones.
+    let mut blocks: Vec<(usize, usize, &Cone)> = Vec::with_capacity(problem.cones.len());
+    let mut start = 0;
+    for cone in &problem.cones {
+        blocks.push((start, cone.dim(), cone));
+        start += cone.dim();
+    }
+    let rank = |cone: &Cone| match cone {
+        Cone::Zero(_) => 0,
+        Cone::NonNegative(_) => 1,
+        Cone::SecondOrder(_) => 2,
+        Cone::PositiveSemidefinite(_) => 3,
+        Cone::Exponential => 4,
+    };
+    blocks.sort_by_key(|&(_, _, cone)| rank(cone));
+
+    let mut position = vec![0; problem.rhs.len()];
+    let mut b = Vec::with_capacity(problem.rhs.len());
+    let mut cone = ScsCone::default();
+    for &(start, dim, kind) in &blocks {
+        let order = match kind {
+            Cone::PositiveSemidefinite(side) => scs_psd_order(*side),
+            _ => (0..dim).collect(),
+        };
+        for offset in order {
+            position[start + offset] = b.len();
+            b.push(problem.rhs[start + offset]);
+        }
+        match kind {
+            Cone::Zero(n) => cone.z += n,
+            Cone::NonNegative(n) => cone.l += n,
+            Cone::SecondOrder(n) => cone.q.push(*n),
+            Cone::PositiveSemidefinite(n) => cone.s.push(*n),
+            Cone::Exponential => cone.ep += 1,
+        }
+    }
+    let columns = columns(&problem.matrix)
+        .into_iter()
+        .map(|column| {
+            column
+                .into_iter()
+                .map(|(row, value)| (position[row], value))
+                .collect()
+        })
+        .collect();
+    let a = csc_from_columns(b.len(), columns);
+
+    Ok(ScsProblem {
+        data: ScsData {
+            m: b.len(),
+            n: problem.nvars(),
+            a: ScsMatrix::from(a),
+            p: problem
+                .quadratic
+                .as_ref()
+                .map(|quadratic| ScsMatrix::from(upper_triangle(quadratic))),
+            b,
+            c: problem.cost.clone(),
+        },
+        cone,
+    })
+}
+
+pub fn from_scs(problem: &ScsProblem) -> Result<ProblemConic<Scalar>> {
+    let cone = &problem.cone;
+    if cone.ed > 0 || !cone.p.is_empty() {
+        bail!("only zero, nonnegative, second-order, PSD and exponential cones are supported");
+    }
+    let mut cones = Vec::new();
+    if cone.z > 0 {
+        cones.push(Cone::Zero(cone.z));
+    }
+    if cone.l > 0 {
+        cones.push(Cone::NonNegative(cone.l));
+    }
+    cones.extend(cone.q.iter().map(|&n| Cone::SecondOrder(n)));
+    cones.extend(cone.s.iter().map(|&n| Cone::PositiveSemidefinite(n)));
+    cones.extend((0..cone.ep).map(|_| Cone::Exponential));
+    let data = &problem.data;
+    let rows: usize = cones.iter().map(Cone::dim).sum();
+    if rows != data.b.len() || data.a.m != data.b.len() {
+        bail!(
+            "cones cover {} rows but the problem has {} (A has {})",
+            rows,
+            data.b.len(),
+            data.a.m
+        );
+    }
+    // SCS stores PSD blocks by the lower triangle; move rows to our order.
+    let mut position: Vec<usize> = (0..rows).collect();
+    let mut start = rows - 3 * cone.ep - cone.s.iter().map(|&n| n * (n + 1) / 2).sum::<usize>();
+    for &side in &cone.s {
+        for (scs, ours) in scs_psd_order(side).into_iter().enumerate() {
+            position[start + scs] = start + ours;
+        }
+        start += side * (side + 1) / 2;
+    }
+    let mut b = vec![0.0; rows];
+    for (row, &value) in data.b.iter().enumerate() {
+        b[position[row]] = value;
+    }
+    let a: CscMatrix<Scalar> = data.a.clone().into();
+    a.validate()?;
+    let columns = columns(&a)
+        .into_iter()
+        .map(|column| {
+            let mut column: Vec<(usize, Scalar)> = column
+                .into_iter()
+                .map(|(row, value)| (position[row], value))
+                .collect();
+            column.sort_by_key(|&(row, _)| row);
+            column
+        })
+        .collect();
+    let matrix = csc_from_columns(rows, columns);
+    let quadratic = match &data.p {
+        Some(p) => full_symmetric(p.clone().into())?,
+        None => None,
+    };
+    let conic = ProblemConic {
+        quadratic,
+        cost: data.c.clone(),
+        matrix,
+        rhs: b,
+        cones,
+    };
+    conic.validate()?;
+    Ok(conic)
+}
+
+pub fn to_clarabel(problem: &ProblemConic<Scalar>) -> Result<ClarabelProblem> {
+    problem.validate()?;
+    let n = problem.nvars();
+    let p = match &problem.quadratic {
+        Some(quadratic) => upper_triangle(quadratic),
+        None => csc_from_columns(n, vec![Vec::new(); n]),
+    };
+    Ok(ClarabelProblem {
+        p: ClarabelMatrix::from(p),
+        q: problem.cost.clone(),
+        a: ClarabelMatrix::from(problem.matrix.clone()),
+        b: problem.rhs.clone(),
+        cones: problem
+            .cones
+            .iter()
+            .map(|cone| match cone {
+                Cone::Zero(n) => ClarabelCone::ZeroConeT(*n),
+                Cone::NonNegative(n) => ClarabelCone::NonnegativeConeT(*n),
+                Cone::SecondOrder(n) => ClarabelCone::SecondOrderConeT(*n),
+                Cone::PositiveSemidefinite(n) => ClarabelCone::PSDTriangleConeT(*n),
+                Cone::Exponential => ClarabelCone::ExponentialConeT(),
+            })
+            .collect(),
+        settings: None,
+    })
+}
+
+pub fn from_clarabel(problem: &ClarabelProblem) -> Result<ProblemConic<Scalar>> {
+    let cones = problem
+        .cones
+        .iter()
+        .map(|cone| match cone {
+            ClarabelCone::ZeroConeT(n) => Ok(Cone::Zero(*n)),
+            ClarabelCone::NonnegativeConeT(n) => Ok(Cone::NonNegative(*n)),
+            ClarabelCone::SecondOrderConeT(n) => Ok(Cone::SecondOrder(*n)),
+            ClarabelCone::PSDTriangleConeT(n) => Ok(Cone::PositiveSemidefinite(*n)),
+            ClarabelCone::ExponentialConeT() => Ok(Cone::Exponential),
+            other => bail!("unsupported cone {:?}", other),
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let conic = ProblemConic {
+        quadratic: full_symmetric(problem.p.clone().into())?,
+        cost: problem.q.clone(),
+        matrix: problem.a.clone().into(),
+        rhs: problem.b.clone(),
+        cones,
+    };
+    conic.validate()?;
+    Ok(conic)
+}
+
+pub fn conic_residuals(
+    problem: &ProblemConic<Scalar>,
+    solution: &ConicSolution,
+) -> Result<ConicResiduals> {
+    let n = problem.nvars();
+    let m = problem.rhs.len();
+    if solution.x.len() != n || solution.y.len() != m || solution.s.len() != m {
+        bail!(
+            "solution sizes ({}, {}, {}) do not match n = {} and m = {}",
+            solution.x.len(),
+            solution.y.len(),
+            solution.s.len(),
+            n,
+            m
+        );
+    }
+    let mut primal: Vec<Scalar> = solution
+        .s
+        .iter()
+        .zip(&problem.rhs)
+        .map(|(s, b)| s - b)
+        .collect();
+    let mut dual = problem.cost.clone();
+    let mut objective: Scalar = problem
+        .cost
+        .iter()
+        .zip(&solution.x)
+        .map(|(c, x)| c * x)
+        .sum();
+    for (col, column) in columns(&problem.matrix).into_iter().enumerate() {
+        for (row, value) in column {
+            primal[row] += value * solution.x[col];
+            dual[col] += value * solution.y[row];
+        }
+    }
+    if let Some(quadratic) = &problem.quadratic {
+        for (col, column) in columns(quadratic).into_iter().enumerate() {
+            for (row, value) in column {
+                dual[row] += value * solution.x[col];
+                objective += 0.5 * solution.x[row] * value * solution.x[col];
+            }
+        }
+    }
+    let norm = |values: &[Scalar]| values.iter().fold(0.0, |acc: Scalar, v| acc.max(v.abs()));
+    Ok(ConicResiduals {
+        primal: norm(&primal),
+        dual: norm(&dual),
+        objective,
+    })
+}
+
+pub fn read_scs_json<P: AsRef<Path>>(path: P) -> Result<ScsProblem> {
+    read_json(path.as_ref(), "SCS problem")
+}
+
+pub fn write_scs_json<P: AsRef<Path>>(path: P, problem: &ScsProblem) -> Result<()> {
+    write_json(path.as_ref(), problem)
+}
+
+pub fn read_clarabel_json<P: AsRef<Path>>(path: P) -> Result<ClarabelProblem> {
+    read_json(path.as_ref(), "Clarabel problem")
+}
+
+pub fn write_clarabel_json<P: AsRef<Path>>(path: P, problem: &ClarabelProblem) -> Result<()> {
+    write_json(path.as_ref(), problem)
+}
+
+pub fn read_conic_solution<P: AsRef<Path>>(path: P) -> Result<ConicSolution> {
+    read_json(path.as_ref(), "conic solution")
+}
+
+fn read_json<T: DeserializeOwned>(path: &Path, what: &str) -> Result<T> {
+    let contents =
+        fs::read_to_string(path).with_context(|| format!("failed to read {:?}", path))?;
+    serde_json::from_str(&contents).with_context(|| format!("failed to parse {} {:?}", what, path))
+}
+
+fn write_json<T: Serialize>(path: &Path, value: &T) -> Result<()> {
+    let file = File::create(path).with_context(|| format!("failed to create {:?}", path))?;
+    let mut writer = BufWriter::new(file);
+    serde_json::to_writer_pretty(&mut writer, value).context("failed to serialise problem")?;
+    writer
+        .flush()
+        .with_context(|| format!("failed to write {:?}", path))
+}
+
+fn columns(matrix: &CscMatrix<Scalar>) -> Vec<Vec<(usize, Scalar)>> {
+    (0..matrix.ncols)
+        .map(|col| {
+            (matrix.indptr[col]..matrix.indptr[col + 1])
+                .map(|idx| (matrix.indices[idx], matrix.data[idx]))
+                .collect()
+        })
+        .collect()
+}
+
+// Our PSD rows (upper triangle by columns, as Clarabel) listed in SCS order
+// (lower triangle by columns). Both scale off-diagonal entries by √2.
+fn scs_psd_order(side: usize) -> Vec<usize> {
+    let mut order = Vec::with_capacity(side * (side + 1) / 2);
+    for col in 0..side {
+        for row in col..side {
+            order.push(psd_index(col, row));
+        }
+    }
+    order
+}
+
+fn upper_triangle(matrix: &CscMatrix<Scalar>) -> CscMatrix<Scalar> {
+    let columns = columns(matrix)
+        .into_iter()
+        .enumerate()
+        .map(|(col, column)| column.into_iter().filter(|&(row, _)| row <= col).collect())
+        .collect();
+    csc_from_columns(matrix.nrows, columns)
+}
+
+// SCS and Clarabel take the upper triangle of P; an empty P means a linear objective.
+fn full_symmetric(matrix: CscMatrix<Scalar>) -> Result<Option<CscMatrix<Scalar>>> {
+    matrix.validate()?;
+    if matrix.nnz() == 0 {
+        return Ok(None);
+    }
+    Ok(Some(csc_from_columns(
+        matrix.nrows,
+        symmetric_columns(columns(&matrix)),
+    )))
+}
+
+impl From<CscMatrix<Scalar>> for ScsMatrix {
+    fn from(matrix: CscMatrix<Scalar>) -> Self {
+        Self {
+            x: matrix.data,
+            i: matrix.indices,
+            p: matrix.indptr,
+            m: matrix.nrows,
+            n: matrix.ncols,
+        }
+    }
+}
+
+impl From<ScsMatrix> for CscMatrix<Scalar> {
+    fn from(matrix: ScsMatrix) -> Self {
+        Self {
+            nrows: matrix.m,
+            ncols: matrix.n,
+            indptr: matrix.p,
+            indices: matrix.i,
+            data: matrix.x,
+        }
+    }
+}
+
+impl From<CscMatrix<Scalar>> for ClarabelMatrix {
+    fn from(matrix: CscMatrix<Scalar>) -> Self {
+        Self {
+            m: matrix.nrows,
+            n: matrix.ncols,
+            colptr: matrix.indptr,
+            rowval: matrix.indices,
+            nzval: matrix.data,
+        }
+    }
+}
+
+impl From<ClarabelMatrix> for CscMatrix<Scalar> {
+    fn from(matrix: ClarabelMatrix) -> Self {
+        Self {
+            nrows: matrix.m,
+            ncols: matrix.n,
+            indptr: matrix.colptr,
+            indices: matrix.rowval,
+            data: matrix.nzval,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn problem() -> ProblemConic<Scalar> {
+        // Rows: x0 + x1 <= 2, x0 - x1 = 0, (t, x0, x1) in SOC with t = 3.
+        ProblemConic {
+            quadratic: Some(csc_from_columns(
+                2,
+                vec![vec![(0, 2.0), (1, 0.5)], vec![(0, 0.5), (1, 1.0)]],
+            )),
+            cost: vec![1.0, -1.0],
+            matrix: csc_from_columns(
+                5,
+                vec![
+                    vec![(0, 1.0), (1, 1.0), (3, -1.0)],
+                    vec![(0, 1.0), (1, -1.0), (4, -1.0)],
+                ],
+            ),
+            rhs: vec![2.0, 0.0, 3.0, 0.0, 0.0],
+            cones: vec![Cone::NonNegative(1), Cone::Zero(1), Cone::SecondOrder(3)],
+        }
+    }
+
+    #[test]
+    fn scs_layout_groups_rows_by_cone() {
+        let original = problem();
+        let scs = to_scs(&original).unwrap();
+        assert_eq!(scs.cone.z, 1);
+        assert_eq!(scs.cone.l, 1);
+        assert_eq!(scs.cone.q, vec![3]);
+        assert_eq!(scs.data.b, vec![0.0, 2.0, 3.0, 0.0, 0.0]);
+        assert_eq!(scs.data.a.i, vec![0, 1, 3, 0, 1, 4]);
+        assert_eq!(scs.data.a.x, vec![1.0, 1.0, -1.0, -1.0, 1.0, -1.0]);
+        let p = scs.data.p.as_ref().unwrap();
+        assert_eq!(
+            (p.i.clone(), p.x.clone()),
+            (vec![0, 0, 1], vec![2.0, 0.5, 1.0])
+        );
+
+        let json = serde_json::to_string(&scs).unwrap();
+        let back = from_scs(&serde_json::from_str(&json).unwrap()).unwrap();
+        assert_eq!(back.quadratic.unwrap().data, vec![2.0, 0.5, 0.5, 1.0]);
+        assert!(matches!(
+            back.cones[..],
+            [Cone::Zero(1), Cone::NonNegative(1), Cone::SecondOrder(3)]
+        ));
+
+        let clarabel = to_clarabel(&original).unwrap();
+        let json = serde_json::to_value(&clarabel).unwrap();
+        assert_eq!(
+            json["cones"][0],
+            serde_json::json!({ "NonnegativeConeT": 1 })
+        );
+        let back = from_clarabel(&serde_json::from_value(json).unwrap()).unwrap();
+        assert_eq!(back.rhs, original.rhs);
+        assert_eq!(back.matrix.data, original.matrix.data);
+    }
+
+    #[test]
+    fn psd_rows_follow_each_format() {
+        // A 3x3 PSD block whose rows carry their own index as the rhs.
+        let problem = ProblemConic {
+            quadratic: None,
+            cost: vec![1.0],
+            matrix: csc_from_columns(7, vec![vec![(0, 1.0), (4, -1.0)]]),
+            rhs: vec![0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0],
+            cones: vec![Cone::Zero(1), Cone::PositiveSemidefinite(3)],
+        };
+        let scs = to_scs(&problem).unwrap();
+        assert_eq!(scs.cone.s, vec![3]);
+        // Lower triangle by columns: (0,0) (1,0) (2,0) (1,1) (2,1) (2,2).
+        assert_eq!(scs.data.b, vec![0.0, 1.0, 2.0, 4.0, 3.0, 5.0, 6.0]);
+        assert_eq!(scs.data.a.i, vec![0, 3]);
+        let back = from_scs(&scs).unwrap();
+        assert_eq!(back.rhs, problem.rhs);
+        assert_eq!(back.matrix.indices, problem.matrix.indices);
+
+        let clarabel = to_clarabel(&problem).unwrap();
+        let json = serde_json::to_value(&clarabel).unwrap();
+        assert_eq!(
+            json["cones"][1],
+            serde_json::json!({ "PSDTriangleConeT": 3 })
+        );
+        let back = from_clarabel(&serde_json::from_value(json).unwrap()).unwrap();
+        assert!(matches!(back.cones[1], Cone::PositiveSemidefinite(3)));
+        assert_eq!(back.rhs, problem.rhs);
+    }
+
+    #[test]
+    fn exponential_cones_come_after_psd_blocks() {
+        let problem = ProblemConic {
+            quadratic: None,
+            cost: vec![1.0],
+            matrix: csc_from_columns(4, vec![vec![(0, 1.0), (3, -1.0)]]),
+            rhs: vec![0.0, 1.0, 2.0, 3.0],
+            cones: vec![Cone::Exponential, Cone::PositiveSemidefinite(1)],
+        };
+        let scs = to_scs(&problem).unwrap();
+        assert_eq!((scs.cone.s.clone(), scs.cone.ep), (vec![1], 1));
+        assert_eq!(scs.data.b, vec![3.0, 0.0, 1.0, 2.0]);
+        let back = from_scs(&scs).unwrap();
+        assert!(matches!(
+            back.cones[..],
+            [Cone::PositiveSemidefinite(1), Cone::Exponential]
+        ));
+
+        let json = serde_json::to_value(to_clarabel(&problem).unwrap()).unwrap();
+        assert_eq!(
+            json["cones"][0],
+            serde_json::json!({ "ExponentialConeT": [] })
+        );
+        let back = from_clarabel(&serde_json::from_str(&json.to_string()).unwrap()).unwrap();
+        assert!(matches!(back.cones[0], Cone::Exponential));
+    }
+
+    #[test]
+    fn residuals_vanish_at_kkt_point() {
+        // minimize x subject to x >= 1: x = 1, s = 0, y = 1.
+        let problem = ProblemConic {
+            quadratic: None,
+            cost: vec![1.0],
+            matrix: csc_from_columns(1, vec![vec![(0, -1.0)]]),
+            rhs: vec![-1.0],
+            cones: vec![Cone::NonNegative(1)],
+        };
+        let solution: ConicSolution =
+            serde_json::from_str(r#"{"x":[1.0],"z":[1.0],"s":[0.0]}"#).unwrap();
+        let residuals = conic_residuals(&problem, &solution).unwrap();
+        assert_eq!(residuals.primal, 0.0);
+        assert_eq!(residuals.dual, 0.0);
+        assert_eq!(residuals.objective, 1.0);
+    }
+}
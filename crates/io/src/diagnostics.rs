@@ -0,0 +1,735 @@
+use crate::repair::{repair_problem, ParseFix, ParseOptions};
+use crate::JsonProblem;
+use cvxrs_core::math::Scalar;
+use cvxrs_core::problem::{Bounds, ProblemError};
+use serde_json::Value;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsonDiagnostic {
+    pub pointer: String,
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for JsonDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let pointer = if self.pointer.is_empty() {
+            "/"
+        } else {
+            self.pointer.as_str()
+        };
+        write!(
+            f,
+            "line {}, column {} ({}): {}",
+            self.line, self.column, pointer, self.message
+        )
+    }
+}
+
+impl std::error::Error for JsonDiagnostic {}
+
+pub fn parse_json_problem(contents: &str) -> Result<JsonProblem, JsonDiagnostic> {
+    parse_json_problem_with(contents, &ParseOptions::default()).map(|(problem, _)| problem)
+}
+
+pub fn parse_json_problem_with(
+    contents: &str,
+    options: &ParseOptions,
+) -> Result<(JsonProblem, Vec<ParseFix>), JsonDiagnostic> {
+    let value: Value = serde_json::from_str(contents).map_err(|err| JsonDiagnostic {
+        pointer: String::new(),
+        line: err.line(),
+        column: err.column(),
+        message: err.to_string(),
+    })?;
+
+    let diagnostic = |pointer: String, message: String| {
+        let (line, column) = locate_pointer(contents, &pointer);
+        JsonDiagnostic {
+            pointer,
+            line,
+            column,
+            message,
+        }
+    };
+
+    let mut unknown = Vec::new();
+    if let Err((pointer, message)) = check_problem_shape(&value, &mut unknown) {
+        return Err(diagnostic(pointer, message));
+    }
+    let mut fixes = Vec::new();
+    for (pointer, name) in unknown {
+        if options.strict {
+            return Err(diagnostic(pointer, format!("unknown field `{name}`")));
+        }
+        fixes.push(ParseFix {
+            pointer,
+            message: format!("ignored unknown field `{name}`"),
+        });
+    }
+    let mut problem = serde_json::from_value::<JsonProblem>(value)
+        .map_err(|err| diagnostic(String::new(), err.to_string()))?;
+    match repair_problem(&mut problem, options) {
+        Ok(repairs) => fixes.extend(repairs),
+        Err((pointer, message)) => return Err(diagnostic(pointer, message)),
+    }
+    if let Err((pointer, err)) = validate_with_pointer(&problem) {
+        return Err(diagnostic(pointer, err.to_string()));
+    }
+    Ok((problem, fixes))
+}
+
+fn validate_with_pointer(problem: &JsonProblem) -> Result<(), (String, ProblemError)> {
+    fn at(field: &'static str) -> impl Fn(ProblemError) -> (String, ProblemError) {
+        move |err| (format!("/problem/{field}"), err)
+    }
+    let (n, equalities, inequalities, bounds) = match problem {
+        JsonProblem::Qp { problem } => {
+            let n = problem.nvars();
+            problem.quadratic.validate().map_err(at("quadratic"))?;
+            if problem.quadratic.nrows != n || problem.quadratic.ncols != n {
+                return Err(at("quadratic")(ProblemError::DimensionMismatch(format!(
+                    "quadratic matrix is {}x{} but `linear` has {n} entries",
+                    problem.quadratic.nrows, problem.quadratic.ncols
+                ))));
+            }
+            (
+                n,
+                &problem.equalities,
+                &problem.inequalities,
+                &problem.bounds,
+            )
+        }
+        JsonProblem::Lp { problem } => (
+            problem.nvars(),
+            &problem.equalities,
+            &problem.inequalities,
+            &problem.bounds,
+        ),
+    };
+    if let Some(bounds) = bounds {
+        validate_bounds(bounds, n).map_err(at("bounds"))?;
+    }
+    if let Some(eq) = equalities {
+        eq.validate(n).map_err(at("equalities"))?;
+    }
+    if let Some(ineq) = inequalities {
+        ineq.validate(n).map_err(at("inequalities"))?;
+    }
+    // Everything but the group row references has been checked above.
+    match problem {
+        JsonProblem::Qp { problem } => problem.validate(),
+        JsonProblem::Lp { problem } => problem.validate(),
+    }
+    .map_err(at("groups"))
+}
+
+fn validate_bounds(bounds: &Bounds<Scalar>, n: usize) -> Result<(), ProblemError> {
+    if bounds.lower.len() != n {
+        return Err(ProblemError::DimensionMismatch(format!(
+            "bounds size {} != nvars {n}",
+            bounds.lower.len()
+        )));
+    }
+    bounds.validate()
+}
+
+#[derive(Clone, Copy)]
+enum Shape {
+    Index,
+    Number,
+    Numbers,
+    Indices,
+    Matrix,
+    Constraints,
+    Bounds,
+    Text,
+    Flag,
+    Flags,
+    Texts,
+    Rows,
+    Groups,
+    QuadraticConstraints,
+    Names,
+}
+
+const MATRIX_FIELDS: &[(&str, Shape, bool)] = &[
+    ("nrows", Shape::Index, true),
+    ("ncols", Shape::Index, true),
+    ("indptr", Shape::Indices, true),
+    ("indices", Shape::Indices, true),
+    ("data", Shape::Numbers, true),
+];
+
+const CONSTRAINT_FIELDS: &[(&str, Shape, bool)] = &[
+    ("matrix", Shape::Matrix, true),
+    ("rhs", Shape::Numbers, true),
+];
+
+const BOUND_FIELDS: &[(&str, Shape, bool)] = &[
+    ("lower", Shape::Numbers, true),
+    ("upper", Shape::Numbers, true),
+];
+
+const GROUP_FIELDS: &[(&str, Shape, bool)] =
+    &[("name", Shape::Text, true), ("rows", Shape::Rows, true)];
+
+const QUADRATIC_CONSTRAINT_FIELDS: &[(&str, Shape, bool)] = &[
+    ("matrix", Shape::Matrix, true),
+    ("linear", Shape::Numbers, true),
+    ("rhs", Shape::Number, true),
+];
+
+const NAME_FIELDS: &[(&str, Shape, bool)] = &[
+    ("variables", Shape::Texts, false),
+    ("equalities", Shape::Texts, false),
+    ("inequalities", Shape::Texts, false),
+];
+
+const QP_FIELDS: &[(&str, Shape, bool)] = &[
+    ("quadratic", Shape::Matrix, true),
+    ("linear", Shape::Numbers, true),
+    ("inequalities", Shape::Constraints, false),
+    ("equalities", Shape::Constraints, false),
+    ("bounds", Shape::Bounds, false),
+    ("groups", Shape::Groups, false),
+    ("integrality", Shape::Flags, false),
+    ("offset", Shape::Number, false),
+    ("quadratic_constraints", Shape::QuadraticConstraints, false),
+    ("names", Shape::Names, false),
+    ("sense", Shape::Text, false),
+];
+
+const LP_FIELDS: &[(&str, Shape, bool)] = &[
+    ("cost", Shape::Numbers, true),
+    ("inequalities", Shape::Constraints, false),
+    ("equalities", Shape::Constraints, false),
+    ("bounds", Shape::Bounds, false),
+    ("groups", Shape::Groups, false),
+    ("integrality", Shape::Flags, false),
+    ("offset", Shape::Number, false),
+    ("names", Shape::Names, false),
+    ("sense", Shape::Text, false),
+];
+
+// Unknown fields are collected as (pointer, name) pairs rather than rejected,
+// so the caller decides between strict and lenient parsing.
+fn check_problem_shape(
+    root: &Value,
+    unknown: &mut Vec<(String, String)>,
+) -> Result<(), (String, String)> {
+    let object = root
+        .as_object()
+        .ok_or_else(|| (String::new(), "expected a JSON object".to_string()))?;
+    let fields = match object.get("kind") {
+        Some(Value::String(kind)) if kind == "qp" => QP_FIELDS,
+        Some(Value::String(kind)) if kind == "lp" => LP_FIELDS,
+        Some(other) => {
+            return Err((
+                "/kind".into(),
+                format!("unknown problem kind {other}, expected \"qp\" or \"lp\""),
+            ))
+        }
+        None => return Err((String::new(), "missing field `kind`".into())),
+    };
+    collect_unknown(object, "", &["kind", "problem"], unknown);
+    match object.get("problem") {
+        Some(problem) => check_object(problem, "/problem", fields, unknown),
+        None => Err((String::new(), "missing field `problem`".into())),
+    }
+}
+
+fn check_object(
+    value: &Value,
+    pointer: &str,
+    fields: &[(&str, Shape, bool)],
+    unknown: &mut Vec<(String, String)>,
+) -> Result<(), (String, String)> {
+    let object = value
+        .as_object()
+        .ok_or_else(|| (pointer.to_string(), "expected an object".to_string()))?;
+    for &(name, shape, required) in fields {
+        let child = format!("{pointer}/{name}");
+        match object.get(name) {
+            None | Some(Value::Null) if !required => {}
+            None => return Err((pointer.to_string(), format!("missing field `{name}`"))),
+            Some(value) => check_shape(value, &child, shape, unknown)?,
+        }
+    }
+    let known: Vec<&str> = fields.iter().map(|&(name, _, _)| name).collect();
+    collect_unknown(object, pointer, &known, unknown);
+    Ok(())
+}
+
+fn collect_unknown(
+    object: &serde_json::Map<String, Value>,
+    pointer: &str,
+    known: &[&str],
+    unknown: &mut Vec<(String, String)>,
+) {
+    for name in object.keys() {
+        if !known.contains(&name.as_str()) {
+            unknown.push((format!("{pointer}/{name}"), name.clone()));
+        }
+    }
+}
+
+fn check_shape(
+    value: &Value,
+    pointer: &str,
+    shape: Shape,
+    unknown: &mut Vec<(String, String)>,
+) -> Result<(), (String, String)> {
+    match shape {
+        Shape::Index => {
+            if value.as_u64().is_none() {
+                return Err((
+                    pointer.to_string(),
+                    format!("expected a non-negative integer, found {value}"),
+                ));
+            }
+            Ok(())
+        }
+        Shape::Number => {
+            if !value.is_number() {
+                return Err((
+                    pointer.to_string(),
+                    format!("expected a number, found {value}"),
+                ));
+            }
+            Ok(())
+        }
+        Shape::Flag => {
+            if !value.is_boolean() {
+                return Err((
+                    pointer.to_string(),
+                    format!("expected true or false, found {value}"),
+                ));
+            }
+            Ok(())
+        }
+        Shape::Numbers | Shape::Indices | Shape::Flags | Shape::Texts => {
+            let items = value.as_array().ok_or_else(|| {
+                (
+                    pointer.to_string(),
+                    format!("expected an array, found {value}"),
+                )
+            })?;
+            let item_shape = match shape {
+                Shape::Indices => Shape::Index,
+                Shape::Flags => Shape::Flag,
+                Shape::Texts => Shape::Text,
+                _ => Shape::Number,
+            };
+            for (idx, item) in items.iter().enumerate() {
+                check_shape(item, &format!("{pointer}/{idx}"), item_shape, unknown)?;
+            }
+            Ok(())
+        }
+        Shape::Matrix => check_object(value, pointer, MATRIX_FIELDS, unknown),
+        Shape::Constraints => check_object(value, pointer, CONSTRAINT_FIELDS, unknown),
+        Shape::Bounds => check_object(value, pointer, BOUND_FIELDS, unknown),
+        Shape::Names => check_object(value, pointer, NAME_FIELDS, unknown),
+        Shape::Text => {
+            if !value.is_string() {
+                return Err((
+                    pointer.to_string(),
+                    format!("expected a string, found {value}"),
+                ));
+            }
+            Ok(())
+        }
+        // Row references are checked against the problem by validation.
+        Shape::Rows => {
+            if !value.is_array() {
+                return Err((
+                    pointer.to_string(),
+                    format!("expected an array, found {value}"),
+                ));
+            }
+            Ok(())
+        }
+        Shape::Groups => {
+            let items = value.as_array().ok_or_else(|| {
+                (
+                    pointer.to_string(),
+                    format!("expected an array, found {value}"),
+                )
+            })?;
+            for (idx, item) in items.iter().enumerate() {
+                check_object(item, &format!("{pointer}/{idx}"), GROUP_FIELDS, unknown)?;
+            }
+            Ok(())
+        }
+        Shape::QuadraticConstraints => {
+            let items = value.as_array().ok_or_else(|| {
+                (
+                    pointer.to_string(),
+                    format!("expected an array, found {value}"),
+                )
+            })?;
+            for (idx, item) in items.iter().enumerate() {
+                let child = format!("{pointer}/{idx}");
+                check_object(item, &child, QUADRATIC_CONSTRAINT_FIELDS, unknown)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+pub fn locate_pointer(contents: &str, pointer: &str) -> (usize, usize) {
+    let offset = pointer_offset(contents, pointer).unwrap_or(0);
+    line_column(contents, offset)
+}
+
+fn line_column(contents: &str, offset: usize) -> (usize, usize) {
+    let prefix = &contents[..offset.min(contents.len())];
+    let line = prefix.matches('\n').count() + 1;
+    let line_start = prefix.rfind('\n').map(|idx| idx + 1).unwrap_or(0);
+    let column = prefix[line_start..].chars().count() + 1;
+    (line, column)
+}
+
+fn pointer_offset(contents: &str, pointer: &str) -> Option<usize> {
+    let mut scanner = Scanner {
+        bytes: contents.as_bytes(),
+        pos: 0,
+    };
+    scanner.skip_whitespace();
+    let mut best = scanner.pos;
+    for token in pointer.split('/').skip(1) {
+        let token = token.replace("~1", "/").replace("~0", "~");
+        let found = match scanner.peek()? {
+            b'{' => scanner.find_key(&token),
+            b'[' => token
+                .parse::<usize>()
+                .ok()
+                .and_then(|idx| scanner.find_index(idx)),
+            _ => None,
+        };
+        match found {
+            Some(pos) => {
+                scanner.pos = pos;
+                best = pos;
+            }
+            None => break,
+        }
+    }
+    Some(best)
+}
+
+struct Scanner<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Scanner<'a> {
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+            self.pos += 1;
+        }
+    }
+
+    fn expect(&mut self, byte: u8) -> Option<()> {
+        self.skip_whitespace();
+        if self.peek()? == byte {
+            self.pos += 1;
+            Some(())
+        } else {
+            None
+        }
+    }
+
+    fn find_key(&mut self, key: &str) -> Option<usize> {
+        self.expect(b'{')?;
+        loop {
+            self.skip_whitespace();
+            if self.peek()? == b'}' {
+                return None;
+            }
+            let name = self.read_string()?;
+            self.expect(b':')?;
+            self.skip_whitespace();
+            if name == key {
+                return Some(self.pos);
+            }
+            self.skip_value()?;
+            self.skip_whitespace();
+            if self.peek()? == b',' {
+                self.pos += 1;
+            }
+        }
+    }
+
+    fn find_index(&mut self, index: usize) -> Option<usize> {
+        self.expect(b'[')?;
+        for _ in 0..index {
+            self.skip_whitespace();
+            if self.peek()? == b']' {
+                return None;
+            }
+            self.skip_value()?;
+            self.expect(b',')?;
+        }
+        self.skip_whitespace();
+        if self.peek()? == b']' {
+            None
+        } else {
+            Some(self.pos)
+        }
+    }
+
+    fn read_string(&mut self) -> Option<String> {
+        self.skip_whitespace();
+        let start = self.pos;
+        self.skip_string()?;
+        serde_json::from_slice(&self.bytes[start..self.pos]).ok()
+    }
+
+    fn skip_string(&mut self) -> Option<()> {
+        if self.peek()? != b'"' {
+            return None;
+        }
+        self.pos += 1;
+        loop {
+            match self.peek()? {
+                b'\\' => self.pos += 2,
+                b'"' => {
+                    self.pos += 1;
+                    return Some(());
+                }
+                _ => self.pos += 1,
+            }
+        }
+    }
+
+    fn skip_value(&mut self) -> Option<()> {
+        self.skip_whitespace();
+        match self.peek()? {
+            b'"' => self.skip_string(),
+            b'{' | b'[' => {
+                let mut depth = 0usize;
+                loop {
+                    match self.peek()? {
+                        b'"' => {
+                            self.skip_string()?;
+                            continue;
+                        }
+                        b'{' | b'[' => depth += 1,
+                        b'}' | b']' => {
+                            depth -= 1;
+                            if depth == 0 {
+                                self.pos += 1;
+                                return Some(());
+                            }
+                        }
+                        _ => {}
+                    }
+                    self.pos += 1;
+                }
+            }
+            _ => {
+                while !matches!(
+                    self.peek(),
+                    None | Some(b',' | b'}' | b']' | b' ' | b'\t' | b'\n' | b'\r')
+                ) {
+                    self.pos += 1;
+                }
+                Some(())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cvxrs_core::problem::ObjectiveSense;
+
+    #[test]
+    fn points_at_bad_bound_entry() {
+        let input = r#"{
+  "kind": "lp",
+  "problem": {
+    "cost": [1.0, 2.0],
+    "bounds": {
+      "lower": [0.0, "zero"],
+      "upper": [1.0, 1.0]
+    }
+  }
+}"#;
+        let diagnostic = parse_json_problem(input).unwrap_err();
+        assert_eq!(diagnostic.pointer, "/problem/bounds/lower/1");
+        assert_eq!(diagnostic.line, 6);
+        assert_eq!(diagnostic.column, 22);
+    }
+
+    #[test]
+    fn anchors_validation_errors_to_component() {
+        let input = r#"{"kind":"lp","problem":{
+"cost":[1.0,2.0],
+"bounds":{"lower":[0.0],"upper":[1.0]}}}"#;
+        let diagnostic = parse_json_problem(input).unwrap_err();
+        assert_eq!(diagnostic.pointer, "/problem/bounds");
+        assert_eq!(diagnostic.line, 3);
+    }
+
+    #[test]
+    fn rejects_indptr_entries_past_the_data() {
+        let input = r#"{"kind":"qp","problem":{
+"quadratic":{"nrows":4,"ncols":4,"indptr":[0,1,9999999999992,3,4],
+"indices":[0,1,2,3],"data":[1.0,1.0,1.0,1.0]},
+"linear":[0.0,0.0,0.0,0.0]}}"#;
+        let diagnostic = parse_json_problem(input).unwrap_err();
+        assert_eq!(diagnostic.pointer, "/problem/quadratic");
+        assert!(diagnostic.message.contains("exceeds nnz"));
+    }
+
+    #[test]
+    fn strict_rejects_what_lenient_repairs() {
+        let input = r#"{"kind":"qp","problem":{
+"quadratic":{"nrows":2,"ncols":2,"indptr":[0,2,3],"indices":[1,0,1],"data":[1.0,2.0,3.0]},
+"linear":[0.0,0.0],"comment":"draft"}}"#;
+        let diagnostic = parse_json_problem_with(input, &ParseOptions::strict()).unwrap_err();
+        assert_eq!(diagnostic.pointer, "/problem/comment");
+
+        let (problem, fixes) = parse_json_problem_with(input, &ParseOptions::lenient()).unwrap();
+        let pointers: Vec<&str> = fixes.iter().map(|fix| fix.pointer.as_str()).collect();
+        assert_eq!(
+            pointers,
+            [
+                "/problem/comment",
+                "/problem/quadratic",
+                "/problem/quadratic"
+            ]
+        );
+        let JsonProblem::Qp { problem } = problem else {
+            panic!("expected a QP");
+        };
+        assert_eq!(problem.quadratic.indptr, vec![0, 2, 4]);
+        assert_eq!(problem.quadratic.indices, vec![0, 1, 0, 1]);
+        assert_eq!(problem.quadratic.data, vec![2.0, 0.5, 0.5, 3.0]);
+    }
+
+    #[test]
+    fn strict_reads_back_what_is_written() {
+        // Every optional field is set, so a field the writer emits but the
+        // strict field lists do not know fails here.
+        let qp = r#"{"kind":"qp","problem":{
+"quadratic":{"nrows":2,"ncols":2,"indptr":[0,1,2],"indices":[0,1],"data":[2.0,2.0]},
+"linear":[1.0,-1.0],
+"inequalities":{"matrix":{"nrows":1,"ncols":2,"indptr":[0,1,2],"indices":[0,0],"data":[1.0,1.0]},"rhs":[1.0]},
+"equalities":{"matrix":{"nrows":1,"ncols":2,"indptr":[0,1,1],"indices":[0],"data":[1.0]},"rhs":[0.5]},
+"bounds":{"lower":[0.0,0.0],"upper":[1.0,1.0]},
+"integrality":[false,true],
+"offset":-2.5,
+"sense":"Maximize",
+"names":{"variables":["x","y"],"equalities":["e"],"inequalities":["i"]},
+"quadratic_constraints":[{"matrix":{"nrows":2,"ncols":2,"indptr":[0,1,2],"indices":[0,1],"data":[1.0,1.0]},"linear":[0.0,0.0],"rhs":1.0}]}}"#;
+        let lp = r#"{"kind":"lp","problem":{
+"cost":[1.0,-1.0],
+"inequalities":{"matrix":{"nrows":1,"ncols":2,"indptr":[0,1,2],"indices":[0,0],"data":[1.0,1.0]},"rhs":[1.0]},
+"equalities":{"matrix":{"nrows":1,"ncols":2,"indptr":[0,1,1],"indices":[0],"data":[1.0]},"rhs":[0.5]},
+"bounds":{"lower":[0.0,0.0],"upper":[1.0,1.0]},
+"integrality":[false,true],
+"offset":-2.5,
+"sense":"Maximize",
+"names":{"variables":["x","y"],"equalities":["e"],"inequalities":["i"]}}}"#;
+        for input in [qp, lp] {
+            let problem: JsonProblem = serde_json::from_str(input).unwrap();
+            let written = serde_json::to_string_pretty(&problem).unwrap();
+            let (_, fixes) = parse_json_problem_with(&written, &ParseOptions::strict())
+                .unwrap_or_else(|diagnostic| panic!("{diagnostic}"));
+            assert!(fixes.is_empty());
+        }
+    }
+
+    #[test]
+    fn strict_accepts_integrality_flags() {
+        let input = r#"{"kind":"lp","problem":{"cost":[1.0,2.0],"integrality":[true,false]}}"#;
+        let (problem, _) = parse_json_problem_with(input, &ParseOptions::strict()).unwrap();
+        let JsonProblem::Lp { problem } = problem else {
+            panic!("expected an LP");
+        };
+        assert_eq!(problem.integrality, [true, false]);
+
+        let input = r#"{"kind":"lp","problem":{"cost":[1.0],"integrality":[1]}}"#;
+        let diagnostic = parse_json_problem_with(input, &ParseOptions::strict()).unwrap_err();
+        assert_eq!(diagnostic.pointer, "/problem/integrality/0");
+    }
+
+    #[test]
+    fn strict_reads_back_written_offsets() {
+        for input in [
+            r#"{"kind":"lp","problem":{"cost":[1.0],"offset":-2.5}}"#,
+            r#"{"kind":"qp","problem":{"quadratic":{"nrows":1,"ncols":1,"indptr":[0,1],"indices":[0],"data":[2.0]},"linear":[1.0],"offset":-2.5}}"#,
+        ] {
+            let problem: JsonProblem = serde_json::from_str(input).unwrap();
+            let written = serde_json::to_string(&problem).unwrap();
+            let (problem, fixes) =
+                parse_json_problem_with(&written, &ParseOptions::strict()).unwrap();
+            assert!(fixes.is_empty());
+            let offset = match problem {
+                JsonProblem::Qp { problem } => problem.offset,
+                JsonProblem::Lp { problem } => problem.offset,
+            };
+            assert_eq!(offset, -2.5);
+        }
+    }
+
+    #[test]
+    fn strict_reads_back_written_quadratic_constraints() {
+        let input = r#"{"kind":"qp","problem":{
+"quadratic":{"nrows":1,"ncols":1,"indptr":[0,1],"indices":[0],"data":[2.0]},
+"linear":[1.0],
+"quadratic_constraints":[{"matrix":{"nrows":1,"ncols":1,"indptr":[0,1],"indices":[0],"data":[2.0]},"linear":[0.0],"rhs":1.0}]}}"#;
+        let problem: JsonProblem = serde_json::from_str(input).unwrap();
+        let written = serde_json::to_string_pretty(&problem).unwrap();
+        let (problem, fixes) = parse_json_problem_with(&written, &ParseOptions::strict())
+            .unwrap_or_else(|diagnostic| panic!("{diagnostic}"));
+        assert!(fixes.is_empty());
+        let JsonProblem::Qp { problem } = problem else {
+            panic!("expected a QP");
+        };
+        assert_eq!(problem.quadratic_constraints.len(), 1);
+        assert_eq!(problem.quadratic_constraints[0].rhs, 1.0);
+
+        let input = r#"{"kind":"lp","problem":{"cost":[1.0],"quadratic_constraints":[]}}"#;
+        let diagnostic = parse_json_problem_with(input, &ParseOptions::strict()).unwrap_err();
+        assert_eq!(diagnostic.pointer, "/problem/quadratic_constraints");
+    }
+
+    #[test]
+    fn strict_accepts_names_but_not_unknown_lists() {
+        let input = r#"{"kind":"lp","problem":{"cost":[1.0,2.0],
+"names":{"variables":["x","y"]}}}"#;
+        let (problem, _) = parse_json_problem_with(input, &ParseOptions::strict()).unwrap();
+        let JsonProblem::Lp { problem } = problem else {
+            panic!("expected an LP");
+        };
+        assert_eq!(problem.names.variables, ["x", "y"]);
+
+        let input = r#"{"kind":"lp","problem":{"cost":[1.0],"names":{"columns":["x"]}}}"#;
+        let diagnostic = parse_json_problem_with(input, &ParseOptions::strict()).unwrap_err();
+        assert_eq!(diagnostic.pointer, "/problem/names/columns");
+    }
+
+    #[test]
+    fn maximizations_parse_in_both_modes() {
+        let input = r#"{"kind":"lp","problem":{"cost":[1.0],"sense":"Maximize"}}"#;
+        for options in [ParseOptions::strict(), ParseOptions::lenient()] {
+            let (problem, fixes) = parse_json_problem_with(input, &options).unwrap();
+            assert!(fixes.is_empty());
+            let JsonProblem::Lp { problem } = problem else {
+                panic!("expected an LP");
+            };
+            assert_eq!(problem.sense, ObjectiveSense::Maximize);
+        }
+    }
+}
@@ -0,0 +1,373 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use cvxrs_core::math::Scalar;
+use cvxrs_core::problem::{Bounds, CscMatrix, ObjectiveSense};
+
+use crate::JsonProblem;
+
+// Blocks larger than this are summarized by their dimensions instead of being
+// written out term by term.
+const MAX_EXPLICIT_VARS: usize = 10;
+const MAX_EXPLICIT_ROWS: usize = 12;
+
+struct View<'a> {
+    quadratic: Option<&'a CscMatrix<Scalar>>,
+    linear: &'a [Scalar],
+    offset: Scalar,
+    sense: ObjectiveSense,
+    equalities: Option<(&'a CscMatrix<Scalar>, &'a [Scalar])>,
+    inequalities: Option<(&'a CscMatrix<Scalar>, &'a [Scalar])>,
+    bounds: Option<&'a Bounds<Scalar>>,
+}
+
+impl<'a> View<'a> {
+    fn new(problem: &'a JsonProblem) -> Self {
+        match problem {
+            JsonProblem::Qp { problem } => Self {
+                quadratic: Some(&problem.quadratic),
+                linear: &problem.linear,
+                offset: problem.offset,
+                sense: problem.sense,
+                equalities: problem
+                    .equalities
+                    .as_ref()
+                    .map(|eq| (&eq.matrix, eq.rhs.as_slice())),
+                inequalities: problem
+                    .inequalities
+                    .as_ref()
+                    .map(|ineq| (&ineq.matrix, ineq.rhs.as_slice())),
+                bounds: problem.bounds.as_ref(),
+            },
+            JsonProblem::Lp { problem } => Self {
+                quadratic: None,
+                linear: &problem.cost,
+                offset: problem.offset,
+                sense: problem.sense,
+                equalities: problem
+                    .equalities
+                    .as_ref()
+                    .map(|eq| (&eq.matrix, eq.rhs.as_slice())),
+                inequalities: problem
+                    .inequalities
+                    .as_ref()
+                    .map(|ineq| (&ineq.matrix, ineq.rhs.as_slice())),
+                bounds: problem.bounds.as_ref(),
+            },
+        }
+    }
+
+    fn nvars(&self) -> usize {
+        self.linear.len()
+    }
+}
+
+// Renders the problem as a LaTeX min (or max) / s.t. display, meant to be pasted into
+// a document with amsmath and amssymb loaded.
+pub fn to_latex(problem: &JsonProblem) -> String {
+    let view = View::new(problem);
+    let n = view.nvars();
+    let explicit = n <= MAX_EXPLICIT_VARS;
+
+    let mut lines = Vec::new();
+    let objective = if explicit {
+        explicit_objective(&view)
+    } else {
+        summarized_objective(&view)
+    };
+    let operator = match view.sense {
+        ObjectiveSense::Minimize => "min",
+        ObjectiveSense::Maximize => "max",
+    };
+    lines.push(format!(
+        "\\{}_{{x \\in \\mathbb{{R}}^{{{}}}}} & {}",
+        operator, n, objective
+    ));
+
+    if let Some((matrix, rhs)) = view.equalities {
+        constraint_lines(&mut lines, matrix, rhs, "=", "A", "b", explicit);
+    }
+    if let Some((matrix, rhs)) = view.inequalities {
+        constraint_lines(&mut lines, matrix, rhs, "\\le", "G", "h", explicit);
+    }
+    if let Some(bounds) = view.bounds {
+        bound_lines(&mut lines, bounds, explicit);
+    }
+
+    let mut out = String::from("\\[\n\\begin{array}{ll}\n");
+    for (idx, line) in lines.iter().enumerate() {
+        let label = match idx {
+            0 => "",
+            1 => "\\text{s.t.} & ",
+            _ => "& ",
+        };
+        let separator = if idx + 1 < lines.len() { " \\\\" } else { "" };
+        let _ = writeln!(out, "{}{}{}", label, line, separator);
+    }
+    out.push_str("\\end{array}\n\\]\n");
+    out
+}
+
+fn explicit_objective(view: &View) -> String {
+    let mut out = String::new();
+    if let Some(quadratic) = view.quadratic {
+        // Both triangles contribute half of each off-diagonal product, so any
+        // storage of P (full, upper or lower) gives the same expression.
+        let mut terms: BTreeMap<(usize, usize), Scalar> = BTreeMap::new();
+        for (row, col, value) in entries(quadratic) {
+            let key = (row.min(col), row.max(col));
+            *terms.entry(key).or_insert(0.0) += 0.5 * value;
+        }
+        for ((i, j), value) in terms {
+            let monomial = if i == j {
+                format!("x_{{{}}}^{{2}}", i + 1)
+            } else {
+                format!("x_{{{}}} x_{{{}}}", i + 1, j + 1)
+            };
+            push_term(&mut out, value, &monomial);
+        }
+    }
+    for (idx, &value) in view.linear.iter().enumerate() {
+        push_term(&mut out, value, &format!("x_{{{}}}", idx + 1));
+    }
+    push_term(&mut out, view.offset, "");
+    if out.is_empty() {
+        out.push('0');
+    }
+    out
+}
+
+fn summarized_objective(view: &View) -> String {
+    let quadratic = view
+        .quadratic
+        .filter(|matrix| matrix.data.iter().any(|&value| value != 0.0));
+    let has_linear = view.linear.iter().any(|&value| value != 0.0);
+    let mut terms = Vec::new();
+    if quadratic.is_some() {
+        terms.push("\\tfrac{1}{2} x^{\\top} P x");
+    }
+    if has_linear {
+        terms.push("q^{\\top} x");
+    }
+    if view.offset != 0.0 {
+        terms.push("r");
+    }
+    match (terms.is_empty(), quadratic) {
+        (true, _) => String::from("0"),
+        (false, Some(matrix)) => format!("{} \\quad (P: {})", terms.join(" + "), shape(matrix)),
+        (false, None) => terms.join(" + "),
+    }
+}
+
+fn constraint_lines(
+    lines: &mut Vec<String>,
+    matrix: &CscMatrix<Scalar>,
+    rhs: &[Scalar],
+    relation: &str,
+    matrix_name: &str,
+    rhs_name: &str,
+    explicit: bool,
+) {
+    if explicit && matrix.nrows <= MAX_EXPLICIT_ROWS {
+        let mut rows = vec![String::new(); matrix.nrows];
+        for (row, col, value) in entries(matrix) {
+            push_term(&mut rows[row], value, &format!("x_{{{}}}", col + 1));
+        }
+        for (row, value) in rows.into_iter().zip(rhs) {
+            let row = if row.is_empty() {
+                String::from("0")
+            } else {
+                row
+            };
+            lines.push(format!("{} {} {}", row, relation, number(*value)));
+        }
+    } else {
+        lines.push(format!(
+            "{} x {} {} \\quad ({}: {})",
+            matrix_name,
+            relation,
+            rhs_name,
+            matrix_name,
+            shape(matrix)
+        ));
+    }
+}
+
+fn bound_lines(lines: &mut Vec<String>, bounds: &Bounds<Scalar>, explicit: bool) {
+    let pairs: Vec<(Scalar, Scalar)> = bounds
+        .lower
+        .iter()
+        .copied()
+        .zip(bounds.upper.iter().copied())
+        .collect();
+    let Some(&first) = pairs.first() else {
+        return;
+    };
+    if pairs.iter().all(|&pair| pair == first) {
+        if let Some(bound) = bound_expression("x", first) {
+            lines.push(bound);
+        }
+        return;
+    }
+    if explicit {
+        for (idx, &pair) in pairs.iter().enumerate() {
+            if let Some(bound) = bound_expression(&format!("x_{{{}}}", idx + 1), pair) {
+                lines.push(bound);
+            }
+        }
+        return;
+    }
+    let lower = pairs.iter().filter(|(lower, _)| lower.is_finite()).count();
+    let upper = pairs.iter().filter(|(_, upper)| upper.is_finite()).count();
+    lines.push(format!(
+        "l \\le x \\le u \\quad (\\text{{{} finite lower, {} finite upper}})",
+        lower, upper
+    ));
+}
+
+fn bound_expression(variable: &str, (lower, upper): (Scalar, Scalar)) -> Option<String> {
+    // Only infinite bounds are left out; a NaN is written so that it shows.
+    match (!lower.is_infinite(), !upper.is_infinite()) {
+        (false, false) => None,
+        _ if lower == upper => Some(format!("{} = {}", variable, number(lower))),
+        (true, false) => Some(format!("{} \\ge {}", variable, number(lower))),
+        (false, true) => Some(format!("{} \\le {}", variable, number(upper))),
+        (true, true) => Some(format!(
+            "{} \\le {} \\le {}",
+            number(lower),
+            variable,
+            number(upper)
+        )),
+    }
+}
+
+fn entries(matrix: &CscMatrix<Scalar>) -> impl Iterator<Item = (usize, usize, Scalar)> + '_ {
+    (0..matrix.ncols).flat_map(move |col| {
+        (matrix.indptr[col]..matrix.indptr[col + 1])
+            .map(move |idx| (matrix.indices[idx], col, matrix.data[idx]))
+    })
+}
+
+fn shape(matrix: &CscMatrix<Scalar>) -> String {
+    format!(
+        "{} \\times {}, \\ \\text{{{} nonzeros}}",
+        matrix.nrows,
+        matrix.ncols,
+        matrix.nnz()
+    )
+}
+
+fn push_term(out: &mut String, value: Scalar, monomial: &str) {
+    if value == 0.0 {
+        return;
+    }
+    let magnitude = value.abs();
+    // An empty monomial is a constant, written out even when it is 1.
+    let coefficient = if monomial.is_empty() {
+        number(magnitude)
+    } else if magnitude == 1.0 {
+        String::new()
+    } else {
+        format!("{} ", number(magnitude))
+    };
+    match (out.is_empty(), value < 0.0) {
+        (true, false) => {}
+        (true, true) => out.push('-'),
+        (false, false) => out.push_str(" + "),
+        (false, true) => out.push_str(" - "),
+    }
+    out.push_str(&coefficient);
+    out.push_str(monomial);
+}
+
+fn number(value: Scalar) -> String {
+    if value.is_nan() {
+        return String::from("\\text{NaN}");
+    }
+    if value.is_infinite() {
+        return String::from(if value > 0.0 { "\\infty" } else { "-\\infty" });
+    }
+    let magnitude = value.abs();
+    if value == 0.0 || (1e-4..1e6).contains(&magnitude) {
+        return format!("{}", value);
+    }
+    let formatted = format!("{:e}", value);
+    match formatted.split_once('e') {
+        Some((mantissa, exponent)) => format!("{} \\cdot 10^{{{}}}", mantissa, exponent),
+        None => formatted,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cvxrs_core::problem::{InequalityConstraints, Names, ProblemLP, ProblemQP};
+
+    #[test]
+    fn writes_small_problems_term_by_term() {
+        let problem = JsonProblem::Qp {
+            problem: ProblemQP {
+                quadratic: CscMatrix {
+                    nrows: 2,
+                    ncols: 2,
+                    indptr: vec![0, 2, 4],
+                    indices: vec![0, 1, 0, 1],
+                    data: vec![4.0, 1.0, 1.0, 2.0],
+                },
+                linear: vec![1.0, -1.0],
+                equalities: None,
+                inequalities: Some(InequalityConstraints {
+                    matrix: CscMatrix {
+                        nrows: 1,
+                        ncols: 2,
+                        indptr: vec![0, 1, 2],
+                        indices: vec![0, 0],
+                        data: vec![1.0, 2.5e-7],
+                    },
+                    rhs: vec![3.0],
+                }),
+                bounds: Some(Bounds {
+                    lower: vec![0.0, 0.0],
+                    upper: vec![Scalar::INFINITY, 1.0],
+                }),
+                groups: Vec::new(),
+                integrality: Vec::new(),
+                names: Names::default(),
+                sense: ObjectiveSense::Minimize,
+                offset: -1.5,
+                quadratic_constraints: Vec::new(),
+            },
+        };
+        let latex = to_latex(&problem);
+        assert!(
+            latex.contains("& 2 x_{1}^{2} + x_{1} x_{2} + x_{2}^{2} + x_{1} - x_{2} - 1.5 \\\\")
+        );
+        assert!(latex.contains("\\text{s.t.} & x_{1} + 2.5 \\cdot 10^{-7} x_{2} \\le 3 \\\\"));
+        assert!(latex.contains("& x_{1} \\ge 0 \\\\"));
+        assert!(latex.contains("& 0 \\le x_{2} \\le 1\n\\end{array}"));
+    }
+
+    #[test]
+    fn writes_non_finite_values_as_text() {
+        let problem = JsonProblem::Lp {
+            problem: ProblemLP {
+                cost: vec![Scalar::NAN, 1.0],
+                inequalities: None,
+                equalities: None,
+                bounds: Some(Bounds {
+                    lower: vec![Scalar::NAN, Scalar::NEG_INFINITY],
+                    upper: vec![1.0, 2.0e9],
+                }),
+                groups: Vec::new(),
+                integrality: Vec::new(),
+                names: Names::default(),
+                sense: ObjectiveSense::Minimize,
+                offset: 0.0,
+            },
+        };
+        let latex = to_latex(&problem);
+        assert!(latex.contains("& \\text{NaN} x_{1} + x_{2}"));
+        assert!(latex.contains("& \\text{NaN} \\le x_{1} \\le 1"));
+        assert!(latex.contains("& x_{2} \\le 2 \\cdot 10^{9}"));
+    }
+}
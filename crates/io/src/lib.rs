@@ -9,7 +9,34 @@ use std::fs::{self, File};
 use std::io::{BufReader, BufWriter, Read, Write};
 use std::path::Path;
 
-#[derive(Debug, Serialize, Deserialize)]
+pub mod checkpoint;
+pub mod conic;
+pub mod diagnostics;
+pub mod latex;
+#[cfg(feature = "matlab")]
+pub mod matlab;
+pub mod mps;
+pub mod repair;
+pub mod tabular;
+
+pub use checkpoint::{problem_fingerprint, read_checkpoint, write_checkpoint, Checkpoint};
+pub use conic::{
+    conic_residuals, from_clarabel, from_scs, read_clarabel_json, read_conic_solution,
+    read_scs_json, to_clarabel, to_scs, write_clarabel_json, write_scs_json, ClarabelCone,
+    ClarabelMatrix, ClarabelProblem, ConicResiduals, ConicSolution, ScsCone, ScsData, ScsMatrix,
+    ScsProblem,
+};
+pub use diagnostics::{
+    locate_pointer, parse_json_problem, parse_json_problem_with, JsonDiagnostic,
+};
+pub use latex::to_latex;
+#[cfg(feature = "matlab")]
+pub use matlab::{parse_mat, read_mat_problem, read_mat_problem_with, MatModel};
+pub use mps::{parse_mps, read_mps_problem, read_mps_problem_with, MpsModel};
+pub use repair::{ParseFix, ParseOptions};
+pub use tabular::{parse_tabular, read_tabular_problem, read_tabular_problem_with};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "kind", rename_all = "lowercase")]
 pub enum JsonProblem {
     Qp { problem: ProblemQP<Scalar> },
@@ -17,6 +44,13 @@ pub enum JsonProblem {
 }
 
 pub fn read_json_problem<P: AsRef<Path>>(path: P) -> Result<JsonProblem> {
+    read_json_problem_with(path, &ParseOptions::default()).map(|(problem, _)| problem)
+}
+
+pub fn read_json_problem_with<P: AsRef<Path>>(
+    path: P,
+    options: &ParseOptions,
+) -> Result<(JsonProblem, Vec<ParseFix>)> {
     let path = path.as_ref();
     let file = File::open(path).with_context(|| format!("failed to open {:?}", path))?;
     let mut reader = BufReader::new(file);
@@ -25,15 +59,15 @@ pub fn read_json_problem<P: AsRef<Path>>(path: P) -> Result<JsonProblem> {
         .read_to_string(&mut contents)
         .with_context(|| format!("failed to read {:?}", path))?;
 
-    match serde_json::from_str::<JsonProblem>(&contents) {
-        Ok(problem) => Ok(problem),
-        Err(parse_err) => {
+    match parse_json_problem_with(&contents, options) {
+        Ok(parsed) => Ok(parsed),
+        Err(diagnostic) => {
             if serde_json::from_str::<Solution<Scalar>>(&contents).is_ok() {
                 Err(anyhow!(
                     "JSON file contains a solver solution, but the GUI expects a cvxrs problem (with a 'kind' field)."
                 ))
             } else {
-                Err(parse_err).context("failed to parse JSON problem")
+                Err(diagnostic).context("failed to parse JSON problem")
             }
         }
     }
@@ -65,9 +99,6 @@ pub fn write_solution<P: AsRef<Path>>(path: P, solution: &Solution<Scalar>) -> R
     Ok(())
 }
 
-pub fn read_mps_problem<P: AsRef<Path>>(_path: P) -> Result<()> {
-    anyhow::bail!("MPS parsing is not yet implemented.");
-}
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -80,4 +111,19 @@ mod tests {
         serde_json::to_writer(&mut buffer, &parsed).unwrap();
         assert!(!buffer.is_empty());
     }
+
+    #[test]
+    fn offset_is_optional_in_json() {
+        let input = r#"{"kind":"lp","problem":{"cost":[1.0],"inequalities":null,"equalities":null,"bounds":null,"offset":-2.5}}"#;
+        let JsonProblem::Lp { mut problem } = serde_json::from_str(input).unwrap() else {
+            panic!("expected an LP");
+        };
+        assert_eq!(problem.offset, -2.5);
+        let written = serde_json::to_string(&problem).unwrap();
+        assert!(written.contains(r#""offset":-2.5"#));
+        // A zero offset is left out, so older files read and write unchanged.
+        problem.offset = 0.0;
+        let written = serde_json::to_string(&problem).unwrap();
+        assert!(!written.contains("offset"));
+    }
 }
@@ -0,0 +1,556 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+
+use anyhow::{anyhow, bail, Context, Result};
+use cvxrs_core::math::Scalar;
+use cvxrs_core::problem::{CscMatrix, Names, ObjectiveSense, ProblemLP, ProblemQP};
+use flate2::read::ZlibDecoder;
+
+use crate::mps::{csc_from_columns, split_ranged_rows, symmetric_columns};
+use crate::repair::{repair_parsed, ParseFix, ParseOptions};
+use crate::JsonProblem;
+
+const HEADER_LEN: usize = 128;
+// OSQP-style collections store missing bounds as large finite values.
+const INFINITY_THRESHOLD: Scalar = 1e20;
+
+const MI_INT8: u32 = 1;
+const MI_UINT8: u32 = 2;
+const MI_INT16: u32 = 3;
+const MI_UINT16: u32 = 4;
+const MI_INT32: u32 = 5;
+const MI_UINT32: u32 = 6;
+const MI_SINGLE: u32 = 7;
+const MI_DOUBLE: u32 = 9;
+const MI_INT64: u32 = 12;
+const MI_UINT64: u32 = 13;
+const MI_MATRIX: u32 = 14;
+const MI_COMPRESSED: u32 = 15;
+
+const MX_SPARSE_CLASS: u32 = 5;
+const MX_DOUBLE_CLASS: u32 = 6;
+const MX_UINT64_CLASS: u32 = 15;
+const COMPLEX_FLAG: u32 = 0x0800;
+
+#[derive(Debug)]
+pub struct MatModel {
+    pub problem: JsonProblem,
+}
+
+#[derive(Debug)]
+enum Array {
+    Dense {
+        nrows: usize,
+        ncols: usize,
+        values: Vec<Scalar>,
+    },
+    Sparse(CscMatrix<Scalar>),
+}
+
+impl Array {
+    fn shape(&self) -> (usize, usize) {
+        match self {
+            Array::Dense { nrows, ncols, .. } => (*nrows, *ncols),
+            Array::Sparse(matrix) => (matrix.nrows, matrix.ncols),
+        }
+    }
+
+    fn into_vector(self, name: &str) -> Result<Vec<Scalar>> {
+        match self {
+            Array::Dense {
+                nrows,
+                ncols,
+                values,
+            } if nrows <= 1 || ncols <= 1 => Ok(values),
+            Array::Sparse(matrix) if matrix.nrows <= 1 || matrix.ncols <= 1 => {
+                let mut values = vec![0.0; matrix.nrows * matrix.ncols];
+                for col in 0..matrix.ncols {
+                    for idx in matrix.indptr[col]..matrix.indptr[col + 1] {
+                        values[col * matrix.nrows + matrix.indices[idx]] = matrix.data[idx];
+                    }
+                }
+                Ok(values)
+            }
+            other => {
+                let (nrows, ncols) = other.shape();
+                bail!("'{}' must be a vector but is {}x{}", name, nrows, ncols)
+            }
+        }
+    }
+
+    fn into_columns(self) -> Vec<Vec<(usize, Scalar)>> {
+        match self {
+            Array::Dense {
+                nrows,
+                ncols,
+                values,
+            } => (0..ncols)
+                .map(|col| {
+                    values[col * nrows..(col + 1) * nrows]
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, value)| **value != 0.0)
+                        .map(|(row, value)| (row, *value))
+                        .collect()
+                })
+                .collect(),
+            Array::Sparse(matrix) => (0..matrix.ncols)
+                .map(|col| {
+                    (matrix.indptr[col]..matrix.indptr[col + 1])
+                        .map(|idx| (matrix.indices[idx], matrix.data[idx]))
+                        .collect()
+                })
+                .collect(),
+        }
+    }
+}
+
+struct Cursor<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+    big_endian: bool,
+}
+
+impl<'a> Cursor<'a> {
+    fn new(bytes: &'a [u8], big_endian: bool) -> Self {
+        Self {
+            bytes,
+            pos: 0,
+            big_endian,
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.pos >= self.bytes.len()
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        let end = self
+            .pos
+            .checked_add(len)
+            .filter(|&end| end <= self.bytes.len())
+            .ok_or_else(|| anyhow!("unexpected end of data at byte {}", self.pos))?;
+        let slice = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        let bytes: [u8; 4] = self.take(4)?.try_into().expect("four bytes");
+        Ok(if self.big_endian {
+            u32::from_be_bytes(bytes)
+        } else {
+            u32::from_le_bytes(bytes)
+        })
+    }
+
+    // Returns the data type and payload of the next element, handling the
+    // small element format and the padding to eight-byte boundaries.
+    fn element(&mut self) -> Result<(u32, &'a [u8])> {
+        let tag = self.u32()?;
+        if tag >> 16 != 0 {
+            let len = (tag >> 16) as usize;
+            let payload = self.take(4)?;
+            if len > 4 {
+                bail!("small data element claims {} bytes", len);
+            }
+            return Ok((tag & 0xffff, &payload[..len]));
+        }
+        let len = self.u32()? as usize;
+        let payload = self.take(len)?;
+        if tag != MI_COMPRESSED {
+            let padding = (8 - len % 8) % 8;
+            self.take(padding.min(self.bytes.len() - self.pos))?;
+        }
+        Ok((tag, payload))
+    }
+}
+
+pub fn read_mat_problem<P: AsRef<Path>>(path: P) -> Result<MatModel> {
+    let path = path.as_ref();
+    let bytes = fs::read(path).with_context(|| format!("failed to read {:?}", path))?;
+    parse_mat(&bytes).with_context(|| format!("failed to parse MAT file {:?}", path))
+}
+
+pub fn read_mat_problem_with<P: AsRef<Path>>(
+    path: P,
+    options: &ParseOptions,
+) -> Result<(MatModel, Vec<ParseFix>)> {
+    let mut model = read_mat_problem(path)?;
+    let fixes = repair_parsed(&mut model.problem, options)?;
+    Ok((model, fixes))
+}
+
+pub fn parse_mat(bytes: &[u8]) -> Result<MatModel> {
+    let variables = read_variables(bytes)?;
+    build_model(variables)
+}
+
+fn read_variables(bytes: &[u8]) -> Result<HashMap<String, Array>> {
+    if bytes.len() < HEADER_LEN {
+        bail!("file is too short for a MAT header");
+    }
+    let big_endian = match &bytes[126..128] {
+        b"IM" => false,
+        b"MI" => true,
+        _ => bail!("not a MATLAB v5/v7 file"),
+    };
+    let version = [bytes[124], bytes[125]];
+    let version = if big_endian {
+        u16::from_be_bytes(version)
+    } else {
+        u16::from_le_bytes(version)
+    };
+    if version == 0x0200 {
+        bail!("MAT v7.3 (HDF5) files are not supported; save with -v7");
+    }
+
+    let mut variables = HashMap::new();
+    let mut cursor = Cursor::new(&bytes[HEADER_LEN..], big_endian);
+    while !cursor.is_empty() {
+        let (kind, payload) = cursor.element()?;
+        let decompressed;
+        let (kind, payload) = if kind == MI_COMPRESSED {
+            let mut buffer = Vec::new();
+            ZlibDecoder::new(payload)
+                .read_to_end(&mut buffer)
+                .context("failed to inflate compressed element")?;
+            decompressed = buffer;
+            Cursor::new(&decompressed, big_endian).element()?
+        } else {
+            (kind, payload)
+        };
+        if kind != MI_MATRIX {
+            continue;
+        }
+        if let Some((name, array)) = read_matrix(payload, big_endian)? {
+            variables.insert(name, array);
+        }
+    }
+    Ok(variables)
+}
+
+fn read_matrix(payload: &[u8], big_endian: bool) -> Result<Option<(String, Array)>> {
+    if payload.is_empty() {
+        return Ok(None);
+    }
+    let mut cursor = Cursor::new(payload, big_endian);
+    let (_, flags) = cursor.element()?;
+    let flags = Cursor::new(flags, big_endian).u32()?;
+    let class = flags & 0xff;
+    let (dims_kind, dims) = cursor.element()?;
+    let dims = to_indices(dims_kind, dims, big_endian)?;
+    let (_, name) = cursor.element()?;
+    let name = String::from_utf8_lossy(name).into_owned();
+
+    let supported =
+        class == MX_SPARSE_CLASS || (MX_DOUBLE_CLASS..=MX_UINT64_CLASS).contains(&class);
+    if !supported {
+        return Ok(None);
+    }
+    if flags & COMPLEX_FLAG != 0 {
+        bail!("'{}' is complex", name);
+    }
+    let [nrows, ncols] = dims[..] else {
+        bail!(
+            "'{}' has {} dimensions; only 2-D arrays are supported",
+            name,
+            dims.len()
+        );
+    };
+
+    let array = if class == MX_SPARSE_CLASS {
+        let (ir_kind, ir) = cursor.element()?;
+        let (jc_kind, jc) = cursor.element()?;
+        let (pr_kind, pr) = cursor.element()?;
+        let indptr = to_indices(jc_kind, jc, big_endian)?;
+        if indptr.len() != ncols + 1 {
+            bail!(
+                "'{}' has {} column pointers for {} columns",
+                name,
+                indptr.len(),
+                ncols
+            );
+        }
+        let nnz = indptr[ncols];
+        let mut indices = to_indices(ir_kind, ir, big_endian)?;
+        let mut data = to_scalars(pr_kind, pr, big_endian)?;
+        if indices.len() < nnz || data.len() < nnz {
+            bail!("'{}' stores fewer than {} nonzeros", name, nnz);
+        }
+        indices.truncate(nnz);
+        data.truncate(nnz);
+        let matrix = CscMatrix {
+            nrows,
+            ncols,
+            indptr,
+            indices,
+            data,
+        };
+        matrix
+            .validate()
+            .with_context(|| format!("'{}' is not a valid sparse matrix", name))?;
+        Array::Sparse(matrix)
+    } else {
+        let (pr_kind, pr) = cursor.element()?;
+        let values = to_scalars(pr_kind, pr, big_endian)?;
+        if values.len() != nrows * ncols {
+            bail!(
+                "'{}' holds {} values for a {}x{} array",
+                name,
+                values.len(),
+                nrows,
+                ncols
+            );
+        }
+        Array::Dense {
+            nrows,
+            ncols,
+            values,
+        }
+    };
+    Ok(Some((name, array)))
+}
+
+fn to_scalars(kind: u32, bytes: &[u8], big_endian: bool) -> Result<Vec<Scalar>> {
+    macro_rules! convert {
+        ($ty:ty) => {
+            bytes
+                .chunks_exact(std::mem::size_of::<$ty>())
+                .map(|chunk| {
+                    let chunk = chunk.try_into().expect("chunk size");
+                    let value = if big_endian {
+                        <$ty>::from_be_bytes(chunk)
+                    } else {
+                        <$ty>::from_le_bytes(chunk)
+                    };
+                    value as Scalar
+                })
+                .collect()
+        };
+    }
+    Ok(match kind {
+        MI_INT8 => convert!(i8),
+        MI_UINT8 => convert!(u8),
+        MI_INT16 => convert!(i16),
+        MI_UINT16 => convert!(u16),
+        MI_INT32 => convert!(i32),
+        MI_UINT32 => convert!(u32),
+        MI_SINGLE => convert!(f32),
+        MI_DOUBLE => convert!(f64),
+        MI_INT64 => convert!(i64),
+        MI_UINT64 => convert!(u64),
+        other => bail!("unsupported numeric data type {}", other),
+    })
+}
+
+fn to_indices(kind: u32, bytes: &[u8], big_endian: bool) -> Result<Vec<usize>> {
+    to_scalars(kind, bytes, big_endian)?
+        .into_iter()
+        .map(|value| {
+            if value >= 0.0 && value.fract() == 0.0 {
+                Ok(value as usize)
+            } else {
+                Err(anyhow!("invalid index {}", value))
+            }
+        })
+        .collect()
+}
+
+fn build_model(mut variables: HashMap<String, Array>) -> Result<MatModel> {
+    let linear = variables
+        .remove("q")
+        .ok_or_else(|| anyhow!("variable 'q' is missing"))?
+        .into_vector("q")?;
+    let n = linear.len();
+
+    let (equalities, inequalities) = match variables.remove("A") {
+        Some(a) => {
+            let (m, ncols) = a.shape();
+            if ncols != n {
+                bail!("'A' has {} columns but 'q' has {} entries", ncols, n);
+            }
+            let mut bound = |name: &str, default: Scalar| -> Result<Vec<Scalar>> {
+                let values = match variables.remove(name) {
+                    Some(array) => array.into_vector(name)?,
+                    None => vec![default; m],
+                };
+                if values.len() != m {
+                    bail!(
+                        "'{}' has {} entries but 'A' has {} rows",
+                        name,
+                        values.len(),
+                        m
+                    );
+                }
+                Ok(values
+                    .into_iter()
+                    .map(|value| {
+                        if value.abs() >= INFINITY_THRESHOLD {
+                            value.signum() * Scalar::INFINITY
+                        } else {
+                            value
+                        }
+                    })
+                    .collect())
+            };
+            let lower = bound("l", Scalar::NEG_INFINITY)?;
+            let upper = bound("u", Scalar::INFINITY)?;
+            split_ranged_rows(&lower, &upper, &a.into_columns())
+        }
+        None => (None, None),
+    };
+
+    let offset = match variables.remove("r") {
+        Some(array) => array.into_vector("r")?.first().copied().unwrap_or(0.0),
+        None => 0.0,
+    };
+
+    let quadratic = match variables.remove("P") {
+        Some(p) if p.shape() != (n, n) => {
+            let (nrows, ncols) = p.shape();
+            bail!("'P' is {}x{} but 'q' has {} entries", nrows, ncols, n);
+        }
+        Some(p) => Some(symmetric_columns(p.into_columns())),
+        None => None,
+    };
+
+    let problem = match quadratic {
+        Some(columns) if columns.iter().any(|column| !column.is_empty()) => {
+            let problem = ProblemQP {
+                quadratic: csc_from_columns(n, columns),
+                linear,
+                inequalities,
+                equalities,
+                bounds: None,
+                groups: Vec::new(),
+                integrality: Vec::new(),
+                names: Names::default(),
+                sense: ObjectiveSense::Minimize,
+                offset,
+                quadratic_constraints: Vec::new(),
+            };
+            problem.validate()?;
+            JsonProblem::Qp { problem }
+        }
+        _ => {
+            let problem = ProblemLP {
+                cost: linear,
+                inequalities,
+                equalities,
+                bounds: None,
+                groups: Vec::new(),
+                integrality: Vec::new(),
+                names: Names::default(),
+                sense: ObjectiveSense::Minimize,
+                offset,
+            };
+            problem.validate()?;
+            JsonProblem::Lp { problem }
+        }
+    };
+    Ok(MatModel { problem })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::write::ZlibEncoder;
+    use flate2::Compression;
+    use std::io::Write;
+
+    fn element(kind: u32, payload: &[u8]) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&kind.to_le_bytes());
+        bytes.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(payload);
+        bytes.resize(bytes.len() + (8 - payload.len() % 8) % 8, 0);
+        bytes
+    }
+
+    fn header(class: u32, name: &str, nrows: i32, ncols: i32) -> Vec<u8> {
+        let mut bytes = element(
+            MI_UINT32,
+            &[class.to_le_bytes(), 0u32.to_le_bytes()].concat(),
+        );
+        bytes.extend(element(
+            MI_INT32,
+            &[nrows.to_le_bytes(), ncols.to_le_bytes()].concat(),
+        ));
+        bytes.extend(element(MI_INT8, name.as_bytes()));
+        bytes
+    }
+
+    fn doubles(values: &[f64]) -> Vec<u8> {
+        values
+            .iter()
+            .flat_map(|value| value.to_le_bytes())
+            .collect()
+    }
+
+    fn ints(values: &[i32]) -> Vec<u8> {
+        values
+            .iter()
+            .flat_map(|value| value.to_le_bytes())
+            .collect()
+    }
+
+    fn dense(name: &str, values: &[f64]) -> Vec<u8> {
+        let mut payload = header(MX_DOUBLE_CLASS, name, values.len() as i32, 1);
+        payload.extend(element(MI_DOUBLE, &doubles(values)));
+        element(MI_MATRIX, &payload)
+    }
+
+    fn sparse(name: &str, nrows: i32, ncols: i32, ir: &[i32], jc: &[i32], pr: &[f64]) -> Vec<u8> {
+        let mut payload = header(MX_SPARSE_CLASS, name, nrows, ncols);
+        payload.extend(element(MI_INT32, &ints(ir)));
+        payload.extend(element(MI_INT32, &ints(jc)));
+        payload.extend(element(MI_DOUBLE, &doubles(pr)));
+        element(MI_MATRIX, &payload)
+    }
+
+    fn compressed(element: &[u8]) -> Vec<u8> {
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(element).unwrap();
+        let payload = encoder.finish().unwrap();
+        let mut bytes = MI_COMPRESSED.to_le_bytes().to_vec();
+        bytes.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+        bytes.extend(payload);
+        bytes
+    }
+
+    #[test]
+    fn reads_osqp_layout() {
+        let mut bytes = vec![b' '; HEADER_LEN];
+        bytes[124..126].copy_from_slice(&0x0100u16.to_le_bytes());
+        bytes[126..128].copy_from_slice(b"IM");
+        // Upper triangle of P = [4 1; 1 2].
+        bytes.extend(sparse("P", 2, 2, &[0, 0, 1], &[0, 1, 3], &[4.0, 1.0, 2.0]));
+        bytes.extend(dense("q", &[1.0, 1.0]));
+        bytes.extend(compressed(&sparse(
+            "A",
+            3,
+            2,
+            &[0, 1, 0, 2],
+            &[0, 2, 4],
+            &[1.0, 1.0, 1.0, 1.0],
+        )));
+        bytes.extend(dense("l", &[1.0, 0.0, -1e30]));
+        bytes.extend(dense("u", &[1.0, 0.7, 0.7]));
+        bytes.extend(dense("r", &[2.5]));
+
+        let model = parse_mat(&bytes).unwrap();
+        let JsonProblem::Qp { problem } = model.problem else {
+            panic!("expected a QP");
+        };
+        assert_eq!(problem.offset, 2.5);
+        assert_eq!(problem.quadratic.indptr, vec![0, 2, 4]);
+        assert_eq!(problem.quadratic.data, vec![4.0, 1.0, 1.0, 2.0]);
+        let equalities = problem.equalities.unwrap();
+        assert_eq!(equalities.rhs, vec![1.0]);
+        let inequalities = problem.inequalities.unwrap();
+        assert_eq!(inequalities.rhs, vec![0.7, 0.0, 0.7]);
+    }
+}
@@ -0,0 +1,617 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+
+use anyhow::{anyhow, bail, Context, Result};
+use cvxrs_core::math::Scalar;
+use cvxrs_core::problem::{
+    Bounds, CscMatrix, LinearConstraints, Names, ObjectiveSense, ProblemLP, ProblemQP, RowSense,
+    SplitRows,
+};
+
+use crate::repair::{repair_parsed, ParseFix, ParseOptions};
+use crate::JsonProblem;
+
+#[derive(Debug)]
+pub struct MpsModel {
+    pub name: String,
+    pub problem: JsonProblem,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Section {
+    Header,
+    Rows,
+    Columns,
+    Rhs,
+    Ranges,
+    Bounds,
+    Quadratic { lower_triangle: bool },
+    // The sense on the line after an OBJSENSE header, in free MPS.
+    ObjSense,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum RowKind {
+    Equal,
+    Less,
+    Greater,
+}
+
+struct Row {
+    kind: RowKind,
+    rhs: Scalar,
+    range: Option<Scalar>,
+}
+
+#[derive(Default)]
+struct Builder {
+    name: String,
+    objective: Option<String>,
+    free_rows: Vec<String>,
+    rows: Vec<Row>,
+    row_index: HashMap<String, usize>,
+    columns: Vec<Vec<(usize, Scalar)>>,
+    column_index: HashMap<String, usize>,
+    cost: Vec<Scalar>,
+    lower: Vec<Scalar>,
+    upper: Vec<Scalar>,
+    integrality: Vec<bool>,
+    // Between the INTORG and INTEND markers of the COLUMNS section.
+    integer_section: bool,
+    quadratic: BTreeMap<(usize, usize), Scalar>,
+    offset: Scalar,
+    sense: ObjectiveSense,
+}
+
+pub fn read_mps_problem<P: AsRef<Path>>(path: P) -> Result<MpsModel> {
+    let path = path.as_ref();
+    let contents =
+        fs::read_to_string(path).with_context(|| format!("failed to read {:?}", path))?;
+    parse_mps(&contents).with_context(|| format!("failed to parse MPS file {:?}", path))
+}
+
+pub fn read_mps_problem_with<P: AsRef<Path>>(
+    path: P,
+    options: &ParseOptions,
+) -> Result<(MpsModel, Vec<ParseFix>)> {
+    let mut model = read_mps_problem(path)?;
+    let fixes = repair_parsed(&mut model.problem, options)?;
+    Ok((model, fixes))
+}
+
+pub fn parse_mps(contents: &str) -> Result<MpsModel> {
+    let mut builder = Builder::default();
+    let mut section = Section::Header;
+    for (number, line) in contents.lines().enumerate() {
+        if line.trim().is_empty() || line.starts_with('*') {
+            continue;
+        }
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if !line.starts_with(char::is_whitespace) {
+            section = match fields[0] {
+                "NAME" => {
+                    builder.name = fields.get(1).copied().unwrap_or_default().to_owned();
+                    Section::Header
+                }
+                "ROWS" => Section::Rows,
+                "COLUMNS" => Section::Columns,
+                "RHS" => Section::Rhs,
+                "RANGES" => Section::Ranges,
+                "BOUNDS" => Section::Bounds,
+                "QUADOBJ" => Section::Quadratic {
+                    lower_triangle: true,
+                },
+                "QMATRIX" | "QSECTION" => Section::Quadratic {
+                    lower_triangle: false,
+                },
+                "OBJSENSE" => match fields.get(1) {
+                    Some(sense) => {
+                        builder.sense = objective_sense(sense)
+                            .map_err(|err| anyhow!("line {}: {}", number + 1, err))?;
+                        Section::Header
+                    }
+                    None => Section::ObjSense,
+                },
+                "ENDATA" => break,
+                other => bail!("line {}: unknown section {}", number + 1, other),
+            };
+            continue;
+        }
+        builder
+            .entry(section, &fields)
+            .map_err(|err| anyhow!("line {}: {}", number + 1, err))?;
+    }
+    builder.finish()
+}
+
+impl Builder {
+    fn entry(&mut self, section: Section, fields: &[&str]) -> Result<()> {
+        match section {
+            Section::Header => {}
+            Section::ObjSense => self.sense = objective_sense(fields[0])?,
+            Section::Rows => self.row(fields)?,
+            Section::Columns => self.column(fields)?,
+            Section::Rhs => {
+                for (row, value) in pairs(fields)? {
+                    if Some(row) == self.objective.as_deref() {
+                        self.offset = -value;
+                    } else if let Some(&idx) = self.row_index.get(row) {
+                        self.rows[idx].rhs = value;
+                    } else if !self.free_rows.iter().any(|name| name == row) {
+                        bail!("unknown row {}", row);
+                    }
+                }
+            }
+            Section::Ranges => {
+                for (row, value) in pairs(fields)? {
+                    let idx = *self
+                        .row_index
+                        .get(row)
+                        .ok_or_else(|| anyhow!("unknown row {}", row))?;
+                    self.rows[idx].range = Some(value);
+                }
+            }
+            Section::Bounds => self.bound(fields)?,
+            Section::Quadratic { lower_triangle } => {
+                if fields.len() != 3 {
+                    bail!("expected two columns and a value");
+                }
+                let i = self.column_position(fields[0])?;
+                let j = self.column_position(fields[1])?;
+                let value = number(fields[2])?;
+                *self.quadratic.entry((i, j)).or_default() += value;
+                if lower_triangle && i != j {
+                    *self.quadratic.entry((j, i)).or_default() += value;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn row(&mut self, fields: &[&str]) -> Result<()> {
+        let [kind, name] = fields else {
+            bail!("expected a row type and a name");
+        };
+        let kind = match *kind {
+            "N" => {
+                if self.objective.is_none() {
+                    self.objective = Some((*name).to_owned());
+                } else {
+                    self.free_rows.push((*name).to_owned());
+                }
+                return Ok(());
+            }
+            "E" => RowKind::Equal,
+            "L" => RowKind::Less,
+            "G" => RowKind::Greater,
+            other => bail!("unknown row type {}", other),
+        };
+        self.row_index.insert((*name).to_owned(), self.rows.len());
+        self.rows.push(Row {
+            kind,
+            rhs: 0.0,
+            range: None,
+        });
+        Ok(())
+    }
+
+    fn column(&mut self, fields: &[&str]) -> Result<()> {
+        if fields.get(1) == Some(&"'MARKER'") {
+            match fields.get(2).copied() {
+                Some("'INTORG'") => self.integer_section = true,
+                Some("'INTEND'") => self.integer_section = false,
+                _ => bail!("expected an INTORG or INTEND marker"),
+            }
+            return Ok(());
+        }
+        let column = fields
+            .first()
+            .ok_or_else(|| anyhow!("missing column name"))?;
+        let idx = match self.column_index.get(*column) {
+            Some(&idx) => idx,
+            None => {
+                let idx = self.columns.len();
+                self.column_index.insert((*column).to_owned(), idx);
+                self.columns.push(Vec::new());
+                self.cost.push(0.0);
+                self.lower.push(0.0);
+                self.upper.push(Scalar::INFINITY);
+                self.integrality.push(self.integer_section);
+                idx
+            }
+        };
+        for (row, value) in pairs(fields)? {
+            if Some(row) == self.objective.as_deref() {
+                self.cost[idx] += value;
+            } else if let Some(&row) = self.row_index.get(row) {
+                self.columns[idx].push((row, value));
+            } else if !self.free_rows.iter().any(|name| name == row) {
+                bail!("unknown row {}", row);
+            }
+        }
+        Ok(())
+    }
+
+    fn bound(&mut self, fields: &[&str]) -> Result<()> {
+        if fields.len() < 3 {
+            bail!("expected a bound type, a set name and a column");
+        }
+        let idx = self.column_position(fields[2])?;
+        let value = fields.get(3).map(|field| number(field)).transpose()?;
+        let required = || value.ok_or_else(|| anyhow!("bound {} needs a value", fields[0]));
+        match fields[0] {
+            "UP" | "UI" => {
+                let value = required()?;
+                self.integrality[idx] |= fields[0] == "UI";
+                if value < 0.0 && self.lower[idx] == 0.0 {
+                    self.lower[idx] = Scalar::NEG_INFINITY;
+                }
+                self.upper[idx] = value;
+            }
+            "LO" | "LI" => {
+                self.lower[idx] = required()?;
+                self.integrality[idx] |= fields[0] == "LI";
+            }
+            "FX" => {
+                let value = required()?;
+                self.lower[idx] = value;
+                self.upper[idx] = value;
+            }
+            "FR" => {
+                self.lower[idx] = Scalar::NEG_INFINITY;
+                self.upper[idx] = Scalar::INFINITY;
+            }
+            "MI" => self.lower[idx] = Scalar::NEG_INFINITY,
+            "PL" => self.upper[idx] = Scalar::INFINITY,
+            "BV" => {
+                self.lower[idx] = 0.0;
+                self.upper[idx] = 1.0;
+                self.integrality[idx] = true;
+            }
+            other => bail!("unsupported bound type {}", other),
+        }
+        Ok(())
+    }
+
+    fn column_position(&self, name: &str) -> Result<usize> {
+        self.column_index
+            .get(name)
+            .copied()
+            .ok_or_else(|| anyhow!("unknown column {}", name))
+    }
+
+    fn finish(self) -> Result<MpsModel> {
+        let n = self.columns.len();
+        let (lower, upper): (Vec<Scalar>, Vec<Scalar>) = self
+            .rows
+            .iter()
+            .map(|row| match (row.kind, row.range) {
+                (RowKind::Equal, None) => (row.rhs, row.rhs),
+                (RowKind::Equal, Some(range)) if range >= 0.0 => (row.rhs, row.rhs + range),
+                (RowKind::Equal, Some(range)) => (row.rhs + range, row.rhs),
+                (RowKind::Less, range) => (
+                    range.map_or(Scalar::NEG_INFINITY, |range| row.rhs - range.abs()),
+                    row.rhs,
+                ),
+                (RowKind::Greater, range) => (
+                    row.rhs,
+                    range.map_or(Scalar::INFINITY, |range| row.rhs + range.abs()),
+                ),
+            })
+            .unzip();
+        let (equalities, inequalities) = split_ranged_rows(&lower, &upper, &self.columns);
+        let names = Names {
+            variables: by_position(self.column_index),
+            ..split_row_names(&lower, &upper, &by_position(self.row_index))
+        };
+        let bounds = Some(Bounds {
+            lower: self.lower,
+            upper: self.upper,
+        });
+        let integrality = if self.integrality.contains(&true) {
+            self.integrality
+        } else {
+            Vec::new()
+        };
+
+        let problem = if self.quadratic.is_empty() {
+            let problem = ProblemLP {
+                cost: self.cost,
+                inequalities,
+                equalities,
+                bounds,
+                groups: Vec::new(),
+                integrality,
+                names,
+                sense: self.sense,
+                offset: self.offset,
+            };
+            problem.validate()?;
+            JsonProblem::Lp { problem }
+        } else {
+            let mut columns = vec![Vec::new(); n];
+            for ((i, j), value) in self.quadratic {
+                columns[j].push((i, value));
+            }
+            let problem = ProblemQP {
+                quadratic: csc_from_columns(n, columns),
+                linear: self.cost,
+                inequalities,
+                equalities,
+                bounds,
+                groups: Vec::new(),
+                integrality,
+                names,
+                sense: self.sense,
+                offset: self.offset,
+                quadratic_constraints: Vec::new(),
+            };
+            problem.validate()?;
+            JsonProblem::Qp { problem }
+        };
+        Ok(MpsModel {
+            name: self.name,
+            problem,
+        })
+    }
+}
+
+fn pairs<'a>(fields: &[&'a str]) -> Result<Vec<(&'a str, Scalar)>> {
+    // RHS, RANGES and COLUMNS lines start with a set or column name.
+    let entries = &fields[1..];
+    if entries.is_empty() || entries.len() % 2 != 0 {
+        bail!("expected name/value pairs");
+    }
+    entries
+        .chunks(2)
+        .map(|pair| Ok((pair[0], number(pair[1])?)))
+        .collect()
+}
+
+fn number(field: &str) -> Result<Scalar> {
+    field
+        .parse::<Scalar>()
+        .map_err(|_| anyhow!("'{}' is not a number", field))
+}
+
+// Splits l <= A x <= u, with A given by columns, into equality rows and
+// one-sided rows of the form A x <= u and -A x <= -l.
+pub(crate) fn split_ranged_rows(
+    lower: &[Scalar],
+    upper: &[Scalar],
+    columns: &[Vec<(usize, Scalar)>],
+) -> SplitRows<Scalar> {
+    let block = LinearConstraints::new(
+        csc_from_columns(lower.len(), columns.to_vec()),
+        lower
+            .iter()
+            .zip(upper)
+            .map(|(&lower, &upper)| RowSense::between(lower, upper))
+            .collect(),
+    );
+    block.split().expect("rows within the matrix")
+}
+
+// The names of the rows `split_ranged_rows` makes from the same bounds. A
+// row kept on both sides becomes `name:upper` and `name:lower`.
+pub(crate) fn split_row_names(lower: &[Scalar], upper: &[Scalar], names: &[String]) -> Names {
+    let mut split = Names::default();
+    for ((&lower, &upper), name) in lower.iter().zip(upper).zip(names) {
+        if lower == upper {
+            split.equalities.push(name.clone());
+            continue;
+        }
+        match (lower.is_finite(), upper.is_finite()) {
+            (true, true) => {
+                split.inequalities.push(format!("{name}:upper"));
+                split.inequalities.push(format!("{name}:lower"));
+            }
+            (false, true) | (true, false) => split.inequalities.push(name.clone()),
+            (false, false) => {}
+        }
+    }
+    split
+}
+
+fn objective_sense(field: &str) -> Result<ObjectiveSense> {
+    match field {
+        "MIN" | "MINIMIZE" => Ok(ObjectiveSense::Minimize),
+        "MAX" | "MAXIMIZE" => Ok(ObjectiveSense::Maximize),
+        other => bail!("unknown objective sense {}", other),
+    }
+}
+
+// The keys of a name-to-position map, in position order.
+fn by_position(index: HashMap<String, usize>) -> Vec<String> {
+    let mut names = vec![String::new(); index.len()];
+    for (name, position) in index {
+        names[position] = name;
+    }
+    names
+}
+
+pub(crate) fn csc_from_columns(
+    nrows: usize,
+    columns: Vec<Vec<(usize, Scalar)>>,
+) -> CscMatrix<Scalar> {
+    let mut indptr = Vec::with_capacity(columns.len() + 1);
+    let mut indices = Vec::new();
+    let mut data = Vec::new();
+    indptr.push(0);
+    let ncols = columns.len();
+    for mut column in columns {
+        column.sort_by_key(|&(row, _)| row);
+        for (row, value) in column {
+            if indices.len() > indptr[indptr.len() - 1] && indices.last() == Some(&row) {
+                *data.last_mut().expect("entry exists") += value;
+            } else {
+                indices.push(row);
+                data.push(value);
+            }
+        }
+        indptr.push(indices.len());
+    }
+    CscMatrix {
+        nrows,
+        ncols,
+        indptr,
+        indices,
+        data,
+    }
+}
+
+// Solver formats often keep only one triangle of P; mirror it so the
+// quadratic term holds the full symmetric matrix.
+pub(crate) fn symmetric_columns(
+    mut columns: Vec<Vec<(usize, Scalar)>>,
+) -> Vec<Vec<(usize, Scalar)>> {
+    let entries = columns
+        .iter()
+        .enumerate()
+        .flat_map(|(col, column)| column.iter().map(move |&(row, _)| (row, col)));
+    let (mut upper, mut lower) = (false, false);
+    for (row, col) in entries {
+        upper |= row < col;
+        lower |= row > col;
+    }
+    if upper == lower {
+        return columns;
+    }
+    let mirrored: Vec<(usize, usize, Scalar)> = columns
+        .iter()
+        .enumerate()
+        .flat_map(|(col, column)| {
+            column
+                .iter()
+                .filter(move |&&(row, _)| row != col)
+                .map(move |&(row, value)| (col, row, value))
+        })
+        .collect();
+    for (row, col, value) in mirrored {
+        columns[col].push((row, value));
+    }
+    columns
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const QP: &str = "\
+NAME          TINYQP
+ROWS
+ N  OBJ
+ G  C1
+ L  C2
+ E  C3
+COLUMNS
+    X1        OBJ       -1.0       C1        1.0
+    X1        C2        1.0        C3        1.0
+    X2        OBJ       -2.0       C1        1.0
+    X2        C3        -1.0
+RHS
+    RHS       C1        1.0        C2        4.0
+    RHS       OBJ       -3.5
+RANGES
+    RNG       C2        2.0
+BOUNDS
+ UP BND       X1        4.0
+ FR BND       X2
+QUADOBJ
+    X1        X1        2.0
+    X1        X2        0.5
+    X2        X2        1.0
+ENDATA
+";
+
+    #[test]
+    fn parses_quadratic_mps() {
+        let model = parse_mps(QP).unwrap();
+        assert_eq!(model.name, "TINYQP");
+        let JsonProblem::Qp { problem } = model.problem else {
+            panic!("expected a QP");
+        };
+        assert_eq!(problem.offset, 3.5);
+        assert_eq!(problem.linear, vec![-1.0, -2.0]);
+        assert_eq!(problem.quadratic.indptr, vec![0, 2, 4]);
+        assert_eq!(problem.quadratic.data, vec![2.0, 0.5, 0.5, 1.0]);
+        let eq = problem.equalities.unwrap();
+        assert_eq!(eq.rhs, vec![0.0]);
+        assert_eq!(eq.matrix.data, vec![1.0, -1.0]);
+        // C1 >= 1 becomes -x1 - x2 <= -1; the ranged C2 yields 2 <= x1 <= 4.
+        let ineq = problem.inequalities.unwrap();
+        assert_eq!(ineq.rhs, vec![-1.0, 4.0, -2.0]);
+        assert_eq!(ineq.matrix.indices, vec![0, 1, 2, 0]);
+        let bounds = problem.bounds.unwrap();
+        assert_eq!(bounds.upper[0], 4.0);
+        assert_eq!(bounds.lower[1], Scalar::NEG_INFINITY);
+        assert!(problem.integrality.is_empty());
+        assert_eq!(problem.names.variables, vec!["X1", "X2"]);
+        assert_eq!(problem.names.equalities, vec!["C3"]);
+        assert_eq!(
+            problem.names.inequalities,
+            vec!["C1", "C2:upper", "C2:lower"]
+        );
+    }
+
+    #[test]
+    fn reads_integer_markers_and_bounds() {
+        let model = parse_mps(
+            "\
+NAME          TINYMIP
+ROWS
+ N  COST
+ L  LIM
+COLUMNS
+    MARKER    'MARKER'  'INTORG'
+    X         COST      -1.0       LIM       2.0
+    MARKER    'MARKER'  'INTEND'
+    Y         COST      -1.0       LIM       1.0
+    Z         COST      1.0
+    W         COST      1.0
+RHS
+    RHS       LIM       3.0
+BOUNDS
+ BV BND       Z
+ UP BND       Y         2.5
+ENDATA
+",
+        )
+        .unwrap();
+        let JsonProblem::Lp { problem } = model.problem else {
+            panic!("expected an LP");
+        };
+        assert_eq!(problem.integrality, vec![true, false, true, false]);
+        let bounds = problem.bounds.unwrap();
+        assert_eq!(bounds.upper[2], 1.0);
+    }
+
+    #[test]
+    fn reads_the_objective_sense_in_both_layouts() {
+        let body = "\
+ROWS
+ N  PROFIT
+ L  CAP
+COLUMNS
+    X         PROFIT    3.0        CAP       1.0
+RHS
+    RHS       CAP       2.0
+ENDATA
+";
+        let sense = |header: &str| {
+            let JsonProblem::Lp { problem } =
+                parse_mps(&format!("{header}{body}")).unwrap().problem
+            else {
+                panic!("expected an LP");
+            };
+            assert_eq!(problem.cost, vec![3.0]);
+            problem.sense
+        };
+        assert_eq!(sense(""), ObjectiveSense::Minimize);
+        assert_eq!(sense("OBJSENSE MAX\n"), ObjectiveSense::Maximize);
+        assert_eq!(sense("OBJSENSE\n    MAXIMIZE\n"), ObjectiveSense::Maximize);
+        assert_eq!(sense("OBJSENSE\n    MIN\n"), ObjectiveSense::Minimize);
+        assert!(parse_mps(&format!("OBJSENSE\n    UP\n{body}")).is_err());
+    }
+}
@@ -0,0 +1,287 @@
+use crate::JsonProblem;
+use cvxrs_core::math::Scalar;
+use cvxrs_core::problem::{Bounds, CscMatrix};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseOptions {
+    // Reject unknown fields, NaN data, unsorted or duplicate row indices and
+    // an asymmetric P instead of repairing them.
+    pub strict: bool,
+    // Lenient mode only: replace an asymmetric P by ½(P + Pᵀ).
+    pub symmetrize: bool,
+}
+
+impl ParseOptions {
+    pub fn strict() -> Self {
+        Self {
+            strict: true,
+            symmetrize: false,
+        }
+    }
+
+    pub fn lenient() -> Self {
+        Self {
+            strict: false,
+            symmetrize: true,
+        }
+    }
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self::lenient()
+    }
+}
+
+// A repair applied in lenient mode, located by JSON pointer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseFix {
+    pub pointer: String,
+    pub message: String,
+}
+
+impl fmt::Display for ParseFix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.pointer, self.message)
+    }
+}
+
+pub(crate) type Rejection = (String, String);
+
+// Same as `repair_problem`, for readers that report errors through anyhow.
+pub(crate) fn repair_parsed(
+    problem: &mut JsonProblem,
+    options: &ParseOptions,
+) -> anyhow::Result<Vec<ParseFix>> {
+    repair_problem(problem, options)
+        .map_err(|(pointer, message)| anyhow::anyhow!("{pointer}: {message}"))
+}
+
+// Checks (strict) or repairs (lenient) the parts of a problem that the core
+// validation would reject or silently misread. Structural errors such as a
+// wrong `indptr` length are left for validation to report.
+pub(crate) fn repair_problem(
+    problem: &mut JsonProblem,
+    options: &ParseOptions,
+) -> Result<Vec<ParseFix>, Rejection> {
+    let mut fixes = Vec::new();
+    let (equalities, inequalities, bounds) = match problem {
+        JsonProblem::Qp { problem } => {
+            reject_nan(&problem.linear, "/problem/linear")?;
+            repair_matrix(
+                &mut problem.quadratic,
+                "/problem/quadratic",
+                options,
+                &mut fixes,
+            )?;
+            symmetrize(
+                &mut problem.quadratic,
+                "/problem/quadratic",
+                options,
+                &mut fixes,
+            )?;
+            (
+                &mut problem.equalities,
+                &mut problem.inequalities,
+                &mut problem.bounds,
+            )
+        }
+        JsonProblem::Lp { problem } => {
+            reject_nan(&problem.cost, "/problem/cost")?;
+            (
+                &mut problem.equalities,
+                &mut problem.inequalities,
+                &mut problem.bounds,
+            )
+        }
+    };
+    if let Some(eq) = equalities {
+        reject_nan(&eq.rhs, "/problem/equalities/rhs")?;
+        repair_matrix(
+            &mut eq.matrix,
+            "/problem/equalities/matrix",
+            options,
+            &mut fixes,
+        )?;
+    }
+    if let Some(ineq) = inequalities {
+        reject_nan(&ineq.rhs, "/problem/inequalities/rhs")?;
+        repair_matrix(
+            &mut ineq.matrix,
+            "/problem/inequalities/matrix",
+            options,
+            &mut fixes,
+        )?;
+    }
+    if let Some(bounds) = bounds {
+        repair_bounds(bounds, options, &mut fixes)?;
+    }
+    Ok(fixes)
+}
+
+fn reject_nan(values: &[Scalar], pointer: &str) -> Result<(), Rejection> {
+    match values.iter().position(|value| value.is_nan()) {
+        Some(idx) => Err((format!("{pointer}/{idx}"), "value is NaN".into())),
+        None => Ok(()),
+    }
+}
+
+// A NaN bound is read as a missing one, which is what spreadsheets mean by an
+// empty cell.
+fn repair_bounds(
+    bounds: &mut Bounds<Scalar>,
+    options: &ParseOptions,
+    fixes: &mut Vec<ParseFix>,
+) -> Result<(), Rejection> {
+    for (side, values, infinity) in [
+        ("lower", &mut bounds.lower, Scalar::NEG_INFINITY),
+        ("upper", &mut bounds.upper, Scalar::INFINITY),
+    ] {
+        let pointer = format!("/problem/bounds/{side}");
+        if options.strict {
+            reject_nan(values, &pointer)?;
+            continue;
+        }
+        let mut replaced = 0;
+        for value in values.iter_mut().filter(|value| value.is_nan()) {
+            *value = infinity;
+            replaced += 1;
+        }
+        if replaced > 0 {
+            fixes.push(ParseFix {
+                pointer,
+                message: format!("treated {replaced} NaN bounds as unbounded"),
+            });
+        }
+    }
+    Ok(())
+}
+
+fn repair_matrix(
+    matrix: &mut CscMatrix<Scalar>,
+    pointer: &str,
+    options: &ParseOptions,
+    fixes: &mut Vec<ParseFix>,
+) -> Result<(), Rejection> {
+    reject_nan(&matrix.data, &format!("{pointer}/data"))?;
+    let well_formed = matrix.indptr.len() == matrix.ncols + 1
+        && matrix.indices.len() == matrix.data.len()
+        && matrix.indptr.first() == Some(&0)
+        && matrix.indptr.last() == Some(&matrix.indices.len())
+        && matrix.indptr.windows(2).all(|pair| pair[0] <= pair[1]);
+    if !well_formed {
+        return Ok(());
+    }
+    let ordered = |col: usize| {
+        matrix.indices[matrix.indptr[col]..matrix.indptr[col + 1]]
+            .windows(2)
+            .all(|pair| pair[0] < pair[1])
+    };
+    let unsorted: Vec<usize> = (0..matrix.ncols).filter(|&col| !ordered(col)).collect();
+    let Some(&first) = unsorted.first() else {
+        return Ok(());
+    };
+    if options.strict {
+        return Err((
+            format!("{pointer}/indices"),
+            format!("row indices in column {first} must be strictly increasing"),
+        ));
+    }
+
+    let mut indptr = vec![0];
+    let mut indices = Vec::with_capacity(matrix.indices.len());
+    let mut data = Vec::with_capacity(matrix.data.len());
+    let mut merged = 0;
+    for col in 0..matrix.ncols {
+        let range = matrix.indptr[col]..matrix.indptr[col + 1];
+        let mut entries: Vec<(usize, Scalar)> = matrix.indices[range.clone()]
+            .iter()
+            .copied()
+            .zip(matrix.data[range].iter().copied())
+            .collect();
+        entries.sort_by_key(|&(row, _)| row);
+        let start = indices.len();
+        for (row, value) in entries {
+            if indices.len() > start && indices.last() == Some(&row) {
+                *data.last_mut().unwrap() += value;
+                merged += 1;
+            } else {
+                indices.push(row);
+                data.push(value);
+            }
+        }
+        indptr.push(indices.len());
+    }
+    matrix.indptr = indptr;
+    matrix.indices = indices;
+    matrix.data = data;
+    let mut message = format!("sorted row indices in {} columns", unsorted.len());
+    if merged > 0 {
+        message.push_str(&format!(" and summed {merged} duplicate entries"));
+    }
+    fixes.push(ParseFix {
+        pointer: pointer.to_string(),
+        message,
+    });
+    Ok(())
+}
+
+fn symmetrize(
+    matrix: &mut CscMatrix<Scalar>,
+    pointer: &str,
+    options: &ParseOptions,
+    fixes: &mut Vec<ParseFix>,
+) -> Result<(), Rejection> {
+    if matrix.nrows != matrix.ncols || matrix.validate().is_err() {
+        return Ok(());
+    }
+    let n = matrix.ncols;
+    let mut entries = std::collections::BTreeMap::new();
+    for col in 0..n {
+        for idx in matrix.indptr[col]..matrix.indptr[col + 1] {
+            entries.insert((matrix.indices[idx], col), matrix.data[idx]);
+        }
+    }
+    let asymmetric = entries.iter().find(|(&(row, col), &value)| {
+        let mirrored = entries.get(&(col, row)).copied().unwrap_or(0.0);
+        (value - mirrored).abs() > 1e-12 * value.abs().max(mirrored.abs())
+    });
+    let Some((&(row, col), _)) = asymmetric else {
+        return Ok(());
+    };
+    if options.strict {
+        return Err((
+            pointer.to_string(),
+            format!("matrix is not symmetric: entries ({row}, {col}) and ({col}, {row}) differ"),
+        ));
+    }
+    if !options.symmetrize {
+        return Ok(());
+    }
+
+    let mut columns = vec![Vec::new(); n];
+    for (&(row, col), &value) in &entries {
+        let mirrored = entries.get(&(col, row)).copied().unwrap_or(0.0);
+        columns[col].push((row, 0.5 * (value + mirrored)));
+        if !entries.contains_key(&(col, row)) {
+            columns[row].push((col, 0.5 * value));
+        }
+    }
+    matrix.indptr = vec![0];
+    matrix.indices.clear();
+    matrix.data.clear();
+    for mut column in columns {
+        column.sort_by_key(|&(row, _)| row);
+        for (row, value) in column {
+            matrix.indices.push(row);
+            matrix.data.push(value);
+        }
+        matrix.indptr.push(matrix.indices.len());
+    }
+    fixes.push(ParseFix {
+        pointer: pointer.to_string(),
+        message: "replaced the matrix by its symmetric part ½(P + Pᵀ)".into(),
+    });
+    Ok(())
+}
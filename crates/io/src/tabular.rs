@@ -0,0 +1,508 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+
+use anyhow::{anyhow, bail, Context, Result};
+use cvxrs_core::math::Scalar;
+use cvxrs_core::problem::{Bounds, Names, ObjectiveSense, ProblemLP, ProblemQP};
+
+use crate::mps::{csc_from_columns, split_ranged_rows, split_row_names, symmetric_columns};
+use crate::repair::{repair_parsed, ParseFix, ParseOptions};
+use crate::JsonProblem;
+
+const VARIABLES_SHEET: [&str; 2] = ["variables", "vars"];
+const CONSTRAINTS_SHEET: [&str; 2] = ["constraints", "restricciones"];
+const QUADRATIC_SHEET: [&str; 2] = ["quadratic", "cuadratica"];
+
+const NAME: [&str; 3] = ["name", "nombre", "variable"];
+const COST: [&str; 5] = ["cost", "objective", "c", "costo", "objetivo"];
+const LOWER: [&str; 5] = ["lower", "lb", "min", "inferior", "minimo"];
+const UPPER: [&str; 5] = ["upper", "ub", "max", "superior", "maximo"];
+const DIAGONAL: [&str; 2] = ["quadratic", "cuadratico"];
+const CONSTRAINT: [&str; 4] = ["constraint", "name", "restriccion", "nombre"];
+const VARIABLE: [&str; 1] = ["variable"];
+const COEFFICIENT: [&str; 4] = ["coefficient", "coef", "coeficiente", "valor"];
+const SENSE: [&str; 4] = ["sense", "type", "sentido", "tipo"];
+const RHS: [&str; 4] = ["rhs", "limit", "lado derecho", "limite"];
+const ROW_VARIABLE: [&str; 3] = ["row", "var1", "fila"];
+const COLUMN_VARIABLE: [&str; 3] = ["column", "var2", "columna"];
+const VALUE: [&str; 4] = ["value", "coefficient", "valor", "coeficiente"];
+
+struct Table {
+    headers: Vec<String>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    fn from_rows(rows: impl IntoIterator<Item = Vec<String>>) -> Result<Self> {
+        let mut rows = rows
+            .into_iter()
+            .filter(|row| row.iter().any(|cell| !cell.trim().is_empty()));
+        let headers = rows
+            .next()
+            .ok_or_else(|| anyhow!("the sheet is empty"))?
+            .into_iter()
+            .map(|header| header.trim().to_owned())
+            .collect();
+        Ok(Self {
+            headers,
+            rows: rows.collect(),
+        })
+    }
+
+    fn column(&self, aliases: &[&str]) -> Option<usize> {
+        self.headers
+            .iter()
+            .position(|header| aliases.contains(&header.to_lowercase().as_str()))
+    }
+
+    fn required(&self, aliases: &[&str], sheet: &str) -> Result<usize> {
+        self.column(aliases)
+            .ok_or_else(|| anyhow!("{} sheet needs a '{}' column", sheet, aliases[0]))
+    }
+
+    fn cell<'a>(&self, row: &'a [String], column: Option<usize>) -> &'a str {
+        column
+            .and_then(|column| row.get(column))
+            .map(|cell| cell.trim())
+            .unwrap_or("")
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Sense {
+    Less,
+    Greater,
+    Equal,
+}
+
+impl Sense {
+    fn parse(text: &str) -> Result<Self> {
+        match text.to_lowercase().as_str() {
+            "<=" | "<" | "≤" | "le" | "l" => Ok(Sense::Less),
+            ">=" | ">" | "≥" | "ge" | "g" => Ok(Sense::Greater),
+            "=" | "==" | "eq" | "e" => Ok(Sense::Equal),
+            other => bail!("unknown constraint sense '{}'", other),
+        }
+    }
+}
+
+struct ConstraintRow {
+    name: String,
+    sense: Option<Sense>,
+    rhs: Option<Scalar>,
+}
+
+// Reads a problem from a spreadsheet. Excel workbooks hold "variables",
+// "constraints" and an optional "quadratic" sheet; a CSV path stands for the
+// variables.csv, constraints.csv and quadratic.csv files in its directory.
+pub fn read_tabular_problem<P: AsRef<Path>>(path: P) -> Result<JsonProblem> {
+    let path = path.as_ref();
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    match extension.as_str() {
+        "csv" => read_csv_directory(path.parent().unwrap_or_else(|| Path::new("."))),
+        "xlsx" | "xlsm" | "xls" | "ods" => read_workbook(path),
+        other => bail!("unsupported spreadsheet extension '{}'", other),
+    }
+}
+
+pub fn read_tabular_problem_with<P: AsRef<Path>>(
+    path: P,
+    options: &ParseOptions,
+) -> Result<(JsonProblem, Vec<ParseFix>)> {
+    let mut problem = read_tabular_problem(path)?;
+    let fixes = repair_parsed(&mut problem, options)?;
+    Ok((problem, fixes))
+}
+
+pub fn parse_tabular(
+    variables: &str,
+    constraints: &str,
+    quadratic: Option<&str>,
+) -> Result<JsonProblem> {
+    let quadratic = quadratic
+        .map(csv_table)
+        .transpose()
+        .context("quadratic sheet")?;
+    build_problem(
+        &csv_table(variables).context("variables sheet")?,
+        &csv_table(constraints).context("constraints sheet")?,
+        quadratic.as_ref(),
+    )
+}
+
+fn read_csv_directory(dir: &Path) -> Result<JsonProblem> {
+    let find = |names: &[&str]| -> Result<Option<String>> {
+        for name in names {
+            let path = dir.join(format!("{}.csv", name));
+            if path.exists() {
+                return fs::read_to_string(&path)
+                    .map(Some)
+                    .with_context(|| format!("failed to read {:?}", path));
+            }
+        }
+        Ok(None)
+    };
+    let variables =
+        find(&VARIABLES_SHEET)?.ok_or_else(|| anyhow!("{:?} has no variables.csv", dir))?;
+    let constraints =
+        find(&CONSTRAINTS_SHEET)?.ok_or_else(|| anyhow!("{:?} has no constraints.csv", dir))?;
+    let quadratic = find(&QUADRATIC_SHEET)?;
+    parse_tabular(&variables, &constraints, quadratic.as_deref())
+}
+
+#[cfg(feature = "excel")]
+fn read_workbook(path: &Path) -> Result<JsonProblem> {
+    use calamine::{open_workbook_auto, Reader};
+
+    let mut workbook =
+        open_workbook_auto(path).with_context(|| format!("failed to open {:?}", path))?;
+    let names = workbook.sheet_names().to_owned();
+    let mut sheet = |aliases: &[&str]| -> Result<Option<Table>> {
+        let Some(name) = names
+            .iter()
+            .find(|name| aliases.contains(&name.trim().to_lowercase().as_str()))
+        else {
+            return Ok(None);
+        };
+        let range = workbook
+            .worksheet_range(name)
+            .with_context(|| format!("failed to read sheet '{}'", name))?;
+        let rows = range
+            .rows()
+            .map(|row| row.iter().map(|cell| cell.to_string()).collect());
+        Table::from_rows(rows)
+            .with_context(|| format!("sheet '{}'", name))
+            .map(Some)
+    };
+    let variables =
+        sheet(&VARIABLES_SHEET)?.ok_or_else(|| anyhow!("{:?} has no 'variables' sheet", path))?;
+    let constraints = sheet(&CONSTRAINTS_SHEET)?
+        .ok_or_else(|| anyhow!("{:?} has no 'constraints' sheet", path))?;
+    let quadratic = sheet(&QUADRATIC_SHEET)?;
+    build_problem(&variables, &constraints, quadratic.as_ref())
+}
+
+#[cfg(not(feature = "excel"))]
+fn read_workbook(path: &Path) -> Result<JsonProblem> {
+    bail!(
+        "{:?}: Excel support is not enabled; rebuild cvxrs-io with the 'excel' feature",
+        path
+    )
+}
+
+fn csv_table(contents: &str) -> Result<Table> {
+    // Spreadsheets saved with a comma decimal separator use ';' between fields.
+    let header = contents.lines().next().unwrap_or_default();
+    let delimiter = if header.contains(';') && !header.contains(',') {
+        b';'
+    } else {
+        b','
+    };
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(contents.as_bytes());
+    let mut rows = Vec::new();
+    for record in reader.records() {
+        rows.push(record?.iter().map(str::to_owned).collect());
+    }
+    Table::from_rows(rows)
+}
+
+fn number(text: &str) -> Result<Option<Scalar>> {
+    if text.is_empty() {
+        return Ok(None);
+    }
+    // Accept decimal commas ("2,5") as written by Spanish locale spreadsheets.
+    let normalized = if text.contains(',') && !text.contains('.') {
+        text.replace(',', ".")
+    } else {
+        text.to_owned()
+    };
+    normalized
+        .parse::<Scalar>()
+        .map(Some)
+        .map_err(|_| anyhow!("'{}' is not a number", text))
+}
+
+fn build_problem(
+    variables: &Table,
+    constraints: &Table,
+    quadratic: Option<&Table>,
+) -> Result<JsonProblem> {
+    let name_col = variables.required(&NAME, "variables")?;
+    let cost_col = variables.column(&COST);
+    let lower_col = variables.column(&LOWER);
+    let upper_col = variables.column(&UPPER);
+    let diagonal_col = variables.column(&DIAGONAL);
+
+    let mut names = Vec::with_capacity(variables.rows.len());
+    let mut index = HashMap::new();
+    let mut cost = Vec::with_capacity(variables.rows.len());
+    let mut lower = Vec::with_capacity(variables.rows.len());
+    let mut upper = Vec::with_capacity(variables.rows.len());
+    let mut entries: BTreeMap<(usize, usize), Scalar> = BTreeMap::new();
+    for (line, row) in variables.rows.iter().enumerate() {
+        let context = || format!("variables row {}", line + 2);
+        let name = variables.cell(row, Some(name_col));
+        if name.is_empty() {
+            bail!("{}: missing variable name", context());
+        }
+        if index.insert(name.to_owned(), names.len()).is_some() {
+            bail!("{}: duplicate variable '{}'", context(), name);
+        }
+        let value = |column| number(variables.cell(row, column)).with_context(context);
+        if let Some(diagonal) = value(diagonal_col)? {
+            entries.insert((names.len(), names.len()), diagonal);
+        }
+        cost.push(value(cost_col)?.unwrap_or(0.0));
+        lower.push(value(lower_col)?.unwrap_or(0.0));
+        upper.push(value(upper_col)?.unwrap_or(Scalar::INFINITY));
+        names.push(name.to_owned());
+    }
+    if names.is_empty() {
+        bail!("the variables sheet lists no variables");
+    }
+
+    let (rows, columns) =
+        if constraints.column(&VARIABLE).is_some() && constraints.column(&COEFFICIENT).is_some() {
+            long_constraints(constraints, &index)?
+        } else {
+            wide_constraints(constraints, &index)?
+        };
+    let mut row_lower = Vec::with_capacity(rows.len());
+    let mut row_upper = Vec::with_capacity(rows.len());
+    for row in &rows {
+        let sense = row
+            .sense
+            .ok_or_else(|| anyhow!("constraint '{}' has no sense", row.name))?;
+        let rhs = row
+            .rhs
+            .ok_or_else(|| anyhow!("constraint '{}' has no right-hand side", row.name))?;
+        let (lo, hi) = match sense {
+            Sense::Less => (Scalar::NEG_INFINITY, rhs),
+            Sense::Greater => (rhs, Scalar::INFINITY),
+            Sense::Equal => (rhs, rhs),
+        };
+        row_lower.push(lo);
+        row_upper.push(hi);
+    }
+    let (equalities, inequalities) = split_ranged_rows(&row_lower, &row_upper, &columns);
+    let row_names: Vec<String> = rows.iter().map(|row| row.name.clone()).collect();
+    let row_names = split_row_names(&row_lower, &row_upper, &row_names);
+
+    if let Some(table) = quadratic {
+        let row_col = table.required(&ROW_VARIABLE, "quadratic")?;
+        let col_col = table.required(&COLUMN_VARIABLE, "quadratic")?;
+        let value_col = table.required(&VALUE, "quadratic")?;
+        for (line, row) in table.rows.iter().enumerate() {
+            let context = || format!("quadratic row {}", line + 2);
+            let lookup = |column| {
+                let name = table.cell(row, Some(column));
+                index
+                    .get(name)
+                    .copied()
+                    .ok_or_else(|| anyhow!("{}: unknown variable '{}'", context(), name))
+            };
+            let (i, j) = (lookup(row_col)?, lookup(col_col)?);
+            let value = number(table.cell(row, Some(value_col)))
+                .with_context(context)?
+                .unwrap_or(0.0);
+            *entries.entry((i, j)).or_insert(0.0) += value;
+        }
+    }
+
+    let n = names.len();
+    let names = Names {
+        variables: names,
+        ..row_names
+    };
+    let bounds = Some(Bounds { lower, upper });
+    let problem = if entries.values().all(|&value| value == 0.0) {
+        let problem = ProblemLP {
+            cost,
+            inequalities,
+            equalities,
+            bounds,
+            groups: Vec::new(),
+            integrality: Vec::new(),
+            names,
+            sense: ObjectiveSense::Minimize,
+            offset: 0.0,
+        };
+        problem.validate()?;
+        JsonProblem::Lp { problem }
+    } else {
+        let mut quadratic = vec![Vec::new(); n];
+        for ((i, j), value) in entries {
+            quadratic[j].push((i, value));
+        }
+        let problem = ProblemQP {
+            quadratic: csc_from_columns(n, symmetric_columns(quadratic)),
+            linear: cost,
+            inequalities,
+            equalities,
+            bounds,
+            groups: Vec::new(),
+            integrality: Vec::new(),
+            names,
+            sense: ObjectiveSense::Minimize,
+            offset: 0.0,
+            quadratic_constraints: Vec::new(),
+        };
+        problem.validate()?;
+        JsonProblem::Qp { problem }
+    };
+    Ok(problem)
+}
+
+type ConstraintColumns = (Vec<ConstraintRow>, Vec<Vec<(usize, Scalar)>>);
+
+fn wide_constraints(table: &Table, index: &HashMap<String, usize>) -> Result<ConstraintColumns> {
+    let name_col = table.column(&CONSTRAINT);
+    let sense_col = table.required(&SENSE, "constraints")?;
+    let rhs_col = table.required(&RHS, "constraints")?;
+    let mut coefficient_cols = Vec::new();
+    for (column, header) in table.headers.iter().enumerate() {
+        if Some(column) == name_col || column == sense_col || column == rhs_col {
+            continue;
+        }
+        match index.get(header) {
+            Some(&var) => coefficient_cols.push((column, var)),
+            None if header.is_empty() => {}
+            None => bail!("constraints column '{}' is not a variable", header),
+        }
+    }
+
+    let mut rows = Vec::with_capacity(table.rows.len());
+    let mut columns = vec![Vec::new(); index.len()];
+    for (line, row) in table.rows.iter().enumerate() {
+        let context = || format!("constraints row {}", line + 2);
+        for &(column, var) in &coefficient_cols {
+            if let Some(value) = number(table.cell(row, Some(column))).with_context(context)? {
+                if value != 0.0 {
+                    columns[var].push((rows.len(), value));
+                }
+            }
+        }
+        let name = match table.cell(row, name_col) {
+            "" => format!("R{}", rows.len() + 1),
+            name => name.to_owned(),
+        };
+        rows.push(ConstraintRow {
+            name,
+            sense: Some(Sense::parse(table.cell(row, Some(sense_col))).with_context(context)?),
+            rhs: number(table.cell(row, Some(rhs_col))).with_context(context)?,
+        });
+    }
+    Ok((rows, columns))
+}
+
+fn long_constraints(table: &Table, index: &HashMap<String, usize>) -> Result<ConstraintColumns> {
+    let name_col = table.required(&CONSTRAINT, "constraints")?;
+    let var_col = table.required(&VARIABLE, "constraints")?;
+    let coef_col = table.required(&COEFFICIENT, "constraints")?;
+    let sense_col = table.column(&SENSE);
+    let rhs_col = table.column(&RHS);
+
+    let mut rows: Vec<ConstraintRow> = Vec::new();
+    let mut positions = HashMap::new();
+    let mut columns = vec![Vec::new(); index.len()];
+    for (line, row) in table.rows.iter().enumerate() {
+        let context = || format!("constraints row {}", line + 2);
+        let name = table.cell(row, Some(name_col));
+        if name.is_empty() {
+            bail!("{}: missing constraint name", context());
+        }
+        let position = *positions.entry(name.to_owned()).or_insert_with(|| {
+            rows.push(ConstraintRow {
+                name: name.to_owned(),
+                sense: None,
+                rhs: None,
+            });
+            rows.len() - 1
+        });
+        let entry = &mut rows[position];
+        let sense = table.cell(row, sense_col);
+        if !sense.is_empty() {
+            let sense = Sense::parse(sense).with_context(context)?;
+            if entry.sense.is_some_and(|previous| previous != sense) {
+                bail!("{}: conflicting sense for '{}'", context(), name);
+            }
+            entry.sense = Some(sense);
+        }
+        if let Some(rhs) = number(table.cell(row, rhs_col)).with_context(context)? {
+            if entry.rhs.is_some_and(|previous| previous != rhs) {
+                bail!("{}: conflicting right-hand side for '{}'", context(), name);
+            }
+            entry.rhs = Some(rhs);
+        }
+        let variable = table.cell(row, Some(var_col));
+        if variable.is_empty() {
+            continue;
+        }
+        let var = *index
+            .get(variable)
+            .ok_or_else(|| anyhow!("{}: unknown variable '{}'", context(), variable))?;
+        if let Some(value) = number(table.cell(row, Some(coef_col))).with_context(context)? {
+            columns[var].push((position, value));
+        }
+    }
+    Ok((rows, columns))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VARIABLES: &str = "\
+name,cost,lower,upper,quadratic
+x,-1,0,4,2
+y,-2,,,
+";
+
+    #[test]
+    fn wide_and_long_forms_agree() {
+        let wide = "\
+constraint,x,y,sense,rhs
+cap,1,1,<=,3
+bal,1,-1,=,0
+";
+        let long = "\
+restriccion;variable;coeficiente;sentido;lado derecho
+cap;x;1;<=;3
+cap;y;1;;
+bal;x;1;=;0
+bal;y;-1;;
+";
+        let quadratic = "row,column,value\nx,y,0.5\n";
+        for constraints in [wide, long] {
+            let JsonProblem::Qp { problem } =
+                parse_tabular(VARIABLES, constraints, Some(quadratic)).unwrap()
+            else {
+                panic!("expected a QP");
+            };
+            assert_eq!(problem.linear, vec![-1.0, -2.0]);
+            assert_eq!(problem.quadratic.data, vec![2.0, 0.5, 0.5]);
+            let bounds = problem.bounds.unwrap();
+            assert_eq!(bounds.lower, vec![0.0, 0.0]);
+            assert_eq!(bounds.upper, vec![4.0, Scalar::INFINITY]);
+            let inequalities = problem.inequalities.unwrap();
+            assert_eq!(inequalities.rhs, vec![3.0]);
+            assert_eq!(inequalities.matrix.data, vec![1.0, 1.0]);
+            let equalities = problem.equalities.unwrap();
+            assert_eq!(equalities.matrix.data, vec![1.0, -1.0]);
+        }
+
+        let error = parse_tabular(VARIABLES, "constraint,x,z,sense,rhs\nc,1,1,<=,1\n", None)
+            .err()
+            .unwrap();
+        assert!(error.to_string().contains("'z'"));
+    }
+}
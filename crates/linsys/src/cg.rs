@@ -0,0 +1,242 @@
+use anyhow::{anyhow, Result};
+use cvxrs_core::math::{dot, norm_inf, RealNumber};
+use cvxrs_core::stats::{KrylovStats, KrylovTermination};
+use cvxrs_core::traits::{KktSolver, LinearOperator};
+use num_traits::{FromPrimitive, One};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone)]
+pub struct CgPattern {
+    dimension: usize,
+}
+
+impl CgPattern {
+    pub fn new(dimension: usize) -> Self {
+        Self { dimension }
+    }
+
+    pub fn dimension(&self) -> usize {
+        self.dimension
+    }
+}
+
+// A symmetric positive definite system given only through its products,
+// with its diagonal for the Jacobi preconditioner.
+#[derive(Clone)]
+pub struct CgSystem<T: RealNumber> {
+    pub operator: Arc<dyn LinearOperator<T>>,
+    pub diagonal: Vec<T>,
+}
+
+impl<T> CgSystem<T>
+where
+    T: RealNumber,
+{
+    pub fn new(operator: Arc<dyn LinearOperator<T>>, diagonal: Vec<T>) -> Self {
+        Self { operator, diagonal }
+    }
+}
+
+// Preconditioned conjugate gradients. "Factoring" only takes the operator
+// and inverts the preconditioner; every solve then costs a handful of
+// products, and nothing of size n² is ever stored.
+pub struct CgKktSolver<T: RealNumber> {
+    dimension: usize,
+    system: Option<CgSystem<T>>,
+    inverse_diagonal: Vec<T>,
+    tolerance: T,
+    max_iterations: Option<usize>,
+    numeric_time: Duration,
+    krylov: Mutex<KrylovStats>,
+}
+
+impl<T> CgKktSolver<T>
+where
+    T: RealNumber + FromPrimitive + One,
+{
+    pub fn new() -> Self {
+        Self {
+            dimension: 0,
+            system: None,
+            inverse_diagonal: Vec::new(),
+            tolerance: T::from_f64(1e-10).unwrap(),
+            max_iterations: None,
+            numeric_time: Duration::ZERO,
+            krylov: Mutex::new(KrylovStats::default()),
+        }
+    }
+
+    // Iterations stop once the residual is within `tolerance` times the
+    // right-hand side, in the infinity norm.
+    pub fn with_tolerance(mut self, tolerance: T) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    // Caps the iterations of each solve; twice the dimension unless set.
+    pub fn with_max_iterations(mut self, iterations: usize) -> Self {
+        self.max_iterations = Some(iterations);
+        self
+    }
+}
+
+impl<T> Default for CgKktSolver<T>
+where
+    T: RealNumber + FromPrimitive + One,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> KktSolver<T> for CgKktSolver<T>
+where
+    T: RealNumber + FromPrimitive + One,
+{
+    type Pattern = CgPattern;
+    type Matrix = CgSystem<T>;
+
+    fn analyze_pattern(&mut self, pattern: &Self::Pattern) -> Result<()> {
+        self.dimension = pattern.dimension();
+        Ok(())
+    }
+
+    fn factor(&mut self, matrix: &Self::Matrix) -> Result<()> {
+        let start = Instant::now();
+        let (rows, cols) = matrix.operator.dim();
+        if rows != cols || matrix.diagonal.len() != rows {
+            return Err(anyhow!(
+                "CG needs a square operator with its diagonal, got {}x{} and {} entries",
+                rows,
+                cols,
+                matrix.diagonal.len()
+            ));
+        }
+        if let Some(i) = matrix.diagonal.iter().position(|&d| d <= T::zero()) {
+            return Err(anyhow!(
+                "non-positive diagonal entry encountered at {}; CG needs a positive definite system",
+                i
+            ));
+        }
+        self.dimension = rows;
+        self.inverse_diagonal = matrix.diagonal.iter().map(|&d| d.recip()).collect();
+        self.system = Some(matrix.clone());
+        self.numeric_time += start.elapsed();
+        Ok(())
+    }
+
+    fn solve(&self, rhs: &mut [T]) -> Result<()> {
+        let Some(system) = &self.system else {
+            return Err(anyhow!("CG solve before the operator was set"));
+        };
+        if rhs.len() != self.dimension {
+            return Err(anyhow!(
+                "rhs length {} does not match dimension {}",
+                rhs.len(),
+                self.dimension
+            ));
+        }
+        let n = self.dimension;
+        let threshold = self.tolerance * norm_inf(rhs);
+        let mut residual = rhs.to_vec();
+        let mut x = vec![T::zero(); n];
+        let mut z: Vec<T> = residual
+            .iter()
+            .zip(&self.inverse_diagonal)
+            .map(|(&r, &d)| r * d)
+            .collect();
+        let mut direction = z.clone();
+        let mut product = vec![T::zero(); n];
+        let mut rz = dot(&residual, &z);
+        let max_iterations = self.max_iterations.unwrap_or(2 * n.max(1));
+        let mut iterations = 0;
+        let mut termination = KrylovTermination::IterationLimit;
+        loop {
+            if norm_inf(&residual) <= threshold {
+                termination = KrylovTermination::Converged;
+                break;
+            }
+            if iterations == max_iterations {
+                break;
+            }
+            iterations += 1;
+            system.operator.apply(&direction, &mut product);
+            let curvature = dot(&direction, &product);
+            if curvature <= T::zero() {
+                return Err(anyhow!(
+                    "non-positive curvature {:.3e} in CG; the operator is not positive definite",
+                    curvature.to_f64().unwrap_or(f64::NAN)
+                ));
+            }
+            let alpha = rz / curvature;
+            for i in 0..n {
+                x[i] += alpha * direction[i];
+                residual[i] -= alpha * product[i];
+                z[i] = residual[i] * self.inverse_diagonal[i];
+            }
+            let rz_next = dot(&residual, &z);
+            let beta = rz_next / rz;
+            rz = rz_next;
+            for (p, &z) in direction.iter_mut().zip(&z) {
+                *p = z + beta * *p;
+            }
+        }
+        rhs.copy_from_slice(&x);
+        self.krylov.lock().unwrap().record(iterations, termination);
+        Ok(())
+    }
+
+    fn numeric_time(&self) -> Duration {
+        self.numeric_time
+    }
+
+    fn krylov_stats(&self) -> Option<KrylovStats> {
+        Some(*self.krylov.lock().unwrap())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The 1-D Laplacian plus a shift, applied without storing it.
+    struct Laplacian {
+        n: usize,
+        shift: f64,
+    }
+
+    impl LinearOperator<f64> for Laplacian {
+        fn dim(&self) -> (usize, usize) {
+            (self.n, self.n)
+        }
+
+        fn apply(&self, x: &[f64], y: &mut [f64]) {
+            for i in 0..self.n {
+                let left = if i > 0 { x[i - 1] } else { 0.0 };
+                let right = if i + 1 < self.n { x[i + 1] } else { 0.0 };
+                y[i] = (2.0 + self.shift) * x[i] - left - right;
+            }
+        }
+    }
+
+    #[test]
+    fn solves_an_operator_system() {
+        let (n, shift) = (40, 0.1);
+        let operator = Arc::new(Laplacian { n, shift });
+        let mut solver = CgKktSolver::new().with_tolerance(1e-12);
+        solver
+            .factor(&CgSystem::new(operator.clone(), vec![2.0 + shift; n]))
+            .unwrap();
+        let b: Vec<f64> = (0..n).map(|i| (i as f64 * 0.3).sin()).collect();
+        let mut x = b.clone();
+        solver.solve(&mut x).unwrap();
+        let mut ax = vec![0.0; n];
+        operator.apply(&x, &mut ax);
+        for (ax, b) in ax.iter().zip(&b) {
+            assert!((ax - b).abs() < 1e-10);
+        }
+        let stats = solver.krylov_stats().unwrap();
+        assert_eq!(stats.last_termination, Some(KrylovTermination::Converged));
+    }
+}
@@ -0,0 +1,74 @@
+use anyhow::Result;
+use cvxrs_core::math::RealNumber;
+use num_traits::FromPrimitive;
+
+// Hager's search for the column of A⁻¹ with the largest 1-norm, with
+// Higham's alternating test vector as a safeguard, for a symmetric A given
+// through `solve`. A handful of solves gives an estimate that is a lower
+// bound on ‖A⁻¹‖₁ and rarely off by more than a small factor.
+pub(crate) fn inverse_norm1<T, F>(n: usize, solve: F) -> Result<T>
+where
+    T: RealNumber + FromPrimitive,
+    F: Fn(&mut [T]) -> Result<()>,
+{
+    const MAX_ITERATIONS: usize = 5;
+    if n == 0 {
+        return Ok(T::zero());
+    }
+    let norm1 = |v: &[T]| v.iter().fold(T::zero(), |acc, &x| acc + x.abs());
+
+    let mut x = vec![T::from_usize(n).unwrap().recip(); n];
+    let mut y = x.clone();
+    solve(&mut y)?;
+    let mut estimate = norm1(&y);
+    for _ in 0..MAX_ITERATIONS {
+        // A is symmetric, so the gradient step solves with A again.
+        let mut z: Vec<T> = y
+            .iter()
+            .map(|&v| if v < T::zero() { -T::one() } else { T::one() })
+            .collect();
+        solve(&mut z)?;
+        let (j, largest) = z
+            .iter()
+            .enumerate()
+            .fold((0, T::zero()), |(j, largest), (i, &v)| {
+                if v.abs() > largest {
+                    (i, v.abs())
+                } else {
+                    (j, largest)
+                }
+            });
+        let slope = z
+            .iter()
+            .zip(&x)
+            .fold(T::zero(), |acc, (&z, &x)| acc + z * x);
+        if largest <= slope {
+            break;
+        }
+        x.iter_mut().for_each(|v| *v = T::zero());
+        x[j] = T::one();
+        y.copy_from_slice(&x);
+        solve(&mut y)?;
+        let next = norm1(&y);
+        if next <= estimate {
+            break;
+        }
+        estimate = next;
+    }
+
+    let mut alternating: Vec<T> = (0..n)
+        .map(|i| {
+            let magnitude = T::one()
+                + T::from_usize(i).unwrap() / T::from_usize(n.saturating_sub(1).max(1)).unwrap();
+            if i % 2 == 0 {
+                magnitude
+            } else {
+                -magnitude
+            }
+        })
+        .collect();
+    solve(&mut alternating)?;
+    let safeguard = T::from_f64(2.0).unwrap() * norm1(&alternating)
+        / (T::from_f64(3.0).unwrap() * T::from_usize(n).unwrap());
+    Ok(estimate.max(safeguard))
+}
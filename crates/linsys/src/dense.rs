@@ -1,7 +1,21 @@
+use crate::condition::inverse_norm1;
+use crate::regularization::{Regularization, REGULARIZED_REFINEMENT_STEPS};
+use crate::UpdateSign;
 use anyhow::{anyhow, Result};
-use cvxrs_core::math::RealNumber;
-use cvxrs_core::traits::KktSolver;
+use cvxrs_core::math::{dot, RealNumber};
+use cvxrs_core::stats::RegularizationStats;
+use cvxrs_core::traits::{KktSolver, LinearOperator};
 use num_traits::{FromPrimitive, One};
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+use std::time::{Duration, Instant};
+
+// Multiply-adds below which a column of L is computed on the calling thread.
+#[cfg(feature = "rayon")]
+const PARALLEL_WORK: usize = 1 << 16;
+
+// Columns factored together as one panel.
+const BLOCK: usize = 48;
 
 #[derive(Debug, Clone)]
 pub struct DensePattern {
@@ -33,17 +47,69 @@ where
         Self { dimension, data }
     }
 
+    pub fn diagonal(&self) -> Vec<T> {
+        (0..self.dimension).map(|i| self.entry(i, i)).collect()
+    }
+
     fn entry(&self, row: usize, col: usize) -> T {
         self.data[row * self.dimension + col]
     }
 }
 
+impl<T: RealNumber> LinearOperator<T> for DenseKktMatrix<T> {
+    fn dim(&self) -> (usize, usize) {
+        (self.dimension, self.dimension)
+    }
+
+    fn apply(&self, x: &[T], y: &mut [T]) {
+        for (value, row) in y
+            .iter_mut()
+            .zip(self.data.chunks_exact(self.dimension.max(1)))
+        {
+            *value = dot(row, x);
+        }
+    }
+}
+
+// Symmetric pivoting of the dense factorization. Quasi-definite matrices
+// factor stably in any order and need none; general indefinite ones, such as
+// a KKT matrix with a zero block, do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DensePivoting {
+    #[default]
+    None,
+    BunchKaufman,
+}
+
+// P K Pᵀ = L D Lᵀ with D block diagonal: `order[k]` is the original index
+// at position k, and `offdiagonal[k]` the coupling of a 2×2 block starting
+// at k.
+#[derive(Debug, Clone)]
+struct Pivots<T> {
+    order: Vec<usize>,
+    offdiagonal: Vec<Option<T>>,
+}
+
 pub struct DenseKktSolver<T: RealNumber> {
     dimension: usize,
     l: Vec<T>,
     d: Vec<T>,
     analyzed: bool,
     last_factor: usize,
+    refinement_steps: usize,
+    pivoting: DensePivoting,
+    // Set when the last factorization pivoted.
+    pivots: Option<Pivots<T>>,
+    regularization: Option<Regularization<T>>,
+    regularization_stats: RegularizationStats<T>,
+    // Whether the last factorization changed any pivot.
+    perturbed: bool,
+    // The factored matrix, kept for the refinement residuals.
+    matrix: Vec<T>,
+    // ‖K‖₁ of the matrix last factored, for the condition estimate.
+    norm1: T,
+    symbolic_time: Duration,
+    numeric_time: Duration,
 }
 
 impl<T> DenseKktSolver<T>
@@ -57,6 +123,393 @@ where
             d: Vec::new(),
             analyzed: false,
             last_factor: 0,
+            refinement_steps: 0,
+            pivoting: DensePivoting::None,
+            pivots: None,
+            regularization: None,
+            regularization_stats: RegularizationStats::new(),
+            perturbed: false,
+            matrix: Vec::new(),
+            norm1: T::zero(),
+            symbolic_time: Duration::ZERO,
+            numeric_time: Duration::ZERO,
+        }
+    }
+
+    // Each solve is followed by `steps` rounds of iterative refinement:
+    // the residual against the factored matrix is solved for and added
+    // back, recovering digits the factorization lost.
+    pub fn with_refinement(mut self, steps: usize) -> Self {
+        self.refinement_steps = steps;
+        self
+    }
+
+    pub fn with_pivoting(mut self, pivoting: DensePivoting) -> Self {
+        self.pivoting = pivoting;
+        self
+    }
+
+    // Regularizes the pivots of unpivoted factorizations; the expected
+    // signs mean nothing once Bunch–Kaufman reorders them.
+    pub fn with_regularization(mut self, regularization: Regularization<T>) -> Self {
+        self.regularization = Some(regularization);
+        self
+    }
+
+    // Turns the factors of K into those of K + v vᵀ or K - v vᵀ in O(n²),
+    // column by column as in Gill, Golub, Murray and Saunders. The new
+    // pivots are checked before anything changes, so a downdate that would
+    // make the matrix singular leaves the factorization as it was.
+    pub fn update(&mut self, v: &[T], sign: UpdateSign) -> Result<()> {
+        if self.last_factor == 0 || v.len() != self.dimension {
+            return Err(anyhow!(
+                "update vector length {} does not match factored dimension {}",
+                v.len(),
+                self.dimension
+            ));
+        }
+        if self.pivots.is_some() {
+            return Err(anyhow!("rank-one updates need an unpivoted factorization"));
+        }
+        let sigma = sign.value::<T>();
+        self.rank_one(v, sigma, false)?;
+        self.rank_one(v, sigma, true)?;
+        if self.matrix.is_empty() {
+            // Without the matrix the norm can only be bounded.
+            let largest = v.iter().fold(T::zero(), |acc, &x| acc.max(x.abs()));
+            let sum = v.iter().fold(T::zero(), |acc, &x| acc + x.abs());
+            self.norm1 += largest * sum;
+        } else {
+            let dim = self.dimension;
+            for (row, &v_i) in self.matrix.chunks_exact_mut(dim).zip(v) {
+                for (value, &v_j) in row.iter_mut().zip(v) {
+                    *value += sigma * v_i * v_j;
+                }
+            }
+            self.norm1 = norm1(&self.matrix, dim);
+        }
+        Ok(())
+    }
+
+    // With `apply` unset only the new pivots are computed and checked.
+    fn rank_one(&mut self, v: &[T], sigma: T, apply: bool) -> Result<()> {
+        let dim = self.dimension;
+        let mut w = v.to_vec();
+        let mut alpha = T::one();
+        for j in 0..dim {
+            let p = w[j];
+            if p == T::zero() {
+                continue;
+            }
+            let alpha_bar = alpha + sigma * p * p / self.d[j];
+            let d_bar = self.d[j] * alpha_bar / alpha;
+            if !d_bar.is_finite() || d_bar.abs() <= Self::epsilon() {
+                return Err(anyhow!(
+                    "near-singular pivot at column {} after the rank-one change",
+                    j
+                ));
+            }
+            let gamma = sigma * p / (self.d[j] * alpha_bar);
+            alpha = alpha_bar;
+            if apply {
+                self.d[j] = d_bar;
+            }
+            for (i, w_i) in w.iter_mut().enumerate().skip(j + 1) {
+                let l_ij = self.l(i, j);
+                *w_i -= p * l_ij;
+                if apply {
+                    *self.l_mut(i, j) = l_ij + gamma * *w_i;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn factor_unpivoted(&mut self, matrix: &DenseKktMatrix<T>) -> Result<()> {
+        self.pivots = None;
+        for i in 0..self.dimension {
+            for j in 0..self.dimension {
+                *self.l_mut(i, j) = if i == j { T::one() } else { T::zero() };
+            }
+        }
+
+        // Left-looking by panels of BLOCK columns. The contribution of all
+        // earlier panels to the current one is a single pass over the rows
+        // below it against BLOCK scaled pivot rows that stay in cache, rather
+        // than one pass per column; the panel itself is then factored column
+        // by column with updates only BLOCK wide.
+        let dim = self.dimension;
+        let mut scaled = vec![T::zero(); BLOCK * dim];
+        let mut pivot_row = [T::zero(); BLOCK];
+        for k0 in (0..dim).step_by(BLOCK) {
+            let k1 = (k0 + BLOCK).min(dim);
+            let width = k1 - k0;
+            for (c, row) in scaled.chunks_exact_mut(dim).take(width).enumerate() {
+                let l_c = &self.l[(k0 + c) * dim..(k0 + c) * dim + k0];
+                for ((w, &l), &d) in row[..k0].iter_mut().zip(l_c).zip(&self.d) {
+                    *w = l * d;
+                }
+            }
+            let scaled = &scaled;
+            let panel = |(offset, row): (usize, &mut [T])| {
+                let i = k0 + offset;
+                let (done, current) = row.split_at_mut(k0);
+                let columns = width.min(i - k0 + 1);
+                let mut c = 0;
+                while c + 4 <= columns {
+                    let w = |offset: usize| &scaled[(c + offset) * dim..(c + offset) * dim + k0];
+                    let sums = dot4(done, [w(0), w(1), w(2), w(3)]);
+                    for (offset, sum) in sums.into_iter().enumerate() {
+                        current[c + offset] = matrix.entry(i, k0 + c + offset) - sum;
+                    }
+                    c += 4;
+                }
+                for (c, value) in current[..columns].iter_mut().enumerate().skip(c) {
+                    let w = &scaled[c * dim..c * dim + k0];
+                    *value = matrix.entry(i, k0 + c) - dot(done, w);
+                }
+            };
+            let below = &mut self.l[k0 * dim..];
+            #[cfg(feature = "rayon")]
+            if (dim - k0) * width * k0 >= PARALLEL_WORK {
+                below.par_chunks_mut(dim).enumerate().for_each(panel);
+            } else {
+                below.chunks_mut(dim).enumerate().for_each(panel);
+            }
+            #[cfg(not(feature = "rayon"))]
+            below.chunks_mut(dim).enumerate().for_each(panel);
+
+            for j in k0..k1 {
+                let inner = j - k0;
+                let mut d_j = self.l(j, j);
+                for (k, (p, &l_jk)) in pivot_row[..inner]
+                    .iter_mut()
+                    .zip(&self.l[j * dim + k0..j * dim + j])
+                    .enumerate()
+                {
+                    *p = l_jk * self.d[k0 + k];
+                    d_j -= l_jk * *p;
+                }
+                if let Some(regularization) = &self.regularization {
+                    d_j = regularization.adjust(j, d_j, &mut self.regularization_stats);
+                }
+                if d_j.abs() <= Self::epsilon() {
+                    let magnitude = d_j.abs().to_f64().unwrap_or(f64::NAN);
+                    return Err(anyhow!(
+                        "near-singular pivot encountered at column {} (|d_j| = {:.3e})",
+                        j,
+                        magnitude
+                    ));
+                }
+                self.d[j] = d_j;
+                *self.l_mut(j, j) = T::one();
+
+                let pivot_row = &pivot_row[..inner];
+                let update = |row: &mut [T]| {
+                    let lij = row[j] - dot(&row[k0..j], pivot_row);
+                    row[j] = lij / d_j;
+                };
+                let below = &mut self.l[(j + 1) * dim..];
+                #[cfg(feature = "rayon")]
+                if (dim - j - 1) * inner >= PARALLEL_WORK {
+                    below.par_chunks_mut(dim).for_each(update);
+                    continue;
+                }
+                below.chunks_mut(dim).for_each(update);
+            }
+        }
+        Ok(())
+    }
+
+    // Bunch–Kaufman: each step pivots on a diagonal entry or a 2×2 block
+    // chosen so that no multiplier exceeds 1/α in magnitude, which keeps the
+    // factorization stable on any nonsingular symmetric matrix. Works on a
+    // full copy of the matrix, whose strictly lower part becomes L as the
+    // trailing part shrinks.
+    fn factor_bunch_kaufman(&mut self, matrix: &DenseKktMatrix<T>) -> Result<()> {
+        let n = self.dimension;
+        let alpha = (T::one() + T::from_f64(17.0).unwrap().sqrt()) / T::from_f64(8.0).unwrap();
+        let mut a = matrix.data.clone();
+        let mut pivots = Pivots {
+            order: (0..n).collect(),
+            offdiagonal: vec![None; n],
+        };
+        let swap = |a: &mut [T], p: usize, q: usize| {
+            for j in 0..n {
+                a.swap(p * n + j, q * n + j);
+            }
+            for i in 0..n {
+                a.swap(i * n + p, i * n + q);
+            }
+        };
+        let mut k = 0;
+        while k < n {
+            let diagonal = a[k * n + k].abs();
+            let (imax, column_max) =
+                (k + 1..n)
+                    .map(|i| (i, a[i * n + k].abs()))
+                    .fold(
+                        (k, T::zero()),
+                        |best, entry| if entry.1 > best.1 { entry } else { best },
+                    );
+            if diagonal.max(column_max) <= Self::epsilon() {
+                return Err(anyhow!(
+                    "singular matrix: no usable pivot at column {}",
+                    pivots.order[k]
+                ));
+            }
+            let (pivot, step) = if diagonal >= alpha * column_max {
+                (k, 1)
+            } else {
+                let row_max = (k..n)
+                    .filter(|&j| j != imax)
+                    .map(|j| a[imax * n + j].abs())
+                    .fold(T::zero(), T::max);
+                if diagonal * row_max >= alpha * column_max * column_max {
+                    (k, 1)
+                } else if a[imax * n + imax].abs() >= alpha * row_max {
+                    (imax, 1)
+                } else {
+                    (imax, 2)
+                }
+            };
+            let target = k + step - 1;
+            if pivot != target {
+                swap(&mut a, pivot, target);
+                pivots.order.swap(pivot, target);
+            }
+
+            if step == 1 {
+                let d = a[k * n + k];
+                for i in k + 1..n {
+                    let l_ik = a[i * n + k] / d;
+                    for j in k + 1..n {
+                        let update = l_ik * a[j * n + k];
+                        a[i * n + j] -= update;
+                    }
+                }
+                for i in k + 1..n {
+                    a[i * n + k] = a[i * n + k] / d;
+                }
+                self.d[k] = d;
+            } else {
+                let (d0, b, d1) = (a[k * n + k], a[(k + 1) * n + k], a[(k + 1) * n + k + 1]);
+                let det = d0 * d1 - b * b;
+                if det.abs() <= Self::epsilon() * (b * b).max(T::one()) {
+                    return Err(anyhow!(
+                        "singular matrix: no usable pivot at column {}",
+                        pivots.order[k]
+                    ));
+                }
+                // Rows of W D⁻¹, with W the two pivot columns below the block.
+                let multipliers: Vec<(T, T)> = (k + 2..n)
+                    .map(|i| {
+                        let (w0, w1) = (a[i * n + k], a[i * n + k + 1]);
+                        ((w0 * d1 - w1 * b) / det, (w1 * d0 - w0 * b) / det)
+                    })
+                    .collect();
+                for (offset, &(l0, l1)) in multipliers.iter().enumerate() {
+                    let i = k + 2 + offset;
+                    for j in k + 2..n {
+                        let update = l0 * a[j * n + k] + l1 * a[j * n + k + 1];
+                        a[i * n + j] -= update;
+                    }
+                }
+                for (offset, &(l0, l1)) in multipliers.iter().enumerate() {
+                    let i = k + 2 + offset;
+                    a[i * n + k] = l0;
+                    a[i * n + k + 1] = l1;
+                }
+                self.d[k] = d0;
+                self.d[k + 1] = d1;
+                pivots.offdiagonal[k] = Some(b);
+            }
+            k += step;
+        }
+
+        for i in 0..n {
+            for j in 0..n {
+                *self.l_mut(i, j) = match i.cmp(&j) {
+                    std::cmp::Ordering::Greater => a[i * n + j],
+                    std::cmp::Ordering::Equal => T::one(),
+                    std::cmp::Ordering::Less => T::zero(),
+                };
+            }
+        }
+        for k in (0..n).filter(|&k| pivots.offdiagonal[k].is_some()) {
+            *self.l_mut(k + 1, k) = T::zero();
+        }
+        self.pivots = Some(pivots);
+        Ok(())
+    }
+
+    fn substitute(&self, rhs: &mut [T]) -> Result<()> {
+        if let Some(pivots) = &self.pivots {
+            return self.substitute_pivoted(pivots, rhs);
+        }
+        for i in 0..self.dimension {
+            for j in 0..i {
+                rhs[i] -= self.l(i, j) * rhs[j];
+            }
+        }
+        for (i, (value, &diagonal)) in rhs.iter_mut().zip(&self.d).enumerate() {
+            if diagonal.abs() <= Self::epsilon() {
+                return Err(anyhow!("singular diagonal entry encountered at {}", i));
+            }
+            *value = *value / diagonal;
+        }
+        for i in (0..self.dimension).rev() {
+            for j in (i + 1)..self.dimension {
+                rhs[i] -= self.l(j, i) * rhs[j];
+            }
+        }
+        Ok(())
+    }
+
+    fn substitute_pivoted(&self, pivots: &Pivots<T>, b: &mut [T]) -> Result<()> {
+        let n = self.dimension;
+        let mut permuted: Vec<T> = pivots.order.iter().map(|&i| b[i]).collect();
+        let rhs = &mut permuted[..];
+        for i in 0..n {
+            for j in 0..i {
+                rhs[i] -= self.l(i, j) * rhs[j];
+            }
+        }
+        let mut k = 0;
+        while k < n {
+            match pivots.offdiagonal[k] {
+                Some(coupling) => {
+                    let (d0, d1) = (self.d[k], self.d[k + 1]);
+                    let det = d0 * d1 - coupling * coupling;
+                    let (r0, r1) = (rhs[k], rhs[k + 1]);
+                    rhs[k] = (d1 * r0 - coupling * r1) / det;
+                    rhs[k + 1] = (d0 * r1 - coupling * r0) / det;
+                    k += 2;
+                }
+                None => {
+                    rhs[k] = rhs[k] / self.d[k];
+                    k += 1;
+                }
+            }
+        }
+        for i in (0..n).rev() {
+            for j in (i + 1)..n {
+                rhs[i] -= self.l(j, i) * rhs[j];
+            }
+        }
+        for (&original, &value) in pivots.order.iter().zip(&permuted) {
+            b[original] = value;
+        }
+        Ok(())
+    }
+
+    // Refinement steps per solve, raised while the factors are those of a
+    // regularized matrix.
+    fn refinement(&self) -> usize {
+        if self.perturbed {
+            self.refinement_steps.max(REGULARIZED_REFINEMENT_STEPS)
+        } else {
+            self.refinement_steps
         }
     }
 
@@ -75,6 +528,28 @@ where
     }
 }
 
+// Four dot products sharing one operand, in one pass over it and with
+// independent accumulators.
+fn dot4<T: RealNumber>(x: &[T], w: [&[T]; 4]) -> [T; 4] {
+    let n = x.len();
+    let (w0, w1, w2, w3) = (&w[0][..n], &w[1][..n], &w[2][..n], &w[3][..n]);
+    let mut sums = [T::zero(); 4];
+    for (k, &x_k) in x.iter().enumerate() {
+        sums[0] += x_k * w0[k];
+        sums[1] += x_k * w1[k];
+        sums[2] += x_k * w2[k];
+        sums[3] += x_k * w3[k];
+    }
+    sums
+}
+
+// ‖K‖₁ of a symmetric matrix stored in full, as its largest row sum.
+fn norm1<T: RealNumber>(data: &[T], dim: usize) -> T {
+    data.chunks_exact(dim.max(1))
+        .map(|row| row.iter().fold(T::zero(), |acc, &x| acc + x.abs()))
+        .fold(T::zero(), T::max)
+}
+
 impl<T> Default for DenseKktSolver<T>
 where
     T: RealNumber + FromPrimitive + One,
@@ -92,6 +567,7 @@ where
     type Matrix = DenseKktMatrix<T>;
 
     fn analyze_pattern(&mut self, pattern: &Self::Pattern) -> Result<()> {
+        let start = Instant::now();
         self.dimension = pattern.dimension();
         self.l = vec![T::zero(); self.dimension * self.dimension];
         self.d = vec![T::zero(); self.dimension];
@@ -99,6 +575,7 @@ where
             *self.l_mut(i, i) = T::one();
         }
         self.analyzed = true;
+        self.symbolic_time += start.elapsed();
         Ok(())
     }
 
@@ -113,38 +590,37 @@ where
                 self.dimension
             ));
         }
-        for i in 0..self.dimension {
-            for j in 0..self.dimension {
-                *self.l_mut(i, j) = if i == j { T::one() } else { T::zero() };
-            }
-        }
-
-        for j in 0..self.dimension {
-            let mut d_j = matrix.entry(j, j);
-            for k in 0..j {
-                let l_jk = self.l(j, k);
-                d_j -= l_jk * l_jk * self.d[k];
-            }
-            if d_j.abs() <= Self::epsilon() {
-                let magnitude = d_j.abs().to_f64().unwrap_or(f64::NAN);
+        if let Some(regularization) = &self.regularization {
+            if self.pivoting != DensePivoting::None {
                 return Err(anyhow!(
-                    "near-singular pivot encountered at column {} (|d_j| = {:.3e})",
-                    j,
-                    magnitude
+                    "pivot regularization needs an unpivoted factorization"
                 ));
             }
-            self.d[j] = d_j;
-
-            for i in (j + 1)..self.dimension {
-                let mut lij = matrix.entry(i, j);
-                for k in 0..j {
-                    lij -= self.l(i, k) * self.l(j, k) * self.d[k];
-                }
-                lij = lij / self.d[j];
-                *self.l_mut(i, j) = lij;
+            if regularization.signs.len() != self.dimension {
+                return Err(anyhow!(
+                    "{} pivot signs for a matrix of dimension {}",
+                    regularization.signs.len(),
+                    self.dimension
+                ));
             }
         }
+        let start = Instant::now();
+        let before = self.regularization_stats;
+        let factored = match self.pivoting {
+            DensePivoting::None => self.factor_unpivoted(matrix),
+            DensePivoting::BunchKaufman => self.factor_bunch_kaufman(matrix),
+        };
+        self.numeric_time += start.elapsed();
+        factored?;
         self.last_factor += 1;
+        self.perturbed = self.regularization.as_ref().is_some_and(|regularization| {
+            regularization.static_shift != T::zero()
+                || self.regularization_stats.dynamic_pivots > before.dynamic_pivots
+        });
+        if self.refinement_steps > 0 || self.regularization.is_some() {
+            self.matrix.clone_from(&matrix.data);
+        }
+        self.norm1 = norm1(&matrix.data, self.dimension);
         Ok(())
     }
 
@@ -156,22 +632,290 @@ where
                 self.dimension
             ));
         }
-        for i in 0..self.dimension {
-            for j in 0..i {
-                rhs[i] -= self.l(i, j) * rhs[j];
+        let steps = self.refinement();
+        if steps == 0 {
+            return self.substitute(rhs);
+        }
+        let dim = self.dimension;
+        let target = rhs.to_vec();
+        self.substitute(rhs)?;
+        let mut residual = vec![T::zero(); dim];
+        for _ in 0..steps {
+            for (i, r) in residual.iter_mut().enumerate() {
+                let row = &self.matrix[i * dim..(i + 1) * dim];
+                *r = target[i]
+                    - row
+                        .iter()
+                        .zip(rhs.iter())
+                        .fold(T::zero(), |acc, (&k, &x)| acc + k * x);
+            }
+            self.substitute(&mut residual)?;
+            for (x, &dx) in rhs.iter_mut().zip(&residual) {
+                *x += dx;
             }
         }
-        for i in 0..self.dimension {
-            if self.d[i].abs() <= Self::epsilon() {
-                return Err(anyhow!("singular diagonal entry encountered at {}", i));
+        Ok(())
+    }
+
+    fn symbolic_time(&self) -> Duration {
+        self.symbolic_time
+    }
+
+    fn numeric_time(&self) -> Duration {
+        self.numeric_time
+    }
+
+    fn regularization_stats(&self) -> Option<RegularizationStats<T>> {
+        self.regularization
+            .as_ref()
+            .map(|_| self.regularization_stats)
+    }
+
+    fn condition_estimate(&self) -> Option<T> {
+        if self.last_factor == 0 {
+            return None;
+        }
+        let inverse = inverse_norm1(self.dimension, |b| self.solve(b)).ok()?;
+        Some(self.norm1 * inverse)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn residual(matrix: &DenseKktMatrix<f64>, x: &[f64], b: &[f64]) -> f64 {
+        let n = matrix.dimension;
+        (0..n)
+            .map(|i| {
+                let row = &matrix.data[i * n..(i + 1) * n];
+                (b[i] - row.iter().zip(x).map(|(k, x)| k * x).sum::<f64>()).abs()
+            })
+            .fold(0.0, f64::max)
+    }
+
+    #[test]
+    fn refinement_recovers_digits_lost_to_small_pivots() {
+        // [δI B; Bᵀ -δI] with a tiny δ: the unpivoted factorization divides
+        // by δ and the plain solve loses most of its digits.
+        let (k, delta) = (3, 1e-7);
+        let b_block = [[1.0, 2.0, 0.5], [0.3, -1.0, 4.0], [2.5, 0.7, 1.0]];
+        let n = 2 * k;
+        let mut data = vec![0.0; n * n];
+        for i in 0..k {
+            data[i * n + i] = delta;
+            data[(k + i) * n + k + i] = -delta;
+            for j in 0..k {
+                data[i * n + k + j] = b_block[i][j];
+                data[(k + j) * n + i] = b_block[i][j];
             }
-            rhs[i] = rhs[i] / self.d[i];
         }
-        for i in (0..self.dimension).rev() {
-            for j in (i + 1)..self.dimension {
-                rhs[i] -= self.l(j, i) * rhs[j];
+        let matrix = DenseKktMatrix::new(n, data);
+        let b: Vec<f64> = (0..n).map(|i| (i as f64 + 1.0).sin()).collect();
+        let solve = |steps| {
+            let mut solver = DenseKktSolver::new().with_refinement(steps);
+            solver.factor(&matrix).unwrap();
+            let mut x = b.clone();
+            solver.solve(&mut x).unwrap();
+            residual(&matrix, &x, &b)
+        };
+        let (plain, refined) = (solve(0), solve(2));
+        assert!(refined < 1e-3 * plain);
+    }
+
+    #[test]
+    fn rank_one_update_matches_refactoring() {
+        // A quasi-definite [H Aᵀ; A -I] and v touching both blocks.
+        let data = vec![
+            4.0, 1.0, 0.0, 1.0, //
+            1.0, 3.0, 0.5, 2.0, //
+            0.0, 0.5, 2.0, -1.0, //
+            1.0, 2.0, -1.0, -1.0,
+        ];
+        let matrix = DenseKktMatrix::new(4, data.clone());
+        let v = [0.5, -1.0, 0.0, 2.0];
+        let mut updated = data;
+        for i in 0..4 {
+            for j in 0..4 {
+                updated[i * 4 + j] += v[i] * v[j];
             }
         }
-        Ok(())
+        let updated = DenseKktMatrix::new(4, updated);
+        let b = vec![1.0, -2.0, 0.5, 3.0];
+
+        let mut solver = DenseKktSolver::new();
+        solver.factor(&matrix).unwrap();
+        solver.update(&v, UpdateSign::Update).unwrap();
+        let mut x = b.clone();
+        solver.solve(&mut x).unwrap();
+        assert!(residual(&updated, &x, &b) < 1e-10);
+
+        solver.update(&v, UpdateSign::Downdate).unwrap();
+        let mut x = b.clone();
+        solver.solve(&mut x).unwrap();
+        assert!(residual(&matrix, &x, &b) < 1e-10);
+
+        // Removing 4 e0 e0ᵀ zeroes the first pivot; the factorization must
+        // survive the rejected downdate.
+        assert!(solver
+            .update(&[2.0, 0.0, 0.0, 0.0], UpdateSign::Downdate)
+            .is_err());
+        let mut x = b.clone();
+        solver.solve(&mut x).unwrap();
+        assert!(residual(&matrix, &x, &b) < 1e-10);
+    }
+
+    #[test]
+    fn factorization_spans_several_panels() {
+        // [H Bᵀ; B -I] with H diagonally dominant, larger than a few panels.
+        let (k, m) = (110, 40);
+        let n = k + m;
+        let mut data = vec![0.0; n * n];
+        for i in 0..k {
+            for j in 0..k {
+                data[i * n + j] = if i == j {
+                    k as f64
+                } else {
+                    ((i * 7 + j * 7) % 11) as f64 / 11.0
+                };
+            }
+        }
+        for r in 0..m {
+            for j in 0..k {
+                let value = ((r * 13 + j * 5) % 17) as f64 / 17.0 - 0.5;
+                data[(k + r) * n + j] = value;
+                data[j * n + k + r] = value;
+            }
+            data[(k + r) * n + k + r] = -1.0;
+        }
+        let matrix = DenseKktMatrix::new(n, data);
+        let b: Vec<f64> = (0..n).map(|i| (i as f64 * 0.1).cos()).collect();
+        let mut solver = DenseKktSolver::new();
+        solver.factor(&matrix).unwrap();
+        let mut x = b.clone();
+        solver.solve(&mut x).unwrap();
+        assert!(residual(&matrix, &x, &b) < 1e-10);
+    }
+
+    #[test]
+    fn pivoting_factors_a_zero_diagonal_block() {
+        // [0 Aᵀ; A 0]: solvable, but the first pivot is exactly zero.
+        let n = 4;
+        let a_block = [[1.0, 2.0], [3.0, 1.0]];
+        let mut data = vec![0.0; n * n];
+        for i in 0..2 {
+            for j in 0..2 {
+                data[(2 + i) * n + j] = a_block[i][j];
+                data[j * n + 2 + i] = a_block[i][j];
+            }
+        }
+        let matrix = DenseKktMatrix::new(n, data);
+        let b = vec![1.0, -1.0, 2.0, 0.5];
+        assert!(DenseKktSolver::new().factor(&matrix).is_err());
+
+        let mut solver = DenseKktSolver::new().with_pivoting(DensePivoting::BunchKaufman);
+        solver.factor(&matrix).unwrap();
+        let mut x = b.clone();
+        solver.solve(&mut x).unwrap();
+        assert!(residual(&matrix, &x, &b) < 1e-12);
+        assert!(solver
+            .update(&[1.0, 0.0, 0.0, 0.0], UpdateSign::Update)
+            .is_err());
+    }
+
+    #[test]
+    fn pivoting_matches_the_unpivoted_factorization() {
+        let (k, m) = (30, 12);
+        let n = k + m;
+        let mut data = vec![0.0; n * n];
+        for i in 0..k {
+            data[i * n + i] = 1.0 + (i % 5) as f64;
+        }
+        for r in 0..m {
+            for j in 0..k {
+                let value = ((r * 13 + j * 5) % 17) as f64 / 17.0 - 0.5;
+                data[(k + r) * n + j] = value;
+                data[j * n + k + r] = value;
+            }
+            data[(k + r) * n + k + r] = -1e-3;
+        }
+        let matrix = DenseKktMatrix::new(n, data);
+        let b: Vec<f64> = (0..n).map(|i| (i as f64).sin()).collect();
+        let solve = |pivoting| {
+            let mut solver = DenseKktSolver::new().with_pivoting(pivoting);
+            solver.factor(&matrix).unwrap();
+            let mut x = b.clone();
+            solver.solve(&mut x).unwrap();
+            x
+        };
+        let (plain, pivoted) = (
+            solve(DensePivoting::None),
+            solve(DensePivoting::BunchKaufman),
+        );
+        assert!(residual(&matrix, &pivoted, &b) < 1e-10);
+        for (x, y) in plain.iter().zip(&pivoted) {
+            assert!((x - y).abs() < 1e-8);
+        }
+    }
+
+    #[test]
+    fn dynamic_regularization_replaces_a_zero_pivot() {
+        // [1 0 1; 0 0 1; 1 1 0]: the second primal pivot is exactly zero.
+        let matrix = DenseKktMatrix::new(3, vec![1.0, 0.0, 1.0, 0.0, 0.0, 1.0, 1.0, 1.0, 0.0]);
+        let b = vec![1.0, 2.0, -1.0];
+        assert!(DenseKktSolver::new().factor(&matrix).is_err());
+
+        let mut solver = DenseKktSolver::new()
+            .with_refinement(3)
+            .with_regularization(Regularization::quasi_definite(2, 1));
+        solver.factor(&matrix).unwrap();
+        let mut x = b.clone();
+        solver.solve(&mut x).unwrap();
+        assert!(residual(&matrix, &x, &b) < 1e-10);
+        let stats = solver.regularization_stats().unwrap();
+        assert_eq!(stats.dynamic_pivots, 1);
+        assert!((stats.max_perturbation - 2e-7).abs() < 1e-12);
+    }
+
+    #[test]
+    fn condition_estimate_brackets_the_exact_condition() {
+        // Exact for a diagonal matrix: κ₁ = 100 / 1e-4.
+        let diagonal =
+            DenseKktMatrix::new(3, vec![1.0, 0.0, 0.0, 0.0, -1e-4, 0.0, 0.0, 0.0, 100.0]);
+        let mut solver = DenseKktSolver::new();
+        assert!(solver.condition_estimate().is_none());
+        solver.factor(&diagonal).unwrap();
+        let estimate: f64 = solver.condition_estimate().unwrap();
+        assert!((estimate / 1e6 - 1.0).abs() < 1e-10);
+
+        // Otherwise a lower bound within a small factor, here checked
+        // against ‖K‖₁‖K⁻¹‖₁ from every column of the inverse.
+        let n = 6;
+        let mut data = vec![0.0; n * n];
+        for i in 0..n {
+            for j in 0..n {
+                data[i * n + j] = 1.0 / (i + j + 1) as f64;
+            }
+            data[i * n + i] += if i < 4 { 1e-3 } else { -1.0 };
+        }
+        let matrix = DenseKktMatrix::new(n, data.clone());
+        let mut solver = DenseKktSolver::new().with_pivoting(DensePivoting::BunchKaufman);
+        solver.factor(&matrix).unwrap();
+        let mut inverse_norm: f64 = 0.0;
+        for j in 0..n {
+            let mut column = vec![0.0; n];
+            column[j] = 1.0;
+            solver.solve(&mut column).unwrap();
+            inverse_norm = inverse_norm.max(column.iter().map(|v: &f64| v.abs()).sum());
+        }
+        let exact = norm1(&data, n) * inverse_norm;
+        let estimate = solver.condition_estimate().unwrap();
+        assert!(
+            estimate <= exact * (1.0 + 1e-8),
+            "{} vs {}",
+            estimate,
+            exact
+        );
+        assert!(estimate >= exact / 3.0, "{} vs {}", estimate, exact);
     }
 }
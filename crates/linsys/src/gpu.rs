@@ -0,0 +1,449 @@
+use crate::dense::{DenseKktMatrix, DensePattern};
+use crate::minres::{DiagonalPreconditioner, MinresKktSolver, MinresPattern, MinresSystem};
+use anyhow::{anyhow, Result};
+use cvxrs_core::math::{cast, RealNumber};
+use cvxrs_core::stats::KrylovStats;
+use cvxrs_core::traits::{KktSolver, LinearOperator};
+use num_traits::{FromPrimitive, One};
+use std::marker::PhantomData;
+use std::sync::{mpsc, Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use wgpu::util::DeviceExt;
+
+const WORKGROUP_SIZE: u32 = 64;
+
+// Row-major dense products y = Mx and y = Mᵀx, one invocation per entry of
+// y. WGSL has no portable f64, so everything on the device is f32.
+const SHADER: &str = r#"
+struct Shape {
+    rows: u32,
+    cols: u32,
+    padding: vec2<u32>,
+}
+
+@group(0) @binding(0) var<uniform> shape: Shape;
+@group(0) @binding(1) var<storage, read> matrix: array<f32>;
+@group(0) @binding(2) var<storage, read> x: array<f32>;
+@group(0) @binding(3) var<storage, read_write> y: array<f32>;
+
+@compute @workgroup_size(64)
+fn multiply(@builtin(global_invocation_id) id: vec3<u32>) {
+    let row = id.x;
+    if (row >= shape.rows) {
+        return;
+    }
+    var acc = 0.0;
+    for (var col = 0u; col < shape.cols; col += 1u) {
+        acc += matrix[row * shape.cols + col] * x[col];
+    }
+    y[row] = acc;
+}
+
+@compute @workgroup_size(64)
+fn multiply_transpose(@builtin(global_invocation_id) id: vec3<u32>) {
+    let col = id.x;
+    if (col >= shape.cols) {
+        return;
+    }
+    var acc = 0.0;
+    for (var row = 0u; row < shape.rows; row += 1u) {
+        acc += matrix[row * shape.cols + col] * x[row];
+    }
+    y[col] = acc;
+}
+"#;
+
+static SHARED: OnceLock<Arc<GpuDevice>> = OnceLock::new();
+
+// A device with the product pipelines compiled.
+pub struct GpuDevice {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    layout: wgpu::BindGroupLayout,
+    multiply: wgpu::ComputePipeline,
+    multiply_transpose: wgpu::ComputePipeline,
+    name: String,
+}
+
+impl GpuDevice {
+    // The first high-performance adapter wgpu finds; fails on machines
+    // without one.
+    pub fn new() -> Result<Arc<Self>> {
+        let instance = wgpu::Instance::default();
+        let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+            power_preference: wgpu::PowerPreference::HighPerformance,
+            ..Default::default()
+        }))
+        .ok_or_else(|| anyhow!("no GPU adapter available"))?;
+        let (device, queue) = pollster::block_on(adapter.request_device(
+            &wgpu::DeviceDescriptor {
+                label: Some("cvxrs"),
+                required_features: wgpu::Features::empty(),
+                required_limits: adapter.limits(),
+            },
+            None,
+        ))?;
+
+        let buffer = |ty| wgpu::BindingType::Buffer {
+            ty,
+            has_dynamic_offset: false,
+            min_binding_size: None,
+        };
+        let storage = |read_only| buffer(wgpu::BufferBindingType::Storage { read_only });
+        let entries = [
+            buffer(wgpu::BufferBindingType::Uniform),
+            storage(true),
+            storage(true),
+            storage(false),
+        ]
+        .into_iter()
+        .enumerate()
+        .map(|(binding, ty)| wgpu::BindGroupLayoutEntry {
+            binding: binding as u32,
+            visibility: wgpu::ShaderStages::COMPUTE,
+            ty,
+            count: None,
+        })
+        .collect::<Vec<_>>();
+        let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("cvxrs products"),
+            entries: &entries,
+        });
+        let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("cvxrs products"),
+            source: wgpu::ShaderSource::Wgsl(SHADER.into()),
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("cvxrs products"),
+            bind_group_layouts: &[&layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = |entry_point| {
+            device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+                label: Some(entry_point),
+                layout: Some(&pipeline_layout),
+                module: &module,
+                entry_point,
+            })
+        };
+        let (multiply, multiply_transpose) = (pipeline("multiply"), pipeline("multiply_transpose"));
+        Ok(Arc::new(Self {
+            name: adapter.get_info().name,
+            device,
+            queue,
+            layout,
+            multiply,
+            multiply_transpose,
+        }))
+    }
+
+    // One device for the whole process, set up on first use.
+    pub fn shared() -> Result<Arc<Self>> {
+        if let Some(device) = SHARED.get() {
+            return Ok(device.clone());
+        }
+        let device = Self::new()?;
+        Ok(SHARED.get_or_init(|| device).clone())
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+// A row-major dense matrix resident on the device. Products upload x and
+// read y back, converting through f32 either way.
+pub struct GpuMatrix<T> {
+    device: Arc<GpuDevice>,
+    rows: usize,
+    cols: usize,
+    input: wgpu::Buffer,
+    output: wgpu::Buffer,
+    staging: wgpu::Buffer,
+    bind_group: wgpu::BindGroup,
+    // The vector buffers are shared, so products run one at a time.
+    lock: Mutex<()>,
+    scalar: PhantomData<fn() -> T>,
+}
+
+impl<T> GpuMatrix<T>
+where
+    T: RealNumber,
+{
+    pub fn upload(device: Arc<GpuDevice>, rows: usize, cols: usize, data: &[T]) -> Result<Self> {
+        if data.len() != rows * cols {
+            return Err(anyhow!(
+                "{} values for a {}x{} matrix",
+                data.len(),
+                rows,
+                cols
+            ));
+        }
+        let limit = device.device.limits().max_storage_buffer_binding_size as usize;
+        if rows.max(cols) > u32::MAX as usize || data.len() * 4 > limit {
+            return Err(anyhow!(
+                "a {}x{} matrix exceeds the device's {} byte buffers",
+                rows,
+                cols,
+                limit
+            ));
+        }
+        // Zero-sized bindings are invalid, so empty operands keep one entry.
+        let mut values: Vec<f32> = data.iter().map(|&v| cast(v)).collect();
+        if values.is_empty() {
+            values.push(0.0);
+        }
+        let init = |label, contents: &[u8], usage| {
+            device
+                .device
+                .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                    label: Some(label),
+                    contents,
+                    usage,
+                })
+        };
+        let shape = init(
+            "cvxrs shape",
+            bytemuck::cast_slice(&[rows as u32, cols as u32, 0, 0]),
+            wgpu::BufferUsages::UNIFORM,
+        );
+        let matrix = init(
+            "cvxrs matrix",
+            bytemuck::cast_slice(&values),
+            wgpu::BufferUsages::STORAGE,
+        );
+        let vector = |label, usage| {
+            device.device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some(label),
+                size: (rows.max(cols).max(1) * 4) as u64,
+                usage,
+                mapped_at_creation: false,
+            })
+        };
+        let input = vector(
+            "cvxrs input",
+            wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+        );
+        let output = vector(
+            "cvxrs output",
+            wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+        );
+        let staging = vector(
+            "cvxrs staging",
+            wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+        );
+        let bind_group = device.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("cvxrs products"),
+            layout: &device.layout,
+            entries: &[&shape, &matrix, &input, &output]
+                .into_iter()
+                .enumerate()
+                .map(|(binding, buffer)| wgpu::BindGroupEntry {
+                    binding: binding as u32,
+                    resource: buffer.as_entire_binding(),
+                })
+                .collect::<Vec<_>>(),
+        });
+        Ok(Self {
+            device,
+            rows,
+            cols,
+            input,
+            output,
+            staging,
+            bind_group,
+            lock: Mutex::new(()),
+            scalar: PhantomData,
+        })
+    }
+
+    // Panics if the device is lost mid-product, as the operator interface
+    // has no way to report it.
+    fn product(&self, transpose: bool, x: &[T], y: &mut [T]) {
+        let (inputs, outputs) = if transpose {
+            (self.rows, self.cols)
+        } else {
+            (self.cols, self.rows)
+        };
+        if outputs == 0 {
+            return;
+        }
+        let GpuDevice { device, queue, .. } = &*self.device;
+        let _guard = self.lock.lock().unwrap();
+        if inputs > 0 {
+            let values: Vec<f32> = x[..inputs].iter().map(|&v| cast(v)).collect();
+            queue.write_buffer(&self.input, 0, bytemuck::cast_slice(&values));
+        }
+        let mut encoder =
+            device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+        {
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: None,
+                timestamp_writes: None,
+            });
+            pass.set_pipeline(if transpose {
+                &self.device.multiply_transpose
+            } else {
+                &self.device.multiply
+            });
+            pass.set_bind_group(0, &self.bind_group, &[]);
+            pass.dispatch_workgroups((outputs as u32).div_ceil(WORKGROUP_SIZE), 1, 1);
+        }
+        let size = (outputs * 4) as u64;
+        encoder.copy_buffer_to_buffer(&self.output, 0, &self.staging, 0, size);
+        queue.submit(Some(encoder.finish()));
+
+        let slice = self.staging.slice(..size);
+        let (sender, receiver) = mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |mapped| {
+            let _ = sender.send(mapped);
+        });
+        device.poll(wgpu::Maintain::Wait);
+        receiver
+            .recv()
+            .expect("GPU readback was dropped")
+            .expect("GPU readback failed");
+        {
+            let view = slice.get_mapped_range();
+            let values: &[f32] = bytemuck::cast_slice(&view);
+            for (y, &v) in y.iter_mut().zip(values) {
+                *y = cast(v);
+            }
+        }
+        self.staging.unmap();
+    }
+}
+
+impl<T: RealNumber> LinearOperator<T> for GpuMatrix<T> {
+    fn dim(&self) -> (usize, usize) {
+        (self.rows, self.cols)
+    }
+
+    fn apply(&self, x: &[T], y: &mut [T]) {
+        self.product(false, x, y);
+    }
+
+    fn apply_transpose(&self, x: &[T], y: &mut [T]) {
+        self.product(true, x, y);
+    }
+}
+
+// MINRES on a dense KKT matrix kept on the GPU, for systems in the
+// thousands where the dense factorization is the bottleneck. Only the
+// products run on the device, in single precision, so the tolerance
+// defaults to 1e-6 rather than MINRES's own.
+pub struct GpuKktSolver<T: RealNumber> {
+    device: Arc<GpuDevice>,
+    inner: MinresKktSolver<T>,
+    upload_time: Duration,
+}
+
+impl<T> GpuKktSolver<T>
+where
+    T: RealNumber + FromPrimitive + One,
+{
+    pub fn new(device: Arc<GpuDevice>) -> Self {
+        Self {
+            device,
+            inner: MinresKktSolver::new().with_tolerance(T::from_f64(1e-6).unwrap()),
+            upload_time: Duration::ZERO,
+        }
+    }
+
+    pub fn with_preconditioner(mut self, preconditioner: DiagonalPreconditioner<T>) -> Self {
+        self.inner = self.inner.with_preconditioner(preconditioner);
+        self
+    }
+
+    pub fn with_tolerance(mut self, tolerance: T) -> Self {
+        self.inner = self.inner.with_tolerance(tolerance);
+        self
+    }
+
+    pub fn with_max_iterations(mut self, iterations: usize) -> Self {
+        self.inner = self.inner.with_max_iterations(iterations);
+        self
+    }
+}
+
+impl<T> KktSolver<T> for GpuKktSolver<T>
+where
+    T: RealNumber + FromPrimitive + One,
+{
+    type Pattern = DensePattern;
+    type Matrix = DenseKktMatrix<T>;
+
+    fn analyze_pattern(&mut self, pattern: &Self::Pattern) -> Result<()> {
+        self.inner
+            .analyze_pattern(&MinresPattern::new(pattern.dimension()))
+    }
+
+    fn factor(&mut self, matrix: &Self::Matrix) -> Result<()> {
+        let start = Instant::now();
+        let n = matrix.dimension;
+        let operator = GpuMatrix::upload(self.device.clone(), n, n, &matrix.data)?;
+        self.upload_time += start.elapsed();
+        self.inner
+            .factor(&MinresSystem::new(Arc::new(operator), matrix.diagonal()))
+    }
+
+    fn solve(&self, rhs: &mut [T]) -> Result<()> {
+        self.inner.solve(rhs)
+    }
+
+    fn numeric_time(&self) -> Duration {
+        self.upload_time + self.inner.numeric_time()
+    }
+
+    fn krylov_stats(&self) -> Option<KrylovStats> {
+        self.inner.krylov_stats()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Machines without an adapter skip these.
+    #[test]
+    fn products_match_the_host() {
+        let Ok(device) = GpuDevice::new() else {
+            return;
+        };
+        let data: Vec<f64> = (0..12).map(|i| (i as f64 * 0.7).sin()).collect();
+        let matrix = GpuMatrix::upload(device, 3, 4, &data).unwrap();
+        let x = vec![1.0, -2.0, 0.5, 3.0];
+        let mut y = vec![0.0; 3];
+        matrix.apply(&x, &mut y);
+        for (row, y) in y.iter().enumerate() {
+            let expected: f64 = (0..4).map(|col| data[row * 4 + col] * x[col]).sum();
+            assert!((y - expected).abs() < 1e-5);
+        }
+        let mut z = vec![0.0; 4];
+        matrix.apply_transpose(&y, &mut z);
+        for (col, z) in z.iter().enumerate() {
+            let expected: f64 = (0..3).map(|row| data[row * 4 + col] * y[row]).sum();
+            assert!((z - expected).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn solves_a_quasi_definite_system() {
+        let Ok(device) = GpuDevice::new() else {
+            return;
+        };
+        let matrix = DenseKktMatrix::new(3, vec![4.0, 1.0, 1.0, 1.0, 3.0, -1.0, 1.0, -1.0, -1e-2]);
+        let b: Vec<f64> = vec![1.0, 2.0, -1.0];
+        let mut solver = GpuKktSolver::new(device);
+        solver.analyze_pattern(&DensePattern::new(3)).unwrap();
+        solver.factor(&matrix).unwrap();
+        let mut x = b.clone();
+        solver.solve(&mut x).unwrap();
+        let mut ax = vec![0.0; 3];
+        matrix.apply(&x, &mut ax);
+        for (ax, b) in ax.iter().zip(&b) {
+            assert!((ax - b).abs() < 1e-4);
+        }
+        assert_eq!(solver.krylov_stats().unwrap().solves, 1);
+    }
+}
@@ -1,7 +1,38 @@
 #![forbid(unsafe_code)]
 
+pub mod cg;
+mod condition;
 pub mod dense;
+#[cfg(feature = "gpu")]
+pub mod gpu;
+pub mod minres;
+pub mod ordering;
+pub mod regularization;
 pub mod sparse;
 
-pub use dense::{DenseKktMatrix, DenseKktSolver, DensePattern};
+pub use cg::{CgKktSolver, CgPattern, CgSystem};
+pub use dense::{DenseKktMatrix, DenseKktSolver, DensePattern, DensePivoting};
+#[cfg(feature = "gpu")]
+pub use gpu::{GpuDevice, GpuKktSolver, GpuMatrix};
+pub use minres::{DiagonalPreconditioner, MinresKktSolver, MinresPattern, MinresSystem};
+pub use ordering::FillOrdering;
+pub use regularization::Regularization;
 pub use sparse::{SparseKktMatrix, SparseKktSolver, SparsePattern};
+
+use cvxrs_core::math::RealNumber;
+
+// Direction of a rank-one modification K ± v vᵀ of a factored matrix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpdateSign {
+    Update,
+    Downdate,
+}
+
+impl UpdateSign {
+    pub fn value<T: RealNumber>(self) -> T {
+        match self {
+            UpdateSign::Update => T::one(),
+            UpdateSign::Downdate => -T::one(),
+        }
+    }
+}
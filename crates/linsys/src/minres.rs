@@ -0,0 +1,311 @@
+use anyhow::{anyhow, Result};
+use cvxrs_core::math::{dot, RealNumber};
+use cvxrs_core::stats::{KrylovStats, KrylovTermination};
+use cvxrs_core::traits::{KktSolver, LinearOperator};
+use num_traits::{FromPrimitive, One};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone)]
+pub struct MinresPattern {
+    dimension: usize,
+}
+
+impl MinresPattern {
+    pub fn new(dimension: usize) -> Self {
+        Self { dimension }
+    }
+
+    pub fn dimension(&self) -> usize {
+        self.dimension
+    }
+}
+
+// A symmetric, possibly indefinite system given through its products, with
+// its diagonal for the preconditioner.
+#[derive(Clone)]
+pub struct MinresSystem<T: RealNumber> {
+    pub operator: Arc<dyn LinearOperator<T>>,
+    pub diagonal: Vec<T>,
+}
+
+impl<T> MinresSystem<T>
+where
+    T: RealNumber,
+{
+    pub fn new(operator: Arc<dyn LinearOperator<T>>, diagonal: Vec<T>) -> Self {
+        Self { operator, diagonal }
+    }
+}
+
+// MINRES needs a positive definite preconditioner even though the system
+// is not, so the diagonal enters through its magnitudes.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum DiagonalPreconditioner<T> {
+    Identity,
+    // Divide by max(|a_ii|, floor); the floor keeps the small regularized
+    // diagonal of the constraint rows from blowing those rows up.
+    #[default]
+    AbsoluteDiagonal,
+    AbsoluteDiagonalWithFloor(T),
+}
+
+// Preconditioned MINRES (Paige and Saunders) for the quasi-definite KKT
+// matrices that have no positive definite reduction to hand to CG. Like
+// the CG solver, "factoring" only takes the operator and the
+// preconditioner.
+pub struct MinresKktSolver<T: RealNumber> {
+    dimension: usize,
+    system: Option<MinresSystem<T>>,
+    preconditioner: DiagonalPreconditioner<T>,
+    inverse_diagonal: Vec<T>,
+    tolerance: T,
+    max_iterations: Option<usize>,
+    numeric_time: Duration,
+    krylov: Mutex<KrylovStats>,
+}
+
+impl<T> MinresKktSolver<T>
+where
+    T: RealNumber + FromPrimitive + One,
+{
+    pub fn new() -> Self {
+        Self {
+            dimension: 0,
+            system: None,
+            preconditioner: DiagonalPreconditioner::default(),
+            inverse_diagonal: Vec::new(),
+            tolerance: T::from_f64(1e-10).unwrap(),
+            max_iterations: None,
+            numeric_time: Duration::ZERO,
+            krylov: Mutex::new(KrylovStats::default()),
+        }
+    }
+
+    pub fn with_preconditioner(mut self, preconditioner: DiagonalPreconditioner<T>) -> Self {
+        self.preconditioner = preconditioner;
+        self
+    }
+
+    // Iterations stop once the preconditioned residual norm is within
+    // `tolerance` times that of the right-hand side.
+    pub fn with_tolerance(mut self, tolerance: T) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    // Caps the iterations of each solve; four times the dimension unless
+    // set.
+    pub fn with_max_iterations(mut self, iterations: usize) -> Self {
+        self.max_iterations = Some(iterations);
+        self
+    }
+}
+
+impl<T> Default for MinresKktSolver<T>
+where
+    T: RealNumber + FromPrimitive + One,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> KktSolver<T> for MinresKktSolver<T>
+where
+    T: RealNumber + FromPrimitive + One,
+{
+    type Pattern = MinresPattern;
+    type Matrix = MinresSystem<T>;
+
+    fn analyze_pattern(&mut self, pattern: &Self::Pattern) -> Result<()> {
+        self.dimension = pattern.dimension();
+        Ok(())
+    }
+
+    fn factor(&mut self, matrix: &Self::Matrix) -> Result<()> {
+        let start = Instant::now();
+        let (rows, cols) = matrix.operator.dim();
+        if rows != cols || matrix.diagonal.len() != rows {
+            return Err(anyhow!(
+                "MINRES needs a square operator with its diagonal, got {}x{} and {} entries",
+                rows,
+                cols,
+                matrix.diagonal.len()
+            ));
+        }
+        let floor = match self.preconditioner {
+            DiagonalPreconditioner::Identity => None,
+            DiagonalPreconditioner::AbsoluteDiagonal => Some(T::from_f64(1e-8).unwrap()),
+            DiagonalPreconditioner::AbsoluteDiagonalWithFloor(floor) => Some(floor),
+        };
+        self.inverse_diagonal = match floor {
+            None => vec![T::one(); rows],
+            Some(floor) => {
+                if floor <= T::zero() {
+                    return Err(anyhow!("the preconditioner floor must be positive"));
+                }
+                matrix
+                    .diagonal
+                    .iter()
+                    .map(|&d| d.abs().max(floor).recip())
+                    .collect()
+            }
+        };
+        self.dimension = rows;
+        self.system = Some(matrix.clone());
+        self.numeric_time += start.elapsed();
+        Ok(())
+    }
+
+    fn solve(&self, rhs: &mut [T]) -> Result<()> {
+        let Some(system) = &self.system else {
+            return Err(anyhow!("MINRES solve before the operator was set"));
+        };
+        if rhs.len() != self.dimension {
+            return Err(anyhow!(
+                "rhs length {} does not match dimension {}",
+                rhs.len(),
+                self.dimension
+            ));
+        }
+        let n = self.dimension;
+        let precondition = |r: &[T], z: &mut [T]| {
+            for ((z, &r), &d) in z.iter_mut().zip(r).zip(&self.inverse_diagonal) {
+                *z = r * d;
+            }
+        };
+
+        // Lanczos vectors r1, r2 of the preconditioned three-term recurrence,
+        // and the last three search directions of the QR update.
+        let mut x = vec![T::zero(); n];
+        let mut r1 = rhs.to_vec();
+        let mut r2 = rhs.to_vec();
+        let mut y = vec![T::zero(); n];
+        precondition(&r1, &mut y);
+        let beta1 = dot(&r1, &y).sqrt();
+        let (mut w, mut w1, mut w2) = (vec![T::zero(); n], vec![T::zero(); n], vec![T::zero(); n]);
+        let mut v = vec![T::zero(); n];
+        let (mut beta, mut old_beta) = (beta1, T::zero());
+        let (mut dbar, mut epsilon) = (T::zero(), T::zero());
+        let mut phibar = beta1;
+        let (mut cs, mut sn) = (-T::one(), T::zero());
+
+        let max_iterations = self.max_iterations.unwrap_or(4 * n.max(1));
+        let threshold = self.tolerance * beta1;
+        let mut iterations = 0;
+        let mut termination = KrylovTermination::IterationLimit;
+        loop {
+            // A vanishing beta means the Krylov space is invariant and the
+            // current iterate is exact.
+            if phibar <= threshold || beta == T::zero() {
+                termination = KrylovTermination::Converged;
+                break;
+            }
+            if iterations == max_iterations {
+                break;
+            }
+            iterations += 1;
+
+            for (v, &y) in v.iter_mut().zip(&y) {
+                *v = y / beta;
+            }
+            system.operator.apply(&v, &mut y);
+            if iterations >= 2 {
+                let ratio = beta / old_beta;
+                for (y, &r) in y.iter_mut().zip(&r1) {
+                    *y -= ratio * r;
+                }
+            }
+            let alpha = dot(&v, &y);
+            let ratio = alpha / beta;
+            for (y, &r) in y.iter_mut().zip(&r2) {
+                *y -= ratio * r;
+            }
+            std::mem::swap(&mut r1, &mut r2);
+            r2.copy_from_slice(&y);
+            precondition(&r2, &mut y);
+            old_beta = beta;
+            let curvature = dot(&r2, &y);
+            if curvature < T::zero() {
+                return Err(anyhow!("MINRES preconditioner is not positive definite"));
+            }
+            beta = curvature.sqrt();
+
+            // Apply the previous rotation, then build the one that
+            // eliminates beta from the new column of the tridiagonal.
+            let old_epsilon = epsilon;
+            let delta = cs * dbar + sn * alpha;
+            let gbar = sn * dbar - cs * alpha;
+            epsilon = sn * beta;
+            dbar = -cs * beta;
+            let gamma = gbar.hypot(beta).max(T::epsilon());
+            cs = gbar / gamma;
+            sn = beta / gamma;
+            let phi = cs * phibar;
+            phibar = sn * phibar;
+
+            std::mem::swap(&mut w1, &mut w2);
+            std::mem::swap(&mut w2, &mut w);
+            for i in 0..n {
+                w[i] = (v[i] - old_epsilon * w1[i] - delta * w2[i]) / gamma;
+                x[i] += phi * w[i];
+            }
+        }
+        rhs.copy_from_slice(&x);
+        self.krylov.lock().unwrap().record(iterations, termination);
+        Ok(())
+    }
+
+    fn numeric_time(&self) -> Duration {
+        self.numeric_time
+    }
+
+    fn krylov_stats(&self) -> Option<KrylovStats> {
+        Some(*self.krylov.lock().unwrap())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dense::DenseKktMatrix;
+
+    #[test]
+    fn solves_a_quasi_definite_system() {
+        // [H Aᵀ; A -δI] with H = diag(4, 3, 2) and one constraint row.
+        let data = vec![
+            4.0, 0.0, 0.0, 1.0, //
+            0.0, 3.0, 0.0, 2.0, //
+            0.0, 0.0, 2.0, -1.0, //
+            1.0, 2.0, -1.0, -1e-6,
+        ];
+        let matrix = DenseKktMatrix::new(4, data);
+        let diagonal = matrix.diagonal();
+        let operator = Arc::new(matrix);
+        let b: Vec<f64> = vec![1.0, -2.0, 0.5, 3.0];
+        for preconditioner in [
+            DiagonalPreconditioner::Identity,
+            DiagonalPreconditioner::AbsoluteDiagonal,
+            DiagonalPreconditioner::AbsoluteDiagonalWithFloor(1.0),
+        ] {
+            let mut solver = MinresKktSolver::new()
+                .with_tolerance(1e-12)
+                .with_preconditioner(preconditioner);
+            solver
+                .factor(&MinresSystem::new(operator.clone(), diagonal.clone()))
+                .unwrap();
+            let mut x = b.clone();
+            solver.solve(&mut x).unwrap();
+            let mut ax = vec![0.0; 4];
+            operator.apply(&x, &mut ax);
+            for (ax, b) in ax.iter().zip(&b) {
+                assert!((ax - b).abs() < 1e-8, "{:?}", preconditioner);
+            }
+            let stats = solver.krylov_stats().unwrap();
+            assert_eq!(stats.solves, 1);
+            assert_eq!(stats.last_termination, Some(KrylovTermination::Converged));
+            assert!(stats.iterations <= 8);
+        }
+    }
+}
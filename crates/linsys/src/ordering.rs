@@ -0,0 +1,97 @@
+use std::collections::BTreeSet;
+
+// Symmetric permutations applied before a sparse factorization.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FillOrdering {
+    // Factor the matrix in the order it was given.
+    Natural,
+    // Approximate minimum degree, which keeps fill-in low on the block
+    // structures KKT systems come with.
+    #[default]
+    Amd,
+}
+
+impl FillOrdering {
+    // The elimination order for the symmetric pattern whose upper triangle is
+    // given in CSC form: `order[k]` is the original index pivoted on k-th.
+    pub fn order(&self, col_ptr: &[usize], row_idx: &[usize]) -> Vec<usize> {
+        match self {
+            FillOrdering::Natural => (0..col_ptr.len() - 1).collect(),
+            FillOrdering::Amd => approximate_minimum_degree(col_ptr, row_idx),
+        }
+    }
+}
+
+// Minimum degree on the quotient graph: an eliminated pivot becomes an
+// element standing for the clique it would create, and elements adjacent to
+// the pivot are absorbed into it, so the graph never grows. Degrees are the
+// approximate bound |A_i| + sum |L_e \ i|, which overcounts variables shared
+// between elements but needs no set unions.
+pub fn approximate_minimum_degree(col_ptr: &[usize], row_idx: &[usize]) -> Vec<usize> {
+    let n = col_ptr.len() - 1;
+    let mut adjacency: Vec<Vec<usize>> = vec![Vec::new(); n];
+    for col in 0..n {
+        for &row in &row_idx[col_ptr[col]..col_ptr[col + 1]] {
+            if row != col {
+                adjacency[row].push(col);
+                adjacency[col].push(row);
+            }
+        }
+    }
+    for neighbours in &mut adjacency {
+        neighbours.sort_unstable();
+        neighbours.dedup();
+    }
+
+    // Elements adjacent to each variable, and the variables of each element
+    // indexed by the pivot that created it; absorbed elements are emptied.
+    let mut elements: Vec<Vec<usize>> = vec![Vec::new(); n];
+    let mut members: Vec<Vec<usize>> = vec![Vec::new(); n];
+    let mut degree: Vec<usize> = adjacency.iter().map(Vec::len).collect();
+    let mut queue: BTreeSet<(usize, usize)> = degree.iter().copied().zip(0..n).collect();
+    let mut mark = vec![usize::MAX; n];
+    let mut order = Vec::with_capacity(n);
+
+    while let Some((_, pivot)) = queue.pop_first() {
+        order.push(pivot);
+        mark[pivot] = pivot;
+        let mut reach = Vec::new();
+        for &i in &adjacency[pivot] {
+            if mark[i] != pivot {
+                mark[i] = pivot;
+                reach.push(i);
+            }
+        }
+        for e in std::mem::take(&mut elements[pivot]) {
+            for i in std::mem::take(&mut members[e]) {
+                if mark[i] != pivot {
+                    mark[i] = pivot;
+                    reach.push(i);
+                }
+            }
+        }
+        adjacency[pivot] = Vec::new();
+
+        // Edges inside the new element are implied by it and can go, along
+        // with every reference to the pivot and the absorbed elements.
+        for &i in &reach {
+            adjacency[i].retain(|&j| mark[j] != pivot);
+            elements[i].retain(|&e| !members[e].is_empty());
+            elements[i].push(pivot);
+        }
+        members[pivot] = reach;
+
+        let remaining = n - order.len();
+        for &i in &members[pivot] {
+            let external = adjacency[i].len()
+                + elements[i]
+                    .iter()
+                    .map(|&e| members[e].len() - 1)
+                    .sum::<usize>();
+            queue.remove(&(degree[i], i));
+            degree[i] = external.min(remaining - 1);
+            queue.insert((degree[i], i));
+        }
+    }
+    order
+}
@@ -0,0 +1,68 @@
+use cvxrs_core::math::RealNumber;
+use cvxrs_core::stats::RegularizationStats;
+use num_traits::FromPrimitive;
+
+// Refinement steps every solve takes after a factorization that changed
+// its pivots, whatever the solver was configured with.
+pub(crate) const REGULARIZED_REFINEMENT_STEPS: usize = 2;
+
+// Static and dynamic regularization of a quasi-definite factorization, as in
+// QDLDL and Clarabel. Every pivot is first pushed by `static_shift` towards
+// its expected sign; one that is then still below `threshold` on that side
+// is replaced by ±`dynamic_shift` instead of failing. The factors are then
+// those of a nearby matrix, and solves refine against the exact one.
+#[derive(Debug, Clone)]
+pub struct Regularization<T> {
+    // Expected sign of each pivot in the original order: 1 for the primal
+    // block, -1 for the dual one.
+    pub signs: Vec<i8>,
+    pub static_shift: T,
+    pub threshold: T,
+    pub dynamic_shift: T,
+}
+
+impl<T> Regularization<T>
+where
+    T: RealNumber + FromPrimitive,
+{
+    // `primal` positive pivots followed by `dual` negative ones, with no
+    // static shift and Clarabel's dynamic defaults.
+    pub fn quasi_definite(primal: usize, dual: usize) -> Self {
+        let mut signs = vec![1; primal];
+        signs.resize(primal + dual, -1);
+        Self {
+            signs,
+            static_shift: T::zero(),
+            threshold: T::from_f64(1e-13).unwrap(),
+            dynamic_shift: T::from_f64(2e-7).unwrap(),
+        }
+    }
+
+    pub fn with_static_shift(mut self, shift: T) -> Self {
+        self.static_shift = shift;
+        self
+    }
+
+    pub fn with_dynamic_shift(mut self, threshold: T, shift: T) -> Self {
+        self.threshold = threshold;
+        self.dynamic_shift = shift;
+        self
+    }
+
+    // The pivot to factor with in place of `pivot` at original index
+    // `index`, with the change recorded in `stats`.
+    pub(crate) fn adjust(&self, index: usize, pivot: T, stats: &mut RegularizationStats<T>) -> T {
+        let sign = if self.signs[index] < 0 {
+            -T::one()
+        } else {
+            T::one()
+        };
+        let mut adjusted = pivot + sign * self.static_shift;
+        if sign * adjusted <= self.threshold {
+            adjusted = sign * self.dynamic_shift;
+            stats.dynamic_pivots += 1;
+        }
+        stats.max_perturbation = stats.max_perturbation.max((adjusted - pivot).abs());
+        adjusted
+    }
+}
@@ -1,18 +1,131 @@
-use crate::dense::{DenseKktMatrix, DenseKktSolver, DensePattern};
-use anyhow::Result;
+use crate::condition::inverse_norm1;
+use crate::ordering::FillOrdering;
+use crate::regularization::{Regularization, REGULARIZED_REFINEMENT_STEPS};
+use crate::UpdateSign;
+use anyhow::{anyhow, Result};
 use cvxrs_core::math::RealNumber;
-use cvxrs_core::traits::KktSolver;
+use cvxrs_core::stats::RegularizationStats;
+use cvxrs_core::traits::{KktSolver, LinearOperator};
 use num_traits::{FromPrimitive, One};
 use sprs::CsMat;
+use std::time::{Duration, Instant};
+
+// Column pointers and row indices of the upper triangle (row <= col) of a
+// symmetric matrix, rows sorted within each column.
+#[derive(Debug, Clone)]
+struct UpperStructure {
+    col_ptr: Vec<usize>,
+    row_idx: Vec<usize>,
+}
+
+// The stored nonzeros of a KKT matrix exactly as handed in; a later matrix
+// with the same storage reuses the analysis made for this one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct InputStructure {
+    csc: bool,
+    indptr: Vec<usize>,
+    indices: Vec<usize>,
+}
+
+impl InputStructure {
+    fn of<T>(matrix: &CsMat<T>) -> Self {
+        let (rows, cols) = matrix.shape();
+        assert_eq!(rows, cols, "sparse KKT matrices must be square");
+        Self {
+            csc: matrix.is_csc(),
+            indptr: matrix.indptr().to_proper().into_owned(),
+            indices: matrix.indices().to_vec(),
+        }
+    }
+
+    fn matches<T>(&self, matrix: &CsMat<T>) -> bool {
+        self.csc == matrix.is_csc()
+            && self.indptr[..] == matrix.indptr().to_proper()[..]
+            && self.indices[..] == *matrix.indices()
+    }
+
+    // The entries between indices marked in `keep`, renumbered to close the
+    // gaps, in the same storage.
+    fn retain(&self, keep: &[bool]) -> Self {
+        let mut renumbered = vec![0; keep.len()];
+        let mut next = 0;
+        for (index, &kept) in renumbered.iter_mut().zip(keep) {
+            *index = next;
+            next += usize::from(kept);
+        }
+        let mut indptr = vec![0];
+        let mut indices = Vec::with_capacity(self.indices.len());
+        for outer in (0..keep.len()).filter(|&outer| keep[outer]) {
+            let range = self.indptr[outer]..self.indptr[outer + 1];
+            indices.extend(
+                self.indices[range]
+                    .iter()
+                    .filter(|&&inner| keep[inner])
+                    .map(|&inner| renumbered[inner]),
+            );
+            indptr.push(indices.len());
+        }
+        Self {
+            csc: self.csc,
+            indptr,
+            indices,
+        }
+    }
+
+    // Folds the symmetric matrix onto its upper triangle, with the slot each
+    // stored entry lands in. An entry stored in both triangles shares one
+    // slot, so full and half storage describe the same matrix.
+    fn upper_triangle(&self) -> (UpperStructure, Vec<usize>) {
+        let n = self.indptr.len() - 1;
+        let mut columns: Vec<Vec<(usize, usize)>> = vec![Vec::new(); n];
+        for outer in 0..n {
+            for p in self.indptr[outer]..self.indptr[outer + 1] {
+                let inner = self.indices[p];
+                columns[outer.max(inner)].push((outer.min(inner), p));
+            }
+        }
+        let mut col_ptr = Vec::with_capacity(n + 1);
+        let mut row_idx = Vec::with_capacity(self.indices.len());
+        let mut slots = vec![0; self.indices.len()];
+        col_ptr.push(0);
+        for mut column in columns {
+            column.sort_unstable();
+            for (row, p) in column {
+                if row_idx.len() == *col_ptr.last().unwrap() || row_idx.last() != Some(&row) {
+                    row_idx.push(row);
+                }
+                slots[p] = row_idx.len() - 1;
+            }
+            col_ptr.push(row_idx.len());
+        }
+        (UpperStructure { col_ptr, row_idx }, slots)
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct SparsePattern {
     dimension: usize,
+    structure: Option<InputStructure>,
 }
 
 impl SparsePattern {
+    // A pattern carrying only the dimension; the symbolic analysis is then
+    // deferred to the first `factor`.
     pub fn new(dimension: usize) -> Self {
-        Self { dimension }
+        Self {
+            dimension,
+            structure: None,
+        }
+    }
+
+    // The nonzero structure of `matrix`, which may store either triangle
+    // or both. Matrices factored later must store exactly these entries for
+    // the analysis to carry over.
+    pub fn from_matrix<T>(matrix: &CsMat<T>) -> Self {
+        Self {
+            dimension: matrix.rows(),
+            structure: Some(InputStructure::of(matrix)),
+        }
     }
 
     pub fn dimension(&self) -> usize {
@@ -33,24 +146,217 @@ where
         Self { matrix }
     }
 
-    fn to_dense(&self) -> DenseKktMatrix<T> {
-        let (rows, cols) = self.matrix.shape();
-        assert_eq!(rows, cols, "sparse KKT matrices must be square");
-        let dimension = rows;
-        let mut data = vec![T::zero(); dimension * dimension];
-        for (col, column) in self.matrix.outer_iterator().enumerate() {
-            for (row, value) in column.iter() {
-                data[row * dimension + col] = *value;
-                data[col * dimension + row] = *value;
+    pub fn diagonal(&self) -> Vec<T> {
+        (0..self.matrix.rows())
+            .map(|i| self.matrix.get(i, i).copied().unwrap_or_else(T::zero))
+            .collect()
+    }
+}
+
+// Products with the symmetric matrix the factorization sees: an entry
+// stored in one triangle stands for its mirror as well.
+impl<T: RealNumber> LinearOperator<T> for SparseKktMatrix<T> {
+    fn dim(&self) -> (usize, usize) {
+        self.matrix.shape()
+    }
+
+    fn apply(&self, x: &[T], y: &mut [T]) {
+        y.iter_mut().for_each(|v| *v = T::zero());
+        for (outer, vector) in self.matrix.outer_iterator().enumerate() {
+            for (inner, &value) in vector.iter() {
+                y[inner] += value * x[outer];
+                if inner != outer && self.matrix.get_outer_inner(inner, outer).is_none() {
+                    y[outer] += value * x[inner];
+                }
             }
         }
-        DenseKktMatrix::new(dimension, data)
+    }
+}
+
+// The upper triangle of P K P^T for the permutation sending index i to
+// `position[i]`, with the slot each original entry moves to.
+fn permute(structure: &UpperStructure, position: &[usize]) -> (UpperStructure, Vec<usize>) {
+    let n = position.len();
+    let mut columns: Vec<Vec<(usize, usize)>> = vec![Vec::new(); n];
+    for col in 0..n {
+        for p in structure.col_ptr[col]..structure.col_ptr[col + 1] {
+            let (row, col) = (position[structure.row_idx[p]], position[col]);
+            columns[row.max(col)].push((row.min(col), p));
+        }
+    }
+    let mut col_ptr = Vec::with_capacity(n + 1);
+    let mut row_idx = Vec::with_capacity(structure.row_idx.len());
+    let mut value_map = vec![0; structure.row_idx.len()];
+    col_ptr.push(0);
+    for mut column in columns {
+        column.sort_unstable();
+        for (row, p) in column {
+            value_map[p] = row_idx.len();
+            row_idx.push(row);
+        }
+        col_ptr.push(row_idx.len());
+    }
+    (UpperStructure { col_ptr, row_idx }, value_map)
+}
+
+// The fill-reducing permutation, the elimination tree of the permuted
+// matrix and the column counts of L, which fix where every nonzero of the
+// numeric factor goes.
+#[derive(Debug, Clone)]
+struct Symbolic {
+    input: InputStructure,
+    // `order[k]` is the original index of pivot k, `position` its inverse.
+    order: Vec<usize>,
+    position: Vec<usize>,
+    permuted: UpperStructure,
+    // Where each stored value of the input lands in `permuted`.
+    value_map: Vec<usize>,
+    parent: Vec<Option<usize>>,
+    l_col_ptr: Vec<usize>,
+}
+
+impl Symbolic {
+    fn analyze(input: InputStructure, ordering: FillOrdering) -> Self {
+        let (upper, _) = input.upper_triangle();
+        let order = ordering.order(&upper.col_ptr, &upper.row_idx);
+        Self::with_order(input, order)
+    }
+
+    // The analysis for a given elimination order, which is the part of it
+    // that is expensive to find.
+    fn with_order(input: InputStructure, order: Vec<usize>) -> Self {
+        let n = order.len();
+        let mut symbolic = Self {
+            input,
+            order,
+            position: Vec::new(),
+            permuted: UpperStructure {
+                col_ptr: Vec::new(),
+                row_idx: Vec::new(),
+            },
+            value_map: Vec::new(),
+            parent: vec![None; n],
+            l_col_ptr: vec![0; n + 1],
+        };
+        symbolic.place();
+        symbolic.eliminate(0);
+        symbolic
+    }
+
+    // Grows the analysis to `input`, whose leading block stores exactly the
+    // analysed entries, with the new indices pivoted last in their given
+    // order. The permuted columns of the old pivots are then unchanged, so
+    // their tree and counts only gain the paths to the new pivots.
+    fn extend(&mut self, input: InputStructure) -> Result<()> {
+        let (old, n) = (self.dimension(), input.indptr.len() - 1);
+        let keep: Vec<bool> = (0..n).map(|i| i < old).collect();
+        if n < old || input.retain(&keep) != self.input {
+            return Err(anyhow!(
+                "the grown matrix must store the analysed entries as before"
+            ));
+        }
+        self.input = input;
+        self.order.extend(old..n);
+        self.parent.resize(n, None);
+        let last = self.l_col_ptr[old];
+        self.l_col_ptr.resize(n + 1, last);
+        self.place();
+        self.eliminate(old);
+        Ok(())
+    }
+
+    // Drops the indices marked in `removed` and renumbers the rest, keeping
+    // their relative order. Removing a pivot can take fill out of L, so the
+    // tree is rebuilt for the order that remains.
+    fn shrink(&mut self, removed: &[bool]) {
+        let keep: Vec<bool> = removed.iter().map(|&removed| !removed).collect();
+        let input = self.input.retain(&keep);
+        let mut renumbered = vec![0; keep.len()];
+        let mut next = 0;
+        for (index, &kept) in renumbered.iter_mut().zip(&keep) {
+            *index = next;
+            next += usize::from(kept);
+        }
+        let order = self
+            .order
+            .iter()
+            .filter(|&&original| keep[original])
+            .map(|&original| renumbered[original])
+            .collect();
+        *self = Self::with_order(input, order);
+    }
+
+    // Positions, permuted upper triangle and value map for the current
+    // input and order.
+    fn place(&mut self) {
+        let (upper, slots) = self.input.upper_triangle();
+        self.position = vec![0; self.order.len()];
+        for (k, &original) in self.order.iter().enumerate() {
+            self.position[original] = k;
+        }
+        let (permuted, permuted_slots) = permute(&upper, &self.position);
+        self.value_map = slots.iter().map(|&slot| permuted_slots[slot]).collect();
+        self.permuted = permuted;
+    }
+
+    // Elimination tree and column counts of L for the permuted columns from
+    // `first` on, on top of those already found for the columns before.
+    fn eliminate(&mut self, first: usize) {
+        let n = self.dimension();
+        let UpperStructure { col_ptr, row_idx } = &self.permuted;
+        let parent = &mut self.parent;
+        let mut counts: Vec<usize> = self.l_col_ptr.windows(2).map(|w| w[1] - w[0]).collect();
+        let mut flag = vec![usize::MAX; n];
+        for k in first..n {
+            flag[k] = k;
+            for &row in &row_idx[col_ptr[k]..col_ptr[k + 1]] {
+                // Walk from each nonzero of row k up the tree until reaching a
+                // node already visited for this row; every node passed
+                // gains an entry in row k of L.
+                let mut i = row;
+                while i < k && flag[i] != k {
+                    if parent[i].is_none() {
+                        parent[i] = Some(k);
+                    }
+                    counts[i] += 1;
+                    flag[i] = k;
+                    i = parent[i].unwrap();
+                }
+            }
+        }
+        for (k, count) in counts.into_iter().enumerate() {
+            self.l_col_ptr[k + 1] = self.l_col_ptr[k] + count;
+        }
+    }
+
+    fn dimension(&self) -> usize {
+        self.parent.len()
     }
 }
 
 pub struct SparseKktSolver<T: RealNumber> {
-    dense: DenseKktSolver<T>,
-    pattern: Option<SparsePattern>,
+    symbolic: Option<Symbolic>,
+    // Strictly lower L by columns, laid out as `l_col_ptr` dictates.
+    l_idx: Vec<usize>,
+    l_values: Vec<T>,
+    d: Vec<T>,
+    last_factor: usize,
+    refinement_steps: usize,
+    ordering: FillOrdering,
+    // Upper triangle of the permuted matrix last factored, kept for the
+    // refinement residuals.
+    values: Vec<T>,
+    // Rank-one terms σ v vᵀ applied since, with v sparse in the original
+    // order.
+    low_rank: Vec<(T, Vec<(usize, T)>)>,
+    regularization: Option<Regularization<T>>,
+    regularization_stats: RegularizationStats<T>,
+    // Whether the last factorization changed any pivot.
+    perturbed: bool,
+    // ‖K‖₁ of the matrix last factored, bounded above after updates.
+    norm1: T,
+    symbolic_time: Duration,
+    numeric_time: Duration,
 }
 
 impl<T> SparseKktSolver<T>
@@ -59,8 +365,344 @@ where
 {
     pub fn new() -> Self {
         Self {
-            dense: DenseKktSolver::new(),
-            pattern: None,
+            symbolic: None,
+            l_idx: Vec::new(),
+            l_values: Vec::new(),
+            d: Vec::new(),
+            last_factor: 0,
+            refinement_steps: 0,
+            ordering: FillOrdering::default(),
+            values: Vec::new(),
+            low_rank: Vec::new(),
+            regularization: None,
+            regularization_stats: RegularizationStats::new(),
+            perturbed: false,
+            norm1: T::zero(),
+            symbolic_time: Duration::ZERO,
+            numeric_time: Duration::ZERO,
+        }
+    }
+
+    pub fn with_refinement(mut self, steps: usize) -> Self {
+        self.refinement_steps = steps;
+        self
+    }
+
+    pub fn with_regularization(mut self, regularization: Regularization<T>) -> Self {
+        self.regularization = Some(regularization);
+        self
+    }
+
+    // The permutation chosen at the next analysis; approximate minimum
+    // degree unless set.
+    pub fn with_ordering(mut self, ordering: FillOrdering) -> Self {
+        self.ordering = ordering;
+        self
+    }
+
+    // Nonzeros in the strictly lower part of L, zero before the first
+    // analysis.
+    pub fn factor_nnz(&self) -> usize {
+        self.symbolic
+            .as_ref()
+            .map_or(0, |symbolic| symbolic.l_col_ptr[symbolic.dimension()])
+    }
+
+    // Grows the analysis to `pattern`, the matrix with rows and columns
+    // appended after the analysed ones, as a cutting-plane loop does when it
+    // adds constraints. No new fill ordering is computed: the appended rows
+    // are pivoted last and the elimination tree is extended to them. The
+    // analysed entries must be stored exactly as before, and under
+    // regularization the new pivots are expected negative. The next
+    // `factor` must be given the grown matrix.
+    pub fn append_rows(&mut self, pattern: &SparsePattern) -> Result<()> {
+        let start = Instant::now();
+        let (Some(symbolic), Some(input)) = (self.symbolic.as_mut(), pattern.structure.clone())
+        else {
+            return Err(anyhow!("appending rows needs an analysed pattern"));
+        };
+        symbolic.extend(input)?;
+        if let Some(regularization) = &mut self.regularization {
+            regularization.signs.resize(symbolic.dimension(), -1);
+        }
+        self.last_factor = 0;
+        self.symbolic_time += start.elapsed();
+        Ok(())
+    }
+
+    // Drops the rows and columns `rows`, in the original numbering, from the
+    // analysis, as an active-set loop does when it releases constraints.
+    // The remaining pivots keep their order, so only the elimination tree is
+    // rebuilt. The next `factor` must be given the matrix without those rows
+    // and columns, with the rest stored as before.
+    pub fn remove_rows(&mut self, rows: &[usize]) -> Result<()> {
+        let start = Instant::now();
+        let Some(symbolic) = self.symbolic.as_mut() else {
+            return Err(anyhow!("removing rows needs an analysed pattern"));
+        };
+        let mut removed = vec![false; symbolic.dimension()];
+        for &row in rows {
+            if row >= removed.len() {
+                return Err(anyhow!(
+                    "row {} is outside the analysed dimension {}",
+                    row,
+                    removed.len()
+                ));
+            }
+            removed[row] = true;
+        }
+        symbolic.shrink(&removed);
+        if let Some(regularization) = &mut self.regularization {
+            let mut index = 0;
+            regularization.signs.retain(|_| {
+                index += 1;
+                !removed[index - 1]
+            });
+        }
+        self.last_factor = 0;
+        self.symbolic_time += start.elapsed();
+        Ok(())
+    }
+
+    // Refinement steps per solve, raised while the factors are those of a
+    // regularized matrix.
+    fn refinement(&self) -> usize {
+        if self.perturbed {
+            self.refinement_steps.max(REGULARIZED_REFINEMENT_STEPS)
+        } else {
+            self.refinement_steps
+        }
+    }
+
+    fn epsilon() -> T {
+        T::from_f64(1e-12).unwrap()
+    }
+
+    // Up-looking LDL^T of the permuted matrix: row k of L is the solution
+    // of a triangular system whose pattern is the set of tree paths from the
+    // nonzeros of column k.
+    fn numeric(&mut self, input: &[T]) -> Result<()> {
+        let symbolic = self.symbolic.as_ref().expect("symbolic analysis missing");
+        let n = symbolic.dimension();
+        let UpperStructure { col_ptr, row_idx } = &symbolic.permuted;
+        self.low_rank.clear();
+        let values = &mut self.values;
+        values.clear();
+        values.resize(row_idx.len(), T::zero());
+        for (&slot, &value) in symbolic.value_map.iter().zip(input) {
+            values[slot] = value;
+        }
+        let mut sums = vec![T::zero(); n];
+        for col in 0..n {
+            for p in col_ptr[col]..col_ptr[col + 1] {
+                sums[col] += values[p].abs();
+                if row_idx[p] != col {
+                    sums[row_idx[p]] += values[p].abs();
+                }
+            }
+        }
+        self.norm1 = sums.into_iter().fold(T::zero(), T::max);
+        self.l_idx.resize(symbolic.l_col_ptr[n], 0);
+        self.l_values.resize(symbolic.l_col_ptr[n], T::zero());
+        self.d.resize(n, T::zero());
+
+        let mut y = vec![T::zero(); n];
+        let mut filled = vec![0; n];
+        let mut flag = vec![0; n];
+        let mut pattern = vec![0; n];
+        for k in 0..n {
+            let mut top = n;
+            flag[k] = k;
+            filled[k] = 0;
+            for p in col_ptr[k]..col_ptr[k + 1] {
+                let mut i = row_idx[p];
+                y[i] += values[p];
+                let start = top;
+                while flag[i] != k {
+                    top -= 1;
+                    pattern[top] = i;
+                    flag[i] = k;
+                    i = symbolic.parent[i].expect("row outside the elimination tree");
+                }
+                // Each path was pushed leaf first; reverse it so that
+                // `pattern[top..]` lists every node before its ancestors.
+                pattern[top..start].reverse();
+            }
+
+            let mut d_k = y[k];
+            y[k] = T::zero();
+            for &i in &pattern[top..n] {
+                let y_i = y[i];
+                y[i] = T::zero();
+                let start = symbolic.l_col_ptr[i];
+                let end = start + filled[i];
+                for p in start..end {
+                    y[self.l_idx[p]] -= self.l_values[p] * y_i;
+                }
+                let l_ki = y_i / self.d[i];
+                d_k -= l_ki * y_i;
+                self.l_idx[end] = k;
+                self.l_values[end] = l_ki;
+                filled[i] += 1;
+            }
+            if let Some(regularization) = &self.regularization {
+                d_k = regularization.adjust(symbolic.order[k], d_k, &mut self.regularization_stats);
+            }
+            if d_k.abs() <= Self::epsilon() {
+                let magnitude = d_k.abs().to_f64().unwrap_or(f64::NAN);
+                return Err(anyhow!(
+                    "near-singular pivot encountered at column {} (|d_j| = {:.3e})",
+                    symbolic.order[k],
+                    magnitude
+                ));
+            }
+            self.d[k] = d_k;
+        }
+        Ok(())
+    }
+
+    // Turns the factors of K into those of K ± v vᵀ without a new analysis.
+    // Only the columns on the elimination tree path from the first nonzero
+    // of the permuted v change, so the cost is that of a solve; this needs
+    // the rest of v to lie in the pattern of L at that column, and a v that
+    // would create fill is refused. A refused update, or a downdate that
+    // would zero a pivot, leaves the factorization as it was.
+    pub fn update(&mut self, v: &[T], sign: UpdateSign) -> Result<()> {
+        let symbolic = self.symbolic.as_ref().expect("symbolic analysis missing");
+        let n = symbolic.dimension();
+        if self.last_factor == 0 || v.len() != n {
+            return Err(anyhow!(
+                "update vector length {} does not match factored dimension {}",
+                v.len(),
+                n
+            ));
+        }
+        let mut w = vec![T::zero(); n];
+        for (&value, &position) in v.iter().zip(&symbolic.position) {
+            w[position] = value;
+        }
+        let Some(first) = w.iter().position(|&value| value != T::zero()) else {
+            return Ok(());
+        };
+        let column = &self.l_idx[symbolic.l_col_ptr[first]..symbolic.l_col_ptr[first + 1]];
+        let mut in_column = vec![false; n];
+        for &row in column {
+            in_column[row] = true;
+        }
+        if let Some(k) = (first + 1..n).find(|&k| w[k] != T::zero() && !in_column[k]) {
+            return Err(anyhow!(
+                "the update would fill in L at column {}; refactor instead",
+                symbolic.order[k]
+            ));
+        }
+
+        let sigma = sign.value::<T>();
+        self.rank_one(first, w.clone(), sigma, false)?;
+        self.rank_one(first, w, sigma, true)?;
+        let entries = v
+            .iter()
+            .enumerate()
+            .filter(|(_, &value)| value != T::zero())
+            .map(|(i, &value)| (i, value))
+            .collect();
+        self.low_rank.push((sigma, entries));
+        let largest = v.iter().fold(T::zero(), |acc, &x| acc.max(x.abs()));
+        let sum = v.iter().fold(T::zero(), |acc, &x| acc + x.abs());
+        self.norm1 += largest * sum;
+        Ok(())
+    }
+
+    // Walks the tree path from `first`, the column of the first nonzero of
+    // the permuted `w`; with `apply` unset only the new pivots are checked.
+    fn rank_one(&mut self, first: usize, mut w: Vec<T>, sigma: T, apply: bool) -> Result<()> {
+        let symbolic = self.symbolic.as_ref().expect("symbolic analysis missing");
+        let mut alpha = T::one();
+        let mut next = Some(first);
+        while let Some(j) = next {
+            next = symbolic.parent[j];
+            let p = w[j];
+            if p == T::zero() {
+                continue;
+            }
+            let alpha_bar = alpha + sigma * p * p / self.d[j];
+            let d_bar = self.d[j] * alpha_bar / alpha;
+            if !d_bar.is_finite() || d_bar.abs() <= Self::epsilon() {
+                return Err(anyhow!(
+                    "near-singular pivot at column {} after the rank-one change",
+                    symbolic.order[j]
+                ));
+            }
+            let gamma = sigma * p / (self.d[j] * alpha_bar);
+            alpha = alpha_bar;
+            if apply {
+                self.d[j] = d_bar;
+            }
+            for q in symbolic.l_col_ptr[j]..symbolic.l_col_ptr[j + 1] {
+                let i = self.l_idx[q];
+                w[i] -= p * self.l_values[q];
+                if apply {
+                    self.l_values[q] += gamma * w[i];
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn substitute(&self, b: &mut [T]) -> Result<()> {
+        let symbolic = self.symbolic.as_ref().expect("symbolic analysis missing");
+        let l_col_ptr = &symbolic.l_col_ptr;
+        let mut rhs: Vec<T> = symbolic.order.iter().map(|&i| b[i]).collect();
+        for j in 0..symbolic.dimension() {
+            let x_j = rhs[j];
+            for p in l_col_ptr[j]..l_col_ptr[j + 1] {
+                rhs[self.l_idx[p]] -= self.l_values[p] * x_j;
+            }
+        }
+        for (i, (value, &diagonal)) in rhs.iter_mut().zip(&self.d).enumerate() {
+            if diagonal.abs() <= Self::epsilon() {
+                return Err(anyhow!(
+                    "singular diagonal entry encountered at {}",
+                    symbolic.order[i]
+                ));
+            }
+            *value = *value / diagonal;
+        }
+        for j in (0..symbolic.dimension()).rev() {
+            let mut x_j = rhs[j];
+            for p in l_col_ptr[j]..l_col_ptr[j + 1] {
+                x_j -= self.l_values[p] * rhs[self.l_idx[p]];
+            }
+            rhs[j] = x_j;
+        }
+        for (value, &position) in b.iter_mut().zip(&symbolic.position) {
+            *value = rhs[position];
+        }
+        Ok(())
+    }
+
+    // out = rhs - K x, with K applied through the upper triangle of its
+    // permuted form.
+    fn residual(&self, rhs: &[T], x: &[T], out: &mut [T]) {
+        let symbolic = self.symbolic.as_ref().expect("symbolic analysis missing");
+        let UpperStructure { col_ptr, row_idx } = &symbolic.permuted;
+        let order = &symbolic.order;
+        out.copy_from_slice(rhs);
+        for col in 0..x.len() {
+            let range = col_ptr[col]..col_ptr[col + 1];
+            for (&row, &value) in row_idx[range.clone()].iter().zip(&self.values[range]) {
+                out[order[row]] -= value * x[order[col]];
+                if row != col {
+                    out[order[col]] -= value * x[order[row]];
+                }
+            }
+        }
+        for (sigma, entries) in &self.low_rank {
+            let vx = entries
+                .iter()
+                .fold(T::zero(), |acc, &(i, v_i)| acc + v_i * x[i]);
+            for &(i, v_i) in entries {
+                out[i] -= *sigma * v_i * vx;
+            }
         }
     }
 }
@@ -82,21 +724,396 @@ where
     type Matrix = SparseKktMatrix<T>;
 
     fn analyze_pattern(&mut self, pattern: &Self::Pattern) -> Result<()> {
-        self.pattern = Some(pattern.clone());
-        self.dense
-            .analyze_pattern(&DensePattern::new(pattern.dimension()))
+        let start = Instant::now();
+        self.symbolic = pattern
+            .structure
+            .clone()
+            .map(|input| Symbolic::analyze(input, self.ordering));
+        self.symbolic_time += start.elapsed();
+        Ok(())
     }
 
+    // Only the numeric phase runs when the matrix stores the same entries
+    // as the one last analysed, as it does for every rho update.
     fn factor(&mut self, matrix: &Self::Matrix) -> Result<()> {
-        if self.pattern.is_none() {
-            let (rows, _) = matrix.matrix.shape();
-            self.analyze_pattern(&SparsePattern::new(rows))?;
+        let reusable = self
+            .symbolic
+            .as_ref()
+            .is_some_and(|symbolic| symbolic.input.matches(&matrix.matrix));
+        if !reusable {
+            let start = Instant::now();
+            let input = InputStructure::of(&matrix.matrix);
+            self.symbolic = Some(Symbolic::analyze(input, self.ordering));
+            self.symbolic_time += start.elapsed();
+        }
+        let dimension = self.symbolic.as_ref().map_or(0, Symbolic::dimension);
+        if let Some(regularization) = &self.regularization {
+            if regularization.signs.len() != dimension {
+                return Err(anyhow!(
+                    "{} pivot signs for a matrix of dimension {}",
+                    regularization.signs.len(),
+                    dimension
+                ));
+            }
         }
-        let dense = matrix.to_dense();
-        self.dense.factor(&dense)
+        let start = Instant::now();
+        let before = self.regularization_stats;
+        let factored = self.numeric(matrix.matrix.data());
+        self.numeric_time += start.elapsed();
+        factored?;
+        self.last_factor += 1;
+        self.perturbed = self.regularization.as_ref().is_some_and(|regularization| {
+            regularization.static_shift != T::zero()
+                || self.regularization_stats.dynamic_pivots > before.dynamic_pivots
+        });
+        Ok(())
     }
 
     fn solve(&self, rhs: &mut [T]) -> Result<()> {
-        self.dense.solve(rhs)
+        let dimension = self.symbolic.as_ref().map_or(0, Symbolic::dimension);
+        if self.last_factor == 0 || rhs.len() != dimension {
+            return Err(anyhow!(
+                "rhs length {} does not match dimension {}",
+                rhs.len(),
+                dimension
+            ));
+        }
+        let steps = self.refinement();
+        if steps == 0 {
+            return self.substitute(rhs);
+        }
+        let target = rhs.to_vec();
+        self.substitute(rhs)?;
+        let mut residual = vec![T::zero(); dimension];
+        for _ in 0..steps {
+            self.residual(&target, rhs, &mut residual);
+            self.substitute(&mut residual)?;
+            for (x, &dx) in rhs.iter_mut().zip(&residual) {
+                *x += dx;
+            }
+        }
+        Ok(())
+    }
+
+    fn symbolic_time(&self) -> Duration {
+        self.symbolic_time
+    }
+
+    fn numeric_time(&self) -> Duration {
+        self.numeric_time
+    }
+
+    fn regularization_stats(&self) -> Option<RegularizationStats<T>> {
+        self.regularization
+            .as_ref()
+            .map(|_| self.regularization_stats)
+    }
+
+    fn condition_estimate(&self) -> Option<T> {
+        if self.last_factor == 0 {
+            return None;
+        }
+        let dimension = self.symbolic.as_ref().map_or(0, Symbolic::dimension);
+        let inverse = inverse_norm1(dimension, |b| self.solve(b)).ok()?;
+        Some(self.norm1 * inverse)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dense::{DenseKktMatrix, DenseKktSolver};
+    use sprs::TriMat;
+
+    // An arrow-shaped quasi-definite matrix whose factor fills in only along
+    // the last row, stored as its lower triangle.
+    fn arrow(n: usize) -> (TriMat<f64>, Vec<f64>) {
+        let mut triplets = TriMat::new((n, n));
+        let mut dense = vec![0.0; n * n];
+        let mut add = |row: usize, col: usize, value: f64| {
+            triplets.add_triplet(row, col, value);
+            dense[row * n + col] = value;
+            dense[col * n + row] = value;
+        };
+        for i in 0..n - 1 {
+            add(i, i, 4.0 + i as f64);
+            add(n - 1, i, 1.0 / (i as f64 + 1.0));
+            if i + 2 < n && i % 3 == 0 {
+                add(i + 1, i, -0.5);
+            }
+        }
+        add(n - 1, n - 1, -2.0);
+        (triplets, dense)
+    }
+
+    #[test]
+    fn matches_the_dense_factorization() {
+        let n = 12;
+        let (triplets, dense) = arrow(n);
+        let b: Vec<f64> = (0..n).map(|i| (i as f64).cos()).collect();
+
+        let mut sparse = SparseKktSolver::new();
+        sparse
+            .factor(&SparseKktMatrix::new(triplets.to_csc()))
+            .unwrap();
+        let mut x = b.clone();
+        sparse.solve(&mut x).unwrap();
+
+        let mut reference = DenseKktSolver::new();
+        reference.factor(&DenseKktMatrix::new(n, dense)).unwrap();
+        let mut expected = b.clone();
+        reference.solve(&mut expected).unwrap();
+
+        for (x, e) in x.iter().zip(&expected) {
+            assert!((x - e).abs() < 1e-10, "{} vs {}", x, e);
+        }
+        // The factor keeps the arrow's own nonzeros: one per column below
+        // the diagonal plus the sub-diagonal links, without dense fill.
+        assert!(sparse.factor_nnz() < 2 * n);
+    }
+
+    #[test]
+    fn full_and_half_storage_agree() {
+        let n = 8;
+        let (triplets, _) = arrow(n);
+        let lower = triplets.to_csc::<usize>();
+        let full = &lower + &lower.transpose_view().to_csc();
+        let mut full_diagonal_halved = TriMat::new((n, n));
+        for (&value, (row, col)) in full.iter() {
+            let value = if row == col { value / 2.0 } else { value };
+            full_diagonal_halved.add_triplet(row, col, value);
+        }
+        let b = vec![1.0; n];
+        let solve = |matrix: CsMat<f64>| {
+            let mut solver = SparseKktSolver::new();
+            solver
+                .analyze_pattern(&SparsePattern::from_matrix(&matrix))
+                .unwrap();
+            solver.factor(&SparseKktMatrix::new(matrix)).unwrap();
+            let mut x = b.clone();
+            solver.solve(&mut x).unwrap();
+            x
+        };
+        let (x, y) = (solve(lower), solve(full_diagonal_halved.to_csr()));
+        for (x, y) in x.iter().zip(&y) {
+            assert!((x - y).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn minimum_degree_avoids_arrow_fill() {
+        // The arrow with its dense row moved first: eliminated in the given
+        // order it fills the whole lower triangle.
+        let n = 30;
+        let mut triplets = TriMat::new((n, n));
+        triplets.add_triplet(0, 0, -2.0);
+        for i in 1..n {
+            triplets.add_triplet(i, i, 3.0);
+            triplets.add_triplet(i, 0, 1.0);
+        }
+        let matrix = SparseKktMatrix::new(triplets.to_csc());
+        let b: Vec<f64> = (0..n).map(|i| i as f64).collect();
+        let factor = |ordering| {
+            let mut solver = SparseKktSolver::new().with_ordering(ordering);
+            solver.factor(&matrix).unwrap();
+            let mut x = b.clone();
+            solver.solve(&mut x).unwrap();
+            (solver.factor_nnz(), x)
+        };
+        let (natural, expected) = factor(FillOrdering::Natural);
+        let (amd, x) = factor(FillOrdering::Amd);
+        assert_eq!(natural, n * (n - 1) / 2);
+        assert_eq!(amd, n - 1);
+        for (x, e) in x.iter().zip(&expected) {
+            assert!((x - e).abs() < 1e-10);
+        }
+    }
+
+    #[test]
+    fn refactoring_reuses_the_analysis() {
+        let n = 10;
+        let (triplets, _) = arrow(n);
+        let first = triplets.to_csc::<usize>();
+        let mut second = first.clone();
+        second.map_inplace(|&value| 3.0 * value - 0.5);
+        let mut solver = SparseKktSolver::new();
+        solver
+            .analyze_pattern(&SparsePattern::from_matrix(&first))
+            .unwrap();
+        solver.factor(&SparseKktMatrix::new(first)).unwrap();
+        let analysed = solver.symbolic_time();
+        solver
+            .factor(&SparseKktMatrix::new(second.clone()))
+            .unwrap();
+        assert_eq!(solver.symbolic_time(), analysed);
+
+        let b: Vec<f64> = (0..n).map(|i| 1.0 / (i as f64 + 1.0)).collect();
+        let mut x = b.clone();
+        solver.solve(&mut x).unwrap();
+        let mut fresh = SparseKktSolver::new();
+        fresh.factor(&SparseKktMatrix::new(second)).unwrap();
+        let mut expected = b;
+        fresh.solve(&mut expected).unwrap();
+        assert_eq!(x, expected);
+    }
+
+    #[test]
+    fn rank_one_update_stays_within_the_pattern() {
+        let n = 10;
+        let (triplets, mut dense) = arrow(n);
+        let b: Vec<f64> = (0..n).map(|i| (i as f64).sin()).collect();
+        let mut solver = SparseKktSolver::new().with_refinement(1);
+        solver
+            .factor(&SparseKktMatrix::new(triplets.to_csc()))
+            .unwrap();
+
+        // A variable and the dense row: already coupled in L.
+        let mut v = vec![0.0; n];
+        v[2] = 0.7;
+        v[n - 1] = -1.5;
+        solver.update(&v, UpdateSign::Update).unwrap();
+        for i in 0..n {
+            for j in 0..n {
+                dense[i * n + j] += v[i] * v[j];
+            }
+        }
+        let mut reference = DenseKktSolver::new();
+        reference.factor(&DenseKktMatrix::new(n, dense)).unwrap();
+        let mut expected = b.clone();
+        reference.solve(&mut expected).unwrap();
+        let mut x = b.clone();
+        solver.solve(&mut x).unwrap();
+        for (x, e) in x.iter().zip(&expected) {
+            assert!((x - e).abs() < 1e-10, "{} vs {}", x, e);
+        }
+
+        // Two variables with no link would need new entries in L.
+        let mut coupling = vec![0.0; n];
+        coupling[2] = 1.0;
+        coupling[5] = 1.0;
+        assert!(solver.update(&coupling, UpdateSign::Update).is_err());
+        let mut x = b.clone();
+        solver.solve(&mut x).unwrap();
+        for (x, e) in x.iter().zip(&expected) {
+            assert!((x - e).abs() < 1e-10);
+        }
+    }
+
+    #[test]
+    fn dynamic_regularization_replaces_a_zero_pivot() {
+        let mut triplets = TriMat::new((3, 3));
+        triplets.add_triplet(0, 0, 1.0);
+        triplets.add_triplet(2, 0, 1.0);
+        triplets.add_triplet(2, 1, 1.0);
+        let matrix = SparseKktMatrix::new(triplets.to_csc());
+        let mut solver = SparseKktSolver::new()
+            .with_ordering(FillOrdering::Natural)
+            .with_refinement(3)
+            .with_regularization(Regularization::quasi_definite(2, 1));
+        solver.factor(&matrix).unwrap();
+        let b: Vec<f64> = vec![1.0, 2.0, -1.0];
+        let mut x = b.clone();
+        solver.solve(&mut x).unwrap();
+        let mut ax = vec![0.0_f64; 3];
+        matrix.apply(&x, &mut ax);
+        for (ax, b) in ax.iter().zip(&b) {
+            assert!((ax - b).abs() < 1e-10);
+        }
+        assert_eq!(solver.regularization_stats().unwrap().dynamic_pivots, 1);
+    }
+
+    #[test]
+    fn condition_estimate_matches_the_dense_solver() {
+        let n = 12;
+        let (triplets, dense) = arrow(n);
+        let mut sparse = SparseKktSolver::new();
+        sparse
+            .factor(&SparseKktMatrix::new(triplets.to_csc()))
+            .unwrap();
+        let mut reference = DenseKktSolver::new();
+        reference.factor(&DenseKktMatrix::new(n, dense)).unwrap();
+        let estimate: f64 = sparse.condition_estimate().unwrap();
+        let expected = reference.condition_estimate().unwrap();
+        assert!(estimate > 1.0);
+        assert!(
+            (estimate / expected - 1.0).abs() < 1e-8,
+            "{} vs {}",
+            estimate,
+            expected
+        );
+    }
+
+    // [P Aᵀ; A -0.1 I] stored as its lower triangle, with P tridiagonal.
+    fn constrained(n: usize, rows: &[&[(usize, f64)]]) -> CsMat<f64> {
+        let mut triplets = TriMat::new((n + rows.len(), n + rows.len()));
+        for i in 0..n {
+            triplets.add_triplet(i, i, 3.0 + i as f64);
+            if i + 1 < n {
+                triplets.add_triplet(i + 1, i, -1.0);
+            }
+        }
+        for (r, row) in rows.iter().enumerate() {
+            for &(col, value) in row.iter() {
+                triplets.add_triplet(n + r, col, value);
+            }
+            triplets.add_triplet(n + r, n + r, -0.1);
+        }
+        triplets.to_csc()
+    }
+
+    fn assert_solves_like_a_fresh_factorization(solver: &SparseKktSolver<f64>, matrix: CsMat<f64>) {
+        let b: Vec<f64> = (0..matrix.rows()).map(|i| (i as f64).sin() + 1.0).collect();
+        let mut fresh = SparseKktSolver::new();
+        fresh.factor(&SparseKktMatrix::new(matrix)).unwrap();
+        let mut expected = b.clone();
+        fresh.solve(&mut expected).unwrap();
+        let mut x = b;
+        solver.solve(&mut x).unwrap();
+        for (x, e) in x.iter().zip(&expected) {
+            assert!((x - e).abs() < 1e-10, "{} vs {}", x, e);
+        }
+    }
+
+    #[test]
+    fn rows_are_added_and_removed_without_a_new_ordering() {
+        let n = 8;
+        let first: &[(usize, f64)] = &[(0, 1.0), (5, 2.0)];
+        let cut: &[(usize, f64)] = &[(1, -1.0), (3, 0.5), (7, 1.0)];
+        let mut solver =
+            SparseKktSolver::new().with_regularization(Regularization::quasi_definite(n, 1));
+        let original = constrained(n, &[first]);
+        solver
+            .analyze_pattern(&SparsePattern::from_matrix(&original))
+            .unwrap();
+        solver.factor(&SparseKktMatrix::new(original)).unwrap();
+        let order = solver.symbolic.as_ref().unwrap().order.clone();
+
+        let grown = constrained(n, &[first, cut]);
+        solver
+            .append_rows(&SparsePattern::from_matrix(&grown))
+            .unwrap();
+        assert!(solver.solve(&mut vec![0.0; n + 2]).is_err());
+        let analysed = solver.symbolic_time();
+        solver.factor(&SparseKktMatrix::new(grown.clone())).unwrap();
+        assert_eq!(solver.symbolic_time(), analysed);
+        assert_eq!(solver.symbolic.as_ref().unwrap().order[..n + 1], order[..]);
+        assert_eq!(solver.regularization.as_ref().unwrap().signs[n + 1], -1);
+        assert_solves_like_a_fresh_factorization(&solver, grown);
+
+        // Releasing the first constraint leaves the cut as row n.
+        solver.remove_rows(&[n]).unwrap();
+        let shrunk = constrained(n, &[cut]);
+        let analysed = solver.symbolic_time();
+        solver
+            .factor(&SparseKktMatrix::new(shrunk.clone()))
+            .unwrap();
+        assert_eq!(solver.symbolic_time(), analysed);
+        assert_solves_like_a_fresh_factorization(&solver, shrunk);
+
+        // A grown matrix that changes the analysed entries is refused.
+        let reordered = constrained(n, &[first, cut]);
+        assert!(solver
+            .append_rows(&SparsePattern::from_matrix(&reordered))
+            .is_err());
     }
 }
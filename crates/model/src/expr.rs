@@ -0,0 +1,364 @@
+use cvxrs_core::math::RealNumber;
+use cvxrs_core::problem::CscMatrix;
+use std::marker::PhantomData;
+use std::ops::{Add, Mul, Neg, Sub};
+
+// A scalar variable of the model that created it.
+#[derive(Debug)]
+pub struct Variable<T> {
+    index: usize,
+    marker: PhantomData<T>,
+}
+
+impl<T> Clone for Variable<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Variable<T> {}
+
+impl<T> Variable<T>
+where
+    T: RealNumber,
+{
+    pub(crate) fn new(index: usize) -> Self {
+        Self {
+            index,
+            marker: PhantomData,
+        }
+    }
+
+    // Its column in the assembled problem.
+    pub fn index(self) -> usize {
+        self.index
+    }
+
+    // Its entry in a solution of the assembled problem.
+    pub fn value(self, primal: &[T]) -> T {
+        primal[self.index]
+    }
+}
+
+// Σ aᵢxᵢ + c. The same variable may appear more than once; the terms are
+// summed when the model is assembled.
+#[derive(Debug, Clone)]
+pub struct LinExpr<T> {
+    pub(crate) terms: Vec<(usize, T)>,
+    pub(crate) constant: T,
+}
+
+impl<T> LinExpr<T>
+where
+    T: RealNumber,
+{
+    pub fn zero() -> Self {
+        Self {
+            terms: Vec::new(),
+            constant: T::zero(),
+        }
+    }
+
+    pub fn constant(&self) -> T {
+        self.constant
+    }
+
+    pub fn value(&self, primal: &[T]) -> T {
+        self.terms
+            .iter()
+            .fold(self.constant, |acc, &(j, a)| acc + a * primal[j])
+    }
+
+    fn add_scaled(mut self, other: Self, factor: T) -> Self {
+        self.terms
+            .extend(other.terms.into_iter().map(|(j, a)| (j, factor * a)));
+        self.constant += factor * other.constant;
+        self
+    }
+
+    fn scale(mut self, factor: T) -> Self {
+        for (_, a) in &mut self.terms {
+            *a *= factor;
+        }
+        self.constant *= factor;
+        self
+    }
+}
+
+impl<T: RealNumber> Default for LinExpr<T> {
+    fn default() -> Self {
+        Self::zero()
+    }
+}
+
+impl<T: RealNumber> From<T> for LinExpr<T> {
+    fn from(constant: T) -> Self {
+        Self {
+            terms: Vec::new(),
+            constant,
+        }
+    }
+}
+
+impl<T: RealNumber> From<Variable<T>> for LinExpr<T> {
+    fn from(variable: Variable<T>) -> Self {
+        Self {
+            terms: vec![(variable.index, T::one())],
+            constant: T::zero(),
+        }
+    }
+}
+
+// Σ wₖxᵢxⱼ plus a linear part. Minimizing it means minimizing exactly
+// this sum, which `Model` turns into ½xᵀPx + qᵀx with P symmetric.
+#[derive(Debug, Clone)]
+pub struct QuadExpr<T> {
+    pub(crate) terms: Vec<(usize, usize, T)>,
+    pub(crate) linear: LinExpr<T>,
+}
+
+impl<T> QuadExpr<T>
+where
+    T: RealNumber,
+{
+    pub fn zero() -> Self {
+        Self {
+            terms: Vec::new(),
+            linear: LinExpr::zero(),
+        }
+    }
+
+    pub fn linear(&self) -> &LinExpr<T> {
+        &self.linear
+    }
+
+    pub fn value(&self, primal: &[T]) -> T {
+        self.terms
+            .iter()
+            .fold(self.linear.value(primal), |acc, &(i, j, w)| {
+                acc + w * primal[i] * primal[j]
+            })
+    }
+
+    fn add_scaled(mut self, other: Self, factor: T) -> Self {
+        self.terms
+            .extend(other.terms.into_iter().map(|(i, j, w)| (i, j, factor * w)));
+        self.linear = self.linear.add_scaled(other.linear, factor);
+        self
+    }
+
+    fn scale(mut self, factor: T) -> Self {
+        for (_, _, w) in &mut self.terms {
+            *w *= factor;
+        }
+        self.linear = self.linear.scale(factor);
+        self
+    }
+}
+
+impl<T: RealNumber> Default for QuadExpr<T> {
+    fn default() -> Self {
+        Self::zero()
+    }
+}
+
+impl<T: RealNumber> From<T> for QuadExpr<T> {
+    fn from(constant: T) -> Self {
+        LinExpr::from(constant).into()
+    }
+}
+
+impl<T: RealNumber> From<Variable<T>> for QuadExpr<T> {
+    fn from(variable: Variable<T>) -> Self {
+        LinExpr::from(variable).into()
+    }
+}
+
+impl<T: RealNumber> From<LinExpr<T>> for QuadExpr<T> {
+    fn from(linear: LinExpr<T>) -> Self {
+        Self {
+            terms: Vec::new(),
+            linear,
+        }
+    }
+}
+
+// Σ xᵢ.
+pub fn sum<T: RealNumber>(x: &[Variable<T>]) -> LinExpr<T> {
+    LinExpr {
+        terms: x.iter().map(|v| (v.index, T::one())).collect(),
+        constant: T::zero(),
+    }
+}
+
+// qᵀx. Panics when q and x differ in length.
+pub fn dot<T: RealNumber>(q: &[T], x: &[Variable<T>]) -> LinExpr<T> {
+    assert_eq!(
+        q.len(),
+        x.len(),
+        "dot of {} weights and {} variables",
+        q.len(),
+        x.len()
+    );
+    LinExpr {
+        terms: x.iter().zip(q).map(|(v, &a)| (v.index, a)).collect(),
+        constant: T::zero(),
+    }
+}
+
+// xᵀPx, without the ½ of the usual QP form. Panics when P is not square of
+// the length of x.
+pub fn quad<T: RealNumber>(p: &CscMatrix<T>, x: &[Variable<T>]) -> QuadExpr<T> {
+    assert!(
+        p.nrows == x.len() && p.ncols == x.len(),
+        "quad of a {}x{} matrix and {} variables",
+        p.nrows,
+        p.ncols,
+        x.len()
+    );
+    let mut terms = Vec::with_capacity(p.data.len());
+    for col in 0..p.ncols {
+        for idx in p.indptr[col]..p.indptr[col + 1] {
+            terms.push((x[p.indices[idx]].index, x[col].index, p.data[idx]));
+        }
+    }
+    QuadExpr {
+        terms,
+        linear: LinExpr::zero(),
+    }
+}
+
+// `lhs + rhs` and `lhs - rhs` in the richer of the two expression kinds.
+macro_rules! sums {
+    ($out:ident: $($lhs:ty, $rhs:ty);* $(;)?) => {$(
+        impl<T: RealNumber> Add<$rhs> for $lhs {
+            type Output = $out<T>;
+
+            fn add(self, rhs: $rhs) -> $out<T> {
+                $out::from(self).add_scaled($out::from(rhs), T::one())
+            }
+        }
+
+        impl<T: RealNumber> Sub<$rhs> for $lhs {
+            type Output = $out<T>;
+
+            fn sub(self, rhs: $rhs) -> $out<T> {
+                $out::from(self).add_scaled($out::from(rhs), -T::one())
+            }
+        }
+    )*};
+}
+
+sums!(LinExpr:
+    LinExpr<T>, LinExpr<T>;
+    LinExpr<T>, Variable<T>;
+    LinExpr<T>, T;
+    Variable<T>, LinExpr<T>;
+    Variable<T>, Variable<T>;
+    Variable<T>, T;
+);
+
+sums!(QuadExpr:
+    QuadExpr<T>, QuadExpr<T>;
+    QuadExpr<T>, LinExpr<T>;
+    QuadExpr<T>, Variable<T>;
+    QuadExpr<T>, T;
+    LinExpr<T>, QuadExpr<T>;
+    Variable<T>, QuadExpr<T>;
+);
+
+// Scaling by a constant on the right, and negation.
+impl<T: RealNumber> Mul<T> for Variable<T> {
+    type Output = LinExpr<T>;
+
+    fn mul(self, factor: T) -> LinExpr<T> {
+        LinExpr::from(self).scale(factor)
+    }
+}
+
+impl<T: RealNumber> Mul<T> for LinExpr<T> {
+    type Output = LinExpr<T>;
+
+    fn mul(self, factor: T) -> LinExpr<T> {
+        self.scale(factor)
+    }
+}
+
+impl<T: RealNumber> Mul<T> for QuadExpr<T> {
+    type Output = QuadExpr<T>;
+
+    fn mul(self, factor: T) -> QuadExpr<T> {
+        self.scale(factor)
+    }
+}
+
+impl<T: RealNumber> Neg for Variable<T> {
+    type Output = LinExpr<T>;
+
+    fn neg(self) -> LinExpr<T> {
+        LinExpr::from(self).scale(-T::one())
+    }
+}
+
+impl<T: RealNumber> Neg for LinExpr<T> {
+    type Output = LinExpr<T>;
+
+    fn neg(self) -> LinExpr<T> {
+        self.scale(-T::one())
+    }
+}
+
+impl<T: RealNumber> Neg for QuadExpr<T> {
+    type Output = QuadExpr<T>;
+
+    fn neg(self) -> QuadExpr<T> {
+        self.scale(-T::one())
+    }
+}
+
+// A constant on the left, which Rust only allows per concrete float type.
+macro_rules! scalar_lhs {
+    ($($float:ty),*) => {$(
+        impl Mul<Variable<$float>> for $float {
+            type Output = LinExpr<$float>;
+
+            fn mul(self, variable: Variable<$float>) -> LinExpr<$float> {
+                variable * self
+            }
+        }
+
+        impl Mul<LinExpr<$float>> for $float {
+            type Output = LinExpr<$float>;
+
+            fn mul(self, expr: LinExpr<$float>) -> LinExpr<$float> {
+                expr * self
+            }
+        }
+
+        impl Mul<QuadExpr<$float>> for $float {
+            type Output = QuadExpr<$float>;
+
+            fn mul(self, expr: QuadExpr<$float>) -> QuadExpr<$float> {
+                expr * self
+            }
+        }
+
+        impl Add<LinExpr<$float>> for $float {
+            type Output = LinExpr<$float>;
+
+            fn add(self, expr: LinExpr<$float>) -> LinExpr<$float> {
+                expr + self
+            }
+        }
+
+        impl Sub<LinExpr<$float>> for $float {
+            type Output = LinExpr<$float>;
+
+            fn sub(self, expr: LinExpr<$float>) -> LinExpr<$float> {
+                -expr + self
+            }
+        }
+    )*};
+}
+
+scalar_lhs!(f32, f64);
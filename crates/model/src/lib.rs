@@ -0,0 +1,7 @@
+#![forbid(unsafe_code)]
+
+mod expr;
+mod model;
+
+pub use expr::{dot, quad, sum, LinExpr, QuadExpr, Variable};
+pub use model::{Constraint, ConstraintId, Model};
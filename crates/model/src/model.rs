@@ -0,0 +1,251 @@
+use crate::expr::{LinExpr, QuadExpr, Variable};
+use cvxrs_api::{QpBuilder, Solution, Solver, SolverError};
+use cvxrs_core::math::RealNumber;
+use cvxrs_core::problem::{Bounds, CscMatrix, ObjectiveSense, ProblemQP};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Sense {
+    Equal,
+    LessEqual,
+    GreaterEqual,
+}
+
+// `expr` (= | <= | >=) 0, built from the comparison methods on expressions.
+#[derive(Debug, Clone)]
+pub struct Constraint<T> {
+    expr: LinExpr<T>,
+    sense: Sense,
+}
+
+impl<T> LinExpr<T>
+where
+    T: RealNumber,
+{
+    pub fn eq(self, rhs: impl Into<LinExpr<T>>) -> Constraint<T> {
+        self.compare(rhs.into(), Sense::Equal)
+    }
+
+    pub fn le(self, rhs: impl Into<LinExpr<T>>) -> Constraint<T> {
+        self.compare(rhs.into(), Sense::LessEqual)
+    }
+
+    pub fn ge(self, rhs: impl Into<LinExpr<T>>) -> Constraint<T> {
+        self.compare(rhs.into(), Sense::GreaterEqual)
+    }
+
+    fn compare(self, rhs: LinExpr<T>, sense: Sense) -> Constraint<T> {
+        Constraint {
+            expr: self - rhs,
+            sense,
+        }
+    }
+}
+
+impl<T> Variable<T>
+where
+    T: RealNumber,
+{
+    pub fn eq(self, rhs: impl Into<LinExpr<T>>) -> Constraint<T> {
+        LinExpr::from(self).eq(rhs)
+    }
+
+    pub fn le(self, rhs: impl Into<LinExpr<T>>) -> Constraint<T> {
+        LinExpr::from(self).le(rhs)
+    }
+
+    pub fn ge(self, rhs: impl Into<LinExpr<T>>) -> Constraint<T> {
+        LinExpr::from(self).ge(rhs)
+    }
+}
+
+// Where `subject_to` put a constraint: the equality or inequality row of
+// the assembled problem, `>=` rows being stored negated as `<=` ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConstraintId {
+    Equality(usize),
+    Inequality(usize),
+}
+
+// Collects variables, an objective and constraints and assembles them into
+// the CSC form the solvers take, so that no indptr array is written by hand.
+#[derive(Debug, Clone)]
+pub struct Model<T: RealNumber> {
+    lower: Vec<T>,
+    upper: Vec<T>,
+    objective: QuadExpr<T>,
+    sense: ObjectiveSense,
+    equalities: Vec<LinExpr<T>>,
+    inequalities: Vec<LinExpr<T>>,
+}
+
+impl<T> Default for Model<T>
+where
+    T: RealNumber,
+{
+    fn default() -> Self {
+        Self {
+            lower: Vec::new(),
+            upper: Vec::new(),
+            objective: QuadExpr::zero(),
+            sense: ObjectiveSense::Minimize,
+            equalities: Vec::new(),
+            inequalities: Vec::new(),
+        }
+    }
+}
+
+impl<T> Model<T>
+where
+    T: RealNumber,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn nvars(&self) -> usize {
+        self.lower.len()
+    }
+
+    // A free variable.
+    pub fn var(&mut self) -> Variable<T> {
+        self.lower.push(T::neg_infinity());
+        self.upper.push(T::infinity());
+        Variable::new(self.lower.len() - 1)
+    }
+
+    pub fn var_vec(&mut self, n: usize) -> Vec<Variable<T>> {
+        (0..n).map(|_| self.var()).collect()
+    }
+
+    // Keeps each of `x` within [lower, upper]; either may be infinite.
+    pub fn bounds(&mut self, x: &[Variable<T>], lower: T, upper: T) {
+        for v in x {
+            self.lower[v.index()] = lower;
+            self.upper[v.index()] = upper;
+        }
+    }
+
+    pub fn minimize(&mut self, objective: impl Into<QuadExpr<T>>) {
+        self.objective = objective.into();
+        self.sense = ObjectiveSense::Minimize;
+    }
+
+    // The objective has to be concave, e.g. a linear one or the negation of
+    // a convex one.
+    pub fn maximize(&mut self, objective: impl Into<QuadExpr<T>>) {
+        self.objective = objective.into();
+        self.sense = ObjectiveSense::Maximize;
+    }
+
+    pub fn subject_to(&mut self, constraint: Constraint<T>) -> ConstraintId {
+        let Constraint { expr, sense } = constraint;
+        match sense {
+            Sense::Equal => {
+                self.equalities.push(expr);
+                ConstraintId::Equality(self.equalities.len() - 1)
+            }
+            Sense::LessEqual => {
+                self.inequalities.push(expr);
+                ConstraintId::Inequality(self.inequalities.len() - 1)
+            }
+            Sense::GreaterEqual => {
+                self.inequalities.push(-expr);
+                ConstraintId::Inequality(self.inequalities.len() - 1)
+            }
+        }
+    }
+
+    // The objective's xᵢxⱼ terms become P, split evenly between (i, j) and
+    // (j, i) so that P is symmetric; each constraint becomes a row with its
+    // constant moved to the right-hand side.
+    pub fn build(&self) -> Result<ProblemQP<T>, SolverError> {
+        let n = self.nvars();
+        self.check_variables()?;
+        let two = T::one() + T::one();
+        let mut p = Vec::with_capacity(2 * self.objective.terms.len());
+        for &(i, j, w) in &self.objective.terms {
+            if i == j {
+                p.push((i, i, two * w));
+            } else {
+                p.push((i, j, w));
+                p.push((j, i, w));
+            }
+        }
+        let mut q = vec![T::zero(); n];
+        for &(j, a) in &self.objective.linear.terms {
+            q[j] += a;
+        }
+
+        let mut builder = QpBuilder::new()
+            .p(CscMatrix::from_triplets(n, n, &p).map_err(invalid)?)
+            .q(q)
+            .offset(self.objective.linear.constant)
+            .sense(self.sense);
+        if !self.equalities.is_empty() {
+            let (matrix, rhs) = rows(&self.equalities, n)?;
+            builder = builder.c(matrix, rhs);
+        }
+        if !self.inequalities.is_empty() {
+            let (matrix, rhs) = rows(&self.inequalities, n)?;
+            builder = builder.a(matrix, rhs);
+        }
+        let bounded = self
+            .lower
+            .iter()
+            .chain(&self.upper)
+            .any(|bound| bound.is_finite());
+        if bounded {
+            builder = builder.bounds(Bounds {
+                lower: self.lower.clone(),
+                upper: self.upper.clone(),
+            });
+        }
+        builder.build()
+    }
+
+    // Assembles the problem and solves it with `solver`; the variables
+    // read their values off the primal.
+    pub fn solve(&self, solver: &mut Solver<T>) -> Result<Solution<T>, SolverError> {
+        solver.solve_qp(self.build()?)
+    }
+
+    // A variable of another model would land on a column this one does not
+    // have.
+    fn check_variables(&self) -> Result<(), SolverError> {
+        let n = self.nvars();
+        let objective = self
+            .objective
+            .terms
+            .iter()
+            .flat_map(|&(i, j, _)| [i, j])
+            .chain(self.objective.linear.terms.iter().map(|&(j, _)| j));
+        let constraints = self
+            .equalities
+            .iter()
+            .chain(&self.inequalities)
+            .flat_map(|row| row.terms.iter().map(|&(j, _)| j));
+        match objective.chain(constraints).find(|&j| j >= n) {
+            Some(j) => Err(SolverError::InvalidProblem(format!(
+                "variable {j} does not belong to this model of {n} variables"
+            ))),
+            None => Ok(()),
+        }
+    }
+}
+
+fn rows<T: RealNumber>(
+    exprs: &[LinExpr<T>],
+    n: usize,
+) -> Result<(CscMatrix<T>, Vec<T>), SolverError> {
+    let triplets: Vec<_> = exprs
+        .iter()
+        .enumerate()
+        .flat_map(|(i, row)| row.terms.iter().map(move |&(j, a)| (i, j, a)))
+        .collect();
+    let matrix = CscMatrix::from_triplets(exprs.len(), n, &triplets).map_err(invalid)?;
+    Ok((matrix, exprs.iter().map(|row| -row.constant).collect()))
+}
+
+fn invalid(err: impl ToString) -> SolverError {
+    SolverError::InvalidProblem(err.to_string())
+}
@@ -0,0 +1,114 @@
+use cvxrs_api::{Solver, SolverError, Status};
+use cvxrs_core::math::Scalar;
+use cvxrs_core::options::SolveOptions;
+use cvxrs_core::problem::{CscMatrix, ObjectiveSense};
+use cvxrs_model::{dot, quad, sum, ConstraintId, Model};
+
+fn solver() -> Solver<Scalar> {
+    Solver::new().options(SolveOptions::with_tolerance(1e-8))
+}
+
+fn identity(n: usize) -> CscMatrix<Scalar> {
+    let triplets: Vec<_> = (0..n).map(|i| (i, i, 1.0)).collect();
+    CscMatrix::from_triplets(n, n, &triplets).unwrap()
+}
+
+#[test]
+#[cfg_attr(feature = "f32", ignore = "needs f64 accuracy")]
+fn projection_onto_the_simplex_plane() {
+    // min ½‖x‖² + qᵀx s.t. Σx = 1 is solved by x = ν1 - q with
+    // ν = (1 + Σq) / n.
+    let mut model = Model::new();
+    let x = model.var_vec(10);
+    let q: Vec<Scalar> = (0..10).map(|i| 0.1 * i as Scalar).collect();
+    model.minimize(0.5 * quad(&identity(10), &x) + dot(&q, &x));
+    let row = model.subject_to(sum(&x).eq(1.0));
+    assert_eq!(row, ConstraintId::Equality(0));
+
+    let problem = model.build().expect("build");
+    assert_eq!(problem.quadratic.data, vec![1.0; 10]);
+    assert_eq!(problem.linear, q);
+    assert!(problem.bounds.is_none());
+
+    let solution = model.solve(&mut solver()).expect("solve");
+    assert_eq!(solution.status, Status::Optimal);
+    let nu = (1.0 + q.iter().sum::<Scalar>()) / 10.0;
+    for (v, q) in x.iter().zip(&q) {
+        assert!((v.value(&solution.primal) - (nu - q)).abs() < 1e-5);
+    }
+}
+
+#[test]
+#[cfg_attr(feature = "f32", ignore = "needs f64 accuracy")]
+fn cross_terms_constants_and_both_inequality_senses() {
+    // min (x - 1)² + xy + y² - 3 over x + 2y >= 2, y <= 0.5 and x in
+    // [0, 10]: P = [2 1; 1 2], q = (-2, 0) and an offset of 1 - 3.
+    let mut model = Model::new();
+    let (x, y) = (model.var(), model.var());
+    let cross = CscMatrix::from_triplets(2, 2, &[(0, 1, 1.0)]).unwrap();
+    let objective =
+        quad(&identity(1), &[x]) - 2.0 * x + 1.0 + quad(&cross, &[x, y]) + quad(&identity(1), &[y])
+            - 3.0;
+    model.minimize(objective.clone());
+    model.bounds(&[x], 0.0, 10.0);
+    let rows = [
+        model.subject_to((x + 2.0 * y).ge(2.0)),
+        model.subject_to(y.le(0.5)),
+    ];
+    assert_eq!(
+        rows,
+        [ConstraintId::Inequality(0), ConstraintId::Inequality(1)]
+    );
+
+    let problem = model.build().expect("build");
+    assert_eq!(problem.quadratic.indptr, vec![0, 2, 4]);
+    assert_eq!(problem.quadratic.data, vec![2.0, 1.0, 1.0, 2.0]);
+    assert_eq!(problem.linear, vec![-2.0, 0.0]);
+    assert_eq!(problem.offset, -2.0);
+    assert_eq!(problem.inequalities.as_ref().unwrap().rhs, vec![-2.0, 0.5]);
+    let bounds = problem.bounds.as_ref().unwrap();
+    assert_eq!((bounds.lower[0], bounds.upper[0]), (0.0, 10.0));
+    assert_eq!(bounds.upper[1], Scalar::INFINITY);
+
+    // On x = 2 - 2y the objective is 3y² - 2y - 2, least at y = 1/3.
+    let solution = model.solve(&mut solver()).expect("solve");
+    assert_eq!(solution.status, Status::Optimal);
+    assert!((x.value(&solution.primal) - 4.0 / 3.0).abs() < 1e-5);
+    assert!((y.value(&solution.primal) - 1.0 / 3.0).abs() < 1e-5);
+    assert!((solution.objective_value + 7.0 / 3.0).abs() < 1e-5);
+    assert!((objective.value(&solution.primal) - solution.objective_value).abs() < 1e-5);
+}
+
+#[test]
+fn variables_of_another_model_are_rejected() {
+    let mut other = Model::<Scalar>::new();
+    let far = other.var_vec(3);
+    let mut model = Model::new();
+    let near = model.var();
+    model.minimize(near + far[2]);
+    assert!(matches!(model.build(), Err(SolverError::InvalidProblem(_))));
+}
+
+#[test]
+#[cfg_attr(feature = "f32", ignore = "needs f64 accuracy")]
+fn maximization_reports_the_objective_as_given() {
+    // max 2x - 4y - x² - y² = 5 - (x - 1)² - (y + 2)² over x + y <= -2 is
+    // reached at the projection of (1, -2) onto the half-plane, (0.5, -2.5),
+    // with value 4.5.
+    let mut model = Model::new();
+    let (x, y) = (model.var(), model.var());
+    let objective = 2.0 * x - 4.0 * y - quad(&identity(2), &[x, y]);
+    model.maximize(objective.clone());
+    model.subject_to((x + y).le(-2.0));
+
+    let problem = model.build().expect("build");
+    assert_eq!(problem.sense, ObjectiveSense::Maximize);
+    assert_eq!(problem.quadratic.data, vec![-2.0, -2.0]);
+
+    let solution = model.solve(&mut solver()).expect("solve");
+    assert_eq!(solution.status, Status::Optimal);
+    assert!((x.value(&solution.primal) - 0.5).abs() < 1e-5);
+    assert!((y.value(&solution.primal) + 2.5).abs() < 1e-5);
+    assert!((solution.objective_value - 4.5).abs() < 1e-5);
+    assert!((objective.value(&solution.primal) - solution.objective_value).abs() < 1e-5);
+}
@@ -0,0 +1,98 @@
+use cvxrs_api::{LpBuilder, SolverError};
+use cvxrs_core::math::RealNumber;
+use cvxrs_core::problem::{Bounds, ProblemLP};
+
+use crate::matrix::csc_from_columns;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Edge<T> {
+    pub from: usize,
+    pub to: usize,
+    pub cost: T,
+    pub capacity: Option<T>,
+}
+
+// Min-cost flow on a directed graph: one variable per arc, one flow-balance
+// equality per node (outflow - inflow = supply, negative supply is demand).
+#[derive(Debug, Clone)]
+pub struct MinCostFlow<T: RealNumber> {
+    nodes: usize,
+    supplies: Vec<(usize, T)>,
+    arcs: Vec<Edge<T>>,
+}
+
+impl<T> MinCostFlow<T>
+where
+    T: RealNumber,
+{
+    pub fn new(nodes: usize) -> Self {
+        Self {
+            nodes,
+            supplies: Vec::new(),
+            arcs: Vec::new(),
+        }
+    }
+
+    pub fn arc(mut self, from: usize, to: usize, cost: T, capacity: Option<T>) -> Self {
+        self.arcs.push(Edge {
+            from,
+            to,
+            cost,
+            capacity,
+        });
+        self
+    }
+
+    pub fn supply(mut self, node: usize, amount: T) -> Self {
+        self.supplies.push((node, amount));
+        self
+    }
+
+    pub fn arcs(&self) -> &[Edge<T>] {
+        &self.arcs
+    }
+
+    pub fn build(self) -> Result<ProblemLP<T>, SolverError> {
+        let nodes = self.nodes;
+        let mut rhs = vec![T::zero(); nodes];
+        for &(node, amount) in &self.supplies {
+            match rhs.get_mut(node) {
+                Some(supply) => *supply += amount,
+                None => {
+                    return Err(SolverError::InvalidProblem(format!(
+                        "supply at node {node} is outside 0..{nodes}"
+                    )))
+                }
+            }
+        }
+        let mut columns = Vec::with_capacity(self.arcs.len());
+        for (index, arc) in self.arcs.iter().enumerate() {
+            if arc.from >= nodes || arc.to >= nodes {
+                return Err(SolverError::InvalidProblem(format!(
+                    "arc {index} ({} -> {}) references a node outside 0..{nodes}",
+                    arc.from, arc.to
+                )));
+            }
+            if arc.from == arc.to {
+                return Err(SolverError::InvalidProblem(format!(
+                    "arc {index} is a self-loop on node {}",
+                    arc.from
+                )));
+            }
+            columns.push(vec![(arc.from, T::one()), (arc.to, -T::one())]);
+        }
+        let incidence = csc_from_columns(nodes, columns);
+        LpBuilder::new()
+            .c(self.arcs.iter().map(|arc| arc.cost).collect())
+            .c_eq(incidence, rhs)
+            .bounds(Bounds {
+                lower: vec![T::zero(); self.arcs.len()],
+                upper: self
+                    .arcs
+                    .iter()
+                    .map(|arc| arc.capacity.unwrap_or_else(T::infinity))
+                    .collect(),
+            })
+            .build()
+    }
+}
@@ -0,0 +1,13 @@
+#![forbid(unsafe_code)]
+
+mod matrix;
+
+pub mod flow;
+pub mod portfolio;
+pub mod regression;
+pub mod svm;
+
+pub use flow::MinCostFlow;
+pub use portfolio::Markowitz;
+pub use regression::Regression;
+pub use svm::SoftMarginSvm;
@@ -0,0 +1,60 @@
+use cvxrs_api::SolverError;
+use cvxrs_core::math::RealNumber;
+use cvxrs_core::problem::CscMatrix;
+
+pub(crate) fn csc_from_columns<T: RealNumber>(
+    nrows: usize,
+    columns: Vec<Vec<(usize, T)>>,
+) -> CscMatrix<T> {
+    let mut indptr = Vec::with_capacity(columns.len() + 1);
+    let mut indices = Vec::new();
+    let mut data = Vec::new();
+    indptr.push(0);
+    let ncols = columns.len();
+    for mut column in columns {
+        column.sort_by_key(|&(row, _)| row);
+        for (row, value) in column {
+            if value != T::zero() {
+                indices.push(row);
+                data.push(value);
+            }
+        }
+        indptr.push(indices.len());
+    }
+    CscMatrix {
+        nrows,
+        ncols,
+        indptr,
+        indices,
+        data,
+    }
+}
+
+// Row-major dense input, as users usually hold covariance or design matrices.
+pub(crate) fn dense_rows<T: RealNumber>(
+    what: &str,
+    rows: &[Vec<T>],
+    ncols: usize,
+) -> Result<(), SolverError> {
+    match rows.iter().position(|row| row.len() != ncols) {
+        Some(index) => Err(SolverError::InvalidProblem(format!(
+            "{what} row {index} has {} entries, expected {ncols}",
+            rows[index].len()
+        ))),
+        None => Ok(()),
+    }
+}
+
+pub(crate) fn gram<T: RealNumber>(rows: &[Vec<T>], ncols: usize) -> Vec<Vec<(usize, T)>> {
+    let mut columns = vec![Vec::new(); ncols];
+    for (j, column) in columns.iter_mut().enumerate() {
+        for i in 0..ncols {
+            let mut acc = T::zero();
+            for row in rows {
+                acc += row[i] * row[j];
+            }
+            column.push((i, acc));
+        }
+    }
+    columns
+}
@@ -0,0 +1,108 @@
+use cvxrs_api::{QpBuilder, SolverError};
+use cvxrs_core::math::RealNumber;
+use cvxrs_core::problem::{Bounds, ProblemQP};
+
+use crate::matrix::{csc_from_columns, dense_rows};
+
+// Mean-variance portfolio: minimize ½ wᵀΣw subject to a fully invested budget
+// (Σw = 1) and, optionally, an exact expected return μᵀw = r. The risk-aversion
+// form instead minimizes ½ wᵀΣw - γ μᵀw.
+#[derive(Debug, Clone)]
+pub struct Markowitz<T: RealNumber> {
+    returns: Vec<T>,
+    covariance: Vec<Vec<T>>,
+    target_return: Option<T>,
+    risk_aversion: Option<T>,
+    long_only: bool,
+    max_weight: Option<T>,
+}
+
+impl<T> Markowitz<T>
+where
+    T: RealNumber,
+{
+    pub fn new(returns: Vec<T>, covariance: Vec<Vec<T>>) -> Self {
+        Self {
+            returns,
+            covariance,
+            target_return: None,
+            risk_aversion: None,
+            long_only: true,
+            max_weight: None,
+        }
+    }
+
+    pub fn target_return(mut self, target: T) -> Self {
+        self.target_return = Some(target);
+        self
+    }
+
+    pub fn risk_aversion(mut self, gamma: T) -> Self {
+        self.risk_aversion = Some(gamma);
+        self
+    }
+
+    pub fn long_only(mut self, long_only: bool) -> Self {
+        self.long_only = long_only;
+        self
+    }
+
+    pub fn max_weight(mut self, max_weight: T) -> Self {
+        self.max_weight = Some(max_weight);
+        self
+    }
+
+    pub fn build(self) -> Result<ProblemQP<T>, SolverError> {
+        let n = self.returns.len();
+        if self.covariance.len() != n {
+            return Err(SolverError::InvalidProblem(format!(
+                "covariance has {} rows for {} assets",
+                self.covariance.len(),
+                n
+            )));
+        }
+        dense_rows("covariance", &self.covariance, n)?;
+
+        let quadratic = csc_from_columns(
+            n,
+            (0..n)
+                .map(|j| (0..n).map(|i| (i, self.covariance[i][j])).collect())
+                .collect(),
+        );
+        let linear = match self.risk_aversion {
+            Some(gamma) => self.returns.iter().map(|&mu| -gamma * mu).collect(),
+            None => vec![T::zero(); n],
+        };
+
+        let mut rhs = vec![T::one()];
+        let columns = (0..n)
+            .map(|j| {
+                let mut column = vec![(0, T::one())];
+                if self.target_return.is_some() {
+                    column.push((1, self.returns[j]));
+                }
+                column
+            })
+            .collect();
+        if let Some(target) = self.target_return {
+            rhs.push(target);
+        }
+        let equality = csc_from_columns(rhs.len(), columns);
+
+        let lower = if self.long_only {
+            T::zero()
+        } else {
+            T::neg_infinity()
+        };
+        let upper = self.max_weight.unwrap_or_else(T::infinity);
+        QpBuilder::new()
+            .p(quadratic)
+            .q(linear)
+            .c(equality, rhs)
+            .bounds(Bounds {
+                lower: vec![lower; n],
+                upper: vec![upper; n],
+            })
+            .build()
+    }
+}
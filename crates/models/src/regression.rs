@@ -0,0 +1,102 @@
+use cvxrs_api::{QpBuilder, SolverError};
+use cvxrs_core::math::RealNumber;
+use cvxrs_core::problem::ProblemQP;
+
+use crate::matrix::{csc_from_columns, dense_rows, gram};
+
+// Regularized least squares: minimize ½‖Xw - y‖² + ½λ₂‖w‖² + λ₁‖w‖₁.
+// The constant ½‖y‖² is dropped from the objective. With an l1 term the
+// problem gains auxiliary t ≥ |w| variables, so x = [w, t].
+#[derive(Debug, Clone)]
+pub struct Regression<T: RealNumber> {
+    features: Vec<Vec<T>>,
+    targets: Vec<T>,
+    ridge: T,
+    lasso: T,
+}
+
+impl<T> Regression<T>
+where
+    T: RealNumber,
+{
+    pub fn new(features: Vec<Vec<T>>, targets: Vec<T>) -> Self {
+        Self {
+            features,
+            targets,
+            ridge: T::zero(),
+            lasso: T::zero(),
+        }
+    }
+
+    pub fn ridge(mut self, lambda: T) -> Self {
+        self.ridge = lambda;
+        self
+    }
+
+    pub fn lasso(mut self, lambda: T) -> Self {
+        self.lasso = lambda;
+        self
+    }
+
+    pub fn dimension(&self) -> usize {
+        self.features.first().map_or(0, Vec::len)
+    }
+
+    // Drops the auxiliary l1 variables from a solution of the built problem.
+    pub fn coefficients(&self, primal: &[T]) -> Vec<T> {
+        primal[..self.dimension()].to_vec()
+    }
+
+    pub fn build(&self) -> Result<ProblemQP<T>, SolverError> {
+        let (n, d) = (self.features.len(), self.dimension());
+        if self.targets.len() != n {
+            return Err(SolverError::InvalidProblem(format!(
+                "{} targets for {} samples",
+                self.targets.len(),
+                n
+            )));
+        }
+        dense_rows("feature", &self.features, d)?;
+        if self.ridge < T::zero() || self.lasso < T::zero() {
+            return Err(SolverError::InvalidProblem(
+                "regularization weights must be nonnegative".into(),
+            ));
+        }
+
+        let l1 = self.lasso > T::zero();
+        let nvars = if l1 { 2 * d } else { d };
+        let mut columns = gram(&self.features, d);
+        for (j, column) in columns.iter_mut().enumerate() {
+            column[j].1 += self.ridge;
+        }
+        columns.resize(nvars, Vec::new());
+        let quadratic = csc_from_columns(nvars, columns);
+
+        let mut linear: Vec<T> = (0..d)
+            .map(|j| {
+                let mut acc = T::zero();
+                for (row, &y) in self.features.iter().zip(&self.targets) {
+                    acc -= row[j] * y;
+                }
+                acc
+            })
+            .collect();
+
+        let mut builder = QpBuilder::new();
+        if l1 {
+            linear.extend(std::iter::repeat(self.lasso).take(d));
+            // w_j - t_j ≤ 0 in row j, -w_j - t_j ≤ 0 in row d + j.
+            let columns = (0..nvars)
+                .map(|j| {
+                    if j < d {
+                        vec![(j, T::one()), (d + j, -T::one())]
+                    } else {
+                        vec![(j - d, -T::one()), (j, -T::one())]
+                    }
+                })
+                .collect();
+            builder = builder.a(csc_from_columns(2 * d, columns), vec![T::zero(); 2 * d]);
+        }
+        builder.p(quadratic).q(linear).build()
+    }
+}
@@ -0,0 +1,110 @@
+use cvxrs_api::{QpBuilder, SolverError, SvmModel, SvmSolver};
+use cvxrs_core::math::RealNumber;
+use cvxrs_core::options::SolveOptions;
+use cvxrs_core::problem::{Bounds, ProblemQP};
+
+use crate::matrix::{csc_from_columns, dense_rows};
+
+// Soft-margin linear SVM in primal form over x = [w, b, ξ]:
+// minimize ½‖w‖² + C Σξ  s.t.  y_i (wᵀx_i + b) ≥ 1 - ξ_i,  ξ ≥ 0.
+#[derive(Debug, Clone)]
+pub struct SoftMarginSvm<T: RealNumber> {
+    features: Vec<Vec<T>>,
+    labels: Vec<T>,
+    penalty: T,
+}
+
+impl<T> SoftMarginSvm<T>
+where
+    T: RealNumber,
+{
+    pub fn new(features: Vec<Vec<T>>, labels: Vec<T>) -> Self {
+        Self {
+            features,
+            labels,
+            penalty: T::one(),
+        }
+    }
+
+    pub fn penalty(mut self, c: T) -> Self {
+        self.penalty = c;
+        self
+    }
+
+    pub fn dimension(&self) -> usize {
+        self.features.first().map_or(0, Vec::len)
+    }
+
+    // Splits a solution of the built problem into (w, b).
+    pub fn hyperplane(&self, primal: &[T]) -> (Vec<T>, T) {
+        let d = self.dimension();
+        (primal[..d].to_vec(), primal[d])
+    }
+
+    // Trains through the dual SMO solver instead of the generic QP path.
+    pub fn train(&self, options: SolveOptions<T>) -> Result<SvmModel<T>, SolverError> {
+        SvmSolver::new(options)
+            .solve_features(&self.features, &self.labels, self.penalty)
+            .map_err(|err| SolverError::InvalidProblem(err.to_string()))
+    }
+
+    pub fn build(&self) -> Result<ProblemQP<T>, SolverError> {
+        let (n, d) = (self.features.len(), self.dimension());
+        if self.labels.len() != n {
+            return Err(SolverError::InvalidProblem(format!(
+                "{} labels for {} samples",
+                self.labels.len(),
+                n
+            )));
+        }
+        dense_rows("feature", &self.features, d)?;
+        if let Some(index) = self
+            .labels
+            .iter()
+            .position(|&y| y != T::one() && y != -T::one())
+        {
+            return Err(SolverError::InvalidProblem(format!(
+                "label {index} must be +1 or -1"
+            )));
+        }
+
+        let nvars = d + 1 + n;
+        let quadratic = csc_from_columns(
+            nvars,
+            (0..nvars)
+                .map(|j| {
+                    if j < d {
+                        vec![(j, T::one())]
+                    } else {
+                        Vec::new()
+                    }
+                })
+                .collect(),
+        );
+        let mut linear = vec![T::zero(); d + 1];
+        linear.extend(std::iter::repeat(self.penalty).take(n));
+
+        // -y_i x_iᵀw - y_i b - ξ_i ≤ -1
+        let mut columns: Vec<Vec<(usize, T)>> = vec![Vec::new(); nvars];
+        for (i, (row, &y)) in self.features.iter().zip(&self.labels).enumerate() {
+            for (j, &value) in row.iter().enumerate() {
+                columns[j].push((i, -y * value));
+            }
+            columns[d].push((i, -y));
+            columns[d + 1 + i].push((i, -T::one()));
+        }
+        let margins = csc_from_columns(n, columns);
+
+        let mut lower = vec![T::neg_infinity(); d + 1];
+        lower.extend(std::iter::repeat(T::zero()).take(n));
+        QpBuilder::new()
+            .p(quadratic)
+            .q(linear)
+            .a(margins, vec![-T::one(); n])
+            .bounds(Bounds {
+                lower,
+                upper: vec![T::infinity(); nvars],
+            })
+            .build()
+    }
+}
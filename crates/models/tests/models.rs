@@ -0,0 +1,129 @@
+use cvxrs_api::{Solver, Status};
+use cvxrs_core::math::Scalar;
+use cvxrs_core::options::SolveOptions;
+use cvxrs_models::{Markowitz, MinCostFlow, Regression, SoftMarginSvm};
+
+fn solver() -> Solver<Scalar> {
+    Solver::new().options(SolveOptions::with_tolerance(1e-7))
+}
+
+#[test]
+fn portfolio_meets_budget_and_target() {
+    let returns = vec![0.12, 0.10, 0.07, 0.03];
+    let covariance = vec![
+        vec![0.05, 0.0, 0.0, 0.0],
+        vec![0.0, 0.02, 0.0, 0.0],
+        vec![0.0, 0.0, 0.01, 0.0],
+        vec![0.0, 0.0, 0.0, 0.005],
+    ];
+    let problem = Markowitz::new(returns.clone(), covariance)
+        .target_return(0.08)
+        .build()
+        .expect("model");
+    let solution = solver().solve_qp(problem).expect("solve");
+    assert_eq!(solution.status, Status::Optimal);
+    let budget: Scalar = solution.primal.iter().sum();
+    let expected: Scalar = solution
+        .primal
+        .iter()
+        .zip(&returns)
+        .map(|(w, mu)| w * mu)
+        .sum();
+    assert!((budget - 1.0).abs() < 1e-4);
+    assert!((expected - 0.08).abs() < 1e-4);
+}
+
+#[test]
+fn min_cost_flow_prefers_the_cheap_path() {
+    // 0 -> 1 -> 3 costs 2 per unit, 0 -> 2 -> 3 costs 5; the cheap path holds 3.
+    let problem = MinCostFlow::new(4)
+        .arc(0, 1, 1.0, Some(3.0))
+        .arc(1, 3, 1.0, None)
+        .arc(0, 2, 2.0, None)
+        .arc(2, 3, 3.0, None)
+        .supply(0, 5.0)
+        .supply(3, -5.0)
+        .build()
+        .expect("model");
+    let solution = solver().solve_lp(problem).expect("solve");
+    let expected = [3.0, 3.0, 2.0, 2.0];
+    for (flow, expected) in solution.primal.iter().zip(expected) {
+        assert!((flow - expected).abs() < 1e-3, "{:?}", solution.primal);
+    }
+}
+
+#[test]
+#[cfg_attr(feature = "f32", ignore = "needs f64 accuracy")]
+fn svm_separates_and_lasso_zeroes_noise() {
+    let features = vec![
+        vec![2.0, 2.0],
+        vec![3.0, 1.0],
+        vec![-2.0, -1.0],
+        vec![-1.0, -3.0],
+    ];
+    let labels = vec![1.0, 1.0, -1.0, -1.0];
+    let svm = SoftMarginSvm::new(features.clone(), labels.clone()).penalty(10.0);
+    let solution = solver()
+        .solve_qp(svm.build().expect("model"))
+        .expect("solve");
+    let (w, b) = svm.hyperplane(&solution.primal);
+    for (x, y) in features.iter().zip(&labels) {
+        let score = w[0] * x[0] + w[1] * x[1] + b;
+        assert!(score * y > 0.0);
+    }
+
+    // y depends on the first feature only.
+    let features = vec![
+        vec![1.0, 0.3],
+        vec![2.0, -0.2],
+        vec![3.0, 0.1],
+        vec![4.0, -0.4],
+    ];
+    let targets = vec![2.0, 4.0, 6.0, 8.0];
+    let lasso = Regression::new(features, targets).lasso(1.0);
+    let solution = solver()
+        .solve_qp(lasso.build().expect("model"))
+        .expect("solve");
+    let coefficients = lasso.coefficients(&solution.primal);
+    assert!(coefficients[0] > 1.9);
+    assert!(coefficients[1].abs() < 1e-3);
+}
+
+#[test]
+#[cfg_attr(feature = "f32", ignore = "needs f64 accuracy")]
+fn svm_dual_fast_path_matches_the_primal_qp() {
+    let features = vec![
+        vec![1.0, 2.0],
+        vec![2.0, 3.0],
+        vec![3.0, 3.0],
+        vec![2.0, 0.5],
+        vec![3.0, 1.0],
+        vec![1.5, 1.8],
+    ];
+    let labels = vec![1.0, 1.0, 1.0, -1.0, -1.0, -1.0];
+    let svm = SoftMarginSvm::new(features.clone(), labels).penalty(2.0);
+    let primal = solver()
+        .solve_qp(svm.build().expect("model"))
+        .expect("solve");
+    let model = svm
+        .train(SolveOptions::with_tolerance(1e-8))
+        .expect("train");
+    assert_eq!(model.status, Status::Optimal);
+
+    let (w, b) = svm.hyperplane(&primal.primal);
+    let weights = model.weights.as_ref().expect("linear weights");
+    for (fast, qp) in weights.iter().zip(&w) {
+        assert!((fast - qp).abs() < 1e-3, "{weights:?} vs {w:?}");
+    }
+    assert!((model.bias - b).abs() < 1e-3);
+    // Strong duality: the dual optimum is minus the primal optimum.
+    assert!((model.objective_value + primal.objective_value).abs() < 1e-3);
+    for x in &features {
+        let direct = model.decision(x).expect("linear decision");
+        let kernel: Vec<_> = features
+            .iter()
+            .map(|xi| xi[0] * x[0] + xi[1] * x[1])
+            .collect();
+        assert!((direct - model.decision_from_kernel(&kernel)).abs() < 1e-9);
+    }
+}
@@ -3,7 +3,7 @@ use anyhow::Result;
 use cvxrs_api::{LpBuilder, Method, Solver};
 use cvxrs_core::math::Scalar;
 use cvxrs_core::options::SolveOptions;
-use cvxrs_core::problem::{Bounds, CscMatrix};
+use cvxrs_core::problem::{Bounds, CscMatrix, TripletBuilder};
 
 fn main() -> Result<()> {
     let cost = vec![2.0, 3.0, 1.5, 2.5, 4.0, 3.5, 3.0, 2.0, 1.0];
@@ -32,28 +32,14 @@ fn main() -> Result<()> {
 }
 
 fn supply_demand_constraints() -> CscMatrix<Scalar> {
-    let mut indptr = vec![0];
-    let mut indices = Vec::new();
-    let mut data = Vec::new();
+    // Flow (source, sink) leaves the source's supply row and enters the
+    // sink's demand row.
+    let mut builder = TripletBuilder::new(6, 9).with_capacity(18);
     for source in 0..3 {
         for sink in 0..3 {
-            indices.push(source);
-            data.push(1.0);
+            let flow = 3 * source + sink;
+            builder.push(source, flow, 1.0).push(3 + sink, flow, 1.0);
         }
-        indptr.push(indices.len());
-    }
-    for sink in 0..3 {
-        for source in 0..3 {
-            indices.push(3 + sink);
-            data.push(1.0);
-        }
-        indptr.push(indices.len());
-    }
-    CscMatrix {
-        nrows: 6,
-        ncols: 9,
-        indptr,
-        indices,
-        data,
     }
+    builder.build().expect("flow indices are in range")
 }
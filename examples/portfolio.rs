@@ -14,24 +14,11 @@ fn main() -> Result<()> {
     let q = vec![0.0; returns.len()];
     let mut builder = QpBuilder::new().p(p).q(q);
 
-    let mut c_indptr = vec![0];
-    let mut c_indices = Vec::new();
-    let mut c_data = Vec::new();
-    let mut rhs = vec![1.0, target_return];
-    for col in 0..returns.len() {
-        c_indices.push(0);
-        c_data.push(1.0);
-        c_indices.push(1);
-        c_data.push(returns[col]);
-        c_indptr.push(c_data.len());
-    }
-    let equality = cvxrs_core::problem::CscMatrix {
-        nrows: 2,
-        ncols: returns.len(),
-        indptr: c_indptr,
-        indices: c_indices,
-        data: c_data,
-    };
+    // Weights sum to one and reach the target return.
+    let mut rows = vec![1.0; returns.len()];
+    rows.extend_from_slice(&returns);
+    let equality = CscMatrix::from_dense(2, returns.len(), &rows)?;
+    let rhs = vec![1.0, target_return];
     builder = builder.c(equality, rhs);
 
     let bounds = Bounds {
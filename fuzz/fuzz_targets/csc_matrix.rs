@@ -0,0 +1,27 @@
+#![no_main]
+
+use arbitrary::Arbitrary;
+use cvxrs_core::problem::CscMatrix;
+use libfuzzer_sys::fuzz_target;
+
+#[derive(Arbitrary, Debug)]
+struct Input {
+    nrows: u8,
+    ncols: u8,
+    indptr: Vec<u16>,
+    indices: Vec<u16>,
+    data: Vec<f64>,
+}
+
+fuzz_target!(|input: Input| {
+    let matrix = CscMatrix {
+        nrows: input.nrows as usize,
+        ncols: input.ncols as usize,
+        indptr: input.indptr.into_iter().map(usize::from).collect(),
+        indices: input.indices.into_iter().map(usize::from).collect(),
+        data: input.data,
+    };
+    let valid = matrix.validate().is_ok();
+    // to_csmat must agree with validate and never panic inside sprs.
+    assert_eq!(valid, matrix.to_csmat().is_ok());
+});
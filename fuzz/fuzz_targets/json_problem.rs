@@ -0,0 +1,19 @@
+#![no_main]
+
+use cvxrs_io::{parse_json_problem, JsonProblem};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(contents) = std::str::from_utf8(data) else {
+        return;
+    };
+    if let Ok(problem) = parse_json_problem(contents) {
+        let valid = match &problem {
+            JsonProblem::Qp { problem } => problem.validate().is_ok(),
+            JsonProblem::Lp { problem } => problem.validate().is_ok(),
+        };
+        assert!(valid, "parse_json_problem accepted an invalid problem");
+    }
+    // The plain deserializer must never panic either, even without validation.
+    let _ = serde_json::from_str::<JsonProblem>(contents);
+});
@@ -0,0 +1,17 @@
+#![no_main]
+
+use cvxrs_io::{parse_mps, JsonProblem};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(contents) = std::str::from_utf8(data) else {
+        return;
+    };
+    if let Ok(model) = parse_mps(contents) {
+        let valid = match &model.problem {
+            JsonProblem::Qp { problem } => problem.validate().is_ok(),
+            JsonProblem::Lp { problem } => problem.validate().is_ok(),
+        };
+        assert!(valid, "parse_mps produced an invalid problem");
+    }
+});